@@ -1,12 +1,16 @@
 use bevy::{
     ecs::query::QueryItem,
-    prelude::{Bundle, Component, GlobalTransform, Transform, With},
+    prelude::{Bundle, Component, GlobalTransform, Handle, Image, Transform, With},
     render::{
-        camera::{Camera, CameraProjection, CameraRenderGraph, DepthCalculation, WindowOrigin},
+        camera::{
+            Camera, CameraProjection, CameraRenderGraph, DepthCalculation, RenderTarget, Viewport,
+            WindowOrigin,
+        },
         extract_component::ExtractComponent,
         primitives::Frustum,
         view::VisibleEntities,
     },
+    window::WindowId,
 };
 
 use super::ortho::UIOrthographicProjection;
@@ -73,4 +77,45 @@ impl UICameraBundle {
             marker: CameraUiKayak,
         }
     }
+
+    /// Creates a UI camera that renders into the given image rather than the window
+    ///
+    /// The image should be created with [`create_ui_texture`](crate::camera::create_ui_texture) (see
+    /// there for details on how the image's size and scale are handled). Unless another UI camera
+    /// targets the window, the `BevyContext` resource will no longer be rendered to it.
+    ///
+    /// Note that input is still read from the window, so interacting with the UI on the texture is
+    /// not currently supported.
+    pub fn with_target_image(image: Handle<Image>) -> Self {
+        let mut bundle = Self::new();
+        bundle.camera.target = RenderTarget::Image(image);
+        // Render before the cameras that might display the image
+        bundle.camera.priority = -1;
+        bundle
+    }
+
+    /// Creates a UI camera that renders to the given window rather than the primary one
+    ///
+    /// To give the window its own UI, insert a `BevyContext` component on the spawned camera
+    /// entity. That context will then be laid out using the size of the window and will only
+    /// receive input from it.
+    pub fn with_target_window(window_id: WindowId) -> Self {
+        let mut bundle = Self::new();
+        bundle.camera.target = RenderTarget::Window(window_id);
+        bundle
+    }
+
+    /// Creates a UI camera that renders to a region of the given window
+    ///
+    /// The UI is laid out using the size of the viewport, with its origin at the viewport's top-left
+    /// corner, and input is mapped into the viewport as well. This allows for split-screen UIs by
+    /// giving each camera its own `BevyContext` component.
+    ///
+    /// Cameras rendering to the same window should be given different priorities, so that their
+    /// draw order is well defined.
+    pub fn with_viewport(window_id: WindowId, viewport: Viewport) -> Self {
+        let mut bundle = Self::with_target_window(window_id);
+        bundle.camera.viewport = Some(viewport);
+        bundle
+    }
 }
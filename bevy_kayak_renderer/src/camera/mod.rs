@@ -5,9 +5,11 @@ use bevy::{
 
 mod camera;
 mod ortho;
+mod texture;
 
 pub use camera::{CameraUiKayak, UICameraBundle};
 pub(crate) use ortho::UIOrthographicProjection;
+pub use texture::{create_ui_texture, target_image, target_image_size};
 
 pub struct KayakUICameraPlugin;
 
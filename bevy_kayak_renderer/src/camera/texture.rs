@@ -0,0 +1,114 @@
+use bevy::{
+    math::Vec2,
+    prelude::{Assets, Handle, Image},
+    render::{
+        camera::{Camera, RenderTarget},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        texture::BevyDefault,
+    },
+};
+
+/// Creates an image that the UI can be rendered into
+///
+/// The returned handle can be given to [`UICameraBundle::with_target_image`](super::UICameraBundle::with_target_image)
+/// and then used like any other texture (e.g. as the `base_color_texture` of a material).
+///
+/// # Size and Scale
+///
+/// The UI is laid out using the size of the image, in pixels, as its logical size. Unlike windows,
+/// images have no scale factor, so one UI pixel maps to exactly one texel. To get crisper results on
+/// a surface that's viewed up close, use a larger image and increase the size of the UI content to
+/// match (e.g. larger font sizes).
+///
+/// Resizing the image (via [`Image::resize`]) will cause the UI to be laid out again at the new size.
+pub fn create_ui_texture(images: &mut Assets<Image>, width: u32, height: u32) -> Handle<Image> {
+    let size = Extent3d {
+        width,
+        height,
+        ..Default::default()
+    };
+
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("kayak_ui_texture"),
+            size,
+            dimension: TextureDimension::D2,
+            // Must match the format of the UI pipeline
+            format: TextureFormat::bevy_default(),
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+        },
+        ..Default::default()
+    };
+    // Fill the image with zeroes (i.e. transparent)
+    image.resize(size);
+
+    images.add(image)
+}
+
+/// Returns the image the given camera renders into, if it doesn't render to a window
+pub fn target_image(camera: &Camera) -> Option<&Handle<Image>> {
+    match &camera.target {
+        RenderTarget::Image(handle) => Some(handle),
+        RenderTarget::Window(..) => None,
+    }
+}
+
+/// Returns the size of the image the given camera renders into, if it doesn't render to a window
+///
+/// Returns `None` if the camera renders to a window or if the image hasn't been loaded.
+pub fn target_image_size(camera: &Camera, images: &Assets<Image>) -> Option<Vec2> {
+    target_image(camera)
+        .and_then(|handle| images.get(handle))
+        .map(|image| image.size())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::{AddAsset, AssetPlugin},
+        math::Vec2,
+        prelude::{App, Assets, Image, MinimalPlugins},
+        render::camera::Camera,
+    };
+
+    use super::{create_ui_texture, target_image, target_image_size};
+    use crate::camera::UICameraBundle;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>();
+        app
+    }
+
+    #[test]
+    fn should_use_the_size_of_the_target_image() {
+        let mut app = app();
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let handle = create_ui_texture(&mut images, 640, 360);
+
+        let bundle = UICameraBundle::with_target_image(handle.clone());
+        assert_eq!(Some(&handle), target_image(&bundle.camera));
+        assert_eq!(
+            Some(Vec2::new(640.0, 360.0)),
+            target_image_size(&bundle.camera, &images)
+        );
+    }
+
+    #[test]
+    fn should_not_have_a_target_image_when_rendering_to_a_window() {
+        let app = app();
+        let images = app.world.resource::<Assets<Image>>();
+        let camera = Camera::default();
+        assert_eq!(None, target_image(&camera));
+        assert_eq!(None, target_image_size(&camera, images));
+        assert_eq!(None, target_image(&UICameraBundle::new().camera));
+    }
+}
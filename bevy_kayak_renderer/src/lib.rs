@@ -28,8 +28,22 @@ fn update_window_size(
     mut window_resized_events: EventReader<WindowResized>,
     mut window_created_events: EventReader<WindowCreated>,
     windows: Res<Windows>,
+    cameras: Query<&Camera, With<CameraUiKayak>>,
+    images: Res<Assets<Image>>,
     mut window_size: ResMut<WindowSize>,
 ) {
+    // When rendering to a texture, the texture's size is used instead of the window's
+    if let Some(size) = cameras
+        .iter()
+        .find_map(|camera| target_image_size(camera, &images))
+    {
+        let size = WindowSize(size.x, size.y);
+        if *window_size != size {
+            *window_size = size;
+        }
+        return;
+    }
+
     let mut changed_window_ids = Vec::new();
     // handle resize events. latest events are handled first because we only want to resize each
     // window once
@@ -1,10 +1,13 @@
 use bevy::{
-    prelude::{Commands, Entity, Plugin, Query, With},
+    math::Vec2,
+    prelude::{Assets, Camera, Commands, Component, Entity, Image, Plugin, Query, Res, With},
     render::{
+        camera::RenderTarget,
         render_graph::{RenderGraph, SlotInfo, SlotType},
         render_phase::{DrawFunctions, RenderPhase},
         Extract, RenderApp, RenderStage,
     },
+    window::Windows,
 };
 
 use crate::{
@@ -62,13 +65,48 @@ impl Plugin for BevyKayakUIRenderPlugin {
     }
 }
 
+/// The render target info of a single UI camera
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ExtractedUIView {
+    /// The size of the area the camera draws to, in physical pixels
+    ///
+    /// This is the size of the camera's viewport if it has one, otherwise the size of its render target.
+    pub physical_size: Vec2,
+    /// The position of the camera's viewport within its render target, in physical pixels
+    pub physical_offset: Vec2,
+}
+
 pub fn extract_core_pipeline_camera_phases(
     mut commands: Commands,
-    active_camera: Extract<Query<Entity, With<CameraUiKayak>>>,
+    cameras: Extract<Query<(Entity, &Camera), With<CameraUiKayak>>>,
+    windows: Extract<Res<Windows>>,
+    images: Extract<Res<Assets<Image>>>,
 ) {
-    if let Ok(entity) = active_camera.get_single() {
-        commands
-            .get_or_spawn(entity)
-            .insert(RenderPhase::<TransparentUI>::default());
+    for (entity, camera) in cameras.iter() {
+        let target_size = match &camera.target {
+            RenderTarget::Window(window_id) => windows.get(*window_id).map(|window| {
+                Vec2::new(
+                    window.physical_width() as f32,
+                    window.physical_height() as f32,
+                )
+            }),
+            RenderTarget::Image(handle) => images.get(handle).map(|image| image.size()),
+        };
+
+        let mut entity_commands = commands.get_or_spawn(entity);
+        entity_commands.insert(RenderPhase::<TransparentUI>::default());
+        let extracted_view = match &camera.viewport {
+            Some(viewport) => Some(ExtractedUIView {
+                physical_size: viewport.physical_size.as_vec2(),
+                physical_offset: viewport.physical_position.as_vec2(),
+            }),
+            None => target_size.map(|physical_size| ExtractedUIView {
+                physical_size,
+                physical_offset: Vec2::ZERO,
+            }),
+        };
+        if let Some(extracted_view) = extracted_view {
+            entity_commands.insert(extracted_view);
+        }
     }
 }
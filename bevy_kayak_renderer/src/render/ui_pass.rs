@@ -2,6 +2,8 @@ use bevy::ecs::prelude::*;
 use bevy::render::render_phase::{DrawFunctionId, PhaseItem};
 use bevy::render::render_resource::{CachedRenderPipelineId, RenderPassColorAttachment};
 use bevy::render::{
+    camera::{ExtractedCamera, RenderTarget},
+    color::Color,
     render_graph::{Node, NodeRunError, RenderGraphContext, SlotInfo, SlotType},
     render_phase::{DrawFunctions, RenderPhase, TrackedRenderPass},
     render_resource::{LoadOp, Operations, RenderPassDescriptor},
@@ -32,8 +34,14 @@ impl PhaseItem for TransparentUI {
 }
 
 pub struct MainPassUINode {
-    query:
-        QueryState<(&'static RenderPhase<TransparentUI>, &'static ViewTarget), With<ExtractedView>>,
+    query: QueryState<
+        (
+            &'static RenderPhase<TransparentUI>,
+            &'static ViewTarget,
+            Option<&'static ExtractedCamera>,
+        ),
+        With<ExtractedView>,
+    >,
 }
 
 impl MainPassUINode {
@@ -64,11 +72,16 @@ impl Node for MainPassUINode {
         let view_entity = graph.get_input_entity(Self::IN_VIEW)?;
         // adapted from bevy itself;
         // see: <https://github.com/bevyengine/bevy/commit/09a3d8abe062984479bf0e99fcc1508bb722baf6>
-        let (transparent_phase, target) = match self.query.get_manual(world, view_entity) {
+        let (transparent_phase, target, camera) = match self.query.get_manual(world, view_entity) {
             Ok(it) => it,
             _ => return Ok(()),
         };
         // let clear_color = world.get_resource::<ClearColor>().unwrap();
+        // Windows are drawn on top of, but texture targets need to be cleared each frame
+        let load = match camera.map(|camera| &camera.target) {
+            Some(RenderTarget::Image(..)) => LoadOp::Clear(Color::NONE.into()),
+            _ => LoadOp::Load,
+        };
         {
             let pass_descriptor = RenderPassDescriptor {
                 label: Some("main_transparent_pass_UI"),
@@ -76,7 +89,7 @@ impl Node for MainPassUINode {
                     view: &target.view,
                     resolve_target: None,
                     ops: Operations {
-                        load: LoadOp::Load, //Clear(clear_color.0.into()),
+                        load, //Clear(clear_color.0.into()),
                         store: true,
                     },
                 })],
@@ -92,6 +105,9 @@ impl Node for MainPassUINode {
                 .begin_render_pass(&pass_descriptor);
             let mut draw_functions = draw_functions.write();
             let mut tracked_pass = TrackedRenderPass::new(render_pass);
+            if let Some(viewport) = camera.and_then(|camera| camera.viewport.as_ref()) {
+                tracked_pass.set_camera_viewport(viewport);
+            }
             for item in transparent_phase.items.iter() {
                 let draw_function = draw_functions.get_mut(item.draw_function).unwrap();
                 draw_function.draw(world, &mut tracked_pass, view_entity, item);
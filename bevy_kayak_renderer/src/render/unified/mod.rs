@@ -1,5 +1,5 @@
 use bevy::{
-    prelude::{Assets, Commands, HandleUntyped, Plugin, Res},
+    prelude::{Assets, Camera, Commands, HandleUntyped, Plugin, Query, Res, With},
     reflect::TypeUuid,
     render::{
         render_phase::DrawFunctions, render_resource::Shader, Extract, RenderApp, RenderStage,
@@ -8,11 +8,12 @@ use bevy::{
 };
 
 use crate::{
+    camera::target_image,
     render::{
         ui_pass::TransparentUI,
         unified::pipeline::{DrawUI, QuadMeta, UnifiedPipeline},
     },
-    WindowSize,
+    CameraUiKayak, WindowSize,
 };
 
 use self::pipeline::ImageBindGroups;
@@ -59,8 +60,13 @@ pub fn extract_baseline(
     mut commands: Commands,
     windows: Extract<Res<Windows>>,
     window_size: Extract<Res<WindowSize>>,
+    cameras: Extract<Query<&Camera, With<CameraUiKayak>>>,
 ) {
-    let dpi = if let Some(window) = windows.get_primary() {
+    // Images have no scale factor
+    let is_texture_target = cameras.iter().any(|camera| target_image(camera).is_some());
+    let dpi = if is_texture_target {
+        1.0
+    } else if let Some(window) = windows.get_primary() {
         window.scale_factor() as f32
     } else {
         1.0
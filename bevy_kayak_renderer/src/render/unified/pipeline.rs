@@ -37,7 +37,7 @@ use kayak_font::{
 };
 
 use super::{Dpi, UNIFIED_SHADER_HANDLE};
-use crate::render::ui_pass::TransparentUI;
+use crate::render::{ui_pass::TransparentUI, ExtractedUIView};
 use crate::{Corner, WindowSize};
 
 pub struct UnifiedPipeline {
@@ -50,6 +50,9 @@ pub struct UnifiedPipeline {
     default_image: (GpuImage, BindGroup),
 }
 
+/// The space left around [lines](UIQuadType::Line) for their anti-aliased edges, in physical pixels
+const LINE_FEATHER: f32 = 1.0;
+
 const QUAD_VERTEX_POSITIONS: &[Vec3] = &[
     Vec3::from_array([0.0, 1.0, 0.0]),
     Vec3::from_array([1.0, 0.0, 0.0]),
@@ -150,7 +153,7 @@ impl FromWorld for UnifiedPipeline {
         });
 
         let vertex_buffer_layout = VertexBufferLayout {
-            array_stride: 60,
+            array_stride: std::mem::size_of::<QuadVertex>() as u64,
             step_mode: VertexStepMode::Vertex,
             attributes: vec![
                 VertexAttribute {
@@ -173,6 +176,36 @@ impl FromWorld for UnifiedPipeline {
                     offset: 44,
                     shader_location: 3,
                 },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 60,
+                    shader_location: 4,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 76,
+                    shader_location: 5,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 92,
+                    shader_location: 6,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 108,
+                    shader_location: 7,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 124,
+                    shader_location: 8,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 140,
+                    shader_location: 9,
+                },
             ],
         };
 
@@ -306,6 +339,38 @@ pub enum UIQuadType {
     Text,
     Image,
     Clip,
+    /// A batch of anti-aliased [lines](ExtractedLine) with round ends, drawn in a single draw call
+    Line,
+}
+
+/// A line of a [line batch](UIQuadType::Line), in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtractedLine {
+    pub start: Vec2,
+    pub end: Vec2,
+    /// Half the width of the line
+    pub radius: f32,
+    pub color: Color,
+    /// The start of the previous line in the batch, if that line ends where this one starts
+    ///
+    /// Where connected lines overlap, only the closest one is drawn, so that their joint isn't
+    /// blended twice.
+    pub previous: Option<Vec2>,
+    /// The end of the next line in the batch, if that line starts where this one ends
+    pub next: Option<Vec2>,
+}
+
+impl ExtractedLine {
+    /// Connects each line to the lines before and after it in the batch, where they meet
+    pub fn connect(lines: &mut [ExtractedLine]) {
+        for index in 1..lines.len() {
+            let (previous, line) = (lines[index - 1], lines[index]);
+            if previous.end == line.start && previous.radius == line.radius {
+                lines[index - 1].next = Some(line.end);
+                lines[index].previous = Some(previous.start);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Component, Clone)]
@@ -322,6 +387,44 @@ pub struct ExtractedQuad {
     pub image: Option<Handle<Image>>,
     pub uv_min: Option<Vec2>,
     pub uv_max: Option<Vec2>,
+    /// The UI camera that should draw this quad, or `None` to draw it with every UI camera
+    pub camera_entity: Option<Entity>,
+    /// The rounded outline this quad is masked to, if any
+    pub mask: Option<QuadMask>,
+    /// The rounded clip this quad is drawn within, if any, which applies on top of its own mask
+    pub clip: Option<QuadMask>,
+    /// The lines drawn by a [line batch](UIQuadType::Line), which are empty for other quads
+    pub lines: Vec<ExtractedLine>,
+}
+
+/// A rounded rect that a quad's pixels are masked to
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QuadMask {
+    pub rect: Rect,
+    pub border_radius: Corner<f32>,
+}
+
+impl QuadMask {
+    /// The rect and corner radii of the given mask, as passed to the shader
+    ///
+    /// An empty mask disables masking in the shader.
+    fn to_vertex(mask: Option<QuadMask>) -> ([f32; 4], [f32; 4]) {
+        match mask {
+            Some(QuadMask {
+                rect,
+                border_radius,
+            }) => (
+                [rect.min.x, rect.min.y, rect.size().x, rect.size().y],
+                [
+                    border_radius.top_left,
+                    border_radius.top_right,
+                    border_radius.bottom_left,
+                    border_radius.bottom_right,
+                ],
+            ),
+            None => ([0.0; 4], [0.0; 4]),
+        }
+    }
 }
 
 #[repr(C)]
@@ -331,6 +434,15 @@ struct QuadVertex {
     pub color: [f32; 4],
     pub uv: [f32; 4],
     pub pos_size: [f32; 4],
+    pub mask: [f32; 4],
+    pub mask_radius: [f32; 4],
+    /// The start and end of a line
+    pub line: [f32; 4],
+    /// The start of the line before a line and the end of the one after it, which are the line's
+    /// own start and end if it isn't connected to them
+    pub line_neighbors: [f32; 4],
+    pub clip: [f32; 4],
+    pub clip_radius: [f32; 4],
 }
 
 #[repr(C)]
@@ -362,6 +474,13 @@ pub struct ImageBindGroups {
     values: HashMap<Handle<Image>, BindGroup>,
 }
 
+impl ImageBindGroups {
+    /// Forgets the bind group of an image that's been removed (or replaced)
+    pub fn remove(&mut self, handle: &Handle<Image>) {
+        self.values.remove(handle);
+    }
+}
+
 pub fn prepare_quads(
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
@@ -379,6 +498,7 @@ pub fn prepare_quads(
     let quad_type_offset = sprite_meta.types_buffer.push(QuadType { t: 0 });
     let text_type_offset = sprite_meta.types_buffer.push(QuadType { t: 1 });
     let image_type_offset = sprite_meta.types_buffer.push(QuadType { t: 2 });
+    let line_type_offset = sprite_meta.types_buffer.push(QuadType { t: 3 });
     sprite_meta
         .types_buffer
         .write_buffer(&render_device, &render_queue);
@@ -389,10 +509,9 @@ pub fn prepare_quads(
         &render_device,
     );
 
-    for (i, mut extracted_sprite) in extracted_quads
+    for mut extracted_sprite in extracted_quads
         .iter_mut()
         .filter(|es| es.quad_type != UIQuadType::Clip)
-        .enumerate()
     {
         let sprite_rect = extracted_sprite.rect;
         let color = extracted_sprite.color.as_linear_rgba_f32();
@@ -401,6 +520,7 @@ pub fn prepare_quads(
             UIQuadType::Quad => extracted_sprite.type_index = quad_type_offset,
             UIQuadType::Text => extracted_sprite.type_index = text_type_offset,
             UIQuadType::Image => extracted_sprite.type_index = image_type_offset,
+            UIQuadType::Line => extracted_sprite.type_index = line_type_offset,
             UIQuadType::Clip => {}
         };
 
@@ -432,6 +552,9 @@ pub fn prepare_quads(
             extracted_sprite.border_radius.bottom_right,
         );
 
+        let (mask, mask_radius) = QuadMask::to_vertex(extracted_sprite.mask);
+        let (clip, clip_radius) = QuadMask::to_vertex(extracted_sprite.clip);
+
         let uvs: [[f32; 4]; 6] = [
             bottom_left.into(),
             top_right.into(),
@@ -441,7 +564,48 @@ pub fn prepare_quads(
             top_right.into(),
         ];
 
-        extracted_sprite.vertex_index = i;
+        // The index of the quad's first vertex, in quads
+        extracted_sprite.vertex_index = sprite_meta.vertices.len() / QUAD_VERTEX_POSITIONS.len();
+
+        // Each line is drawn with a quad rotated along it, which also fits its round ends
+        if extracted_sprite.quad_type == UIQuadType::Line {
+            for line in extracted_sprite.lines.iter() {
+                let direction = (line.end - line.start).normalize_or_zero();
+                let direction = if direction == Vec2::ZERO {
+                    Vec2::X
+                } else {
+                    direction
+                };
+                let normal = direction.perp();
+                let half_width = line.radius + LINE_FEATHER;
+                let size = Vec2::new(
+                    (line.end - line.start).length() + half_width * 2.0,
+                    half_width * 2.0,
+                );
+                let origin = line.start - direction * half_width - normal * half_width;
+                let previous = line.previous.unwrap_or(line.start);
+                let next = line.next.unwrap_or(line.end);
+                for vertex_position in QUAD_VERTEX_POSITIONS.iter() {
+                    let position = origin
+                        + direction * (vertex_position.x * size.x)
+                        + normal * (vertex_position.y * size.y);
+                    sprite_meta.vertices.push(QuadVertex {
+                        position: position.extend(0.0).into(),
+                        color: line.color.as_linear_rgba_f32(),
+                        uv: [0.0, 0.0, 0.0, line.radius],
+                        pos_size: [0.0; 4],
+                        mask,
+                        mask_radius,
+                        line: [line.start.x, line.start.y, line.end.x, line.end.y],
+                        line_neighbors: [previous.x, previous.y, next.x, next.y],
+                        clip,
+                        clip_radius,
+                    });
+                }
+            }
+            continue;
+        }
+
         for (index, vertex_position) in QUAD_VERTEX_POSITIONS.iter().enumerate() {
             let world = Mat4::from_scale_rotation_translation(
                 sprite_rect.size().extend(1.0),
@@ -459,6 +623,12 @@ pub fn prepare_quads(
                     sprite_rect.size().x,
                     sprite_rect.size().y,
                 ],
+                mask,
+                mask_radius,
+                line: [0.0; 4],
+                line_neighbors: [0.0; 4],
+                clip,
+                clip_radius,
             });
         }
     }
@@ -474,7 +644,7 @@ pub fn queue_quads(
     view_uniforms: Res<ViewUniforms>,
     quad_pipeline: Res<UnifiedPipeline>,
     mut extracted_sprites: Query<(Entity, &ExtractedQuad)>,
-    mut views: Query<&mut RenderPhase<TransparentUI>>,
+    mut views: Query<(Entity, &mut RenderPhase<TransparentUI>)>,
     mut image_bind_groups: ResMut<ImageBindGroups>,
     unified_pipeline: Res<UnifiedPipeline>,
     gpu_images: Res<RenderAssets<Image>>,
@@ -502,8 +672,16 @@ pub fn queue_quads(
         }));
 
         let draw_quad = draw_functions.read().get_id::<DrawUI>().unwrap();
-        for mut transparent_phase in views.iter_mut() {
+        for (view_entity, mut transparent_phase) in views.iter_mut() {
             for (entity, quad) in extracted_sprites.iter_mut() {
+                // Each context only renders to its own camera
+                if quad
+                    .camera_entity
+                    .map_or(false, |camera_entity| camera_entity != view_entity)
+                {
+                    continue;
+                }
+
                 if let Some(image_handle) = quad.image.as_ref() {
                     if let Some(gpu_image) = gpu_images.get(&image_handle) {
                         image_bind_groups
@@ -549,6 +727,7 @@ pub struct DrawUI {
         SRes<ImageBindGroups>,
         SRes<WindowSize>,
         SRes<Dpi>,
+        SQuery<Read<ExtractedUIView>>,
         SQuery<Read<ViewUniformOffset>>,
         SQuery<Read<ExtractedQuad>>,
     )>,
@@ -578,6 +757,7 @@ impl Draw<TransparentUI> for DrawUI {
             image_bind_groups,
             window_size,
             dpi,
+            ui_views,
             views,
             quads,
         ) = self.params.get(world);
@@ -587,7 +767,17 @@ impl Draw<TransparentUI> for DrawUI {
         let extracted_quad = quads.get(item.entity).unwrap();
 
         if extracted_quad.quad_type == UIQuadType::Clip {
-            let window_size = (window_size.0 * dpi.0, window_size.1 * dpi.0);
+            let (window_size, offset) = if let Ok(ui_view) = ui_views.get(view) {
+                (
+                    (ui_view.physical_size.x, ui_view.physical_size.y),
+                    (
+                        ui_view.physical_offset.x as u32,
+                        ui_view.physical_offset.y as u32,
+                    ),
+                )
+            } else {
+                ((window_size.0 * dpi.0, window_size.1 * dpi.0), (0, 0))
+            };
             let x = extracted_quad.rect.min.x as u32;
             let y = extracted_quad.rect.min.y as u32;
             let mut width = extracted_quad.rect.width() as u32;
@@ -603,7 +793,8 @@ impl Draw<TransparentUI> for DrawUI {
             if y + height > window_size.1 as u32 {
                 height = window_size.1 as u32 - y;
             }
-            pass.set_scissor_rect(x, y, width, height);
+            // Clips are relative to the camera's viewport, but scissor rects are relative to the target
+            pass.set_scissor_rect(x + offset.0, y + offset.1, width, height);
             return;
         }
 
@@ -645,9 +836,16 @@ impl Draw<TransparentUI> for DrawUI {
                 pass.set_bind_group(3, &unified_pipeline.default_image.1, &[]);
             }
 
+            // Line batches draw all of their lines at once
+            let quad_count = if extracted_quad.quad_type == UIQuadType::Line {
+                extracted_quad.lines.len()
+            } else {
+                1
+            };
             pass.draw(
                 (extracted_quad.vertex_index * QUAD_VERTEX_POSITIONS.len()) as u32
-                    ..((extracted_quad.vertex_index + 1) * QUAD_VERTEX_POSITIONS.len()) as u32,
+                    ..((extracted_quad.vertex_index + quad_count) * QUAD_VERTEX_POSITIONS.len())
+                        as u32,
                 0..1,
             );
         }
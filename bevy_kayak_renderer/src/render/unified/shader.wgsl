@@ -19,6 +19,14 @@ struct VertexOutput {
     @location(3) size: vec2<f32>,
     @location(4) border_radius: f32,
     @location(5) pixel_position: vec2<f32>,
+    @location(6) mask_pos: vec2<f32>,
+    @location(7) mask_size: vec2<f32>,
+    @location(8) mask_radius: vec4<f32>,
+    @location(9) @interpolate(flat) line_ends: vec4<f32>,
+    @location(10) @interpolate(flat) line_neighbors: vec4<f32>,
+    @location(11) clip_pos: vec2<f32>,
+    @location(12) clip_size: vec2<f32>,
+    @location(13) clip_radius: vec4<f32>,
 };
 
 @vertex
@@ -27,6 +35,12 @@ fn vertex(
     @location(1) vertex_color: vec4<f32>,
     @location(2) vertex_uv: vec4<f32>,
     @location(3) vertex_pos_size: vec4<f32>,
+    @location(4) vertex_mask: vec4<f32>,
+    @location(5) vertex_mask_radius: vec4<f32>,
+    @location(6) vertex_line: vec4<f32>,
+    @location(7) vertex_line_neighbors: vec4<f32>,
+    @location(8) vertex_clip: vec4<f32>,
+    @location(9) vertex_clip_radius: vec4<f32>,
 ) -> VertexOutput {
     var out: VertexOutput;
     out.color = vertex_color;
@@ -36,6 +50,14 @@ fn vertex(
     out.uv = vertex_uv.xyz;
     out.size = vertex_pos_size.zw;
     out.border_radius = vertex_uv.w;
+    out.mask_pos = (vertex_position.xy - vertex_mask.xy);
+    out.mask_size = vertex_mask.zw;
+    out.mask_radius = vertex_mask_radius;
+    out.line_ends = vertex_line;
+    out.line_neighbors = vertex_line_neighbors;
+    out.clip_pos = (vertex_position.xy - vertex_clip.xy);
+    out.clip_size = vertex_clip.zw;
+    out.clip_radius = vertex_clip_radius;
     return out;
 }
 
@@ -57,8 +79,39 @@ fn sdRoundBox(p: vec2<f32>, b: vec2<f32>, r: f32) -> f32 {
     return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - r;
 }
 
+// Where P is the position relative to the center of the box, B is half the size of the box and R holds
+// the radius of each corner (top left, top right, bottom left, bottom right).
+fn sdRoundBoxCorners(p: vec2<f32>, b: vec2<f32>, r: vec4<f32>) -> f32 {
+    var top = select(r.y, r.x, p.x < 0.0);
+    var bottom = select(r.w, r.z, p.x < 0.0);
+    var radius = min(select(bottom, top, p.y < 0.0), min(b.x, b.y));
+    return sdRoundBox(p, b, radius);
+}
+
+// The distance from P to the line from A to B.
+fn sdSegment(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    var pa = p - a;
+    var ba = b - a;
+    var h = clamp(dot(pa, ba) / max(dot(ba, ba), 0.0001), 0.0, 1.0);
+    return length(pa - ba * h);
+}
+
+// How much of the pixel at P (relative to the top left of a rounded box) is within the box, where
+// B is the size of the box and R holds the radius of each corner. An empty box masks nothing.
+fn roundBoxMask(p: vec2<f32>, b: vec2<f32>, r: vec4<f32>) -> f32 {
+    var half_size = b * 0.5;
+    var dist = sdRoundBoxCorners(p - half_size, half_size, r);
+    var mask = 1.0 - smoothstep(0.0, fwidth(dist), dist);
+    return select(mask, 1.0, b.x <= 0.0 || b.y <= 0.0);
+}
+
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The quad's own rounded mask, within the rounded clip it's drawn in. This is computed up front
+    // since fwidth needs uniform control flow.
+    var clip_mask = roundBoxMask(in.mask_pos, in.mask_size, in.mask_radius)
+        * roundBoxMask(in.clip_pos, in.clip_size, in.clip_radius);
+
     if (quad_type.t == 0) {
         var size = in.size;
         var pos = in.pos.xy * 2.0;
@@ -70,7 +123,7 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
             bs,
         );
         rect_dist = 1.0 - smoothstep(0.0, fwidth(rect_dist), rect_dist);
-        return vec4<f32>(in.color.rgb, rect_dist * in.color.a);
+        return vec4<f32>(in.color.rgb, rect_dist * in.color.a * clip_mask);
     }
     if (quad_type.t == 1) {
         var px_range = 3.5;
@@ -80,7 +133,7 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         var v = max(min(x.r, x.g), min(max(x.r, x.g), x.b));
         var sig_dist = (v - 0.5) * dot(msdf_unit, 0.5 / fwidth(in.uv.xy));
         var a = clamp(sig_dist + 0.5, 0.0, 1.0);
-        return vec4<f32>(in.color.rgb, a);
+        return vec4<f32>(in.color.rgb, a * clip_mask);
     }
     if (quad_type.t == 2) {
         var bs = min(in.border_radius, min(in.size.x, in.size.y));
@@ -91,7 +144,24 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         );
         mask = 1.0 - smoothstep(0.0, fwidth(mask), mask);
         var color = textureSample(image_texture, image_sampler, vec2<f32>(in.uv.x, 1.0 - in.uv.y));
-        return vec4<f32>(color.rgb * in.color.rgb, color.a * in.color.a * mask);
+        return vec4<f32>(color.rgb * in.color.rgb, color.a * in.color.a * mask * clip_mask);
+    }
+    if (quad_type.t == 3) {
+        // Lines are capsules with round ends, positioned in the same space as the fragment
+        var start = in.line_ends.xy;
+        var end = in.line_ends.zw;
+        var dist = sdSegment(in.pos, start, end);
+        var line_dist = dist - in.border_radius;
+        line_dist = 1.0 - smoothstep(0.0, fwidth(line_dist), line_dist);
+
+        // Where the ends of connected lines overlap, only the closest line is drawn, so that their
+        // joint isn't blended twice. Lines that aren't connected have themselves as neighbors.
+        var previous = in.line_neighbors.xy;
+        var next = in.line_neighbors.zw;
+        var is_previous_closer = any(previous != start) && sdSegment(in.pos, previous, start) <= dist;
+        var is_next_closer = any(next != end) && sdSegment(in.pos, end, next) < dist;
+        line_dist = select(line_dist, 0.0, is_previous_closer || is_next_closer);
+        return vec4<f32>(in.color.rgb, line_dist * in.color.a * clip_mask);
     }
-    return in.color;
+    return vec4<f32>(in.color.rgb, in.color.a * clip_mask);
 }
@@ -1,6 +1,9 @@
 use std::sync::{Arc, RwLock};
 
-use kayak_core::context::KayakContext;
+use bevy::prelude::Component;
+use kayak_core::{bind, context::KayakContext, Binding, Bound, MutableBound};
+
+use crate::WindowSize;
 
 /// A wrapper around `KayakContext` to be used in Bevy integrations
 ///
@@ -12,6 +15,22 @@ use kayak_core::context::KayakContext;
 ///   // ...
 /// }
 /// ```
+///
+/// # Multiple Windows
+///
+/// Inserted as a resource, the context belongs to the primary window (or whichever window the UI
+/// camera renders to). To give another window its own UI, insert a separate context as a component
+/// on a UI camera that targets that window:
+///
+/// ```ignore
+/// commands
+///     .spawn_bundle(UICameraBundle::with_target_window(window_id))
+///     .insert(BevyContext::new(|context| { /* ... */ }));
+/// ```
+///
+/// Each context has its own widget tree, focus, and event handling, and only receives input from
+/// its own window. Fonts and images are shared between all contexts.
+#[derive(Component)]
 pub struct BevyContext {
     pub kayak_context: Arc<RwLock<KayakContext>>,
 }
@@ -48,6 +67,73 @@ impl BevyContext {
         Self { kayak_context }
     }
 
+    /// Sets the size of the window (or image) this context renders to
+    ///
+    /// This is only used by contexts attached to a camera, since the resource context reads the
+    /// window size from the `World`.
+    pub(crate) fn set_window_size(&self, window_size: WindowSize) {
+        if let Ok(mut kayak_context) = self.kayak_context.write() {
+            let binding = kayak_context
+                .get_global::<Binding<WindowSize>>()
+                .map(|binding| (*binding).clone());
+
+            match binding {
+                Ok(binding) => {
+                    if binding.get() != window_size {
+                        binding.set(window_size);
+                    }
+                }
+                Err(..) => kayak_context.set_global(bind(window_size)),
+            }
+        }
+    }
+
+    /// Returns true if the context needs to be rendered
+    ///
+    /// The context is only rendered when this is true, so an idle UI costs (almost) nothing.
+    pub fn is_dirty(&self) -> bool {
+        if let Ok(kayak_context) = self.kayak_context.read() {
+            kayak_context.is_dirty()
+        } else {
+            false
+        }
+    }
+
+    /// Requests that the context be rendered on the next frame, even if nothing is dirty
+    pub fn request_render(&self) {
+        if let Ok(mut kayak_context) = self.kayak_context.write() {
+            kayak_context.request_render();
+        }
+    }
+
+    /// Returns true if the debug overlay is drawn above the UI
+    pub fn debug_overlay(&self) -> bool {
+        if let Ok(kayak_context) = self.kayak_context.read() {
+            kayak_context.debug_overlay()
+        } else {
+            false
+        }
+    }
+
+    /// Shows or hides the debug overlay, which visualizes the layout of every widget
+    ///
+    /// See [`kayak_core::debug_overlay`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// fn toggle_debug_overlay(keys: Res<Input<KeyCode>>, context: Res<BevyContext>) {
+    ///     if keys.just_pressed(KeyCode::F12) {
+    ///         context.set_debug_overlay(!context.debug_overlay());
+    ///     }
+    /// }
+    /// ```
+    pub fn set_debug_overlay(&self, enabled: bool) {
+        if let Ok(mut kayak_context) = self.kayak_context.write() {
+            kayak_context.set_debug_overlay(enabled);
+        }
+    }
+
     /// Returns true if the cursor is currently over a valid widget
     ///
     /// For the purposes of this method, a valid widget is one which has the means to display a visual component on its own.
@@ -85,3 +171,34 @@ impl BevyContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use kayak_core::{Binding, Bound};
+
+    use super::BevyContext;
+    use crate::WindowSize;
+
+    fn window_size(bevy_context: &BevyContext) -> Binding<WindowSize> {
+        let mut kayak_context = bevy_context.kayak_context.write().unwrap();
+        let binding = kayak_context.get_global::<Binding<WindowSize>>().unwrap();
+        (*binding).clone()
+    }
+
+    #[test]
+    fn should_keep_a_separate_window_size_per_context() {
+        let primary = BevyContext::new(|_| {});
+        let secondary = BevyContext::new(|_| {});
+        primary.set_window_size(WindowSize(1280.0, 720.0));
+        secondary.set_window_size(WindowSize(640.0, 480.0));
+
+        let binding = window_size(&primary);
+        assert_eq!(WindowSize(1280.0, 720.0), binding.get());
+        assert_eq!(WindowSize(640.0, 480.0), window_size(&secondary).get());
+
+        // Resizing updates the existing binding so that widgets bound to it re-render
+        primary.set_window_size(WindowSize(800.0, 600.0));
+        assert_eq!(WindowSize(800.0, 600.0), binding.get());
+        assert_eq!(WindowSize(640.0, 480.0), window_size(&secondary).get());
+    }
+}
@@ -0,0 +1,19 @@
+/// An IME (Input Method Editor) event to be forwarded to the focused widget
+///
+/// Bevy does not yet surface platform IME events, so they aren't sent automatically. Instead,
+/// applications (or plugins with access to the platform's IME state) can send these using an
+/// `EventWriter<ImeEvent>` and they will be delivered to the currently focused widget as
+/// [`EventType::ImePreedit`](kayak_core::EventType::ImePreedit) and
+/// [`EventType::ImeCommit`](kayak_core::EventType::ImeCommit).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImeEvent {
+    /// The IME updated its in-progress (composing) text
+    ///
+    /// The `cursor` is the `(start, end)` byte range within `text` targeted by the IME, if any.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// The IME committed its finalized text
+    Commit(String),
+}
@@ -1,26 +1,44 @@
+use std::sync::RwLock;
+
 use bevy::{
     input::{
         keyboard::KeyboardInput,
         mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+        touch::{TouchInput, TouchPhase},
         ButtonState,
     },
     math::Vec2,
-    prelude::{EventReader, IntoExclusiveSystem, MouseButton, Plugin, Res, World},
-    render::color::Color,
-    window::{CursorMoved, ReceivedCharacter, WindowCreated, WindowResized, Windows},
+    prelude::{
+        Assets, Camera, EventReader, Events, Image, IntoExclusiveSystem, MouseButton, Plugin,
+        Query, Res, ResMut, With, Without, World,
+    },
+    render::{
+        camera::{RenderTarget, Viewport},
+        color::Color,
+    },
+    window::{
+        CursorMoved, ReceivedCharacter, RequestRedraw, WindowCreated, WindowFocused, WindowId,
+        WindowResized, Windows,
+    },
 };
 
 mod bevy_context;
 mod cursor;
+mod ime;
 mod key;
+#[cfg(feature = "reflect")]
+mod reflect;
 mod render;
 
 use crate::cursor::convert_cursor_icon;
 pub use bevy_context::BevyContext;
 pub use bevy_kayak_renderer::camera::*;
-use kayak_core::{bind, Binding, InputEvent, MutableBound};
+pub use ime::ImeEvent;
+use kayak_core::{bind, context::KayakContext, Binding, Bound, InputEvent, MutableBound};
+#[cfg(feature = "reflect")]
+pub use reflect::ReflectedStyle;
 pub use render::font::FontMapping;
-pub use render::image::ImageManager;
+pub use render::image::{ImageError, ImageManager};
 
 #[derive(Default)]
 pub struct BevyKayakUIPlugin;
@@ -28,11 +46,17 @@ pub struct BevyKayakUIPlugin;
 impl Plugin for BevyKayakUIPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.insert_resource(bind(WindowSize::default()))
+            .init_resource::<FocusedWindow>()
+            .add_event::<ImeEvent>()
             .add_plugin(bevy_kayak_renderer::BevyKayakRendererPlugin)
             .add_plugin(render::BevyKayakUIExtractPlugin)
             .add_system(update_window_size)
+            .add_system(update_focused_window)
             .add_system(process_events.exclusive_system())
             .add_system(update.exclusive_system());
+
+        #[cfg(feature = "reflect")]
+        reflect::build(app);
     }
 }
 
@@ -42,104 +66,351 @@ pub(crate) fn to_bevy_color(color: &kayak_core::color::Color) -> Color {
 
 pub fn update(world: &mut World) {
     if let Some(bevy_context) = world.remove_resource::<BevyContext>() {
-        if let Ok(mut context) = bevy_context.kayak_context.write() {
-            context.set_global(std::mem::take(world));
-            context.render();
-            *world = context.remove_global::<World>().unwrap();
+        let (window_id, _) = resource_context_target(world);
+        render_context(world, &bevy_context.kayak_context, Some(window_id));
+        world.insert_resource(bevy_context);
+    }
+
+    let mut cameras = world.query_filtered::<(&Camera, &BevyContext), With<CameraUiKayak>>();
+    let camera_contexts = {
+        let windows = world.get_resource::<Windows>();
+        let images = world.get_resource::<Assets<Image>>();
+        cameras
+            .iter(world)
+            .map(|(camera, bevy_context)| {
+                // Keep the context's size in sync with its own render target
+                if let Some(size) = windows
+                    .zip(images)
+                    .and_then(|(windows, images)| target_size(camera, windows, images))
+                {
+                    bevy_context.set_window_size(size);
+                }
+
+                (target_window(camera), bevy_context.kayak_context.clone())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for (window_id, kayak_context) in camera_contexts {
+        render_context(world, &kayak_context, window_id);
+    }
+}
+
+fn render_context(
+    world: &mut World,
+    kayak_context: &RwLock<KayakContext>,
+    window_id: Option<WindowId>,
+) {
+    if let Ok(mut context) = kayak_context.write() {
+        // Widgets whose queried ECS data changed need to be re-rendered
+        context.update_world_queries(world);
+
+        // Nothing has changed since the last render, so there's nothing to do
+        if !context.is_dirty() {
+            context.apply_world_commands(world);
+            return;
+        }
+
+        context.set_global(std::mem::take(world));
+        context.render();
+        *world = context.remove_global::<World>().unwrap();
 
+        // Commands queued by event handlers (or widgets) only run once the render is committed
+        context.apply_world_commands(world);
+
+        // Widgets that are still animating need another frame, even if the app only updates on
+        // demand (e.g. with `WinitSettings::desktop_app`)
+        if context.is_dirty() {
+            if let Some(mut request_redraw_events) =
+                world.get_resource_mut::<Events<RequestRedraw>>()
+            {
+                request_redraw_events.send(RequestRedraw);
+            }
+        }
+
+        if let Some(window_id) = window_id {
             if let Some(ref mut windows) = world.get_resource_mut::<Windows>() {
-                if let Some(window) = windows.get_primary_mut() {
+                if let Some(window) = windows.get_mut(window_id) {
                     window.set_cursor_icon(convert_cursor_icon(context.cursor_icon()));
                 }
             }
         }
+    }
+}
 
+pub fn process_events(world: &mut World) {
+    if let Some(bevy_context) = world.remove_resource::<BevyContext>() {
+        let (window_id, viewport) = resource_context_target(world);
+        process_context_events(
+            world,
+            &bevy_context.kayak_context,
+            window_id,
+            viewport.as_ref(),
+        );
         world.insert_resource(bevy_context);
     }
+
+    let mut cameras = world.query_filtered::<(&Camera, &BevyContext), With<CameraUiKayak>>();
+    let camera_contexts = cameras
+        .iter(world)
+        .filter_map(|(camera, bevy_context)| {
+            // Contexts rendered to an image don't receive any input
+            target_window(camera).map(|window_id| {
+                (
+                    window_id,
+                    camera.viewport.clone(),
+                    bevy_context.kayak_context.clone(),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (window_id, viewport, kayak_context) in camera_contexts {
+        process_context_events(world, &kayak_context, window_id, viewport.as_ref());
+    }
 }
 
-pub fn process_events(world: &mut World) {
-    let window_size = if let Some(windows) = world.get_resource::<Windows>() {
-        if let Some(window) = windows.get_primary() {
-            Vec2::new(window.width(), window.height())
+/// Sends the input events belonging to the given window to the given context
+///
+/// Cursor movement and character events are tagged with their window, so they're always routed
+/// to that window's context. For all other events, Bevy doesn't record which window they came from,
+/// so mouse events are routed to the window under the cursor and keyboard events to the focused window.
+///
+/// If the context is drawn to a viewport, positions are made relative to the viewport and mouse and
+/// keyboard events are only sent while the cursor is over it (as are touches within it).
+fn process_context_events(
+    world: &mut World,
+    kayak_context: &RwLock<KayakContext>,
+    window_id: WindowId,
+    viewport: Option<&Viewport>,
+) {
+    let focused_window = world
+        .get_resource::<FocusedWindow>()
+        .and_then(|focused_window| focused_window.0);
+
+    let (window_height, viewport_rect, has_pointer, has_keyboard) =
+        if let Some(windows) = world.get_resource::<Windows>() {
+            if let Some(window) = windows.get(window_id) {
+                // With only one window, there's no need to check where the input came from
+                let is_single_window = windows.iter().count() == 1;
+                let viewport_rect = viewport.map(|viewport| {
+                    let scale_factor = window.scale_factor() as f32;
+                    ViewportRect {
+                        position: viewport.physical_position.as_vec2() / scale_factor,
+                        size: viewport.physical_size.as_vec2() / scale_factor,
+                    }
+                });
+                let is_over_viewport = match (viewport_rect, window.cursor_position()) {
+                    (Some(rect), Some(position)) => {
+                        rect.contains(Vec2::new(position.x, window.height() - position.y))
+                    }
+                    (Some(..), None) => false,
+                    (None, _) => true,
+                };
+                (
+                    window.height(),
+                    viewport_rect,
+                    (is_single_window || window.cursor_position().is_some()) && is_over_viewport,
+                    (is_single_window || focused_window == Some(window_id)) && is_over_viewport,
+                )
+            } else {
+                log::warn!("Couldn't find window {:?}!", window_id);
+                return;
+            }
         } else {
-            log::warn!("Couldn't find primiary window!");
+            log::warn!("Couldn't find window {:?}!", window_id);
             return;
-        }
-    } else {
-        log::warn!("Couldn't find primiary window!");
-        return;
-    };
+        };
 
-    if let Some(bevy_context) = world.remove_resource::<BevyContext>() {
-        if let Ok(mut context) = bevy_context.kayak_context.write() {
-            let mut input_events = Vec::new();
-
-            context.set_global(std::mem::take(world));
-            context.query_world::<(
-                EventReader<CursorMoved>,
-                EventReader<MouseButtonInput>,
-                EventReader<MouseWheel>,
-                EventReader<ReceivedCharacter>,
-                EventReader<KeyboardInput>,
-            ), _, _>(
-                |(
-                    mut cursor_moved_events,
-                    mut mouse_button_input_events,
-                    mut mouse_wheel_events,
-                    mut char_input_events,
-                    mut keyboard_input_events,
-                )| {
-                    if let Some(event) = cursor_moved_events.iter().last() {
-                        // Currently, we can only handle a single MouseMoved event at a time so everything but the last needs to be skipped
-                        input_events.push(InputEvent::MouseMoved((
-                            event.position.x as f32,
-                            window_size.y - event.position.y as f32,
-                        )));
-                    }
+    if let Ok(mut context) = kayak_context.write() {
+        let mut input_events = Vec::new();
+        let to_local = |position: Vec2| {
+            viewport_rect.map_or(position, |viewport_rect| position - viewport_rect.position)
+        };
 
-                    for event in mouse_button_input_events.iter() {
-                        match event.button {
-                            MouseButton::Left => {
-                                if event.state == ButtonState::Pressed {
-                                    input_events.push(InputEvent::MouseLeftPress);
-                                } else if event.state == ButtonState::Released {
-                                    input_events.push(InputEvent::MouseLeftRelease);
-                                }
+        context.set_global(std::mem::take(world));
+        context.query_world::<(
+            EventReader<CursorMoved>,
+            EventReader<MouseButtonInput>,
+            EventReader<MouseWheel>,
+            EventReader<ReceivedCharacter>,
+            EventReader<KeyboardInput>,
+            EventReader<ImeEvent>,
+            EventReader<TouchInput>,
+        ), _, _>(
+            |(
+                mut cursor_moved_events,
+                mut mouse_button_input_events,
+                mut mouse_wheel_events,
+                mut char_input_events,
+                mut keyboard_input_events,
+                mut ime_events,
+                mut touch_events,
+            )| {
+                if let Some(event) = cursor_moved_events
+                    .iter()
+                    .filter(|event| event.id == window_id)
+                    .last()
+                {
+                    // Currently, we can only handle a single MouseMoved event at a time so everything but the last needs to be skipped
+                    let position = to_local(Vec2::new(
+                        event.position.x,
+                        window_height - event.position.y,
+                    ));
+                    input_events.push(InputEvent::MouseMoved((position.x, position.y)));
+                }
+
+                for event in mouse_button_input_events.iter().filter(|_| has_pointer) {
+                    match event.button {
+                        MouseButton::Left => {
+                            if event.state == ButtonState::Pressed {
+                                input_events.push(InputEvent::MouseLeftPress);
+                            } else if event.state == ButtonState::Released {
+                                input_events.push(InputEvent::MouseLeftRelease);
                             }
-                            _ => {}
                         }
+                        _ => {}
                     }
+                }
 
-                    for MouseWheel { x, y, unit } in mouse_wheel_events.iter() {
-                        input_events.push(InputEvent::Scroll {
-                            dx: *x,
-                            dy: *y,
-                            is_line: matches!(unit, MouseScrollUnit::Line),
-                        })
-                    }
+                for MouseWheel { x, y, unit } in mouse_wheel_events.iter().filter(|_| has_pointer) {
+                    input_events.push(InputEvent::Scroll {
+                        dx: *x,
+                        dy: *y,
+                        is_line: matches!(unit, MouseScrollUnit::Line),
+                    })
+                }
 
-                    for event in char_input_events.iter() {
-                        input_events.push(InputEvent::CharEvent { c: event.char });
-                    }
+                for event in char_input_events
+                    .iter()
+                    .filter(|event| event.id == window_id)
+                {
+                    input_events.push(InputEvent::CharEvent { c: event.char });
+                }
 
-                    for event in keyboard_input_events.iter() {
-                        if let Some(key_code) = event.key_code {
-                            let kayak_key_code = key::convert_virtual_key_code(key_code);
-                            input_events.push(InputEvent::Keyboard {
-                                key: kayak_key_code,
-                                is_pressed: matches!(event.state, ButtonState::Pressed),
-                            });
-                        }
+                for event in keyboard_input_events.iter().filter(|_| has_keyboard) {
+                    if let Some(key_code) = event.key_code {
+                        let kayak_key_code = key::convert_virtual_key_code(key_code);
+                        input_events.push(InputEvent::Keyboard {
+                            key: kayak_key_code,
+                            is_pressed: matches!(event.state, ButtonState::Pressed),
+                        });
                     }
-                },
-            );
+                }
 
-            context.process_events(input_events);
-            *world = context.remove_global::<World>().unwrap()
+                for event in ime_events.iter().filter(|_| has_keyboard) {
+                    input_events.push(match event {
+                        ImeEvent::Preedit { text, cursor } => InputEvent::ImePreedit {
+                            text: text.clone(),
+                            cursor: *cursor,
+                        },
+                        ImeEvent::Commit(text) => InputEvent::ImeCommit(text.clone()),
+                    });
+                }
+
+                // Touches aren't tied to a window, so they're sent to the focused one
+                let touch_events = touch_events.iter().filter(|event| match viewport_rect {
+                    Some(viewport_rect) => viewport_rect.contains(event.position),
+                    None => has_keyboard,
+                });
+                for event in touch_events {
+                    let position = to_local(event.position);
+                    input_events.push(InputEvent::Touch {
+                        id: event.id,
+                        phase: match event.phase {
+                            TouchPhase::Started => kayak_core::TouchPhase::Started,
+                            TouchPhase::Moved => kayak_core::TouchPhase::Moved,
+                            TouchPhase::Ended => kayak_core::TouchPhase::Ended,
+                            TouchPhase::Cancelled => kayak_core::TouchPhase::Cancelled,
+                        },
+                        // Unlike the cursor, touch positions already have their origin at the top-left
+                        position: (position.x, position.y),
+                    });
+                }
+            },
+        );
+
+        context.process_events(input_events);
+        *world = context.remove_global::<World>().unwrap()
+    }
+}
+
+/// The logical bounds of a camera's viewport, with the origin at the top-left of its window
+#[derive(Debug, Clone, Copy)]
+struct ViewportRect {
+    position: Vec2,
+    size: Vec2,
+}
+
+impl ViewportRect {
+    fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.position).all() && point.cmplt(self.position + self.size).all()
+    }
+}
+
+/// Returns the window (and viewport) the `BevyContext` resource belongs to
+///
+/// This is the window rendered to by the UI camera that doesn't have its own context, falling back
+/// to the whole primary window.
+fn resource_context_target(world: &mut World) -> (WindowId, Option<Viewport>) {
+    let mut cameras =
+        world.query_filtered::<&Camera, (With<CameraUiKayak>, Without<BevyContext>)>();
+    cameras
+        .iter(world)
+        .find_map(|camera| {
+            target_window(camera).map(|window_id| (window_id, camera.viewport.clone()))
+        })
+        .unwrap_or_else(|| (WindowId::primary(), None))
+}
+
+/// Returns the window the given camera renders to, if any
+fn target_window(camera: &Camera) -> Option<WindowId> {
+    match camera.target {
+        RenderTarget::Window(window_id) => Some(window_id),
+        RenderTarget::Image(..) => None,
+    }
+}
+
+/// Returns the logical size of the window or image the given camera renders to
+///
+/// If the camera has a viewport, the UI only fills that, so the viewport's size is returned instead.
+fn target_size(camera: &Camera, windows: &Windows, images: &Assets<Image>) -> Option<WindowSize> {
+    if camera.viewport.is_some() {
+        return camera
+            .logical_viewport_size()
+            .map(|size| WindowSize(size.x, size.y));
+    }
+
+    match camera.target {
+        RenderTarget::Window(window_id) => windows
+            .get(window_id)
+            .map(|window| WindowSize(window.width(), window.height())),
+        RenderTarget::Image(..) => {
+            target_image_size(camera, images).map(|size| WindowSize(size.x, size.y))
         }
+    }
+}
 
-        world.insert_resource(bevy_context);
+/// Tracks the window that currently has keyboard focus.
+#[derive(Debug, Clone, Copy)]
+struct FocusedWindow(Option<WindowId>);
+
+impl Default for FocusedWindow {
+    fn default() -> Self {
+        Self(Some(WindowId::primary()))
+    }
+}
+
+fn update_focused_window(
+    mut window_focused_events: EventReader<WindowFocused>,
+    mut focused_window: ResMut<FocusedWindow>,
+) {
+    for event in window_focused_events.iter() {
+        if event.focused {
+            focused_window.0 = Some(event.id);
+        } else if focused_window.0 == Some(event.id) {
+            focused_window.0 = None;
+        }
     }
 }
 
@@ -151,8 +422,31 @@ fn update_window_size(
     mut window_resized_events: EventReader<WindowResized>,
     mut window_created_events: EventReader<WindowCreated>,
     windows: Res<Windows>,
+    cameras: Query<&Camera, (With<CameraUiKayak>, Without<BevyContext>)>,
+    images: Res<Assets<Image>>,
     window_size: Res<Binding<WindowSize>>,
 ) {
+    // Contexts attached to a camera track their own size, so only the resource context's window matters here
+    let context_window = cameras
+        .iter()
+        .find_map(target_window)
+        .unwrap_or_else(WindowId::primary);
+
+    // When rendering to a texture or a viewport, its size is used instead of the window's
+    if let Some(size) = cameras.iter().find_map(|camera| {
+        if camera.viewport.is_some() {
+            camera.logical_viewport_size()
+        } else {
+            target_image_size(camera, &images)
+        }
+    }) {
+        let size = WindowSize(size.x, size.y);
+        if window_size.get() != size {
+            window_size.set(size);
+        }
+        return;
+    }
+
     let mut changed_window_ids = Vec::new();
     // handle resize events. latest events are handled first because we only want to resize each
     // window once
@@ -175,6 +469,10 @@ fn update_window_size(
     }
 
     for window_id in changed_window_ids {
+        if window_id != context_window {
+            continue;
+        }
+
         if let Some(window) = windows.get(window_id) {
             let width = window.width();
             let height = window.height();
@@ -182,3 +480,61 @@ fn update_window_size(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::{UVec2, Vec2},
+        prelude::World,
+        render::camera::Viewport,
+        window::WindowId,
+    };
+
+    use super::{resource_context_target, BevyContext, UICameraBundle, ViewportRect};
+
+    fn viewport(x: u32) -> Viewport {
+        Viewport {
+            physical_position: UVec2::new(x, 0),
+            physical_size: UVec2::new(400, 300),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_only_contain_points_within_the_viewport() {
+        let rect = ViewportRect {
+            position: Vec2::new(400.0, 0.0),
+            size: Vec2::new(400.0, 300.0),
+        };
+        assert!(rect.contains(Vec2::new(400.0, 0.0)));
+        assert!(rect.contains(Vec2::new(799.0, 299.0)));
+        assert!(!rect.contains(Vec2::new(399.0, 150.0)));
+        assert!(!rect.contains(Vec2::new(800.0, 150.0)));
+        assert!(!rect.contains(Vec2::new(600.0, 300.0)));
+    }
+
+    #[test]
+    fn should_target_the_viewport_of_the_camera_without_a_context() {
+        let mut world = World::new();
+        let (window_id, primary_viewport) = resource_context_target(&mut world);
+        assert_eq!(WindowId::primary(), window_id);
+        assert!(primary_viewport.is_none());
+
+        // Cameras with their own context belong to that context instead
+        let secondary = WindowId::new();
+        world
+            .spawn()
+            .insert_bundle(UICameraBundle::with_viewport(secondary, viewport(0)))
+            .insert(BevyContext::new(|_| {}));
+        world
+            .spawn()
+            .insert_bundle(UICameraBundle::with_viewport(secondary, viewport(400)));
+
+        let (window_id, viewport) = resource_context_target(&mut world);
+        assert_eq!(secondary, window_id);
+        assert_eq!(
+            Some(UVec2::new(400, 0)),
+            viewport.map(|viewport| viewport.physical_position)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::prelude::{App, Changed, Component, Query, Reflect, ReflectComponent};
+use kayak_core::render_command::RenderCommand;
+use kayak_core::styles::{
+    AlignItems, BorderStyle, BoxSizing, Corner, Edge, FlexWrap, FocusAnimation, FocusOutline,
+    JustifyContent, LayoutType, Position, StackAlignment, Style, StyleProp, TextOutline,
+    TextShadow, TextTransform, Transform, Units, Visibility,
+};
+use kayak_core::{bind, Binding, Color, CursorIcon, MutableBound, PointerEvents};
+
+/// A [`Style`] stored on an entity so that it can be edited from the Bevy world, such as
+/// with `bevy-inspector-egui`
+///
+/// Widgets use the style by binding to its [`binding`](Self::binding), which is updated whenever
+/// the component changes, causing them to re-render:
+///
+/// ```ignore
+/// #[widget]
+/// fn MyWidget(entity: Entity) {
+///   let styles = context.query_world::<Query<&ReflectedStyle>, _, _>(|query| {
+///     query.get(entity).ok().map(|style| style.binding())
+///   });
+///   if let Some(styles) = &styles {
+///     context.bind(styles);
+///   }
+///
+///   rsx! {
+///     <Background styles={styles.map(|styles| styles.get())}>
+///       {children}
+///     </Background>
+///   }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component)]
+pub struct ReflectedStyle {
+    /// The style to edit
+    pub style: Style,
+    #[reflect(ignore)]
+    binding: Binding<Style>,
+}
+
+impl ReflectedStyle {
+    pub fn new(style: Style) -> Self {
+        Self {
+            binding: bind(style.clone()),
+            style,
+        }
+    }
+
+    /// Returns the binding that is kept in sync with this component's style
+    pub fn binding(&self) -> Binding<Style> {
+        self.binding.clone()
+    }
+}
+
+impl Default for ReflectedStyle {
+    fn default() -> Self {
+        Self::new(Style::default())
+    }
+}
+
+/// Registers each of the given types along with the [`StyleProp`] wrapping it
+macro_rules! register_style_props {
+    ($app: expr, $($ty: ty),* $(,)?) => {
+        $app$(.register_type::<$ty>().register_type::<StyleProp<$ty>>())*
+    };
+}
+
+/// Registers the reflected style types and keeps [`ReflectedStyle`] bindings in sync
+///
+/// This covers every type reachable from a [`ReflectedStyle`], so inspectors can edit all of it.
+pub(crate) fn build(app: &mut App) {
+    register_style_props!(
+        app,
+        AlignItems,
+        BoxSizing,
+        Color,
+        Corner<f32>,
+        CursorIcon,
+        Edge<BorderStyle>,
+        Edge<Color>,
+        Edge<f32>,
+        Edge<Units>,
+        FlexWrap,
+        FocusOutline,
+        JustifyContent,
+        LayoutType,
+        PointerEvents,
+        Position,
+        RenderCommand,
+        StackAlignment,
+        TextOutline,
+        TextShadow,
+        TextTransform,
+        Transform,
+        Units,
+        Visibility,
+        bool,
+        f32,
+        String,
+    )
+    .register_type::<ReflectedStyle>()
+    .register_type::<Style>()
+    .register_type::<BorderStyle>()
+    .register_type::<FocusAnimation>()
+    .register_type::<(f32, f32)>()
+    .register_type::<()>()
+    .add_system(update_reflected_styles);
+}
+
+/// Pushes changes to [`ReflectedStyle`] components into their bindings
+///
+/// Since bindings only notify on actual changes, merely accessing the component mutably
+/// (as inspectors tend to do) doesn't re-render anything.
+fn update_reflected_styles(query: Query<&ReflectedStyle, Changed<ReflectedStyle>>) {
+    for reflected_style in query.iter() {
+        reflected_style.binding.set(reflected_style.style.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use bevy::prelude::App;
+    use bevy::reflect::{TypeInfo, TypeRegistryArc};
+
+    use super::ReflectedStyle;
+
+    #[test]
+    fn should_register_every_type_reachable_from_the_reflected_style() {
+        let mut app = App::new();
+        super::build(&mut app);
+        let registry = app.world.resource::<TypeRegistryArc>().read();
+
+        let mut visited = Vec::new();
+        let mut pending = vec![(TypeId::of::<ReflectedStyle>(), "ReflectedStyle")];
+        while let Some((type_id, type_name)) = pending.pop() {
+            if visited.contains(&type_id) {
+                continue;
+            }
+            visited.push(type_id);
+
+            let registration = registry
+                .get(type_id)
+                .unwrap_or_else(|| panic!("Expected {} to be registered", type_name));
+            match registration.type_info() {
+                TypeInfo::Struct(info) => pending.extend(
+                    info.iter()
+                        .map(|field| (field.type_id(), field.type_name())),
+                ),
+                TypeInfo::Tuple(info) => pending.extend(
+                    info.iter()
+                        .map(|field| (field.type_id(), field.type_name())),
+                ),
+                _ => {}
+            }
+        }
+
+        // Every style property holds a value, so the walk should have gone beyond `Style`
+        assert!(visited.contains(&TypeId::of::<kayak_core::styles::Edge<f32>>()));
+    }
+}
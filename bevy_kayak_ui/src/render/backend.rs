@@ -0,0 +1,381 @@
+use std::sync::Arc;
+
+use bevy::{
+    asset::HandleId,
+    math::Vec2,
+    prelude::{Assets, Handle, Res},
+    render::{
+        color::Color,
+        render_asset::{RenderAsset, RenderAssets},
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        renderer::{RenderDevice, RenderQueue},
+        texture::{DefaultImageSampler, Image},
+    },
+    sprite::Rect,
+    utils::HashMap,
+};
+use bevy_kayak_renderer::{
+    render::unified::pipeline::{
+        ExtractQuadBundle, ExtractedQuad, ImageBindGroups, QuadMask, UIQuadType,
+    },
+    Corner,
+};
+use kayak_core::{
+    curve::LineSegment,
+    layout_cache::Rect as LayoutRect,
+    render_backend::{ColorGlyph, GlyphRun, RenderBackend, TextureUpload, UiTexture},
+    render_primitive::ClipMask,
+    styles::{Corner as LayoutCorner, Edge},
+    DrawCommand, DrawQuad,
+};
+use kayak_font::{KayakFont, TextProperties};
+
+use super::{curve, font, image, nine_patch, quad, texture_atlas};
+use crate::{BevyContext, FontMapping, ImageManager};
+
+/// The textures generated by each context, as they were last uploaded to the GPU
+#[derive(Default)]
+pub struct UiTextures {
+    /// The color glyphs of each context, by context
+    color_glyphs: HashMap<usize, UploadedTexture>,
+}
+
+/// A texture generated by a context, as it was last uploaded
+#[derive(Debug, Clone)]
+pub struct UploadedTexture {
+    handle: Handle<Image>,
+    version: u64,
+    size: (u32, u32),
+}
+
+impl UiTextures {
+    /// Takes the color glyphs uploaded for the given context, to draw it with a [`BevyBackend`]
+    pub(crate) fn take_color_glyphs(&mut self, context: &BevyContext) -> Option<UploadedTexture> {
+        self.color_glyphs.remove(&Self::context_key(context))
+    }
+
+    /// Puts back the color glyphs of the given context, once it's been drawn
+    pub(crate) fn set_color_glyphs(
+        &mut self,
+        context: &BevyContext,
+        texture: Option<UploadedTexture>,
+    ) {
+        if let Some(texture) = texture {
+            self.color_glyphs
+                .insert(Self::context_key(context), texture);
+        }
+    }
+
+    /// Releases the textures of contexts other than the given ones
+    pub(crate) fn retain<'a>(
+        &mut self,
+        contexts: impl Iterator<Item = &'a BevyContext>,
+        gpu_images: &mut RenderAssets<Image>,
+        image_bind_groups: &mut ImageBindGroups,
+    ) {
+        let keys = contexts.map(Self::context_key).collect::<Vec<_>>();
+        self.color_glyphs.retain(|key, texture| {
+            let keep = keys.contains(key);
+            if !keep {
+                gpu_images.remove(&texture.handle);
+                image_bind_groups.remove(&texture.handle);
+            }
+            keep
+        });
+    }
+
+    fn context_key(context: &BevyContext) -> usize {
+        Arc::as_ptr(&context.kayak_context) as usize
+    }
+}
+
+/// The resources needed to upload an image to the GPU
+pub type ImageParams<'w> = (
+    Res<'w, RenderDevice>,
+    Res<'w, RenderQueue>,
+    Res<'w, DefaultImageSampler>,
+);
+
+/// The [`RenderBackend`] of the Bevy renderer
+///
+/// This turns the draw commands of a context into the quads extracted for the UI pipeline. Each
+/// context is drawn with its own backend, for each camera it's drawn to.
+pub struct BevyBackend<'a, 'w> {
+    fonts: &'a Res<'w, Assets<KayakFont>>,
+    font_mapping: &'a Res<'w, FontMapping>,
+    image_manager: &'a Res<'w, ImageManager>,
+    images: &'a Res<'w, Assets<Image>>,
+    image_params: &'a mut ImageParams<'w>,
+    gpu_images: &'a mut RenderAssets<Image>,
+    image_bind_groups: &'a mut ImageBindGroups,
+    /// The color glyphs of the context, as they were last uploaded
+    color_glyphs: Option<UploadedTexture>,
+    dpi: f32,
+    /// The rounded mask of the current clip, applied to everything drawn within it
+    current_mask: Option<QuadMask>,
+    quads: Vec<ExtractQuadBundle>,
+}
+
+impl<'a, 'w> BevyBackend<'a, 'w> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        fonts: &'a Res<'w, Assets<KayakFont>>,
+        font_mapping: &'a Res<'w, FontMapping>,
+        image_manager: &'a Res<'w, ImageManager>,
+        images: &'a Res<'w, Assets<Image>>,
+        image_params: &'a mut ImageParams<'w>,
+        gpu_images: &'a mut RenderAssets<Image>,
+        image_bind_groups: &'a mut ImageBindGroups,
+        color_glyphs: Option<UploadedTexture>,
+        dpi: f32,
+    ) -> Self {
+        Self {
+            fonts,
+            font_mapping,
+            image_manager,
+            images,
+            image_params,
+            gpu_images,
+            image_bind_groups,
+            color_glyphs,
+            dpi,
+            current_mask: None,
+            quads: Vec::new(),
+        }
+    }
+
+    /// The quads drawn so far, along with the color glyphs of the context as they were uploaded
+    pub fn finish(self) -> (Vec<ExtractQuadBundle>, Option<UploadedTexture>) {
+        (self.quads, self.color_glyphs)
+    }
+
+    /// Adds the quads of a draw command, masking them to the current clip
+    fn push(&mut self, quads: Vec<ExtractQuadBundle>) {
+        let first_quad = self.quads.len();
+        self.quads.extend(quads);
+        if self.current_mask.is_some() {
+            // Quads that are masked themselves (such as the sides of a border) keep their own mask
+            for quad in self.quads[first_quad..].iter_mut() {
+                quad.extracted_quad.clip = self.current_mask;
+            }
+        }
+    }
+
+    /// Creates the quads drawing the given color glyphs from the uploaded atlas
+    fn color_glyph_quads(&self, run: &GlyphRun) -> Vec<ExtractQuadBundle> {
+        let uploaded = match &self.color_glyphs {
+            Some(uploaded) if !run.color_glyphs.is_empty() => uploaded,
+            _ => return Vec::new(),
+        };
+        let properties = TextProperties {
+            font_size: run.font_size,
+            subpixel_positioning: run.subpixel_positioning,
+            ..Default::default()
+        };
+        let atlas_size = Vec2::new(uploaded.size.0 as f32, uploaded.size.1 as f32);
+
+        run.color_glyphs
+            .iter()
+            .map(|ColorGlyph { glyph, region }| {
+                let position = Vec2::from(properties.snap_position(glyph.position, self.dpi));
+                let (texel_min, texel_max) = (
+                    Vec2::new(region.position.0 as f32, region.position.1 as f32),
+                    Vec2::new(
+                        (region.position.0 + region.size.0) as f32,
+                        (region.position.1 + region.size.1) as f32,
+                    ),
+                );
+                ExtractQuadBundle {
+                    extracted_quad: ExtractedQuad {
+                        font_handle: None,
+                        rect: Rect {
+                            min: position,
+                            max: position + Vec2::from(glyph.size),
+                        },
+                        color: Color::WHITE,
+                        vertex_index: 0,
+                        char_id: 0,
+                        z_index: run.rect.z_index,
+                        quad_type: UIQuadType::Image,
+                        type_index: 0,
+                        border_radius: Corner::default(),
+                        image: Some(uploaded.handle.clone_weak()),
+                        uv_min: Some(Vec2::new(
+                            texel_min.x / atlas_size.x,
+                            1.0 - texel_max.y / atlas_size.y,
+                        )),
+                        uv_max: Some(Vec2::new(
+                            texel_max.x / atlas_size.x,
+                            1.0 - texel_min.y / atlas_size.y,
+                        )),
+                        camera_entity: None,
+                        mask: None,
+                        clip: None,
+                        lines: Vec::new(),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'a, 'w> RenderBackend for BevyBackend<'a, 'w> {
+    fn upload_texture(&mut self, texture: TextureUpload) {
+        match texture.texture {
+            UiTexture::ColorGlyphs => {}
+        }
+        if self
+            .color_glyphs
+            .as_ref()
+            .map_or(false, |uploaded| uploaded.version == texture.version)
+        {
+            return;
+        }
+
+        let image = Image::new(
+            Extent3d {
+                width: texture.size.0,
+                height: texture.size.1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            texture.data.to_vec(),
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let gpu_image = match Image::prepare_asset(image, self.image_params) {
+            Ok(gpu_image) => gpu_image,
+            Err(_) => return,
+        };
+        // Each upload gets a new handle, since the pipeline keeps the bind group of each image
+        let handle = Handle::weak(HandleId::random::<Image>());
+        self.gpu_images.insert(handle.clone_weak(), gpu_image);
+        let previous = self.color_glyphs.replace(UploadedTexture {
+            handle,
+            version: texture.version,
+            size: texture.size,
+        });
+        if let Some(previous) = previous {
+            self.gpu_images.remove(&previous.handle);
+            self.image_bind_groups.remove(&previous.handle);
+        }
+    }
+
+    fn set_clip(&mut self, rect: LayoutRect, mask: Option<ClipMask>) {
+        self.current_mask = mask.map(|mask| QuadMask {
+            rect: Rect {
+                min: Vec2::new(mask.layout.posx, mask.layout.posy),
+                max: Vec2::new(
+                    mask.layout.posx + mask.layout.width,
+                    mask.layout.posy + mask.layout.height,
+                ),
+            },
+            border_radius: Corner {
+                top_left: mask.border_radius.top_left,
+                top_right: mask.border_radius.top_right,
+                bottom_left: mask.border_radius.bottom_left,
+                bottom_right: mask.border_radius.bottom_right,
+            },
+        });
+        let dpi = self.dpi;
+        self.quads.push(ExtractQuadBundle {
+            extracted_quad: ExtractedQuad {
+                rect: Rect {
+                    min: Vec2::new(rect.posx, rect.posy) * dpi,
+                    max: Vec2::new(rect.posx + rect.width, rect.posy + rect.height) * dpi,
+                },
+                color: Color::default(),
+                vertex_index: 0,
+                char_id: 0,
+                z_index: rect.z_index,
+                font_handle: None,
+                quad_type: UIQuadType::Clip,
+                type_index: 0,
+                border_radius: Corner::default(),
+                image: None,
+                uv_min: None,
+                uv_max: None,
+                camera_entity: None,
+                mask: None,
+                clip: None,
+                lines: Vec::new(),
+            },
+        });
+    }
+
+    fn draw_quads(&mut self, quads: &[DrawQuad]) {
+        for draw_quad in quads {
+            let primitive = DrawCommand::Quad(*draw_quad).into_primitive();
+            let quads = quad::extract_quads(&primitive, 1.0);
+            self.push(quads);
+        }
+    }
+
+    fn draw_glyphs(&mut self, run: GlyphRun) {
+        if !run.glyphs.is_empty() {
+            let primitive = DrawCommand::Glyphs {
+                font: run.font.to_string(),
+                font_size: run.font_size,
+                color: run.color,
+                glyphs: run.glyphs.to_vec(),
+                subpixel_positioning: run.subpixel_positioning,
+                rect: run.rect,
+            }
+            .into_primitive();
+            let quads = font::extract_texts(&primitive, self.fonts, self.font_mapping, self.dpi);
+            self.push(quads);
+        }
+        let quads = self.color_glyph_quads(&run);
+        self.push(quads);
+    }
+
+    fn draw_image(&mut self, rect: LayoutRect, handle: u16, border_radius: LayoutCorner<f32>) {
+        let primitive = DrawCommand::Image {
+            rect,
+            handle,
+            border_radius,
+        }
+        .into_primitive();
+        let quads = image::extract_images(&primitive, self.image_manager, self.dpi);
+        self.push(quads);
+    }
+
+    fn draw_texture_atlas(
+        &mut self,
+        rect: LayoutRect,
+        handle: u16,
+        position: (f32, f32),
+        size: (f32, f32),
+    ) {
+        let primitive = DrawCommand::TextureAtlas {
+            rect,
+            handle,
+            position,
+            size,
+        }
+        .into_primitive();
+        let quads = texture_atlas::extract_texture_atlas(
+            &primitive,
+            self.image_manager,
+            self.images,
+            self.dpi,
+        );
+        self.push(quads);
+    }
+
+    fn draw_nine_patch(&mut self, rect: LayoutRect, handle: u16, border: Edge<f32>) {
+        let primitive = DrawCommand::NinePatch {
+            rect,
+            handle,
+            border,
+        }
+        .into_primitive();
+        let quads =
+            nine_patch::extract_nine_patch(&primitive, self.image_manager, self.images, self.dpi);
+        self.push(quads);
+    }
+
+    fn draw_lines(&mut self, rect: LayoutRect, lines: &[LineSegment]) {
+        let quads = curve::extract_lines(lines, rect.z_index, self.dpi);
+        self.push(quads);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::to_bevy_color;
+use bevy::{math::Vec2, render::color::Color, sprite::Rect};
+use bevy_kayak_renderer::{
+    render::unified::pipeline::{ExtractQuadBundle, ExtractedLine, ExtractedQuad, UIQuadType},
+    Corner,
+};
+use kayak_core::curve::LineSegment;
+
+/// Extracts lines positioned in the UI's coordinate space, as a single batch drawn at once
+pub fn extract_lines(lines: &[LineSegment], z_index: f32, dpi: f32) -> Vec<ExtractQuadBundle> {
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines: Vec<ExtractedLine> = lines
+        .iter()
+        .map(|line| ExtractedLine {
+            start: Vec2::new(line.start.0, line.start.1) * dpi,
+            end: Vec2::new(line.end.0, line.end.1) * dpi,
+            radius: line.width / 2.0 * dpi,
+            color: to_bevy_color(&line.color),
+            previous: None,
+            next: None,
+        })
+        .collect();
+    ExtractedLine::connect(&mut lines);
+
+    let (min, max) = lines.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), line| {
+            let radius = Vec2::splat(line.radius);
+            (
+                min.min(line.start.min(line.end) - radius),
+                max.max(line.start.max(line.end) + radius),
+            )
+        },
+    );
+    vec![ExtractQuadBundle {
+        extracted_quad: ExtractedQuad {
+            rect: Rect { min, max },
+            color: Color::WHITE,
+            vertex_index: 0,
+            char_id: 0,
+            z_index,
+            font_handle: None,
+            quad_type: UIQuadType::Line,
+            type_index: 0,
+            border_radius: Corner::default(),
+            image: None,
+            uv_min: None,
+            uv_max: None,
+            camera_entity: None,
+            mask: None,
+            clip: None,
+            lines,
+        },
+    }]
+}
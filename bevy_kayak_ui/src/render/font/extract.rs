@@ -18,7 +18,7 @@ pub fn extract_texts(
     render_primitive: &RenderPrimitive,
     fonts: &Res<Assets<KayakFont>>,
     font_mapping: &Res<FontMapping>,
-    _dpi: f32,
+    dpi: f32,
 ) -> Vec<ExtractQuadBundle> {
     let mut extracted_texts = Vec::new();
     let (background_color, text_layout, layout, font, properties) = match render_primitive {
@@ -44,6 +44,7 @@ pub fn extract_texts(
     for glyph_rect in text_layout.glyphs() {
         let mut position = Vec2::from(glyph_rect.position);
         position += base_position;
+        let position = Vec2::from(properties.snap_position(position.into(), dpi));
 
         let size = Vec2::from(glyph_rect.size);
 
@@ -64,6 +65,10 @@ pub fn extract_texts(
                 image: None,
                 uv_max: None,
                 uv_min: None,
+                camera_entity: None,
+                mask: None,
+                clip: None,
+                lines: Vec::new(),
             },
         });
     }
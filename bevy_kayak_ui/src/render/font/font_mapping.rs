@@ -1,8 +1,10 @@
+use std::sync::Arc;
+
 use bevy::{
-    prelude::{Assets, Handle, Res},
+    prelude::{Assets, Handle},
     utils::HashMap,
 };
-use kayak_font::KayakFont;
+use kayak_font::{ColorFont, KayakFont};
 
 use crate::BevyContext;
 
@@ -39,6 +41,7 @@ pub struct FontMapping {
     font_ids: HashMap<Handle<KayakFont>, String>,
     font_handles: HashMap<String, Handle<KayakFont>>,
     new_fonts: Vec<String>,
+    emoji_font: Option<Arc<ColorFont>>,
 }
 
 impl Default for FontMapping {
@@ -47,6 +50,7 @@ impl Default for FontMapping {
             font_ids: HashMap::default(),
             font_handles: HashMap::default(),
             new_fonts: Vec::new(),
+            emoji_font: None,
         }
     }
 }
@@ -67,6 +71,22 @@ impl FontMapping {
         self.add(kayak_core::DEFAULT_FONT, handle);
     }
 
+    /// Set the color font that every `KayakFont` falls back to, for emoji and any characters the
+    /// `KayakFont` doesn't have
+    ///
+    /// No emoji font is bundled with Kayak, so without one, emoji are drawn as each font's missing
+    /// glyph. The font is loaded from its original font file (such as Noto Color Emoji's `.ttf`),
+    /// using [`ColorFont::from_bytes`].
+    pub fn set_emoji_font(&mut self, font: ColorFont) {
+        self.emoji_font = Some(Arc::new(font));
+        self.mark_all_as_new();
+    }
+
+    /// Get the color font that fonts fall back to, if any
+    pub fn emoji_font(&self) -> Option<&Arc<ColorFont>> {
+        self.emoji_font.as_ref()
+    }
+
     pub(crate) fn mark_all_as_new(&mut self) {
         self.new_fonts
             .extend(self.font_handles.keys().map(|key| key.clone()));
@@ -88,13 +108,23 @@ impl FontMapping {
 
     pub(crate) fn add_loaded_to_kayak(
         &mut self,
-        fonts: &Res<Assets<KayakFont>>,
+        fonts: &mut Assets<KayakFont>,
         context: &BevyContext,
     ) {
         if let Ok(mut kayak_context) = context.kayak_context.write() {
             let new_fonts = self.new_fonts.drain(..).collect::<Vec<_>>();
             for font_key in new_fonts {
                 let font_handle = self.font_handles.get(&font_key).unwrap();
+                // The renderer draws from the loaded font, so it falls back to the emoji font too
+                let needs_fallback = fonts
+                    .get(font_handle)
+                    .map_or(false, |font| font.fallback() != self.emoji_font.as_ref());
+                if needs_fallback {
+                    if let Some(font) = fonts.get_mut(font_handle) {
+                        font.set_fallback(self.emoji_font.clone());
+                    }
+                }
+
                 if let Some(font) = fonts.get(font_handle) {
                     kayak_context.set_asset(font_key, font.clone());
                 } else {
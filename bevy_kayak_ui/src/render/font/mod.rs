@@ -21,13 +21,13 @@ impl Plugin for TextRendererPlugin {
 
 fn process_loaded_fonts(
     mut font_mapping: ResMut<FontMapping>,
-    fonts: Res<Assets<KayakFont>>,
+    mut fonts: ResMut<Assets<KayakFont>>,
     bevy_context: Option<Res<BevyContext>>,
 ) {
     if let Some(context) = bevy_context {
         if context.is_added() {
             font_mapping.mark_all_as_new();
         }
-        font_mapping.add_loaded_to_kayak(&fonts, &context);
+        font_mapping.add_loaded_to_kayak(&mut fonts, &context);
     }
 }
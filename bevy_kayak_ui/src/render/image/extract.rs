@@ -45,6 +45,10 @@ pub fn extract_images(
                 .and_then(|a| Some(a.clone_weak())),
             uv_max: None,
             uv_min: None,
+            camera_entity: None,
+            mask: None,
+            clip: None,
+            lines: Vec::new(),
         },
     }]
 }
@@ -1,4 +1,12 @@
-use bevy::{prelude::Handle, render::texture::Image, utils::HashMap};
+use bevy::{
+    prelude::{Assets, Handle},
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::Image,
+    },
+    utils::{HashMap, HashSet},
+};
+use image::ImageError;
 
 /// A resource used to manage images for use in a `KayakContext`
 ///
@@ -38,6 +46,10 @@ pub struct ImageManager {
     mapping: HashMap<u16, Handle<Image>>,
     /// A map of _weak_ image handles to their ID
     reverse_mapping: HashMap<Handle<Image>, u16>,
+    /// The IDs whose image was decoded by this manager (rather than given to it)
+    decoded: HashSet<u16>,
+    /// Whether an image was replaced since the contexts were last asked to render
+    replaced: bool,
 }
 
 impl ImageManager {
@@ -46,6 +58,8 @@ impl ImageManager {
             count: 0,
             mapping: HashMap::default(),
             reverse_mapping: HashMap::default(),
+            decoded: HashSet::default(),
+            replaced: false,
         }
     }
 
@@ -69,4 +83,176 @@ impl ImageManager {
     pub fn get_handle(&self, id: &u16) -> Option<&Handle<Image>> {
         self.mapping.get(id)
     }
+
+    /// Decode an image from raw bytes and get an ID for it
+    ///
+    /// The format (PNG, JPEG, etc.) is detected from the contents of the buffer.
+    pub fn load_bytes(
+        &mut self,
+        bytes: &[u8],
+        images: &mut Assets<Image>,
+    ) -> Result<u16, ImageError> {
+        let image = decode_image(bytes)?;
+        let id = self.get(&images.add(image));
+        self.decoded.insert(id);
+        Ok(id)
+    }
+
+    /// Decode an image from raw bytes and replace the image for the given ID with it
+    ///
+    /// Widgets already displaying the given ID will display the new image from the next frame, as
+    /// the contexts using this manager are rendered again. If the previous image was decoded by
+    /// this manager (see [`load_bytes`](Self::load_bytes)), it's removed from the image assets.
+    /// Images from handles given to [`get`](Self::get) are left alone, since they may be used
+    /// elsewhere.
+    pub fn replace_bytes(
+        &mut self,
+        id: u16,
+        bytes: &[u8],
+        images: &mut Assets<Image>,
+    ) -> Result<(), ImageError> {
+        let image = decode_image(bytes)?;
+        let handle = images.add(image);
+        if let Some(previous) = self.mapping.insert(id, handle.clone()) {
+            self.reverse_mapping.remove(&previous);
+            if self.decoded.contains(&id) {
+                images.remove(&previous);
+            }
+        }
+        self.reverse_mapping.insert(handle.clone_weak(), id);
+        self.decoded.insert(id);
+        self.replaced = true;
+        Ok(())
+    }
+
+    /// Returns true if an image was replaced since this was last called
+    pub(crate) fn take_replaced(&mut self) -> bool {
+        std::mem::take(&mut self.replaced)
+    }
+}
+
+fn decode_image(bytes: &[u8]) -> Result<Image, ImageError> {
+    let image = image::load_from_memory(bytes)?.into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        image.into_raw(),
+        TextureFormat::Rgba8UnormSrgb,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::{Arc, RwLock};
+
+    use bevy::{
+        asset::{AddAsset, AssetPlugin},
+        prelude::{App, Assets, MinimalPlugins},
+        render::texture::Image,
+    };
+    use image::{ImageOutputFormat, RgbaImage};
+    use kayak_core::KayakContext;
+
+    use super::ImageManager;
+    use crate::BevyContext;
+
+    /// Encodes a transparent PNG, one pixel high
+    fn png(width: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbaImage::new(width, 1)
+            .write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>()
+            .insert_resource(ImageManager::new());
+        app
+    }
+
+    fn width(images: &Assets<Image>, image_manager: &ImageManager, id: u16) -> u32 {
+        let handle = image_manager.get_handle(&id).unwrap();
+        images.get(handle).unwrap().texture_descriptor.size.width
+    }
+
+    #[test]
+    fn should_replace_the_image_of_an_id() {
+        let mut app = app();
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let mut image_manager = ImageManager::new();
+        let id = image_manager.load_bytes(&png(2), &mut images).unwrap();
+        let previous = image_manager.get_handle(&id).unwrap().clone_weak();
+
+        image_manager
+            .replace_bytes(id, &png(3), &mut images)
+            .unwrap();
+        assert_eq!(3, width(&images, &image_manager, id));
+        let handle = image_manager.get_handle(&id).unwrap().clone();
+        assert_eq!(id, image_manager.get(&handle));
+        // The previous image was only used through the ID
+        assert!(images.get(&previous).is_none());
+    }
+
+    #[test]
+    fn should_keep_images_it_was_given() {
+        let mut app = app();
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let mut image_manager = ImageManager::new();
+        let given = images.add(Image::default());
+        let id = image_manager.get(&given);
+
+        image_manager
+            .replace_bytes(id, &png(3), &mut images)
+            .unwrap();
+        assert_eq!(3, width(&images, &image_manager, id));
+        assert!(images.get(&given).is_some());
+    }
+
+    #[test]
+    fn should_not_replace_images_that_fail_to_decode() {
+        let mut app = app();
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let mut image_manager = ImageManager::new();
+        let id = image_manager.load_bytes(&png(2), &mut images).unwrap();
+
+        assert!(image_manager
+            .replace_bytes(id, b"not an image", &mut images)
+            .is_err());
+        assert_eq!(2, width(&images, &image_manager, id));
+        assert!(!image_manager.take_replaced());
+    }
+
+    #[test]
+    fn should_render_contexts_again_once_replaced() {
+        let mut app = app();
+        app.add_system(super::super::render_replaced_images);
+        app.insert_resource(BevyContext {
+            kayak_context: Arc::new(RwLock::new(KayakContext::new())),
+        });
+        app.update();
+        assert!(!app.world.resource::<BevyContext>().is_dirty());
+
+        let world = &mut app.world;
+        world.resource_scope(
+            |world, mut image_manager: bevy::prelude::Mut<ImageManager>| {
+                let mut images = world.resource_mut::<Assets<Image>>();
+                let id = image_manager.load_bytes(&png(2), &mut images).unwrap();
+                image_manager
+                    .replace_bytes(id, &png(3), &mut images)
+                    .unwrap();
+            },
+        );
+        app.update();
+        assert!(app.world.resource::<BevyContext>().is_dirty());
+    }
 }
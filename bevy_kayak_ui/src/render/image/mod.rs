@@ -1,14 +1,31 @@
-use bevy::prelude::Plugin;
+use bevy::prelude::{Plugin, Query, Res, ResMut};
+
+use crate::BevyContext;
 
 mod extract;
 mod image_manager;
 pub use extract::extract_images;
+pub use image::ImageError;
 pub use image_manager::ImageManager;
 
 pub struct ImageRendererPlugin;
 
 impl Plugin for ImageRendererPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.insert_resource(ImageManager::new());
+        app.insert_resource(ImageManager::new())
+            .add_system(render_replaced_images);
+    }
+}
+
+/// Renders the contexts again once an image was replaced, so that widgets show the new image
+fn render_replaced_images(
+    mut image_manager: ResMut<ImageManager>,
+    bevy_context: Option<Res<BevyContext>>,
+    cameras: Query<&BevyContext>,
+) {
+    if image_manager.take_replaced() {
+        for context in bevy_context.as_deref().into_iter().chain(cameras.iter()) {
+            context.request_render();
+        }
     }
 }
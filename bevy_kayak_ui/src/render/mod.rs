@@ -1,18 +1,24 @@
 use crate::{BevyContext, FontMapping, ImageManager};
 use bevy::{
-    math::Vec2,
-    prelude::{Assets, Commands, Plugin, Res},
-    render::{color::Color, texture::Image, Extract, RenderApp, RenderStage},
-    sprite::Rect,
+    prelude::{Assets, Camera, Commands, Entity, Plugin, Query, Res, ResMut, With},
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssets,
+        renderer::{RenderDevice, RenderQueue},
+        texture::{DefaultImageSampler, Image},
+        Extract, RenderApp, RenderStage,
+    },
     window::Windows,
 };
 use bevy_kayak_renderer::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
-    Corner,
+    render::unified::pipeline::ImageBindGroups, target_image, CameraUiKayak,
 };
-use kayak_core::render_primitive::RenderPrimitive;
 use kayak_font::KayakFont;
 
+pub use backend::{BevyBackend, ImageParams, UiTextures, UploadedTexture};
+
+mod backend;
+mod curve;
 pub mod font;
 pub mod image;
 mod nine_patch;
@@ -27,10 +33,13 @@ impl Plugin for BevyKayakUIExtractPlugin {
             .add_plugin(image::ImageRendererPlugin);
 
         let render_app = app.sub_app_mut(RenderApp);
-        render_app.add_system_to_stage(RenderStage::Extract, extract);
+        render_app
+            .init_resource::<UiTextures>()
+            .add_system_to_stage(RenderStage::Extract, extract);
     }
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn extract(
     mut commands: Commands,
     context: Extract<Option<Res<BevyContext>>>,
@@ -39,77 +48,72 @@ pub fn extract(
     image_manager: Extract<Res<ImageManager>>,
     images: Extract<Res<Assets<Image>>>,
     windows: Extract<Res<Windows>>,
+    cameras: Extract<Query<(Entity, &Camera, Option<&BevyContext>), With<CameraUiKayak>>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    default_sampler: Res<DefaultImageSampler>,
+    mut ui_textures: ResMut<UiTextures>,
+    mut gpu_images: ResMut<RenderAssets<Image>>,
+    mut image_bind_groups: ResMut<ImageBindGroups>,
 ) {
-    if context.is_none() {
-        return;
-    }
+    #[cfg(feature = "tracing")]
+    let span = bevy::log::info_span!("kayak_extract", quads = bevy::utils::tracing::field::Empty)
+        .entered();
 
-    let context = context.as_ref().unwrap();
+    let mut image_params = (render_device, render_queue, default_sampler);
+    let mut extracted_quads = Vec::new();
+    for (camera_entity, camera, camera_context) in cameras.iter() {
+        // Cameras without their own context render the resource context
+        let context = match camera_context.or(context.as_deref()) {
+            Some(context) => context,
+            None => continue,
+        };
 
-    let render_primitives = if let Ok(context) = context.kayak_context.read() {
-        context.widget_manager.build_render_primitives()
-    } else {
-        vec![]
-    };
+        let mut kayak_context = match context.kayak_context.write() {
+            Ok(kayak_context) => kayak_context,
+            Err(_) => continue,
+        };
 
-    // dbg!(&render_primitives);
+        // Images have no scale factor
+        let dpi = if target_image(camera).is_some() {
+            1.0
+        } else if let RenderTarget::Window(window_id) = camera.target {
+            windows
+                .get(window_id)
+                .map_or(1.0, |window| window.scale_factor() as f32)
+        } else {
+            1.0
+        };
 
-    let dpi = if let Some(window) = windows.get_primary() {
-        window.scale_factor() as f32
-    } else {
-        1.0
-    };
+        let mut backend = BevyBackend::new(
+            &fonts,
+            &font_mapping,
+            &image_manager,
+            &images,
+            &mut image_params,
+            &mut gpu_images,
+            &mut image_bind_groups,
+            ui_textures.take_color_glyphs(context),
+            dpi,
+        );
+        kayak_context.draw_with(&mut backend);
+        let (mut context_quads, color_glyphs) = backend.finish();
+        ui_textures.set_color_glyphs(context, color_glyphs);
 
-    let mut extracted_quads = Vec::new();
-    for render_primitive in render_primitives {
-        match render_primitive {
-            RenderPrimitive::Text { .. } => {
-                let text_quads = font::extract_texts(&render_primitive, &fonts, &font_mapping, dpi);
-                extracted_quads.extend(text_quads);
-            }
-            RenderPrimitive::Image { .. } => {
-                let image_quads = image::extract_images(&render_primitive, &image_manager, dpi);
-                extracted_quads.extend(image_quads);
-            }
-            RenderPrimitive::Quad { .. } => {
-                let quad_quads = quad::extract_quads(&render_primitive, 1.0);
-                extracted_quads.extend(quad_quads);
-            }
-            RenderPrimitive::NinePatch { .. } => {
-                let nine_patch_quads =
-                    nine_patch::extract_nine_patch(&render_primitive, &image_manager, &images, dpi);
-                extracted_quads.extend(nine_patch_quads);
-            }
-            RenderPrimitive::TextureAtlas { .. } => {
-                let texture_atlas_quads =
-                    texture_atlas::extract_texture_atlas(&render_primitive, &image_manager, &images, dpi);
-                extracted_quads.extend(texture_atlas_quads);
-            }
-            RenderPrimitive::Clip { layout } => {
-                extracted_quads.push(ExtractQuadBundle {
-                    extracted_quad: ExtractedQuad {
-                        rect: Rect {
-                            min: Vec2::new(layout.posx, layout.posy) * dpi,
-                            max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height)
-                                * dpi,
-                        },
-                        color: Color::default(),
-                        vertex_index: 0,
-                        char_id: 0,
-                        z_index: layout.z_index,
-                        font_handle: None,
-                        quad_type: UIQuadType::Clip,
-                        type_index: 0,
-                        border_radius: Corner::default(),
-                        image: None,
-                        uv_min: None,
-                        uv_max: None,
-                    },
-                });
-            }
-            _ => {}
+        for quad in context_quads.iter_mut() {
+            quad.extracted_quad.camera_entity = Some(camera_entity);
         }
+        extracted_quads.extend(context_quads);
     }
 
+    let contexts = context
+        .as_deref()
+        .into_iter()
+        .chain(cameras.iter().filter_map(|(_, _, context)| context));
+    ui_textures.retain(contexts, &mut gpu_images, &mut image_bind_groups);
+
+    #[cfg(feature = "tracing")]
+    span.record("quads", &extracted_quads.len());
+
     commands.spawn_batch(extracted_quads);
 }
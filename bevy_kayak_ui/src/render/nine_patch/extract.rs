@@ -64,6 +64,10 @@ pub fn extract_nine_patch(
         image: image_handle,
         uv_max: None,
         uv_min: None,
+        camera_entity: None,
+        mask: None,
+        clip: None,
+        lines: Vec::new(),
     };
 
     // TOP
@@ -1,87 +1,126 @@
 use crate::to_bevy_color;
 use bevy::{math::Vec2, sprite::Rect};
 use bevy_kayak_renderer::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, QuadMask, UIQuadType},
     Corner,
 };
-use kayak_core::render_primitive::RenderPrimitive;
+use kayak_core::{border, color::Color, layout_cache, render_primitive::RenderPrimitive, styles};
 
 pub fn extract_quads(render_primitive: &RenderPrimitive, dpi: f32) -> Vec<ExtractQuadBundle> {
-    let (background_color, border_color, layout, border_radius, mut border) = match render_primitive
-    {
-        RenderPrimitive::Quad {
-            background_color,
-            border_color,
-            layout,
-            border_radius,
-            border,
-        } => (
-            *background_color,
-            *border_color,
-            *layout,
-            *border_radius,
-            *border,
-        ),
-        _ => panic!(""),
-    };
+    let (background_color, border_color, layout, border_radius, mut border, border_style) =
+        match render_primitive {
+            RenderPrimitive::Quad {
+                background_color,
+                border_colors: border_color,
+                layout,
+                border_radius,
+                border,
+                border_style,
+                ..
+            } => (
+                *background_color,
+                *border_color,
+                *layout,
+                *border_radius,
+                *border,
+                *border_style,
+            ),
+            _ => panic!(""),
+        };
 
     border *= dpi;
+    let layout = layout_cache::Rect {
+        width: layout.width * dpi,
+        height: layout.height * dpi,
+        ..layout
+    };
+    let outline = Rect {
+        min: Vec2::new(layout.posx, layout.posy),
+        max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height),
+    };
 
-    vec![
-        // Border
-        ExtractQuadBundle {
-            extracted_quad: ExtractedQuad {
-                rect: Rect {
-                    min: Vec2::new(layout.posx, layout.posy),
-                    max: Vec2::new(
-                        layout.posx + (layout.width * dpi),
-                        layout.posy + (layout.height * dpi),
-                    ),
-                },
-                color: to_bevy_color(&border_color),
-                vertex_index: 0,
-                char_id: 0,
-                z_index: layout.z_index,
-                font_handle: None,
-                quad_type: UIQuadType::Quad,
-                type_index: 0,
-                border_radius: Corner {
-                    top_left: border_radius.top_left,
-                    top_right: border_radius.top_right,
-                    bottom_left: border_radius.bottom_left,
-                    bottom_right: border_radius.bottom_right,
-                },
-                image: None,
-                uv_max: None,
-                uv_min: None,
-            },
+    let background = create_quad(
+        Rect {
+            min: Vec2::new(layout.posx + border.left, layout.posy + border.top),
+            max: Vec2::new(
+                (layout.posx + layout.width) - border.right,
+                (layout.posy + layout.height) - border.bottom,
+            ),
         },
-        ExtractQuadBundle {
-            extracted_quad: ExtractedQuad {
-                rect: Rect {
-                    min: Vec2::new(layout.posx + border.left, layout.posy + border.top),
+        layout.z_index,
+        background_color,
+        border_radius,
+        None,
+    );
+
+    if let Some(border_color) = border::uniform_color(&border, &border_color, &border_style) {
+        return vec![
+            create_quad(outline, layout.z_index, border_color, border_radius, None),
+            background,
+        ];
+    }
+
+    // Each side is drawn separately, masked to the rounded outline of the widget
+    let mask = QuadMask {
+        rect: outline,
+        border_radius: to_corner(border::mask_radius(&border, &border_radius)),
+    };
+    let mut quads: Vec<_> = border::side_quads(&layout, &border, &border_color, &border_style)
+        .into_iter()
+        .map(|side| {
+            create_quad(
+                Rect {
+                    min: Vec2::new(side.layout.posx, side.layout.posy),
                     max: Vec2::new(
-                        (layout.posx + (layout.width * dpi)) - border.right,
-                        (layout.posy + (layout.height * dpi)) - border.bottom,
+                        side.layout.posx + side.layout.width,
+                        side.layout.posy + side.layout.height,
                     ),
                 },
-                color: to_bevy_color(&background_color),
-                vertex_index: 0,
-                char_id: 0,
-                z_index: layout.z_index,
-                font_handle: None,
-                quad_type: UIQuadType::Quad,
-                type_index: 0,
-                border_radius: Corner {
-                    top_left: border_radius.top_left,
-                    top_right: border_radius.top_right,
-                    bottom_left: border_radius.bottom_left,
-                    bottom_right: border_radius.bottom_right,
-                },
-                image: None,
-                uv_max: None,
-                uv_min: None,
-            },
+                layout.z_index,
+                side.color,
+                styles::Corner::default(),
+                Some(mask),
+            )
+        })
+        .collect();
+    quads.push(background);
+    quads
+}
+
+fn to_corner(corner: styles::Corner<f32>) -> Corner<f32> {
+    Corner {
+        top_left: corner.top_left,
+        top_right: corner.top_right,
+        bottom_left: corner.bottom_left,
+        bottom_right: corner.bottom_right,
+    }
+}
+
+fn create_quad(
+    rect: Rect,
+    z_index: f32,
+    color: Color,
+    border_radius: styles::Corner<f32>,
+    mask: Option<QuadMask>,
+) -> ExtractQuadBundle {
+    ExtractQuadBundle {
+        extracted_quad: ExtractedQuad {
+            rect,
+            color: to_bevy_color(&color),
+            vertex_index: 0,
+            char_id: 0,
+            z_index,
+            font_handle: None,
+            quad_type: UIQuadType::Quad,
+            type_index: 0,
+            border_radius: to_corner(border_radius),
+            image: None,
+            uv_max: None,
+            uv_min: None,
+            camera_entity: None,
+            mask,
+            clip: None,
+            lines: Vec::new(),
         },
-    ]
+    }
 }
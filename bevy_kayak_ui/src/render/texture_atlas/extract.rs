@@ -72,6 +72,10 @@ pub fn extract_texture_atlas(
         type_index: 0,
         border_radius: Corner::default(),
         image: image_handle,
+        camera_entity: None,
+        mask: None,
+        clip: None,
+        lines: Vec::new(),
         },
     };
     extracted_quads.push(quad);
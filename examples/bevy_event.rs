@@ -1,5 +1,5 @@
 use bevy::{
-    prelude::{App as BevyApp, AssetServer, Commands, EventReader, EventWriter, Res, ResMut},
+    prelude::{App as BevyApp, AssetServer, Commands, EventReader, Res, ResMut},
     window::WindowDescriptor,
     DefaultPlugins,
 };
@@ -23,7 +23,8 @@ fn EventWindow() {
 
     let on_event = OnEvent::new(move |ctx, event| match event.event_type {
         EventType::Click(..) => {
-            ctx.query_world::<EventWriter<MyEvent>, _, ()>(|mut writer| writer.send(MyEvent));
+            // The event is sent once the UI has finished rendering this frame
+            ctx.world_commands().send_event(MyEvent);
         }
         _ => {}
     });
@@ -0,0 +1,68 @@
+use bevy::{
+    prelude::{App as BevyApp, AssetServer, Commands, Res, ResMut},
+    window::WindowDescriptor,
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle};
+use kayak_ui::core::{
+    render, rsx,
+    styles::{Style, StyleProp, Units},
+    use_state, widget, Color, Handler,
+};
+use kayak_ui::widgets::{App, Background, ColorPicker, Window};
+
+#[widget]
+fn ColorPickerExample() {
+    let (color, set_color, ..) = use_state!(Color::new(0.2, 0.6, 0.4, 1.0));
+    let on_change = Handler::new(move |color| {
+        set_color(color);
+    });
+
+    let swatch_styles = Style {
+        background_color: StyleProp::Value(color),
+        height: StyleProp::Value(Units::Pixels(40.0)),
+        top: StyleProp::Value(Units::Pixels(12.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <Window position={(50.0, 50.0)} size={(240.0, 320.0)} title={"Color Picker Example".to_string()}>
+            <ColorPicker color={Some(color)} on_change={Some(on_change)} />
+            <Background styles={Some(swatch_styles)} />
+        </Window>
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn_bundle(UICameraBundle::new());
+
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    let context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <ColorPickerExample />
+            </App>
+        }
+    });
+
+    commands.insert_resource(context);
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .run();
+}
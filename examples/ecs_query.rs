@@ -0,0 +1,76 @@
+use bevy::{
+    prelude::{App as BevyApp, AssetServer, Commands, Component, Query, Res, ResMut, With},
+    time::Time,
+    window::WindowDescriptor,
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle};
+use kayak_ui::core::{render, rsx, use_query, widget};
+use kayak_ui::widgets::{App, Text, Window};
+
+#[derive(Component)]
+struct Player;
+
+#[derive(Component)]
+struct Health(f32);
+
+#[widget]
+fn HealthDisplay() {
+    // Only the whole number of health points is displayed, so the widget only re-renders when
+    // that changes, rather than on every frame that the health drains
+    let health = use_query!(Query<&Health, With<Player>>, |query| {
+        query
+            .get_single()
+            .map_or(0, |health| health.0.ceil() as u32)
+    });
+
+    rsx! {
+        <>
+            <Window position={(50.0, 50.0)} size={(300.0, 300.0)} title={"ECS Query Example".to_string()}>
+                <Text size={32.0} content={format!("Health: {}", health)}>{}</Text>
+            </Window>
+        </>
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn_bundle(UICameraBundle::new());
+
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    commands.spawn().insert(Player).insert(Health(100.0));
+
+    let context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <HealthDisplay />
+            </App>
+        }
+    });
+    commands.insert_resource(context);
+}
+
+fn drain_health(time: Res<Time>, mut query: Query<&mut Health, With<Player>>) {
+    for mut health in query.iter_mut() {
+        health.0 = (health.0 - time.delta_seconds() * 2.0).max(0.0);
+    }
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .add_system(drain_health)
+        .run();
+}
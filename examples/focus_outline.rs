@@ -0,0 +1,97 @@
+use bevy::{
+    prelude::{App as BevyApp, AssetServer, Commands, Res, ResMut},
+    window::WindowDescriptor,
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle};
+use kayak_ui::core::{
+    render, rsx,
+    styles::{BorderStyle, FocusAnimation, FocusOutline, Style, StyleProp, Units},
+    widget, Color,
+};
+use kayak_ui::widgets::{App, Button, Element, Text, Window};
+
+#[widget]
+fn FocusOutlines() {
+    // The outline is inherited, so this themes every button in the window
+    let theme = Style {
+        focus_outline: StyleProp::Value(FocusOutline {
+            color: Color::new(1.0, 0.8, 0.2, 1.0),
+            style: BorderStyle::Dashed,
+            animation: FocusAnimation::March { speed: 16.0 },
+            ..Default::default()
+        }),
+        row_between: StyleProp::Value(Units::Pixels(12.0)),
+        ..Default::default()
+    };
+
+    let pulsing = Style {
+        focus_outline: StyleProp::Value(FocusOutline {
+            style: BorderStyle::Dotted,
+            animation: FocusAnimation::Pulse { period: 1.2 },
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let hidden = Style {
+        focus_outline: StyleProp::Value(FocusOutline::NONE),
+        ..Default::default()
+    };
+
+    let text_styles = Style {
+        left: StyleProp::Value(Units::Stretch(1.0)),
+        right: StyleProp::Value(Units::Stretch(1.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <Window position={(50.0, 50.0)} size={(300.0, 260.0)} title={"Press Tab to move focus".to_string()}>
+            <Element styles={Some(theme)}>
+                <Button>
+                    <Text styles={Some(text_styles.clone())} line_height={Some(40.0)} size={20.0} content={"Themed".to_string()}>{}</Text>
+                </Button>
+                <Button styles={Some(pulsing)}>
+                    <Text styles={Some(text_styles.clone())} line_height={Some(40.0)} size={20.0} content={"Custom".to_string()}>{}</Text>
+                </Button>
+                <Button styles={Some(hidden)}>
+                    <Text styles={Some(text_styles)} line_height={Some(40.0)} size={20.0} content={"No outline".to_string()}>{}</Text>
+                </Button>
+            </Element>
+        </Window>
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn_bundle(UICameraBundle::new());
+
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    let context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <FocusOutlines />
+            </App>
+        }
+    });
+
+    commands.insert_resource(context);
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .run();
+}
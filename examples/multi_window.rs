@@ -0,0 +1,118 @@
+use bevy::{
+    prelude::{App as BevyApp, AssetServer, Commands, EventWriter, Res, ResMut},
+    window::{CreateWindow, WindowDescriptor, WindowId},
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle};
+use kayak_ui::core::{
+    render, rsx,
+    styles::{Style, StyleProp, Units},
+    use_state, widget, EventType, OnEvent, WidgetProps,
+};
+use kayak_ui::widgets::{App, Button, OnChange, Text, TextBox, Window};
+
+#[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+struct CounterProps {
+    title: String,
+}
+
+#[widget]
+fn Counter(props: CounterProps) {
+    let text_styles = Style {
+        bottom: StyleProp::Value(Units::Stretch(1.0)),
+        left: StyleProp::Value(Units::Stretch(0.1)),
+        right: StyleProp::Value(Units::Stretch(0.1)),
+        top: StyleProp::Value(Units::Stretch(1.0)),
+        width: StyleProp::Value(Units::Stretch(1.0)),
+        height: StyleProp::Value(Units::Pixels(28.0)),
+        ..Default::default()
+    };
+
+    let button_text_styles = Style {
+        left: StyleProp::Value(Units::Stretch(1.0)),
+        right: StyleProp::Value(Units::Stretch(1.0)),
+        ..Default::default()
+    };
+
+    let (count, set_count, ..) = use_state!(0i32);
+    let on_event = OnEvent::new(move |_, event| match event.event_type {
+        EventType::Click(..) => set_count(count + 1),
+        _ => {}
+    });
+
+    let (value, set_value, ..) = use_state!(String::new());
+    let on_change = OnChange::new(move |event| {
+        set_value(event.value);
+    });
+
+    let title = props.title.clone();
+    rsx! {
+        <>
+            <Window draggable={true} position={(50.0, 50.0)} size={(300.0, 300.0)} title={title}>
+                <Text styles={Some(text_styles)} size={32.0} content={format!("Current Count: {}", count).to_string()}>{}</Text>
+                <Button on_event={Some(on_event)}>
+                    <Text styles={Some(button_text_styles)} line_height={Some(40.0)} size={24.0} content={"Count!".to_string()}>{}</Text>
+                </Button>
+                <TextBox value={value} on_change={Some(on_change)} placeholder={Some("Type here...".to_string())} />
+            </Window>
+        </>
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+    mut create_window_events: EventWriter<CreateWindow>,
+) {
+    // Fonts (and images) are shared by every context
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    // The primary window uses the context resource, like any other example
+    commands.spawn_bundle(UICameraBundle::new());
+    let context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <Counter title={"Primary Window".to_string()} />
+            </App>
+        }
+    });
+    commands.insert_resource(context);
+
+    // The second window gets its own context, attached to the camera that renders to it
+    let window_id = WindowId::new();
+    create_window_events.send(CreateWindow {
+        id: window_id,
+        descriptor: WindowDescriptor {
+            width: 800.0,
+            height: 600.0,
+            title: String::from("Second Window"),
+            ..Default::default()
+        },
+    });
+
+    let second_context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <Counter title={"Second Window".to_string()} />
+            </App>
+        }
+    });
+    commands
+        .spawn_bundle(UICameraBundle::with_target_window(window_id))
+        .insert(second_context);
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .run();
+}
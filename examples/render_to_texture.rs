@@ -0,0 +1,96 @@
+use bevy::{
+    prelude::{
+        shape, AlphaMode, App as BevyApp, AssetServer, Assets, Camera3dBundle, Commands, Component,
+        Image, Mesh, PbrBundle, Quat, Query, Res, ResMut, StandardMaterial, Time, Transform, Vec3,
+        With,
+    },
+    window::WindowDescriptor,
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{
+    create_ui_texture, BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle,
+};
+use kayak_ui::core::{
+    render,
+    styles::{Style, StyleProp, Units},
+};
+use kayak_ui::widgets::{App, Text, Window};
+
+/// The size of the UI texture (and therefore the size of the UI)
+const TEXTURE_SIZE: (u32, u32) = (512, 512);
+
+#[derive(Component)]
+struct UiSurface;
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    // Render the UI into a texture instead of the window
+    let texture = create_ui_texture(&mut images, TEXTURE_SIZE.0, TEXTURE_SIZE.1);
+    commands.spawn_bundle(UICameraBundle::with_target_image(texture.clone()));
+
+    // Display the texture on a surface in the 3D scene
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Quad::new(bevy::math::Vec2::splat(2.0)))),
+            material: materials.add(StandardMaterial {
+                base_color_texture: Some(texture),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .insert(UiSurface);
+
+    commands.spawn_bundle(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..Default::default()
+    });
+
+    let context = BevyContext::new(|context| {
+        let text_styles = Style {
+            top: StyleProp::Value(Units::Stretch(1.0)),
+            bottom: StyleProp::Value(Units::Stretch(1.0)),
+            ..Default::default()
+        };
+
+        render! {
+            <App>
+                <Window position={(50.0, 50.0)} size={(400.0, 200.0)} title={"UI on a Texture".to_string()}>
+                    <Text styles={Some(text_styles)} size={32.0} content={"Hello from 3D!".to_string()} />
+                </Window>
+            </App>
+        }
+    });
+
+    commands.insert_resource(context);
+}
+
+fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<UiSurface>>) {
+    for mut transform in query.iter_mut() {
+        transform.rotation = Quat::from_rotation_y(time.seconds_since_startup().sin() as f32 * 0.5);
+    }
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .add_system(rotate)
+        .run();
+}
@@ -6,9 +6,10 @@ use bevy::{
 use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, ImageManager, UICameraBundle};
 use kayak_ui::core::{
     render,
-    styles::{Edge, Style, StyleProp, Units},
+    styles::{Edge, Position, Style, StyleProp, Units},
+    Color,
 };
-use kayak_ui::widgets::{App, Inspector, NinePatch, ScrollBox, Text};
+use kayak_ui::widgets::{App, Background, Inspector, NinePatch, ScrollBox, Text};
 
 fn startup(
     mut commands: Commands,
@@ -32,6 +33,15 @@ fn startup(
             ..Style::default()
         };
 
+        // The header stays at the top of the scroll box while the text scrolls underneath it
+        let header_styles = Style {
+            background_color: StyleProp::Value(Color::new(0.45, 0.3, 0.2, 1.0)),
+            height: StyleProp::Value(Units::Pixels(28.0)),
+            padding: StyleProp::Value(Edge::all(Units::Pixels(6.0))),
+            position: StyleProp::Value(Position::Sticky { top: 0.0 }),
+            ..Style::default()
+        };
+
         let lorem_ipsum = r#"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Cras sed tellus neque. Proin tempus ligula a mi molestie aliquam. Orci varius natoque penatibus et magnis dis parturient montes, nascetur ridiculus mus. Nullam venenatis consequat ultricies. Sed ac orci purus. Nullam velit nisl, dapibus vel mauris id, dignissim elementum sapien. Vestibulum faucibus sapien ut erat bibendum, id lobortis nisi luctus. Mauris feugiat at lectus at pretium. Pellentesque vitae finibus ante. Nulla non ex neque. Cras varius, lorem facilisis consequat blandit, lorem mauris mollis massa, eget consectetur magna sem vel enim. Nam aliquam risus pulvinar, volutpat leo eget, eleifend urna. Suspendisse in magna sed ligula vehicula volutpat non vitae augue. Phasellus aliquam viverra consequat. Nam rhoncus molestie purus, sed laoreet neque imperdiet eget. Sed egestas metus eget sodales congue.
 
 Sed vel ante placerat, posuere lacus sit amet, tempus enim. Cras ullamcorper ex vitae metus consequat, a blandit leo semper. Nunc lacinia porta massa, a tempus leo laoreet nec. Sed vel metus tincidunt, scelerisque ex sit amet, lacinia dui. In sollicitudin pulvinar odio vitae hendrerit. Maecenas mollis tempor egestas. Nulla facilisi. Praesent nisi turpis, accumsan eu lobortis vestibulum, ultrices id nibh. Suspendisse sed dui porta, mollis elit sed, ornare sem. Cras molestie est libero, quis faucibus leo semper at.
@@ -51,6 +61,9 @@ Vestibulum rutrum imperdiet nisl, et consequat massa porttitor vel. Ut velit jus
                     handle={panel_brown_handle}
                 >
                     <ScrollBox>
+                        <Background styles={Some(header_styles)}>
+                            <Text content={"Lorem Ipsum".to_string()} size={16.0} />
+                        </Background>
                         <Text content={lorem_ipsum} size={14.0} />
                     </ScrollBox>
                 </NinePatch>
@@ -0,0 +1,75 @@
+use bevy::{
+    prelude::{App as BevyApp, AssetServer, Commands, Res, ResMut},
+    window::WindowDescriptor,
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle};
+use kayak_ui::core::{constructor, render, rsx, use_state, widget, Handler};
+use kayak_ui::widgets::{reorder, App, ScrollBox, SortableItem, SortableList, Text, Window};
+
+#[widget]
+fn SortableListExample() {
+    let (tasks, set_tasks, ..) = use_state!((1..=20)
+        .map(|index| format!("Task {}", index))
+        .collect::<Vec<_>>());
+
+    let items = tasks
+        .iter()
+        .map(|task| {
+            SortableItem::new(
+                task.clone(),
+                constructor! {
+                    <Text content={task.clone()} size={16.0} />
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let on_reorder = Handler::new(move |(from, to)| {
+        let mut tasks = tasks.clone();
+        reorder(&mut tasks, from, to);
+        set_tasks(tasks);
+    });
+
+    rsx! {
+        <Window position={(50.0, 50.0)} size={(300.0, 400.0)} title={"Sortable List Example".to_string()}>
+            <ScrollBox>
+                <SortableList items={items} on_reorder={Some(on_reorder)} />
+            </ScrollBox>
+        </Window>
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn_bundle(UICameraBundle::new());
+
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    let context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <SortableListExample />
+            </App>
+        }
+    });
+
+    commands.insert_resource(context);
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .run();
+}
@@ -0,0 +1,135 @@
+use bevy::{
+    math::UVec2,
+    prelude::{
+        App as BevyApp, AssetServer, Camera, Commands, Component, EventReader, Query, Res, ResMut,
+        With,
+    },
+    render::camera::Viewport,
+    window::{WindowDescriptor, WindowId, WindowResized, Windows},
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, CameraUiKayak, FontMapping, UICameraBundle};
+use kayak_ui::core::{
+    render, rsx,
+    styles::{Style, StyleProp, Units},
+    use_state, widget, EventType, OnEvent, WidgetProps,
+};
+use kayak_ui::widgets::{App, Button, Text, Window};
+
+#[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+struct PlayerProps {
+    name: String,
+}
+
+#[widget]
+fn Player(props: PlayerProps) {
+    let text_styles = Style {
+        left: StyleProp::Value(Units::Stretch(1.0)),
+        right: StyleProp::Value(Units::Stretch(1.0)),
+        ..Default::default()
+    };
+
+    let (score, set_score, ..) = use_state!(0i32);
+    let on_event = OnEvent::new(move |_, event| match event.event_type {
+        EventType::Click(..) => set_score(score + 1),
+        _ => {}
+    });
+
+    let name = props.name.clone();
+    rsx! {
+        <>
+            <Window draggable={true} position={(25.0, 25.0)} size={(300.0, 200.0)} title={name}>
+                <Text size={32.0} content={format!("Score: {}", score)}>{}</Text>
+                <Button on_event={Some(on_event)}>
+                    <Text styles={Some(text_styles)} line_height={Some(40.0)} size={24.0} content={"Score!".to_string()}>{}</Text>
+                </Button>
+            </Window>
+        </>
+    }
+}
+
+/// Marks which half of the window a UI camera is drawn to
+#[derive(Component)]
+enum Half {
+    Left,
+    Right,
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    font_mapping.set_default(asset_server.load("roboto.kayak_font"));
+
+    // Each half of the window gets its own context, attached to the camera drawing that half.
+    // The viewports are sized once the window is known (see `update_viewports`).
+    for (half, name, priority) in [
+        (Half::Left, "Player 1", isize::MAX - 2),
+        (Half::Right, "Player 2", isize::MAX - 1),
+    ] {
+        let context = BevyContext::new(move |context| {
+            render! {
+                <App>
+                    <Player name={name.to_string()} />
+                </App>
+            }
+        });
+
+        let mut camera = UICameraBundle::with_viewport(WindowId::primary(), Viewport::default());
+        camera.camera.priority = priority;
+        commands.spawn_bundle(camera).insert(context).insert(half);
+    }
+}
+
+fn update_viewports(
+    windows: Res<Windows>,
+    mut resize_events: EventReader<WindowResized>,
+    mut cameras: Query<(&mut Camera, &Half), With<CameraUiKayak>>,
+) {
+    let is_resized = resize_events
+        .iter()
+        .any(|event| event.id == WindowId::primary());
+    let is_unsized = cameras.iter().any(|(camera, _)| {
+        camera
+            .viewport
+            .as_ref()
+            .map_or(true, |viewport| viewport.physical_size == UVec2::ZERO)
+    });
+    if !is_resized && !is_unsized {
+        return;
+    }
+
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let half_width = window.physical_width() / 2;
+    let height = window.physical_height();
+    for (mut camera, half) in cameras.iter_mut() {
+        let x = match half {
+            Half::Left => 0,
+            Half::Right => half_width,
+        };
+        camera.viewport = Some(Viewport {
+            physical_position: UVec2::new(x, 0),
+            physical_size: UVec2::new(half_width, height),
+            ..Default::default()
+        });
+    }
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .add_system(update_viewports)
+        .run();
+}
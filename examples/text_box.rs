@@ -9,9 +9,11 @@ use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle
 use kayak_ui::core::{
     render, rsx,
     styles::{Style, StyleProp, Units},
-    widget,
+    widget, Handler,
+};
+use kayak_ui::widgets::{
+    App, InputPattern, Inspector, OnChange, SpinBox, SpinBoxStyle, TextArea, TextBox, Window,
 };
-use kayak_ui::widgets::{App, Inspector, OnChange, SpinBox, SpinBoxStyle, TextBox, Window};
 
 #[widget]
 fn TextBoxExample() {
@@ -19,6 +21,8 @@ fn TextBoxExample() {
     let (empty_value, set_empty_value, _) = use_state!("".to_string());
     let (red_value, set_red_value, _) = use_state!("This text is red".to_string());
     let (spin_value, set_spin_value, _) = use_state!("3".to_string());
+    let (phone_value, set_phone_value, _) = use_state!("".to_string());
+    let (notes_value, set_notes_value, _) = use_state!("".to_string());
 
     let input_styles = Style {
         top: StyleProp::Value(Units::Pixels(10.0)),
@@ -46,11 +50,21 @@ fn TextBoxExample() {
         set_spin_value(event.value);
     });
 
+    let on_change_phone = OnChange::new(move |event| {
+        set_phone_value(event.value);
+    });
+
+    let on_change_notes = Handler::new(move |value: String| {
+        set_notes_value(value);
+    });
+
+    let phone_pattern = Some(InputPattern::Mask("(###) ###-####".to_string()));
+
     let vert = SpinBoxStyle::Vertical;
 
     rsx! {
-        <Window position={(50.0, 50.0)} size={(500.0, 300.0)} title={"TextBox Example".to_string()}>
-            <TextBox styles={Some(input_styles)} value={value} on_change={Some(on_change)} />
+        <Window position={(50.0, 50.0)} size={(500.0, 460.0)} title={"TextBox Example".to_string()}>
+            <TextBox styles={Some(input_styles)} value={value} on_change={Some(on_change)} autofocus={true} />
             <TextBox
                 styles={Some(input_styles)}
                 value={empty_value}
@@ -58,6 +72,21 @@ fn TextBoxExample() {
                 placeholder={Some("This is a placeholder".to_string())}
             />
             <TextBox styles={Some(red_text_styles)} value={red_value} on_change={Some(on_change_red)} />
+            <TextBox
+                styles={Some(input_styles)}
+                value={phone_value}
+                on_change={Some(on_change_phone)}
+                pattern={phone_pattern}
+                flash_invalid={true}
+                placeholder={Some("(555) 555-5555".to_string())}
+            />
+            <TextArea
+                styles={Some(input_styles)}
+                value={notes_value}
+                on_change={Some(on_change_notes)}
+                max_lines={Some(8)}
+                placeholder={Some("Write some notes...".to_string())}
+            />
             <SpinBox
                 styles={Some(input_styles)}
                 value={spin_value}
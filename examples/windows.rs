@@ -4,19 +4,41 @@ use bevy::{
     DefaultPlugins,
 };
 use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle};
-use kayak_ui::core::{render, rsx, widget};
+use kayak_ui::core::{portal, render, rsx, use_state, widget, Handler};
 use kayak_ui::widgets::{App, Inspector, Window};
 
 #[widget]
 fn CustomWidget() {
+    let (is_open, set_is_open, ..) = use_state!(true);
+    let on_close = Handler::new(move |_| {
+        set_is_open(false);
+    });
+
     rsx! {
         <>
-            <Window draggable={true} position={(50.0, 50.0)} size={(300.0, 300.0)} title={"Window 1".to_string()}>
-                {}
-            </Window>
-            <Window draggable={true} position={(550.0, 50.0)} size={(200.0, 200.0)} title={"Window 2".to_string()}>
+            <Window
+                draggable={true}
+                layer={Some(portal::WINDOW.to_string())}
+                position={(50.0, 50.0)}
+                size={(300.0, 300.0)}
+                title={"Window 1".to_string()}
+            >
                 {}
             </Window>
+            {if is_open {
+                <Window
+                    draggable={true}
+                    resizable={true}
+                    collapsible={true}
+                    layer={Some(portal::WINDOW.to_string())}
+                    on_close={Some(on_close)}
+                    position={(550.0, 50.0)}
+                    size={(200.0, 200.0)}
+                    title={"Window 2".to_string()}
+                >
+                    {}
+                </Window>
+            }}
         </>
     }
 }
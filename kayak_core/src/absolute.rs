@@ -0,0 +1,303 @@
+//! Positioning of [absolute](crate::styles::Position::Absolute) widgets within their containing widget
+//!
+//! An absolute widget is positioned by its offsets from its containing widget: its nearest
+//! ancestor that isn't itself absolute. The layout positions (and stretches) absolute widgets
+//! relative to their parent, which is already right whenever the parent is the containing widget.
+//!
+//! Absolute widgets nested in other absolute widgets are fixed up in two steps. Those stretching
+//! between their offsets are first resized to the space between those offsets within the
+//! containing widget, before layout is calculated a second time. Afterwards, they're moved to their
+//! offsets from the containing widget, along with their descendants. Along an axis where neither
+//! offset is set, the widget is left where the layout placed it.
+
+use morphorm::{Node as LayoutNode, Units};
+
+use crate::flex::{self, resolve_units};
+use crate::layout_cache::{LayoutCache, Rect};
+use crate::node::Node;
+use crate::tree::Tree;
+use crate::{Arena, Index};
+
+/// Returns true if the given node is positioned absolutely
+fn is_absolute(id: Index, nodes: &Arena<Option<Node>>) -> bool {
+    matches!(nodes.get(id), Some(Some(node)) if node.is_absolute())
+}
+
+/// Finds the containing widget of the given node if it's absolute and that isn't its parent
+///
+/// The containing widget is the nearest ancestor that isn't positioned absolutely.
+fn containing_widget(id: Index, nodes: &Arena<Option<Node>>, node_tree: &Tree) -> Option<Index> {
+    if !is_absolute(id, nodes) {
+        return None;
+    }
+    let parent = node_tree.get_parent(id)?;
+    if !is_absolute(parent, nodes) {
+        return None;
+    }
+
+    let mut current = node_tree.get_parent(parent);
+    while let Some(ancestor) = current {
+        if !is_absolute(ancestor, nodes) {
+            return Some(ancestor);
+        }
+        current = node_tree.get_parent(ancestor);
+    }
+    None
+}
+
+/// The offsets of a node along a single axis, resolved against its containing widget
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AxisOffsets {
+    start: Option<f32>,
+    end: Option<f32>,
+}
+
+impl AxisOffsets {
+    fn new(start: Option<Units>, end: Option<Units>, container_size: f32) -> Self {
+        let resolve =
+            |units: Option<Units>| units.and_then(|units| resolve_units(units, container_size));
+        Self {
+            start: resolve(start),
+            end: resolve(end),
+        }
+    }
+
+    /// Calculates the size of a node stretching between these offsets
+    ///
+    /// Returns `None` unless both offsets are set.
+    fn stretched_size(&self, container_size: f32) -> Option<f32> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => Some((container_size - start - end).max(0.0)),
+            _ => None,
+        }
+    }
+
+    /// Calculates the position of a node of the given size
+    ///
+    /// The start offset takes precedence over the end one. Returns `None` if neither is set.
+    fn position(&self, size: f32, container_pos: f32, container_size: f32) -> Option<f32> {
+        match (self.start, self.end) {
+            (Some(start), _) => Some(container_pos + start),
+            (None, Some(end)) => Some(container_pos + container_size - end - size),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Returns the horizontal and vertical offsets of the given node within the given rect
+fn offsets(id: Index, nodes: &Arena<Option<Node>>, container: &Rect) -> (AxisOffsets, AxisOffsets) {
+    (
+        AxisOffsets::new(id.left(nodes), id.right(nodes), container.width),
+        AxisOffsets::new(id.top(nodes), id.bottom(nodes), container.height),
+    )
+}
+
+/// Calculate the sizes of nested absolute nodes that stretch between their offsets
+///
+/// Returns the width and height of each node to resize, either of which is `None` if it
+/// doesn't stretch along that axis.
+pub(crate) fn calculate_absolute_sizes(
+    nodes: &Arena<Option<Node>>,
+    node_tree: &Tree,
+    layout_cache: &LayoutCache,
+) -> Vec<(Index, Option<f32>, Option<f32>)> {
+    let ids = match node_tree.root_node {
+        Some(root) => node_tree.flatten_node(root),
+        None => return Vec::new(),
+    };
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let container = containing_widget(id, nodes, node_tree)?;
+            let container = layout_cache.rect.get(&container)?;
+            let (horizontal, vertical) = offsets(id, nodes, container);
+            let is_stretched = |units: Option<Units>| matches!(units, Some(Units::Stretch(..)));
+
+            let width = horizontal
+                .stretched_size(container.width)
+                .filter(|_| is_stretched(id.width(nodes)));
+            let height = vertical
+                .stretched_size(container.height)
+                .filter(|_| is_stretched(id.height(nodes)));
+            if width.is_none() && height.is_none() {
+                return None;
+            }
+            Some((id, width, height))
+        })
+        .collect()
+}
+
+/// Moves all nested absolute nodes to their offsets from their containing widget
+///
+/// Parents are visited before their children, so each node is moved from its final position.
+pub(crate) fn apply_absolute_offsets(
+    nodes: &Arena<Option<Node>>,
+    node_tree: &Tree,
+    layout_cache: &mut LayoutCache,
+) {
+    let ids = match node_tree.root_node {
+        Some(root) => node_tree.flatten_node(root),
+        None => return,
+    };
+
+    for id in ids {
+        let container = match containing_widget(id, nodes, node_tree) {
+            Some(container) => container,
+            None => continue,
+        };
+        let (rect, container) = match (
+            layout_cache.rect.get(&id),
+            layout_cache.rect.get(&container),
+        ) {
+            (Some(rect), Some(container)) => (*rect, *container),
+            _ => continue,
+        };
+
+        let (horizontal, vertical) = offsets(id, nodes, &container);
+        let posx = horizontal
+            .position(rect.width, container.posx, container.width)
+            .unwrap_or(rect.posx);
+        let posy = vertical
+            .position(rect.height, container.posy, container.height)
+            .unwrap_or(rect.posy);
+        flex::translate(
+            id,
+            posx - rect.posx,
+            posy - rect.posy,
+            node_tree,
+            layout_cache,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_absolute_offsets, calculate_absolute_sizes, containing_widget, AxisOffsets};
+    use crate::layout_cache::{LayoutCache, Rect};
+    use crate::node::{Node, NodeBuilder};
+    use crate::styles::{Position, Style, StyleProp, Units};
+    use crate::tree::Tree;
+    use crate::{Arena, Index};
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    fn absolute(style: Style) -> Style {
+        Style {
+            position: StyleProp::Value(Position::Absolute),
+            ..style
+        }
+    }
+
+    /// Builds a relative root containing an absolute panel, itself containing an absolute badge
+    fn nested(badge_style: Style) -> (Arena<Option<Node>>, Tree, LayoutCache, [Index; 3]) {
+        let mut nodes = Arena::new();
+        let root = nodes.insert(None);
+        let panel = nodes.insert(None);
+        let badge = nodes.insert(None);
+        nodes[root] = Some(NodeBuilder::new(root, Style::new_default()).build());
+        nodes[panel] = Some(
+            NodeBuilder::new(
+                panel,
+                absolute(Style {
+                    left: StyleProp::Value(Units::Pixels(100.0)),
+                    top: StyleProp::Value(Units::Pixels(50.0)),
+                    width: StyleProp::Value(Units::Pixels(200.0)),
+                    height: StyleProp::Value(Units::Pixels(100.0)),
+                    ..Style::new_default()
+                }),
+            )
+            .build(),
+        );
+        nodes[badge] = Some(NodeBuilder::new(badge, absolute(badge_style)).build());
+
+        let mut node_tree = Tree::default();
+        node_tree.add(root, None);
+        node_tree.add(panel, Some(root));
+        node_tree.add(badge, Some(panel));
+
+        let mut layout_cache = LayoutCache::default();
+        layout_cache.rect.insert(root, rect(0.0, 0.0, 800.0, 600.0));
+        layout_cache
+            .rect
+            .insert(panel, rect(100.0, 50.0, 200.0, 100.0));
+
+        (nodes, node_tree, layout_cache, [root, panel, badge])
+    }
+
+    #[test]
+    fn should_find_the_nearest_non_absolute_ancestor() {
+        let (nodes, node_tree, _, [root, panel, badge]) = nested(Style::new_default());
+        assert_eq!(Some(root), containing_widget(badge, &nodes, &node_tree));
+        // The layout already positions absolute children of relative widgets
+        assert_eq!(None, containing_widget(panel, &nodes, &node_tree));
+        assert_eq!(None, containing_widget(root, &nodes, &node_tree));
+    }
+
+    #[test]
+    fn should_position_nested_absolute_widgets_within_their_containing_widget() {
+        let (nodes, node_tree, mut layout_cache, [_, _, badge]) = nested(Style {
+            right: StyleProp::Value(Units::Pixels(10.0)),
+            bottom: StyleProp::Value(Units::Percentage(10.0)),
+            width: StyleProp::Value(Units::Pixels(20.0)),
+            height: StyleProp::Value(Units::Pixels(20.0)),
+            ..Style::new_default()
+        });
+        // Where the layout placed it, relative to the panel
+        layout_cache
+            .rect
+            .insert(badge, rect(270.0, 70.0, 20.0, 20.0));
+
+        apply_absolute_offsets(&nodes, &node_tree, &mut layout_cache);
+        assert_eq!(
+            Some(&rect(770.0, 520.0, 20.0, 20.0)),
+            layout_cache.rect.get(&badge)
+        );
+    }
+
+    #[test]
+    fn should_stretch_nested_absolute_widgets_within_their_containing_widget() {
+        let (nodes, node_tree, layout_cache, [_, _, badge]) = nested(Style {
+            left: StyleProp::Value(Units::Pixels(10.0)),
+            right: StyleProp::Value(Units::Pixels(30.0)),
+            top: StyleProp::Value(Units::Pixels(5.0)),
+            ..Style::new_default()
+        });
+
+        // The height fits the content, since only one of its offsets is set
+        assert_eq!(
+            vec![(badge, Some(760.0), None)],
+            calculate_absolute_sizes(&nodes, &node_tree, &layout_cache)
+        );
+    }
+
+    #[test]
+    fn should_prefer_the_start_offset() {
+        let offsets = AxisOffsets {
+            start: Some(10.0),
+            end: Some(20.0),
+        };
+        assert_eq!(Some(60.0), offsets.position(30.0, 50.0, 100.0));
+        assert_eq!(Some(70.0), offsets.stretched_size(100.0));
+
+        let offsets = AxisOffsets {
+            start: None,
+            end: Some(20.0),
+        };
+        assert_eq!(Some(100.0), offsets.position(30.0, 50.0, 100.0));
+        assert_eq!(None, offsets.stretched_size(100.0));
+
+        let offsets = AxisOffsets {
+            start: None,
+            end: None,
+        };
+        assert_eq!(None, offsets.position(30.0, 50.0, 100.0));
+    }
+}
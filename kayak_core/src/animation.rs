@@ -0,0 +1,317 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Binding, Bound, Easing, MutableBound};
+
+/// How an animation created by the `use_animation!` macro plays
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationOptions {
+    /// How long a single loop of the animation takes
+    pub duration: Duration,
+    /// The easing curve of each loop
+    pub easing: Easing,
+    /// The number of loops to play before stopping, or `None` to loop forever
+    pub loops: Option<u32>,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(200),
+            easing: Easing::default(),
+            loops: Some(1),
+        }
+    }
+}
+
+/// A change to an animation, applied on the next render
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Play,
+    Pause,
+    Reverse,
+    Seek(f32),
+}
+
+/// The playback state of an animation
+#[derive(Debug, Default)]
+struct Playback {
+    /// How far the animation is, in loops (so `1.5` is halfway through the second loop)
+    position: f32,
+    /// The time the position was last updated
+    updated: Option<Instant>,
+    playing: bool,
+    reversed: bool,
+    options: Option<AnimationOptions>,
+    /// The changes to apply on the next render, in order
+    commands: Vec<Command>,
+}
+
+impl Playback {
+    /// The position at the end of the last loop, which is infinite if the animation loops forever
+    fn last(&self) -> f32 {
+        match self.options.as_ref().and_then(|options| options.loops) {
+            Some(loops) => loops as f32,
+            None => f32::INFINITY,
+        }
+    }
+
+    /// The position at which the animation stops when playing in its current direction
+    fn end(&self) -> f32 {
+        if self.reversed {
+            0.0
+        } else {
+            self.last()
+        }
+    }
+
+    /// Moves the animation forward to the given time, applying any pending commands
+    fn update(&mut self, now: Instant, options: &AnimationOptions) {
+        if let (true, Some(updated)) = (self.playing, self.updated) {
+            let elapsed = now.saturating_duration_since(updated).as_secs_f32();
+            let loops = if options.duration.is_zero() {
+                f32::INFINITY
+            } else {
+                elapsed / options.duration.as_secs_f32()
+            };
+            self.position = if self.reversed {
+                (self.position - loops).max(self.end())
+            } else {
+                (self.position + loops).min(self.end())
+            };
+        }
+        self.updated = Some(now);
+        self.options = Some(options.clone());
+
+        for command in std::mem::take(&mut self.commands) {
+            match command {
+                Command::Play => {
+                    // Playing a finished animation replays it
+                    if self.position == self.end() {
+                        self.position = if self.reversed { self.last() } else { 0.0 };
+                    }
+                    self.playing = true;
+                }
+                Command::Pause => self.playing = false,
+                Command::Reverse => {
+                    self.reversed = !self.reversed;
+                    self.playing = true;
+                }
+                Command::Seek(progress) => {
+                    // A finished animation seeks within its last loop
+                    let start = self.position.min(self.last() - 1.0).max(0.0).floor();
+                    self.position = (start + progress.clamp(0.0, 1.0)).min(self.last());
+                }
+            }
+        }
+
+        if self.position == self.end() {
+            self.playing = false;
+        }
+    }
+
+    /// How far the animation is through its current loop, from `0.0` to `1.0`
+    fn progress(&self) -> f32 {
+        let last = self.last();
+        if last > 0.0 && self.position >= last {
+            1.0
+        } else {
+            self.position.fract()
+        }
+    }
+}
+
+/// The playback state of an animation, stored as widget state
+///
+/// Updating it doesn't re-render the widget, since it's updated while rendering.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct AnimationState(Arc<Mutex<Playback>>);
+
+impl PartialEq for AnimationState {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The controller returned by the `use_animation!` macro
+///
+/// The animation goes from `0.0` to `1.0` over each loop, and starts out paused at `0.0`. Its
+/// [`value`](Self::value) is read while rendering, and the widget is re-rendered every frame while
+/// the animation plays. The controls can be used from anywhere, such as event handlers, and take
+/// effect on the widget's next render.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationController {
+    state: AnimationState,
+    /// Changed to re-render the widget when a control is used
+    revision: Binding<u64>,
+}
+
+impl AnimationController {
+    pub(crate) fn new(state: AnimationState, revision: Binding<u64>) -> Self {
+        Self { state, revision }
+    }
+
+    /// Moves the animation forward to the given time, returning whether it's still playing
+    pub(crate) fn update(&self, now: Instant, options: &AnimationOptions) -> bool {
+        match self.state.0.lock() {
+            Ok(mut playback) => {
+                playback.update(now, options);
+                playback.playing
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Plays the animation in its current direction
+    ///
+    /// If it had already finished, it's played again from the start.
+    pub fn play(&self) {
+        self.send(Command::Play);
+    }
+
+    /// Pauses the animation where it is
+    pub fn pause(&self) {
+        self.send(Command::Pause);
+    }
+
+    /// Plays the animation back the other way, starting from where it is
+    pub fn reverse(&self) {
+        self.send(Command::Reverse);
+    }
+
+    /// Jumps to the given progress through the current loop, from `0.0` to `1.0`
+    ///
+    /// This doesn't change whether the animation is playing.
+    pub fn seek(&self, progress: f32) {
+        self.send(Command::Seek(progress));
+    }
+
+    /// Returns the eased value of the animation, from `0.0` to `1.0`
+    ///
+    /// Some easing curves overshoot this range along the way.
+    pub fn value(&self) -> f32 {
+        self.with_playback(|playback| {
+            let easing = playback
+                .options
+                .as_ref()
+                .map(|options| options.easing.clone())
+                .unwrap_or_default();
+            easing.ease(playback.progress())
+        })
+    }
+
+    /// Returns how far the animation is through its current loop, from `0.0` to `1.0`
+    pub fn progress(&self) -> f32 {
+        self.with_playback(Playback::progress)
+    }
+
+    /// Returns true if the animation is playing
+    pub fn is_playing(&self) -> bool {
+        self.with_playback(|playback| playback.playing)
+    }
+
+    /// Returns true if the animation is playing backwards
+    pub fn is_reversed(&self) -> bool {
+        self.with_playback(|playback| playback.reversed)
+    }
+
+    fn send(&self, command: Command) {
+        if let Ok(mut playback) = self.state.0.lock() {
+            playback.commands.push(command);
+        }
+        self.revision.set(self.revision.get().wrapping_add(1));
+    }
+
+    fn with_playback<T: Default>(&self, f: impl FnOnce(&Playback) -> T) -> T {
+        self.state
+            .0
+            .lock()
+            .map(|playback| f(&playback))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{AnimationOptions, Command, Playback};
+    use crate::Easing;
+
+    const DURATION: Duration = Duration::from_millis(100);
+
+    fn options(loops: Option<u32>) -> AnimationOptions {
+        AnimationOptions {
+            duration: DURATION,
+            easing: Easing::Linear,
+            loops,
+        }
+    }
+
+    #[test]
+    fn should_play_and_stop_at_the_end() {
+        let start = Instant::now();
+        let options = options(Some(2));
+        let mut playback = Playback::default();
+        playback.update(start, &options);
+        assert!(!playback.playing);
+
+        playback.commands.push(Command::Play);
+        playback.update(start, &options);
+        assert!(playback.playing);
+
+        playback.update(start + DURATION * 3 / 2, &options);
+        assert_eq!(0.5, playback.progress());
+
+        playback.update(start + DURATION * 5, &options);
+        assert!(!playback.playing);
+        assert_eq!(1.0, playback.progress());
+
+        // Replays from the start
+        playback.commands.push(Command::Play);
+        playback.update(start + DURATION * 5, &options);
+        assert!(playback.playing);
+        assert_eq!(0.0, playback.progress());
+    }
+
+    #[test]
+    fn should_pause_reverse_and_seek() {
+        let start = Instant::now();
+        let options = options(Some(1));
+        let mut playback = Playback::default();
+        playback.commands.push(Command::Play);
+        playback.update(start, &options);
+
+        playback.commands.push(Command::Pause);
+        playback.update(start + DURATION / 2, &options);
+        playback.update(start + DURATION, &options);
+        assert_eq!(0.5, playback.progress());
+
+        playback.commands.push(Command::Reverse);
+        playback.update(start + DURATION, &options);
+        playback.update(start + DURATION * 5 / 4, &options);
+        assert_eq!(0.25, playback.progress());
+
+        playback.update(start + DURATION * 2, &options);
+        assert!(!playback.playing);
+        assert_eq!(0.0, playback.progress());
+
+        playback.commands.push(Command::Seek(0.75));
+        playback.update(start + DURATION * 2, &options);
+        assert!(!playback.playing);
+        assert_eq!(0.75, playback.progress());
+    }
+
+    #[test]
+    fn should_loop_forever() {
+        let start = Instant::now();
+        let options = options(None);
+        let mut playback = Playback::default();
+        playback.commands.push(Command::Play);
+        playback.update(start, &options);
+
+        playback.update(start + DURATION * 41 / 4, &options);
+        assert!(playback.playing);
+        assert_eq!(0.25, playback.progress());
+    }
+}
@@ -1,9 +1,73 @@
+use std::ops::Deref;
+use std::sync::Arc;
 use std::time::Instant;
 
+use crate::flo_binding::WithBound;
 pub use crate::flo_binding::{
     bind, computed, notify, Binding, Bound, Changeable, ComputedBinding, MutableBound, Releasable,
 };
 
+/// The setter returned by the `use_state!` macro
+///
+/// This can be called directly like a function (`set_state(value)`) to replace the state,
+/// or used to mutate the state in-place via [`update`](Self::update).
+pub struct StateSetter<T: 'static + Clone + PartialEq + Send + Sync> {
+    binding: Binding<T>,
+    setter: Arc<dyn Fn(T) + Send + Sync>,
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> StateSetter<T> {
+    pub fn new(binding: Binding<T>) -> Self {
+        let cloned_binding = binding.clone();
+        Self {
+            binding,
+            setter: Arc::new(move |value| cloned_binding.set(value)),
+        }
+    }
+
+    /// Replace the current state with the given value
+    pub fn set(&self, value: T) {
+        self.binding.set(value);
+    }
+
+    /// Mutate the current state in-place
+    ///
+    /// The closure is given the _latest_ value at the time it's applied (not the value
+    /// captured at render), so multiple updates within the same frame won't overwrite
+    /// each other. The state is locked for the duration of the closure.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (state, set_state, ..) = use_state!(MyState::default());
+    /// set_state.update(|state| state.count += 1);
+    /// ```
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        self.binding.with_mut(|value| {
+            let previous = value.clone();
+            f(value);
+            *value != previous
+        });
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> Clone for StateSetter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            binding: self.binding.clone(),
+            setter: self.setter.clone(),
+        }
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> Deref for StateSetter<T> {
+    type Target = dyn Fn(T) + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        self.setter.as_ref()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Debouncer {
     last_updated: Instant,
@@ -29,3 +93,39 @@ impl Debouncer {
         return false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{bind, Bound, StateSetter};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Counter {
+        count: u32,
+        name: String,
+    }
+
+    #[test]
+    fn update_should_apply_to_latest_value() {
+        let state = bind(Counter {
+            count: 0,
+            name: String::from("Counter"),
+        });
+        let set_state = StateSetter::new(state.clone());
+
+        set_state.update(|state| state.count += 1);
+        set_state.update(|state| state.count += 1);
+
+        assert_eq!(2, state.get().count);
+        assert_eq!("Counter", state.get().name);
+    }
+
+    #[test]
+    fn setter_should_be_callable() {
+        let state = bind(0);
+        let set_state = StateSetter::new(state.clone());
+
+        set_state(5);
+
+        assert_eq!(5, state.get());
+    }
+}
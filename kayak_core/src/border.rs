@@ -0,0 +1,320 @@
+//! Geometry of the borders drawn around quads
+//!
+//! A border whose visible sides are all solid and share the same color is drawn as a single
+//! rounded quad behind the widget's background. Any other border is drawn as a separate quad per
+//! side (or a series of quads for dashed and dotted sides), which renderers mask to the rounded
+//! outline of the widget given by [`mask_radius`].
+
+use crate::color::Color;
+use crate::layout_cache::Rect;
+use crate::styles::{BorderStyle, Corner, Edge};
+
+/// A single quad making up part of a border
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorderQuad {
+    pub layout: Rect,
+    pub color: Color,
+}
+
+/// Returns the color of the border if it can be drawn as a single quad
+///
+/// Sides with no width are ignored, since they aren't drawn.
+pub fn uniform_color(
+    border: &Edge<f32>,
+    colors: &Edge<Color>,
+    styles: &Edge<BorderStyle>,
+) -> Option<Color> {
+    let sides = [
+        (border.top, colors.top, styles.top),
+        (border.right, colors.right, styles.right),
+        (border.bottom, colors.bottom, styles.bottom),
+        (border.left, colors.left, styles.left),
+    ];
+    let mut visible = sides.iter().filter(|(width, ..)| *width > 0.0);
+    let first_color = match visible.clone().next() {
+        Some((_, color, _)) => *color,
+        None => return Some(colors.top),
+    };
+    visible
+        .all(|(_, color, style)| *style == BorderStyle::Solid && *color == first_color)
+        .then_some(first_color)
+}
+
+/// Returns the radius of the outline that the sides of a border are masked to
+///
+/// A corner is only rounded if both of its adjacent sides are drawn, otherwise the side that is
+/// drawn runs square into the corner.
+pub fn mask_radius(border: &Edge<f32>, border_radius: &Corner<f32>) -> Corner<f32> {
+    let corner = |first: f32, second: f32, radius: f32| {
+        if first > 0.0 && second > 0.0 {
+            radius
+        } else {
+            0.0
+        }
+    };
+    Corner {
+        top_left: corner(border.top, border.left, border_radius.top_left),
+        top_right: corner(border.top, border.right, border_radius.top_right),
+        bottom_left: corner(border.bottom, border.left, border_radius.bottom_left),
+        bottom_right: corner(border.bottom, border.right, border_radius.bottom_right),
+    }
+}
+
+/// Calculate the quads for each side of a border
+///
+/// Horizontal sides span the full width of the layout and are drawn after the vertical sides, so
+/// they cover the corners where two sides meet.
+pub fn side_quads(
+    layout: &Rect,
+    border: &Edge<f32>,
+    colors: &Edge<Color>,
+    styles: &Edge<BorderStyle>,
+) -> Vec<BorderQuad> {
+    build_side_quads(layout, border, colors, styles, None)
+}
+
+/// Calculate the quads for each side of a border, with its dashes (or dots) shifted along it
+///
+/// As `phase` (in pixels) increases, the pattern moves clockwise around the layout, which is used
+/// to animate focus outlines. Unlike [`side_quads`], the gaps aren't stretched to fit each side,
+/// so that the pattern doesn't change as it moves.
+pub fn marching_side_quads(
+    layout: &Rect,
+    border: &Edge<f32>,
+    colors: &Edge<Color>,
+    styles: &Edge<BorderStyle>,
+    phase: f32,
+) -> Vec<BorderQuad> {
+    build_side_quads(layout, border, colors, styles, Some(phase))
+}
+
+fn build_side_quads(
+    layout: &Rect,
+    border: &Edge<f32>,
+    colors: &Edge<Color>,
+    styles: &Edge<BorderStyle>,
+    phase: Option<f32>,
+) -> Vec<BorderQuad> {
+    let mut quads = Vec::new();
+
+    let right = layout.posx + layout.width - border.right;
+    let bottom = layout.posy + layout.height - border.bottom;
+    // The last value is whether the side runs against the clockwise direction
+    let sides = [
+        (
+            layout.posx,
+            border.left,
+            colors.left,
+            styles.left,
+            false,
+            true,
+        ),
+        (
+            right,
+            border.right,
+            colors.right,
+            styles.right,
+            false,
+            false,
+        ),
+        (layout.posy, border.top, colors.top, styles.top, true, false),
+        (
+            bottom,
+            border.bottom,
+            colors.bottom,
+            styles.bottom,
+            true,
+            true,
+        ),
+    ];
+
+    for (offset, width, color, style, horizontal, reversed) in sides {
+        if width <= 0.0 {
+            continue;
+        }
+
+        let length = if horizontal {
+            layout.width
+        } else {
+            layout.height
+        };
+        let dash = match style {
+            BorderStyle::Solid => None,
+            BorderStyle::Dashed => Some(width * 2.0),
+            BorderStyle::Dotted => Some(width),
+        };
+        let segments = match (dash, phase) {
+            (None, _) => vec![(0.0, length)],
+            (Some(dash), None) => dashes(length, dash),
+            (Some(dash), Some(phase)) => shifted_dashes(length, dash, phase)
+                .into_iter()
+                .map(|(start, size)| {
+                    if reversed {
+                        (length - start - size, size)
+                    } else {
+                        (start, size)
+                    }
+                })
+                .collect(),
+        };
+
+        for (start, size) in segments {
+            let layout = if horizontal {
+                Rect {
+                    posx: layout.posx + start,
+                    posy: offset,
+                    width: size,
+                    height: width,
+                    z_index: layout.z_index,
+                }
+            } else {
+                Rect {
+                    posx: offset,
+                    posy: layout.posy + start,
+                    width,
+                    height: size,
+                    z_index: layout.z_index,
+                }
+            };
+            quads.push(BorderQuad { layout, color });
+        }
+    }
+
+    quads
+}
+
+/// Split a side of the given length into dashes, returned as `(start, length)` pairs
+///
+/// Dashes are placed at both ends of the side, and the gaps between them are stretched so that
+/// they're roughly as long as the dashes themselves.
+fn dashes(length: f32, dash: f32) -> Vec<(f32, f32)> {
+    let count = ((length + dash) / (dash * 2.0)).floor() as usize;
+    if count <= 1 {
+        return vec![(0.0, length)];
+    }
+
+    let gap = (length - dash * count as f32) / (count - 1) as f32;
+    (0..count)
+        .map(|index| (index as f32 * (dash + gap), dash))
+        .collect()
+}
+
+/// Split a side of the given length into evenly spaced dashes, offset by `phase`
+///
+/// Dashes that cross either end of the side are cut short.
+fn shifted_dashes(length: f32, dash: f32, phase: f32) -> Vec<(f32, f32)> {
+    let period = dash * 2.0;
+    let mut segments = Vec::new();
+    let mut start = phase.rem_euclid(period) - period;
+    while start < length {
+        let from = start.max(0.0);
+        let to = (start + dash).min(length);
+        if to > from {
+            segments.push((from, to - from));
+        }
+        start += period;
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dashes, mask_radius, shifted_dashes, side_quads, uniform_color};
+    use crate::color::Color;
+    use crate::layout_cache::Rect;
+    use crate::styles::{BorderStyle, Corner, Edge};
+
+    #[test]
+    fn uniform_color_should_ignore_hidden_sides() {
+        let border = Edge::new(0.0, 0.0, 2.0, 0.0);
+        let colors = Edge::new(Color::WHITE, Color::WHITE, Color::BLACK, Color::WHITE);
+        let styles = Edge::new(
+            BorderStyle::Dashed,
+            BorderStyle::Solid,
+            BorderStyle::Solid,
+            BorderStyle::Solid,
+        );
+        assert_eq!(Some(Color::BLACK), uniform_color(&border, &colors, &styles));
+
+        let border = Edge::all(2.0);
+        assert_eq!(
+            None,
+            uniform_color(&border, &colors, &Edge::all(BorderStyle::Solid))
+        );
+        assert_eq!(
+            None,
+            uniform_color(
+                &border,
+                &Edge::all(Color::WHITE),
+                &Edge::all(BorderStyle::Dashed)
+            )
+        );
+    }
+
+    #[test]
+    fn should_only_round_corners_between_drawn_sides() {
+        let border = Edge::new(1.0, 1.0, 0.0, 1.0);
+        assert_eq!(
+            Corner::new(4.0, 4.0, 0.0, 0.0),
+            mask_radius(&border, &Corner::all(4.0))
+        );
+    }
+
+    #[test]
+    fn should_draw_each_side() {
+        let layout = Rect {
+            posx: 10.0,
+            posy: 20.0,
+            width: 100.0,
+            height: 50.0,
+            z_index: 1.0,
+        };
+        let quads = side_quads(
+            &layout,
+            &Edge::new(0.0, 0.0, 4.0, 2.0),
+            &Edge::all(Color::WHITE),
+            &Edge::all(BorderStyle::Solid),
+        );
+        let rects: Vec<_> = quads
+            .iter()
+            .map(|quad| {
+                (
+                    quad.layout.posx,
+                    quad.layout.posy,
+                    quad.layout.width,
+                    quad.layout.height,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![(10.0, 20.0, 2.0, 50.0), (10.0, 66.0, 100.0, 4.0)],
+            rects
+        );
+    }
+
+    #[test]
+    fn dashes_should_fill_both_ends() {
+        let segments = dashes(100.0, 10.0);
+        assert_eq!(5, segments.len());
+        assert_eq!((0.0, 10.0), segments[0]);
+        assert_eq!((90.0, 10.0), segments[4]);
+
+        assert_eq!(vec![(0.0, 15.0)], dashes(15.0, 10.0));
+    }
+
+    #[test]
+    fn shifted_dashes_should_cut_off_at_both_ends() {
+        assert_eq!(
+            vec![(0.0, 10.0), (20.0, 10.0), (40.0, 5.0)],
+            shifted_dashes(45.0, 10.0, 0.0)
+        );
+        assert_eq!(
+            vec![(0.0, 5.0), (15.0, 10.0), (35.0, 10.0)],
+            shifted_dashes(45.0, 10.0, 15.0)
+        );
+        assert_eq!(
+            shifted_dashes(45.0, 10.0, 5.0),
+            shifted_dashes(45.0, 10.0, 25.0)
+        );
+    }
+}
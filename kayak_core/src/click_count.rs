@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The maximum time between two presses for them to count towards the same multi-click
+pub const MULTI_CLICK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The maximum distance (in pixels) the cursor can move between two presses of the same multi-click
+const MULTI_CLICK_DISTANCE: f32 = 4.0;
+
+#[derive(Debug, Default)]
+struct ClickState {
+    /// The time and position of the last press
+    last: Option<(Instant, (f32, f32))>,
+    count: u32,
+}
+
+/// Counts consecutive presses, for detecting double and triple clicks
+///
+/// This is meant to be stored as widget state (via
+/// [`create_state`](crate::KayakContextRef::create_state)). Registering a press doesn't re-render
+/// the widget.
+#[derive(Debug, Clone, Default)]
+pub struct ClickCount(Arc<Mutex<ClickState>>);
+
+impl ClickCount {
+    /// Registers a press at the given position, returning the number of consecutive presses
+    ///
+    /// A press counts towards the previous one if it happened within [`MULTI_CLICK_TIMEOUT`] of it
+    /// and close to where it happened. Otherwise, the count restarts at `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The position of the cursor
+    /// * `now`: The time of the press
+    ///
+    pub fn press(&self, position: (f32, f32), now: Instant) -> u32 {
+        let mut state = match self.0.lock() {
+            Ok(state) => state,
+            Err(_) => return 1,
+        };
+
+        let is_repeat = state.last.map_or(false, |(time, last_position)| {
+            let distance = (position.0 - last_position.0).hypot(position.1 - last_position.1);
+            now.saturating_duration_since(time) <= MULTI_CLICK_TIMEOUT
+                && distance <= MULTI_CLICK_DISTANCE
+        });
+
+        state.count = if is_repeat {
+            state.count.saturating_add(1)
+        } else {
+            1
+        };
+        state.last = Some((now, position));
+        state.count
+    }
+}
+
+impl PartialEq for ClickCount {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClickCount, MULTI_CLICK_TIMEOUT};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn should_count_consecutive_presses() {
+        let clicks = ClickCount::default();
+        let now = Instant::now();
+        assert_eq!(1, clicks.press((10.0, 10.0), now));
+        assert_eq!(
+            2,
+            clicks.press((11.0, 10.0), now + Duration::from_millis(100))
+        );
+        assert_eq!(
+            3,
+            clicks.press((11.0, 11.0), now + Duration::from_millis(200))
+        );
+
+        // Too slow
+        let later = now + Duration::from_millis(200) + MULTI_CLICK_TIMEOUT * 2;
+        assert_eq!(1, clicks.press((11.0, 11.0), later));
+
+        // Too far
+        assert_eq!(1, clicks.press((50.0, 11.0), later));
+    }
+}
@@ -0,0 +1,29 @@
+/// A clipboard that widgets can copy text to (and paste text from)
+///
+/// Kayak doesn't talk to the system clipboard itself. By default, a [`LocalClipboard`] is used,
+/// which only shares text between widgets of the same context. Integrations (or apps) can provide
+/// access to the system clipboard by implementing this trait and passing it to
+/// [`KayakContext::set_clipboard`](crate::KayakContext::set_clipboard).
+pub trait Clipboard: Send + Sync {
+    /// Returns the text currently on the clipboard, if any
+    fn get_text(&mut self) -> Option<String>;
+
+    /// Replaces the contents of the clipboard with the given text
+    fn set_text(&mut self, text: String);
+}
+
+/// A clipboard that only lives within the current context
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LocalClipboard {
+    text: Option<String>,
+}
+
+impl Clipboard for LocalClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
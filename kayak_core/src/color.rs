@@ -1,5 +1,13 @@
+#[cfg(feature = "reflect")]
+use bevy::reflect::Reflect;
+
 /// A color in the sRGB color space.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect(PartialEq)
+)]
 pub struct Color {
     /// Red component, 0.0 - 1.0
     pub r: f32,
@@ -45,4 +53,101 @@ impl Color {
     pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
         Self { r, g, b, a }
     }
+
+    /// Create a color from its hue, saturation, value and alpha
+    ///
+    /// # Arguments
+    ///
+    /// * `hue`: The hue, in degrees (wrapped into 0.0 - 360.0)
+    /// * `saturation`: The saturation, 0.0 - 1.0
+    /// * `value`: The value (or brightness), 0.0 - 1.0
+    /// * `alpha`: The transparency, 0.0 - 1.0
+    ///
+    pub fn from_hsva(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+        Self::new(r + m, g + m, b + m, alpha)
+    }
+
+    /// The hue (in degrees), saturation, value and alpha of this color
+    ///
+    /// The hue of a gray color (with no saturation) is 0.0.
+    pub fn to_hsva(&self) -> (f32, f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+
+        let hue = if chroma <= f32::EPSILON {
+            0.0
+        } else if max == self.r {
+            60.0 * ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / chroma + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / chroma + 4.0)
+        };
+        let saturation = if max <= f32::EPSILON {
+            0.0
+        } else {
+            chroma / max
+        };
+
+        (hue, saturation, max, self.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    fn assert_close(expected: (f32, f32, f32, f32), actual: (f32, f32, f32, f32)) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(
+            close(expected.0, actual.0)
+                && close(expected.1, actual.1)
+                && close(expected.2, actual.2)
+                && close(expected.3, actual.3),
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn should_convert_hsva() {
+        let red = Color::from_hsva(0.0, 1.0, 1.0, 1.0);
+        assert_eq!(Color::new(1.0, 0.0, 0.0, 1.0), red);
+        let cyan = Color::from_hsva(180.0, 1.0, 1.0, 0.5);
+        assert_eq!(Color::new(0.0, 1.0, 1.0, 0.5), cyan);
+        // Hues wrap around
+        assert_eq!(red, Color::from_hsva(360.0, 1.0, 1.0, 1.0));
+
+        assert_close((0.0, 1.0, 1.0, 1.0), red.to_hsva());
+        assert_close((180.0, 1.0, 1.0, 0.5), cyan.to_hsva());
+        assert_close(
+            (0.0, 0.0, 0.5, 1.0),
+            Color::new(0.5, 0.5, 0.5, 1.0).to_hsva(),
+        );
+    }
+
+    #[test]
+    fn hsva_should_round_trip() {
+        let color = Color::new(0.2, 0.6, 0.4, 0.8);
+        let (h, s, v, a) = color.to_hsva();
+        let round_trip = Color::from_hsva(h, s, v, a);
+        assert_close(
+            (color.r, color.g, color.b, color.a),
+            (round_trip.r, round_trip.g, round_trip.b, round_trip.a),
+        );
+    }
 }
@@ -1,15 +1,46 @@
 use crate::assets::Assets;
+use crate::clipboard::{Clipboard, LocalClipboard};
+use crate::dynamic_widget::{add_registered_widget, WidgetConstructor};
+use crate::instrument::phase_span;
 use crate::layout_dispatcher::LayoutEventDispatcher;
-use crate::{Binding, Changeable, CursorIcon, KayakContextRef};
-use std::collections::HashMap;
+use crate::paused::Effect;
+use crate::selection::{Selection, SharedSelection};
+use crate::shortcut::Shortcuts;
+#[cfg(feature = "serde")]
+use crate::snapshot::{widget_path, SnapshotState, SnapshotStateType};
+use crate::throttle::Throttle;
+#[cfg(feature = "bevy_renderer")]
+use crate::world_query::{ProjectedQuery, WorldQuery};
+#[cfg(feature = "bevy_renderer")]
+use crate::WorldCommands;
+use crate::{
+    Binding, Changeable, CursorIcon, DrawCommand, DrawList, DynamicProps, Event, EventType,
+    FrameMetrics, Gestures, KayakContextRef, KeyboardEvent, Layout, OnShortcut, Shortcut,
+    ShortcutScope, Theme, ThemeBinding, ThrottledSetter, Widget,
+};
+use kayak_font::KayakFont;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::event_dispatcher::EventDispatcher;
 use crate::{
-    multi_state::MultiState, widget_manager::WidgetManager, Index, InputEvent, MutableBound,
+    multi_state::MultiState, widget_manager::WidgetManager, Bound, Index, InputEvent, MutableBound,
     Releasable,
 };
 
+/// A function queued to run after a render, via [`KayakContextRef::after_render`]
+pub(crate) type AfterRender = Box<dyn FnOnce(&mut KayakContextRef) + Send + Sync>;
+
+/// A function queued to run once a widget is unmounted, via [`KayakContextRef::on_unmount`]
+pub(crate) type OnUnmount = Box<dyn FnOnce() + Send + Sync>;
+
+/// A projection created via [`KayakContext::select`], along with the handle that keeps it updated
+type SelectionEntry = (Box<dyn Any + Send + Sync>, Box<dyn Releasable>);
+
 /// The context in which all widgets are contained
 ///
 /// This manages everything from rendering widgets to processing events.
@@ -19,14 +50,58 @@ use crate::{
 /// the other hand, will likely need to work with this struct directly so they can
 /// control when to render, dispatch events, load assets, etc.
 pub struct KayakContext {
+    /// The functions to run once the current render has been laid out, queued via
+    /// [`KayakContextRef::after_render`] along with the widget that queued them
+    after_render: Vec<(crate::Index, AfterRender)>,
     assets: Assets,
+    /// The clipboard that widgets copy text to, set via [`set_clipboard`](Self::set_clipboard)
+    clipboard: Box<dyn Clipboard>,
     pub(crate) current_effect_index: usize,
     pub(crate) current_state_index: usize,
+    /// The widgets acting as error boundaries, mapped to the message of a panic caught within them
+    /// that they haven't received yet (see [`error_boundary`](crate::error_boundary))
+    pub(crate) error_boundaries: HashMap<crate::Index, Option<String>>,
     /// Processes and dispatches all events
     event_dispatcher: EventDispatcher,
+    /// The time at which the current (or most recent) render started
+    frame_time: Instant,
     global_bindings: HashMap<crate::Index, Vec<crate::flo_binding::Uuid>>,
     global_state: resources::Resources,
+    /// Contains the throttles created via [`create_throttled_setter`](Self::create_throttled_setter)
+    /// along with the widget that created them, mapped by their state's ID
+    throttles: HashMap<crate::flo_binding::Uuid, (crate::Index, Box<dyn Throttle>)>,
     pub(crate) last_state_type_id: Option<std::any::TypeId>,
+    /// The metrics of the most recent render, if they're being collected
+    metrics: Option<FrameMetrics>,
+    /// The draw list of the most recent render, if it's being built
+    ///
+    /// This is also retained while metrics are collected (without being exposed by
+    /// [`draw_list`](Self::draw_list)), so that the primitives counted for them aren't built again
+    /// to be drawn.
+    draw_list: Option<DrawList>,
+    /// Set via [`set_draw_list_enabled`](Self::set_draw_list_enabled)
+    draw_list_enabled: bool,
+    /// The widget to focus at the end of the next render, set via [`focus`](Self::focus)
+    pending_focus: Option<crate::Index>,
+    /// Set while [`render`](Self::render) is building widgets, whose focus requests are applied at
+    /// the end of the same render
+    is_building: bool,
+    /// Set when another render has been requested via [`request_render`](Self::request_render)
+    render_requested: bool,
+    /// Widgets that became dirty while paused in an inactive fragment, which are re-rendered once
+    /// the fragment is active again
+    pub(crate) paused_renders: HashSet<crate::Index>,
+    /// Widgets that should be re-rendered at a later time, mapped to the time they're due
+    scheduled_renders: HashMap<crate::Index, Instant>,
+    /// Contains the shortcuts registered via [`register_shortcut`](Self::register_shortcut)
+    shortcuts: Shortcuts,
+    /// The theme of widgets outside of any `ThemeProvider`, set via [`set_theme`](Self::set_theme)
+    theme: ThemeBinding,
+    /// The state types registered via [`register_snapshot_state`](Self::register_snapshot_state), mapped by their type name
+    #[cfg(feature = "serde")]
+    snapshot_types: HashMap<&'static str, Box<dyn SnapshotState>>,
+    /// The number of widgets rendered during the current (or most recent) render
+    pub(crate) widgets_built: usize,
     // TODO: Make widget_manager private.
     /// The widget manager containing information about the widget tree and layout
     ///
@@ -37,13 +112,29 @@ pub struct KayakContext {
     /// accessible through controlled layers of abstraction.
     pub widget_manager: WidgetManager,
     widget_effects: HashMap<crate::Index, resources::Resources>,
+    /// Contains the last known layout of widgets that requested it via [`use_layout`](Self::use_layout)
+    widget_layouts: HashMap<crate::Index, Binding<Option<Layout>>>,
     /// Contains provider state data to be accessed by consumers.
     ///
     /// Maps the type of the data to a mapping of the provider node's ID to the state data
     widget_providers: HashMap<std::any::TypeId, HashMap<crate::Index, resources::Resources>>,
+    /// Contains the widgets registered via [`register_widget`](Self::register_widget), mapped by their tag
+    widget_registry: HashMap<String, WidgetConstructor>,
+    /// Contains the projections created via [`select`](Self::select), mapped by their binding's ID and projection type
+    widget_selections:
+        HashMap<crate::Index, HashMap<(crate::flo_binding::Uuid, TypeId), SelectionEntry>>,
     widget_state_lifetimes:
         HashMap<crate::Index, HashMap<crate::flo_binding::Uuid, Box<dyn crate::Releasable>>>,
     widget_states: HashMap<crate::Index, resources::Resources>,
+    /// The functions to run once each widget is unmounted, queued via
+    /// [`KayakContextRef::on_unmount`] by the key they were registered with
+    unmount_handlers: HashMap<crate::Index, HashMap<String, OnUnmount>>,
+    /// The commands queued via [`world_commands`](Self::world_commands)
+    #[cfg(feature = "bevy_renderer")]
+    world_commands: WorldCommands,
+    /// Contains the queries created via [`use_query`](Self::use_query), mapped by their widget and projection type
+    #[cfg(feature = "bevy_renderer")]
+    world_queries: HashMap<crate::Index, HashMap<TypeId, Box<dyn WorldQuery>>>,
     cursor_icon: CursorIcon,
 }
 
@@ -57,22 +148,78 @@ impl KayakContext {
     /// Creates a new [`KayakContext`].
     pub fn new() -> Self {
         Self {
+            after_render: Vec::new(),
             assets: Assets::default(),
+            clipboard: Box::<LocalClipboard>::default(),
             current_effect_index: 0,
             current_state_index: 0,
             cursor_icon: CursorIcon::Default,
+            draw_list: None,
+            draw_list_enabled: false,
+            error_boundaries: HashMap::new(),
             event_dispatcher: EventDispatcher::new(),
+            frame_time: Instant::now(),
             global_bindings: HashMap::new(),
             global_state: resources::Resources::default(),
             last_state_type_id: None,
+            metrics: None,
+            pending_focus: None,
+            is_building: false,
+            render_requested: false,
+            paused_renders: HashSet::new(),
+            scheduled_renders: HashMap::new(),
+            shortcuts: Shortcuts::default(),
+            theme: ThemeBinding::new(Theme::default()),
+            #[cfg(feature = "serde")]
+            snapshot_types: HashMap::new(),
+            throttles: HashMap::new(),
+            unmount_handlers: HashMap::new(),
             widget_effects: HashMap::new(),
+            widget_layouts: HashMap::new(),
             widget_manager: WidgetManager::new(),
             widget_providers: HashMap::new(),
+            widget_registry: HashMap::new(),
+            widget_selections: HashMap::new(),
             widget_state_lifetimes: HashMap::new(),
             widget_states: HashMap::new(),
+            widgets_built: 0,
+            #[cfg(feature = "bevy_renderer")]
+            world_commands: WorldCommands::default(),
+            #[cfg(feature = "bevy_renderer")]
+            world_queries: HashMap::new(),
         }
     }
 
+    /// Gets the theme of widgets outside of any `ThemeProvider`
+    pub fn theme(&self) -> Theme {
+        self.theme.0.get()
+    }
+
+    /// Sets the theme of widgets outside of any `ThemeProvider`
+    ///
+    /// Only the widgets that read the theme with [`use_theme`](Self::use_theme) are re-rendered.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme.0.set(theme);
+    }
+
+    /// Gets the current theme of the given widget, re-rendering it whenever the theme changes
+    ///
+    /// This is the theme of the nearest `ThemeProvider` ancestor, or the [app-wide
+    /// theme](Self::theme) if there is none.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    ///
+    pub fn use_theme(&mut self, widget_id: Index) -> Theme {
+        let theme = self
+            .create_consumer::<ThemeBinding>(widget_id)
+            .map(|provided| provided.get())
+            .unwrap_or_else(|| self.theme.clone());
+        self.bind(widget_id, &theme.0);
+        theme.0.get()
+    }
+
     /// Bind the given widget to a `Binding<T>` value
     ///
     /// "Binding" means that whenever the bound value is changed, the given widget will be re-rendered.
@@ -323,6 +470,268 @@ impl KayakContext {
         return self.get_state(widget_id);
     }
 
+    /// Registers a widget to be created for the given custom element tag
+    ///
+    /// This allows widgets to be resolved at runtime, such as ones provided by a plugin or chosen
+    /// by data. Within `rsx!`, a namespaced tag (`<plugin:Foo>`) creates a
+    /// [`DynamicWidget`](crate::DynamicWidget) that renders whichever widget is registered as
+    /// `"plugin:Foo"`, passing it the tag's attributes as [`DynamicProps`]. Registering the same
+    /// tag again replaces the previous widget.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The full tag, including its namespace (i.e. `"plugin:Foo"`)
+    ///
+    pub fn register_widget<W>(&mut self, tag: &str)
+    where
+        W: Widget + 'static,
+        W::Props: From<DynamicProps>,
+    {
+        self.widget_registry.insert(
+            tag.to_string(),
+            std::sync::Arc::new(add_registered_widget::<W>),
+        );
+    }
+
+    /// Returns true if a widget has been registered for the given tag
+    pub fn is_widget_registered(&self, tag: &str) -> bool {
+        self.widget_registry.contains_key(tag)
+    }
+
+    pub(crate) fn get_widget_constructor(&self, tag: &str) -> Option<WidgetConstructor> {
+        self.widget_registry.get(tag).cloned()
+    }
+
+    /// Registers a state type to be captured by [`snapshot_state`](Self::snapshot_state)
+    ///
+    /// States of unregistered types are never captured or restored.
+    #[cfg(feature = "serde")]
+    pub fn register_snapshot_state<T>(&mut self)
+    where
+        T: resources::Resource + Clone + PartialEq + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.snapshot_types.insert(
+            std::any::type_name::<T>(),
+            Box::new(SnapshotStateType::<T>::new()),
+        );
+    }
+
+    /// Captures the current value of all registered states (see
+    /// [`register_snapshot_state`](Self::register_snapshot_state)) of all mounted widgets
+    ///
+    /// The returned snapshot can be serialized and later passed to
+    /// [`restore_state`](Self::restore_state). See [`StateSnapshot`](crate::StateSnapshot) for
+    /// its limitations.
+    #[cfg(feature = "serde")]
+    pub fn snapshot_state(&self) -> crate::StateSnapshot {
+        let mut snapshot = crate::StateSnapshot::default();
+        let tree = &self.widget_manager.tree;
+        for (widget_id, states) in &self.widget_states {
+            if !tree.contains(*widget_id) {
+                continue;
+            }
+
+            let path = widget_path(tree, *widget_id);
+            for (type_name, state_type) in &self.snapshot_types {
+                if let Some(values) = state_type.save(states) {
+                    snapshot.insert(path.clone(), type_name, values);
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Restores the states captured by [`snapshot_state`](Self::snapshot_state)
+    ///
+    /// Widgets whose state changes are re-rendered on the next render, just as if their state had
+    /// been set normally. States that aren't in the snapshot (or that no longer exist) are left as-is.
+    ///
+    /// Returns the number of states restored, or an error if a captured value could not be
+    /// deserialized into its state type. Nothing is restored if any value fails to deserialize.
+    #[cfg(feature = "serde")]
+    pub fn restore_state(
+        &mut self,
+        snapshot: &crate::StateSnapshot,
+    ) -> Result<usize, serde_json::Error> {
+        // All values are deserialized before any state is set, so that a failure doesn't leave
+        // the tree partially restored
+        let mut pending = Vec::new();
+        let tree = &self.widget_manager.tree;
+        for (widget_id, states) in &self.widget_states {
+            if !tree.contains(*widget_id) {
+                continue;
+            }
+
+            let path = widget_path(tree, *widget_id);
+            for (type_name, state_type) in &self.snapshot_types {
+                if let Some(values) = snapshot.get(&path, type_name) {
+                    pending.push(state_type.restore(states, values)?);
+                }
+            }
+        }
+        Ok(pending.into_iter().map(|restore| restore()).sum())
+    }
+
+    /// Creates a throttled setter for the given state
+    ///
+    /// The returned setter commits its value (and therefore re-renders any bound widgets) at most
+    /// once per `interval`. Values set in between are coalesced, and the latest one is committed
+    /// on the first render after the interval has elapsed.
+    ///
+    /// Calling this again with the same state returns the existing setter, updated to use the
+    /// given `interval`. The setter is dropped once the widget is removed from the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    /// * `state`: The state to throttle
+    /// * `interval`: The minimum amount of time between commits
+    ///
+    pub fn create_throttled_setter<T: resources::Resource + Clone + PartialEq>(
+        &mut self,
+        widget_id: Index,
+        state: &Binding<T>,
+        interval: Duration,
+    ) -> ThrottledSetter<T> {
+        if let Some(setter) = self
+            .throttles
+            .get(&state.id)
+            .and_then(|(_, throttle)| throttle.as_any().downcast_ref::<ThrottledSetter<T>>())
+        {
+            setter.set_interval(interval);
+            return setter.clone();
+        }
+
+        let setter = ThrottledSetter::new(state.clone(), interval);
+        self.throttles
+            .insert(state.id, (widget_id, Box::new(setter.clone())));
+        setter
+    }
+
+    /// Subscribes the given widget to a projection of a binding, returning the projected value
+    ///
+    /// Unlike [`bind`](Self::bind), the widget is only re-rendered when the projected value
+    /// changes, rather than whenever anything in the binding does. This avoids unnecessary
+    /// re-renders of widgets that only read part of a larger state.
+    ///
+    /// Projections are identified by their binding and the type of the projection function, which
+    /// is unique to each closure. The same closure should therefore not be used to select different
+    /// values from the same binding (such as within a loop).
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    /// * `binding`: The binding to subscribe to
+    /// * `projection`: The function that selects a value from the binding
+    ///
+    pub fn select<T, U, F>(&mut self, widget_id: Index, binding: &Binding<T>, projection: F) -> U
+    where
+        T: Clone + PartialEq + Send + Sync + 'static,
+        U: Clone + PartialEq + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        let value = projection(&binding.get());
+        let key = (binding.id, TypeId::of::<F>());
+        let selections = self.widget_selections.entry(widget_id).or_default();
+
+        if let Some((selection, _)) = selections.get(&key) {
+            if let Some(selection) = selection.downcast_ref::<SharedSelection<T, U>>() {
+                if let Ok(mut selection) = selection.lock() {
+                    selection.replace(projection, value.clone());
+                }
+                return value;
+            }
+        }
+
+        let selection: SharedSelection<T, U> =
+            Arc::new(Mutex::new(Selection::new(projection, value.clone())));
+        let lifetime = {
+            let selection = selection.clone();
+            let source = binding.clone();
+            let dirty_nodes = self.widget_manager.dirty_nodes.clone();
+            binding.when_changed(crate::notify(move || {
+                let changed = selection
+                    .lock()
+                    .map_or(false, |mut selection| selection.update(&source));
+                if changed {
+                    if let Ok(mut dirty_nodes) = dirty_nodes.lock() {
+                        dirty_nodes.insert(widget_id);
+                    }
+                }
+            }))
+        };
+        selections.insert(key, (Box::new(selection), lifetime));
+
+        value
+    }
+
+    /// Get the layout of the given widget as of the previous render
+    ///
+    /// Calling this subscribes the widget to its own layout, causing it to be re-rendered
+    /// whenever that layout changes.
+    ///
+    /// # Important Note
+    ///
+    /// Layout is calculated _after_ rendering, so the returned value always lags one frame
+    /// behind. On the widget's first render this returns `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    ///
+    pub fn use_layout(&mut self, widget_id: Index) -> Option<Layout> {
+        if !self.widget_layouts.contains_key(&widget_id) {
+            let layout = self
+                .widget_manager
+                .get_layout(&widget_id)
+                .map(|rect| Layout::from(*rect));
+            let binding = crate::bind(layout);
+            self.bind(widget_id, &binding);
+            self.widget_layouts.insert(widget_id, binding);
+        }
+
+        self.widget_layouts.get(&widget_id).unwrap().get()
+    }
+
+    /// Forgets the data kept for widgets that are no longer mounted
+    ///
+    /// See [`WidgetManager::prune_removed_widgets`] for the data kept by the widget manager.
+    fn prune_removed_widgets(&mut self, mounted: &HashSet<Index>) {
+        self.shortcuts.prune(mounted);
+        self.widget_layouts.retain(|id, _| mounted.contains(id));
+        self.error_boundaries.retain(|id, _| mounted.contains(id));
+        self.throttles.retain(|_, (id, _)| mounted.contains(id));
+        self.paused_renders.retain(|id| mounted.contains(id));
+        self.widget_selections.retain(|id, selections| {
+            let is_mounted = mounted.contains(id);
+            if !is_mounted {
+                for (_, lifetime) in selections.values_mut() {
+                    lifetime.done();
+                }
+            }
+            is_mounted
+        });
+
+        let unmounted = self
+            .unmount_handlers
+            .keys()
+            .filter(|id| !mounted.contains(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for id in unmounted {
+            for (_, f) in self.unmount_handlers.remove(&id).unwrap_or_default() {
+                f();
+            }
+        }
+    }
+
+    /// Updates the layout for the given widget if it was requested via [`use_layout`](Self::use_layout)
+    pub(crate) fn update_widget_layout(&self, widget_id: Index, layout: Layout) {
+        if let Some(binding) = self.widget_layouts.get(&widget_id) {
+            binding.set(Some(layout));
+        }
+    }
+
     /// Creates a callback that runs as a side-effect of one of its dependencies being changed.
     ///
     /// All dependencies must be implement the [Changeable](crate::Changeable) trait, which means it will generally
@@ -365,7 +774,10 @@ impl KayakContext {
         dependencies: &[&'a dyn Changeable],
     ) {
         // === Bind to Dependencies === //
-        let notification = crate::notify(effect);
+        // The effect is deferred while the widget is paused within an inactive fragment
+        let effect: Effect = Arc::new(effect);
+        let paused = self.widget_manager.paused.clone();
+        let notification = crate::notify(move || paused.run_effect(widget_id, &effect));
         let mut lifetimes = Vec::default();
         for dependency in dependencies {
             let lifetime = dependency.when_changed(notification.clone());
@@ -543,24 +955,359 @@ impl KayakContext {
 
     /// Re-render all widgets that need rendering (i.e., marked dirty)
     pub fn render(&mut self) {
-        let dirty_nodes: Vec<_> =
+        let _frame_span = phase_span!("kayak_frame");
+
+        // Widgets that request another frame while rendering will set this again
+        self.render_requested = false;
+
+        // Commit any throttled values that are now due, scheduling a render for those that aren't
+        let now = Instant::now();
+        self.frame_time = now;
+        let mut throttled = Vec::new();
+        for (widget_id, throttle) in self.throttles.values() {
+            throttle.flush(now);
+            if let Some(due) = throttle.due() {
+                throttled.push((*widget_id, due));
+            }
+        }
+        for (widget_id, due) in throttled {
+            self.request_render_at(widget_id, due);
+        }
+
+        // Re-render any widgets whose scheduled render is now due (as long as they're still mounted)
+        let tree = &self.widget_manager.tree;
+        if let Ok(mut dirty_nodes) = self.widget_manager.dirty_nodes.lock() {
+            self.scheduled_renders.retain(|widget_id, due| {
+                if !tree.contains(*widget_id) {
+                    false
+                } else if *due <= now {
+                    dirty_nodes.insert(*widget_id);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        let mut dirty_nodes: Vec<_> =
             if let Ok(mut dirty_nodes) = self.widget_manager.dirty_nodes.lock() {
                 dirty_nodes.drain(..).collect()
             } else {
                 panic!("Couldn't get lock on dirty nodes!")
             };
-        for node_index in dirty_nodes {
-            let mut widget = self.widget_manager.take(node_index);
-            let mut context = KayakContextRef::new(self, Some(node_index));
-            widget.render(&mut context);
-            self.widget_manager.repossess(widget);
-            self.widget_manager.dirty_render_nodes.insert(node_index);
+
+        let build_span = phase_span!("kayak_build", widgets);
+        self.widgets_built = 0;
+        self.is_building = true;
+        loop {
+            // Defer the renders of paused widgets, catching up on those of widgets that resumed
+            // (which may only happen while building, as their fragment becomes active again)
+            for effect in self.widget_manager.update_inactive_widgets() {
+                effect();
+            }
+            let paused = &self.widget_manager.paused;
+            let tree = &self.widget_manager.tree;
+            self.paused_renders.retain(|widget_id| {
+                if paused.contains(*widget_id) {
+                    return true;
+                }
+                if tree.contains(*widget_id) && !dirty_nodes.contains(widget_id) {
+                    dirty_nodes.push(*widget_id);
+                }
+                false
+            });
+            let paused_renders = &mut self.paused_renders;
+            dirty_nodes.retain(|widget_id| {
+                let is_paused = paused.contains(*widget_id);
+                if is_paused {
+                    paused_renders.insert(*widget_id);
+                }
+                !is_paused
+            });
+            if dirty_nodes.is_empty() {
+                break;
+            }
+
+            for node_index in std::mem::take(&mut dirty_nodes) {
+                let mut widget = self.widget_manager.take(node_index);
+                let result = {
+                    let mut context = KayakContextRef::new(self, Some(node_index));
+                    std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        crate::widget::validate_props(widget.as_ref());
+                        widget.render(&mut context);
+                    }))
+                };
+                self.widget_manager.repossess(widget);
+                if let Err(payload) = result {
+                    self.contain_panic(node_index, payload);
+                    continue;
+                }
+                self.widget_manager.dirty_render_nodes.insert(node_index);
+                self.widgets_built += 1;
+            }
         }
+        self.is_building = false;
+        build_span.record("widgets", self.widgets_built);
+        build_span.exit();
 
         // self.widget_manager.dirty_nodes.clear();
+        let mounted = self.widget_manager.prune_removed_widgets();
+        self.prune_removed_widgets(&mounted);
         self.widget_manager.render(&mut self.assets);
+        self.apply_pending_focus();
         LayoutEventDispatcher::dispatch(self);
+        self.run_after_render();
         self.update_cursor();
+
+        // Keep rendering while the focus outline is animated, so that it keeps moving
+        if self.widget_manager.update_focus_outline(now) {
+            self.render_requested = true;
+        }
+
+        if self.metrics.is_none() && !self.draw_list_enabled {
+            return;
+        }
+        let primitives = self.widget_manager.build_render_primitives();
+        if let Some(metrics) = &mut self.metrics {
+            metrics.widgets = self.widget_manager.tree.len();
+            metrics.widgets_rendered = self.widgets_built;
+            metrics.layout_nodes = self.widget_manager.node_tree.len();
+            metrics.glyph_cache = self.widget_manager.color_atlas().len();
+            metrics.build_time = now.elapsed();
+            metrics.count_draw_calls(&primitives);
+        }
+        let generation = self
+            .draw_list
+            .as_ref()
+            .map_or(0, |draw_list| draw_list.generation);
+        self.draw_list = Some(DrawList::from_primitives(&primitives, generation + 1));
+    }
+
+    /// Enables or disables building a [`DrawList`] on every render, for custom render backends
+    ///
+    /// The draw list is disabled by default, since [`draw_with`](Self::draw_with) (which the
+    /// built-in integration draws with) builds one on the spot without it. See
+    /// [`draw_list`](crate::draw_list) for the format of the list.
+    pub fn set_draw_list_enabled(&mut self, enabled: bool) {
+        if enabled != self.draw_list_enabled {
+            self.draw_list_enabled = enabled;
+            if enabled {
+                self.request_render();
+            } else if self.metrics.is_none() {
+                self.draw_list = None;
+            }
+        }
+    }
+
+    /// Returns the draw list of the most recent render
+    ///
+    /// Returns `None` if the draw list is disabled (see
+    /// [`set_draw_list_enabled`](Self::set_draw_list_enabled)). The list is retained until the
+    /// context renders again, which only happens when something changed.
+    pub fn draw_list(&self) -> Option<&DrawList> {
+        self.draw_list.as_ref().filter(|_| self.draw_list_enabled)
+    }
+
+    /// Draws the most recent render with a [`RenderBackend`](crate::render_backend::RenderBackend)
+    ///
+    /// This replays the retained draw list if it's enabled, and otherwise builds one on the spot.
+    /// Color glyphs that are new since the last frame are rasterized by the backend first, and
+    /// the atlas containing them is uploaded before anything is drawn.
+    pub fn draw_with<B: crate::render_backend::RenderBackend + ?Sized>(&mut self, backend: &mut B) {
+        let built;
+        let draw_list = match &self.draw_list {
+            Some(draw_list) => draw_list,
+            None => {
+                let primitives = self.widget_manager.build_render_primitives();
+                built = DrawList::from_primitives(&primitives, 0);
+                &built
+            }
+        };
+
+        // Only the fonts with a color fallback can have color glyphs
+        let mut fonts = HashMap::new();
+        for command in draw_list.commands.iter() {
+            if let DrawCommand::Glyphs { font, .. } = command {
+                if !fonts.contains_key(font.as_str()) {
+                    let asset = self.assets.get_asset::<KayakFont, _>(font.clone()).get();
+                    let asset = asset.filter(|font| font.fallback().is_some());
+                    fonts.insert(font.as_str(), asset);
+                }
+            }
+        }
+
+        let color_atlas = self.widget_manager.color_atlas_mut();
+        color_atlas.rasterize_queued(|font, glyph, pixels_per_em| {
+            backend.rasterize_glyph(font, glyph, pixels_per_em)
+        });
+        // Once the atlas is full, only the glyphs that are still displayed are added back
+        if self
+            .widget_manager
+            .evict_unused_color_glyphs(&mut self.assets)
+        {
+            let color_atlas = self.widget_manager.color_atlas_mut();
+            color_atlas.rasterize_queued(|font, glyph, pixels_per_em| {
+                backend.rasterize_glyph(font, glyph, pixels_per_em)
+            });
+        }
+        let color_atlas = self.widget_manager.color_atlas();
+        draw_list.replay_with(backend, Some(color_atlas), |font, font_size, glyph| {
+            let font = fonts.get(font)?.as_ref()?;
+            if !font.is_color_glyph(glyph) {
+                return None;
+            }
+            Some(color_atlas.get(font.fallback()?, glyph, font_size))
+        });
+    }
+
+    /// Enables or disables the collection of [`FrameMetrics`]
+    ///
+    /// Metrics are disabled by default. While enabled, each render builds its render primitives
+    /// up front to count its draw calls, and keeps them to be drawn by
+    /// [`draw_with`](Self::draw_with).
+    pub fn set_metrics_enabled(&mut self, enabled: bool) {
+        if enabled != self.metrics.is_some() {
+            self.metrics = enabled.then(FrameMetrics::default);
+            if !enabled && !self.draw_list_enabled {
+                self.draw_list = None;
+            }
+        }
+    }
+
+    /// Returns the metrics of the most recent render
+    ///
+    /// Returns `None` if metrics are disabled (see [`set_metrics_enabled`](Self::set_metrics_enabled)).
+    /// Metrics are only updated when the context actually renders, so these may be from several
+    /// frames ago if the UI is idle.
+    pub fn metrics(&self) -> Option<FrameMetrics> {
+        self.metrics
+    }
+
+    /// Returns true if the context needs to be rendered
+    ///
+    /// This is the case when a widget's state has changed, a throttled state update or a scheduled
+    /// render has come due, input has been processed, or another render was requested via
+    /// [`request_render`](Self::request_render) (such as by an active animation) since the last call
+    /// to [`render`](Self::render).
+    ///
+    /// Integrations can use this to skip rendering entirely while the UI is idle.
+    pub fn is_dirty(&self) -> bool {
+        self.render_requested
+            || !self.widget_manager.dirty_render_nodes.is_empty()
+            || self
+                .widget_manager
+                .dirty_nodes
+                .lock()
+                .map_or(true, |dirty_nodes| !dirty_nodes.is_empty())
+            || {
+                let now = Instant::now();
+                let is_due = |due: Instant| due <= now;
+                self.throttles
+                    .values()
+                    .any(|(_, throttle)| throttle.due().map_or(false, is_due))
+                    || self.scheduled_renders.values().copied().any(is_due)
+            }
+    }
+
+    /// Returns the time at which the current (or most recent) render started
+    ///
+    /// This is the clock that time-based widgets, such as animations, should use so that every
+    /// widget in a render agrees on the current time.
+    pub fn frame_time(&self) -> Instant {
+        self.frame_time
+    }
+
+    /// Schedules the given widget to be re-rendered once the given time has passed
+    ///
+    /// Unlike [`KayakContextRef::request_render`], this doesn't re-render the widget on every frame
+    /// in between, which makes it useful for animations that only change every so often. If the
+    /// widget already has a render scheduled, the earlier of the two times is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    /// * `due`: The time at which the widget should be re-rendered
+    ///
+    pub fn request_render_at(&mut self, widget_id: Index, due: Instant) {
+        let scheduled = self.scheduled_renders.entry(widget_id).or_insert(due);
+        if due < *scheduled {
+            *scheduled = due;
+        }
+    }
+
+    /// Requests that the context be rendered again, even if nothing is dirty
+    ///
+    /// This only affects integrations that check [`is_dirty`](Self::is_dirty) before rendering. To
+    /// re-render a specific widget on the next frame (such as for an animation), use
+    /// [`KayakContextRef::request_render`] instead.
+    pub fn request_render(&mut self) {
+        self.render_requested = true;
+    }
+
+    /// Returns the root font size, in pixels
+    ///
+    /// This is the size that [`Units::Rem`](crate::styles::Units::Rem) is relative to. It is also
+    /// used as the font size of any widget that doesn't set (or inherit) one, which in turn affects
+    /// [`Units::Em`](crate::styles::Units::Em). Defaults to `14.0`.
+    pub fn root_font_size(&self) -> f32 {
+        self.widget_manager.root_font_size
+    }
+
+    /// Sets the root font size, in pixels
+    ///
+    /// Changing the root font size re-resolves all styles on the next render.
+    pub fn set_root_font_size(&mut self, root_font_size: f32) {
+        if self.widget_manager.root_font_size == root_font_size {
+            return;
+        }
+
+        self.widget_manager.root_font_size = root_font_size;
+        // Parents must be resolved before their children so that inherited font sizes are up to date
+        for widget_id in self.widget_manager.tree.flatten() {
+            self.widget_manager.dirty_render_nodes.insert(widget_id);
+        }
+    }
+
+    /// Returns true if the [debug overlay](crate::debug_overlay) is drawn above the widget tree
+    pub fn debug_overlay(&self) -> bool {
+        self.widget_manager.debug_overlay
+    }
+
+    /// Shows or hides the [debug overlay](crate::debug_overlay), which visualizes the layout
+    /// rects, padding, spacing and clip regions of every widget
+    ///
+    /// No widgets need to be re-rendered for this to take effect, only the render primitives are
+    /// rebuilt.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled`: Whether the overlay should be drawn
+    ///
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        if self.widget_manager.debug_overlay != enabled {
+            self.widget_manager.debug_overlay = enabled;
+            self.request_render();
+        }
+    }
+
+    /// Builds the given [`Subtree`](crate::Subtree) and commits it under the given parent
+    ///
+    /// The subtree's widgets are added after the parent's current children, which are left as
+    /// they are. Passing `None` commits the subtree as the root, just like `render!`.
+    ///
+    /// Note that the subtree will be replaced if its parent re-renders, since widgets always
+    /// rebuild their own children. Use a parent that doesn't render any children itself (such as
+    /// one built by another subtree).
+    pub fn commit_subtree(&mut self, subtree: crate::Subtree, parent: Option<Index>) {
+        let mut context = KayakContextRef::new(self, parent);
+        context.keep_children();
+        subtree.build(&mut context);
+        context.commit();
+
+        if let Some(parent) = parent {
+            self.widget_manager.dirty_render_nodes.insert(parent);
+        }
+        self.request_render();
     }
 
     /// Processes the given input events
@@ -569,10 +1316,11 @@ impl KayakContext {
     /// associated [W3 specifications](https://www.w3.org/TR/uievents/#dom-event-architecture).
     ///
     /// ## Capture:
-    /// Currently, we do not support the Capture Phase. This is because the current event handling system is
-    ///   made to handle events as a single enum. To achieve proper capturing, widgets would need to be able to
-    ///   register separate event handlers so that specific ones could be captured while others would not. It
-    ///   should generally be okay to skip this as it's not a common use-case.
+    ///   The Capture Phase travels down the tree from the root node to the target node, firing the capture
+    ///   event handler (see [`WidgetProps::get_on_event_capture`](crate::WidgetProps::get_on_event_capture))
+    ///   of each widget along the way. This happens for every event, even those that don't propagate. Calling
+    ///   [`event.stop_propagation()`](Event::stop_propagation) here stops the event entirely, meaning neither
+    ///   the target nor its ancestors will receive it in the following phases.
     ///
     /// ## Target:
     ///   The Target Phase simply identifies the target for an event so that we can generate the propagation path
@@ -584,12 +1332,98 @@ impl KayakContext {
     ///   [`event.stop_propagation()`](Event::stop_propagation). Not every event can be propagated, in which case,
     ///   they will only fire for their specified target.
     pub fn process_events(&mut self, input_events: Vec<InputEvent>) {
+        // Input may change hover and focus state without marking any widget dirty
+        if !input_events.is_empty() {
+            self.request_render();
+        }
+
         let mut dispatcher = self.event_dispatcher.to_owned();
         dispatcher.process_events(input_events, self);
         self.event_dispatcher.merge(dispatcher);
     }
 
-    #[allow(dead_code)]
+    /// Registers a keyboard shortcut for the given widget
+    ///
+    /// When the shortcut's key is pressed with exactly its modifiers, the handler is called and the
+    /// key press is consumed, meaning it won't be delivered to the focused widget. Shortcuts are
+    /// checked before any other key handling and are active even when nothing is focused (though
+    /// [subtree](ShortcutScope::Subtree) shortcuts require focus to be within the widget).
+    ///
+    /// If multiple registered shortcuts match, subtree shortcuts win over global ones and,
+    /// within the same scope, the most recently registered shortcut wins. Registering the same
+    /// shortcut again from the same widget (such as on every render) just replaces its scope and
+    /// handler. Shortcuts are automatically unregistered once their widget is removed from the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    /// * `shortcut`: The key combination
+    /// * `scope`: Where the shortcut is active
+    /// * `handler`: The function to call when the shortcut is triggered
+    ///
+    pub fn register_shortcut(
+        &mut self,
+        widget_id: Index,
+        shortcut: Shortcut,
+        scope: ShortcutScope,
+        handler: OnShortcut,
+    ) {
+        self.shortcuts.register(widget_id, shortcut, scope, handler);
+    }
+
+    /// Unregisters a keyboard shortcut previously registered for the given widget
+    ///
+    /// Returns true if the shortcut was registered.
+    pub fn unregister_shortcut(&mut self, widget_id: Index, shortcut: Shortcut) -> bool {
+        self.shortcuts.unregister(widget_id, shortcut)
+    }
+
+    /// Calls the handler of the shortcut triggered by the given key event, if any
+    ///
+    /// Returns true if a shortcut was triggered (and the event should therefore be consumed).
+    pub(crate) fn dispatch_shortcut(&mut self, event: &KeyboardEvent) -> bool {
+        let focus = self.widget_manager.focus_tree.current();
+        if let Some((owner, handler)) = self.shortcuts.find(event, focus, &self.widget_manager.tree)
+        {
+            let mut context = KayakContextRef::new(self, Some(owner));
+            handler.try_call(&mut context);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Passes a panic from rendering the given widget to its closest error boundary
+    ///
+    /// The boundary is re-rendered on the next frame. If the widget isn't within a boundary, the
+    /// panic continues to unwind.
+    fn contain_panic(&mut self, index: Index, payload: Box<dyn Any + Send>) {
+        let message = crate::error_boundary::panic_message(payload.as_ref());
+        if self.report_error(index, message).is_err() {
+            std::panic::resume_unwind(payload);
+        }
+    }
+
+    /// Passes an error from rendering the given widget to its closest error boundary
+    ///
+    /// The boundary is re-rendered on the next frame. If the widget isn't within a boundary, the
+    /// error is returned back.
+    pub(crate) fn report_error(&mut self, index: Index, message: String) -> Result<(), String> {
+        let mut parents = Vec::new();
+        self.get_all_parents(index, &mut parents);
+        let boundary = parents
+            .into_iter()
+            .find(|parent| self.error_boundaries.contains_key(parent))
+            .ok_or_else(|| message.clone())?;
+
+        self.error_boundaries.insert(boundary, Some(message));
+        if let Ok(mut dirty_nodes) = self.widget_manager.dirty_nodes.lock() {
+            dirty_nodes.insert(boundary);
+        }
+        self.request_render();
+        Ok(())
+    }
+
     fn get_all_parents(&self, current: Index, parents: &mut Vec<Index>) {
         if let Some(parent) = self.widget_manager.tree.parents.get(&current) {
             parents.push(*parent);
@@ -608,6 +1442,92 @@ impl KayakContext {
         self.widget_manager.focus_tree.current()
     }
 
+    /// Focuses the widget with the given ID
+    ///
+    /// The focus changes at the end of the next render, once the widget has been added to the tree
+    /// (which makes this safe to call on a widget that's still being built). Like focusing a widget
+    /// by clicking on it or pressing `Tab`, this sends a [`Focus`](crate::EventType::Focus) event to
+    /// the widget and a [`Blur`](crate::EventType::Blur) event to the previously focused widget.
+    ///
+    /// If this is called several times before the next render, only the last call takes effect.
+    /// Widgets that can't be focused (such as disabled widgets) are ignored. Calling this while
+    /// widgets are being rendered (such as when a widget [autofocuses](crate::WidgetProps::get_autofocus))
+    /// focuses the widget at the end of that same render, without requesting another one.
+    pub fn focus(&mut self, index: Index) {
+        self.pending_focus = Some(index);
+        if !self.is_building {
+            self.request_render();
+        }
+    }
+
+    /// Queues a function to run in the given widget's context once the current render has been
+    /// laid out
+    ///
+    /// See [`KayakContextRef::after_render`] for details.
+    pub(crate) fn after_render(&mut self, widget_id: Index, f: AfterRender) {
+        self.after_render.push((widget_id, f));
+    }
+
+    /// Queues a function to run once the given widget is unmounted, replacing the one queued with
+    /// the same key (or removing it if `None`)
+    ///
+    /// See [`KayakContextRef::on_unmount`] for details.
+    pub(crate) fn on_unmount(&mut self, widget_id: Index, key: &str, f: Option<OnUnmount>) {
+        match f {
+            Some(f) => {
+                self.unmount_handlers
+                    .entry(widget_id)
+                    .or_default()
+                    .insert(key.to_string(), f);
+            }
+            None => {
+                if let Some(handlers) = self.unmount_handlers.get_mut(&widget_id) {
+                    handlers.remove(key);
+                    if handlers.is_empty() {
+                        self.unmount_handlers.remove(&widget_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the functions queued via [`after_render`](Self::after_render), skipping those of
+    /// widgets that have since been removed from the tree
+    fn run_after_render(&mut self) {
+        for (widget_id, f) in std::mem::take(&mut self.after_render) {
+            if self.widget_manager.tree.contains(widget_id) {
+                let mut context = KayakContextRef::new(self, Some(widget_id));
+                f(&mut context);
+            }
+        }
+    }
+
+    /// Focuses the widget requested via [`focus`](Self::focus), if any
+    fn apply_pending_focus(&mut self) {
+        let index = match self.pending_focus.take() {
+            Some(index) => index,
+            None => return,
+        };
+        if !self.widget_manager.focus_tree.contains(index) {
+            return;
+        }
+
+        let current_focus = self.widget_manager.focus_tree.current();
+        if current_focus == Some(index) {
+            return;
+        }
+
+        let mut events = vec![Event::new(index, EventType::Focus)];
+        if let Some(current_focus) = current_focus {
+            events.push(Event::new(current_focus, EventType::Blur));
+        }
+        self.widget_manager.focus_tree.focus(index);
+
+        let mut dispatcher = self.event_dispatcher.to_owned();
+        dispatcher.dispatch_events(events, self);
+        self.event_dispatcher.merge(dispatcher);
+    }
+
     /// Gets whether the widget with the given ID can be focused
     ///
     /// The values are:
@@ -622,6 +1542,14 @@ impl KayakContext {
         self.widget_manager.get_focusable(index)
     }
 
+    /// Checks if the widget with the given ID is disabled
+    ///
+    /// This is the resolved state, meaning a widget is disabled if it or its nearest ancestor
+    /// with a specified state is disabled (see [`WidgetProps::get_disabled`](crate::WidgetProps::get_disabled)).
+    pub fn is_disabled(&self, index: Index) -> bool {
+        self.widget_manager.is_disabled(index)
+    }
+
     /// Sets the "focusability" of the widget with the given ID
     ///
     /// The values are:
@@ -636,6 +1564,93 @@ impl KayakContext {
         self.widget_manager.set_focusable(focusable, index, false);
     }
 
+    /// Gets the gestures recognized by the widget with the given ID
+    pub fn get_gestures(&self, index: Index) -> Gestures {
+        self.widget_manager.get_gestures(index)
+    }
+
+    /// Sets the gestures recognized by the widget with the given ID
+    ///
+    /// See [`Gestures`] for details on how gestures are dispatched.
+    pub fn set_gestures(&mut self, gestures: Gestures, index: Index) {
+        self.widget_manager.set_gestures(gestures, index);
+    }
+
+    /// Gets the widget whose content the widget with the given ID shows an overview of, if any
+    pub fn get_overview(&self, index: Index) -> Option<Index> {
+        self.widget_manager.get_overview(index)
+    }
+
+    /// Makes the widget with the given ID draw an overview of the source's content
+    ///
+    /// See [`KayakContextRef::set_overview`](crate::KayakContextRef::set_overview) for details.
+    pub fn set_overview(&mut self, source: Option<Index>, index: Index) {
+        self.widget_manager.set_overview(source, index);
+    }
+
+    /// Registers a layer that portals can be drawn into, or changes the order of an existing one
+    ///
+    /// Layers with a higher order are drawn above those with a lower one. See
+    /// [`portal`](crate::portal) for the default layers.
+    pub fn register_portal_layer(&mut self, name: &str, order: i32) {
+        self.widget_manager.register_portal_layer(name, order);
+    }
+
+    /// Gets the layer the widget with the given ID is drawn into, if it's a portal
+    pub fn get_portal(&self, index: Index) -> Option<&str> {
+        self.widget_manager.get_portal(index)
+    }
+
+    /// Draws the widget with the given ID (and its descendants) into the given portal layer
+    ///
+    /// Passing `None` draws the widget in place again.
+    pub fn set_portal(&mut self, layer: Option<&str>, index: Index) {
+        self.widget_manager
+            .set_portal(layer.map(|layer| layer.to_string()), index);
+    }
+
+    /// Draws the portal with the given ID above all other portals in its layer
+    pub fn raise_portal(&mut self, index: Index) {
+        self.widget_manager.raise_portal(index);
+    }
+
+    /// Finds the topmost widget at the given point
+    ///
+    /// This uses the same logic as pointer event dispatch when picking between overlapping widgets
+    /// and respects the [`PointerEvents`](crate::PointerEvents) style. Widgets clipped out of view
+    /// cannot be hit.
+    ///
+    /// Returns `None` if no widget is at the given point.
+    ///
+    /// # Arguments
+    ///
+    /// * `point`: The point to test, in the same coordinate space as the cursor
+    ///
+    pub fn hit_test(&self, point: (f32, f32)) -> Option<Index> {
+        EventDispatcher::hit_test(point, &self.widget_manager)
+    }
+
+    /// Finds the topmost widget at the given point, along with all of its ancestors
+    ///
+    /// The returned path starts at the hit widget and ends at the root, which is the same path a
+    /// pointer event at this point would propagate along. See [`hit_test`](Self::hit_test) for details.
+    ///
+    /// Returns an empty path if no widget is at the given point.
+    ///
+    /// # Arguments
+    ///
+    /// * `point`: The point to test, in the same coordinate space as the cursor
+    ///
+    pub fn hit_test_path(&self, point: (f32, f32)) -> Vec<Index> {
+        let mut path = Vec::new();
+        let mut current = self.hit_test(point);
+        while let Some(index) = current {
+            path.push(index);
+            current = self.widget_manager.node_tree.get_parent(index);
+        }
+        path
+    }
+
     /// Get the last calculated mouse position.
     ///
     /// Calling this from a widget will return the last mouse position at the time the widget was rendered.
@@ -686,6 +1701,107 @@ impl KayakContext {
         r
     }
 
+    /// Subscribes the given widget to a projection of the Bevy `World`, returning the projected value
+    ///
+    /// The projection is called with the given `SystemParam` when the widget renders and again
+    /// whenever [`update_world_queries`](Self::update_world_queries) is called (once per frame in
+    /// the Bevy integration). The widget is only re-rendered when the projected value changes, so
+    /// a widget that displays a single field of a component isn't re-rendered when any other part
+    /// of it changes.
+    ///
+    /// The query's `SystemState` is kept for as long as the widget is mounted, which means change
+    /// detection (such as `Changed<T>` filters or `Res::is_changed`) is relative to the previous
+    /// frame, not the previous render of the widget.
+    ///
+    /// Queries are identified by the type of the projection function, which is unique to each
+    /// closure. The same closure should therefore not be used for different queries (such as within
+    /// a loop).
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget
+    /// * `projection`: The function that selects a value from the system parameter
+    ///
+    #[cfg(feature = "bevy_renderer")]
+    pub fn use_query<P, T, F>(&mut self, widget_id: Index, projection: F) -> T
+    where
+        P: bevy::ecs::system::SystemParam + 'static,
+        T: Clone + PartialEq + Send + Sync + 'static,
+        F: FnMut(<P::Fetch as bevy::ecs::system::SystemParamFetch<'_, '_>>::Item) -> T
+            + Send
+            + Sync
+            + 'static,
+    {
+        let mut world = self.remove_global::<bevy::prelude::World>().unwrap();
+        let queries = self.world_queries.entry(widget_id).or_default();
+        let value = if let Some(query) = queries
+            .get_mut(&TypeId::of::<F>())
+            .and_then(|query| query.as_any_mut().downcast_mut::<ProjectedQuery<P, T, F>>())
+        {
+            query.replace(&mut world, projection)
+        } else {
+            let query = ProjectedQuery::<P, T, F>::new(&mut world, projection);
+            let value = query.value();
+            queries.insert(TypeId::of::<F>(), Box::new(query));
+            value
+        };
+        self.set_global(world);
+
+        value
+    }
+
+    /// Re-runs the queries created via [`use_query`](Self::use_query), marking any widget whose
+    /// projected value changed as dirty
+    ///
+    /// Integrations should call this once per frame, before checking [`is_dirty`](Self::is_dirty).
+    /// Queries belonging to widgets that are no longer mounted are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `world`: The Bevy `World` to query
+    ///
+    #[cfg(feature = "bevy_renderer")]
+    pub fn update_world_queries(&mut self, world: &mut bevy::prelude::World) {
+        let tree = &self.widget_manager.tree;
+        self.world_queries
+            .retain(|widget_id, _| tree.contains(*widget_id));
+
+        if let Ok(mut dirty_nodes) = self.widget_manager.dirty_nodes.lock() {
+            for (widget_id, queries) in self.world_queries.iter_mut() {
+                let mut changed = false;
+                for query in queries.values_mut() {
+                    changed = query.update(world) || changed;
+                }
+                if changed {
+                    dirty_nodes.insert(*widget_id);
+                }
+            }
+        }
+    }
+
+    /// Get the queue of commands to apply to the Bevy `World` once the current frame has rendered
+    ///
+    /// See [`WorldCommands`] for details.
+    #[cfg(feature = "bevy_renderer")]
+    pub fn world_commands(&mut self) -> &mut WorldCommands {
+        &mut self.world_commands
+    }
+
+    /// Applies the commands queued via [`world_commands`](Self::world_commands) to the given `World`
+    ///
+    /// Integrations should call this once per frame, after [`render`](Self::render) (if the
+    /// context was rendered at all), so that commands always run after the render has been
+    /// committed.
+    ///
+    /// # Arguments
+    ///
+    /// * `world`: The Bevy `World` to apply the commands to
+    ///
+    #[cfg(feature = "bevy_renderer")]
+    pub fn apply_world_commands(&mut self, world: &mut bevy::prelude::World) {
+        self.world_commands.apply(world);
+    }
+
     /// Get a stored asset with the given asset key
     ///
     /// The type of the asset [T] must implement `Clone` and `PartialEq` so that a `Binding<Option<T>>`
@@ -800,6 +1916,24 @@ impl KayakContext {
         self.cursor_icon
     }
 
+    /// Get the clipboard that widgets copy text to
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        self.clipboard.as_mut()
+    }
+
+    /// Sets the clipboard that widgets copy text to
+    ///
+    /// Defaults to a [`LocalClipboard`], which doesn't share text with other apps. Use this to
+    /// provide access to the system clipboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `clipboard`: The clipboard to use
+    ///
+    pub fn set_clipboard<C: Clipboard + 'static>(&mut self, clipboard: C) {
+        self.clipboard = Box::new(clipboard);
+    }
+
     #[allow(dead_code)]
     pub(crate) fn set_cursor_icon(&mut self, icon: CursorIcon) {
         self.cursor_icon = icon;
@@ -819,3 +1953,163 @@ impl KayakContext {
         }
     }
 }
+
+/// Mounts the given widget as the root of the given context and renders it
+///
+/// This is meant for tests, which usually render a single widget and then interact with it. It's
+/// available outside of this crate with the `test_utils` feature.
+#[cfg(any(test, feature = "test_utils"))]
+pub fn mount_widget<W: Widget>(context: &mut KayakContext, widget: W) {
+    let subtree = crate::Subtree::new(move |context| context.add_widget(widget, 0));
+    context.commit_subtree(subtree, None);
+    context.render();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KayakContext;
+    use crate::Index;
+
+    /// Adds a root with the given number of children to the tree, returning the children
+    fn mount(context: &mut KayakContext, count: usize) -> Vec<Index> {
+        let root = Index::from_raw_parts(0, 0);
+        context.widget_manager.tree.add(root, None);
+        (1..=count)
+            .map(|index| {
+                let child = Index::from_raw_parts(index, 0);
+                context.widget_manager.tree.add(child, Some(root));
+                child
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_prune_layouts_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 2);
+        for child in &children {
+            context.use_layout(*child);
+        }
+        context.widget_manager.tree.remove(children[1]);
+        context.render();
+
+        assert!(context.widget_layouts.contains_key(&children[0]));
+        assert!(!context.widget_layouts.contains_key(&children[1]));
+    }
+
+    #[test]
+    fn should_prune_selections_of_removed_widgets() {
+        use crate::MutableBound;
+
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 2);
+        let binding = crate::bind((1, 2));
+        for child in &children {
+            context.select(*child, &binding, |value| value.0);
+        }
+        context.widget_manager.tree.remove(children[1]);
+        context.render();
+
+        assert!(context.widget_selections.contains_key(&children[0]));
+        assert!(!context.widget_selections.contains_key(&children[1]));
+
+        // The removed widget's selection no longer marks it dirty
+        binding.set((3, 2));
+        let dirty_nodes = context.widget_manager.dirty_nodes.lock().unwrap().clone();
+        assert!(dirty_nodes.contains(&children[0]));
+        assert!(!dirty_nodes.contains(&children[1]));
+    }
+
+    #[test]
+    fn should_prune_shortcuts_of_removed_widgets() {
+        use crate::{KeyCode, OnShortcut, Shortcut, ShortcutScope};
+
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 2);
+        let shortcut = Shortcut::new(KeyCode::S).ctrl();
+        for child in &children {
+            let handler = OnShortcut::new(|_| {});
+            context.register_shortcut(*child, shortcut, ShortcutScope::Global, handler);
+        }
+        context.widget_manager.tree.remove(children[1]);
+        context.render();
+
+        assert!(context.unregister_shortcut(children[0], shortcut));
+        assert!(!context.unregister_shortcut(children[1], shortcut));
+    }
+
+    #[test]
+    fn should_prune_error_boundaries_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 2);
+        for child in &children {
+            context
+                .error_boundaries
+                .insert(*child, Some(String::from("error")));
+        }
+        context.widget_manager.tree.remove(children[1]);
+        context.render();
+
+        assert!(context.error_boundaries.contains_key(&children[0]));
+        assert!(!context.error_boundaries.contains_key(&children[1]));
+    }
+
+    #[test]
+    fn should_prune_throttles_of_removed_widgets() {
+        use std::time::Duration;
+
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 2);
+        let bindings = [crate::bind(0), crate::bind(0)];
+        for (child, binding) in children.iter().zip(&bindings) {
+            context.create_throttled_setter(*child, binding, Duration::from_millis(100));
+        }
+        context.widget_manager.tree.remove(children[1]);
+        context.render();
+
+        assert!(context.throttles.contains_key(&bindings[0].id));
+        assert!(!context.throttles.contains_key(&bindings[1].id));
+    }
+
+    #[test]
+    fn should_update_the_interval_of_an_existing_throttle() {
+        use crate::Bound;
+        use std::time::Duration;
+
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 1);
+        let binding = crate::bind(0);
+        let set_state = context.create_throttled_setter(children[0], &binding, Duration::MAX);
+        set_state.set(1);
+        set_state.set(2);
+
+        // The returned setter is the same one, now with the new interval
+        let set_state = context.create_throttled_setter(children[0], &binding, Duration::ZERO);
+        context.render();
+        assert_eq!(2, binding.get());
+        set_state.set(3);
+        assert_eq!(3, binding.get());
+    }
+
+    #[test]
+    fn should_only_be_dirty_once_a_throttled_value_is_due() {
+        use std::time::Duration;
+
+        let mut context = KayakContext::new();
+        let children = mount(&mut context, 1);
+        let binding = crate::bind(0);
+        let interval = Duration::from_secs(60);
+        let set_state = context.create_throttled_setter(children[0], &binding, interval);
+        set_state.set(1);
+        set_state.set(2);
+
+        // The pending value is scheduled instead of rendering on every frame until it's due
+        context.render();
+        assert!(!context.is_dirty());
+        assert_eq!(
+            set_state.due(),
+            context.scheduled_renders.get(&children[0]).copied()
+        );
+        assert!(set_state.due().unwrap() > context.frame_time());
+    }
+}
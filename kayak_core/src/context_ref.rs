@@ -1,6 +1,8 @@
+use std::panic::{self, AssertUnwindSafe};
 use std::path::PathBuf;
 
-use crate::{Binding, Changeable, Index, KayakContext, WidgetTree};
+use crate::instrument::phase_span;
+use crate::{Binding, Changeable, Gestures, Index, KayakContext, WidgetTree};
 
 /// A temporary struct used to provide limited access to the containing [`KayakContext`]
 ///
@@ -12,6 +14,8 @@ pub struct KayakContextRef<'a> {
     current_id: Option<Index>,
     /// The currently generated widget tree
     tree: Option<WidgetTree>,
+    /// The number of existing children that newly added widgets are placed after
+    index_offset: usize,
 }
 
 impl<'a> KayakContextRef<'a> {
@@ -31,9 +35,31 @@ impl<'a> KayakContextRef<'a> {
             context,
             current_id,
             tree: Some(WidgetTree::new()),
+            index_offset: 0,
         }
     }
 
+    /// Keeps the current widget's existing children, placing any widgets added after them
+    ///
+    /// This is used to append a [`Subtree`](crate::Subtree) to a widget without rebuilding the
+    /// children it already has.
+    pub(crate) fn keep_children(&mut self) {
+        let children = self
+            .current_id
+            .and_then(|id| self.context.widget_manager.tree.children.get(&id).cloned());
+        for child in children.unwrap_or_default() {
+            self.tree.as_ref().unwrap().add(child, self.current_id);
+            self.index_offset += 1;
+        }
+    }
+
+    /// Returns the ID of the current widget
+    ///
+    /// Returns `None` when building the root widget.
+    pub fn current_id(&self) -> Option<Index> {
+        self.current_id
+    }
+
     /// Bind this widget to a `Binding<T>` value
     ///
     /// "Binding" means that whenever the bound value is changed, the current widget will be re-rendered.
@@ -94,6 +120,15 @@ impl<'a> KayakContextRef<'a> {
             .create_consumer(self.current_id.unwrap_or_default())
     }
 
+    /// Gets the current theme, re-rendering the current widget whenever the theme changes
+    ///
+    /// This is the theme of the nearest `ThemeProvider` ancestor, or the [app-wide
+    /// theme](KayakContext::theme) if there is none. Switching themes only re-renders the widgets
+    /// that called this, rather than the entire tree.
+    pub fn use_theme(&mut self) -> crate::Theme {
+        self.context.use_theme(self.current_id.unwrap_or_default())
+    }
+
     /// Create a state
     ///
     /// A "state" is a value that is maintained across re-renders of a widget. Additionally, widgets
@@ -149,6 +184,123 @@ impl<'a> KayakContextRef<'a> {
             .create_state(self.current_id.unwrap_or_default(), initial_state)
     }
 
+    /// Subscribes the current widget to a projection of a binding, returning the projected value
+    ///
+    /// The widget is only re-rendered when the projected value changes. For example, a widget that
+    /// only displays a user's name won't re-render when some other field of the user changes. See
+    /// [`KayakContext::select`] for details.
+    ///
+    /// ```ignore
+    /// let name = context.select(&user, |user| user.name.clone());
+    /// ```
+    pub fn select<T, U, F>(&mut self, binding: &Binding<T>, projection: F) -> U
+    where
+        T: Clone + PartialEq + Send + Sync + 'static,
+        U: Clone + PartialEq + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        self.context
+            .select(self.current_id.unwrap_or_default(), binding, projection)
+    }
+
+    /// Creates a throttled setter for the given state
+    ///
+    /// The returned setter commits its value (and therefore re-renders any bound widgets) at most
+    /// once per `interval`. Values set in between are coalesced, and the latest one is committed
+    /// on the first render after the interval has elapsed.
+    ///
+    /// Calling this again with the same state returns the existing setter, updated to use the
+    /// given `interval`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state`: The state to throttle
+    /// * `interval`: The minimum amount of time between commits
+    ///
+    pub fn create_throttled_setter<T: resources::Resource + Clone + PartialEq>(
+        &mut self,
+        state: &crate::Binding<T>,
+        interval: std::time::Duration,
+    ) -> crate::ThrottledSetter<T> {
+        self.context
+            .create_throttled_setter(self.current_id.unwrap_or_default(), state, interval)
+    }
+
+    /// Runs the given callback every `interval`, using the context's [frame time](Self::frame_time)
+    ///
+    /// This should be called on every render of the widget. The widget is automatically scheduled to
+    /// re-render when the next tick is due, at which point the callback runs (at most once per render).
+    /// Ticks are timed from the previous tick rather than from the frame they fired on, so they don't
+    /// drift over time.
+    ///
+    /// Changing the interval restarts the timer, as does resuming it after being `paused`. No more
+    /// renders are scheduled while paused or once the widget is removed from the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval`: The time between ticks
+    /// * `paused`: Whether the timer is currently paused
+    /// * `callback`: The function to call when the timer ticks
+    ///
+    pub fn create_interval<F: FnOnce()>(
+        &mut self,
+        interval: std::time::Duration,
+        paused: bool,
+        callback: F,
+    ) {
+        use crate::Bound;
+
+        let timer = match self.create_state(crate::timer::Timer::default()) {
+            Some(timer) => timer.get(),
+            None => return,
+        };
+
+        let (ticked, next_tick) = timer.update_interval(self.frame_time(), interval, paused);
+        if let Some(next_tick) = next_tick {
+            self.request_render_at(next_tick);
+        }
+        if ticked {
+            callback();
+        }
+    }
+
+    /// Runs the given callback once, after `delay` has elapsed
+    ///
+    /// This should be called on every render of the widget. The delay starts on the first render and
+    /// the widget is automatically scheduled to re-render when it's due, at which point the callback runs.
+    /// The timeout is restarted whenever the delay or `key` (such as a hash of some dependencies)
+    /// changes, and can also be restarted or cancelled through the returned handle. It won't fire once
+    /// the widget is removed from the tree.
+    ///
+    /// Each call creates its own timer, so multiple timeouts in the same widget don't affect one another.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay`: The time to wait before running the callback
+    /// * `key`: A value that restarts the timeout when changed
+    /// * `callback`: The function to call once the delay has elapsed
+    ///
+    pub fn create_timeout<F: FnOnce()>(
+        &mut self,
+        delay: std::time::Duration,
+        key: u64,
+        callback: F,
+    ) -> Option<crate::TimeoutHandle> {
+        use crate::Bound;
+
+        let timer = self.create_state(crate::timer::Timer::default())?;
+
+        let (fired, deadline) = timer.get().update_timeout(self.frame_time(), delay, key);
+        if let Some(deadline) = deadline {
+            self.request_render_at(deadline);
+        }
+        if fired {
+            callback();
+        }
+
+        Some(crate::TimeoutHandle { timer })
+    }
+
     /// Creates a callback that runs as a side-effect of one of its dependencies being changed.
     ///
     /// All dependencies must be implement the [Changeable](crate::Changeable) trait, which means it will generally
@@ -274,6 +426,13 @@ impl<'a> KayakContextRef<'a> {
         self.context.current_focus()
     }
 
+    /// Focuses the widget with the given ID at the end of the next render
+    ///
+    /// See [`KayakContext::focus`] for details.
+    pub fn focus(&mut self, id: Index) {
+        self.context.focus(id);
+    }
+
     /// Gets whether the widget with the given ID can be focused
     ///
     /// The values are:
@@ -288,6 +447,16 @@ impl<'a> KayakContextRef<'a> {
         self.context.get_focusable(id)
     }
 
+    /// Checks if the current widget is disabled
+    ///
+    /// This is the resolved state, meaning a widget is disabled if it or its nearest ancestor
+    /// with a specified state is disabled. Interactive widgets should use this to render themselves
+    /// as disabled.
+    pub fn is_disabled(&self) -> bool {
+        self.current_id
+            .map_or(false, |id| self.context.is_disabled(id))
+    }
+
     /// Sets the current widget's "focusability"
     ///
     /// The values are:
@@ -304,6 +473,116 @@ impl<'a> KayakContextRef<'a> {
         }
     }
 
+    /// Gets the gestures recognized by the widget with the given ID
+    pub fn get_gestures(&self, id: Index) -> Gestures {
+        self.context.get_gestures(id)
+    }
+
+    /// Sets the gestures recognized by the current widget
+    ///
+    /// Widgets only receive the gesture events they recognize, which prevents nested widgets from
+    /// fighting over the same touch. See [`Gestures`] for details.
+    ///
+    /// ```ignore
+    /// // A pannable canvas that can also be zoomed
+    /// context.set_gestures(Gestures::PAN | Gestures::PINCH);
+    /// ```
+    pub fn set_gestures(&mut self, gestures: Gestures) {
+        if let Some(id) = self.current_id {
+            self.context.set_gestures(gestures, id);
+        }
+    }
+
+    /// Gets the widget whose content the widget with the given ID shows an overview of, if any
+    pub fn get_overview(&self, id: Index) -> Option<Index> {
+        self.context.get_overview(id)
+    }
+
+    /// Makes the current widget draw a scaled-down overview of the source's content
+    ///
+    /// The source's descendants are recorded into a retained draw list whenever the tree renders,
+    /// which is drawn again within the current widget's layout, scaled down so that the bounds of
+    /// the source's children fit inside it, centered. This reuses their existing layout rather
+    /// than laying them out again, so it's cheap enough for minimaps of large trees. The overview is only drawn: nothing within it can
+    /// be hit or focused. Passing `None` stops drawing the overview.
+    ///
+    /// ```ignore
+    /// // A minimap of the canvas
+    /// context.set_overview(Some(canvas_id));
+    /// ```
+    pub fn set_overview(&mut self, source: Option<Index>) {
+        if let Some(id) = self.current_id {
+            self.context.set_overview(source, id);
+        }
+    }
+
+    /// Draws the current widget (and its descendants) into the given portal layer
+    ///
+    /// Portals are drawn above the rest of the tree and outside of any clipping, which makes them
+    /// useful for dropdowns, modals and tooltips. Passing `None` draws the widget in place again.
+    ///
+    /// ```ignore
+    /// context.set_portal(Some(portal::TOOLTIP));
+    /// ```
+    pub fn set_portal(&mut self, layer: Option<&str>) {
+        if let Some(id) = self.current_id {
+            self.context.set_portal(layer, id);
+        }
+    }
+
+    /// Draws the portal with the given ID above all other portals in its layer, such as when a
+    /// window is clicked
+    ///
+    /// ```ignore
+    /// let window_id = self.get_id();
+    /// let on_event = OnEvent::new(move |ctx, event| {
+    ///     if let EventType::MouseDown(..) = event.event_type {
+    ///         ctx.raise_portal(window_id);
+    ///     }
+    /// });
+    /// ```
+    pub fn raise_portal(&mut self, index: Index) {
+        self.context.raise_portal(index);
+    }
+
+    /// Builds widgets with the given function, catching any panic from the widgets it builds
+    ///
+    /// This makes the current widget an error boundary: panics from descendants that are
+    /// re-rendered on their own later on are caught as well, and the current widget is re-rendered
+    /// so that its next call to this method returns the error (without calling `f`). See
+    /// [`error_boundary`](crate::error_boundary) for details.
+    ///
+    /// Returns the message of the caught panic, if any. When a panic is caught, all widgets added
+    /// to the current widget during this render are discarded, so a fallback can be built in their
+    /// place.
+    ///
+    /// # Caveats
+    ///
+    /// * Only panics that unwind are caught. Aborts (including panics with `panic = "abort"` and
+    ///   panics while already panicking) still terminate the app.
+    /// * The panic hook still runs, so the panic is printed as usual.
+    /// * The function is treated as [`UnwindSafe`](std::panic::UnwindSafe). Any state it (or the
+    ///   widgets it builds) modified before panicking is left as-is, which may leave bindings,
+    ///   globals or locks (which are poisoned) in an unexpected state.
+    ///
+    /// ```ignore
+    /// let children = children.clone();
+    /// if let Err(message) = context.catch_panic(move |context| rsx! { <>{children}</> }) {
+    ///     println!("Caught a panic: {}", message);
+    /// }
+    /// ```
+    pub fn catch_panic<F: FnOnce(&mut KayakContextRef)>(&mut self, f: F) -> Result<(), String> {
+        let id = self.current_id.unwrap_or_default();
+        if let Some(Some(message)) = self.context.error_boundaries.insert(id, None) {
+            return Err(message);
+        }
+
+        panic::catch_unwind(AssertUnwindSafe(|| f(self))).map_err(|payload| {
+            self.tree = Some(WidgetTree::new());
+            crate::error_boundary::panic_message(payload.as_ref())
+        })
+    }
+
     /// Query the Bevy `World` with the given `SystemParam`
     ///
     /// The function passed to this method will be called with the retrieved value from `World`. If
@@ -339,6 +618,71 @@ impl<'a> KayakContextRef<'a> {
         self.context.query_world::<T, F, R>(f)
     }
 
+    /// Subscribes the current widget to a projection of the Bevy `World`, returning the projected value
+    ///
+    /// The projection is re-run once per frame, and the widget is only re-rendered when the
+    /// projected value changes. Project only the data the widget displays, so that unrelated
+    /// changes to the same component or resource don't cause re-renders.
+    ///
+    /// Change detection (such as `Changed<T>` filters or `Res::is_changed`) is relative to the
+    /// previous frame, since the query's `SystemState` is kept for as long as the widget is mounted.
+    ///
+    /// # Arguments
+    ///
+    /// * `projection`: The function that selects a value from the system parameter
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use bevy::prelude::{Component, Query, With};
+    ///
+    /// #[derive(Component)]
+    /// struct Player;
+    ///
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// #[widget]
+    /// fn HealthBar() {
+    ///   let health = context.use_query::<Query<&Health, With<Player>>, _, _>(|query| {
+    ///     query.get_single().map_or(0, |health| health.0)
+    ///   });
+    /// }
+    /// ```
+    #[cfg(feature = "bevy_renderer")]
+    pub fn use_query<P, T, F>(&mut self, projection: F) -> T
+    where
+        P: bevy::ecs::system::SystemParam + 'static,
+        T: Clone + PartialEq + Send + Sync + 'static,
+        F: FnMut(<P::Fetch as bevy::ecs::system::SystemParamFetch<'_, '_>>::Item) -> T
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.context
+            .use_query::<P, T, F>(self.current_id.unwrap_or_default(), projection)
+    }
+
+    /// Get the queue of commands to apply to the Bevy `World` once the current frame has rendered
+    ///
+    /// This is mostly useful within event handlers, for sending Bevy events or mutating resources
+    /// in response to user input. See [`WorldCommands`](crate::WorldCommands) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// struct MyEvent;
+    ///
+    /// let on_event = OnEvent::new(|context, event| match event.event_type {
+    ///     EventType::Click(..) => context.world_commands().send_event(MyEvent),
+    ///     _ => {}
+    /// });
+    /// ```
+    #[cfg(feature = "bevy_renderer")]
+    pub fn world_commands(&mut self) -> &mut crate::WorldCommands {
+        self.context.world_commands()
+    }
+
     /// Get a stored asset with the given asset key
     ///
     /// The type of the asset [T] must implement `Clone` and `PartialEq` so that a `Binding<Option<T>>`
@@ -390,6 +734,24 @@ impl<'a> KayakContextRef<'a> {
         self.context.set_asset(key, asset)
     }
 
+    /// Finds the topmost widget at the given point
+    ///
+    /// This uses the same logic as pointer event dispatch when picking between overlapping widgets
+    /// and respects the [`PointerEvents`](crate::PointerEvents) style. Widgets clipped out of view
+    /// cannot be hit.
+    ///
+    /// Returns `None` if no widget is at the given point.
+    pub fn hit_test(&self, point: (f32, f32)) -> Option<Index> {
+        self.context.hit_test(point)
+    }
+
+    /// Finds the topmost widget at the given point, along with all of its ancestors
+    ///
+    /// The returned path starts at the hit widget and ends at the root.
+    pub fn hit_test_path(&self, point: (f32, f32)) -> Vec<Index> {
+        self.context.hit_test_path(point)
+    }
+
     /// Get the last calculated mouse position.
     ///
     /// Calling this from a widget will return the last mouse position at the time the widget was rendered.
@@ -455,6 +817,13 @@ impl<'a> KayakContextRef<'a> {
         self.context.force_release_cursor()
     }
 
+    /// Get the clipboard that widgets copy text to
+    ///
+    /// See [`KayakContext::set_clipboard`] for providing access to the system clipboard.
+    pub fn clipboard(&mut self) -> &mut dyn crate::Clipboard {
+        self.context.clipboard()
+    }
+
     /// Attempts to get the parent of the widget with the given ID
     ///
     /// A "valid" parent is simply one that does not have a render command of
@@ -491,6 +860,202 @@ impl<'a> KayakContextRef<'a> {
         self.context.widget_manager.get_layout(widget_id)
     }
 
+    /// Finds the char boundary within a text widget closest to the given point
+    ///
+    /// The point is in the same space as cursor events (such as a
+    /// [`CursorEvent`](crate::CursorEvent)'s position), and the boundary is found using the text
+    /// layout calculated for rendering. See
+    /// [`TextLayout::hit_test_char`](kayak_font::TextLayout::hit_test_char) for details.
+    ///
+    /// Returns `None` if the widget doesn't render text or hasn't been laid out yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the text widget
+    /// * `point`: The point to test
+    ///
+    pub fn hit_test_text(&self, widget_id: &Index, point: (f32, f32)) -> Option<usize> {
+        let layout = self.get_layout(widget_id)?;
+        // Text is laid out in its untransformed space
+        let scale = self.get_scale(widget_id).max(f32::EPSILON);
+        let position = (
+            (point.0 - layout.posx) / scale,
+            (point.1 - layout.posy) / scale,
+        );
+        match self.get_node(widget_id)?.primitive {
+            crate::render_primitive::RenderPrimitive::Text { text_layout, .. } => {
+                Some(text_layout.hit_test_char(position))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the scale a widget is drawn at, as of the previous render
+    ///
+    /// This is the product of the [transform](crate::styles::Style::transform) scales of the
+    /// widget and its ancestors, so it's `1.0` for widgets that aren't transformed. Dividing a
+    /// distance within the widget's [layout](Self::get_layout) by the scale gives the distance in
+    /// the widget's untransformed space.
+    pub fn get_scale(&self, widget_id: &Index) -> f32 {
+        self.context
+            .widget_manager
+            .layout_cache
+            .scale
+            .get(widget_id)
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Get the layout of the current widget as of the previous render
+    ///
+    /// The current widget will be re-rendered whenever its layout changes. Since layout
+    /// is calculated _after_ rendering, the returned value always lags one frame behind
+    /// (and is `None` on the first render).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[widget]
+    /// fn MyWidget() {
+    ///   let layout = context.use_layout();
+    ///   let should_wrap = layout.map(|layout| layout.width < 200.0).unwrap_or_default();
+    /// }
+    /// ```
+    pub fn use_layout(&mut self) -> Option<crate::Layout> {
+        self.context.use_layout(self.current_id.unwrap_or_default())
+    }
+
+    /// Get the value passed to this call on the previous render
+    ///
+    /// The given value is stored for the next render, and the one stored on the previous render is
+    /// returned (or `None` on the first render). Storing the value doesn't cause a re-render, so the
+    /// returned value always lags exactly one render behind. This makes it easy to detect how a
+    /// prop or state changed, such as the direction of a transition.
+    ///
+    /// Like [`create_state`](Self::create_state), this must be called unconditionally and in the
+    /// same order on every render.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[widget]
+    /// fn MyWidget(props: MyWidgetProps) {
+    ///   let previous_page = context.use_previous(props.page);
+    ///   let is_forward = previous_page.map_or(true, |previous_page| props.page > previous_page);
+    /// }
+    /// ```
+    pub fn use_previous<T: resources::Resource + Clone>(&mut self, value: T) -> Option<T> {
+        use crate::Bound;
+
+        let previous = self.create_state(crate::previous::Previous::<T>::default())?;
+        previous.get().replace(value)
+    }
+
+    /// Create an animation that's played, paused, reversed and seeked from code
+    ///
+    /// The returned [`AnimationController`](crate::AnimationController) keeps its playback state
+    /// across renders, and its controls take effect on the widget's next render. While the
+    /// animation plays, the widget is re-rendered every frame, reading the animation's current
+    /// value from the controller. Changing the options changes the animation from where it is.
+    ///
+    /// Like [`create_state`](Self::create_state), this must be called unconditionally and in the
+    /// same order on every render.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[widget]
+    /// fn MyWidget() {
+    ///   let animation = context.use_animation(AnimationOptions::default()).unwrap();
+    ///   let opacity = animation.value();
+    ///   let on_event = OnEvent::new(move |_, event| {
+    ///     if let EventType::Click(..) = event.event_type {
+    ///       animation.play();
+    ///     }
+    ///   });
+    /// }
+    /// ```
+    pub fn use_animation(
+        &mut self,
+        options: crate::AnimationOptions,
+    ) -> Option<crate::AnimationController> {
+        use crate::Bound;
+
+        let state = self.create_state(crate::animation::AnimationState::default())?;
+        let revision = self.create_state(0u64)?;
+        let controller = crate::AnimationController::new(state.get(), revision);
+        if controller.update(self.frame_time(), &options) {
+            self.request_render();
+        }
+        Some(controller)
+    }
+
+    /// Get an identifier that's unique to this widget instance
+    ///
+    /// The identifier is derived from the widget's index in the tree, so it stays the same across
+    /// every render of the widget for as long as it's mounted, and no other mounted widget shares
+    /// it. Building the same tree in the same order always produces the same identifiers. This
+    /// makes it suitable for linking widgets together, such as a label to the input it describes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[widget]
+    /// fn MyField(props: MyFieldProps) {
+    ///   let input_id = context.use_id();
+    ///   let label_id = format!("{}-label", input_id);
+    /// }
+    /// ```
+    pub fn use_id(&self) -> String {
+        let (index, generation) = self.current_id.unwrap_or_default().into_raw_parts();
+        format!("kayak-{}-{}", index, generation)
+    }
+
+    /// Manage the keyboard navigation of a list, such as a menu or listbox
+    ///
+    /// The returned [`ListNavigation`](crate::ListNavigation) tracks the highlighted item, which
+    /// is moved with the arrow keys, Home/End, and type-ahead search, skipping any disabled items.
+    /// Pressing Enter calls `on_activate` with the index of the highlighted item. The widget is
+    /// re-rendered whenever the highlight moves.
+    ///
+    /// Like [`create_state`](Self::create_state), this must be called unconditionally and in the
+    /// same order on every render.
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The items of the list
+    /// * `wrap`: Whether moving past either end of the list continues from the other end
+    /// * `on_activate`: The function to call when an item is activated
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[widget]
+    /// fn MyMenu(props: MyMenuProps) {
+    ///   let items = props.options.iter().map(|option| ListItem::new(option)).collect();
+    ///   let navigation = context.use_list_navigation(items, true, |index| println!("{}", index));
+    ///   props.on_event = Some(navigation.on_event());
+    /// }
+    /// ```
+    pub fn use_list_navigation<F: FnMut(usize) + Send + Sync + 'static>(
+        &mut self,
+        items: Vec<crate::ListItem>,
+        wrap: bool,
+        on_activate: F,
+    ) -> Option<crate::ListNavigation> {
+        use crate::Bound;
+
+        let highlighted = self.create_state::<Option<usize>>(None)?;
+        let type_ahead = self.create_state(crate::list_navigation::TypeAhead::default())?;
+        Some(crate::ListNavigation::new(
+            items,
+            wrap,
+            highlighted,
+            type_ahead.get(),
+            crate::Handler::new(on_activate),
+        ))
+    }
+
     /// Get the render node for the widget with the given ID
     ///
     /// This is useful if you need access to the resolved styles, z-index, etc. of a widget.
@@ -516,42 +1081,113 @@ impl<'a> KayakContextRef<'a> {
     /// Adds a widget to the context reference tree that will be committed to the main tree when `commit` is called.
     /// This also adds the widget to the `KayakContext` and renders the new widget.
     ///
+    /// If the widget wasn't in the tree before, its [mount handler](crate::OnMount) is called right
+    /// before it's rendered, and it's focused if it [autofocuses](crate::WidgetProps::get_autofocus).
+    /// A widget of another type than the one previously at this index replaces it, and is
+    /// therefore mounted as well.
+    ///
     /// # Arguments
     ///
     /// * `widget`: The widget to add
     /// * `widget_index`: The widget's zero-based index amongst its siblings
     ///
     pub fn add_widget<W: crate::Widget>(&mut self, widget: W, widget_index: usize) {
-        let (_, child_id) =
+        let (is_new, child_id) = self.context.widget_manager.create_widget(
+            self.index_offset + widget_index,
+            widget,
+            self.current_id,
+        );
+        self.render_child(is_new, child_id);
+    }
+
+    /// Adds a widget identified by a key rather than by its index amongst its siblings
+    ///
+    /// A widget added with the same key as during the previous render of its parent is updated
+    /// in place, even if it moved to another index, so reordering keyed widgets preserves their
+    /// state. Keys only need to be unique amongst the widget's siblings.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The key identifying the widget amongst its siblings
+    /// * `widget`: The widget to add
+    ///
+    pub fn add_keyed_widget<W: crate::Widget>(&mut self, key: &str, widget: W) {
+        let (is_new, child_id) =
             self.context
                 .widget_manager
-                .create_widget(widget_index, widget, self.current_id);
+                .create_keyed_widget(key, widget, self.current_id);
+        self.render_child(is_new, child_id);
+    }
+
+    fn render_child(&mut self, is_new: bool, child_id: Index) {
         self.tree.as_ref().unwrap().add(child_id, self.current_id);
 
         let mut child_widget = self.context.widget_manager.take(child_id);
-        {
+        let result = {
             let mut context = KayakContextRef::new(&mut self.context, Some(child_id));
-            // TODO: Use context ref here instead
-            child_widget.render(&mut context);
-        }
+            // The widget is always given back to the widget manager, even if it panics, so that an
+            // error boundary further up can recover from the panic
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                // TODO: Use context ref here instead
+                crate::widget::validate_props(child_widget.as_ref());
+                if is_new {
+                    if child_widget.get_props().get_autofocus() {
+                        context.focus(child_id);
+                    }
+                    if let Some(on_mount) = child_widget.get_props().get_on_mount() {
+                        on_mount.try_call(&mut context);
+                    }
+                }
+                child_widget.render(&mut context);
+            }))
+        };
         self.context.widget_manager.repossess(child_widget);
+        if let Err(payload) = result {
+            panic::resume_unwind(payload);
+        }
+        self.context.widgets_built += 1;
+        // A widget that resumed as its parent rendered it has already caught up
+        self.context.paused_renders.remove(&child_id);
     }
 
     /// Consumes the `KayakContextRef`. Internally this commits the newly built tree to the main widget tree.
     pub fn commit(&mut self) {
         // Consume the widget tree taking the inner value
-        let tree = self.tree.take().unwrap().take();
+        let mut tree = self.tree.take().unwrap().take();
+
+        // A top-level commit only adds the root, whose children were already committed as it
+        // rendered. Diffing against the default index would compare them against the root's own
+        // (empty) children in this tree and erase them.
+        let parent_id = match self.current_id {
+            Some(parent_id) => parent_id,
+            None => return,
+        };
+
+        // Children that deferred their unmount stay in place while they exit, and re-render to
+        // start exiting
+        let exiting = self
+            .context
+            .widget_manager
+            .retain_exiting_children(&mut tree, parent_id);
+        if !exiting.is_empty() {
+            if let Ok(mut dirty_nodes) = self.context.widget_manager.dirty_nodes.lock() {
+                dirty_nodes.extend(exiting);
+            }
+            self.context.request_render();
+        }
 
         // Evaluate changes to the tree.
+        let diff_span = phase_span!("kayak_diff", changes);
         let changes = self
             .context
             .widget_manager
             .tree
-            .diff_children(&tree, self.current_id.unwrap_or_default());
+            .diff_children(&tree, parent_id);
+        diff_span.record("changes", changes.changes.len());
         self.context
             .widget_manager
             .tree
-            .merge(&tree, self.current_id.unwrap_or_default(), changes);
+            .merge(&tree, parent_id, changes);
     }
 
     /// Marks the current widget as dirty (needing to be re-rendered)
@@ -567,6 +1203,182 @@ impl<'a> KayakContextRef<'a> {
             dirty_nodes.insert(self.current_id.unwrap_or_default());
         }
     }
+
+    /// Hides the current widget and its descendants while `inactive`, without unmounting them
+    ///
+    /// Like the children of an [inactive fragment](crate::FragmentProps::inactive), they keep
+    /// their state, but aren't laid out, drawn or focusable. Unlike them, they aren't paused, so
+    /// they keep rendering and running their effects.
+    pub fn set_inactive(&mut self, inactive: bool) {
+        if let Some(id) = self.current_id {
+            self.context
+                .widget_manager
+                .set_inactive(inactive, false, id);
+        }
+    }
+
+    /// Runs the given function once the current widget is unmounted
+    ///
+    /// This is meant for cleaning up after a widget, such as removing it from something it
+    /// registered itself with. Each function is identified by a key, so that rendering the widget
+    /// again replaces its function rather than adding another one. Use
+    /// [`cancel_on_unmount`](Self::cancel_on_unmount) once there's nothing left to clean up.
+    pub fn on_unmount<F: FnOnce() + Send + Sync + 'static>(&mut self, key: &str, f: F) {
+        if let Some(id) = self.current_id {
+            self.context.on_unmount(id, key, Some(Box::new(f)));
+        }
+    }
+
+    /// Removes the function with the given key queued via [`on_unmount`](Self::on_unmount)
+    pub fn cancel_on_unmount(&mut self, key: &str) {
+        if let Some(id) = self.current_id {
+            self.context.on_unmount(id, key, None);
+        }
+    }
+
+    /// Keeps the current widget mounted once its parent stops adding it, until it calls
+    /// [`finish_exit`](Self::finish_exit)
+    ///
+    /// This lets a widget play an exit animation before it's removed. When its parent renders
+    /// without it, the widget stays where it was and renders again, with
+    /// [`is_exiting`](Self::is_exiting) returning true. It should then keep rendering (see
+    /// [`request_render`](Self::request_render)) until its exit completes. If its parent adds it
+    /// back in the meantime, it stops exiting and is updated in place.
+    pub fn defer_unmount(&mut self) {
+        if let Some(id) = self.current_id {
+            self.context.widget_manager.defer_unmount(id);
+        }
+    }
+
+    /// Returns true if the current widget [deferred its unmount](Self::defer_unmount) and its
+    /// parent stopped adding it
+    pub fn is_exiting(&self) -> bool {
+        self.current_id
+            .map_or(false, |id| self.context.widget_manager.is_exiting(id))
+    }
+
+    /// Lets the current widget be unmounted, after it [deferred its unmount](Self::defer_unmount)
+    ///
+    /// If the widget is exiting, it's removed once its parent renders again (which this requests).
+    pub fn finish_exit(&mut self) {
+        let parent = self
+            .current_id
+            .and_then(|id| self.context.widget_manager.finish_exit(id));
+        if let Some(parent) = parent {
+            if let Ok(mut dirty_nodes) = self.context.widget_manager.dirty_nodes.lock() {
+                dirty_nodes.insert(parent);
+            }
+            self.context.request_render();
+        }
+    }
+
+    /// Runs the given function once the current render has finished and been laid out
+    ///
+    /// Widgets shouldn't change state (their own or that of others) while rendering, since the
+    /// changes would only be picked up on the next render. This defers such changes, like scrolling
+    /// or moving to fit content that was just laid out, to right after the render. The function is
+    /// given the context of the current widget, and isn't run if the widget was removed in the
+    /// meantime.
+    ///
+    /// ```ignore
+    /// let widget_id = self.get_id();
+    /// let height = height.clone();
+    /// context.after_render(move |context| {
+    ///     // The layout of the current render is available here
+    ///     height.set(context.get_layout(&widget_id).map_or(0.0, |layout| layout.height));
+    /// });
+    /// ```
+    pub fn after_render<F: FnOnce(&mut KayakContextRef) + Send + Sync + 'static>(&mut self, f: F) {
+        if let Some(id) = self.current_id {
+            self.context.after_render(id, Box::new(f));
+        }
+    }
+
+    /// Requests that the current widget be re-rendered on the next frame
+    ///
+    /// This is meant for animations and transitions, which should call this on every render until
+    /// they complete. This keeps the context dirty (see [`KayakContext::is_dirty`]) so that on-demand
+    /// integrations keep requesting frames, while allowing them to go idle once the animation is done.
+    pub fn request_render(&mut self) {
+        self.mark_dirty();
+        self.context.request_render();
+    }
+
+    /// Shows or hides the [debug overlay](crate::debug_overlay)
+    ///
+    /// See [`KayakContext::set_debug_overlay`] for details.
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.context.set_debug_overlay(enabled);
+    }
+
+    /// Returns true if the [debug overlay](crate::debug_overlay) is drawn above the widget tree
+    pub fn debug_overlay(&self) -> bool {
+        self.context.debug_overlay()
+    }
+
+    /// Requests that the current widget be re-rendered once the given time has passed
+    ///
+    /// See [`KayakContext::request_render_at`] for details.
+    pub fn request_render_at(&mut self, due: std::time::Instant) {
+        self.context
+            .request_render_at(self.current_id.unwrap_or_default(), due);
+    }
+
+    /// Registers a keyboard shortcut for the current widget
+    ///
+    /// See [`KayakContext::register_shortcut`] for details.
+    pub fn register_shortcut(
+        &mut self,
+        shortcut: crate::Shortcut,
+        scope: crate::ShortcutScope,
+        handler: crate::OnShortcut,
+    ) {
+        self.context.register_shortcut(
+            self.current_id.unwrap_or_default(),
+            shortcut,
+            scope,
+            handler,
+        );
+    }
+
+    /// Unregisters a keyboard shortcut previously registered for the current widget
+    ///
+    /// Returns true if the shortcut was registered.
+    pub fn unregister_shortcut(&mut self, shortcut: crate::Shortcut) -> bool {
+        self.context
+            .unregister_shortcut(self.current_id.unwrap_or_default(), shortcut)
+    }
+
+    /// Returns true if the context needs to be rendered
+    ///
+    /// See [`KayakContext::is_dirty`] for details.
+    pub fn is_dirty(&self) -> bool {
+        self.context.is_dirty()
+    }
+
+    /// Returns the time at which the current render started
+    ///
+    /// See [`KayakContext::frame_time`] for details.
+    pub fn frame_time(&self) -> std::time::Instant {
+        self.context.frame_time()
+    }
+
+    /// Returns the metrics of the most recent render, if they're being collected
+    ///
+    /// See [`KayakContext::metrics`] for details.
+    pub fn metrics(&self) -> Option<crate::FrameMetrics> {
+        self.context.metrics()
+    }
+
+    /// Builds the given [`Subtree`](crate::Subtree) and adds it to the children of the given parent
+    ///
+    /// This is meant for event handlers, such as one that opens a panel built elsewhere. While
+    /// rendering, add the subtree's widgets as the current widget's own children instead.
+    ///
+    /// See [`KayakContext::commit_subtree`] for details.
+    pub fn commit_subtree(&mut self, subtree: crate::Subtree, parent: Option<Index>) {
+        self.context.commit_subtree(subtree, parent);
+    }
 }
 
 #[test]
@@ -584,3 +1396,616 @@ fn test_context_ref() {
     let state_value = state.get();
     assert!(state_value == 1.0);
 }
+
+#[test]
+fn test_request_render() {
+    let mut kayak_context = KayakContext::new();
+    assert!(!kayak_context.is_dirty());
+
+    kayak_context.request_render();
+    assert!(kayak_context.is_dirty());
+
+    kayak_context.render();
+    assert!(!kayak_context.is_dirty());
+}
+
+#[test]
+fn test_after_render() {
+    use crate::binding::{Bound, MutableBound};
+
+    let mut kayak_context = KayakContext::new();
+    let widget_id = Index::default();
+    kayak_context.widget_manager.tree.add(widget_id, None);
+
+    let ran = crate::bind(false);
+    let cloned_ran = ran.clone();
+    let mut context = KayakContextRef::new(&mut kayak_context, Some(widget_id));
+    context.after_render(move |context| {
+        assert_eq!(Some(widget_id), context.current_id());
+        cloned_ran.set(true);
+    });
+    assert!(!ran.get());
+
+    // Runs once, after the next render
+    kayak_context.render();
+    assert!(ran.get());
+    ran.set(false);
+    kayak_context.render();
+    assert!(!ran.get());
+}
+
+#[test]
+fn test_on_unmount() {
+    use crate::binding::{Bound, MutableBound};
+
+    let mut kayak_context = KayakContext::new();
+    let root = Index::default();
+    kayak_context.widget_manager.tree.add(root, None);
+    let (child, cancelled) = (Index::from_raw_parts(1, 0), Index::from_raw_parts(2, 0));
+    kayak_context.widget_manager.tree.add(child, Some(root));
+    kayak_context.widget_manager.tree.add(cancelled, Some(root));
+
+    let count = crate::bind(0);
+    for id in [child, cancelled] {
+        let mut context = KayakContextRef::new(&mut kayak_context, Some(id));
+        // Registering again with the same key replaces the previous function
+        for _ in 0..2 {
+            let count = count.clone();
+            context.on_unmount("count", move || count.set(count.get() + 1));
+        }
+    }
+    KayakContextRef::new(&mut kayak_context, Some(cancelled)).cancel_on_unmount("count");
+
+    // Only runs once the widget is removed
+    kayak_context.render();
+    assert_eq!(0, count.get());
+    kayak_context.widget_manager.tree.remove(child);
+    kayak_context.widget_manager.tree.remove(cancelled);
+    kayak_context.render();
+    assert_eq!(1, count.get());
+    kayak_context.render();
+    assert_eq!(1, count.get());
+}
+
+#[test]
+fn test_request_render_at() {
+    use std::time::{Duration, Instant};
+
+    let mut kayak_context = KayakContext::new();
+    // Scheduled renders are dropped for widgets that aren't in the tree
+    kayak_context
+        .widget_manager
+        .tree
+        .add(Index::default(), None);
+    let due = Instant::now() + Duration::from_secs(60);
+    kayak_context.request_render_at(Index::default(), due);
+
+    // Doesn't make the context dirty until it's due
+    assert!(!kayak_context.is_dirty());
+    kayak_context.render();
+    assert!(!kayak_context.is_dirty());
+    assert!(kayak_context.frame_time() < due);
+
+    // The earlier of two scheduled renders is kept
+    kayak_context.request_render_at(Index::default(), Instant::now());
+    assert!(kayak_context.is_dirty());
+}
+
+#[test]
+fn test_metrics() {
+    let mut kayak_context = KayakContext::new();
+    kayak_context.render();
+    assert!(kayak_context.metrics().is_none());
+
+    kayak_context.set_metrics_enabled(true);
+    kayak_context.render();
+    let metrics = kayak_context.metrics().unwrap();
+    assert_eq!(0, metrics.widgets_rendered);
+    assert_eq!(0, metrics.draw_calls);
+    assert_eq!(0, metrics.glyph_cache);
+    // The primitives built for the metrics are kept to be drawn, but not exposed as a draw list
+    assert!(kayak_context.draw_list().is_none());
+
+    kayak_context.set_draw_list_enabled(true);
+    kayak_context.render();
+    assert_eq!(2, kayak_context.draw_list().unwrap().generation);
+    kayak_context.set_metrics_enabled(false);
+    assert!(kayak_context.draw_list().is_some());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_state_snapshot() {
+    use crate::binding::{Bound, MutableBound};
+
+    let mut kayak_context = KayakContext::new();
+    let widget_id = Index::default();
+    kayak_context.widget_manager.tree.add(widget_id, None);
+    kayak_context.register_snapshot_state::<u32>();
+
+    let state = kayak_context.create_state(widget_id, 1u32).unwrap();
+    let snapshot = kayak_context.snapshot_state();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    state.set(2);
+
+    let snapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(1, kayak_context.restore_state(&snapshot).unwrap());
+    assert_eq!(1, state.get());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_state_snapshot_restores_all_or_nothing() {
+    use crate::binding::{Bound, MutableBound};
+
+    let mut kayak_context = KayakContext::new();
+    let widget_id = Index::default();
+    kayak_context.widget_manager.tree.add(widget_id, None);
+    kayak_context.register_snapshot_state::<u32>();
+    kayak_context.register_snapshot_state::<String>();
+
+    let number = kayak_context.create_state(widget_id, 1u32).unwrap();
+    let text = kayak_context
+        .create_state(widget_id, "one".to_string())
+        .unwrap();
+    let snapshot = kayak_context.snapshot_state();
+    number.set(2);
+    text.set("two".to_string());
+
+    // Neither state is restored if either of them can't be
+    for (type_name, value) in [
+        (std::any::type_name::<u32>(), serde_json::json!("one")),
+        (std::any::type_name::<String>(), serde_json::json!(1)),
+    ] {
+        let mut snapshot = snapshot.clone();
+        snapshot.insert("/".to_string(), type_name, vec![value]);
+        assert!(kayak_context.restore_state(&snapshot).is_err());
+        assert_eq!(2, number.get());
+        assert_eq!("two", text.get());
+    }
+
+    assert_eq!(2, kayak_context.restore_state(&snapshot).unwrap());
+    assert_eq!(1, number.get());
+}
+
+#[test]
+fn test_commit_subtree() {
+    use crate::binding::{Bound, MutableBound};
+    use crate::Subtree;
+
+    let mut kayak_context = KayakContext::new();
+    let built = Binding::new(false);
+    let built_clone = built.clone();
+    let subtree = Subtree::new(move |_| built_clone.set(true));
+    assert!(!built.get());
+
+    kayak_context.commit_subtree(subtree, None);
+    assert!(built.get());
+    assert!(kayak_context.is_dirty());
+}
+
+#[test]
+fn test_commit_subtree_appends() {
+    use crate::{Fragment, Subtree};
+
+    let mut kayak_context = KayakContext::new();
+    let root = Subtree::new(|context| context.add_widget(Fragment::default(), 0));
+    kayak_context.commit_subtree(root, None);
+    let root = kayak_context.widget_manager.tree.root_node;
+
+    let fragment = || Subtree::new(|context| context.add_widget(Fragment::default(), 0));
+    kayak_context.commit_subtree(fragment(), root);
+    let first = kayak_context.widget_manager.tree.children[&root.unwrap()].clone();
+    assert_eq!(1, first.len());
+
+    // Committing again keeps the widgets of the first subtree
+    let mut context = KayakContextRef::new(&mut kayak_context, None);
+    context.commit_subtree(fragment(), root);
+    let children = &kayak_context.widget_manager.tree.children[&root.unwrap()];
+    assert_eq!(2, children.len());
+    assert_eq!(first[0], children[0]);
+}
+
+#[test]
+fn test_register_widget() {
+    use crate::{mount_widget, DynamicProps, DynamicWidget, Fragment};
+
+    let mut kayak_context = KayakContext::new();
+    kayak_context.register_widget::<Fragment>("test:Fragment");
+    assert!(kayak_context.is_widget_registered("test:Fragment"));
+    assert!(!kayak_context.is_widget_registered("test:Missing"));
+
+    let mut props = DynamicProps::new("test:Fragment");
+    props.set("label", String::from("Hello"));
+    assert_eq!(Some(&String::from("Hello")), props.get::<String>("label"));
+    assert_eq!(None, props.get::<u32>("label"));
+    mount_widget(&mut kayak_context, DynamicWidget::new(props));
+
+    // The dynamic widget along with the widget it resolved to
+    assert_eq!(2, kayak_context.widget_manager.tree.len());
+}
+
+#[test]
+#[should_panic(expected = "test:Missing")]
+fn test_unregistered_widget() {
+    use crate::{mount_widget, DynamicProps, DynamicWidget};
+
+    let mut kayak_context = KayakContext::new();
+    mount_widget(
+        &mut kayak_context,
+        DynamicWidget::new(DynamicProps::new("test:Missing")),
+    );
+}
+
+#[cfg(test)]
+mod autofocus {
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{
+        mount_widget, Binding, Bound, EventType, Fragment, Index, KayakContext, MutableBound,
+        OnEvent, Widget,
+    };
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct InputProps {
+        #[prop_field(Focusable)]
+        focusable: Option<bool>,
+        #[prop_field(Autofocus)]
+        autofocus: bool,
+        #[prop_field(OnEvent)]
+        on_event: Option<OnEvent>,
+    }
+
+    #[widget]
+    fn Input(props: InputProps) {
+        context.add_widget(Fragment::default(), 0);
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct FormProps {
+        /// Whether each input autofocuses, in order
+        autofocus: Binding<Vec<bool>>,
+        on_event: Option<OnEvent>,
+    }
+
+    #[widget]
+    fn Form(props: FormProps) {
+        context.bind(&props.autofocus);
+        for (index, autofocus) in props.autofocus.get().into_iter().enumerate() {
+            let input = Input::constructor(InputProps {
+                focusable: Some(true),
+                autofocus,
+                on_event: props.on_event.clone(),
+            });
+            context.add_widget(input, index);
+        }
+    }
+
+    /// Mounts a `Form` as the root, returning the IDs of its inputs
+    fn mount(context: &mut KayakContext, props: FormProps) -> Vec<Index> {
+        mount_widget(context, Form::constructor(props));
+        let tree = &context.widget_manager.tree;
+        tree.children[&tree.root_node.unwrap()].clone()
+    }
+
+    #[test]
+    fn should_focus_the_last_autofocused_widget_on_mount() {
+        let mut context = KayakContext::new();
+        let inputs = mount(
+            &mut context,
+            FormProps {
+                autofocus: Binding::new(vec![true, false, true, false]),
+                ..Default::default()
+            },
+        );
+        assert_eq!(Some(inputs[2]), context.current_focus());
+    }
+
+    #[test]
+    fn should_not_render_again_to_autofocus() {
+        let mut context = KayakContext::new();
+        let inputs = mount(
+            &mut context,
+            FormProps {
+                autofocus: Binding::new(vec![true]),
+                ..Default::default()
+            },
+        );
+        assert_eq!(Some(inputs[0]), context.current_focus());
+        assert!(!context.is_dirty());
+    }
+
+    #[test]
+    fn should_only_autofocus_once_mounted() {
+        let mut context = KayakContext::new();
+        let autofocus = Binding::new(vec![true, false]);
+        let inputs = mount(
+            &mut context,
+            FormProps {
+                autofocus: autofocus.clone(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(Some(inputs[0]), context.current_focus());
+
+        // Re-rendering an existing widget that now autofocuses doesn't move the focus
+        autofocus.set(vec![false, true]);
+        context.render();
+        assert_eq!(Some(inputs[0]), context.current_focus());
+    }
+
+    #[test]
+    fn should_send_a_focus_event() {
+        let mut context = KayakContext::new();
+        let focused = Binding::new(Vec::new());
+        let on_focus = focused.clone();
+        let on_event = OnEvent::new(move |_, event| {
+            if let EventType::Focus = event.event_type {
+                let mut focused = on_focus.get();
+                focused.push(event.target);
+                on_focus.set(focused);
+            }
+        });
+        let inputs = mount(
+            &mut context,
+            FormProps {
+                autofocus: Binding::new(vec![false, true]),
+                on_event: Some(on_event),
+            },
+        );
+        assert_eq!(vec![inputs[1]], focused.get());
+    }
+
+    #[test]
+    fn should_focus_outside_of_a_render() {
+        let mut context = KayakContext::new();
+        let inputs = mount(
+            &mut context,
+            FormProps {
+                autofocus: Binding::new(vec![false, false]),
+                ..Default::default()
+            },
+        );
+        assert_ne!(Some(inputs[1]), context.current_focus());
+
+        // Focusing from outside of a render requests one to apply it
+        context.focus(inputs[1]);
+        assert!(context.is_dirty());
+        context.render();
+        assert_eq!(Some(inputs[1]), context.current_focus());
+    }
+}
+
+#[cfg(test)]
+mod on_mount {
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{mount_widget, Binding, Bound, KayakContext, MutableBound, OnMount, Widget};
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct MountedProps {
+        #[prop_field(OnMount)]
+        on_mount: Option<OnMount>,
+    }
+
+    #[widget]
+    fn Label(props: MountedProps) {}
+
+    #[widget]
+    fn Button(props: MountedProps) {}
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct SwitchProps {
+        /// Whether a `Button` is rendered rather than a `Label`
+        button: Binding<bool>,
+        /// The names of the widgets mounted so far, in order
+        mounted: Binding<Vec<&'static str>>,
+    }
+
+    #[widget]
+    fn Switch(props: SwitchProps) {
+        context.bind(&props.button);
+        let button = props.button.get();
+        let mounted = props.mounted.clone();
+        let name = if button { "button" } else { "label" };
+        let on_mount = Some(OnMount::new(move |_| {
+            let mut names = mounted.get();
+            names.push(name);
+            mounted.set(names);
+        }));
+        if button {
+            context.add_widget(Button::constructor(MountedProps { on_mount }), 0);
+        } else {
+            context.add_widget(Label::constructor(MountedProps { on_mount }), 0);
+        }
+    }
+
+    #[test]
+    fn should_only_call_on_mount_once() {
+        let mut context = KayakContext::new();
+        let button = Binding::new(false);
+        let mounted = Binding::new(Vec::new());
+        let props = SwitchProps {
+            button: button.clone(),
+            mounted: mounted.clone(),
+        };
+        mount_widget(&mut context, Switch::constructor(props));
+        assert_eq!(vec!["label"], mounted.get());
+
+        // Re-rendering the same widget doesn't mount it again
+        let tree = &context.widget_manager.tree;
+        let switch = tree.root_node.unwrap();
+        context
+            .widget_manager
+            .dirty_nodes
+            .lock()
+            .unwrap()
+            .insert(switch);
+        context.render();
+        assert_eq!(vec!["label"], mounted.get());
+    }
+
+    #[test]
+    fn should_mount_a_widget_replacing_another_type() {
+        let mut context = KayakContext::new();
+        let button = Binding::new(false);
+        let mounted = Binding::new(Vec::new());
+        let props = SwitchProps {
+            button: button.clone(),
+            mounted: mounted.clone(),
+        };
+        mount_widget(&mut context, Switch::constructor(props));
+        let tree = &context.widget_manager.tree;
+        let switch = tree.root_node.unwrap();
+        let label = tree.children[&switch][0];
+
+        button.set(true);
+        context.render();
+        assert_eq!(vec!["label", "button"], mounted.get());
+
+        // The button doesn't take over the label's ID (and therefore its state)
+        let tree = &context.widget_manager.tree;
+        assert_eq!(1, tree.children[&switch].len());
+        assert_ne!(label, tree.children[&switch][0]);
+
+        button.set(false);
+        context.render();
+        assert_eq!(vec!["label", "button", "label"], mounted.get());
+    }
+}
+
+#[cfg(test)]
+mod theme {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{mount_widget, KayakContext, Theme, Widget};
+
+    /// The number of times a widget was rendered
+    #[derive(Clone, Debug, Default)]
+    struct Renders(Arc<AtomicUsize>);
+
+    impl PartialEq for Renders {
+        fn eq(&self, other: &Self) -> bool {
+            Arc::ptr_eq(&self.0, &other.0)
+        }
+    }
+
+    impl Renders {
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct CountedProps {
+        renders: Renders,
+    }
+
+    #[widget]
+    fn Themed(props: CountedProps) {
+        props.renders.0.fetch_add(1, Ordering::SeqCst);
+        context.use_theme();
+    }
+
+    #[widget]
+    fn Plain(props: CountedProps) {
+        props.renders.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct SiblingsProps {
+        themed: Renders,
+        plain: Renders,
+    }
+
+    #[widget]
+    fn Siblings(props: SiblingsProps) {
+        let themed = CountedProps {
+            renders: props.themed.clone(),
+        };
+        let plain = CountedProps {
+            renders: props.plain.clone(),
+        };
+        context.add_widget(Themed::constructor(themed), 0);
+        context.add_widget(Plain::constructor(plain), 1);
+    }
+
+    #[test]
+    fn should_only_re_render_subscribers_when_the_theme_changes() {
+        let mut context = KayakContext::new();
+        let props = SiblingsProps::default();
+        let (themed, plain) = (props.themed.clone(), props.plain.clone());
+        mount_widget(&mut context, Siblings::constructor(props));
+        let (themed_renders, plain_renders) = (themed.count(), plain.count());
+        assert!(themed_renders > 0 && plain_renders > 0);
+
+        context.set_theme(Theme::light());
+        context.render();
+        assert_eq!(themed_renders + 1, themed.count());
+        assert_eq!(plain_renders, plain.count());
+    }
+}
+
+#[cfg(test)]
+mod use_id {
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{mount_widget, Binding, Bound, KayakContext, MutableBound, Widget};
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct FieldProps {
+        /// Set to re-render the field
+        revision: Binding<u32>,
+        /// The ID of the field on each of its renders
+        ids: Binding<Vec<String>>,
+    }
+
+    #[widget]
+    fn Field(props: FieldProps) {
+        context.bind(&props.revision);
+        let mut ids = props.ids.get();
+        ids.push(context.use_id());
+        props.ids.set(ids);
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct FormProps {
+        revision: Binding<u32>,
+        first: Binding<Vec<String>>,
+        second: Binding<Vec<String>>,
+    }
+
+    #[widget]
+    fn Form(props: FormProps) {
+        for (index, ids) in [&props.first, &props.second].into_iter().enumerate() {
+            let field = Field::constructor(FieldProps {
+                revision: props.revision.clone(),
+                ids: ids.clone(),
+            });
+            context.add_widget(field, index);
+        }
+    }
+
+    #[test]
+    fn should_keep_ids_across_renders_and_distinct_between_instances() {
+        let mut context = KayakContext::new();
+        let props = FormProps::default();
+        let (revision, first, second) = (
+            props.revision.clone(),
+            props.first.clone(),
+            props.second.clone(),
+        );
+        mount_widget(&mut context, Form::constructor(props));
+        revision.set(1);
+        context.render();
+
+        let (first, second) = (first.get(), second.get());
+        assert!(first.len() >= 2 && second.len() >= 2);
+        assert!(first.iter().all(|id| *id == first[0]));
+        assert!(second.iter().all(|id| *id == second[0]));
+        assert_ne!(first[0], second[0]);
+    }
+}
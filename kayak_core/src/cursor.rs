@@ -1,13 +1,35 @@
 /// Controls how the cursor interacts on a given node
+///
+/// Nodes that don't receive pointer events are transparent to them: the events (and hit tests)
+/// fall through to whatever is behind the node instead.
+///
+/// Note that a node's children can only _further_ restrict pointer events. A child setting
+/// [`All`](Self::All) cannot re-enable pointer events when an ancestor uses [`None`](Self::None)
+/// or [`SelfOnly`](Self::SelfOnly), since the whole subtree is skipped. To make an overlay that's
+/// transparent itself but has interactive children, use [`ChildrenOnly`](Self::ChildrenOnly) instead.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum PointerEvents {
-    /// Allow all pointer events on this node and its children
+    /// Allow all pointer events on this node and its children (the default)
     All,
     /// Allow pointer events on this node but not on its children
     SelfOnly,
     /// Allow pointer events on this node's children but not on itself
+    ///
+    /// This is useful for non-interactive overlay layers that contain interactive widgets.
     ChildrenOnly,
     /// Disallow all pointer events on this node and its children
+    ///
+    /// This is useful for purely decorative overlays that should never intercept the cursor.
     None,
 }
 
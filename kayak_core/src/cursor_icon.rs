@@ -1,4 +1,14 @@
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum CursorIcon {
     Default,
     Crosshair,
@@ -0,0 +1,364 @@
+//! Cubic bezier curves and arrows, such as for the connections of node editors
+//!
+//! Curves are drawn by widgets with a [`RenderCommand::Curves`](crate::render_command::RenderCommand::Curves)
+//! render command. Their points are relative to the top-left corner of the widget drawing them,
+//! in its untransformed space, so they move and scale along with the widget (such as within a
+//! zoomed viewport).
+//!
+//! Renderers draw each curve as a series of short, anti-aliased [`LineSegment`]s with round ends,
+//! which meet at their round ends to form smooth joints. All of a widget's curves are drawn as a
+//! single batch.
+
+use crate::color::Color;
+
+/// The most segments a single curve is split into when drawn
+const MAX_SEGMENTS: usize = 64;
+
+/// The furthest (in pixels) a drawn curve strays from the true curve
+const TOLERANCE: f32 = 0.25;
+
+/// The shortest distance (in pixels) from an end of a [`Bezier::between`] curve to its control point
+const MIN_CONTROL_DISTANCE: f32 = 30.0;
+
+/// A cubic bezier curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bezier {
+    /// The point the curve starts at
+    pub start: (f32, f32),
+    /// The control point the curve leaves its start towards
+    pub start_control: (f32, f32),
+    /// The control point the curve arrives at its end from
+    pub end_control: (f32, f32),
+    /// The point the curve ends at
+    pub end: (f32, f32),
+}
+
+impl Bezier {
+    /// Creates a curve from its points
+    pub fn new(
+        start: (f32, f32),
+        start_control: (f32, f32),
+        end_control: (f32, f32),
+        end: (f32, f32),
+    ) -> Self {
+        Self {
+            start,
+            start_control,
+            end_control,
+            end,
+        }
+    }
+
+    /// Creates a straight line between two points
+    pub fn line(start: (f32, f32), end: (f32, f32)) -> Self {
+        let third = scale(sub(end, start), 1.0 / 3.0);
+        Self::new(start, add(start, third), sub(end, third), end)
+    }
+
+    /// Creates a curve that leaves the start point and arrives at the end point in the given
+    /// directions, such as for a connection between two ports of a node editor
+    ///
+    /// Each direction points _out_ of its port, so an output port on the right side of a node has
+    /// a direction of `(1.0, 0.0)`, while an input port on the left side of another node has a
+    /// direction of `(-1.0, 0.0)`. The directions don't need to be normalized. The further apart the
+    /// points are, the further the curve bows out in those directions.
+    pub fn between(
+        start: (f32, f32),
+        start_direction: (f32, f32),
+        end: (f32, f32),
+        end_direction: (f32, f32),
+    ) -> Self {
+        let distance = length(sub(end, start));
+        let control_distance = (distance / 2.0).max(MIN_CONTROL_DISTANCE);
+        Self::new(
+            start,
+            add(start, scale(normalize(start_direction), control_distance)),
+            add(end, scale(normalize(end_direction), control_distance)),
+            end,
+        )
+    }
+
+    /// Returns the point on the curve at the given progress, from `0.0` (the start) to `1.0`
+    /// (the end)
+    pub fn point_at(&self, t: f32) -> (f32, f32) {
+        let u = 1.0 - t;
+        let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+        self.weighted(weights)
+    }
+
+    /// Returns the direction the curve is heading in at the given progress (not normalized)
+    pub fn tangent_at(&self, t: f32) -> (f32, f32) {
+        let u = 1.0 - t;
+        let weights = [
+            -3.0 * u * u,
+            3.0 * u * u - 6.0 * u * t,
+            6.0 * u * t - 3.0 * t * t,
+            3.0 * t * t,
+        ];
+        let tangent = self.weighted(weights);
+        if tangent == (0.0, 0.0) {
+            // The control points sit on the ends, so head straight from one end to the other
+            sub(self.end, self.start)
+        } else {
+            tangent
+        }
+    }
+
+    /// Returns points along the curve, from its start to its end, such that the straight lines
+    /// between them never stray from the curve by more than `tolerance` pixels
+    pub fn flatten(&self, tolerance: f32) -> Vec<(f32, f32)> {
+        // The distance between a cubic curve and its uniform subdivision into n lines is at most
+        // 3/4 of the larger of its control polygon's second differences, divided by n squared
+        let second_difference = length(add(
+            sub(self.start, scale(self.start_control, 2.0)),
+            self.end_control,
+        ))
+        .max(length(add(
+            sub(self.start_control, scale(self.end_control, 2.0)),
+            self.end,
+        )));
+        let segments = (0.75 * second_difference / tolerance.max(f32::EPSILON))
+            .sqrt()
+            .ceil()
+            .clamp(1.0, MAX_SEGMENTS as f32) as usize;
+
+        (0..=segments)
+            .map(|segment| self.point_at(segment as f32 / segments as f32))
+            .collect()
+    }
+
+    /// Returns the sum of this curve's points, weighted by the given weights
+    fn weighted(&self, weights: [f32; 4]) -> (f32, f32) {
+        let points = [self.start, self.start_control, self.end_control, self.end];
+        points
+            .iter()
+            .zip(weights)
+            .fold((0.0, 0.0), |sum, (point, weight)| {
+                add(sum, scale(*point, weight))
+            })
+    }
+
+    /// Returns this curve with each of its points mapped by the given function
+    pub(crate) fn map(&self, f: impl Fn((f32, f32)) -> (f32, f32)) -> Self {
+        Self::new(
+            f(self.start),
+            f(self.start_control),
+            f(self.end_control),
+            f(self.end),
+        )
+    }
+}
+
+/// An open arrowhead drawn at the end of a [`Curve`], pointing in the direction the curve ends in
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arrow {
+    /// How far the arrowhead reaches back along the curve, in pixels
+    pub length: f32,
+    /// How wide the arrowhead is at its base, in pixels
+    pub width: f32,
+}
+
+impl Default for Arrow {
+    fn default() -> Self {
+        Self {
+            length: 10.0,
+            width: 10.0,
+        }
+    }
+}
+
+/// A stroked cubic bezier curve, optionally ending in an arrow
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Curve {
+    /// The path of the curve
+    pub bezier: Bezier,
+    /// The width of the stroke, in pixels
+    pub width: f32,
+    /// The color of the stroke (and arrowhead)
+    pub color: Color,
+    /// The arrowhead drawn at the end of the curve, if any
+    pub arrow: Option<Arrow>,
+}
+
+impl Curve {
+    /// Creates a curve along the given path, without an arrowhead
+    pub fn new(bezier: Bezier, width: f32, color: Color) -> Self {
+        Self {
+            bezier,
+            width,
+            color,
+            arrow: None,
+        }
+    }
+
+    /// Returns this curve with an arrowhead at its end
+    pub fn with_arrow(self, arrow: Arrow) -> Self {
+        Self {
+            arrow: Some(arrow),
+            ..self
+        }
+    }
+
+    /// Returns the line segments this curve is drawn with, including those of its arrowhead
+    ///
+    /// The segments are positioned relative to the same point as the curve. Consecutive segments
+    /// of the curve (and of its arrowhead) meet end to start, forming a joint there.
+    pub fn segments(&self) -> Vec<LineSegment> {
+        if self.width <= 0.0 || self.color.a <= 0.0 {
+            return Vec::new();
+        }
+
+        let segment = |start, end| LineSegment {
+            start,
+            end,
+            width: self.width,
+            color: self.color,
+        };
+        let points = self.bezier.flatten(TOLERANCE);
+        let mut segments: Vec<_> = points
+            .windows(2)
+            .map(|points| segment(points[0], points[1]))
+            .collect();
+
+        if let Some(arrow) = self.arrow {
+            let tip = self.bezier.end;
+            let back = scale(normalize(self.bezier.tangent_at(1.0)), -arrow.length);
+            let side = scale(normalize((-back.1, back.0)), arrow.width / 2.0);
+            let base = add(tip, back);
+            // The two sides of the arrowhead are joined at its tip
+            segments.push(segment(add(base, side), tip));
+            segments.push(segment(tip, sub(base, side)));
+        }
+        segments
+    }
+
+    /// Returns this curve with its points mapped by the given function and its width scaled
+    pub(crate) fn transformed(
+        &self,
+        f: impl Fn((f32, f32)) -> (f32, f32),
+        width_scale: f32,
+    ) -> Self {
+        Self {
+            bezier: self.bezier.map(f),
+            width: self.width * width_scale,
+            arrow: self.arrow.map(|arrow| Arrow {
+                length: arrow.length * width_scale,
+                width: arrow.width * width_scale,
+            }),
+            ..*self
+        }
+    }
+}
+
+/// A straight, anti-aliased line with round ends, making up part of a drawn [`Curve`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineSegment {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    /// The width of the line, in pixels
+    pub width: f32,
+    pub color: Color,
+}
+
+impl LineSegment {
+    /// Returns this line, moved by the given offset
+    pub fn translated(&self, offset: (f32, f32)) -> Self {
+        Self {
+            start: add(self.start, offset),
+            end: add(self.end, offset),
+            ..*self
+        }
+    }
+}
+
+fn add(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn sub(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn scale(a: (f32, f32), factor: f32) -> (f32, f32) {
+    (a.0 * factor, a.1 * factor)
+}
+
+fn length(a: (f32, f32)) -> f32 {
+    (a.0 * a.0 + a.1 * a.1).sqrt()
+}
+
+/// Returns the given vector with a length of one, or the zero vector if it has no length
+fn normalize(a: (f32, f32)) -> (f32, f32) {
+    let length = length(a);
+    if length > 0.0 {
+        scale(a, 1.0 / length)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arrow, Bezier, Curve};
+    use crate::color::Color;
+
+    #[test]
+    fn between_should_leave_and_arrive_in_the_given_directions() {
+        let bezier = Bezier::between((0.0, 0.0), (1.0, 0.0), (200.0, 100.0), (-2.0, 0.0));
+        assert_eq!((0.0, 0.0), bezier.point_at(0.0));
+        assert_eq!((200.0, 100.0), bezier.point_at(1.0));
+
+        let start = bezier.tangent_at(0.0);
+        assert!(start.0 > 0.0 && start.1 == 0.0);
+        // The curve arrives heading against the end's direction, into the port
+        let end = bezier.tangent_at(1.0);
+        assert!(end.0 > 0.0 && end.1 == 0.0);
+
+        // Points that are close together still get a visible bow
+        let close = Bezier::between((0.0, 0.0), (1.0, 0.0), (10.0, 0.0), (-1.0, 0.0));
+        assert_eq!((30.0, 0.0), close.start_control);
+        assert_eq!((-20.0, 0.0), close.end_control);
+    }
+
+    #[test]
+    fn flattened_curves_should_stay_close_to_the_curve() {
+        let bezier = Bezier::between((0.0, 0.0), (1.0, 0.0), (300.0, 200.0), (-1.0, 0.0));
+        let points = bezier.flatten(0.25);
+        assert!(points.len() > 2);
+        assert_eq!(Some(&bezier.start), points.first());
+        assert_eq!(Some(&bezier.end), points.last());
+
+        // The middle of each line is close to the curve
+        let segments = (points.len() - 1) as f32;
+        for (index, line) in points.windows(2).enumerate() {
+            let middle = ((line[0].0 + line[1].0) / 2.0, (line[0].1 + line[1].1) / 2.0);
+            let curve = bezier.point_at((index as f32 + 0.5) / segments);
+            let distance = ((middle.0 - curve.0).powi(2) + (middle.1 - curve.1).powi(2)).sqrt();
+            assert!(distance <= 0.25, "{} is too far from the curve", distance);
+        }
+
+        // Straight lines need a single segment
+        assert_eq!(
+            2,
+            Bezier::line((0.0, 0.0), (100.0, 0.0)).flatten(0.25).len()
+        );
+    }
+
+    #[test]
+    fn arrows_should_point_along_the_end_of_the_curve() {
+        let curve = Curve::new(Bezier::line((0.0, 0.0), (100.0, 0.0)), 2.0, Color::WHITE)
+            .with_arrow(Arrow {
+                length: 10.0,
+                width: 8.0,
+            });
+        let segments = curve.segments();
+        assert_eq!(3, segments.len());
+        assert_eq!((90.0, -4.0), segments[1].start);
+        assert_eq!((100.0, 0.0), segments[1].end);
+        assert_eq!((100.0, 0.0), segments[2].start);
+        assert_eq!((90.0, 4.0), segments[2].end);
+    }
+}
@@ -0,0 +1,172 @@
+//! A debug overlay that visualizes the layout of every widget
+//!
+//! When enabled (see [`KayakContext::set_debug_overlay`](crate::KayakContext::set_debug_overlay)),
+//! the overlay is drawn above everything else, including portals. For each widget with a layout it
+//! draws:
+//!
+//! * The outline of its layout rect, in [`LAYOUT_COLOR`]
+//! * Its padding, in [`PADDING_COLOR`]
+//! * The space around it (its `left`, `right`, `top` and `bottom`), in [`MARGIN_COLOR`]
+//! * A dashed outline of any clip region it creates, in [`CLIP_COLOR`]
+//!
+//! Only spacing in pixels or percentages is shown, since stretched spacing depends on the
+//! widget's siblings. The overlay is made of plain render primitives, so it isn't part of the
+//! widget tree and never receives pointer events.
+
+use morphorm::Node as _;
+
+use crate::border;
+use crate::color::Color;
+use crate::layout_cache::{LayoutCache, Rect};
+use crate::node::Node;
+use crate::render_primitive::RenderPrimitive;
+use crate::styles::{BorderStyle, Corner, Edge};
+use crate::tree::Tree;
+use crate::Arena;
+
+/// The color of the outline drawn around each layout rect
+pub const LAYOUT_COLOR: Color = Color {
+    r: 0.0,
+    g: 0.8,
+    b: 1.0,
+    a: 0.8,
+};
+
+/// The color of the padding within each layout rect
+pub const PADDING_COLOR: Color = Color {
+    r: 0.4,
+    g: 0.8,
+    b: 0.3,
+    a: 0.35,
+};
+
+/// The color of the space around each layout rect
+pub const MARGIN_COLOR: Color = Color {
+    r: 1.0,
+    g: 0.6,
+    b: 0.2,
+    a: 0.35,
+};
+
+/// The color of the outline drawn around each clip region
+pub const CLIP_COLOR: Color = Color {
+    r: 1.0,
+    g: 0.2,
+    b: 0.3,
+    a: 0.9,
+};
+
+/// Builds the primitives making up the overlay, drawn above the given z-index
+pub(crate) fn build_primitives(
+    node_tree: &Tree,
+    layout_cache: &LayoutCache,
+    nodes: &Arena<Option<Node>>,
+    z_index: f32,
+) -> Vec<RenderPrimitive> {
+    let root = match node_tree.root_node {
+        Some(root) => root,
+        None => return Vec::new(),
+    };
+    let root_layout = match layout_cache.rect.get(&root) {
+        Some(layout) => Rect { z_index, ..*layout },
+        None => return Vec::new(),
+    };
+
+    // The overlay is drawn outside of any clip
+    let mut primitives = vec![RenderPrimitive::Clip {
+        layout: root_layout,
+        mask: None,
+    }];
+
+    let mut push_sides = |layout: &Rect, sides: Edge<f32>, color: Color, style: BorderStyle| {
+        let quads = border::side_quads(layout, &sides, &Edge::all(color), &Edge::all(style));
+        primitives.extend(quads.into_iter().map(|quad| RenderPrimitive::Quad {
+            layout: quad.layout,
+            background_color: quad.color,
+            border_color: quad.color,
+            border_colors: Edge::all(quad.color),
+            border: Edge::all(0.0),
+            border_radius: Corner::default(),
+            border_style: Edge::all(BorderStyle::Solid),
+        }));
+    };
+
+    for id in node_tree.flatten() {
+        let layout = match layout_cache.rect.get(&id) {
+            Some(layout) => Rect { z_index, ..*layout },
+            None => continue,
+        };
+        let parent_size = node_tree
+            .get_parent(id)
+            .and_then(|parent| layout_cache.rect.get(&parent))
+            .map_or((layout.width, layout.height), |parent| {
+                (parent.width, parent.height)
+            });
+
+        let margin = Edge::new(
+            to_pixels(id.top(nodes), parent_size.1),
+            to_pixels(id.right(nodes), parent_size.0),
+            to_pixels(id.bottom(nodes), parent_size.1),
+            to_pixels(id.left(nodes), parent_size.0),
+        );
+        let outer = Rect {
+            posx: layout.posx - margin.left,
+            posy: layout.posy - margin.top,
+            width: layout.width + margin.left + margin.right,
+            height: layout.height + margin.top + margin.bottom,
+            z_index,
+        };
+        push_sides(&outer, margin, MARGIN_COLOR, BorderStyle::Solid);
+
+        let padding = Edge::new(
+            to_pixels(id.child_top(nodes), layout.height),
+            to_pixels(id.child_right(nodes), layout.width),
+            to_pixels(id.child_bottom(nodes), layout.height),
+            to_pixels(id.child_left(nodes), layout.width),
+        );
+        push_sides(&layout, padding, PADDING_COLOR, BorderStyle::Solid);
+
+        push_sides(&layout, Edge::all(1.0), LAYOUT_COLOR, BorderStyle::Solid);
+
+        let is_clip = matches!(
+            nodes.get(id),
+            Some(Some(Node {
+                primitive: RenderPrimitive::Clip { .. },
+                ..
+            }))
+        );
+        if is_clip {
+            push_sides(&layout, Edge::all(1.0), CLIP_COLOR, BorderStyle::Dashed);
+        }
+    }
+
+    primitives
+}
+
+/// Converts the given spacing into pixels, where percentages are relative to the given size
+///
+/// Spacing that can't be known ahead of layout (such as stretch) is treated as zero.
+fn to_pixels(units: Option<morphorm::Units>, size: f32) -> f32 {
+    match units {
+        Some(morphorm::Units::Pixels(value)) => value.max(0.0),
+        Some(morphorm::Units::Percentage(value)) => (value / 100.0 * size).max(0.0),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_pixels;
+
+    #[test]
+    fn should_only_show_known_spacing() {
+        assert_eq!(5.0, to_pixels(Some(morphorm::Units::Pixels(5.0)), 200.0));
+        assert_eq!(
+            50.0,
+            to_pixels(Some(morphorm::Units::Percentage(25.0)), 200.0)
+        );
+        assert_eq!(0.0, to_pixels(Some(morphorm::Units::Stretch(1.0)), 200.0));
+        assert_eq!(0.0, to_pixels(Some(morphorm::Units::Auto), 200.0));
+        assert_eq!(0.0, to_pixels(None, 200.0));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::layout_cache::Rect;
+
+/// Constraints applied to the position of a dragged element, such as a window
+///
+/// Both constraints are opt-in: by default, positions are left as they are.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DragConstraints {
+    /// The size of the grid (in pixels) that positions snap to
+    ///
+    /// A size of zero (or less) along an axis doesn't snap along that axis.
+    pub snap: Option<(f32, f32)>,
+    /// The rect the dragged element must stay within
+    ///
+    /// Elements larger than the rect are aligned to its top-left corner.
+    pub bounds: Option<Rect>,
+}
+
+impl DragConstraints {
+    /// Constraints that snap positions to a grid of the given size
+    pub fn snap(width: f32, height: f32) -> Self {
+        Self {
+            snap: Some((width, height)),
+            bounds: None,
+        }
+    }
+
+    /// Keep the dragged element within the given rect
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Applies these constraints to the position of a dragged element
+    ///
+    /// Positions are snapped to the grid first and then moved within the bounds, so an element
+    /// pushed against the edge of its bounds may be placed off the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The unconstrained position of the element (its top-left corner)
+    /// * `size`: The size of the element
+    ///
+    pub fn apply(&self, position: (f32, f32), size: (f32, f32)) -> DragPosition {
+        let mut snapped = position;
+        if let Some((width, height)) = self.snap {
+            snapped = (snap_to(snapped.0, width), snap_to(snapped.1, height));
+        }
+
+        if let Some(bounds) = self.bounds {
+            let max_x = (bounds.posx + bounds.width - size.0).max(bounds.posx);
+            let max_y = (bounds.posy + bounds.height - size.1).max(bounds.posy);
+            snapped = (
+                snapped.0.clamp(bounds.posx, max_x),
+                snapped.1.clamp(bounds.posy, max_y),
+            );
+        }
+
+        DragPosition {
+            raw: position,
+            snapped,
+        }
+    }
+}
+
+/// The position of a dragged element, before and after applying its [`DragConstraints`]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DragPosition {
+    /// The position following the cursor, without any constraints
+    pub raw: (f32, f32),
+    /// The position snapped to the grid and kept within the bounds
+    ///
+    /// This is the position the element is actually moved to.
+    pub snapped: (f32, f32),
+}
+
+fn snap_to(value: f32, step: f32) -> f32 {
+    if step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DragConstraints;
+    use crate::layout_cache::Rect;
+
+    #[test]
+    fn should_snap_to_grid() {
+        let constraints = DragConstraints::snap(10.0, 0.0);
+        let position = constraints.apply((14.0, 17.0), (50.0, 50.0));
+
+        assert_eq!((14.0, 17.0), position.raw);
+        assert_eq!((10.0, 17.0), position.snapped);
+        assert_eq!(
+            (20.0, 17.0),
+            constraints.apply((15.0, 17.0), (50.0, 50.0)).snapped
+        );
+    }
+
+    #[test]
+    fn should_stay_within_bounds() {
+        let bounds = Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: 100.0,
+            height: 100.0,
+            z_index: 0.0,
+        };
+        let constraints = DragConstraints::snap(8.0, 8.0).with_bounds(bounds);
+
+        assert_eq!(
+            (60.0, 0.0),
+            constraints.apply((75.0, -10.0), (40.0, 20.0)).snapped
+        );
+        // Elements larger than the bounds are aligned to their top-left corner
+        assert_eq!(
+            (0.0, 0.0),
+            constraints.apply((20.0, 20.0), (200.0, 200.0)).snapped
+        );
+        // Without constraints, positions are left as they are
+        assert_eq!(
+            (3.5, -2.0),
+            DragConstraints::default()
+                .apply((3.5, -2.0), (10.0, 10.0))
+                .snapped
+        );
+    }
+}
@@ -0,0 +1,635 @@
+//! A portable list of draw commands, for rendering the UI with a custom backend
+//!
+//! When enabled (see [`KayakContext::set_draw_list_enabled`](crate::KayakContext::set_draw_list_enabled)),
+//! the context rebuilds its [`DrawList`] at the end of every render and keeps it until the next
+//! one. A backend can read it at any time with [`KayakContext::draw_list`](crate::KayakContext::draw_list),
+//! and can compare its [`generation`](DrawList::generation) with the last one it drew to skip
+//! frames in which nothing changed.
+//!
+//! # Coordinate space
+//!
+//! All positions and sizes are in logical pixels, relative to the top-left corner of the UI, with
+//! the x-axis pointing right and the y-axis pointing down. Backends are expected to multiply them
+//! by the scale factor of their surface themselves.
+//!
+//! Each [`Rect`] also carries the layer of its command in [`z_index`](Rect::z_index), where higher
+//! indices are drawn above lower ones. The commands are already listed in drawing order though, so
+//! backends that draw them in order (painter's algorithm) can ignore it.
+//!
+//! # Clipping
+//!
+//! A [`DrawCommand::Clip`] restricts all commands after it to its rect (and mask), up until the
+//! next clip command. Clips are already limited to the bounds of any enclosing clip, so there's no
+//! stack to maintain: each clip simply replaces the previous one.
+//!
+//! # Textures
+//!
+//! Images, texture atlases and nine-patches refer to their texture by the handle it was registered
+//! with by the integration. Glyphs refer to their font by name and to their character, leaving the
+//! font atlas to the backend.
+//!
+//! Rather than matching on the commands, a backend can also implement
+//! [`RenderBackend`](crate::render_backend::RenderBackend) and have the list
+//! [replayed](DrawList::replay) into it.
+
+use kayak_font::{ColorAtlas, ColorAtlasRegion, GlyphRect, TextLayout, TextProperties};
+
+use crate::curve::{Bezier, Curve, LineSegment};
+use crate::layout_cache::Rect;
+use crate::render_backend::{ColorGlyph, GlyphRun, RenderBackend, TextureUpload, UiTexture};
+use crate::render_primitive::{ClipMask, RenderPrimitive};
+use crate::styles::{BorderStyle, Corner, Edge, Transform};
+use crate::Color;
+
+/// The draw commands of a single render, in drawing order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DrawList {
+    /// The commands to draw, from the bottom to the top
+    pub commands: Vec<DrawCommand>,
+    /// The number of times the list has been rebuilt
+    ///
+    /// This increases every time the context renders, so a backend can tell whether the list
+    /// changed since it last drew it.
+    pub generation: u64,
+}
+
+/// A single command of a [`DrawList`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    /// Restricts the commands after it to a rect, up until the next clip
+    Clip {
+        rect: Rect,
+        /// The rounded outline to mask content to, if any
+        mask: Option<ClipMask>,
+    },
+    /// A rectangle with an optional border and rounded corners
+    Quad(DrawQuad),
+    /// A run of glyphs set in a single font, size and color
+    Glyphs {
+        /// The name of the font
+        font: String,
+        font_size: f32,
+        color: Color,
+        /// The glyphs, positioned in the UI's coordinate space
+        glyphs: Vec<GlyphRect>,
+        /// If false, glyph positions should be rounded to whole physical pixels
+        subpixel_positioning: bool,
+        /// The bounds of the text
+        rect: Rect,
+    },
+    /// An image, stretched to fill the rect
+    Image {
+        rect: Rect,
+        handle: u16,
+        border_radius: Corner<f32>,
+    },
+    /// A region of a texture atlas, stretched to fill the rect
+    TextureAtlas {
+        rect: Rect,
+        handle: u16,
+        /// The position of the region within the atlas, in texels
+        position: (f32, f32),
+        /// The size of the region within the atlas, in texels
+        size: (f32, f32),
+    },
+    /// An image whose edges keep their size while its center stretches to fill the rect
+    NinePatch {
+        rect: Rect,
+        handle: u16,
+        /// The size of each of the edges that aren't stretched, in texels
+        border: Edge<f32>,
+    },
+    /// A batch of anti-aliased lines with round ends, making up the curves of a widget
+    Lines {
+        /// The bounds of the widget drawing the curves
+        ///
+        /// The lines themselves may reach outside of it.
+        rect: Rect,
+        /// The lines, positioned in the UI's coordinate space
+        lines: Vec<LineSegment>,
+    },
+}
+
+/// A rectangle with an optional border and rounded corners
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawQuad {
+    pub rect: Rect,
+    pub color: Color,
+    pub border_color: Color,
+    /// The color of each side of the border
+    pub border_colors: Edge<Color>,
+    /// The width of the border on each side, drawn inside the rect
+    pub border: Edge<f32>,
+    pub border_radius: Corner<f32>,
+    pub border_style: Edge<BorderStyle>,
+}
+
+impl DrawCommand {
+    /// Returns this command moved and scaled by the given transform
+    ///
+    /// Points are transformed relative to the top-left corner of the UI. Border widths, corner
+    /// radii, font sizes and line widths are scaled along with the rects, except for nine-patch
+    /// borders, which keep their size by design.
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let point = |point: (f32, f32)| transform.apply(point, (0.0, 0.0));
+        let rect = |rect: &Rect| {
+            let (posx, posy) = point((rect.posx, rect.posy));
+            Rect {
+                posx,
+                posy,
+                width: rect.width * transform.scale,
+                height: rect.height * transform.scale,
+                z_index: rect.z_index,
+            }
+        };
+
+        let mut command = self.clone();
+        match &mut command {
+            DrawCommand::Clip { rect: clip, mask } => {
+                *clip = rect(clip);
+                if let Some(mask) = mask {
+                    mask.layout = rect(&mask.layout);
+                    mask.border_radius *= transform.scale;
+                }
+            }
+            DrawCommand::Quad(quad) => {
+                quad.rect = rect(&quad.rect);
+                quad.border *= transform.scale;
+                quad.border_radius *= transform.scale;
+            }
+            DrawCommand::Glyphs {
+                font_size,
+                glyphs,
+                rect: bounds,
+                ..
+            } => {
+                *font_size *= transform.scale;
+                for glyph in glyphs.iter_mut() {
+                    glyph.position = point(glyph.position);
+                    glyph.size = (
+                        glyph.size.0 * transform.scale,
+                        glyph.size.1 * transform.scale,
+                    );
+                }
+                *bounds = rect(bounds);
+            }
+            DrawCommand::Image {
+                rect: bounds,
+                border_radius,
+                ..
+            } => {
+                *bounds = rect(bounds);
+                *border_radius *= transform.scale;
+            }
+            DrawCommand::TextureAtlas { rect: bounds, .. }
+            | DrawCommand::NinePatch { rect: bounds, .. } => *bounds = rect(bounds),
+            DrawCommand::Lines {
+                rect: bounds,
+                lines,
+            } => {
+                *bounds = rect(bounds);
+                for line in lines.iter_mut() {
+                    line.start = point(line.start);
+                    line.end = point(line.end);
+                    line.width *= transform.scale;
+                }
+            }
+        }
+        command
+    }
+
+    /// Converts this command back into a render primitive, such as to draw a retained list again
+    ///
+    /// This is the inverse of [`DrawList::from_primitives`], except that text only keeps its
+    /// positioned glyphs (not its content or lines), and curves are drawn with the straight lines
+    /// they were flattened into.
+    pub fn into_primitive(self) -> RenderPrimitive {
+        match self {
+            DrawCommand::Clip { rect, mask } => RenderPrimitive::Clip { layout: rect, mask },
+            DrawCommand::Quad(quad) => RenderPrimitive::Quad {
+                layout: quad.rect,
+                background_color: quad.color,
+                border_color: quad.border_color,
+                border_colors: quad.border_colors,
+                border: quad.border,
+                border_radius: quad.border_radius,
+                border_style: quad.border_style,
+            },
+            DrawCommand::Glyphs {
+                font,
+                font_size,
+                color,
+                glyphs,
+                subpixel_positioning,
+                rect,
+            } => {
+                let properties = TextProperties {
+                    font_size,
+                    line_height: font_size * 1.2,
+                    subpixel_positioning,
+                    ..Default::default()
+                };
+                // Glyphs are positioned relative to the baseline of the first line
+                let origin = (rect.posx, rect.posy + font_size);
+                let glyphs = glyphs
+                    .into_iter()
+                    .map(|glyph| GlyphRect {
+                        position: (glyph.position.0 - origin.0, glyph.position.1 - origin.1),
+                        ..glyph
+                    })
+                    .collect();
+                RenderPrimitive::Text {
+                    color,
+                    content: String::new(),
+                    font,
+                    text_layout: TextLayout::new(
+                        glyphs,
+                        Vec::new(),
+                        (rect.width, rect.height),
+                        properties,
+                    ),
+                    layout: rect,
+                    properties,
+                }
+            }
+            DrawCommand::Image {
+                rect,
+                handle,
+                border_radius,
+            } => RenderPrimitive::Image {
+                border_radius,
+                layout: rect,
+                handle,
+            },
+            DrawCommand::TextureAtlas {
+                rect,
+                handle,
+                position,
+                size,
+            } => RenderPrimitive::TextureAtlas {
+                size,
+                position,
+                layout: rect,
+                handle,
+            },
+            DrawCommand::NinePatch {
+                rect,
+                handle,
+                border,
+            } => RenderPrimitive::NinePatch {
+                border,
+                layout: rect,
+                handle,
+            },
+            DrawCommand::Lines { rect, lines } => RenderPrimitive::Curves {
+                layout: rect,
+                curves: lines
+                    .iter()
+                    .map(|line| {
+                        let line = line.translated((-rect.posx, -rect.posy));
+                        Curve::new(Bezier::line(line.start, line.end), line.width, line.color)
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl DrawList {
+    /// Builds a draw list from the render primitives of the widget tree
+    pub fn from_primitives(primitives: &[RenderPrimitive], generation: u64) -> Self {
+        let commands = primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                RenderPrimitive::Empty => None,
+                RenderPrimitive::Clip { layout, mask } => Some(DrawCommand::Clip {
+                    rect: *layout,
+                    mask: *mask,
+                }),
+                RenderPrimitive::Quad {
+                    layout,
+                    background_color,
+                    border_color,
+                    border_colors,
+                    border,
+                    border_radius,
+                    border_style,
+                } => Some(DrawCommand::Quad(DrawQuad {
+                    rect: *layout,
+                    color: *background_color,
+                    border_color: *border_color,
+                    border_colors: *border_colors,
+                    border: *border,
+                    border_radius: *border_radius,
+                    border_style: *border_style,
+                })),
+                RenderPrimitive::Text {
+                    color,
+                    font,
+                    text_layout,
+                    layout,
+                    properties,
+                    ..
+                } => {
+                    // Glyphs are positioned relative to the baseline of the first line
+                    let origin = (layout.posx, layout.posy + properties.font_size);
+                    let glyphs = text_layout
+                        .glyphs()
+                        .iter()
+                        .map(|glyph| GlyphRect {
+                            position: (origin.0 + glyph.position.0, origin.1 + glyph.position.1),
+                            ..*glyph
+                        })
+                        .collect();
+                    Some(DrawCommand::Glyphs {
+                        font: font.clone(),
+                        font_size: properties.font_size,
+                        color: *color,
+                        glyphs,
+                        subpixel_positioning: properties.subpixel_positioning,
+                        rect: *layout,
+                    })
+                }
+                RenderPrimitive::Image {
+                    border_radius,
+                    layout,
+                    handle,
+                } => Some(DrawCommand::Image {
+                    rect: *layout,
+                    handle: *handle,
+                    border_radius: *border_radius,
+                }),
+                RenderPrimitive::TextureAtlas {
+                    size,
+                    position,
+                    layout,
+                    handle,
+                } => Some(DrawCommand::TextureAtlas {
+                    rect: *layout,
+                    handle: *handle,
+                    position: *position,
+                    size: *size,
+                }),
+                RenderPrimitive::NinePatch {
+                    border,
+                    layout,
+                    handle,
+                } => Some(DrawCommand::NinePatch {
+                    rect: *layout,
+                    handle: *handle,
+                    border: *border,
+                }),
+                RenderPrimitive::Curves { layout, curves } => Some(DrawCommand::Lines {
+                    rect: *layout,
+                    lines: curves
+                        .iter()
+                        .flat_map(Curve::segments)
+                        .map(|line| line.translated((layout.posx, layout.posy)))
+                        .collect(),
+                }),
+            })
+            .collect();
+
+        Self {
+            commands,
+            generation,
+        }
+    }
+
+    /// Draws the commands of this list with the given backend, in order
+    ///
+    /// Consecutive quads are drawn in a single batch. Telling the color glyphs of a run apart
+    /// requires its font, so every glyph is drawn as part of [`GlyphRun::glyphs`]:
+    /// [`KayakContext::draw_with`](crate::KayakContext::draw_with) draws color glyphs as well.
+    pub fn replay<B: RenderBackend + ?Sized>(&self, backend: &mut B) {
+        self.replay_with(backend, None, |_, _, _| None);
+    }
+
+    /// Draws the commands of this list with the given backend, uploading the color atlas first
+    ///
+    /// The color glyphs of each run are looked up by its font and size. The lookup returns `None`
+    /// for glyphs that aren't drawn in color, and otherwise their region of the atlas (which is
+    /// itself `None` for glyphs that didn't fit, and so aren't drawn).
+    pub(crate) fn replay_with<B: RenderBackend + ?Sized>(
+        &self,
+        backend: &mut B,
+        color_atlas: Option<&ColorAtlas>,
+        mut color_glyph: impl FnMut(&str, f32, char) -> Option<Option<ColorAtlasRegion>>,
+    ) {
+        backend.begin_frame(self.generation);
+        if let Some(color_atlas) = color_atlas {
+            backend.upload_texture(TextureUpload {
+                texture: UiTexture::ColorGlyphs,
+                version: color_atlas.version(),
+                size: color_atlas.size(),
+                data: color_atlas.data(),
+            });
+        }
+
+        let mut quads = Vec::new();
+        for command in self.commands.iter() {
+            if let DrawCommand::Quad(quad) = command {
+                quads.push(*quad);
+                continue;
+            }
+            if !quads.is_empty() {
+                backend.draw_quads(&quads);
+                quads.clear();
+            }
+
+            match command {
+                DrawCommand::Clip { rect, mask } => backend.set_clip(*rect, *mask),
+                DrawCommand::Quad(..) => {}
+                DrawCommand::Glyphs {
+                    font,
+                    font_size,
+                    color,
+                    glyphs,
+                    subpixel_positioning,
+                    rect,
+                } => {
+                    let mut text_glyphs = Vec::with_capacity(glyphs.len());
+                    let mut color_glyphs = Vec::new();
+                    for glyph in glyphs {
+                        match color_glyph(font, *font_size, glyph.content) {
+                            None => text_glyphs.push(*glyph),
+                            Some(Some(region)) => color_glyphs.push(ColorGlyph {
+                                glyph: *glyph,
+                                region,
+                            }),
+                            Some(None) => {}
+                        }
+                    }
+                    backend.draw_glyphs(GlyphRun {
+                        font,
+                        font_size: *font_size,
+                        color: *color,
+                        glyphs: &text_glyphs,
+                        color_glyphs: &color_glyphs,
+                        subpixel_positioning: *subpixel_positioning,
+                        rect: *rect,
+                    })
+                }
+                DrawCommand::Image {
+                    rect,
+                    handle,
+                    border_radius,
+                } => backend.draw_image(*rect, *handle, *border_radius),
+                DrawCommand::TextureAtlas {
+                    rect,
+                    handle,
+                    position,
+                    size,
+                } => backend.draw_texture_atlas(*rect, *handle, *position, *size),
+                DrawCommand::NinePatch {
+                    rect,
+                    handle,
+                    border,
+                } => backend.draw_nine_patch(*rect, *handle, *border),
+                DrawCommand::Lines { rect, lines } => backend.draw_lines(*rect, lines),
+            }
+        }
+        if !quads.is_empty() {
+            backend.draw_quads(&quads);
+        }
+
+        backend.end_frame();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_font::{GlyphRect, TextLayout, TextProperties};
+
+    use super::{DrawCommand, DrawList};
+    use crate::curve::{Bezier, Curve};
+    use crate::layout_cache::Rect;
+    use crate::render_primitive::RenderPrimitive;
+    use crate::styles::Transform;
+    use crate::Color;
+
+    #[test]
+    fn should_position_glyphs_in_ui_space() {
+        let properties = TextProperties {
+            font_size: 10.0,
+            ..Default::default()
+        };
+        let glyph = GlyphRect {
+            position: (2.0, -8.0),
+            size: (6.0, 9.0),
+            content: 'a',
+        };
+        let layout = Rect {
+            posx: 100.0,
+            posy: 50.0,
+            width: 20.0,
+            height: 12.0,
+            z_index: 3.0,
+        };
+        let primitives = vec![
+            RenderPrimitive::Empty,
+            RenderPrimitive::Text {
+                color: Color::WHITE,
+                content: String::from("a"),
+                font: String::from("roboto"),
+                text_layout: TextLayout::new(vec![glyph], Vec::new(), (6.0, 9.0), properties),
+                layout,
+                properties,
+            },
+        ];
+
+        let draw_list = DrawList::from_primitives(&primitives, 7);
+        assert_eq!(7, draw_list.generation);
+        assert_eq!(1, draw_list.commands.len());
+        match &draw_list.commands[0] {
+            DrawCommand::Glyphs { glyphs, rect, .. } => {
+                assert_eq!((102.0, 52.0), glyphs[0].position);
+                assert_eq!('a', glyphs[0].content);
+                assert_eq!(layout, *rect);
+            }
+            command => panic!("Expected glyphs, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn should_position_curves_in_ui_space() {
+        let layout = Rect {
+            posx: 100.0,
+            posy: 50.0,
+            width: 200.0,
+            height: 100.0,
+            z_index: 1.0,
+        };
+        let curve = Curve::new(Bezier::line((0.0, 0.0), (10.0, 20.0)), 2.0, Color::WHITE);
+        let primitives = vec![RenderPrimitive::Curves {
+            layout,
+            curves: vec![curve],
+        }];
+
+        let draw_list = DrawList::from_primitives(&primitives, 0);
+        match &draw_list.commands[0] {
+            DrawCommand::Lines { lines, rect } => {
+                assert_eq!(1, lines.len());
+                assert_eq!((100.0, 50.0), lines[0].start);
+                assert_eq!((110.0, 70.0), lines[0].end);
+                assert_eq!(layout, *rect);
+            }
+            command => panic!("Expected lines, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn should_transform_commands_into_primitives() {
+        let properties = TextProperties {
+            font_size: 10.0,
+            ..Default::default()
+        };
+        let glyph = GlyphRect {
+            position: (2.0, -8.0),
+            size: (6.0, 9.0),
+            content: 'a',
+        };
+        let layout = Rect {
+            posx: 100.0,
+            posy: 50.0,
+            width: 20.0,
+            height: 12.0,
+            z_index: 3.0,
+        };
+        let primitives = vec![RenderPrimitive::Text {
+            color: Color::WHITE,
+            content: String::from("a"),
+            font: String::from("roboto"),
+            text_layout: TextLayout::new(vec![glyph], Vec::new(), (6.0, 9.0), properties),
+            layout,
+            properties,
+        }];
+
+        let draw_list = DrawList::from_primitives(&primitives, 0);
+        let command = draw_list.commands[0].transformed(&Transform::new((10.0, 0.0), 0.5));
+        match command.into_primitive() {
+            RenderPrimitive::Text {
+                text_layout,
+                layout,
+                properties,
+                ..
+            } => {
+                assert_eq!(
+                    Rect {
+                        posx: 60.0,
+                        posy: 25.0,
+                        width: 10.0,
+                        height: 6.0,
+                        z_index: 3.0,
+                    },
+                    layout
+                );
+                assert_eq!(5.0, properties.font_size);
+                // Still relative to the baseline of the scaled text
+                assert_eq!((1.0, -4.0), text_layout.glyphs()[0].position);
+                assert_eq!((3.0, 4.5), text_layout.glyphs()[0].size);
+            }
+            primitive => panic!("Expected text, got {:?}", primitive),
+        }
+    }
+}
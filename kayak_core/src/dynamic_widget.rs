@@ -0,0 +1,314 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::{
+    context_ref::KayakContextRef, styles::Style, Children, Index, OnEvent, OnLayout, Widget,
+    WidgetProps,
+};
+
+/// A type-erased function that adds a registered widget to the tree
+pub(crate) type WidgetConstructor =
+    Arc<dyn Fn(&mut KayakContextRef, DynamicProps, usize) + Send + Sync>;
+
+/// Adds a widget of type `W` to the tree, converting the given props into its own
+pub(crate) fn add_registered_widget<W>(
+    context: &mut KayakContextRef,
+    props: DynamicProps,
+    index: usize,
+) where
+    W: Widget + 'static,
+    W::Props: From<DynamicProps>,
+{
+    context.add_widget(W::constructor(props.into()), index);
+}
+
+/// A type-erased value that can still be compared to other values
+///
+/// This is used for the attributes of [`DynamicProps`], and by props that hold values of any type
+/// (such as widgets) but still need to tell whether they changed. Values of different types are
+/// never equal.
+#[derive(Clone)]
+pub struct DynamicValue {
+    value: Arc<dyn Any + Send + Sync>,
+    eq: fn(&dyn Any, &dyn Any) -> bool,
+}
+
+impl DynamicValue {
+    pub fn new<T: Any + Send + Sync + PartialEq>(value: T) -> Self {
+        Self {
+            value: Arc::new(value),
+            eq: eq_as::<T>,
+        }
+    }
+
+    /// Returns the value if it's of type `T`
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+}
+
+impl Debug for DynamicValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicValue").finish()
+    }
+}
+
+impl PartialEq for DynamicValue {
+    fn eq(&self, other: &Self) -> bool {
+        (self.eq)(self.value.as_ref(), other.value.as_ref())
+    }
+}
+
+/// Compares two type-erased values as values of type `T`
+fn eq_as<T: Any + PartialEq>(a: &dyn Any, b: &dyn Any) -> bool {
+    match (a.downcast_ref::<T>(), b.downcast_ref::<T>()) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Props given to a widget created from a custom element tag (such as `<plugin:Foo>`)
+///
+/// Since the widget behind a custom tag is only known at runtime, its attributes can't be
+/// assigned to typed fields. Instead, the common props are stored as-is and all other attributes
+/// are stored by name, to be retrieved with [`get`](Self::get). A widget registered via
+/// [`KayakContext::register_widget`](crate::KayakContext::register_widget) converts these into its
+/// own props using `From<DynamicProps>`.
+#[derive(Default, Clone)]
+pub struct DynamicProps {
+    tag: String,
+    pub styles: Option<Style>,
+    pub children: Option<Children>,
+    pub on_event: Option<OnEvent>,
+    pub on_event_capture: Option<OnEvent>,
+    pub on_layout: Option<OnLayout>,
+    pub focusable: Option<bool>,
+    attributes: BTreeMap<String, DynamicValue>,
+}
+
+impl DynamicProps {
+    pub fn new<S: Into<String>>(tag: S) -> Self {
+        Self {
+            tag: tag.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The tag these props were created for, including its namespace (i.e. `"plugin:Foo"`)
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Sets the value of the attribute with the given name
+    ///
+    /// Attribute values are compared when the props are, so that a widget is only re-rendered
+    /// when one of them actually changes.
+    pub fn set<T: Any + Send + Sync + PartialEq>(&mut self, name: &str, value: T) {
+        self.attributes
+            .insert(name.to_string(), DynamicValue::new(value));
+    }
+
+    /// Gets the value of the attribute with the given name
+    ///
+    /// Returns `None` if the attribute wasn't given or isn't of type `T`.
+    pub fn get<T: Any>(&self, name: &str) -> Option<&T> {
+        self.attributes.get(name)?.downcast_ref::<T>()
+    }
+
+    /// Sets the value of the attribute with the given name to an already shared value
+    pub(crate) fn insert(&mut self, name: &str, value: DynamicValue) {
+        self.attributes.insert(name.to_string(), value);
+    }
+
+    /// Returns true if an attribute with the given name was given
+    pub fn contains(&self, name: &str) -> bool {
+        self.attributes.contains_key(name)
+    }
+
+    /// Returns the names of all given attributes (excluding the common props)
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.attributes.keys().map(|name| name.as_str())
+    }
+}
+
+impl Debug for DynamicProps {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicProps")
+            .field("tag", &self.tag)
+            .field("styles", &self.styles)
+            .field("focusable", &self.focusable)
+            .field("attributes", &self.attributes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PartialEq for DynamicProps {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+            && self.styles == other.styles
+            && self.children == other.children
+            && self.on_event == other.on_event
+            && self.on_event_capture == other.on_event_capture
+            && self.on_layout == other.on_layout
+            && self.focusable == other.focusable
+            && self.attributes == other.attributes
+    }
+}
+
+impl WidgetProps for DynamicProps {
+    fn get_children(&self) -> Option<Children> {
+        self.children.clone()
+    }
+
+    fn set_children(&mut self, children: Option<Children>) {
+        self.children = children;
+    }
+
+    fn get_styles(&self) -> Option<Style> {
+        self.styles.clone()
+    }
+
+    fn get_on_event(&self) -> Option<OnEvent> {
+        self.on_event.clone()
+    }
+
+    fn get_on_event_capture(&self) -> Option<OnEvent> {
+        self.on_event_capture.clone()
+    }
+
+    fn get_on_layout(&self) -> Option<OnLayout> {
+        self.on_layout.clone()
+    }
+
+    fn get_focusable(&self) -> Option<bool> {
+        self.focusable
+    }
+}
+
+/// Props used by the [`DynamicWidget`] widget
+///
+/// These only wrap the [`DynamicProps`] so that the wrapper itself stays out of the way: all
+/// common props are handled by the widget it resolves to.
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct DynamicWidgetProps {
+    props: DynamicProps,
+}
+
+impl WidgetProps for DynamicWidgetProps {
+    fn get_children(&self) -> Option<Children> {
+        None
+    }
+
+    fn set_children(&mut self, children: Option<Children>) {
+        self.props.children = children;
+    }
+
+    fn get_styles(&self) -> Option<Style> {
+        None
+    }
+
+    fn get_on_event(&self) -> Option<OnEvent> {
+        None
+    }
+
+    fn get_on_layout(&self) -> Option<OnLayout> {
+        None
+    }
+
+    fn get_focusable(&self) -> Option<bool> {
+        Some(false)
+    }
+}
+
+/// The widget created for a custom element tag (such as `<plugin:Foo>`)
+///
+/// When rendered, this looks up the widget registered for its tag via
+/// [`KayakContext::register_widget`](crate::KayakContext::register_widget) and renders it as its
+/// only child. Like [`Fragment`](crate::Fragment), it takes no part in layout itself.
+///
+/// # Panics
+///
+/// Panics when rendered if no widget has been registered for its tag.
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct DynamicWidget {
+    pub id: Index,
+    props: DynamicWidgetProps,
+}
+
+impl DynamicWidget {
+    /// Creates a widget that resolves to whichever widget is registered for the tag of `props`
+    pub fn new(props: DynamicProps) -> Self {
+        Self::constructor(DynamicWidgetProps { props })
+    }
+}
+
+impl Widget for DynamicWidget {
+    type Props = DynamicWidgetProps;
+
+    fn constructor(props: Self::Props) -> Self
+    where
+        Self: Sized,
+    {
+        Self {
+            id: Index::default(),
+            props,
+        }
+    }
+
+    fn get_id(&self) -> Index {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Index) {
+        self.id = id;
+    }
+
+    fn get_props(&self) -> &Self::Props {
+        &self.props
+    }
+
+    fn get_props_mut(&mut self) -> &mut Self::Props {
+        &mut self.props
+    }
+
+    fn render(&mut self, context: &mut KayakContextRef) {
+        let tag = self.props.props.tag();
+        let constructor = match context.context.get_widget_constructor(tag) {
+            Some(constructor) => constructor,
+            None => panic!(
+                "No widget is registered for the tag <{}>. Custom tags must be registered with `KayakContext::register_widget` before they're rendered",
+                tag
+            ),
+        };
+
+        constructor(context, self.props.props.clone(), 0);
+        context.commit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynamicProps;
+
+    fn props(label: &str) -> DynamicProps {
+        let mut props = DynamicProps::new("test:Label");
+        props.set("label", label.to_string());
+        props
+    }
+
+    #[test]
+    fn should_compare_attribute_values() {
+        assert_eq!(props("Hello"), props("Hello"));
+        assert_ne!(props("Hello"), props("World"));
+    }
+
+    #[test]
+    fn should_not_equal_attributes_of_another_type() {
+        let mut other = DynamicProps::new("test:Label");
+        other.set("label", 1u32);
+        assert_ne!(props("Hello"), other);
+        assert_ne!(props("Hello"), DynamicProps::new("test:Label"));
+    }
+}
@@ -0,0 +1,224 @@
+use std::f32::consts::PI;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// A curve that maps the linear progress of an animation to its eased progress
+///
+/// Both the input and output go from `0.0` (the start of the animation) to `1.0` (its end),
+/// though the output of some curves (like [`ElasticOut`](Easing::ElasticOut)) overshoots the end
+/// along the way. The `In` curves start slow, the `Out` curves end slow, and the `InOut` curves
+/// do both.
+#[derive(Clone)]
+pub enum Easing {
+    /// Moves at a constant speed
+    Linear,
+    /// A quadratic curve that starts slow
+    EaseIn,
+    /// A quadratic curve that ends slow
+    EaseOut,
+    /// A quadratic curve that starts and ends slow
+    EaseInOut,
+    /// A cubic curve that starts slow
+    CubicIn,
+    /// A cubic curve that ends slow
+    CubicOut,
+    /// A cubic curve that starts and ends slow
+    CubicInOut,
+    /// Winds up with a growing wobble before moving to the end
+    ElasticIn,
+    /// Overshoots the end and wobbles back and forth before settling
+    ElasticOut,
+    /// Wobbles at both the start and the end
+    ElasticInOut,
+    /// Bounces off the start a few times before moving to the end
+    BounceIn,
+    /// Bounces off the end a few times before settling
+    BounceOut,
+    /// Bounces at both the start and the end
+    BounceInOut,
+    /// A custom curve, created with [`Easing::custom`]
+    Custom(Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+}
+
+impl Easing {
+    /// Creates an easing from a custom curve
+    ///
+    /// The curve should map `0.0` to `0.0` and `1.0` to `1.0`, or the animation will jump at its
+    /// start or end.
+    pub fn custom<F: Fn(f32) -> f32 + Send + Sync + 'static>(curve: F) -> Self {
+        Self::Custom(Arc::new(curve))
+    }
+
+    /// Returns the eased progress for the given linear progress
+    ///
+    /// The progress is clamped to `0.0..=1.0` first.
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Self::ElasticIn => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+                }
+            }
+            Self::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }
+            Self::ElasticInOut => {
+                let wave = ((20.0 * t - 11.125) * (2.0 * PI / 4.5)).sin();
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    -(2f32.powf(20.0 * t - 10.0) * wave) / 2.0
+                } else {
+                    2f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+                }
+            }
+            Self::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Self::BounceOut => bounce_out(t),
+            Self::BounceInOut => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
+            Self::Custom(curve) => curve(t),
+        }
+    }
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::CubicInOut
+    }
+}
+
+impl PartialEq for Easing {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Only the data pointers are compared, since vtable pointers aren't unique
+            (Self::Custom(a), Self::Custom(b)) => {
+                std::ptr::eq(Arc::as_ptr(a) as *const (), Arc::as_ptr(b) as *const ())
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Debug for Easing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Linear => "Linear",
+            Self::EaseIn => "EaseIn",
+            Self::EaseOut => "EaseOut",
+            Self::EaseInOut => "EaseInOut",
+            Self::CubicIn => "CubicIn",
+            Self::CubicOut => "CubicOut",
+            Self::CubicInOut => "CubicInOut",
+            Self::ElasticIn => "ElasticIn",
+            Self::ElasticOut => "ElasticOut",
+            Self::ElasticInOut => "ElasticInOut",
+            Self::BounceIn => "BounceIn",
+            Self::BounceOut => "BounceOut",
+            Self::BounceInOut => "BounceInOut",
+            Self::Custom(..) => "Custom",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A curve that bounces off the end three times with decreasing height
+fn bounce_out(t: f32) -> f32 {
+    const STRENGTH: f32 = 7.5625;
+    const SPAN: f32 = 2.75;
+
+    if t < 1.0 / SPAN {
+        STRENGTH * t * t
+    } else if t < 2.0 / SPAN {
+        let t = t - 1.5 / SPAN;
+        STRENGTH * t * t + 0.75
+    } else if t < 2.5 / SPAN {
+        let t = t - 2.25 / SPAN;
+        STRENGTH * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / SPAN;
+        STRENGTH * t * t + 0.984375
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Easing;
+
+    #[test]
+    fn curves_should_start_and_end_in_place() {
+        let curves = [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::CubicIn,
+            Easing::CubicOut,
+            Easing::CubicInOut,
+            Easing::ElasticIn,
+            Easing::ElasticOut,
+            Easing::ElasticInOut,
+            Easing::BounceIn,
+            Easing::BounceOut,
+            Easing::BounceInOut,
+        ];
+        for curve in curves {
+            assert!(
+                curve.ease(0.0).abs() < 1e-5,
+                "{:?} should start at 0",
+                curve
+            );
+            assert!(
+                (curve.ease(1.0) - 1.0).abs() < 1e-5,
+                "{:?} should end at 1",
+                curve
+            );
+        }
+    }
+
+    #[test]
+    fn should_clamp_progress() {
+        assert_eq!(0.0, Easing::EaseIn.ease(-1.0));
+        assert_eq!(1.0, Easing::EaseIn.ease(2.0));
+        assert_eq!(0.25, Easing::EaseIn.ease(0.5));
+    }
+
+    #[test]
+    fn custom_curves_should_only_equal_themselves() {
+        let steps = Easing::custom(|t| (t * 4.0).floor() / 4.0);
+        assert_eq!(0.5, steps.ease(0.6));
+        assert_eq!(steps, steps.clone());
+        assert_ne!(steps, Easing::custom(|t| (t * 4.0).floor() / 4.0));
+        assert_ne!(steps, Easing::Linear);
+    }
+}
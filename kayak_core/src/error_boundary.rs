@@ -0,0 +1,121 @@
+//! Containment of panics within part of the widget tree
+//!
+//! A widget becomes an error boundary by building its children with
+//! [`KayakContextRef::catch_panic`](crate::KayakContextRef::catch_panic). Panics are caught in
+//! two places:
+//!
+//! 1. While the boundary itself builds its children, in which case the error is returned directly.
+//! 2. While a descendant of the boundary is re-rendered on its own (such as after a state change).
+//!    The error is then stored for the closest boundary, which is re-rendered on the next frame and
+//!    receives the error from its next call to `catch_panic`.
+//!
+//! Panics that aren't within a boundary continue to unwind as before.
+
+use std::any::Any;
+
+/// Returns the message of the given panic payload
+///
+/// Payloads created by `panic!` are either a `&str` or a `String`. Any other payload (such as one
+/// passed to `std::panic::panic_any`) has no readable message.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Unknown panic")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use super::panic_message;
+    use crate::{mount_widget, Binding, Bound, KayakContext, MutableBound, Widget};
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct BuggyProps {
+        /// Whether the widget panics when rendered
+        panics: Binding<bool>,
+    }
+
+    #[widget]
+    fn Buggy(props: BuggyProps) {
+        context.bind(&props.panics);
+        if props.panics.get() {
+            panic!("buggy widget");
+        }
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct BoundaryProps {
+        panics: Binding<bool>,
+        /// The errors returned by `catch_panic`, in order
+        errors: Binding<Vec<String>>,
+    }
+
+    #[widget]
+    fn Boundary(props: BoundaryProps) {
+        let panics = props.panics.clone();
+        let result = context.catch_panic(move |context| {
+            context.add_widget(Buggy::constructor(BuggyProps { panics }), 0);
+        });
+        if let Err(error) = result {
+            let mut errors = props.errors.get();
+            errors.push(error);
+            props.errors.set(errors);
+        }
+    }
+
+    #[test]
+    fn should_read_panic_messages() {
+        let payload = std::panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!("static message", panic_message(payload.as_ref()));
+
+        let value = 3;
+        let payload =
+            std::panic::catch_unwind(|| panic!("formatted message {}", value)).unwrap_err();
+        assert_eq!("formatted message 3", panic_message(payload.as_ref()));
+
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(3)).unwrap_err();
+        assert_eq!("Unknown panic", panic_message(payload.as_ref()));
+    }
+
+    #[test]
+    fn should_catch_panics_while_building_children() {
+        let mut context = KayakContext::new();
+        let errors = Binding::new(Vec::new());
+        let props = BoundaryProps {
+            panics: Binding::new(true),
+            errors: errors.clone(),
+        };
+        mount_widget(&mut context, Boundary::constructor(props));
+        assert_eq!(vec![String::from("buggy widget")], errors.get());
+
+        // The widgets added before the panic are discarded
+        let tree = &context.widget_manager.tree;
+        let boundary = tree.root_node.unwrap();
+        assert!(tree.children.get(&boundary).map_or(true, Vec::is_empty));
+    }
+
+    #[test]
+    fn should_catch_panics_from_re_rendered_children() {
+        let mut context = KayakContext::new();
+        let panics = Binding::new(false);
+        let errors = Binding::new(Vec::new());
+        let props = BoundaryProps {
+            panics: panics.clone(),
+            errors: errors.clone(),
+        };
+        mount_widget(&mut context, Boundary::constructor(props));
+        assert!(errors.get().is_empty());
+
+        // Only the child re-renders and panics, so the boundary gets the error on its next render
+        panics.set(true);
+        context.render();
+        assert!(context.is_dirty());
+        context.render();
+        assert_eq!(vec![String::from("buggy widget")], errors.get());
+    }
+}
@@ -1,8 +1,9 @@
 use crate::cursor::{CursorEvent, ScrollEvent};
+use crate::gesture::{PanEvent, PinchEvent, TouchEvent};
 use crate::{Index, KeyboardEvent};
 
 /// An event type sent to widgets
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Event {
     /// The node targeted by this event
     pub target: Index,
@@ -37,8 +38,8 @@ impl Event {
         Self {
             target,
             current_target: target,
-            event_type,
             should_propagate: event_type.propagates(),
+            event_type,
             default_prevented: false,
         }
     }
@@ -49,6 +50,8 @@ impl Event {
     }
 
     /// If called, prevents this event from propagating up the hierarchy
+    ///
+    /// If called during the capture phase, this also prevents the event from reaching its target.
     pub fn stop_propagation(&mut self) {
         self.should_propagate = false;
     }
@@ -69,7 +72,7 @@ impl Event {
 /// __Note:__ This type implements `PartialEq` and `Hash` in a way that only considers the variant itself,
 /// not the underlying data. If full comparisons are needed, they should be done with the inner data or
 /// with a custom wrapper.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum EventType {
     /// An event that occurs when the user clicks a widget
     Click(CursorEvent),
@@ -85,6 +88,32 @@ pub enum EventType {
     MouseUp(CursorEvent),
     /// An event that occurs when the user scrolls over a widget
     Scroll(ScrollEvent),
+    /// An event that occurs when the user touches a widget
+    TouchStart(TouchEvent),
+    /// An event that occurs when a touch that started on a widget moves
+    ///
+    /// This is sent to the widget the touch started on, even if the touch has since left it.
+    TouchMove(TouchEvent),
+    /// An event that occurs when a touch that started on a widget ends (or is cancelled)
+    ///
+    /// This is sent to the widget the touch started on, even if the touch has since left it.
+    TouchEnd(TouchEvent),
+    /// An event that occurs when the user quickly touches a widget without moving
+    ///
+    /// Only sent to widgets that recognize [`Gestures::TAP`](crate::Gestures::TAP).
+    Tap(TouchEvent),
+    /// An event that occurs when the user holds a touch in place over a widget
+    ///
+    /// Only sent to widgets that recognize [`Gestures::LONG_PRESS`](crate::Gestures::LONG_PRESS).
+    LongPress(TouchEvent),
+    /// An event that occurs when the user drags a single touch across a widget
+    ///
+    /// Only sent to widgets that recognize [`Gestures::PAN`](crate::Gestures::PAN).
+    Pan(PanEvent),
+    /// An event that occurs when the user moves two touches towards or away from each other over a widget
+    ///
+    /// Only sent to widgets that recognize [`Gestures::PINCH`](crate::Gestures::PINCH).
+    Pinch(PinchEvent),
     /// An event that occurs when a widget receives focus
     Focus,
     /// An event that occurs when a widget loses focus
@@ -95,6 +124,24 @@ pub enum EventType {
     KeyUp(KeyboardEvent),
     /// An event that occurs when the user presses a key down within a _focused_ widget
     KeyDown(KeyboardEvent),
+    /// An event that occurs when an IME updates its in-progress (composing) text within a _focused_ widget
+    ///
+    /// The `text` is not yet committed and should be rendered at the widget's caret position, in place
+    /// of any previous preedit text. To indicate that it's still being composed, widgets should underline
+    /// the entire preedit `text`. The `cursor`, if any, is the `(start, end)` byte range within `text`
+    /// that the IME is currently targeting and should be rendered with a thicker underline (or as a caret
+    /// if `start == end`).
+    ///
+    /// An empty `text` means the composition was cleared and any rendered preedit text should be removed.
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// An event that occurs when an IME commits its finalized text within a _focused_ widget
+    ///
+    /// This replaces any current preedit text (see [`ImePreedit`](Self::ImePreedit)) and should be
+    /// inserted just like regular [`CharInput`](Self::CharInput).
+    ImeCommit(String),
 }
 
 impl Eq for EventType {}
@@ -122,6 +169,8 @@ pub enum EventCategory {
     Keyboard,
     /// A category for events related to focus
     Focus,
+    /// A category for events related to touches and gestures
+    Touch,
 }
 
 impl EventType {
@@ -137,14 +186,24 @@ impl EventType {
             Self::MouseDown(..) => true,
             Self::MouseUp(..) => true,
             Self::Scroll(..) => true,
+            Self::TouchStart(..) => true,
+            Self::TouchMove(..) => true,
+            Self::TouchEnd(..) => true,
             Self::CharInput { .. } => true,
             Self::KeyUp(..) => true,
             Self::KeyDown(..) => true,
+            Self::ImePreedit { .. } => true,
+            Self::ImeCommit(..) => true,
             // Doesn't Propagate
             Self::MouseIn(..) => false,
             Self::MouseOut(..) => false,
             Self::Focus => false,
             Self::Blur => false,
+            // Gestures are sent directly to the widget that recognizes them
+            Self::Tap(..) => false,
+            Self::LongPress(..) => false,
+            Self::Pan(..) => false,
+            Self::Pinch(..) => false,
         }
     }
 
@@ -163,9 +222,19 @@ impl EventType {
             Self::CharInput { .. } => EventCategory::Keyboard,
             Self::KeyUp(..) => EventCategory::Keyboard,
             Self::KeyDown(..) => EventCategory::Keyboard,
+            Self::ImePreedit { .. } => EventCategory::Keyboard,
+            Self::ImeCommit(..) => EventCategory::Keyboard,
             // Focus
             Self::Focus => EventCategory::Focus,
             Self::Blur => EventCategory::Focus,
+            // Touch
+            Self::TouchStart(..) => EventCategory::Touch,
+            Self::TouchMove(..) => EventCategory::Touch,
+            Self::TouchEnd(..) => EventCategory::Touch,
+            Self::Tap(..) => EventCategory::Touch,
+            Self::LongPress(..) => EventCategory::Touch,
+            Self::Pan(..) => EventCategory::Touch,
+            Self::Pinch(..) => EventCategory::Touch,
         }
     }
 }
@@ -1,14 +1,17 @@
 use crate::flo_binding::{Binding, MutableBound};
 
 use crate::cursor::{CursorEvent, ScrollEvent, ScrollUnit};
+use crate::gesture::GestureRecognizer;
 use crate::layout_cache::Rect;
 use crate::render_command::RenderCommand;
 use crate::widget_manager::WidgetManager;
 use crate::{
-    BoxedWidget, Event, EventType, Index, InputEvent, InputEventCategory, KayakContext,
-    KayakContextRef, KeyCode, KeyboardEvent, KeyboardModifiers, PointerEvents,
+    Event, EventType, Gestures, Index, InputEvent, InputEventCategory, KayakContext,
+    KayakContextRef, KeyCode, KeyboardEvent, KeyboardModifiers, PointerEvents, TouchEvent,
+    TouchPhase,
 };
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 type EventMap = HashMap<Index, HashSet<EventType>>;
 type TreeNode = (
@@ -43,6 +46,7 @@ pub(crate) struct EventDispatcher {
     next_mouse_position: (f32, f32),
     previous_events: EventMap,
     keyboard_modifiers: KeyboardModifiers,
+    gestures: GestureRecognizer,
     pub last_clicked: Binding<Index>,
     contains_cursor: Option<bool>,
     wants_cursor: Option<bool>,
@@ -61,6 +65,7 @@ impl EventDispatcher {
             next_mouse_position: Default::default(),
             previous_events: Default::default(),
             keyboard_modifiers: Default::default(),
+            gestures: Default::default(),
             contains_cursor: None,
             wants_cursor: None,
             has_cursor: None,
@@ -176,6 +181,35 @@ impl EventDispatcher {
         // === Dispatch Events === //
         let mut next_events = HashMap::default();
         for mut event in events {
+            if let EventType::KeyDown(key_event) = &event.event_type {
+                // Shortcuts are handled before (and instead of) delivering the key to the focused widget
+                if context.dispatch_shortcut(key_event) {
+                    continue;
+                }
+
+                if context.widget_manager.focus_tree.current().is_none() {
+                    // Nothing is focused, so there's no one else to deliver the key to
+                    continue;
+                }
+            }
+
+            if Self::is_blocked(&event.event_type, event.target, &context.widget_manager) {
+                // Disabled widgets don't receive (or propagate) interaction events
+                continue;
+            }
+
+            // --- Capture Phase --- //
+            // The target still counts as having received the event, even if it ends up being captured,
+            // so that stateful events (such as `MouseIn`) aren't re-sent
+            Self::insert_event(&mut next_events, &event.target, event.event_type.clone());
+            if self.capture_event(&mut event, context) {
+                if !event.default_prevented {
+                    self.execute_default(event, context);
+                }
+                continue;
+            }
+
+            // --- Bubble Phase --- //
             let mut current_target: Option<Index> = Some(event.target);
             while let Some(index) = current_target {
                 // Create a copy of the event, specific for this node
@@ -183,17 +217,19 @@ impl EventDispatcher {
                 // (e.g., changing the event type, removing the target, etc.)
                 let mut node_event = Event {
                     current_target: index,
-                    ..event
+                    ..event.clone()
                 };
 
                 // --- Update State --- //
-                Self::insert_event(&mut next_events, &index, node_event.event_type);
+                Self::insert_event(&mut next_events, &index, node_event.event_type.clone());
 
                 // --- Call Event --- //
-                let mut target_widget = context.widget_manager.take(index);
-                let mut ctx = KayakContextRef::new(context, Some(index));
-                target_widget.on_event(&mut ctx, &mut node_event);
-                context.widget_manager.repossess(target_widget);
+                if !Self::is_blocked(&node_event.event_type, index, &context.widget_manager) {
+                    let mut target_widget = context.widget_manager.take(index);
+                    let mut ctx = KayakContextRef::new(context, Some(index));
+                    target_widget.on_event(&mut ctx, &mut node_event);
+                    context.widget_manager.repossess(target_widget);
+                }
 
                 event.default_prevented |= node_event.default_prevented;
 
@@ -244,6 +280,51 @@ impl EventDispatcher {
         self.previous_events = next_events;
     }
 
+    /// Runs the capture phase of the given event, from the root down to (and including) its target
+    ///
+    /// Returns true if a capture handler stopped the event from propagating any further.
+    fn capture_event(&mut self, event: &mut Event, context: &mut KayakContext) -> bool {
+        let mut path = vec![event.target];
+        while let Some(parent) = context
+            .widget_manager
+            .node_tree
+            .get_parent(path[path.len() - 1])
+        {
+            path.push(parent);
+        }
+
+        for index in path.into_iter().rev() {
+            if Self::is_blocked(&event.event_type, index, &context.widget_manager) {
+                continue;
+            }
+
+            let on_event_capture = context
+                .widget_manager
+                .current_widgets
+                .get(index)
+                .and_then(|widget| widget.as_ref())
+                .and_then(|widget| widget.get_props().get_on_event_capture());
+            if let Some(on_event_capture) = on_event_capture {
+                // Capturing always starts out as propagating, even for events that don't bubble
+                let mut node_event = Event {
+                    current_target: index,
+                    should_propagate: true,
+                    ..event.clone()
+                };
+
+                let mut ctx = KayakContextRef::new(context, Some(index));
+                on_event_capture.try_call(&mut ctx, &mut node_event);
+
+                event.default_prevented |= node_event.default_prevented;
+                if !node_event.should_propagate {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
     /// Generates a stream of [Events](crate::Event) from a set of [InputEvents](crate::InputEvent)
     fn build_event_stream(
         &mut self,
@@ -367,13 +448,33 @@ impl EventDispatcher {
             event_stream.extend(events);
         }
 
+        // === Touch Events === //
+        let now = Instant::now();
+        for input_event in input_events {
+            if let InputEvent::Touch {
+                id,
+                phase,
+                position,
+            } = input_event
+            {
+                let events = self.process_touch_event(*id, *phase, *position, now, widget_manager);
+                event_stream.extend(events);
+            }
+        }
+
+        // Some gestures (such as long-presses) are recognized without any new input
+        let events = self.gestures.update(now, |origin, gesture| {
+            Self::find_gesture_target(origin, gesture, widget_manager)
+        });
+        event_stream.extend(events);
+
         // === Additional Events === //
         let mut had_focus_event = false;
 
         // These events are ones that require a specific target and need the tree to be evaluated before selecting the best match
         for (event_type, state) in states {
             if let Some(node) = state.best_match {
-                event_stream.push(Event::new(node, event_type));
+                event_stream.push(Event::new(node, event_type.clone()));
 
                 match event_type {
                     EventType::Focus => {
@@ -458,11 +559,9 @@ impl EventDispatcher {
                         }
                     }
                     if self.contains_cursor.is_none() || !self.contains_cursor.unwrap_or_default() {
-                        if let Some(widget) = widget_manager.current_widgets.get(node).unwrap() {
-                            // Check if the cursor moved onto a widget that qualifies as one that can contain it
-                            if ignore_layout || Self::can_contain_cursor(widget) {
-                                self.contains_cursor = Some(is_contained);
-                            }
+                        // Check if the cursor moved onto a widget that qualifies as one that can contain it
+                        if ignore_layout || Self::can_contain_cursor(node, widget_manager) {
+                            self.contains_cursor = Some(is_contained);
                         }
                     }
 
@@ -497,14 +596,11 @@ impl EventDispatcher {
                             }
                         }
 
-                        if self.has_cursor.is_none() {
-                            let widget = widget_manager.current_widgets.get(node).unwrap();
-                            if let Some(widget) = widget {
-                                // Check if the cursor moved onto a widget that qualifies as one that can contain it
-                                if Self::can_contain_cursor(widget) {
-                                    self.has_cursor = Some(node);
-                                }
-                            }
+                        // Check if the cursor moved onto a widget that qualifies as one that can contain it
+                        if self.has_cursor.is_none()
+                            && Self::can_contain_cursor(node, widget_manager)
+                        {
+                            self.has_cursor = Some(node);
                         }
                     }
                 }
@@ -556,13 +652,30 @@ impl EventDispatcher {
         event_stream
     }
 
+    /// Checks if the given event should be blocked from reaching the given widget because it's disabled
+    ///
+    /// Only [`Blur`](EventType::Blur) is allowed through, so that a widget disabled while focused can
+    /// still clean up its focus state.
+    fn is_blocked(event_type: &EventType, index: Index, widget_manager: &WidgetManager) -> bool {
+        !matches!(event_type, EventType::Blur) && widget_manager.is_disabled(index)
+    }
+
+    /// Resolves the [`PointerEvents`] style of a widget
+    ///
+    /// Hidden widgets never receive pointer events themselves, though their children still might.
     fn resolve_pointer_events(index: Index, widget_manager: &WidgetManager) -> PointerEvents {
         let mut pointer_events = PointerEvents::default();
-        if let Some(widget) = widget_manager.current_widgets.get(index).unwrap() {
-            if let Some(styles) = widget.get_props().get_styles() {
-                pointer_events = styles.pointer_events.resolve();
-            }
+        if let Some(styles) = widget_manager.get_styles(index) {
+            pointer_events = styles.pointer_events.resolve();
+        }
+
+        if widget_manager.is_hidden(index) {
+            pointer_events = match pointer_events {
+                PointerEvents::All | PointerEvents::ChildrenOnly => PointerEvents::ChildrenOnly,
+                PointerEvents::SelfOnly | PointerEvents::None => PointerEvents::None,
+            };
         }
+
         pointer_events
     }
 
@@ -584,30 +697,44 @@ impl EventDispatcher {
         widget_manager: &WidgetManager,
     ) -> Vec<Event> {
         let mut event_stream = Vec::new();
+
+        // === Modifers === //
+        // These are tracked even when nothing is focused so that shortcuts still work
+        if let InputEvent::Keyboard { key, is_pressed } = input_event {
+            match key {
+                KeyCode::LControl | KeyCode::RControl => {
+                    self.keyboard_modifiers.is_ctrl_pressed = *is_pressed
+                }
+                KeyCode::LShift | KeyCode::RShift => {
+                    self.keyboard_modifiers.is_shift_pressed = *is_pressed
+                }
+                KeyCode::LAlt | KeyCode::RAlt => {
+                    self.keyboard_modifiers.is_alt_pressed = *is_pressed
+                }
+                KeyCode::LWin | KeyCode::RWin => {
+                    self.keyboard_modifiers.is_meta_pressed = *is_pressed
+                }
+                _ => {}
+            }
+        }
+
         if let Some(current_focus) = widget_manager.focus_tree.current() {
             match input_event {
                 InputEvent::CharEvent { c } => {
                     event_stream.push(Event::new(current_focus, EventType::CharInput { c: *c }))
                 }
+                InputEvent::ImePreedit { text, cursor } => event_stream.push(Event::new(
+                    current_focus,
+                    EventType::ImePreedit {
+                        text: text.clone(),
+                        cursor: *cursor,
+                    },
+                )),
+                InputEvent::ImeCommit(text) => event_stream.push(Event::new(
+                    current_focus,
+                    EventType::ImeCommit(text.clone()),
+                )),
                 InputEvent::Keyboard { key, is_pressed } => {
-                    // === Modifers === //
-                    match key {
-                        KeyCode::LControl | KeyCode::RControl => {
-                            self.keyboard_modifiers.is_ctrl_pressed = *is_pressed
-                        }
-                        KeyCode::LShift | KeyCode::RShift => {
-                            self.keyboard_modifiers.is_shift_pressed = *is_pressed
-                        }
-                        KeyCode::LAlt | KeyCode::RAlt => {
-                            self.keyboard_modifiers.is_alt_pressed = *is_pressed
-                        }
-                        KeyCode::LWin | KeyCode::RWin => {
-                            self.keyboard_modifiers.is_meta_pressed = *is_pressed
-                        }
-                        _ => {}
-                    }
-
-                    // === Event === //
                     if *is_pressed {
                         event_stream.push(Event::new(
                             current_focus,
@@ -622,11 +749,76 @@ impl EventDispatcher {
                 }
                 _ => {}
             }
+        } else if let InputEvent::Keyboard {
+            key,
+            is_pressed: true,
+        } = input_event
+        {
+            // Without a focused widget, key presses can only trigger shortcuts (see `dispatch_events`)
+            if let Some(root) = widget_manager.tree.root_node {
+                event_stream.push(Event::new(
+                    root,
+                    EventType::KeyDown(KeyboardEvent::new(*key, self.keyboard_modifiers)),
+                ));
+            }
+        }
+
+        event_stream
+    }
+
+    /// Process a touch, along with any gestures it completes
+    fn process_touch_event(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: (f32, f32),
+        now: Instant,
+        widget_manager: &WidgetManager,
+    ) -> Vec<Event> {
+        let mut event_stream = Vec::new();
+
+        // Touches stay with the widget they started on, similar to capturing the cursor
+        let origin = match phase {
+            TouchPhase::Started => Self::hit_test(position, widget_manager),
+            _ => self.gestures.origin(id),
+        };
+
+        if let Some(origin) = origin {
+            let touch_event = TouchEvent { id, position };
+            let event_type = match phase {
+                TouchPhase::Started => EventType::TouchStart(touch_event),
+                TouchPhase::Moved => EventType::TouchMove(touch_event),
+                TouchPhase::Ended | TouchPhase::Cancelled => EventType::TouchEnd(touch_event),
+            };
+            event_stream.push(Event::new(origin, event_type));
         }
 
+        let events =
+            self.gestures
+                .process_touch(id, phase, position, origin, now, |origin, gesture| {
+                    Self::find_gesture_target(origin, gesture, widget_manager)
+                });
+        event_stream.extend(events);
+
         event_stream
     }
 
+    /// Finds the nearest widget at or above the given one that recognizes the given gesture
+    fn find_gesture_target(
+        index: Index,
+        gesture: Gestures,
+        widget_manager: &WidgetManager,
+    ) -> Option<Index> {
+        let mut current = Some(index);
+        while let Some(index) = current {
+            if widget_manager.get_gestures(index).contains(gesture) {
+                return Some(index);
+            }
+            current = widget_manager.tree.get_parent(index);
+        }
+        None
+    }
+
     /// Updates the state data for the given event
     fn update_state(
         states: &mut HashMap<EventType, EventState>,
@@ -635,7 +827,14 @@ impl EventDispatcher {
         event_type: EventType,
     ) {
         let state = states.entry(event_type).or_insert(EventState::default());
+        Self::update_best_match(state, tree_node, layout);
+    }
 
+    /// Updates the best match of the given state if the given node is a better fit
+    ///
+    /// A node is a better fit if it's above the current best z-level, or if it's at least as deep as
+    /// the current best match while being at or above the current best z-level.
+    fn update_best_match(state: &mut EventState, tree_node: TreeNode, layout: &Rect) {
         let (node, depth) = tree_node;
         // Node is at or above best depth and is at or above best z-level
         let mut should_update = depth >= state.best_depth && layout.z_index >= state.best_z_index;
@@ -649,6 +848,64 @@ impl EventDispatcher {
         }
     }
 
+    /// Finds the topmost widget at the given point
+    ///
+    /// This uses the same rules as pointer event dispatch for picking the best match between
+    /// overlapping widgets and for respecting the [`PointerEvents`] style. Additionally, widgets
+    /// that lie outside the bounds of a clipping ancestor (i.e. those that aren't visible) can't be hit.
+    pub(crate) fn hit_test(point: (f32, f32), widget_manager: &WidgetManager) -> Option<Index> {
+        let root = widget_manager.node_tree.root_node?;
+        let mut state = EventState::default();
+
+        let mut stack: Vec<TreeNode> = vec![(root, 0)];
+        while let Some((current, depth)) = stack.pop() {
+            let layout = widget_manager.get_layout(&current);
+            let is_contained = layout.map_or(false, |layout| layout.contains(&point));
+
+            let mut enter_children = true;
+            // A widget's PointerEvents style will determine how it and its children are processed
+            let pointer_events = Self::resolve_pointer_events(current, widget_manager);
+            match pointer_events {
+                PointerEvents::All | PointerEvents::SelfOnly => {
+                    if let (true, Some(layout)) = (is_contained, layout) {
+                        Self::update_best_match(&mut state, (current, depth), layout);
+                    }
+
+                    if matches!(pointer_events, PointerEvents::SelfOnly) {
+                        enter_children = false;
+                    }
+                }
+                PointerEvents::None => enter_children = false,
+                PointerEvents::ChildrenOnly => {}
+            }
+
+            // Children outside of a clip are not visible
+            if !is_contained && Self::is_clip(current, widget_manager) {
+                enter_children = false;
+            }
+
+            // --- Push Children to Stack --- //
+            if enter_children {
+                if let Some(children) = widget_manager.node_tree.children.get(&current) {
+                    for child in children {
+                        stack.push((*child, depth + 1));
+                    }
+                }
+            }
+        }
+
+        state.best_match
+    }
+
+    /// Checks if the given widget clips its children
+    fn is_clip(index: Index, widget_manager: &WidgetManager) -> bool {
+        if let Some(Some(node)) = widget_manager.nodes.get(index) {
+            node.resolved_styles.render_command.resolve() == RenderCommand::Clip
+        } else {
+            false
+        }
+    }
+
     /// Checks if the given event map contains a specific event for the given widget
     fn contains_event(events: &EventMap, widget_id: &Index, event_type: &EventType) -> bool {
         if let Some(entry) = events.get(widget_id) {
@@ -667,9 +924,10 @@ impl EventDispatcher {
     /// Checks if the given widget is eligible to "contain" the cursor (i.e. the cursor is considered contained when hovering over it)
     ///
     /// Currently a valid widget is defined as one where:
-    /// * RenderCommands is neither `Empty` nor `Layout` nor `Clip`
-    fn can_contain_cursor(widget: &BoxedWidget) -> bool {
-        if let Some(styles) = widget.get_props().get_styles() {
+    /// * RenderCommands is neither `Empty` nor `Layout` nor `Clip` (including any styles forwarded
+    ///   to it by its parent)
+    fn can_contain_cursor(index: Index, widget_manager: &WidgetManager) -> bool {
+        if let Some(styles) = widget_manager.get_styles(index) {
             let cmds = styles.render_command.resolve();
             !matches!(
                 cmds,
@@ -733,6 +991,7 @@ impl EventDispatcher {
         self.next_mouse_position = from.next_mouse_position;
         self.previous_events = from.previous_events;
         self.keyboard_modifiers = from.keyboard_modifiers;
+        self.gestures = from.gestures;
         self.contains_cursor = from.contains_cursor;
         self.wants_cursor = from.wants_cursor;
         self.has_cursor = from.has_cursor;
@@ -742,3 +1001,71 @@ impl EventDispatcher {
         // self.cursor_capture = from.cursor_capture;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use kayak_render_macros::{widget, WidgetProps};
+    use std::sync::{Arc, Mutex};
+
+    use crate::{mount_widget, EventType, Fragment, InputEvent, KayakContext, OnEvent, Widget};
+
+    /// The IME events received, in order
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct InputProps {
+        #[prop_field(Focusable)]
+        focusable: Option<bool>,
+        #[prop_field(Autofocus)]
+        autofocus: bool,
+        #[prop_field(OnEvent)]
+        on_event: Option<OnEvent>,
+    }
+
+    #[widget]
+    fn Input(props: InputProps) {
+        context.add_widget(Fragment::default(), 0);
+    }
+
+    /// Mounts an autofocused `Input` as the root, returning the log of the IME events it received
+    fn mount(context: &mut KayakContext) -> Log {
+        let log = Log::default();
+        let on_ime = log.clone();
+        let on_event = OnEvent::new(move |_, event| match &event.event_type {
+            EventType::ImePreedit { text, cursor } => on_ime
+                .lock()
+                .unwrap()
+                .push(format!("preedit {} {:?}", text, cursor)),
+            EventType::ImeCommit(text) => on_ime.lock().unwrap().push(format!("commit {}", text)),
+            _ => {}
+        });
+        let props = InputProps {
+            focusable: Some(true),
+            autofocus: true,
+            on_event: Some(on_event),
+        };
+        mount_widget(context, Input::constructor(props));
+        log
+    }
+
+    #[test]
+    fn should_send_ime_events_to_the_focused_widget() {
+        let mut context = KayakContext::new();
+        let log = mount(&mut context);
+        context.process_events(vec![
+            InputEvent::ImePreedit {
+                text: String::from("かな"),
+                cursor: Some((0, 3)),
+            },
+            InputEvent::ImePreedit {
+                text: String::new(),
+                cursor: None,
+            },
+            InputEvent::ImeCommit(String::from("仮名")),
+        ]);
+        assert_eq!(
+            vec!["preedit かな Some((0, 3))", "preedit  None", "commit 仮名"],
+            *log.lock().unwrap()
+        );
+    }
+}
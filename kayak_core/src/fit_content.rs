@@ -0,0 +1,578 @@
+//! Sizing of containers to their content
+//!
+//! A row or column with an `Auto` width or height is sized to fit its flow children: along its
+//! layout axis (i.e. the width of a row) it's as large as all of them combined, plus the spacing
+//! between them, while along the other axis it's as large as the largest of them. Either way, its
+//! pixel padding and border are included and the result is clamped to its min/max.
+//!
+//! Sizes are calculated from the innermost containers outwards, so that nested containers fit
+//! the final size of their children rather than the one given by the first layout pass. Layout is
+//! then calculated again with the resulting sizes.
+//!
+//! Containers with an [intrinsic size](IntrinsicSize) shrink-to-fit their parent instead: their
+//! `Auto` width is their max-content width, limited by the space available within their parent
+//! but never less than their min-content width. Text within them wraps to that same space.
+
+use std::collections::HashMap;
+
+use morphorm::{Node as LayoutNode, PositionType, Units};
+
+use crate::flex::{flow_direction, insets, is_wrapping, pixels, resolve_units, SIZE_EPSILON};
+use crate::layout_cache::{IntrinsicSize, LayoutCache};
+use crate::node::Node;
+use crate::styles::StyleProp;
+use crate::{Arena, Index};
+
+/// Returns the widest the content of the given node may be if it's sized to its content
+///
+/// This is the smaller of the space available to the node (such as the width of its parent, if
+/// known) and its pixel max width, less its pixel padding and border. Text within such a node is measured
+/// against this width, since the node's own width depends on that text.
+pub(crate) fn max_content_width(
+    id: Index,
+    nodes: &Arena<Option<Node>>,
+    available: Option<f32>,
+) -> Option<f32> {
+    let node = match nodes.get(id) {
+        Some(Some(node)) => node,
+        _ => return None,
+    };
+    if node.resolved_styles.width != StyleProp::Value(crate::styles::Units::Auto) {
+        return None;
+    }
+
+    let max = match id.max_width(nodes) {
+        Some(Units::Pixels(max)) => Some(max),
+        _ => None,
+    };
+    let available =
+        available.map(|available| available - pixels(id.left(nodes)) - pixels(id.right(nodes)));
+    let (inset_start, inset_end) = insets(id, nodes, true);
+    let padding = inset_start + inset_end;
+    match (max, available) {
+        (Some(max), Some(available)) => Some((max.min(available) - padding).max(0.0)),
+        (Some(size), None) | (None, Some(size)) => Some((size - padding).max(0.0)),
+        (None, None) => Some(f32::MAX),
+    }
+}
+
+/// Calculate the intrinsic sizes of the given node and its descendants
+///
+/// Nodes with measured content (i.e. text) use their measured size and nodes with a pixel width
+/// are as wide as that width. Containers combine the intrinsic sizes of their flow children (plus
+/// their pixel margins) along their layout axis, adding their spacing, pixel padding and border.
+/// A wrapping row is only as narrow as its narrowest line allows: its widest child. Children
+/// whose width depends on their parent's (such as stretched children) don't contribute.
+///
+/// Returns the intrinsic size of each node that has one.
+pub(crate) fn calculate_intrinsic_sizes(
+    nodes: &Arena<Option<Node>>,
+    children: &HashMap<Index, Vec<Index>>,
+    root: Index,
+    measured_sizes: &HashMap<Index, IntrinsicSize>,
+) -> HashMap<Index, IntrinsicSize> {
+    let mut sizes = HashMap::new();
+    intrinsic_size(root, nodes, children, measured_sizes, &mut sizes);
+    sizes
+}
+
+fn intrinsic_size(
+    id: Index,
+    nodes: &Arena<Option<Node>>,
+    children: &HashMap<Index, Vec<Index>>,
+    measured_sizes: &HashMap<Index, IntrinsicSize>,
+    sizes: &mut HashMap<Index, IntrinsicSize>,
+) -> Option<IntrinsicSize> {
+    let child_sizes = children
+        .get(&id)
+        .into_iter()
+        .flatten()
+        .filter_map(|child_id| {
+            let size = intrinsic_size(*child_id, nodes, children, measured_sizes, sizes)?;
+            if matches!(
+                child_id.position_type(nodes),
+                Some(PositionType::SelfDirected)
+            ) {
+                return None;
+            }
+            let margins = pixels(child_id.left(nodes)) + pixels(child_id.right(nodes));
+            Some(IntrinsicSize {
+                min_content: size.min_content + margins,
+                max_content: size.max_content + margins,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let size = if let Some(measured) = measured_sizes.get(&id) {
+        Some(*measured)
+    } else if let Some(Units::Pixels(width)) = id.width(nodes) {
+        Some(IntrinsicSize {
+            min_content: width,
+            max_content: width,
+        })
+    } else if child_sizes.is_empty() {
+        None
+    } else {
+        let node = nodes.get(id).and_then(Option::as_ref);
+        let is_row = node.and_then(flow_direction) == Some(true);
+        let wraps = node.map_or(false, is_wrapping);
+        let (inset_start, inset_end) = insets(id, nodes, true);
+        let padding = inset_start + inset_end;
+
+        // Rows place children side-by-side, while columns stack them
+        let (min_content, max_content) = if is_row {
+            let between = pixels(id.col_between(nodes)) * (child_sizes.len() - 1) as f32;
+            let (min_content, max_content) = child_sizes
+                .iter()
+                .fold((between, between), |(min, max), size| {
+                    (min + size.min_content, max + size.max_content)
+                });
+            if wraps {
+                let widest = child_sizes
+                    .iter()
+                    .fold(0.0f32, |widest, size| widest.max(size.min_content));
+                (widest, max_content)
+            } else {
+                (min_content, max_content)
+            }
+        } else {
+            child_sizes
+                .iter()
+                .fold((0.0f32, 0.0f32), |(min, max), size| {
+                    (min.max(size.min_content), max.max(size.max_content))
+                })
+        };
+        Some(IntrinsicSize {
+            min_content: min_content + padding,
+            max_content: max_content + padding,
+        })
+    };
+
+    if let Some(size) = size {
+        sizes.insert(id, size);
+    }
+    size
+}
+
+/// Calculate the size of a container along a single axis from the sizes of its children
+///
+/// Returns `None` if none of its children contribute to its size.
+fn fit_axis(
+    id: Index,
+    child_sizes: &[(Index, (f32, f32))],
+    nodes: &Arena<Option<Node>>,
+    horizontal: bool,
+    is_main: bool,
+) -> Option<f32> {
+    let extents = child_sizes
+        .iter()
+        .filter(|(child_id, _)| {
+            !matches!(
+                child_id.position_type(nodes),
+                Some(PositionType::SelfDirected)
+            )
+        })
+        .filter_map(|(child_id, (width, height))| {
+            let (units, start, end, size) = if horizontal {
+                (
+                    child_id.width(nodes),
+                    child_id.left(nodes),
+                    child_id.right(nodes),
+                    width,
+                )
+            } else {
+                (
+                    child_id.height(nodes),
+                    child_id.top(nodes),
+                    child_id.bottom(nodes),
+                    height,
+                )
+            };
+
+            // These depend on the size of the container itself
+            if matches!(
+                units,
+                Some(Units::Stretch(..)) | Some(Units::Percentage(..))
+            ) {
+                return None;
+            }
+
+            Some(size + pixels(start) + pixels(end))
+        })
+        .collect::<Vec<_>>();
+    if extents.is_empty() {
+        return None;
+    }
+
+    let between = if horizontal {
+        pixels(id.col_between(nodes))
+    } else {
+        pixels(id.row_between(nodes))
+    };
+    let (inset_start, inset_end) = insets(id, nodes, horizontal);
+    let padding = inset_start + inset_end;
+
+    let content = if is_main {
+        extents.iter().sum::<f32>() + between * (extents.len() - 1) as f32
+    } else {
+        extents.iter().copied().fold(0.0, f32::max)
+    };
+    Some(content + padding)
+}
+
+/// Fit the given node and its descendants to their content
+///
+/// Returns the size of the node after fitting, or `None` if it has no layout.
+fn fit_node(
+    id: Index,
+    nodes: &Arena<Option<Node>>,
+    children: &HashMap<Index, Vec<Index>>,
+    layout_cache: &LayoutCache,
+    parent_size: (f32, f32),
+    overrides: &mut Vec<(Index, Option<f32>, Option<f32>)>,
+) -> Option<(f32, f32)> {
+    let rect = layout_cache.rect.get(&id)?;
+    let mut size = (rect.width, rect.height);
+
+    let child_sizes = children
+        .get(&id)
+        .into_iter()
+        .flatten()
+        .filter_map(|child_id| {
+            fit_node(*child_id, nodes, children, layout_cache, size, overrides)
+                .map(|child_size| (*child_id, child_size))
+        })
+        .collect::<Vec<_>>();
+
+    let node = match nodes.get(id) {
+        Some(Some(node)) => node,
+        _ => return Some(size),
+    };
+    // Stacks are sized separately (see `stack`)
+    let is_row = match flow_direction(node) {
+        Some(is_row) => is_row,
+        None => return Some(size),
+    };
+    let wraps = is_wrapping(node);
+
+    let mut width = None;
+    let mut height = None;
+    for horizontal in [true, false] {
+        let is_main = horizontal == is_row;
+        // The cross size of a wrapping container depends on its lines (see `flex`)
+        if wraps && !is_main {
+            continue;
+        }
+
+        let (units, min, max, current, parent) = if horizontal {
+            (
+                id.width(nodes),
+                id.min_width(nodes),
+                id.max_width(nodes),
+                size.0,
+                parent_size.0,
+            )
+        } else {
+            (
+                id.height(nodes),
+                id.min_height(nodes),
+                id.max_height(nodes),
+                size.1,
+                parent_size.1,
+            )
+        };
+        if units != Some(Units::Auto) {
+            continue;
+        }
+
+        // Containers with an intrinsic size shrink-to-fit the space left by their margins
+        let intrinsic = layout_cache.intrinsic_size(&id).filter(|_| horizontal);
+        let content = match intrinsic {
+            Some(intrinsic) => {
+                let margins = pixels(id.left(nodes)) + pixels(id.right(nodes));
+                intrinsic.fit(parent - margins)
+            }
+            None => match fit_axis(id, &child_sizes, nodes, horizontal, is_main) {
+                Some(content) => content,
+                None => continue,
+            },
+        };
+        let min = min
+            .and_then(|units| resolve_units(units, parent))
+            .unwrap_or(0.0);
+        let max = max
+            .and_then(|units| resolve_units(units, parent))
+            .unwrap_or(f32::INFINITY);
+        // If the constraints conflict, the minimum wins
+        let fitted = content.min(max).max(min);
+        if (fitted - current).abs() <= SIZE_EPSILON {
+            continue;
+        }
+
+        if horizontal {
+            width = Some(fitted);
+            size.0 = fitted;
+        } else {
+            height = Some(fitted);
+            size.1 = fitted;
+        }
+    }
+
+    if width.is_some() || height.is_some() {
+        overrides.push((id, width, height));
+    }
+
+    Some(size)
+}
+
+/// Calculate the sizes of all containers that are sized to their content
+///
+/// Returns the overridden `(width, height)` for each container whose size differs from the one
+/// given by the first layout pass.
+pub(crate) fn calculate_content_sizes(
+    nodes: &Arena<Option<Node>>,
+    children: &HashMap<Index, Vec<Index>>,
+    root: Index,
+    layout_cache: &LayoutCache,
+) -> Vec<(Index, Option<f32>, Option<f32>)> {
+    let mut overrides = Vec::new();
+    let root_size = layout_cache
+        .rect
+        .get(&root)
+        .map_or((0.0, 0.0), |rect| (rect.width, rect.height));
+    fit_node(
+        root,
+        nodes,
+        children,
+        layout_cache,
+        root_size,
+        &mut overrides,
+    );
+    overrides
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{calculate_content_sizes, calculate_intrinsic_sizes, max_content_width};
+    use crate::layout_cache::{IntrinsicSize, LayoutCache, Rect};
+    use crate::node::{Node, NodeBuilder};
+    use crate::styles::{Edge, FlexWrap, LayoutType, Style, StyleProp, Units};
+    use crate::{Arena, Index};
+
+    fn rect(width: f32, height: f32) -> Rect {
+        Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    fn intrinsic(min_content: f32, max_content: f32) -> IntrinsicSize {
+        IntrinsicSize {
+            min_content,
+            max_content,
+        }
+    }
+
+    fn leaf(nodes: &mut Arena<Option<Node>>, id: Index, width: Units, height: f32) {
+        nodes[id] = Some(
+            NodeBuilder::new(
+                id,
+                Style {
+                    width: StyleProp::Value(width),
+                    height: StyleProp::Value(Units::Pixels(height)),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+    }
+
+    /// Builds a column containing a row of three children, where both containers are `Auto` sized
+    fn nested(outer_style: Style) -> (Arena<Option<Node>>, [Index; 5]) {
+        let mut nodes = Arena::new();
+        let outer = nodes.insert(None);
+        let inner = nodes.insert(None);
+        let icon = nodes.insert(None);
+        let label = nodes.insert(None);
+        let spacer = nodes.insert(None);
+
+        nodes[outer] = Some(
+            NodeBuilder::new(
+                outer,
+                Style {
+                    layout_type: StyleProp::Value(LayoutType::Column),
+                    width: StyleProp::Value(Units::Auto),
+                    height: StyleProp::Value(Units::Auto),
+                    padding: StyleProp::Value(Edge::all(Units::Pixels(5.0))),
+                    ..outer_style
+                },
+            )
+            .build(),
+        );
+        nodes[inner] = Some(
+            NodeBuilder::new(
+                inner,
+                Style {
+                    layout_type: StyleProp::Value(LayoutType::Row),
+                    width: StyleProp::Value(Units::Auto),
+                    height: StyleProp::Value(Units::Auto),
+                    col_between: StyleProp::Value(Units::Pixels(10.0)),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+        leaf(&mut nodes, icon, Units::Pixels(20.0), 10.0);
+        leaf(&mut nodes, label, Units::Pixels(30.0), 15.0);
+        leaf(&mut nodes, spacer, Units::Stretch(1.0), 5.0);
+
+        (nodes, [outer, inner, icon, label, spacer])
+    }
+
+    fn layout(ids: &[Index; 5]) -> (HashMap<Index, Vec<Index>>, LayoutCache) {
+        let [outer, inner, icon, label, spacer] = *ids;
+
+        let mut layout_cache = LayoutCache::default();
+        layout_cache.rect.insert(outer, rect(0.0, 0.0));
+        layout_cache.rect.insert(inner, rect(0.0, 0.0));
+        layout_cache.rect.insert(icon, rect(20.0, 10.0));
+        layout_cache.rect.insert(label, rect(30.0, 15.0));
+        layout_cache.rect.insert(spacer, rect(0.0, 5.0));
+
+        let mut children = HashMap::new();
+        children.insert(outer, vec![inner]);
+        children.insert(inner, vec![icon, label, spacer]);
+
+        (children, layout_cache)
+    }
+
+    #[test]
+    fn should_fit_nested_containers() {
+        let (nodes, ids) = nested(Style::new_default());
+        let (children, layout_cache) = layout(&ids);
+        let [outer, inner, ..] = ids;
+
+        // The inner row fits its children side-by-side (ignoring the spacer, which stretches),
+        // and the outer column fits the inner row's final size plus its padding
+        assert_eq!(
+            vec![
+                (inner, Some(60.0), Some(15.0)),
+                (outer, Some(70.0), Some(25.0))
+            ],
+            calculate_content_sizes(&nodes, &children, outer, &layout_cache)
+        );
+    }
+
+    #[test]
+    fn should_clamp_to_constraints() {
+        let (nodes, ids) = nested(Style {
+            max_width: StyleProp::Value(Units::Pixels(50.0)),
+            min_height: StyleProp::Value(Units::Pixels(40.0)),
+            ..Style::new_default()
+        });
+        let (children, layout_cache) = layout(&ids);
+        let [outer, inner, ..] = ids;
+
+        assert_eq!(
+            vec![
+                (inner, Some(60.0), Some(15.0)),
+                (outer, Some(50.0), Some(40.0))
+            ],
+            calculate_content_sizes(&nodes, &children, outer, &layout_cache)
+        );
+    }
+
+    #[test]
+    fn should_calculate_intrinsic_sizes() {
+        let (nodes, ids) = nested(Style::new_default());
+        let (children, _) = layout(&ids);
+        let [outer, inner, icon, label, spacer] = ids;
+
+        let mut measured_sizes = HashMap::new();
+        measured_sizes.insert(label, intrinsic(20.0, 120.0));
+        let sizes = calculate_intrinsic_sizes(&nodes, &children, outer, &measured_sizes);
+
+        // The spacer stretches, so it doesn't contribute to the row, unlike the spacing between
+        // the icon and the label
+        assert_eq!(Some(&intrinsic(20.0, 20.0)), sizes.get(&icon));
+        assert_eq!(None, sizes.get(&spacer));
+        assert_eq!(Some(&intrinsic(50.0, 150.0)), sizes.get(&inner));
+        assert_eq!(Some(&intrinsic(60.0, 160.0)), sizes.get(&outer));
+    }
+
+    #[test]
+    fn should_fit_within_the_border() {
+        let (nodes, ids) = nested(Style {
+            border: StyleProp::Value(Edge::all(2.0)),
+            ..Style::new_default()
+        });
+        let (children, layout_cache) = layout(&ids);
+        let [outer, inner, ..] = ids;
+
+        // The border takes up space within the container, just like the padding
+        assert_eq!(
+            vec![
+                (inner, Some(60.0), Some(15.0)),
+                (outer, Some(74.0), Some(29.0))
+            ],
+            calculate_content_sizes(&nodes, &children, outer, &layout_cache)
+        );
+    }
+
+    #[test]
+    fn wrapping_rows_should_be_as_narrow_as_their_widest_child() {
+        let (mut nodes, ids) = nested(Style::new_default());
+        let (children, _) = layout(&ids);
+        let [outer, inner, _, label, _] = ids;
+        if let Some(Some(node)) = nodes.get_mut(inner) {
+            node.resolved_styles.flex_wrap = StyleProp::Value(FlexWrap::Wrap);
+        }
+
+        let mut measured_sizes = HashMap::new();
+        measured_sizes.insert(label, intrinsic(20.0, 120.0));
+        let sizes = calculate_intrinsic_sizes(&nodes, &children, outer, &measured_sizes);
+
+        // Each child may get a line of its own
+        assert_eq!(Some(&intrinsic(20.0, 150.0)), sizes.get(&inner));
+    }
+
+    #[test]
+    fn should_shrink_to_fit_the_parent() {
+        let (nodes, ids) = nested(Style::new_default());
+        let (children, mut layout_cache) = layout(&ids);
+        let [outer, inner, ..] = ids;
+        layout_cache.rect.insert(outer, rect(100.0, 0.0));
+        layout_cache
+            .intrinsic_size
+            .insert(inner, intrinsic(50.0, 150.0));
+
+        // The inner row is limited to its parent's width, which the outer column then fits
+        assert_eq!(
+            vec![
+                (inner, Some(100.0), Some(15.0)),
+                (outer, Some(110.0), Some(25.0))
+            ],
+            calculate_content_sizes(&nodes, &children, outer, &layout_cache)
+        );
+    }
+
+    #[test]
+    fn should_wrap_content_to_the_available_space() {
+        let (nodes, [outer, ..]) = nested(Style::new_default());
+        assert_eq!(Some(f32::MAX), max_content_width(outer, &nodes, None));
+        assert_eq!(Some(190.0), max_content_width(outer, &nodes, Some(200.0)));
+
+        let (nodes, [outer, ..]) = nested(Style {
+            max_width: StyleProp::Value(Units::Pixels(50.0)),
+            ..Style::new_default()
+        });
+        assert_eq!(Some(40.0), max_content_width(outer, &nodes, None));
+        assert_eq!(Some(40.0), max_content_width(outer, &nodes, Some(200.0)));
+        assert_eq!(Some(20.0), max_content_width(outer, &nodes, Some(30.0)));
+    }
+}
@@ -0,0 +1,1234 @@
+//! Resolution of min/max size constraints for flow layouts
+//!
+//! The layout solver resolves each child's base size and distributes free space in a single
+//! pass, which means `min_*`/`max_*` clamps are not always respected (especially when mixing
+//! units, such as a percentage width with a pixel max-width). This module re-resolves the sizes
+//! of a parent's children following the order used by CSS flexbox:
+//!
+//! 1. Resolve the base size of each child
+//! 2. Clamp inflexible children to their min/max
+//! 3. Distribute the remaining free space to flexible children
+//! 4. Freeze any flexible child that violates its min/max and redistribute the remainder
+//!    to its siblings, repeating until no violations remain
+//!
+//! It also handles the `flex_wrap`, `justify_content` and `align_items` styles, which are applied
+//! as a final pass that breaks each parent's flow children into lines and moves them (and their
+//! descendants) into place.
+
+use std::ops::Range;
+
+use morphorm::{Cache, GeometryChanged, Node as LayoutNode, PositionType, Units};
+
+use crate::layout_cache::LayoutCache;
+use crate::node::Node;
+use crate::styles::{AlignItems, FlexWrap, JustifyContent, LayoutType, StyleProp};
+use crate::tree::Tree;
+use crate::{Arena, Index};
+
+/// The smallest difference (in pixels) that is considered a change in size
+pub(crate) const SIZE_EPSILON: f32 = 0.5;
+
+/// A single child along the main axis of its parent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FlexItem {
+    /// The base size of the item (ignored for flexible items)
+    pub basis: f32,
+    /// The stretch factor of the item, or `0.0` if the item is not flexible
+    pub grow: f32,
+    /// The minimum size of the item
+    pub min: f32,
+    /// The maximum size of the item
+    pub max: f32,
+}
+
+impl FlexItem {
+    /// Create an inflexible item with the given base size
+    pub fn fixed(basis: f32) -> Self {
+        Self {
+            basis,
+            grow: 0.0,
+            min: 0.0,
+            max: f32::INFINITY,
+        }
+    }
+
+    /// Create a flexible item with the given stretch factor
+    pub fn stretch(grow: f32) -> Self {
+        Self {
+            basis: 0.0,
+            grow,
+            min: 0.0,
+            max: f32::INFINITY,
+        }
+    }
+
+    /// Set the min and max constraints of this item
+    pub fn with_constraints(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Clamp the given size to this item's constraints
+    ///
+    /// If the constraints conflict (`min > max`), the minimum wins.
+    fn clamp(&self, size: f32) -> f32 {
+        size.min(self.max).max(self.min)
+    }
+
+    fn is_flexible(&self) -> bool {
+        self.grow > 0.0
+    }
+}
+
+/// Resolve the sizes of the given items within the available space
+///
+/// Items that hit their max return the freed space to their flexible siblings (and
+/// items that hit their min take it from them).
+///
+/// Returns the resolved size of each item, in the same order as given.
+pub(crate) fn resolve_flex(available: f32, items: &[FlexItem]) -> Vec<f32> {
+    let mut sizes = items
+        .iter()
+        .map(|item| item.clamp(item.basis))
+        .collect::<Vec<_>>();
+    let mut frozen = items
+        .iter()
+        .map(|item| !item.is_flexible())
+        .collect::<Vec<_>>();
+
+    while frozen.iter().any(|is_frozen| !is_frozen) {
+        let used: f32 = sizes
+            .iter()
+            .zip(&frozen)
+            .filter(|(_, is_frozen)| **is_frozen)
+            .map(|(size, _)| size)
+            .sum();
+        let free = (available - used).max(0.0);
+        let grow_sum: f32 = items
+            .iter()
+            .zip(&frozen)
+            .filter(|(_, is_frozen)| !**is_frozen)
+            .map(|(item, _)| item.grow)
+            .sum();
+
+        // Distribute free space and record each clamp violation
+        let mut total_violation = 0.0;
+        let mut violations = vec![0.0; items.len()];
+        for (index, item) in items.iter().enumerate() {
+            if frozen[index] {
+                continue;
+            }
+
+            let unclamped = free * item.grow / grow_sum;
+            let clamped = item.clamp(unclamped);
+            violations[index] = clamped - unclamped;
+            total_violation += violations[index];
+            sizes[index] = clamped;
+        }
+
+        // Freeze the appropriate items: all of them if there's no violation, otherwise
+        // only those violating in the same direction as the total
+        for (index, violation) in violations.iter().enumerate() {
+            if frozen[index] {
+                continue;
+            }
+
+            let should_freeze = if total_violation.abs() < f32::EPSILON {
+                true
+            } else if total_violation > 0.0 {
+                *violation > 0.0
+            } else {
+                *violation < 0.0
+            };
+
+            if should_freeze {
+                frozen[index] = true;
+            }
+        }
+    }
+
+    sizes
+}
+
+/// Resolve the given units to pixels, relative to the given parent size
+///
+/// Returns `None` for units that cannot be resolved ahead of time (`Auto` and `Stretch`).
+pub(crate) fn resolve_units(units: Units, parent_size: f32) -> Option<f32> {
+    match units {
+        Units::Pixels(value) => Some(value),
+        Units::Percentage(value) => Some(value / 100.0 * parent_size),
+        _ => None,
+    }
+}
+
+/// Build the [`FlexItem`] for a node along the given axis
+///
+/// Sizes are taken from the node's layout properties, so defaults and box sizing are
+/// already accounted for.
+///
+/// Returns `None` if the node's size depends on its content (`Auto`), in which case the
+/// first-pass layout is kept as is.
+fn flex_item(
+    id: Index,
+    nodes: &Arena<Option<Node>>,
+    is_row: bool,
+    parent_size: f32,
+) -> Option<FlexItem> {
+    let (size, min, max) = if is_row {
+        (id.width(nodes), id.min_width(nodes), id.max_width(nodes))
+    } else {
+        (id.height(nodes), id.min_height(nodes), id.max_height(nodes))
+    };
+
+    let min = min
+        .and_then(|units| resolve_units(units, parent_size))
+        .unwrap_or(0.0);
+    let max = max
+        .and_then(|units| resolve_units(units, parent_size))
+        .unwrap_or(f32::INFINITY);
+
+    let item = match size.unwrap_or(Units::Stretch(1.0)) {
+        Units::Stretch(grow) => FlexItem::stretch(grow),
+        Units::Auto => return None,
+        units => FlexItem::fixed(resolve_units(units, parent_size)?),
+    };
+
+    Some(item.with_constraints(min, max))
+}
+
+/// Calculate the sizes that need to be overridden in order to respect min/max constraints
+///
+/// Each parent's flow children are re-resolved along the parent's main axis, within the parent's
+/// content (less the spacing around and between the children). Children that don't participate in flow (self-directed)
+/// or that are sized by their content are left untouched.
+///
+/// The children of a wrapping parent are instead resolved line by line, within the parent's
+/// content (see [`resolve_wrapped_sizes`]).
+///
+/// Returns the overridden `(width, height)` for each node that needs it.
+pub(crate) fn calculate_clamped_sizes(
+    nodes: &Arena<Option<Node>>,
+    children: &std::collections::HashMap<Index, Vec<Index>>,
+    layout_cache: &LayoutCache,
+) -> Vec<(Index, Option<f32>, Option<f32>)> {
+    let mut overrides = Vec::new();
+
+    for (parent_id, child_ids) in children {
+        let parent = match nodes.get(*parent_id) {
+            Some(Some(parent)) => parent,
+            _ => continue,
+        };
+        let parent_rect = match layout_cache.rect.get(parent_id) {
+            Some(rect) => rect,
+            None => continue,
+        };
+
+        let is_row = match flow_direction(parent) {
+            Some(is_row) => is_row,
+            None => continue,
+        };
+        let parent_size = if is_row {
+            parent_rect.width
+        } else {
+            parent_rect.height
+        };
+
+        if is_wrapping(parent) {
+            let child_ids = flow_children(child_ids, nodes, layout_cache);
+            let (sizes, _) =
+                resolve_wrapped_sizes(*parent_id, &child_ids, nodes, layout_cache, is_row);
+            for (child_id, size) in child_ids.into_iter().zip(sizes) {
+                let rect = &layout_cache.rect[&child_id];
+                let current = if is_row { rect.width } else { rect.height };
+                push_override(&mut overrides, child_id, is_row, size, current);
+            }
+            continue;
+        }
+
+        let mut ids = Vec::new();
+        let mut items = Vec::new();
+        let mut spacing = 0.0;
+        let mut occupied = 0.0;
+        let mut is_resolvable = true;
+        for child_id in child_ids {
+            if !matches!(nodes.get(*child_id), Some(Some(_))) {
+                continue;
+            }
+            if matches!(
+                child_id.position_type(nodes),
+                Some(PositionType::SelfDirected)
+            ) {
+                continue;
+            }
+            let rect = match layout_cache.rect.get(child_id) {
+                Some(rect) => rect,
+                None => continue,
+            };
+
+            if let Some(item) = flex_item(*child_id, nodes, is_row, parent_size) {
+                let (start, end) = offsets(*child_id, nodes, is_row);
+                ids.push(*child_id);
+                items.push(item);
+                spacing += start + end;
+                occupied += if is_row { rect.width } else { rect.height };
+            } else {
+                is_resolvable = false;
+                break;
+            }
+        }
+
+        if !is_resolvable || ids.is_empty() {
+            continue;
+        }
+
+        // The first layout pass doesn't hand the space freed by a clamped child to its siblings,
+        // so the space to distribute is taken from the parent rather than from the children.
+        // Stretching spacing shares that space with the children, in which case only the space
+        // they were given can be redistributed.
+        let available = if has_stretch_spacing(*parent_id, &ids, nodes, is_row) {
+            occupied
+        } else {
+            let (between, _) = flow_spacing(*parent_id, nodes, is_row);
+            spacing += between * (ids.len() - 1) as f32;
+            content_size(*parent_id, layout_cache, nodes, is_row) - spacing
+        };
+        let sizes = resolve_flex(available, &items);
+        for (child_id, size) in ids.into_iter().zip(sizes) {
+            let rect = &layout_cache.rect[&child_id];
+            let current = if is_row { rect.width } else { rect.height };
+            push_override(&mut overrides, child_id, is_row, size, current);
+        }
+    }
+
+    overrides
+}
+
+/// Returns the value of the given units if they're in pixels, otherwise zero
+pub(crate) fn pixels(units: Option<Units>) -> f32 {
+    match units {
+        Some(Units::Pixels(value)) => value,
+        _ => 0.0,
+    }
+}
+
+/// Calculate the main-axis offset of each item for the given justification
+///
+/// Offsets are relative to the start of the available space. The `between` spacing is applied
+/// between items in addition to any space distributed by the justification.
+pub(crate) fn justify_items(
+    justify: JustifyContent,
+    available: f32,
+    sizes: &[f32],
+    between: f32,
+) -> Vec<f32> {
+    let count = sizes.len();
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let used: f32 = sizes.iter().sum::<f32>() + between * (count - 1) as f32;
+    let free = available - used;
+
+    let (start, gap) = match justify {
+        JustifyContent::Start => (0.0, 0.0),
+        JustifyContent::Center => (free / 2.0, 0.0),
+        JustifyContent::End => (free, 0.0),
+        JustifyContent::SpaceBetween if free > 0.0 && count > 1 => (0.0, free / (count - 1) as f32),
+        JustifyContent::SpaceBetween => (0.0, 0.0),
+        JustifyContent::SpaceAround if free > 0.0 => {
+            let gap = free / count as f32;
+            (gap / 2.0, gap)
+        }
+        JustifyContent::SpaceEvenly if free > 0.0 => {
+            let gap = free / (count + 1) as f32;
+            (gap, gap)
+        }
+        JustifyContent::SpaceAround | JustifyContent::SpaceEvenly => (free / 2.0, 0.0),
+    };
+
+    let mut offset = start;
+    sizes
+        .iter()
+        .map(|size| {
+            let current = offset;
+            offset += size + between + gap;
+            current
+        })
+        .collect()
+}
+
+/// Calculate the cross-axis offset of an item for the given alignment
+///
+/// The offset is relative to the start of the available space.
+pub(crate) fn align_item(align: AlignItems, available: f32, size: f32) -> f32 {
+    match align {
+        AlignItems::Start | AlignItems::Stretch => 0.0,
+        AlignItems::Center => (available - size) / 2.0,
+        AlignItems::End => available - size,
+    }
+}
+
+/// Returns the layout direction of the given node, if it lays out its children in a row or column
+///
+/// This is `true` for rows and `false` for columns.
+pub(crate) fn flow_direction(node: &Node) -> Option<bool> {
+    match node.resolved_styles.layout_type {
+        StyleProp::Value(LayoutType::Row) => Some(true),
+        StyleProp::Value(LayoutType::Column) | StyleProp::Default | StyleProp::Unset => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the children of the given parent that participate in flow
+fn flow_children(
+    child_ids: &[Index],
+    nodes: &Arena<Option<Node>>,
+    layout_cache: &LayoutCache,
+) -> Vec<Index> {
+    child_ids
+        .iter()
+        .filter(|child_id| matches!(nodes.get(**child_id), Some(Some(_))))
+        .filter(|child_id| {
+            !matches!(
+                child_id.position_type(nodes),
+                Some(PositionType::SelfDirected)
+            )
+        })
+        .filter(|child_id| layout_cache.rect.contains_key(child_id))
+        .copied()
+        .collect()
+}
+
+/// Returns the pixel offsets `(start, end)` of the given node along an axis
+fn offsets(id: Index, nodes: &Arena<Option<Node>>, horizontal: bool) -> (f32, f32) {
+    if horizontal {
+        (pixels(id.left(nodes)), pixels(id.right(nodes)))
+    } else {
+        (pixels(id.top(nodes)), pixels(id.bottom(nodes)))
+    }
+}
+
+/// Returns true if any spacing along the given parent's main axis stretches, whether it's around
+/// the parent's content, between its children, or around the given children
+fn has_stretch_spacing(
+    parent_id: Index,
+    child_ids: &[Index],
+    nodes: &Arena<Option<Node>>,
+    is_row: bool,
+) -> bool {
+    let is_stretch = |units: Option<Units>| matches!(units, Some(Units::Stretch(..)));
+    let parent_spacing = if is_row {
+        [
+            parent_id.child_left(nodes),
+            parent_id.child_right(nodes),
+            parent_id.col_between(nodes),
+        ]
+    } else {
+        [
+            parent_id.child_top(nodes),
+            parent_id.child_bottom(nodes),
+            parent_id.row_between(nodes),
+        ]
+    };
+
+    parent_spacing.into_iter().any(is_stretch)
+        || child_ids.iter().any(|child_id| {
+            if is_row {
+                is_stretch(child_id.left(nodes)) || is_stretch(child_id.right(nodes))
+            } else {
+                is_stretch(child_id.top(nodes)) || is_stretch(child_id.bottom(nodes))
+            }
+        })
+}
+
+/// Returns the pixel insets `(start, end)` of the given node's content along an axis
+///
+/// This includes both the node's padding and its border.
+pub(crate) fn insets(id: Index, nodes: &Arena<Option<Node>>, horizontal: bool) -> (f32, f32) {
+    if horizontal {
+        (
+            pixels(id.child_left(nodes)) + pixels(id.border_left(nodes)),
+            pixels(id.child_right(nodes)) + pixels(id.border_right(nodes)),
+        )
+    } else {
+        (
+            pixels(id.child_top(nodes)) + pixels(id.border_top(nodes)),
+            pixels(id.child_bottom(nodes)) + pixels(id.border_bottom(nodes)),
+        )
+    }
+}
+
+/// Split the given items into lines that each fit within the available space
+///
+/// Every line holds at least one item, so an item larger than the available space is given a
+/// line of its own.
+///
+/// Returns the range of items on each line.
+pub(crate) fn wrap_lines(available: f32, sizes: &[f32], between: f32) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut used = 0.0;
+    for (index, size) in sizes.iter().enumerate() {
+        if index == start {
+            used = *size;
+        } else if used + between + size > available + SIZE_EPSILON {
+            lines.push(start..index);
+            start = index;
+            used = *size;
+        } else {
+            used += between + size;
+        }
+    }
+
+    if start < sizes.len() {
+        lines.push(start..sizes.len());
+    }
+    lines
+}
+
+/// Returns true if the given node wraps its children onto multiple lines
+pub(crate) fn is_wrapping(node: &Node) -> bool {
+    node.resolved_styles.flex_wrap == StyleProp::Value(FlexWrap::Wrap)
+}
+
+/// Returns the spacing between the flow children of the given parent, along its main axis and
+/// between its lines
+fn flow_spacing(parent_id: Index, nodes: &Arena<Option<Node>>, is_row: bool) -> (f32, f32) {
+    let (col_between, row_between) = (
+        pixels(parent_id.col_between(nodes)),
+        pixels(parent_id.row_between(nodes)),
+    );
+    if is_row {
+        (col_between, row_between)
+    } else {
+        (row_between, col_between)
+    }
+}
+
+/// Returns the size of the given parent's content along an axis
+fn content_size(
+    parent_id: Index,
+    layout_cache: &LayoutCache,
+    nodes: &Arena<Option<Node>>,
+    horizontal: bool,
+) -> f32 {
+    let rect = &layout_cache.rect[&parent_id];
+    let (start, end) = insets(parent_id, nodes, horizontal);
+    let size = if horizontal { rect.width } else { rect.height };
+    size - start - end
+}
+
+/// Resolve the main-axis sizes of the flow children of a wrapping parent, line by line
+///
+/// Children are broken into lines by their base size: the size of an inflexible child, or the
+/// min size of a stretching one. The space left on each line is then distributed among the
+/// stretching children on that line, so that they wrap like any other child rather than sharing a
+/// single line. Children sized by their content keep the size given by the first layout pass.
+///
+/// Returns the size of each child (not including its pixel offsets), along with the range of
+/// children on each line.
+fn resolve_wrapped_sizes(
+    parent_id: Index,
+    child_ids: &[Index],
+    nodes: &Arena<Option<Node>>,
+    layout_cache: &LayoutCache,
+    is_row: bool,
+) -> (Vec<f32>, Vec<Range<usize>>) {
+    let rect = &layout_cache.rect[&parent_id];
+    let parent_size = if is_row { rect.width } else { rect.height };
+    let available = content_size(parent_id, layout_cache, nodes, is_row);
+    let (between, _) = flow_spacing(parent_id, nodes, is_row);
+
+    let items = child_ids
+        .iter()
+        .map(|child_id| {
+            flex_item(*child_id, nodes, is_row, parent_size).unwrap_or_else(|| {
+                let rect = &layout_cache.rect[child_id];
+                FlexItem::fixed(if is_row { rect.width } else { rect.height })
+            })
+        })
+        .collect::<Vec<_>>();
+    let child_offsets = child_ids
+        .iter()
+        .map(|child_id| {
+            let (start, end) = offsets(*child_id, nodes, is_row);
+            start + end
+        })
+        .collect::<Vec<_>>();
+    let base_sizes = items
+        .iter()
+        .zip(&child_offsets)
+        .map(|(item, offset)| item.clamp(item.basis) + offset)
+        .collect::<Vec<_>>();
+
+    let lines = wrap_lines(available, &base_sizes, between);
+    let mut sizes = Vec::with_capacity(items.len());
+    for line in &lines {
+        let spacing =
+            child_offsets[line.clone()].iter().sum::<f32>() + between * (line.len() - 1) as f32;
+        sizes.extend(resolve_flex(available - spacing, &items[line.clone()]));
+    }
+    (sizes, lines)
+}
+
+/// The flow children of a parent, grouped into lines
+struct FlowLines {
+    is_row: bool,
+    child_ids: Vec<Index>,
+    /// The size of each child along the main axis, including its pixel offsets
+    main_sizes: Vec<f32>,
+    /// The range of children on each line
+    lines: Vec<Range<usize>>,
+    /// The size of each line along the cross axis
+    line_sizes: Vec<f32>,
+    /// The spacing between children on the same line
+    between: f32,
+    /// The spacing between lines
+    line_between: f32,
+    /// The position of the parent's content along the main axis
+    main_start: f32,
+    /// The size of the parent's content along the main axis
+    main_available: f32,
+    /// The position of the parent's content along the cross axis
+    cross_start: f32,
+}
+
+impl FlowLines {
+    /// Group the flow children of the given parent into lines
+    ///
+    /// A parent that doesn't wrap has a single line spanning its entire content. Otherwise, each
+    /// line is as large as its largest child along the cross axis, not counting children whose
+    /// cross size depends on the line (`Stretch` or a percentage).
+    ///
+    /// Returns `None` if the parent doesn't lay out its children in a row or column.
+    fn new(
+        parent_id: Index,
+        parent: &Node,
+        child_ids: &[Index],
+        nodes: &Arena<Option<Node>>,
+        layout_cache: &LayoutCache,
+    ) -> Option<Self> {
+        let is_row = flow_direction(parent)?;
+        let rect = layout_cache.rect.get(&parent_id)?;
+        let child_ids = flow_children(child_ids, nodes, layout_cache);
+
+        let (between, line_between) = flow_spacing(parent_id, nodes, is_row);
+        let (main_pos, cross_pos) = if is_row {
+            (rect.posx, rect.posy)
+        } else {
+            (rect.posy, rect.posx)
+        };
+        let (main_inset_start, _) = insets(parent_id, nodes, is_row);
+        let (cross_inset_start, _) = insets(parent_id, nodes, !is_row);
+        let main_available = content_size(parent_id, layout_cache, nodes, is_row);
+        let cross_available = content_size(parent_id, layout_cache, nodes, !is_row);
+
+        let main_sizes = child_ids
+            .iter()
+            .map(|child_id| {
+                let rect = &layout_cache.rect[child_id];
+                let (start, end) = offsets(*child_id, nodes, is_row);
+                start + end + if is_row { rect.width } else { rect.height }
+            })
+            .collect::<Vec<_>>();
+
+        let (lines, line_sizes) = if is_wrapping(parent) {
+            // Lines are broken the same way the sizes of their children are resolved
+            let (_, lines) =
+                resolve_wrapped_sizes(parent_id, &child_ids, nodes, layout_cache, is_row);
+            let line_sizes = lines
+                .iter()
+                .map(|line| {
+                    child_ids[line.clone()]
+                        .iter()
+                        .filter_map(|child_id| cross_extent(*child_id, nodes, layout_cache, is_row))
+                        .fold(0.0, f32::max)
+                })
+                .collect();
+            (lines, line_sizes)
+        } else {
+            (vec![0..child_ids.len()], vec![cross_available])
+        };
+
+        Some(Self {
+            is_row,
+            child_ids,
+            main_sizes,
+            lines,
+            line_sizes,
+            between,
+            line_between,
+            main_start: main_pos + main_inset_start,
+            main_available,
+            cross_start: cross_pos + cross_inset_start,
+        })
+    }
+}
+
+/// Returns the size of the given child along its parent's cross axis, including its pixel offsets
+///
+/// Returns `None` if the child's cross size depends on the size of its line.
+fn cross_extent(
+    child_id: Index,
+    nodes: &Arena<Option<Node>>,
+    layout_cache: &LayoutCache,
+    is_row: bool,
+) -> Option<f32> {
+    let size = if is_row {
+        child_id.height(nodes)
+    } else {
+        child_id.width(nodes)
+    };
+    if matches!(size, Some(Units::Stretch(..)) | Some(Units::Percentage(..))) {
+        return None;
+    }
+
+    let rect = &layout_cache.rect[&child_id];
+    let (start, end) = offsets(child_id, nodes, !is_row);
+    Some(start + end + if is_row { rect.height } else { rect.width })
+}
+
+/// Add an override for the size of the given node along a single axis, if it differs
+fn push_override(
+    overrides: &mut Vec<(Index, Option<f32>, Option<f32>)>,
+    id: Index,
+    horizontal: bool,
+    size: f32,
+    current: f32,
+) {
+    if (current - size).abs() > SIZE_EPSILON {
+        if horizontal {
+            overrides.push((id, Some(size), None));
+        } else {
+            overrides.push((id, None, Some(size)));
+        }
+    }
+}
+
+/// Calculate the sizes that need to be overridden in order to fit children along the cross axis
+///
+/// This covers:
+/// * Children sized by their content (`Auto`) within a parent with an [`AlignItems::Stretch`]
+///   alignment, which are stretched to fill their line
+/// * Children that stretch along the cross axis within a wrapping parent, which fill their line
+///   rather than the entire parent
+/// * Wrapping parents with an `Auto` cross size, which grow to fit all of their lines
+///
+/// Returns the overridden `(width, height)` for each node that needs it.
+pub(crate) fn calculate_cross_sizes(
+    nodes: &Arena<Option<Node>>,
+    children: &std::collections::HashMap<Index, Vec<Index>>,
+    layout_cache: &LayoutCache,
+) -> Vec<(Index, Option<f32>, Option<f32>)> {
+    let mut overrides = Vec::new();
+
+    for (parent_id, child_ids) in children {
+        let parent = match nodes.get(*parent_id) {
+            Some(Some(parent)) => parent,
+            _ => continue,
+        };
+        let wraps = is_wrapping(parent);
+        let stretches = parent.resolved_styles.align_items == StyleProp::Value(AlignItems::Stretch);
+        if !wraps && !stretches {
+            continue;
+        }
+        let flow = match FlowLines::new(*parent_id, parent, child_ids, nodes, layout_cache) {
+            Some(flow) => flow,
+            None => continue,
+        };
+        let is_row = flow.is_row;
+
+        for (line, line_size) in flow.lines.iter().zip(&flow.line_sizes) {
+            if *line_size <= 0.0 {
+                continue;
+            }
+
+            for child_id in &flow.child_ids[line.clone()] {
+                let size = if is_row {
+                    child_id.height(nodes)
+                } else {
+                    child_id.width(nodes)
+                };
+                let fills_line = match size {
+                    Some(Units::Auto) => stretches,
+                    Some(Units::Stretch(..)) => wraps,
+                    _ => false,
+                };
+                if !fills_line {
+                    continue;
+                }
+
+                let (start, end) = offsets(*child_id, nodes, !is_row);
+                let rect = &layout_cache.rect[child_id];
+                let current = if is_row { rect.height } else { rect.width };
+                let size = (line_size - start - end).max(0.0);
+                push_override(&mut overrides, *child_id, !is_row, size, current);
+            }
+        }
+
+        let cross_size = if is_row {
+            parent_id.height(nodes)
+        } else {
+            parent_id.width(nodes)
+        };
+        if wraps && cross_size == Some(Units::Auto) {
+            let (inset_start, inset_end) = insets(*parent_id, nodes, !is_row);
+            let gaps = flow.lines.len().saturating_sub(1) as f32 * flow.line_between;
+            let size = flow.line_sizes.iter().sum::<f32>() + gaps + inset_start + inset_end;
+            let rect = &layout_cache.rect[parent_id];
+            let current = if is_row { rect.height } else { rect.width };
+            push_override(&mut overrides, *parent_id, !is_row, size, current);
+        }
+    }
+
+    overrides
+}
+
+/// Moves the given node and all of its descendants by the given amount
+pub(crate) fn translate(
+    id: Index,
+    dx: f32,
+    dy: f32,
+    node_tree: &Tree,
+    layout_cache: &mut LayoutCache,
+) {
+    if dx.abs() < f32::EPSILON && dy.abs() < f32::EPSILON {
+        return;
+    }
+
+    for descendant in node_tree.flatten_node(id) {
+        if let Some(rect) = layout_cache.rect.get_mut(&descendant) {
+            rect.posx += dx;
+            rect.posy += dy;
+        }
+        if dx.abs() >= f32::EPSILON {
+            layout_cache.set_geo_changed(descendant, GeometryChanged::POSX_CHANGED, true);
+        }
+        if dy.abs() >= f32::EPSILON {
+            layout_cache.set_geo_changed(descendant, GeometryChanged::POSY_CHANGED, true);
+        }
+    }
+}
+
+/// Position the flow children of each parent according to its `flex_wrap`, `justify_content`
+/// and `align_items`
+///
+/// Parents are visited before their children, so nested alignments build on one another. Once a
+/// parent sets either alignment, its children's pixel offsets along that axis act as margins
+/// rather than positions (any other offsets are ignored). Wrapped lines are always positioned
+/// from scratch, falling back to the start of the parent (or line) for any unset alignment.
+pub(crate) fn apply_alignment(
+    nodes: &Arena<Option<Node>>,
+    node_tree: &Tree,
+    layout_cache: &mut LayoutCache,
+) {
+    let parent_ids = match node_tree.root_node {
+        Some(root) => node_tree.flatten_node(root),
+        None => return,
+    };
+
+    for parent_id in parent_ids {
+        let parent = match nodes.get(parent_id) {
+            Some(Some(parent)) => parent,
+            _ => continue,
+        };
+        let wraps = is_wrapping(parent);
+        let justify = match parent.resolved_styles.justify_content {
+            StyleProp::Value(justify) => Some(justify),
+            _ if wraps => Some(JustifyContent::Start),
+            _ => None,
+        };
+        let align = match parent.resolved_styles.align_items {
+            StyleProp::Value(align) => Some(align),
+            _ if wraps => Some(AlignItems::Start),
+            _ => None,
+        };
+        if justify.is_none() && align.is_none() {
+            continue;
+        }
+        let flow = match node_tree.children.get(&parent_id) {
+            Some(child_ids) => {
+                match FlowLines::new(parent_id, parent, child_ids, nodes, layout_cache) {
+                    Some(flow) => flow,
+                    None => continue,
+                }
+            }
+            None => continue,
+        };
+        let is_row = flow.is_row;
+
+        let mut line_start = flow.cross_start;
+        for (line, line_size) in flow.lines.iter().zip(&flow.line_sizes) {
+            let main_positions = justify.map(|justify| {
+                justify_items(
+                    justify,
+                    flow.main_available,
+                    &flow.main_sizes[line.clone()],
+                    flow.between,
+                )
+            });
+
+            for (index, child_id) in flow.child_ids[line.clone()].iter().enumerate() {
+                let rect = layout_cache.rect[child_id];
+                let (child_main, child_cross, child_cross_size) = if is_row {
+                    (rect.posx, rect.posy, rect.height)
+                } else {
+                    (rect.posy, rect.posx, rect.width)
+                };
+
+                let main_delta = main_positions.as_ref().map_or(0.0, |positions| {
+                    let (start, _) = offsets(*child_id, nodes, is_row);
+                    flow.main_start + positions[index] + start - child_main
+                });
+                let cross_delta = align.map_or(0.0, |align| {
+                    let (start, end) = offsets(*child_id, nodes, !is_row);
+                    let offset = align_item(align, *line_size, start + child_cross_size + end);
+                    line_start + offset + start - child_cross
+                });
+
+                let (dx, dy) = if is_row {
+                    (main_delta, cross_delta)
+                } else {
+                    (cross_delta, main_delta)
+                };
+                translate(*child_id, dx, dy, node_tree, layout_cache);
+            }
+
+            line_start += line_size + flow.line_between;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        align_item, calculate_clamped_sizes, calculate_cross_sizes, justify_items, resolve_flex,
+        wrap_lines, FlexItem,
+    };
+    use crate::layout_cache::{LayoutCache, Rect};
+    use crate::node::NodeBuilder;
+    use crate::styles::{
+        AlignItems, FlexWrap, JustifyContent, LayoutType, Style, StyleProp, Units,
+    };
+    use crate::Arena;
+
+    #[test]
+    fn should_distribute_free_space_by_grow() {
+        let items = [FlexItem::stretch(1.0), FlexItem::stretch(3.0)];
+        assert_eq!(vec![25.0, 75.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn should_clamp_fixed_items() {
+        let items = [
+            FlexItem::fixed(80.0).with_constraints(0.0, 50.0),
+            FlexItem::fixed(10.0).with_constraints(20.0, f32::INFINITY),
+        ];
+        assert_eq!(vec![50.0, 20.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn max_should_return_freed_space_to_siblings() {
+        let items = [
+            FlexItem::stretch(1.0).with_constraints(0.0, 20.0),
+            FlexItem::stretch(1.0),
+        ];
+        assert_eq!(vec![20.0, 80.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn min_should_take_space_from_siblings() {
+        let items = [
+            FlexItem::stretch(1.0).with_constraints(70.0, f32::INFINITY),
+            FlexItem::stretch(1.0),
+        ];
+        assert_eq!(vec![70.0, 30.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn clamped_fixed_item_should_give_space_to_stretch_siblings() {
+        // e.g. a `width: 80%` child with a `max_width: 40px` sibling to a stretch child
+        let items = [
+            FlexItem::fixed(80.0).with_constraints(0.0, 40.0),
+            FlexItem::stretch(1.0),
+        ];
+        assert_eq!(vec![40.0, 60.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn should_resolve_multiple_violations() {
+        let items = [
+            FlexItem::stretch(1.0).with_constraints(0.0, 10.0),
+            FlexItem::stretch(1.0).with_constraints(0.0, 20.0),
+            FlexItem::stretch(2.0),
+        ];
+        assert_eq!(vec![10.0, 20.0, 70.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn min_should_win_over_max() {
+        let items = [FlexItem::stretch(1.0).with_constraints(60.0, 40.0)];
+        assert_eq!(vec![60.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn should_not_grow_past_available_space() {
+        let items = [FlexItem::fixed(120.0), FlexItem::stretch(1.0)];
+        assert_eq!(vec![120.0, 0.0], resolve_flex(100.0, &items));
+    }
+
+    #[test]
+    fn should_justify_start() {
+        let justify = JustifyContent::Start;
+        assert_eq!(vec![0.0], justify_items(justify, 100.0, &[10.0], 0.0));
+        assert_eq!(
+            vec![0.0, 10.0],
+            justify_items(justify, 100.0, &[10.0, 20.0], 0.0)
+        );
+        assert_eq!(
+            vec![0.0, 15.0, 40.0],
+            justify_items(justify, 100.0, &[10.0, 20.0, 30.0], 5.0)
+        );
+    }
+
+    #[test]
+    fn should_justify_center() {
+        let justify = JustifyContent::Center;
+        assert_eq!(vec![45.0], justify_items(justify, 100.0, &[10.0], 0.0));
+        assert_eq!(
+            vec![35.0, 45.0],
+            justify_items(justify, 100.0, &[10.0, 20.0], 0.0)
+        );
+        assert_eq!(
+            vec![15.0, 30.0, 55.0],
+            justify_items(justify, 100.0, &[10.0, 20.0, 30.0], 5.0)
+        );
+    }
+
+    #[test]
+    fn should_justify_end() {
+        let justify = JustifyContent::End;
+        assert_eq!(vec![90.0], justify_items(justify, 100.0, &[10.0], 0.0));
+        assert_eq!(
+            vec![70.0, 80.0],
+            justify_items(justify, 100.0, &[10.0, 20.0], 0.0)
+        );
+        assert_eq!(
+            vec![30.0, 45.0, 70.0],
+            justify_items(justify, 100.0, &[10.0, 20.0, 30.0], 5.0)
+        );
+    }
+
+    #[test]
+    fn should_justify_space_between() {
+        let justify = JustifyContent::SpaceBetween;
+        assert_eq!(vec![0.0], justify_items(justify, 100.0, &[10.0], 0.0));
+        assert_eq!(
+            vec![0.0, 80.0],
+            justify_items(justify, 100.0, &[10.0, 20.0], 0.0)
+        );
+        assert_eq!(
+            vec![0.0, 30.0, 70.0],
+            justify_items(justify, 100.0, &[10.0, 20.0, 30.0], 0.0)
+        );
+    }
+
+    #[test]
+    fn should_justify_space_around() {
+        let justify = JustifyContent::SpaceAround;
+        assert_eq!(vec![45.0], justify_items(justify, 100.0, &[10.0], 0.0));
+        assert_eq!(
+            vec![17.5, 62.5],
+            justify_items(justify, 100.0, &[10.0, 20.0], 0.0)
+        );
+        assert_eq!(
+            vec![10.0, 40.0, 80.0],
+            justify_items(justify, 100.0, &[10.0, 20.0, 10.0], 0.0)
+        );
+    }
+
+    #[test]
+    fn should_justify_space_evenly() {
+        let justify = JustifyContent::SpaceEvenly;
+        assert_eq!(vec![45.0], justify_items(justify, 100.0, &[10.0], 0.0));
+        assert_eq!(
+            vec![20.0, 50.0],
+            justify_items(justify, 100.0, &[10.0, 30.0], 0.0)
+        );
+        assert_eq!(
+            vec![10.0, 30.0, 60.0],
+            justify_items(justify, 100.0, &[10.0, 20.0, 30.0], 0.0)
+        );
+    }
+
+    #[test]
+    fn should_fall_back_when_overflowing() {
+        let sizes = [60.0, 60.0];
+        assert_eq!(
+            vec![0.0, 60.0],
+            justify_items(JustifyContent::SpaceBetween, 100.0, &sizes, 0.0)
+        );
+        assert_eq!(
+            vec![-10.0, 50.0],
+            justify_items(JustifyContent::SpaceAround, 100.0, &sizes, 0.0)
+        );
+        assert_eq!(
+            vec![-10.0, 50.0],
+            justify_items(JustifyContent::SpaceEvenly, 100.0, &sizes, 0.0)
+        );
+    }
+
+    #[test]
+    fn should_align_items() {
+        assert_eq!(0.0, align_item(AlignItems::Start, 100.0, 20.0));
+        assert_eq!(40.0, align_item(AlignItems::Center, 100.0, 20.0));
+        assert_eq!(80.0, align_item(AlignItems::End, 100.0, 20.0));
+        assert_eq!(0.0, align_item(AlignItems::Stretch, 100.0, 20.0));
+    }
+
+    #[test]
+    fn should_wrap_overflowing_items() {
+        let sizes = [40.0, 40.0, 40.0, 40.0, 40.0];
+        assert_eq!(vec![0..2, 2..4, 4..5], wrap_lines(100.0, &sizes, 10.0));
+        assert_eq!(vec![0..5], wrap_lines(240.0, &sizes, 10.0));
+    }
+
+    #[test]
+    fn should_give_oversized_items_their_own_line() {
+        let sizes = [20.0, 150.0, 20.0, 20.0];
+        assert_eq!(vec![0..1, 1..2, 2..4], wrap_lines(100.0, &sizes, 0.0));
+        assert!(wrap_lines(100.0, &[], 0.0).is_empty());
+    }
+
+    #[test]
+    fn max_should_return_freed_space_to_stretch_siblings_in_a_row() {
+        let mut nodes = Arena::new();
+        let row = nodes.insert(None);
+        let clamped = nodes.insert(None);
+        let sibling = nodes.insert(None);
+        nodes[row] = Some(
+            NodeBuilder::new(
+                row,
+                Style {
+                    layout_type: StyleProp::Value(LayoutType::Row),
+                    width: StyleProp::Value(Units::Pixels(200.0)),
+                    height: StyleProp::Value(Units::Pixels(20.0)),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+        nodes[clamped] = Some(
+            NodeBuilder::new(
+                clamped,
+                Style {
+                    width: StyleProp::Value(Units::Stretch(1.0)),
+                    max_width: StyleProp::Value(Units::Pixels(50.0)),
+                    height: StyleProp::Value(Units::Pixels(20.0)),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+        nodes[sibling] = Some(
+            NodeBuilder::new(
+                sibling,
+                Style {
+                    width: StyleProp::Value(Units::Stretch(1.0)),
+                    height: StyleProp::Value(Units::Pixels(20.0)),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+        let children = HashMap::from([(row, vec![clamped, sibling])]);
+
+        // The first layout pass clamps the first child, but keeps the sibling at its even share
+        let mut layout_cache = LayoutCache::default();
+        let rect = |posx: f32, width: f32| Rect {
+            posx,
+            width,
+            height: 20.0,
+            ..Default::default()
+        };
+        layout_cache.rect.insert(row, rect(0.0, 200.0));
+        layout_cache.rect.insert(clamped, rect(0.0, 50.0));
+        layout_cache.rect.insert(sibling, rect(100.0, 100.0));
+
+        // The sibling takes the remainder of the row
+        assert_eq!(
+            vec![(sibling, Some(150.0), None)],
+            calculate_clamped_sizes(&nodes, &children, &layout_cache)
+        );
+    }
+
+    #[test]
+    fn should_wrap_stretching_children_by_their_min_size() {
+        let mut nodes = Arena::new();
+        let row = nodes.insert(None);
+        let tags = [nodes.insert(None), nodes.insert(None), nodes.insert(None)];
+        nodes[row] = Some(
+            NodeBuilder::new(
+                row,
+                Style {
+                    layout_type: StyleProp::Value(LayoutType::Row),
+                    flex_wrap: StyleProp::Value(FlexWrap::Wrap),
+                    width: StyleProp::Value(Units::Pixels(260.0)),
+                    height: StyleProp::Value(Units::Auto),
+                    col_between: StyleProp::Value(Units::Pixels(10.0)),
+                    row_between: StyleProp::Value(Units::Pixels(5.0)),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+        for tag in tags {
+            nodes[tag] = Some(
+                NodeBuilder::new(
+                    tag,
+                    Style {
+                        width: StyleProp::Value(Units::Stretch(1.0)),
+                        min_width: StyleProp::Value(Units::Pixels(100.0)),
+                        height: StyleProp::Value(Units::Pixels(20.0)),
+                        ..Style::new_default()
+                    },
+                )
+                .build(),
+            );
+        }
+        let children = HashMap::from([(row, tags.to_vec())]);
+
+        // The first layout pass shares a single line between all of them
+        let mut layout_cache = LayoutCache::default();
+        let rect = |posx: f32, width: f32, height: f32| Rect {
+            posx,
+            width,
+            height,
+            ..Default::default()
+        };
+        layout_cache.rect.insert(row, rect(0.0, 260.0, 20.0));
+        for (index, tag) in tags.iter().enumerate() {
+            layout_cache
+                .rect
+                .insert(*tag, rect(index as f32 * 90.0, 80.0, 20.0));
+        }
+
+        // Two fit on the first line, sharing its free space, while the third fills its own line
+        assert_eq!(
+            vec![
+                (tags[0], Some(125.0), None),
+                (tags[1], Some(125.0), None),
+                (tags[2], Some(260.0), None),
+            ],
+            calculate_clamped_sizes(&nodes, &children, &layout_cache)
+        );
+        // The row grows to fit both lines
+        assert_eq!(
+            vec![(row, None, Some(45.0))],
+            calculate_cross_sizes(&nodes, &children, &layout_cache)
+        );
+    }
+}
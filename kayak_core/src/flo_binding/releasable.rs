@@ -84,9 +84,11 @@ impl ReleasableNotifiable {
 impl Releasable for ReleasableNotifiable {
     fn done(&mut self) {
         // Reset the optional item so that it's 'None'
-        let mut target = self.target.lock().unwrap();
+        let target = self.target.lock().unwrap().take();
 
-        *target = None;
+        // Drop the target outside of the lock, since it may hold the last reference to the binding
+        // that's notifying it (which releases this notifiable again as it's dropped)
+        drop(target);
     }
 
     fn keep_alive(&mut self) {
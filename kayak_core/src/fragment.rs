@@ -1,6 +1,8 @@
+use std::fmt;
+
 use crate::{
-    context_ref::KayakContextRef, styles::Style, Children, Index, OnEvent, OnLayout, Widget,
-    WidgetProps,
+    context_ref::KayakContextRef, styles::Style, Children, DynamicProps, Index, OnEvent, OnLayout,
+    Widget, WidgetProps,
 };
 
 /// Props used by the [`Fragment`] widget
@@ -8,10 +10,68 @@ use crate::{
 pub struct FragmentProps {
     pub styles: Option<Style>,
     pub children: Option<crate::Children>,
+    /// If true, [`styles`](Self::styles) are merged onto this fragment's only child rather than
+    /// applied to the fragment itself
+    ///
+    /// This allows the styles of a widget to be set in one place without adding another node to
+    /// the tree. Fields set in the forwarded styles take precedence over the child's own.
+    ///
+    /// # Errors
+    ///
+    /// Styles are only forwarded while the fragment has at most one child. Otherwise, they're
+    /// dropped and a [`ForwardStylesError`] is passed to the closest error boundary, if any. Note
+    /// that a `match` or `if` expression in the children position renders a child for each of its
+    /// branches.
+    pub forward_styles: bool,
+    /// If true, this fragment's children are kept as they were last rendered, without being laid
+    /// out, drawn or focusable
+    ///
+    /// The children stay mounted, so they keep their state for when the fragment becomes active
+    /// again. This is how the arms of a `match` (or the branches of an `if`) in the children
+    /// position keep their state while another one is shown. The given children are ignored while
+    /// the fragment is inactive.
+    ///
+    /// The children are paused in the meantime: they don't re-render when their bindings change,
+    /// and their effects, intervals and timeouts don't run. Whatever changed is caught up on once
+    /// the fragment becomes active again.
+    pub inactive: bool,
+}
+
+/// The error reported when a [`Fragment`] can't forward its styles, because it has more than one
+/// child (see [`FragmentProps::forward_styles`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForwardStylesError {
+    /// The widget ID of the fragment
+    pub fragment: Index,
+    /// The number of children the fragment has
+    pub child_count: usize,
+}
+
+impl fmt::Display for ForwardStylesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "A `Fragment` that forwards its styles must have at most one child, but it has {}",
+            self.child_count
+        )
+    }
 }
 
+impl std::error::Error for ForwardStylesError {}
+
 /// The base widget, used to actually build and render children
 ///
+/// A fragment doesn't add a node to the layout. It can also be used to style its only child
+/// without wrapping it in another widget (see [`FragmentProps::forward_styles`]):
+///
+/// ```ignore
+/// rsx! {
+///     <Fragment styles={Some(card_styles)} forward_styles={true}>
+///         <Button />
+///     </Fragment>
+/// }
+/// ```
+///
 /// # Props
 ///
 /// __Type:__ [`FragmentProps`]
@@ -39,7 +99,11 @@ impl WidgetProps for FragmentProps {
     }
 
     fn get_styles(&self) -> Option<Style> {
-        self.styles.clone()
+        if self.forward_styles {
+            None
+        } else {
+            self.styles.clone()
+        }
     }
 
     fn get_on_event(&self) -> Option<OnEvent> {
@@ -55,6 +119,22 @@ impl WidgetProps for FragmentProps {
     }
 }
 
+impl From<DynamicProps> for FragmentProps {
+    fn from(props: DynamicProps) -> Self {
+        let forward_styles = props
+            .get::<bool>("forward_styles")
+            .copied()
+            .unwrap_or_default();
+        let inactive = props.get::<bool>("inactive").copied().unwrap_or_default();
+        Self {
+            styles: props.styles,
+            children: props.children,
+            forward_styles,
+            inactive,
+        }
+    }
+}
+
 impl Widget for Fragment {
     type Props = FragmentProps;
 
@@ -86,13 +166,534 @@ impl Widget for Fragment {
 
     fn render(&mut self, context: &mut KayakContextRef) {
         let parent_id = self.get_id();
+        context
+            .context
+            .widget_manager
+            .set_inactive(self.props.inactive, true, parent_id);
+        if self.props.inactive {
+            return;
+        }
+
         if let Some(children) = self.props.children.take() {
             let mut context = KayakContextRef::new(&mut context.context, Some(parent_id));
             children.build(Some(parent_id), &mut context);
+        }
+
+        let forwarded_styles = if self.props.forward_styles {
+            self.props.styles.clone()
         } else {
-            return;
+            None
+        };
+        let result = context
+            .context
+            .widget_manager
+            .set_forwarded_styles(forwarded_styles, parent_id);
+        if let Err(error) = result {
+            // Outside of an error boundary, the styles are just left unforwarded
+            context
+                .context
+                .report_error(parent_id, error.to_string())
+                .ok();
         }
 
         // Note: No need to do anything here with this KayakContextRef.
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use kayak_render_macros::{render, rsx, widget, WidgetProps};
+
+    use crate::render_command::RenderCommand;
+    use crate::styles::{Style, StyleProp, Units};
+    use crate::{mount_widget, Binding, Bound, Fragment, KayakContext, MutableBound, Widget};
+
+    static SHOWN_RENDERS: AtomicUsize = AtomicUsize::new(0);
+    static HIDDEN_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+    #[widget]
+    fn Shown() {
+        SHOWN_RENDERS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[widget]
+    fn Hidden() {
+        HIDDEN_RENDERS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct PanelProps {
+        show: bool,
+    }
+
+    #[widget]
+    fn Panel(props: PanelProps) {
+        let show = props.show;
+        rsx! {
+            <Fragment>
+                {if show {
+                    <Shown />
+                } else {
+                    <Hidden />
+                }}
+                {if !show {
+                    <Hidden />
+                }}
+            </Fragment>
+        }
+    }
+
+    #[test]
+    fn should_only_build_taken_branches() {
+        let mut context = KayakContext::new();
+        {
+            let context = &mut context;
+            render! {
+                <Panel show={true} />
+            }
+        }
+
+        assert_eq!(1, SHOWN_RENDERS.load(Ordering::SeqCst));
+        assert_eq!(0, HIDDEN_RENDERS.load(Ordering::SeqCst));
+    }
+
+    /// Where a counter puts its state as it renders, along with how often it rendered and ran its
+    /// effect
+    #[derive(Clone, Debug, Default)]
+    struct CountSlot {
+        count: Arc<Mutex<Option<Binding<u32>>>>,
+        renders: Arc<AtomicUsize>,
+        effects: Arc<AtomicUsize>,
+    }
+
+    impl PartialEq for CountSlot {
+        fn eq(&self, other: &Self) -> bool {
+            Arc::ptr_eq(&self.count, &other.count)
+        }
+    }
+
+    /// Styles that give a widget a layout
+    fn layout() -> Option<Style> {
+        Some(Style {
+            render_command: StyleProp::Value(RenderCommand::Layout),
+            ..Default::default()
+        })
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct CounterProps {
+        count: CountSlot,
+        #[prop_field(Styles)]
+        styles: Option<Style>,
+    }
+
+    #[widget]
+    fn Counter(props: CounterProps) {
+        props.styles = layout();
+        let count = context.create_state(0u32).unwrap();
+        props.count.renders.fetch_add(1, Ordering::SeqCst);
+        let effects = props.count.effects.clone();
+        context.create_effect(
+            move || {
+                effects.fetch_add(1, Ordering::SeqCst);
+            },
+            &[&count],
+        );
+        *props.count.count.lock().unwrap() = Some(count);
+    }
+
+    #[widget]
+    fn Tab() {}
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct TabsProps {
+        tab: Binding<u8>,
+        count: CountSlot,
+        /// Whether the tabs are an `if` rather than a `match`
+        use_if: bool,
+        #[prop_field(Styles)]
+        styles: Option<Style>,
+    }
+
+    #[widget]
+    fn Tabs(props: TabsProps) {
+        props.styles = layout();
+        context.bind(&props.tab);
+        let tab = props.tab.get();
+        let count = props.count.clone();
+        if props.use_if {
+            rsx! {
+                <Fragment>
+                    {if tab == 0 {
+                        <Counter count={count} />
+                    } else {
+                        <Tab />
+                    }}
+                </Fragment>
+            }
+        } else {
+            rsx! {
+                <Fragment>
+                    {match tab {
+                        0 => <Counter count={count} />,
+                        1 => {
+                            <Tab />
+                            <Tab />
+                        }
+                        _ => <Fragment></Fragment>,
+                    }}
+                </Fragment>
+            }
+        }
+    }
+
+    /// Renders the tabs, returning the state of the counter in the first tab
+    fn mount_tabs(context: &mut KayakContext, tab: &Binding<u8>, use_if: bool) -> Binding<u32> {
+        let props = TabsProps {
+            tab: tab.clone(),
+            use_if,
+            ..Default::default()
+        };
+        let count = props.count.clone();
+        mount_widget(context, Tabs::constructor(props));
+        let count = count.count.lock().unwrap().clone();
+        count.unwrap()
+    }
+
+    /// Returns the widgets in the slot of the first tab
+    fn first_tab(context: &KayakContext) -> Vec<crate::Index> {
+        let tree = &context.widget_manager.tree;
+        let fragment = tree.children[&tree.root_node.unwrap()][0];
+        let slot = tree.children[&fragment][0];
+        tree.children.get(&slot).cloned().unwrap_or_default()
+    }
+
+    /// Returns the widgets in the layout of the given context
+    fn laid_out(context: &KayakContext) -> Vec<crate::Index> {
+        context.widget_manager.node_tree.flatten()
+    }
+
+    #[test]
+    fn should_keep_the_state_of_inactive_match_arms() {
+        let mut context = KayakContext::new();
+        let tab = Binding::new(0);
+        let count = mount_tabs(&mut context, &tab, false);
+        count.set(5);
+        context.render();
+        let counter = first_tab(&context)[0];
+
+        tab.set(1);
+        context.render();
+        // The counter stays mounted, but isn't laid out
+        assert_eq!(vec![counter], first_tab(&context));
+        assert!(context.widget_manager.is_inactive(counter));
+        assert!(!laid_out(&context).contains(&counter));
+
+        tab.set(2);
+        context.render();
+        tab.set(0);
+        context.render();
+        assert_eq!(vec![counter], first_tab(&context));
+        assert!(!context.widget_manager.is_inactive(counter));
+        assert!(laid_out(&context).contains(&counter));
+        assert_eq!(5, count.get());
+    }
+
+    #[test]
+    fn should_pause_inactive_match_arms_until_they_are_active_again() {
+        let mut context = KayakContext::new();
+        let tab = Binding::new(0);
+        let props = TabsProps {
+            tab: tab.clone(),
+            ..Default::default()
+        };
+        let slot = props.count.clone();
+        mount_widget(&mut context, Tabs::constructor(props));
+        let count = slot.count.lock().unwrap().clone().unwrap();
+        assert_eq!(1, slot.renders.load(Ordering::SeqCst));
+        assert_eq!(1, slot.effects.load(Ordering::SeqCst));
+
+        tab.set(1);
+        context.render();
+        count.set(5);
+        context.render();
+        count.set(6);
+        context.render();
+        // The counter neither re-renders nor runs its effect while its arm is inactive
+        assert_eq!(1, slot.renders.load(Ordering::SeqCst));
+        assert_eq!(1, slot.effects.load(Ordering::SeqCst));
+
+        tab.set(0);
+        context.render();
+        // It catches up once the arm is active again
+        assert_eq!(2, slot.renders.load(Ordering::SeqCst));
+        assert_eq!(2, slot.effects.load(Ordering::SeqCst));
+        assert_eq!(6, count.get());
+    }
+
+    #[test]
+    fn should_render_match_arms_with_multiple_children() {
+        let mut context = KayakContext::new();
+        let tab = Binding::new(1);
+        let props = TabsProps {
+            tab,
+            ..Default::default()
+        };
+        mount_widget(&mut context, Tabs::constructor(props));
+
+        // The tabs, their fragment, a slot per arm and the active arm's children
+        let widget_manager = &context.widget_manager;
+        let tabs = widget_manager.tree.root_node.unwrap();
+        let fragment = widget_manager.tree.children[&tabs][0];
+        let slots = &widget_manager.tree.children[&fragment];
+        assert_eq!(3, slots.len());
+        assert_eq!(None, widget_manager.tree.children.get(&slots[0]));
+        assert_eq!(2, widget_manager.tree.children[&slots[1]].len());
+    }
+
+    /// A fragment of the given width, built without `rsx!`
+    fn sized_fragment(width: f32) -> Fragment {
+        Fragment::constructor(crate::FragmentProps {
+            styles: Some(Style {
+                width: StyleProp::Value(Units::Pixels(width)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct SizedProps {
+        size: u8,
+    }
+
+    #[widget]
+    fn SizedByMatch(props: SizedProps) {
+        let size = props.size;
+        rsx! {
+            <Fragment>
+                {match size {
+                    0 => <Tab />,
+                    _ => <Tab />,
+                }}
+                {match size {
+                    0 => sized_fragment(10.0),
+                    _ => sized_fragment(20.0),
+                }}
+            </Fragment>
+        }
+    }
+
+    /// Returns the width of the given widget
+    fn width_of(context: &KayakContext, id: crate::Index) -> StyleProp<Units> {
+        context.widget_manager.get_styles(id).unwrap().width
+    }
+
+    #[test]
+    fn should_leave_match_expressions_without_widgets_as_expressions() {
+        let mut context = KayakContext::new();
+        {
+            let context = &mut context;
+            render! {
+                <SizedByMatch size={1} />
+            }
+        }
+
+        // A slot per arm of the first match, then the value of the second
+        let tree = &context.widget_manager.tree;
+        let fragment = tree.children[&tree.root_node.unwrap()][0];
+        let children = &tree.children[&fragment];
+        assert_eq!(3, children.len());
+        assert_eq!(
+            StyleProp::Value(Units::Pixels(20.0)),
+            width_of(&context, children[2])
+        );
+    }
+
+    #[widget]
+    fn SizedByIf(props: SizedProps) {
+        let size = props.size;
+        rsx! {
+            <Fragment>
+                {if size == 0 {
+                    <Tab />
+                }}
+                {if size == 0 { sized_fragment(10.0) } else { sized_fragment(20.0) }}
+            </Fragment>
+        }
+    }
+
+    #[test]
+    fn should_leave_if_expressions_without_widgets_as_expressions() {
+        let mut context = KayakContext::new();
+        {
+            let context = &mut context;
+            render! {
+                <SizedByIf size={1} />
+            }
+        }
+
+        // A slot for the first `if`, then the value of the second
+        let tree = &context.widget_manager.tree;
+        let fragment = tree.children[&tree.root_node.unwrap()][0];
+        let children = &tree.children[&fragment];
+        assert_eq!(2, children.len());
+        assert_eq!(None, tree.children.get(&children[0]));
+        assert_eq!(
+            StyleProp::Value(Units::Pixels(20.0)),
+            width_of(&context, children[1])
+        );
+    }
+
+    #[test]
+    fn should_remove_branches_that_are_no_longer_taken() {
+        let mut context = KayakContext::new();
+        let tab = Binding::new(0);
+        let count = mount_tabs(&mut context, &tab, true);
+        count.set(5);
+        context.render();
+        let counter = first_tab(&context)[0];
+
+        tab.set(1);
+        context.render();
+        assert!(!context.widget_manager.tree.flatten().contains(&counter));
+        assert!(first_tab(&context).is_empty());
+    }
+
+    #[test]
+    fn should_forward_styles_to_only_child() {
+        let mut context = KayakContext::new();
+        {
+            let context = &mut context;
+            let styles = Style {
+                width: StyleProp::Value(Units::Pixels(10.0)),
+                ..Default::default()
+            };
+            render! {
+                <Fragment styles={Some(styles)} forward_styles={true}>
+                    <Fragment />
+                </Fragment>
+            }
+        }
+
+        let widget_manager = &context.widget_manager;
+        let root = widget_manager.tree.root_node.unwrap();
+        let child = widget_manager.tree.children[&root][0];
+        assert_eq!(None, widget_manager.get_styles(root));
+        assert_eq!(
+            StyleProp::Value(Units::Pixels(10.0)),
+            widget_manager.get_styles(child).unwrap().width
+        );
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct ForwardingProps {
+        /// The number of children to forward the styles to
+        children: Binding<usize>,
+        /// The errors caught by the boundary, in order
+        errors: Binding<Vec<String>>,
+        #[prop_field(Styles)]
+        styles: Option<Style>,
+    }
+
+    #[widget]
+    fn Forwarding(props: ForwardingProps) {
+        props.styles = layout();
+        let count = props.children.clone();
+        context.bind(&count);
+        let result = context.catch_panic(move |context| {
+            let styles = Style {
+                width: StyleProp::Value(Units::Pixels(10.0)),
+                ..Default::default()
+            };
+            if count.get() > 1 {
+                rsx! {
+                    <Fragment styles={Some(styles)} forward_styles={true}>
+                        <Counter />
+                        <Counter />
+                    </Fragment>
+                }
+            } else {
+                rsx! {
+                    <Fragment styles={Some(styles)} forward_styles={true}>
+                        <Counter />
+                    </Fragment>
+                }
+            }
+        });
+        if let Err(error) = result {
+            let mut errors = props.errors.get();
+            errors.push(error);
+            props.errors.set(errors);
+        }
+    }
+
+    /// Returns the widths set on the counters within the forwarding fragment
+    fn forwarded_widths(context: &KayakContext) -> Vec<Option<StyleProp<Units>>> {
+        let widget_manager = &context.widget_manager;
+        let tree = &widget_manager.tree;
+        let fragment = tree.children[&tree.root_node.unwrap()][0];
+        tree.children
+            .get(&fragment)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| {
+                let width = widget_manager.get_styles(id).unwrap().width;
+                (width != StyleProp::Default).then_some(width)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_not_forward_styles_to_multiple_children() {
+        let mut context = KayakContext::new();
+        let count = Binding::new(2);
+        let errors = Binding::new(Vec::new());
+        let props = ForwardingProps {
+            children: count.clone(),
+            errors: errors.clone(),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Forwarding::constructor(props));
+
+        // The error is passed to the closest error boundary, which is re-rendered with it
+        assert_eq!(
+            vec![String::from(
+                "A `Fragment` that forwards its styles must have at most one child, but it has 2"
+            )],
+            errors.get()
+        );
+
+        count.set(1);
+        context.render();
+        assert_eq!(
+            vec![Some(StyleProp::Value(Units::Pixels(10.0)))],
+            forwarded_widths(&context)
+        );
+        assert_eq!(1, errors.get().len());
+    }
+
+    #[test]
+    fn should_leave_styles_unforwarded_outside_of_error_boundaries() {
+        let mut context = KayakContext::new();
+        {
+            let context = &mut context;
+            render! {
+                <Fragment styles={Some(Style::default())} forward_styles={true}>
+                    <Fragment />
+                    <Fragment />
+                </Fragment>
+            }
+        }
+
+        let widget_manager = &context.widget_manager;
+        let root = widget_manager.tree.root_node.unwrap();
+        for child in &widget_manager.tree.children[&root] {
+            assert_eq!(None, widget_manager.get_styles(*child));
+        }
+    }
+}
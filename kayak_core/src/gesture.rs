@@ -0,0 +1,605 @@
+use std::ops::{BitOr, BitOrAssign};
+use std::time::{Duration, Instant};
+
+use crate::{Event, EventType, Index};
+
+/// The distance (in pixels) a touch can move before it's no longer considered a tap or long-press
+const TOUCH_SLOP: f32 = 10.0;
+/// How long a touch needs to be held in place to be considered a long-press
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
+/// The phase of a touch
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TouchPhase {
+    /// The touch began
+    Started,
+    /// The touch moved
+    Moved,
+    /// The touch ended normally
+    Ended,
+    /// The touch was cancelled (such as by the system)
+    Cancelled,
+}
+
+/// An event created by a touch
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct TouchEvent {
+    /// The ID of the touch, which is unique amongst all active touches
+    pub id: u64,
+    /// The position of the touch
+    pub position: (f32, f32),
+}
+
+/// The phase of a continuous gesture, such as a pan or pinch
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GesturePhase {
+    /// The gesture was just recognized
+    Started,
+    /// The gesture was updated
+    Changed,
+    /// The gesture ended
+    Ended,
+}
+
+impl Default for GesturePhase {
+    fn default() -> Self {
+        Self::Started
+    }
+}
+
+/// An event created while panning with a single touch
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct PanEvent {
+    /// The phase of the pan
+    pub phase: GesturePhase,
+    /// The current position of the touch
+    pub position: (f32, f32),
+    /// The distance moved since the previous pan event
+    pub delta: (f32, f32),
+}
+
+/// An event created while pinching with two touches
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PinchEvent {
+    /// The phase of the pinch
+    pub phase: GesturePhase,
+    /// The change in scale since the previous pinch event
+    ///
+    /// This is the ratio between the current and previous distance of the touches, so values above
+    /// `1.0` zoom in and values below `1.0` zoom out. Scales can be accumulated by multiplying them.
+    pub scale_delta: f32,
+    /// The point between the two touches
+    pub focal_point: (f32, f32),
+}
+
+impl Default for PinchEvent {
+    fn default() -> Self {
+        Self {
+            phase: GesturePhase::default(),
+            scale_delta: 1.0,
+            focal_point: Default::default(),
+        }
+    }
+}
+
+/// A set of gestures recognized by a widget
+///
+/// Widgets need to opt into the gestures they want to receive (see
+/// [`KayakContextRef::set_gestures`](crate::KayakContextRef::set_gestures)). A gesture is sent
+/// to the nearest widget at or above the touched widget that recognizes it, rather than
+/// propagating. This allows nested widgets to handle different gestures without conflicting,
+/// such as a button recognizing taps within a scroll box that recognizes pans.
+///
+/// Raw touch events ([`TouchStart`](EventType::TouchStart), [`TouchMove`](EventType::TouchMove)
+/// and [`TouchEnd`](EventType::TouchEnd)) are always sent to the touched widget.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Gestures(u8);
+
+impl Gestures {
+    /// No gestures
+    pub const NONE: Self = Self(0);
+    /// A quick touch that doesn't move
+    pub const TAP: Self = Self(1 << 0);
+    /// A touch held in place
+    pub const LONG_PRESS: Self = Self(1 << 1);
+    /// A single touch moving around
+    pub const PAN: Self = Self(1 << 2);
+    /// Two touches moving towards or away from each other
+    pub const PINCH: Self = Self(1 << 3);
+    /// All gestures
+    pub const ALL: Self = Self(Self::TAP.0 | Self::LONG_PRESS.0 | Self::PAN.0 | Self::PINCH.0);
+
+    /// Returns true if all of the given gestures are in this set
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if this set contains no gestures
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Gestures {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Gestures {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ActiveTouch {
+    id: u64,
+    /// The widget that was touched
+    origin: Index,
+    start_position: (f32, f32),
+    position: (f32, f32),
+    started_at: Instant,
+    /// Whether this touch can no longer be a tap or long-press
+    ///
+    /// This is set once the touch moves past the slop or becomes part of a multi-touch gesture.
+    moved: bool,
+    long_pressed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct ActivePinch {
+    target: Index,
+    distance: f32,
+    focal_point: (f32, f32),
+}
+
+/// Synthesizes gesture events from raw touches
+#[derive(Debug, Default, Clone)]
+pub(crate) struct GestureRecognizer {
+    touches: Vec<ActiveTouch>,
+    pan: Option<Index>,
+    pinch: Option<ActivePinch>,
+}
+
+impl GestureRecognizer {
+    /// Returns the widget touched by the given active touch
+    pub fn origin(&self, id: u64) -> Option<Index> {
+        self.touches
+            .iter()
+            .find(|touch| touch.id == id)
+            .map(|touch| touch.origin)
+    }
+
+    /// Processes a touch, returning the resulting gesture events
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The ID of the touch
+    /// * `phase`: The phase of the touch
+    /// * `position`: The position of the touch
+    /// * `origin`: The widget that was touched (only used when the touch starts)
+    /// * `now`: The current time
+    /// * `resolve`: Finds the widget that should receive the given gesture for a touched widget
+    ///
+    /// returns: Vec<Event>
+    pub fn process_touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: (f32, f32),
+        origin: Option<Index>,
+        now: Instant,
+        resolve: impl Fn(Index, Gestures) -> Option<Index>,
+    ) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        match phase {
+            TouchPhase::Started => {
+                let origin = match origin {
+                    Some(origin) => origin,
+                    None => return events,
+                };
+
+                self.touches.retain(|touch| touch.id != id);
+                self.touches.push(ActiveTouch {
+                    id,
+                    origin,
+                    start_position: position,
+                    position,
+                    started_at: now,
+                    moved: false,
+                    long_pressed: false,
+                });
+
+                if self.touches.len() == 2 {
+                    // A second touch turns any single touch gesture into a pinch
+                    self.end_pan(self.touches[0].position, &mut events);
+                    for touch in &mut self.touches {
+                        touch.moved = true;
+                    }
+
+                    let (distance, focal_point) = self.pinch_geometry();
+                    if let Some(target) = resolve(self.touches[0].origin, Gestures::PINCH) {
+                        self.pinch = Some(ActivePinch {
+                            target,
+                            distance,
+                            focal_point,
+                        });
+                        events.push(Event::new(
+                            target,
+                            EventType::Pinch(PinchEvent {
+                                phase: GesturePhase::Started,
+                                scale_delta: 1.0,
+                                focal_point,
+                            }),
+                        ));
+                    }
+                }
+            }
+            TouchPhase::Moved => {
+                let index = match self.touches.iter().position(|touch| touch.id == id) {
+                    Some(index) => index,
+                    None => return events,
+                };
+
+                let touch = &mut self.touches[index];
+                let previous = touch.position;
+                touch.position = position;
+
+                if self.touches.len() == 1 {
+                    let touch = &mut self.touches[0];
+                    if !touch.moved && distance(touch.start_position, position) > TOUCH_SLOP {
+                        touch.moved = true;
+                        self.pan = resolve(touch.origin, Gestures::PAN);
+                        if let Some(target) = self.pan {
+                            events.push(Event::new(
+                                target,
+                                EventType::Pan(PanEvent {
+                                    phase: GesturePhase::Started,
+                                    position,
+                                    delta: difference(position, touch.start_position),
+                                }),
+                            ));
+                        }
+                    } else if let Some(target) = self.pan {
+                        events.push(Event::new(
+                            target,
+                            EventType::Pan(PanEvent {
+                                phase: GesturePhase::Changed,
+                                position,
+                                delta: difference(position, previous),
+                            }),
+                        ));
+                    }
+                } else if index < 2 {
+                    let (distance, focal_point) = self.pinch_geometry();
+                    if let Some(pinch) = &mut self.pinch {
+                        if pinch.distance > f32::EPSILON {
+                            events.push(Event::new(
+                                pinch.target,
+                                EventType::Pinch(PinchEvent {
+                                    phase: GesturePhase::Changed,
+                                    scale_delta: distance / pinch.distance,
+                                    focal_point,
+                                }),
+                            ));
+                        }
+                        pinch.distance = distance;
+                        pinch.focal_point = focal_point;
+                    }
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let index = match self.touches.iter().position(|touch| touch.id == id) {
+                    Some(index) => index,
+                    None => return events,
+                };
+
+                let touch = self.touches.remove(index);
+                if self.touches.is_empty() {
+                    self.end_pan(position, &mut events);
+
+                    let is_tap = matches!(phase, TouchPhase::Ended)
+                        && !touch.moved
+                        && !touch.long_pressed
+                        && now.duration_since(touch.started_at) < LONG_PRESS_DURATION;
+                    if is_tap {
+                        if let Some(target) = resolve(touch.origin, Gestures::TAP) {
+                            events.push(Event::new(
+                                target,
+                                EventType::Tap(TouchEvent { id, position }),
+                            ));
+                        }
+                    }
+                }
+
+                if self.touches.len() < 2 {
+                    if let Some(pinch) = self.pinch.take() {
+                        events.push(Event::new(
+                            pinch.target,
+                            EventType::Pinch(PinchEvent {
+                                phase: GesturePhase::Ended,
+                                scale_delta: 1.0,
+                                focal_point: pinch.focal_point,
+                            }),
+                        ));
+                    }
+                }
+            }
+        }
+
+        events
+    }
+
+    /// Checks for gestures that are recognized by the passing of time, such as long-presses
+    pub fn update(
+        &mut self,
+        now: Instant,
+        resolve: impl Fn(Index, Gestures) -> Option<Index>,
+    ) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        if let [touch] = self.touches.as_mut_slice() {
+            let is_long_press = !touch.moved
+                && !touch.long_pressed
+                && now.duration_since(touch.started_at) >= LONG_PRESS_DURATION;
+            if is_long_press {
+                touch.long_pressed = true;
+                if let Some(target) = resolve(touch.origin, Gestures::LONG_PRESS) {
+                    events.push(Event::new(
+                        target,
+                        EventType::LongPress(TouchEvent {
+                            id: touch.id,
+                            position: touch.position,
+                        }),
+                    ));
+                }
+            }
+        }
+
+        events
+    }
+
+    fn end_pan(&mut self, position: (f32, f32), events: &mut Vec<Event>) {
+        if let Some(target) = self.pan.take() {
+            events.push(Event::new(
+                target,
+                EventType::Pan(PanEvent {
+                    phase: GesturePhase::Ended,
+                    position,
+                    delta: (0.0, 0.0),
+                }),
+            ));
+        }
+    }
+
+    /// Returns the distance between the first two touches and the point between them
+    fn pinch_geometry(&self) -> (f32, (f32, f32)) {
+        let a = self.touches[0].position;
+        let b = self.touches[1].position;
+        (distance(a, b), ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0))
+    }
+}
+
+fn difference(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = difference(a, b);
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GestureRecognizer, Gestures, TouchPhase, LONG_PRESS_DURATION};
+    use crate::{Event, EventType, GesturePhase, Index};
+    use std::time::{Duration, Instant};
+
+    fn resolve_all(origin: Index, _: Gestures) -> Option<Index> {
+        Some(origin)
+    }
+
+    fn event_types(events: Vec<Event>) -> Vec<EventType> {
+        events.into_iter().map(|event| event.event_type).collect()
+    }
+
+    #[test]
+    fn should_recognize_tap() {
+        let origin = Index::from_raw_parts(1, 0);
+        let now = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        let events = recognizer.process_touch(
+            0,
+            TouchPhase::Started,
+            (10.0, 10.0),
+            Some(origin),
+            now,
+            resolve_all,
+        );
+        assert!(events.is_empty());
+        let events =
+            recognizer.process_touch(0, TouchPhase::Moved, (12.0, 11.0), None, now, resolve_all);
+        assert!(events.is_empty());
+        let events = recognizer.process_touch(
+            0,
+            TouchPhase::Ended,
+            (12.0, 11.0),
+            None,
+            now + Duration::from_millis(100),
+            resolve_all,
+        );
+
+        assert_eq!(1, events.len());
+        assert_eq!(origin, events[0].target);
+        assert!(matches!(events[0].event_type, EventType::Tap(..)));
+    }
+
+    #[test]
+    fn should_recognize_long_press_instead_of_tap() {
+        let origin = Index::from_raw_parts(1, 0);
+        let now = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.process_touch(
+            0,
+            TouchPhase::Started,
+            (10.0, 10.0),
+            Some(origin),
+            now,
+            resolve_all,
+        );
+        assert!(recognizer.update(now, resolve_all).is_empty());
+
+        let later = now + LONG_PRESS_DURATION;
+        let events = event_types(recognizer.update(later, resolve_all));
+        assert!(matches!(events[..], [EventType::LongPress(..)]));
+        assert!(recognizer.update(later, resolve_all).is_empty());
+
+        let events =
+            recognizer.process_touch(0, TouchPhase::Ended, (10.0, 10.0), None, later, resolve_all);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn should_recognize_pan() {
+        let origin = Index::from_raw_parts(1, 0);
+        let now = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.process_touch(
+            0,
+            TouchPhase::Started,
+            (10.0, 10.0),
+            Some(origin),
+            now,
+            resolve_all,
+        );
+        let mut events = Vec::new();
+        for (phase, position) in [
+            (TouchPhase::Moved, (30.0, 10.0)),
+            (TouchPhase::Moved, (30.0, 25.0)),
+            (TouchPhase::Ended, (30.0, 25.0)),
+        ] {
+            events.extend(event_types(recognizer.process_touch(
+                0,
+                phase,
+                position,
+                None,
+                now,
+                resolve_all,
+            )));
+        }
+
+        let pans: Vec<_> = events
+            .into_iter()
+            .map(|event_type| match event_type {
+                EventType::Pan(pan) => (pan.phase, pan.delta),
+                _ => panic!("expected only pan events"),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (GesturePhase::Started, (20.0, 0.0)),
+                (GesturePhase::Changed, (0.0, 15.0)),
+                (GesturePhase::Ended, (0.0, 0.0)),
+            ],
+            pans
+        );
+    }
+
+    #[test]
+    fn should_recognize_pinch() {
+        let origin = Index::from_raw_parts(1, 0);
+        let now = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.process_touch(
+            0,
+            TouchPhase::Started,
+            (0.0, 0.0),
+            Some(origin),
+            now,
+            resolve_all,
+        );
+        let events = event_types(recognizer.process_touch(
+            1,
+            TouchPhase::Started,
+            (10.0, 0.0),
+            Some(origin),
+            now,
+            resolve_all,
+        ));
+        assert!(matches!(events[..], [EventType::Pinch(..)]));
+
+        let events = event_types(recognizer.process_touch(
+            1,
+            TouchPhase::Moved,
+            (20.0, 0.0),
+            None,
+            now,
+            resolve_all,
+        ));
+        match events[..] {
+            [EventType::Pinch(pinch)] => {
+                assert_eq!(GesturePhase::Changed, pinch.phase);
+                assert_eq!(2.0, pinch.scale_delta);
+                assert_eq!((10.0, 0.0), pinch.focal_point);
+            }
+            _ => panic!("expected a pinch event"),
+        }
+
+        // Lifting one finger ends the pinch without tapping or panning
+        let events = event_types(recognizer.process_touch(
+            1,
+            TouchPhase::Ended,
+            (20.0, 0.0),
+            None,
+            now,
+            resolve_all,
+        ));
+        assert!(matches!(events[..], [EventType::Pinch(..)]));
+        let events = event_types(recognizer.process_touch(
+            0,
+            TouchPhase::Moved,
+            (40.0, 0.0),
+            None,
+            now,
+            resolve_all,
+        ));
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn should_only_send_gestures_to_recognizing_widgets() {
+        let origin = Index::from_raw_parts(1, 0);
+        let parent = Index::from_raw_parts(2, 0);
+        let now = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+        let resolve = |origin: Index, gesture: Gestures| {
+            if gesture == Gestures::PAN {
+                Some(parent)
+            } else {
+                Some(origin)
+            }
+        };
+
+        recognizer.process_touch(
+            0,
+            TouchPhase::Started,
+            (0.0, 0.0),
+            Some(origin),
+            now,
+            resolve,
+        );
+        let events =
+            recognizer.process_touch(0, TouchPhase::Moved, (0.0, 50.0), None, now, resolve);
+
+        assert_eq!(1, events.len());
+        assert_eq!(parent, events[0].target);
+    }
+}
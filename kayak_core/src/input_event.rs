@@ -1,4 +1,4 @@
-use crate::KeyCode;
+use crate::{KeyCode, TouchPhase};
 
 /// Events sent to [`KayakContext`](crate::KayakContext) containing user input data
 #[derive(Debug, PartialEq)]
@@ -11,10 +11,28 @@ pub enum InputEvent {
     MouseLeftRelease,
     /// An event that occurs when the user scrolls
     Scroll { dx: f32, dy: f32, is_line: bool },
+    /// An event that occurs when a touch starts, moves, or ends
+    ///
+    /// The `id` identifies the touch amongst all active touches and the `position` has its origin
+    /// at the top-left corner, just like [`MouseMoved`](Self::MouseMoved).
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        position: (f32, f32),
+    },
     /// An event that occurs when the user types in a character
     CharEvent { c: char },
     /// An event that occurs when the user presses or releases a key
     Keyboard { key: KeyCode, is_pressed: bool },
+    /// An event that occurs when an IME updates its in-progress (composing) text
+    ///
+    /// The `cursor` is the `(start, end)` byte range within `text` targeted by the IME, if any.
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// An event that occurs when an IME commits its finalized text
+    ImeCommit(String),
 }
 
 /// The various categories an input event can belong to
@@ -23,6 +41,8 @@ pub enum InputEventCategory {
     Mouse,
     /// A category for events related to the keyboard
     Keyboard,
+    /// A category for events related to touches
+    Touch,
     // TODO: Gamepad, etc.
 }
 
@@ -35,9 +55,13 @@ impl InputEvent {
             Self::MouseLeftPress => InputEventCategory::Mouse,
             Self::MouseLeftRelease => InputEventCategory::Mouse,
             Self::Scroll { .. } => InputEventCategory::Mouse,
+            // Touch events
+            Self::Touch { .. } => InputEventCategory::Touch,
             // Keyboard events
             Self::CharEvent { .. } => InputEventCategory::Keyboard,
             Self::Keyboard { .. } => InputEventCategory::Keyboard,
+            Self::ImePreedit { .. } => InputEventCategory::Keyboard,
+            Self::ImeCommit(..) => InputEventCategory::Keyboard,
         }
     }
 }
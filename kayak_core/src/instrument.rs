@@ -0,0 +1,177 @@
+//! Tracing spans for the phases of the render pipeline
+//!
+//! With the `tracing` feature enabled, each phase is wrapped in a [`tracing`] span whose fields
+//! record how much work the phase did. Without it, spans compile down to nothing.
+
+/// A span covering one phase of the render pipeline
+///
+/// The span is exited when this is dropped.
+#[must_use]
+pub(crate) struct PhaseSpan(#[cfg(feature = "tracing")] tracing::span::EnteredSpan);
+
+impl PhaseSpan {
+    #[cfg(feature = "tracing")]
+    pub fn new(span: tracing::Span) -> Self {
+        Self(span.entered())
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self()
+    }
+
+    /// Records a count for one of the fields declared by [`phase_span`]
+    #[inline(always)]
+    #[allow(unused_variables)]
+    pub fn record(&self, field: &'static str, count: usize) {
+        #[cfg(feature = "tracing")]
+        self.0.record(field, &count);
+    }
+
+    /// Exits the span before the end of its scope
+    #[inline(always)]
+    pub fn exit(self) {}
+}
+
+/// Enters a [`PhaseSpan`] with the given name and (initially empty) count fields
+///
+/// ```ignore
+/// let span = phase_span!("kayak_layout", nodes);
+/// span.record("nodes", node_count);
+/// ```
+macro_rules! phase_span {
+    ($name: literal $(, $field: ident)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        let span = $crate::instrument::PhaseSpan::new(tracing::info_span!(
+            $name,
+            $($field = tracing::field::Empty),*
+        ));
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::instrument::PhaseSpan::new();
+        span
+    }};
+}
+
+pub(crate) use phase_span;
+
+#[cfg(test)]
+mod tests {
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{mount_widget, Binding, Bound, KayakContext, MutableBound, Widget};
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct LabelProps {
+        text: Binding<u32>,
+    }
+
+    #[widget]
+    fn Label(props: LabelProps) {
+        context.bind(&props.text);
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct LabelsProps {
+        first: Binding<u32>,
+        second: Binding<u32>,
+    }
+
+    #[widget]
+    fn Labels(props: LabelsProps) {
+        let first = props.first.clone();
+        let second = props.second.clone();
+        context.add_widget(Label::constructor(LabelProps { text: first }), 0);
+        context.add_widget(Label::constructor(LabelProps { text: second }), 1);
+    }
+
+    /// Mounts two labels, returning the binding of the first
+    fn mount(context: &mut KayakContext) -> Binding<u32> {
+        let first = Binding::new(0);
+        let props = LabelsProps {
+            first: first.clone(),
+            second: Binding::new(0),
+        };
+        mount_widget(context, Labels::constructor(props));
+        first
+    }
+
+    #[test]
+    fn should_count_the_widgets_built_by_a_render() {
+        let mut context = KayakContext::new();
+        let first = mount(&mut context);
+
+        first.set(1);
+        context.render();
+        assert_eq!(1, context.widgets_built);
+
+        context.render();
+        assert_eq!(0, context.widgets_built);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn should_record_the_phases_of_a_render() {
+        use std::fmt::Debug;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Dispatch, Event, Metadata, Subscriber};
+
+        /// Logs the name of each new span and each recorded field
+        #[derive(Default)]
+        struct Recorder {
+            spans: Mutex<Vec<&'static str>>,
+            log: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct Fields<'a>(&'static str, &'a Mutex<Vec<String>>);
+
+        impl Visit for Fields<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                let entry = format!("{} {}={:?}", self.0, field.name(), value);
+                self.1.lock().unwrap().push(entry);
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let name = span.metadata().name();
+                self.log.lock().unwrap().push(name.to_string());
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(name);
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, span: &Id, values: &Record<'_>) {
+                let name = self.spans.lock().unwrap()[span.into_u64() as usize - 1];
+                values.record(&mut Fields(name, &self.log));
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, _: &Event<'_>) {}
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let mut context = KayakContext::new();
+        let first = mount(&mut context);
+        let recorder = Recorder::default();
+        let log = recorder.log.clone();
+
+        first.set(1);
+        tracing::dispatcher::with_default(&Dispatch::new(recorder), || context.render());
+
+        let log = log.lock().unwrap();
+        assert_eq!(Some("kayak_frame"), log.first().map(String::as_str));
+        assert!(log.contains(&String::from("kayak_build widgets=1")));
+        assert!(log.contains(&String::from("kayak_layout")));
+    }
+}
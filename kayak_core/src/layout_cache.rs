@@ -19,6 +19,41 @@ impl Rect {
         (point.0 >= self.posx && point.0 <= self.posx + self.width)
             && (point.1 >= self.posy && point.1 <= self.posy + self.height)
     }
+
+    /// Returns the smallest rect containing both this rect and the given one, keeping this rect's
+    /// z-index
+    pub fn union(&self, other: &Rect) -> Rect {
+        let posx = self.posx.min(other.posx);
+        let posy = self.posy.min(other.posy);
+        let right = (self.posx + self.width).max(other.posx + other.width);
+        let bottom = (self.posy + self.height).max(other.posy + other.height);
+
+        Rect {
+            posx,
+            posy,
+            width: right - posx,
+            height: bottom - posy,
+            z_index: self.z_index,
+        }
+    }
+
+    /// Returns the overlapping area of this rect and the given one, keeping this rect's z-index
+    ///
+    /// If the rects don't overlap, the returned rect has a width and/or height of zero.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let posx = self.posx.max(other.posx);
+        let posy = self.posy.max(other.posy);
+        let right = (self.posx + self.width).min(other.posx + other.width);
+        let bottom = (self.posy + self.height).min(other.posy + other.height);
+
+        Rect {
+            posx,
+            posy,
+            width: (right - posx).max(0.0),
+            height: (bottom - posy).max(0.0),
+            z_index: self.z_index,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
@@ -35,10 +70,42 @@ pub struct Size {
     pub height: f32,
 }
 
+/// The intrinsic (content-based) widths of a node
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct IntrinsicSize {
+    /// The narrowest the content can be without overflowing (e.g. the longest word of some text)
+    pub min_content: f32,
+    /// The width of the content without any wrapping
+    pub max_content: f32,
+}
+
+impl IntrinsicSize {
+    /// Calculates the shrink-to-fit width within the given available space
+    ///
+    /// This is the max-content width, limited by the available space, but never less
+    /// than the min-content width.
+    pub fn fit(&self, available: f32) -> f32 {
+        self.max_content.min(available).max(self.min_content)
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct LayoutCache {
     // Computed Outputs
     pub rect: HashMap<Index, Rect>,
+    /// The intrinsic sizes of nodes (calculated from their content)
+    pub(crate) intrinsic_size: HashMap<Index, IntrinsicSize>,
+    /// The intrinsic sizes measured directly for leaf nodes, such as text
+    pub(crate) measured_size: HashMap<Index, IntrinsicSize>,
+    /// The scale that nodes are drawn at, from the [transforms](crate::styles::Style::transform)
+    /// of themselves and their ancestors
+    ///
+    /// Nodes drawn at their laid out size have no entry.
+    pub(crate) scale: HashMap<Index, f32>,
+    /// The rects of the nodes moved by transforms, as of the last layout
+    ///
+    /// These are used to tell whether a transformed node actually moved since the last layout.
+    pub(crate) transformed_rect: HashMap<Index, Rect>,
 
     // Intermediate Values
     space: HashMap<Index, Space>,
@@ -106,6 +173,14 @@ impl LayoutCache {
         self.rect.entry(node).or_default();
     }
 
+    /// Returns the intrinsic size of the given node, if it has one
+    ///
+    /// Only nodes with measurable content (i.e. text) or with descendants containing
+    /// such content will have an intrinsic size.
+    pub fn intrinsic_size(&self, node: &Index) -> Option<&IntrinsicSize> {
+        self.intrinsic_size.get(node)
+    }
+
     /// Returns an iterator over nodes whose layout have been changed since the last update
     pub fn iter_changed(&self) -> Iter<'_, Index, GeometryChanged> {
         self.geometry_changed.iter()
@@ -384,3 +459,76 @@ impl Cache for LayoutCache {
         *self.stack_last_child.get_mut(&node).unwrap() = value;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{IntrinsicSize, Rect};
+
+    #[test]
+    fn intrinsic_size_should_fit_available_space() {
+        let size = IntrinsicSize {
+            min_content: 40.0,
+            max_content: 120.0,
+        };
+
+        // Shrinks to max-content when there's plenty of room
+        assert_eq!(120.0, size.fit(200.0));
+        // Fills the available space when it's between the two
+        assert_eq!(80.0, size.fit(80.0));
+        // Never shrinks below min-content
+        assert_eq!(40.0, size.fit(10.0));
+    }
+
+    #[test]
+    fn intersection_should_clamp_to_overlap() {
+        let outer = Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: 100.0,
+            height: 100.0,
+            z_index: 1.0,
+        };
+        let inner = Rect {
+            posx: 50.0,
+            posy: 80.0,
+            width: 100.0,
+            height: 100.0,
+            z_index: 2.0,
+        };
+
+        let overlap = inner.intersection(&outer);
+        assert_eq!((50.0, 80.0), (overlap.posx, overlap.posy));
+        assert_eq!((50.0, 20.0), (overlap.width, overlap.height));
+        assert_eq!(2.0, overlap.z_index);
+
+        let disjoint = Rect {
+            posx: 200.0,
+            ..outer
+        };
+        let empty = disjoint.intersection(&outer);
+        assert_eq!(0.0, empty.width);
+    }
+
+    #[test]
+    fn union_should_contain_both_rects() {
+        let a = Rect {
+            posx: 10.0,
+            posy: 50.0,
+            width: 20.0,
+            height: 10.0,
+            z_index: 1.0,
+        };
+        let b = Rect {
+            posx: 40.0,
+            posy: 20.0,
+            width: 10.0,
+            height: 10.0,
+            z_index: 2.0,
+        };
+
+        let union = a.union(&b);
+        assert_eq!((10.0, 20.0), (union.posx, union.posy));
+        assert_eq!((40.0, 40.0), (union.width, union.height));
+        assert_eq!(1.0, union.z_index);
+    }
+}
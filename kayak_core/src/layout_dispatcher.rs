@@ -37,6 +37,10 @@ impl LayoutEventDispatcher {
     }
 
     fn process(index: Index, flags: GeometryChanged, context: &mut KayakContext) {
+        if let Some(rect) = context.widget_manager.layout_cache.rect.get(&index) {
+            context.update_widget_layout(index, (*rect).into());
+        }
+
         // We should be able to just get layout from WidgetManager here
         // since the layouts will be calculated by this point
         let widget = context.widget_manager.take(index);
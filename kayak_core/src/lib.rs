@@ -1,59 +1,127 @@
+mod absolute;
+mod animation;
 mod assets;
 mod binding;
+pub mod border;
 mod children;
+mod click_count;
+mod clipboard;
 pub mod color;
 pub mod context;
 mod context_ref;
 mod cursor;
 mod cursor_icon;
+pub mod curve;
+pub mod debug_overlay;
+mod drag;
+pub mod draw_list;
+mod dynamic_widget;
+mod easing;
+pub mod error_boundary;
 pub mod event;
 mod event_dispatcher;
+mod fit_content;
+mod flex;
 mod flo_binding;
 mod focus_tree;
 pub mod fragment;
 pub(crate) mod generational_arena;
+mod gesture;
 mod input_event;
+mod instrument;
 mod keyboard;
 mod keys;
 mod layout;
 pub mod layout_cache;
 mod layout_dispatcher;
 mod lifetime;
+pub mod list_navigation;
+pub mod markup;
+mod metrics;
 mod multi_state;
 pub mod node;
 mod on_event;
 mod on_layout;
+mod on_mount;
+mod overview;
+mod paused;
+pub mod portal;
+mod previous;
+pub mod prop_fields;
+pub mod render_backend;
 pub mod render_command;
 pub mod render_primitive;
+mod selection;
+mod shortcut;
+mod spring;
+#[cfg(feature = "serde")]
+mod snapshot;
+mod stack;
+mod sticky;
 pub mod styles;
+mod subtree;
+mod theme;
+mod throttle;
+mod timer;
+mod transform;
+mod transition;
 pub mod tree;
 mod vec;
 pub mod widget;
 pub mod widget_manager;
+#[cfg(feature = "bevy_renderer")]
+mod world_commands;
+#[cfg(feature = "bevy_renderer")]
+mod world_query;
 
 use std::sync::{Arc, RwLock};
 
+pub use animation::{AnimationController, AnimationOptions};
 pub use binding::*;
 pub use children::Children;
+pub use click_count::{ClickCount, MULTI_CLICK_TIMEOUT};
+pub use clipboard::{Clipboard, LocalClipboard};
 pub use color::Color;
 pub use context::*;
 pub use context_ref::KayakContextRef;
 pub use cursor::*;
 pub use cursor_icon::CursorIcon;
+pub use curve::{Arrow, Bezier, Curve};
+pub use drag::{DragConstraints, DragPosition};
+pub use draw_list::{DrawCommand, DrawList, DrawQuad};
+pub use dynamic_widget::{DynamicProps, DynamicValue, DynamicWidget, DynamicWidgetProps};
+pub use easing::Easing;
 pub use event::*;
 pub use focus_tree::FocusTree;
-pub use fragment::{Fragment, FragmentProps};
+pub use fragment::{ForwardStylesError, Fragment, FragmentProps};
 pub use generational_arena::{Arena, Index};
+pub use gesture::{GesturePhase, Gestures, PanEvent, PinchEvent, TouchEvent, TouchPhase};
 pub use input_event::*;
 pub use keyboard::{KeyboardEvent, KeyboardModifiers};
 pub use keys::KeyCode;
 pub use layout::*;
+pub use list_navigation::{ListItem, ListNavigation};
+pub use markup::{Markup, MarkupBindings, MarkupError};
+pub use metrics::FrameMetrics;
 pub use on_event::OnEvent;
 pub use on_layout::OnLayout;
+pub use on_mount::OnMount;
+pub use prop_fields::PropFields;
 pub use resources::Resources;
+pub use shortcut::{OnShortcut, Shortcut, ShortcutScope};
+pub use spring::Spring;
+#[cfg(feature = "serde")]
+pub use snapshot::StateSnapshot;
+pub use subtree::Subtree;
+pub use theme::{Theme, ThemeBinding, ThemeColors, ThemeSizes};
+pub use throttle::ThrottledSetter;
+pub use timer::TimeoutHandle;
+pub use transition::Transition;
 pub use tree::{Tree, WidgetTree};
 pub use vec::{VecTracker, VecTrackerProps};
 pub use widget::{BaseWidget, Widget, WidgetProps};
+#[cfg(feature = "bevy_renderer")]
+pub use world_commands::WorldCommands;
 
 /// The default font name used by Kayak
 pub const DEFAULT_FONT: &str = "Kayak-Default";
@@ -0,0 +1,407 @@
+//! Keyboard navigation of lists, such as menus and listboxes
+//!
+//! A [`ListNavigation`] tracks which item of a list is highlighted and moves that highlight in
+//! response to keyboard events. It's created with
+//! [`KayakContextRef::use_list_navigation`](crate::KayakContextRef::use_list_navigation) (or the
+//! `use_list_navigation!` macro), and its [`on_event`](ListNavigation::on_event) handler should be
+//! given to the focusable widget containing the list.
+//!
+//! | Key             | Action                                                  |
+//! | :-------------- | :------------------------------------------------------ |
+//! | `Up` / `Down`   | Highlight the previous/next item                        |
+//! | `Home` / `End`  | Highlight the first/last item                           |
+//! | `Return`        | Activate the highlighted item                           |
+//! | Any character   | Highlight the next item whose label starts with the text typed so far |
+//!
+//! Disabled items are always skipped.
+//!
+//! Typed characters are collected into a type-ahead query, which starts over once nothing has been
+//! typed for [`TYPE_AHEAD_TIMEOUT`]. Queries are matched against item labels with a
+//! [`TypeAheadMatcher`], which defaults to case-insensitive prefix matching.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Binding, Bound, EventType, Handler, KeyCode, MutableBound, OnEvent};
+
+/// How long after the last typed character a type-ahead search starts over
+pub const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// An item within a navigable list
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    /// The text that type-ahead searches are matched against
+    pub label: String,
+    /// Whether this item is skipped by navigation and can't be activated
+    pub disabled: bool,
+}
+
+impl ListItem {
+    /// Creates a new, enabled item with the given label
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            disabled: false,
+        }
+    }
+
+    /// Sets whether this item is disabled
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl From<&str> for ListItem {
+    fn from(label: &str) -> Self {
+        Self::new(label)
+    }
+}
+
+impl From<String> for ListItem {
+    fn from(label: String) -> Self {
+        Self::new(label)
+    }
+}
+
+/// Decides whether an item matches a type-ahead query
+///
+/// The default matcher checks whether the item's label starts with the query, ignoring case.
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_core::list_navigation::TypeAheadMatcher;
+///
+/// // Match any item whose label contains the query
+/// let matcher = TypeAheadMatcher::new(|item, query| {
+///     item.label.to_lowercase().contains(&query.to_lowercase())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct TypeAheadMatcher(pub Arc<dyn Fn(&ListItem, &str) -> bool + Send + Sync + 'static>);
+
+impl TypeAheadMatcher {
+    /// Create a new matcher from a function taking the item and the query typed so far
+    pub fn new<F: Fn(&ListItem, &str) -> bool + Send + Sync + 'static>(f: F) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Returns true if the given item matches the query
+    pub fn matches(&self, item: &ListItem, query: &str) -> bool {
+        (self.0)(item, query)
+    }
+}
+
+impl Default for TypeAheadMatcher {
+    fn default() -> Self {
+        Self::new(|item, query| item.label.to_lowercase().starts_with(&query.to_lowercase()))
+    }
+}
+
+impl std::fmt::Debug for TypeAheadMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypeAheadMatcher").finish()
+    }
+}
+
+/// The characters typed so far for a type-ahead search
+#[derive(Debug, Default)]
+struct TypeAheadQuery {
+    text: String,
+    last_input: Option<Instant>,
+}
+
+/// A type-ahead query, stored as widget state
+///
+/// Typing doesn't re-render the widget by itself, only highlighting a new item does.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TypeAhead(Arc<Mutex<TypeAheadQuery>>);
+
+impl PartialEq for TypeAhead {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The keyboard navigation state of a list
+///
+/// See the [module-level documentation](self) for the supported keys.
+#[derive(Clone)]
+pub struct ListNavigation {
+    items: Vec<ListItem>,
+    wrap: bool,
+    highlighted: Binding<Option<usize>>,
+    type_ahead: TypeAhead,
+    matcher: TypeAheadMatcher,
+    on_activate: Handler<usize>,
+}
+
+impl ListNavigation {
+    pub(crate) fn new(
+        items: Vec<ListItem>,
+        wrap: bool,
+        highlighted: Binding<Option<usize>>,
+        type_ahead: TypeAhead,
+        on_activate: Handler<usize>,
+    ) -> Self {
+        Self {
+            items,
+            wrap,
+            highlighted,
+            type_ahead,
+            matcher: TypeAheadMatcher::default(),
+            on_activate,
+        }
+    }
+
+    /// Sets the matcher used for type-ahead search
+    ///
+    /// This should be set before creating the [`on_event`](Self::on_event) handler.
+    pub fn with_matcher(mut self, matcher: TypeAheadMatcher) -> Self {
+        self.matcher = matcher;
+        self
+    }
+
+    /// Returns the index of the highlighted item, if any
+    pub fn highlighted(&self) -> Option<usize> {
+        self.highlighted
+            .get()
+            .filter(|index| *index < self.items.len())
+    }
+
+    /// Highlights the item at the given index, such as when it's hovered
+    ///
+    /// Disabled items can't be highlighted.
+    pub fn set_highlighted(&self, index: Option<usize>) {
+        let index = index.filter(|index| is_enabled(&self.items, *index));
+        if self.highlighted() != index {
+            self.highlighted.set(index);
+        }
+    }
+
+    /// Activates the highlighted item
+    ///
+    /// Returns false if no item is highlighted.
+    pub fn activate(&self) -> bool {
+        match self.highlighted() {
+            Some(index) if is_enabled(&self.items, index) => {
+                self.on_activate.call(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles the given event, returning true if it was used for navigation
+    ///
+    /// # Arguments
+    ///
+    /// * `event_type`: The event to handle
+    /// * `now`: The current time, used to time out type-ahead searches
+    ///
+    pub fn handle(&self, event_type: &EventType, now: Instant) -> bool {
+        let current = self.highlighted();
+        let index = match event_type {
+            EventType::KeyDown(event) => match event.key() {
+                KeyCode::Up => step(&self.items, current, false, self.wrap),
+                KeyCode::Down => step(&self.items, current, true, self.wrap),
+                KeyCode::Home => first(&self.items),
+                KeyCode::End => last(&self.items),
+                KeyCode::Return => return self.activate(),
+                _ => return false,
+            },
+            EventType::CharInput { c } => match self.type_ahead(*c, now) {
+                Some(query) => search(&self.items, current, &query, &self.matcher),
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        if index.is_some() {
+            self.set_highlighted(index);
+        }
+        true
+    }
+
+    /// Creates an event handler that calls [`handle`](Self::handle), using the context's
+    /// [frame time](crate::KayakContextRef::frame_time)
+    ///
+    /// Handled events are stopped from propagating, so that nested lists don't navigate their
+    /// parent as well.
+    pub fn on_event(&self) -> OnEvent {
+        let navigation = self.clone();
+        OnEvent::new(move |context, event| {
+            if navigation.handle(&event.event_type, context.frame_time()) {
+                event.stop_propagation();
+            }
+        })
+    }
+
+    /// Adds the given character to the type-ahead query, returning the updated query
+    ///
+    /// Returns `None` if the character can't be part of a query.
+    fn type_ahead(&self, c: char, now: Instant) -> Option<String> {
+        if c.is_control() {
+            return None;
+        }
+
+        let mut query = self.type_ahead.0.lock().ok()?;
+        let is_expired = query.last_input.map_or(true, |last_input| {
+            now.duration_since(last_input) > TYPE_AHEAD_TIMEOUT
+        });
+        if is_expired {
+            query.text.clear();
+        }
+        // A leading space is more likely meant for the widget itself (such as to open a menu)
+        if c.is_whitespace() && query.text.is_empty() {
+            return None;
+        }
+
+        query.text.push(c);
+        query.last_input = Some(now);
+        Some(query.text.clone())
+    }
+}
+
+fn is_enabled(items: &[ListItem], index: usize) -> bool {
+    items.get(index).map_or(false, |item| !item.disabled)
+}
+
+/// Returns the index of the first enabled item
+fn first(items: &[ListItem]) -> Option<usize> {
+    items.iter().position(|item| !item.disabled)
+}
+
+/// Returns the index of the last enabled item
+fn last(items: &[ListItem]) -> Option<usize> {
+    items.iter().rposition(|item| !item.disabled)
+}
+
+/// Returns the index of the next (or previous) enabled item
+///
+/// Without wrapping, the highlight stays where it is at either end of the list.
+fn step(items: &[ListItem], from: Option<usize>, forward: bool, wrap: bool) -> Option<usize> {
+    let from = match from {
+        Some(from) => from,
+        None if forward => return first(items),
+        None => return last(items),
+    };
+
+    let len = items.len() as isize;
+    for offset in 1..len {
+        let index = if forward {
+            from as isize + offset
+        } else {
+            from as isize - offset
+        };
+        if !wrap && (index < 0 || index >= len) {
+            break;
+        }
+
+        let index = index.rem_euclid(len) as usize;
+        if is_enabled(items, index) {
+            return Some(index);
+        }
+    }
+
+    Some(from)
+}
+
+/// Returns the index of the first enabled item matching the given query
+///
+/// The search starts from the highlighted item, so that typing more of its label keeps it
+/// highlighted. Typing the same character repeatedly (ignoring case) instead cycles through the
+/// items matching that character.
+fn search(
+    items: &[ListItem],
+    from: Option<usize>,
+    query: &str,
+    matcher: &TypeAheadMatcher,
+) -> Option<usize> {
+    let first_char = query.chars().next()?;
+    let is_repeated = query
+        .chars()
+        .all(|c| c.to_lowercase().eq(first_char.to_lowercase()));
+    let (query, start) = if is_repeated {
+        (first_char.to_string(), from.map_or(0, |from| from + 1))
+    } else {
+        (query.to_string(), from.unwrap_or(0))
+    };
+
+    (0..items.len())
+        .map(|offset| (start + offset) % items.len())
+        .find(|index| {
+            let item = &items[*index];
+            !item.disabled && matcher.matches(item, &query)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first, last, step, ListItem, TypeAheadMatcher};
+
+    fn search(items: &[ListItem], from: Option<usize>, query: &str) -> Option<usize> {
+        super::search(items, from, query, &TypeAheadMatcher::default())
+    }
+
+    fn items() -> Vec<ListItem> {
+        vec![
+            ListItem::new("Apple"),
+            ListItem::new("Apricot").with_disabled(true),
+            ListItem::new("Banana"),
+            ListItem::new("Avocado"),
+            ListItem::new("Cherry").with_disabled(true),
+        ]
+    }
+
+    #[test]
+    fn should_skip_disabled_items() {
+        let items = items();
+        assert_eq!(Some(0), first(&items));
+        assert_eq!(Some(3), last(&items));
+        assert_eq!(Some(2), step(&items, Some(0), true, true));
+        assert_eq!(Some(0), step(&items, Some(2), false, true));
+    }
+
+    #[test]
+    fn should_wrap_if_enabled() {
+        let items = items();
+        assert_eq!(Some(0), step(&items, Some(3), true, true));
+        assert_eq!(Some(3), step(&items, Some(0), false, true));
+        assert_eq!(Some(3), step(&items, Some(3), true, false));
+        assert_eq!(Some(0), step(&items, Some(0), false, false));
+    }
+
+    #[test]
+    fn should_start_at_either_end() {
+        let items = items();
+        assert_eq!(Some(0), step(&items, None, true, false));
+        assert_eq!(Some(3), step(&items, None, false, false));
+        assert_eq!(None, step(&[], None, true, true));
+    }
+
+    #[test]
+    fn type_ahead_should_match_prefix() {
+        let items = items();
+        assert_eq!(Some(3), search(&items, None, "av"));
+        assert_eq!(Some(3), search(&items, Some(3), "avo"));
+        assert_eq!(Some(2), search(&items, Some(0), "B"));
+        assert_eq!(None, search(&items, None, "ch"));
+    }
+
+    #[test]
+    fn type_ahead_should_cycle_on_repeated_character() {
+        let items = items();
+        assert_eq!(Some(3), search(&items, Some(0), "a"));
+        assert_eq!(Some(0), search(&items, Some(3), "aA"));
+    }
+
+    #[test]
+    fn type_ahead_should_use_matcher() {
+        let items = items();
+        let matcher = TypeAheadMatcher::new(|item, query| item.label.contains(query));
+        assert_eq!(Some(2), super::search(&items, None, "nan", &matcher));
+        assert_eq!(None, super::search(&items, None, "NAN", &matcher));
+    }
+}
@@ -0,0 +1,740 @@
+//! A declarative markup format for building widget trees at runtime
+//!
+//! Markup documents use a small, RSX-like subset of XML:
+//!
+//! ```text
+//! <!-- Comments are ignored -->
+//! <ui:Panel title="Settings" width=300 collapsible>
+//!     <ui:Label content={greeting} styles={label_styles} />
+//! </ui:Panel>
+//! ```
+//!
+//! Every tag refers to a widget registered via
+//! [`KayakContext::register_widget`](crate::KayakContext::register_widget), which receives its
+//! attributes as [`DynamicProps`]. Attribute values may be:
+//!
+//! * A string (`"Settings"`), stored as a [`String`]
+//! * A number (`300` or `-1.5`), stored as an [`f32`]
+//! * `true` or `false` (or just the attribute name, meaning `true`), stored as a [`bool`]
+//! * A binding (`{greeting}`), stored as whichever value was given for it in the
+//!   [`MarkupBindings`]
+//!
+//! The `styles` attribute must be a binding to a [`Style`] and `focusable` must be a `bool`. Text
+//! content isn't supported, so text should be given to a widget as an attribute instead.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::dynamic_widget::DynamicValue;
+use crate::styles::Style;
+use crate::{Children, DynamicProps, DynamicWidget, KayakContext, Subtree};
+
+/// A parsed markup document
+///
+/// See the [module-level documentation](self) for the supported syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Markup {
+    elements: Vec<MarkupElement>,
+}
+
+/// An element of a [`Markup`] document, such as `<ui:Label content="Hello" />`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupElement {
+    /// The tag of this element (i.e. `"ui:Label"`)
+    pub tag: String,
+    /// The attributes of this element, in the order they were written
+    pub attributes: Vec<MarkupAttribute>,
+    /// The child elements of this element
+    pub children: Vec<MarkupElement>,
+    /// The line on which this element starts (starting from 1)
+    pub line: usize,
+    /// The column at which this element starts (starting from 1)
+    pub column: usize,
+}
+
+/// An attribute of a [`MarkupElement`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupAttribute {
+    pub name: String,
+    pub value: MarkupValue,
+    /// The line on which this attribute starts (starting from 1)
+    pub line: usize,
+    /// The column at which this attribute starts (starting from 1)
+    pub column: usize,
+}
+
+/// The value of a [`MarkupAttribute`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkupValue {
+    String(String),
+    Number(f32),
+    Bool(bool),
+    /// The name of a value given in the [`MarkupBindings`]
+    Binding(String),
+}
+
+/// The values that can be bound to attributes in a [`Markup`] document (such as `{greeting}`)
+#[derive(Default, Clone)]
+pub struct MarkupBindings {
+    values: HashMap<String, DynamicValue>,
+}
+
+impl MarkupBindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value bound to the given name
+    pub fn set<T: Any + Send + Sync + PartialEq>(&mut self, name: &str, value: T) {
+        self.values
+            .insert(name.to_string(), DynamicValue::new(value));
+    }
+
+    /// Sets the value bound to the given name, returning the bindings
+    pub fn with<T: Any + Send + Sync + PartialEq>(mut self, name: &str, value: T) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Returns true if a value is bound to the given name
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+}
+
+impl Debug for MarkupBindings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MarkupBindings")
+            .field("values", &self.values.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// An error encountered while loading or building a [`Markup`] document
+///
+/// Errors that aren't tied to a location within the document (such as I/O errors) have a line
+/// and column of 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupError {
+    pub kind: MarkupErrorKind,
+    /// The line at which the error occurred (starting from 1)
+    pub line: usize,
+    /// The column at which the error occurred (starting from 1)
+    pub column: usize,
+}
+
+/// The kind of a [`MarkupError`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkupErrorKind {
+    /// The document couldn't be read
+    Io(String),
+    /// The document ended before the current element or value was complete
+    UnexpectedEnd,
+    /// An unexpected character was found
+    UnexpectedChar { expected: &'static str, found: char },
+    /// A closing tag didn't match the element it closes
+    MismatchedClosingTag { expected: String, found: String },
+    /// Text was found outside of an attribute
+    UnsupportedText,
+    /// An attribute was given more than once on the same element
+    DuplicateAttribute(String),
+    /// A number couldn't be parsed
+    InvalidNumber(String),
+    /// No widget is registered for a tag
+    UnknownTag(String),
+    /// No value is bound to a binding's name
+    UnknownBinding(String),
+    /// A common prop was given a value of the wrong type
+    InvalidPropType {
+        name: String,
+        expected: &'static str,
+    },
+}
+
+impl Display for MarkupErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read markup: {}", error),
+            Self::UnexpectedEnd => write!(f, "unexpected end of markup"),
+            Self::UnexpectedChar { expected, found } => {
+                write!(f, "expected {}, found `{}`", expected, found)
+            }
+            Self::MismatchedClosingTag { expected, found } => write!(
+                f,
+                "expected closing tag for <{}>, found </{}>",
+                expected, found
+            ),
+            Self::UnsupportedText => write!(
+                f,
+                "text content isn't supported, pass it to a widget as an attribute instead"
+            ),
+            Self::DuplicateAttribute(name) => write!(f, "attribute `{}` is given twice", name),
+            Self::InvalidNumber(number) => write!(f, "invalid number `{}`", number),
+            Self::UnknownTag(tag) => write!(
+                f,
+                "no widget is registered for the tag <{}> (see `KayakContext::register_widget`)",
+                tag
+            ),
+            Self::UnknownBinding(name) => write!(f, "no value is bound to `{{{}}}`", name),
+            Self::InvalidPropType { name, expected } => {
+                write!(f, "attribute `{}` must be {}", name, expected)
+            }
+        }
+    }
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+impl Markup {
+    /// Parses a markup document
+    pub fn parse(source: &str) -> Result<Self, MarkupError> {
+        Parser::new(source).parse_document()
+    }
+
+    /// Reads and parses the markup document at the given path
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MarkupError> {
+        let source = std::fs::read_to_string(path).map_err(|error| MarkupError {
+            kind: MarkupErrorKind::Io(error.to_string()),
+            line: 0,
+            column: 0,
+        })?;
+        Self::parse(&source)
+    }
+
+    /// The top-level elements of this document
+    pub fn elements(&self) -> &[MarkupElement] {
+        &self.elements
+    }
+
+    /// Creates a [`Subtree`] containing the widgets of this document
+    ///
+    /// All tags and bindings are resolved up front, so any that are missing are reported here
+    /// rather than when the subtree is rendered. The returned subtree can then be committed with
+    /// [`KayakContext::commit_subtree`].
+    ///
+    /// # Arguments
+    ///
+    /// * `context`: The context whose registered widgets the tags refer to
+    /// * `bindings`: The values to bind to attributes
+    ///
+    pub fn build(
+        &self,
+        context: &KayakContext,
+        bindings: &MarkupBindings,
+    ) -> Result<Subtree, MarkupError> {
+        let elements = self
+            .elements
+            .iter()
+            .map(|element| ResolvedElement::resolve(element, context, bindings))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Subtree::new(move |context| {
+            for (index, element) in elements.iter().enumerate() {
+                context.add_widget(element.to_widget(), index);
+            }
+        }))
+    }
+}
+
+/// An element whose tag and bindings have been checked, ready to be turned into a widget
+struct ResolvedElement {
+    tag: String,
+    styles: Option<Style>,
+    focusable: Option<bool>,
+    attributes: Vec<(String, DynamicValue)>,
+    children: Arc<Vec<ResolvedElement>>,
+}
+
+impl ResolvedElement {
+    fn resolve(
+        element: &MarkupElement,
+        context: &KayakContext,
+        bindings: &MarkupBindings,
+    ) -> Result<Self, MarkupError> {
+        if !context.is_widget_registered(&element.tag) {
+            return Err(MarkupError {
+                kind: MarkupErrorKind::UnknownTag(element.tag.clone()),
+                line: element.line,
+                column: element.column,
+            });
+        }
+
+        let mut resolved = Self {
+            tag: element.tag.clone(),
+            styles: None,
+            focusable: None,
+            attributes: Vec::new(),
+            children: Arc::new(Vec::new()),
+        };
+
+        for attribute in &element.attributes {
+            let error = |kind| MarkupError {
+                kind,
+                line: attribute.line,
+                column: attribute.column,
+            };
+
+            let value = match &attribute.value {
+                MarkupValue::String(value) => DynamicValue::new(value.clone()),
+                MarkupValue::Number(value) => DynamicValue::new(*value),
+                MarkupValue::Bool(value) => DynamicValue::new(*value),
+                MarkupValue::Binding(name) => bindings
+                    .values
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| error(MarkupErrorKind::UnknownBinding(name.clone())))?,
+            };
+
+            let invalid_type = |expected| {
+                error(MarkupErrorKind::InvalidPropType {
+                    name: attribute.name.clone(),
+                    expected,
+                })
+            };
+            match attribute.name.as_str() {
+                "styles" => {
+                    let styles = value
+                        .downcast_ref::<Style>()
+                        .ok_or_else(|| invalid_type("a binding to a `Style`"))?;
+                    resolved.styles = Some(styles.clone());
+                }
+                "focusable" => {
+                    let focusable = value
+                        .downcast_ref::<bool>()
+                        .ok_or_else(|| invalid_type("a `bool`"))?;
+                    resolved.focusable = Some(*focusable);
+                }
+                _ => resolved.attributes.push((attribute.name.clone(), value)),
+            }
+        }
+
+        resolved.children = Arc::new(
+            element
+                .children
+                .iter()
+                .map(|child| Self::resolve(child, context, bindings))
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+
+        Ok(resolved)
+    }
+
+    fn to_widget(&self) -> DynamicWidget {
+        let mut props = DynamicProps::new(self.tag.clone());
+        props.styles = self.styles.clone();
+        props.focusable = self.focusable;
+        for (name, value) in &self.attributes {
+            props.insert(name, value.clone());
+        }
+
+        if !self.children.is_empty() {
+            let children = self.children.clone();
+            props.children = Some(Children::new(move |_, context| {
+                for (index, child) in children.iter().enumerate() {
+                    context.add_widget(child.to_widget(), index);
+                }
+                context.commit();
+            }));
+        }
+
+        DynamicWidget::new(props)
+    }
+}
+
+/// Checks if the given character can be part of a tag or attribute name
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == ':' || c == '.'
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            position: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn parse_document(mut self) -> Result<Markup, MarkupError> {
+        let mut elements = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                None => break,
+                Some('<') => elements.push(self.parse_element()?),
+                Some(..) => return Err(self.error(MarkupErrorKind::UnsupportedText)),
+            }
+        }
+        Ok(Markup { elements })
+    }
+
+    fn parse_element(&mut self) -> Result<MarkupElement, MarkupError> {
+        let (line, column) = (self.line, self.column);
+        self.expect('<', "`<`")?;
+        let tag = self.parse_name("a tag name")?;
+
+        let mut attributes: Vec<MarkupAttribute> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('/') => {
+                    self.next();
+                    self.expect('>', "`>`")?;
+                    return Ok(MarkupElement {
+                        tag,
+                        attributes,
+                        children: Vec::new(),
+                        line,
+                        column,
+                    });
+                }
+                Some('>') => {
+                    self.next();
+                    break;
+                }
+                Some(..) => {
+                    let attribute = self.parse_attribute()?;
+                    if attributes.iter().any(|other| other.name == attribute.name) {
+                        return Err(MarkupError {
+                            kind: MarkupErrorKind::DuplicateAttribute(attribute.name),
+                            line: attribute.line,
+                            column: attribute.column,
+                        });
+                    }
+                    attributes.push(attribute);
+                }
+                None => return Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+            }
+        }
+
+        let mut children = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            match (self.peek(), self.peek_at(1)) {
+                (Some('<'), Some('/')) => break,
+                (Some('<'), _) => children.push(self.parse_element()?),
+                (Some(..), _) => return Err(self.error(MarkupErrorKind::UnsupportedText)),
+                (None, _) => return Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+            }
+        }
+
+        let (closing_line, closing_column) = (self.line, self.column);
+        self.expect('<', "`<`")?;
+        self.expect('/', "`/`")?;
+        let closing_tag = self.parse_name("a tag name")?;
+        if closing_tag != tag {
+            return Err(MarkupError {
+                kind: MarkupErrorKind::MismatchedClosingTag {
+                    expected: tag,
+                    found: closing_tag,
+                },
+                line: closing_line,
+                column: closing_column,
+            });
+        }
+        self.skip_whitespace();
+        self.expect('>', "`>`")?;
+
+        Ok(MarkupElement {
+            tag,
+            attributes,
+            children,
+            line,
+            column,
+        })
+    }
+
+    fn parse_attribute(&mut self) -> Result<MarkupAttribute, MarkupError> {
+        let (line, column) = (self.line, self.column);
+        let name = self.parse_name("an attribute name")?;
+
+        self.skip_whitespace();
+        let value = if self.peek() == Some('=') {
+            self.next();
+            self.skip_whitespace();
+            self.parse_value()?
+        } else {
+            MarkupValue::Bool(true)
+        };
+
+        Ok(MarkupAttribute {
+            name,
+            value,
+            line,
+            column,
+        })
+    }
+
+    fn parse_value(&mut self) -> Result<MarkupValue, MarkupError> {
+        match self.peek() {
+            Some('"') => {
+                self.next();
+                let mut value = String::new();
+                loop {
+                    match self.next() {
+                        Some('"') => break,
+                        Some('\\') => match self.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => return Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+                    }
+                }
+                Ok(MarkupValue::String(value))
+            }
+            Some('{') => {
+                self.next();
+                self.skip_whitespace();
+                let name = self.parse_name("a binding name")?;
+                self.skip_whitespace();
+                self.expect('}', "`}`")?;
+                Ok(MarkupValue::Binding(name))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let (line, column) = (self.line, self.column);
+                let mut number = String::new();
+                while let Some(c) = self
+                    .peek()
+                    .filter(|c| *c == '-' || *c == '.' || c.is_ascii_digit())
+                {
+                    number.push(c);
+                    self.next();
+                }
+                number
+                    .parse::<f32>()
+                    .map(MarkupValue::Number)
+                    .map_err(|_| MarkupError {
+                        kind: MarkupErrorKind::InvalidNumber(number),
+                        line,
+                        column,
+                    })
+            }
+            Some(c) if is_name_char(c) => {
+                let (line, column) = (self.line, self.column);
+                match self.parse_name("a value")?.as_str() {
+                    "true" => Ok(MarkupValue::Bool(true)),
+                    "false" => Ok(MarkupValue::Bool(false)),
+                    _ => Err(MarkupError {
+                        kind: MarkupErrorKind::UnexpectedChar {
+                            expected: "a string, number, bool, or binding",
+                            found: c,
+                        },
+                        line,
+                        column,
+                    }),
+                }
+            }
+            Some(c) => Err(self.error(MarkupErrorKind::UnexpectedChar {
+                expected: "a string, number, bool, or binding",
+                found: c,
+            })),
+            None => Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    fn parse_name(&mut self, expected: &'static str) -> Result<String, MarkupError> {
+        let mut name = String::new();
+        while let Some(c) = self.peek().filter(|c| is_name_char(*c)) {
+            name.push(c);
+            self.next();
+        }
+
+        if name.is_empty() {
+            return Err(match self.peek() {
+                Some(found) => self.error(MarkupErrorKind::UnexpectedChar { expected, found }),
+                None => self.error(MarkupErrorKind::UnexpectedEnd),
+            });
+        }
+        Ok(name)
+    }
+
+    /// Skips all whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), MarkupError> {
+        loop {
+            self.skip_whitespace();
+            if !self.starts_with("<!--") {
+                return Ok(());
+            }
+
+            while !self.starts_with("-->") {
+                if self.next().is_none() {
+                    return Err(self.error(MarkupErrorKind::UnexpectedEnd));
+                }
+            }
+            for _ in 0..3 {
+                self.next();
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected_char: char, expected: &'static str) -> Result<(), MarkupError> {
+        match self.peek() {
+            Some(c) if c == expected_char => {
+                self.next();
+                Ok(())
+            }
+            Some(found) => Err(self.error(MarkupErrorKind::UnexpectedChar { expected, found })),
+            None => Err(self.error(MarkupErrorKind::UnexpectedEnd)),
+        }
+    }
+
+    fn starts_with(&self, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| self.peek_at(offset) == Some(c))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Creates an error at the current position
+    fn error(&self, kind: MarkupErrorKind) -> MarkupError {
+        MarkupError {
+            kind,
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Markup, MarkupBindings, MarkupErrorKind, MarkupValue};
+    use crate::{Fragment, KayakContext};
+
+    #[test]
+    fn should_parse_elements_and_attributes() {
+        let markup = Markup::parse(
+            r#"
+            <!-- A comment -->
+            <ui:Panel title="Hello \"World\"" width=-1.5 collapsible visible=false>
+                <ui:Label content={greeting} />
+                <ui:Label></ui:Label>
+            </ui:Panel>
+            "#,
+        )
+        .unwrap();
+
+        let panel = &markup.elements()[0];
+        assert_eq!("ui:Panel", panel.tag);
+        assert_eq!((3, 13), (panel.line, panel.column));
+        let values = panel
+            .attributes
+            .iter()
+            .map(|attribute| (attribute.name.as_str(), attribute.value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    "title",
+                    MarkupValue::String(String::from("Hello \"World\""))
+                ),
+                ("width", MarkupValue::Number(-1.5)),
+                ("collapsible", MarkupValue::Bool(true)),
+                ("visible", MarkupValue::Bool(false)),
+            ],
+            values
+        );
+
+        assert_eq!(2, panel.children.len());
+        assert_eq!(
+            MarkupValue::Binding(String::from("greeting")),
+            panel.children[0].attributes[0].value
+        );
+    }
+
+    #[test]
+    fn should_report_error_location() {
+        let error = Markup::parse("<ui:Panel>\n    <ui:Label />\n</ui:Label>").unwrap_err();
+        assert_eq!(
+            MarkupErrorKind::MismatchedClosingTag {
+                expected: String::from("ui:Panel"),
+                found: String::from("ui:Label"),
+            },
+            error.kind
+        );
+        assert_eq!((3, 1), (error.line, error.column));
+
+        let error = Markup::parse("<ui:Panel>\n  Hello\n</ui:Panel>").unwrap_err();
+        assert_eq!(MarkupErrorKind::UnsupportedText, error.kind);
+        assert_eq!((2, 3), (error.line, error.column));
+
+        let error = Markup::parse("<ui:Panel title=\"Hello").unwrap_err();
+        assert_eq!(MarkupErrorKind::UnexpectedEnd, error.kind);
+    }
+
+    #[test]
+    fn should_resolve_tags_and_bindings() {
+        let mut context = KayakContext::new();
+        context.register_widget::<Fragment>("ui:Group");
+
+        let markup = Markup::parse("<ui:Group>\n  <ui:Missing />\n</ui:Group>").unwrap();
+        let error = markup.build(&context, &MarkupBindings::new()).unwrap_err();
+        assert_eq!(
+            MarkupErrorKind::UnknownTag(String::from("ui:Missing")),
+            error.kind
+        );
+        assert_eq!((2, 3), (error.line, error.column));
+
+        let markup = Markup::parse("<ui:Group label={label} />").unwrap();
+        let error = markup.build(&context, &MarkupBindings::new()).unwrap_err();
+        assert_eq!(
+            MarkupErrorKind::UnknownBinding(String::from("label")),
+            error.kind
+        );
+        assert_eq!((1, 11), (error.line, error.column));
+
+        let bindings = MarkupBindings::new().with("label", String::from("Hello"));
+        let subtree = markup.build(&context, &bindings).unwrap();
+        context.commit_subtree(subtree, None);
+        assert_eq!(2, context.widget_manager.tree.len());
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use crate::render_primitive::RenderPrimitive;
+
+/// Statistics about a single render of a [`KayakContext`](crate::KayakContext)
+///
+/// These are only collected while enabled (see
+/// [`KayakContext::set_metrics_enabled`](crate::KayakContext::set_metrics_enabled)).
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct FrameMetrics {
+    /// The total number of widgets in the tree
+    pub widgets: usize,
+    /// The number of widgets that were re-rendered
+    pub widgets_rendered: usize,
+    /// The number of nodes that took part in layout
+    pub layout_nodes: usize,
+    /// The number of render primitives that draw something (quads, text, images, etc.)
+    pub draw_calls: usize,
+    /// The number of color glyph rasterizations cached in the color glyph atlas
+    ///
+    /// Other glyphs are drawn from the distance field atlas of their font, which contains all of
+    /// them up front.
+    pub glyph_cache: usize,
+    /// The time spent rendering, from building widgets to calculating layout
+    pub build_time: Duration,
+}
+
+impl FrameMetrics {
+    /// Counts the draw calls of the given render primitives
+    pub(crate) fn count_draw_calls(&mut self, primitives: &[RenderPrimitive]) {
+        self.draw_calls = primitives
+            .iter()
+            .filter(|primitive| {
+                !matches!(
+                    primitive,
+                    RenderPrimitive::Empty | RenderPrimitive::Clip { .. }
+                )
+            })
+            .count();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::render_primitive::RenderPrimitive;
 use crate::{
-    styles::{Style, StyleProp},
+    styles::{AxisAlignment, BoxSizing, Position, Style, StyleProp, Units},
     Arena, Index,
 };
 
@@ -19,6 +19,12 @@ pub struct Node {
     pub primitive: RenderPrimitive,
     /// The z-index of this node, used for controlling layering
     pub z: f32,
+    /// The `(width, height)` this node was clamped to in order to satisfy its min/max constraints
+    ///
+    /// This is set during layout and overrides the node's resolved width and height, respectively.
+    pub(crate) clamped_size: (Option<f32>, Option<f32>),
+    /// Set if this node's parent has a layout type of [`LayoutType::Stack`](crate::styles::LayoutType::Stack)
+    pub(crate) is_stacked: bool,
 }
 
 /// A struct used for building a [`Node`]
@@ -37,6 +43,8 @@ impl NodeBuilder {
                 raw_styles: None,
                 primitive: RenderPrimitive::Empty,
                 z: 0.0,
+                clamped_size: (None, None),
+                is_stacked: false,
             },
         }
     }
@@ -51,6 +59,8 @@ impl NodeBuilder {
                 raw_styles: None,
                 primitive: RenderPrimitive::Empty,
                 z: 0.0,
+                clamped_size: (None, None),
+                is_stacked: false,
             },
         }
     }
@@ -86,6 +96,122 @@ impl NodeBuilder {
     }
 }
 
+impl Node {
+    /// Returns true if this node is positioned absolutely
+    pub(crate) fn is_absolute(&self) -> bool {
+        matches!(
+            self.resolved_styles.position,
+            StyleProp::Value(Position::Absolute)
+        )
+    }
+
+    /// Returns the box sizing of this node
+    fn box_sizing(&self) -> BoxSizing {
+        self.resolved_styles.box_sizing.resolve_or_default()
+    }
+
+    /// Returns the offset to use for an unset side
+    ///
+    /// Children of a stack are placed according to their stack alignment. Along an axis where they
+    /// stretch to fill the stack, both offsets are zero. All other nodes leave their offsets to
+    /// the layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `horizontal`: Whether the side is on the horizontal axis (left or right)
+    /// * `start`: Whether the side is at the start of its axis (left or top)
+    ///
+    fn default_offset(&self, horizontal: bool, start: bool) -> morphorm::Units {
+        if !self.is_stacked {
+            return morphorm::Units::Auto;
+        }
+
+        let size = if horizontal {
+            &self.resolved_styles.width
+        } else {
+            &self.resolved_styles.height
+        };
+        let is_stretched = match size {
+            StyleProp::Value(units) => matches!(units, Units::Stretch(..)),
+            _ => true,
+        };
+        if is_stretched {
+            return morphorm::Units::Pixels(0.0);
+        }
+
+        let alignment = match &self.resolved_styles.stack_alignment {
+            StyleProp::Value(alignment) => *alignment,
+            _ => Default::default(),
+        };
+        let alignment = if horizontal {
+            alignment.horizontal()
+        } else {
+            alignment.vertical()
+        };
+        match (alignment, start) {
+            (AxisAlignment::Start, true) | (AxisAlignment::End, false) => {
+                morphorm::Units::Pixels(0.0)
+            }
+            _ => morphorm::Units::Stretch(1.0),
+        }
+    }
+
+    /// Returns the offset this node is pinned at if it's positioned stickily
+    pub(crate) fn sticky_top(&self) -> Option<f32> {
+        match self.resolved_styles.position {
+            StyleProp::Value(Position::Sticky { top }) => Some(top),
+            _ => None,
+        }
+    }
+
+    /// Returns the size to use for an unset width or height
+    ///
+    /// Absolute nodes stretch between their offsets if both are set, otherwise they fit to
+    /// their content. Relative nodes stretch to fill the available space.
+    fn default_size(
+        &self,
+        start: Option<morphorm::Units>,
+        end: Option<morphorm::Units>,
+    ) -> morphorm::Units {
+        if !self.is_absolute() {
+            return morphorm::Units::Stretch(1.0);
+        }
+
+        let is_set = |units: Option<morphorm::Units>| {
+            units.map_or(false, |units| units != morphorm::Units::Auto)
+        };
+        if is_set(start) && is_set(end) {
+            morphorm::Units::Stretch(1.0)
+        } else {
+            morphorm::Units::Auto
+        }
+    }
+}
+
+impl Index {
+    /// Returns the padding and border sizes of this node along the horizontal axis
+    fn horizontal_insets(&self, store: &Arena<Option<Node>>) -> [Option<morphorm::Units>; 4] {
+        use morphorm::Node as _;
+        [
+            self.child_left(store),
+            self.child_right(store),
+            self.border_left(store),
+            self.border_right(store),
+        ]
+    }
+
+    /// Returns the padding and border sizes of this node along the vertical axis
+    fn vertical_insets(&self, store: &Arena<Option<Node>>) -> [Option<morphorm::Units>; 4] {
+        use morphorm::Node as _;
+        [
+            self.child_top(store),
+            self.child_bottom(store),
+            self.border_top(store),
+            self.border_bottom(store),
+        ]
+    }
+}
+
 impl<'a> morphorm::Node<'a> for Index {
     type Data = Arena<Option<Node>>;
 
@@ -94,7 +220,7 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.layout_type {
                     StyleProp::Default => Some(morphorm::LayoutType::default()),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::LayoutType::default()),
                 };
             }
@@ -105,6 +231,10 @@ impl<'a> morphorm::Node<'a> for Index {
     fn position_type(&self, store: &'_ Self::Data) -> Option<morphorm::PositionType> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
+                if node.is_absolute() || node.is_stacked {
+                    return Some(morphorm::PositionType::SelfDirected);
+                }
+
                 return match node.resolved_styles.position_type {
                     StyleProp::Default => Some(morphorm::PositionType::default()),
                     StyleProp::Value(prop) => Some(prop),
@@ -118,11 +248,20 @@ impl<'a> morphorm::Node<'a> for Index {
     fn width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
-                return match node.resolved_styles.width {
-                    StyleProp::Default => Some(morphorm::Units::Stretch(1.0)),
-                    StyleProp::Value(prop) => Some(prop),
-                    _ => Some(morphorm::Units::Stretch(1.0)),
+                if let Some(size) = node.clamped_size.0 {
+                    return Some(morphorm::Units::Pixels(size));
+                }
+
+                let default_size = node.default_size(self.left(store), self.right(store));
+                let width = match node.resolved_styles.width {
+                    StyleProp::Default => default_size,
+                    StyleProp::Value(prop) => prop.into(),
+                    _ => default_size,
                 };
+                return Some(
+                    node.box_sizing()
+                        .resolve(width, &self.horizontal_insets(store)),
+                );
             }
         }
         return Some(morphorm::Units::Stretch(1.0));
@@ -131,11 +270,20 @@ impl<'a> morphorm::Node<'a> for Index {
     fn height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
-                return match node.resolved_styles.height {
-                    StyleProp::Default => Some(morphorm::Units::Stretch(1.0)),
-                    StyleProp::Value(prop) => Some(prop),
-                    _ => Some(morphorm::Units::Stretch(1.0)),
+                if let Some(size) = node.clamped_size.1 {
+                    return Some(morphorm::Units::Pixels(size));
+                }
+
+                let default_size = node.default_size(self.top(store), self.bottom(store));
+                let height = match node.resolved_styles.height {
+                    StyleProp::Default => default_size,
+                    StyleProp::Value(prop) => prop.into(),
+                    _ => default_size,
                 };
+                return Some(
+                    node.box_sizing()
+                        .resolve(height, &self.vertical_insets(store)),
+                );
             }
         }
         return Some(morphorm::Units::Stretch(1.0));
@@ -144,11 +292,15 @@ impl<'a> morphorm::Node<'a> for Index {
     fn min_width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
-                return match node.resolved_styles.min_width {
+                let min_width = match node.resolved_styles.min_width {
                     StyleProp::Default => Some(morphorm::Units::Pixels(0.0)),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
+                return min_width.map(|units| {
+                    node.box_sizing()
+                        .resolve(units, &self.horizontal_insets(store))
+                });
             }
         }
         Some(morphorm::Units::Auto)
@@ -157,11 +309,15 @@ impl<'a> morphorm::Node<'a> for Index {
     fn min_height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
-                return match node.resolved_styles.min_height {
+                let min_height = match node.resolved_styles.min_height {
                     StyleProp::Default => Some(morphorm::Units::Pixels(0.0)),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
+                return min_height.map(|units| {
+                    node.box_sizing()
+                        .resolve(units, &self.vertical_insets(store))
+                });
             }
         }
         Some(morphorm::Units::Auto)
@@ -170,11 +326,15 @@ impl<'a> morphorm::Node<'a> for Index {
     fn max_width(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
-                return match node.resolved_styles.max_width {
+                let max_width = match node.resolved_styles.max_width {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
+                return max_width.map(|units| {
+                    node.box_sizing()
+                        .resolve(units, &self.horizontal_insets(store))
+                });
             }
         }
         Some(morphorm::Units::Auto)
@@ -183,11 +343,15 @@ impl<'a> morphorm::Node<'a> for Index {
     fn max_height(&self, store: &'_ Self::Data) -> Option<morphorm::Units> {
         if let Some(node) = store.get(*self) {
             if let Some(node) = node {
-                return match node.resolved_styles.max_height {
+                let max_height = match node.resolved_styles.max_height {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
+                return max_height.map(|units| {
+                    node.box_sizing()
+                        .resolve(units, &self.vertical_insets(store))
+                });
             }
         }
         Some(morphorm::Units::Auto)
@@ -198,11 +362,11 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.left {
                     StyleProp::Default => match node.resolved_styles.offset {
-                        StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.left),
+                        StyleProp::Default => Some(node.default_offset(true, true)),
+                        StyleProp::Value(prop) => Some(prop.left.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -215,11 +379,11 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.right {
                     StyleProp::Default => match node.resolved_styles.offset {
-                        StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.right),
+                        StyleProp::Default => Some(node.default_offset(true, false)),
+                        StyleProp::Value(prop) => Some(prop.right.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -232,11 +396,11 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.top {
                     StyleProp::Default => match node.resolved_styles.offset {
-                        StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.top),
+                        StyleProp::Default => Some(node.default_offset(false, true)),
+                        StyleProp::Value(prop) => Some(prop.top.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -249,11 +413,11 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.bottom {
                     StyleProp::Default => match node.resolved_styles.offset {
-                        StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.bottom),
+                        StyleProp::Default => Some(node.default_offset(false, false)),
+                        StyleProp::Value(prop) => Some(prop.bottom.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -299,10 +463,10 @@ impl<'a> morphorm::Node<'a> for Index {
                 return match node.resolved_styles.padding_left {
                     StyleProp::Default => match node.resolved_styles.padding {
                         StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.left),
+                        StyleProp::Value(prop) => Some(prop.left.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -316,10 +480,10 @@ impl<'a> morphorm::Node<'a> for Index {
                 return match node.resolved_styles.padding_right {
                     StyleProp::Default => match node.resolved_styles.padding {
                         StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.right),
+                        StyleProp::Value(prop) => Some(prop.right.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -333,10 +497,10 @@ impl<'a> morphorm::Node<'a> for Index {
                 return match node.resolved_styles.padding_top {
                     StyleProp::Default => match node.resolved_styles.padding {
                         StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.top),
+                        StyleProp::Value(prop) => Some(prop.top.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -350,10 +514,10 @@ impl<'a> morphorm::Node<'a> for Index {
                 return match node.resolved_styles.padding_bottom {
                     StyleProp::Default => match node.resolved_styles.padding {
                         StyleProp::Default => Some(morphorm::Units::Auto),
-                        StyleProp::Value(prop) => Some(prop.bottom),
+                        StyleProp::Value(prop) => Some(prop.bottom.into()),
                         _ => Some(morphorm::Units::Auto),
                     },
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -366,7 +530,7 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.row_between {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -379,7 +543,7 @@ impl<'a> morphorm::Node<'a> for Index {
             if let Some(node) = node {
                 return match node.resolved_styles.col_between {
                     StyleProp::Default => Some(morphorm::Units::Auto),
-                    StyleProp::Value(prop) => Some(prop),
+                    StyleProp::Value(prop) => Some(prop.into()),
                     _ => Some(morphorm::Units::Auto),
                 };
             }
@@ -1,4 +1,5 @@
-use crate::{Event, KayakContextRef};
+use crate::{Event, Index, KayakContextRef};
+use resources::Resource;
 use std::fmt::{Debug, Formatter};
 use std::sync::{Arc, RwLock};
 
@@ -10,6 +11,9 @@ use std::sync::{Arc, RwLock};
 #[derive(Clone)]
 pub struct OnEvent(
     Arc<RwLock<dyn FnMut(&mut KayakContextRef, &mut Event) + Send + Sync + 'static>>,
+    /// For handlers created with [`OnEvent::wrap`], the widget that wrapped the handler and the
+    /// handler it was given
+    Option<(Index, Option<Box<OnEvent>>)>,
 );
 
 impl OnEvent {
@@ -21,7 +25,92 @@ impl OnEvent {
     pub fn new<F: FnMut(&mut KayakContextRef, &mut Event) + Send + Sync + 'static>(
         f: F,
     ) -> OnEvent {
-        OnEvent(Arc::new(RwLock::new(f)))
+        OnEvent(Arc::new(RwLock::new(f)), None)
+    }
+
+    /// Create an event handler for a widget that's called before the handler it was given
+    ///
+    /// This is meant for widgets that handle some events themselves while still passing them on to
+    /// the `on_event` in their props. A widget's props (including its handler) are kept when it
+    /// re-renders itself, so simply wrapping that handler with [`OnEvent::new`] would wrap the
+    /// previous wrapper again on each of those renders, calling `f` more than once per event. A
+    /// handler created with this method is unwrapped again when the same widget wraps it.
+    ///
+    /// # Arguments
+    ///
+    /// * `widget_id`: The ID of the widget that's wrapping the handler
+    /// * `on_event`: The handler the widget was given
+    /// * `f`: The widget's own handler
+    ///
+    pub fn wrap<F: FnMut(&mut KayakContextRef, &mut Event) + Send + Sync + 'static>(
+        widget_id: Index,
+        on_event: Option<OnEvent>,
+        mut f: F,
+    ) -> OnEvent {
+        let on_event = match on_event {
+            Some(OnEvent(_, Some((owner, inner)))) if owner == widget_id => {
+                inner.map(|inner| *inner)
+            }
+            on_event => on_event,
+        };
+
+        OnEvent(
+            Arc::new(RwLock::new({
+                let on_event = on_event.clone();
+                move |context: &mut KayakContextRef, event: &mut Event| {
+                    f(context, event);
+                    if let Some(on_event) = &on_event {
+                        on_event.try_call(context, event);
+                    }
+                }
+            })),
+            Some((widget_id, on_event.map(Box::new))),
+        )
+    }
+
+    /// Create a new event handler that is given a reference to a global value
+    ///
+    /// Rather than capturing shared data (such as a store or a service handle) in every handler,
+    /// this fetches the global of type `T` (see [`KayakContext::set_global`](crate::KayakContext::set_global))
+    /// each time an event is dispatched. The value doesn't need to be `Clone`.
+    ///
+    /// The handler should be a closure that takes the following arguments:
+    /// 1. The current context
+    /// 2. The event
+    /// 3. The global value
+    ///
+    /// If no global of type `T` has been set, the handler isn't called. While the handler runs,
+    /// the global is lent out to it, so it can't also be fetched from the context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kayak_core::{EventType, OnEvent};
+    /// struct Store {
+    ///     items: Vec<String>,
+    /// }
+    ///
+    /// let on_event = OnEvent::with_global(|_, event, store: &Store| {
+    ///     if let EventType::Click(..) = event.event_type {
+    ///         println!("There are {} items", store.items.len());
+    ///     }
+    /// });
+    /// ```
+    pub fn with_global<T, F>(mut f: F) -> OnEvent
+    where
+        T: Resource,
+        F: FnMut(&mut KayakContextRef, &mut Event, &T) + Send + Sync + 'static,
+    {
+        OnEvent::new(move |context, event| {
+            if let Some(global) = context.remove_global::<T>() {
+                f(context, event, &global);
+
+                // Don't overwrite a value set by the handler itself
+                if context.get_global::<T>().is_err() {
+                    context.set_global(global);
+                }
+            }
+        })
     }
 
     /// Call the event handler
@@ -49,3 +138,66 @@ impl PartialEq for OnEvent {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::OnEvent;
+    use crate::{Event, Index, KayakContext, KayakContextRef};
+
+    struct Store(Vec<u32>);
+
+    #[test]
+    fn should_pass_global_to_handler() {
+        let mut context = KayakContext::new();
+        context.set_global(Store(vec![1, 2, 3]));
+
+        let on_event = OnEvent::with_global(|context, _, store: &Store| {
+            context.set_global(store.0.len());
+        });
+
+        let mut event = Event::default();
+        let mut context_ref = KayakContextRef::new(&mut context, None);
+        assert!(on_event.try_call(&mut context_ref, &mut event));
+
+        assert_eq!(3, *context.get_global::<usize>().unwrap());
+        assert!(context.get_global::<Store>().is_ok());
+    }
+
+    #[test]
+    fn should_skip_handler_without_global() {
+        let mut context = KayakContext::new();
+        let on_event = OnEvent::with_global(|context, _, _: &Store| {
+            context.set_global(true);
+        });
+
+        let mut event = Event::default();
+        let mut context_ref = KayakContextRef::new(&mut context, None);
+        on_event.try_call(&mut context_ref, &mut event);
+
+        assert!(context.get_global::<bool>().is_err());
+    }
+
+    #[test]
+    fn should_not_wrap_own_handler_twice() {
+        let mut context = KayakContext::new();
+        context.set_global(0_u32);
+        let count = |context: &mut KayakContextRef, _: &mut Event| {
+            let mut calls = context.get_global_mut::<u32>().unwrap();
+            *calls += 1;
+        };
+
+        let widget_id = Index::from_raw_parts(1, 0);
+        let given = OnEvent::new(count);
+        let mut on_event = OnEvent::wrap(widget_id, Some(given), count);
+        // Re-rendering wraps the widget's own handler, which should replace it
+        on_event = OnEvent::wrap(widget_id, Some(on_event), count);
+        // Another widget wrapping it should keep it, though
+        on_event = OnEvent::wrap(Index::from_raw_parts(2, 0), Some(on_event), count);
+
+        let mut event = Event::default();
+        let mut context_ref = KayakContextRef::new(&mut context, None);
+        on_event.try_call(&mut context_ref, &mut event);
+
+        assert_eq!(3, *context.get_global::<u32>().unwrap());
+    }
+}
@@ -0,0 +1,47 @@
+use crate::KayakContextRef;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+/// A container for a function that's called when a widget is mounted
+///
+/// A widget is mounted when it's first added to the tree, or when it replaces a widget of another
+/// type at the same index. The handler is called exactly once,
+/// right before the widget's first render, meaning it runs before any effects created during
+/// that render and before the widget is first laid out. Like [`OnLayout`](crate::OnLayout), it's
+/// given the [`KayakContextRef`] of the mounted widget.
+#[derive(Clone)]
+pub struct OnMount(Arc<RwLock<dyn FnMut(&mut KayakContextRef) + Send + Sync + 'static>>);
+
+impl OnMount {
+    /// Create a new mount handler
+    ///
+    /// The handler should be a closure that takes the context of the mounted widget.
+    pub fn new<F: FnMut(&mut KayakContextRef) + Send + Sync + 'static>(f: F) -> OnMount {
+        OnMount(Arc::new(RwLock::new(f)))
+    }
+
+    /// Call the mount handler
+    ///
+    /// Returns true if the handler was successfully invoked.
+    pub fn try_call(&self, context: &mut KayakContextRef) -> bool {
+        if let Ok(mut on_mount) = self.0.write() {
+            on_mount(context);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Debug for OnMount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnMount").finish()
+    }
+}
+
+impl PartialEq for OnMount {
+    fn eq(&self, _: &Self) -> bool {
+        // Never prevent "==" from being true because of this struct
+        true
+    }
+}
@@ -0,0 +1,177 @@
+//! Drawing a scaled-down copy of another widget's content, such as for minimaps
+//!
+//! An overview widget (see [`KayakContextRef::set_overview`](crate::KayakContextRef::set_overview))
+//! doesn't lay out anything itself. Instead, the source's descendants are recorded into a retained
+//! [`DrawList`] whenever the tree renders, which is then moved and scaled so that the bounds of the
+//! source's children fit within the overview, centered. Since the copy is only drawn, it can't be
+//! hit, focused or interacted with in any way.
+
+use crate::draw_list::DrawList;
+use crate::layout_cache::{LayoutCache, Rect};
+use crate::render_primitive::RenderPrimitive;
+use crate::styles::Transform;
+use crate::tree::Tree;
+use crate::Index;
+
+/// The retained content of an overview's source, as of the last render
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OverviewContent {
+    /// The bounds of the source's children, in their transformed space
+    pub bounds: Rect,
+    /// The draw commands of the source and its descendants
+    pub draw_list: DrawList,
+}
+
+/// Returns the bounds of the children of the given source, in their transformed space
+///
+/// Returns `None` if the source has no laid out children.
+pub(crate) fn content_bounds(
+    source: Index,
+    node_tree: &Tree,
+    layout_cache: &LayoutCache,
+) -> Option<Rect> {
+    node_tree
+        .children
+        .get(&source)?
+        .iter()
+        .filter_map(|child| layout_cache.rect.get(child).copied())
+        .reduce(|a, b| a.union(&b))
+}
+
+/// Moves and scales the retained content of a source into the given frame
+///
+/// Everything is clipped to the frame (and to the `prev_clip` the overview is drawn in), after
+/// which `prev_clip` is restored. If there's no enclosing clip, the `unclipped` rect (the bounds of
+/// the whole tree) is restored instead. All of the returned primitives are drawn at the given
+/// z-index, in order.
+pub(crate) fn build_primitives(
+    content: &OverviewContent,
+    frame: Rect,
+    prev_clip: &RenderPrimitive,
+    unclipped: Rect,
+    z_index: f32,
+) -> Vec<RenderPrimitive> {
+    let (frame_layout, frame_mask, restored_clip) = match prev_clip {
+        RenderPrimitive::Clip { layout, mask } => {
+            (frame.intersection(layout), *mask, prev_clip.clone())
+        }
+        _ => (
+            frame,
+            None,
+            RenderPrimitive::Clip {
+                layout: unclipped,
+                mask: None,
+            },
+        ),
+    };
+
+    let fit = Transform::fit(content.bounds, frame);
+    let mut primitives = vec![RenderPrimitive::Clip {
+        layout: frame_layout,
+        mask: frame_mask,
+    }];
+    for command in content.draw_list.commands.iter() {
+        let mut primitive = command.transformed(&fit).into_primitive();
+        match &mut primitive {
+            RenderPrimitive::Clip { layout, mask } => {
+                *layout = layout.intersection(&frame_layout);
+                if mask.is_none() {
+                    *mask = frame_mask;
+                }
+            }
+            _ => {
+                if let Some(mut layout) = primitive.layout() {
+                    layout.z_index = z_index;
+                    primitive.set_layout(layout);
+                }
+            }
+        }
+        primitives.push(primitive);
+    }
+    primitives.push(restored_clip);
+
+    for primitive in primitives.iter_mut() {
+        if let RenderPrimitive::Clip { layout, .. } = primitive {
+            layout.z_index = z_index;
+        }
+    }
+    primitives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_primitives, OverviewContent};
+    use crate::draw_list::DrawList;
+    use crate::layout_cache::Rect;
+    use crate::render_primitive::RenderPrimitive;
+    use crate::styles::{BorderStyle, Corner, Edge};
+    use crate::Color;
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    #[test]
+    fn should_fit_content_within_frame() {
+        let quad = RenderPrimitive::Quad {
+            layout: rect(200.0, 100.0, 100.0, 50.0),
+            background_color: Color::WHITE,
+            border_color: Color::BLACK,
+            border_colors: Edge::all(Color::BLACK),
+            border: Edge::all(2.0),
+            border_radius: Corner::all(4.0),
+            border_style: Edge::all(BorderStyle::Solid),
+        };
+        let content = OverviewContent {
+            bounds: rect(100.0, 100.0, 400.0, 200.0),
+            draw_list: DrawList::from_primitives(&[quad], 0),
+        };
+        let primitives = build_primitives(
+            &content,
+            rect(0.0, 0.0, 200.0, 100.0),
+            &RenderPrimitive::Empty,
+            rect(0.0, 0.0, 1000.0, 1000.0),
+            5.0,
+        );
+
+        assert_eq!(3, primitives.len());
+        match &primitives[1] {
+            RenderPrimitive::Quad {
+                layout,
+                border,
+                border_radius,
+                ..
+            } => {
+                assert_eq!(
+                    Rect {
+                        posx: 50.0,
+                        posy: 0.0,
+                        width: 50.0,
+                        height: 25.0,
+                        z_index: 5.0,
+                    },
+                    *layout
+                );
+                assert_eq!(Edge::all(1.0), *border);
+                assert_eq!(Corner::all(2.0), *border_radius);
+            }
+            primitive => panic!("Expected a quad, got {:?}", primitive),
+        }
+        assert_eq!(
+            RenderPrimitive::Clip {
+                layout: Rect {
+                    z_index: 5.0,
+                    ..rect(0.0, 0.0, 1000.0, 1000.0)
+                },
+                mask: None,
+            },
+            primitives[2]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::Index;
+
+/// An effect created via [`KayakContext::create_effect`](crate::KayakContext::create_effect)
+pub(crate) type Effect = Arc<dyn Fn() + Send + Sync>;
+
+#[derive(Default)]
+struct PausedState {
+    widgets: HashSet<Index>,
+    /// The effects whose dependencies changed while their widget was paused, in order
+    deferred_effects: Vec<(Index, Effect)>,
+}
+
+/// The widgets within [inactive fragments](crate::FragmentProps::inactive), which don't render or
+/// run their effects until the fragment is active again
+///
+/// This is shared with the effects themselves, since they run as soon as their dependencies change
+/// rather than when their widget renders.
+#[derive(Default, Clone)]
+pub(crate) struct PausedWidgets(Arc<Mutex<PausedState>>);
+
+impl PausedWidgets {
+    /// Returns true if the widget with the given ID is paused
+    pub fn contains(&self, index: Index) -> bool {
+        self.0
+            .lock()
+            .map_or(false, |state| state.widgets.contains(&index))
+    }
+
+    /// Replaces the paused widgets, returning the deferred effects of the widgets that resumed
+    pub fn set(&self, widgets: HashSet<Index>) -> Vec<(Index, Effect)> {
+        let mut state = match self.0.lock() {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+        state.widgets = widgets;
+        let (deferred, resumed): (Vec<_>, Vec<_>) = std::mem::take(&mut state.deferred_effects)
+            .into_iter()
+            .partition(|(widget_id, _)| state.widgets.contains(widget_id));
+        state.deferred_effects = deferred;
+        resumed
+    }
+
+    /// Runs the given effect of the given widget, or defers it until the widget resumes if it's
+    /// paused
+    ///
+    /// An effect is only deferred once, however many times its dependencies change in the meantime.
+    pub fn run_effect(&self, widget_id: Index, effect: &Effect) {
+        if let Ok(mut state) = self.0.lock() {
+            if state.widgets.contains(&widget_id) {
+                let is_deferred = state
+                    .deferred_effects
+                    .iter()
+                    .any(|(_, deferred)| Arc::ptr_eq(deferred, effect));
+                if !is_deferred {
+                    state.deferred_effects.push((widget_id, effect.clone()));
+                }
+                return;
+            }
+        }
+        effect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::{Effect, PausedWidgets};
+    use crate::Index;
+
+    #[test]
+    fn should_defer_the_effects_of_paused_widgets_until_they_resume() {
+        let paused = PausedWidgets::default();
+        let widget_id = Index::from_raw_parts(1, 0);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let effect: Effect = Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        paused.run_effect(widget_id, &effect);
+        assert_eq!(1, runs.load(Ordering::SeqCst));
+
+        paused.set(HashSet::from([widget_id]));
+        paused.run_effect(widget_id, &effect);
+        paused.run_effect(widget_id, &effect);
+        assert_eq!(1, runs.load(Ordering::SeqCst));
+
+        let resumed = paused.set(HashSet::new());
+        assert_eq!(1, resumed.len());
+        assert_eq!(widget_id, resumed[0].0);
+        assert!(Arc::ptr_eq(&effect, &resumed[0].1));
+    }
+}
@@ -0,0 +1,98 @@
+//! Layers for drawing portals above the rest of the widget tree
+//!
+//! Widgets are drawn into the [`CONTENT`] layer by default, which is the main tree. A widget marked
+//! as a portal (see [`KayakContextRef::set_portal`](crate::KayakContextRef::set_portal)), or with
+//! a [`layer`](crate::styles::Style::layer) style, is taken out of the normal drawing order, along
+//! with all of its descendants, and drawn into a named layer instead. Hit-testing follows the same
+//! order: widgets in a higher layer are hit first.
+//!
+//! Layers are drawn after the main tree, from the lowest order to the highest, while portals within
+//! the same layer are drawn in tree order, unless they've been raised (see
+//! [`KayakContextRef::raise_portal`](crate::KayakContextRef::raise_portal)). Portals into a layer
+//! that hasn't been registered (or into the content layer) are drawn in place.
+//!
+//! The default layers, from bottom to top, are:
+//!
+//! | Layer        | Order |
+//! |--------------|-------|
+//! | [`CONTENT`]  | 0     |
+//! | [`WINDOW`]   | 50    |
+//! | [`DROPDOWN`] | 100   |
+//! | [`MODAL`]    | 200   |
+//! | [`TOOLTIP`]  | 300   |
+//!
+//! Additional layers can be registered (and the default ones reordered) with
+//! [`KayakContext::register_portal_layer`](crate::KayakContext::register_portal_layer). Since the
+//! content layer is always drawn first, other layers should have a positive order.
+
+use std::collections::HashMap;
+
+/// The base layer for the content of the main tree, which all widgets are drawn into by default
+pub const CONTENT: &str = "content";
+/// The layer for floating windows and panels
+pub const WINDOW: &str = "window";
+/// The layer for dropdowns, menus and other popups anchored to a widget
+pub const DROPDOWN: &str = "dropdown";
+/// The layer for modals and dialogs
+pub const MODAL: &str = "modal";
+/// The layer for tooltips
+pub const TOOLTIP: &str = "tooltip";
+
+/// The registered portal layers, mapped to their order
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PortalLayers {
+    orders: HashMap<String, i32>,
+}
+
+impl Default for PortalLayers {
+    fn default() -> Self {
+        let mut layers = Self {
+            orders: HashMap::new(),
+        };
+        layers.register(WINDOW, 50);
+        layers.register(DROPDOWN, 100);
+        layers.register(MODAL, 200);
+        layers.register(TOOLTIP, 300);
+        layers
+    }
+}
+
+impl PortalLayers {
+    /// Registers a layer with the given order, replacing the order of any existing layer with
+    /// the same name
+    pub fn register(&mut self, name: &str, order: i32) {
+        self.orders.insert(name.to_string(), order);
+    }
+
+    /// Returns the order of the given layer, if it's registered
+    pub fn order(&self, name: &str) -> Option<i32> {
+        self.orders.get(name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PortalLayers, DROPDOWN, MODAL, TOOLTIP, WINDOW};
+
+    #[test]
+    fn default_layers_should_be_ordered() {
+        let layers = PortalLayers::default();
+        let window = layers.order(WINDOW).unwrap();
+        let dropdown = layers.order(DROPDOWN).unwrap();
+        let modal = layers.order(MODAL).unwrap();
+        let tooltip = layers.order(TOOLTIP).unwrap();
+
+        assert!(window < dropdown && dropdown < modal && modal < tooltip);
+        assert_eq!(None, layers.order("missing"));
+    }
+
+    #[test]
+    fn should_reorder_existing_layer() {
+        let mut layers = PortalLayers::default();
+        layers.register("toast", 250);
+        layers.register(DROPDOWN, 400);
+
+        assert_eq!(Some(250), layers.order("toast"));
+        assert_eq!(Some(400), layers.order(DROPDOWN));
+    }
+}
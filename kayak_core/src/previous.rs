@@ -0,0 +1,50 @@
+use std::sync::{Arc, Mutex};
+
+/// A value from the previous render, stored as widget state
+///
+/// Replacing the value doesn't re-render the widget, since it's only meant to be read on the next
+/// render. See [`KayakContextRef::use_previous`](crate::KayakContextRef::use_previous).
+#[derive(Debug)]
+pub(crate) struct Previous<T>(Arc<Mutex<Option<T>>>);
+
+impl<T> Previous<T> {
+    /// Stores the given value, returning the one stored before it (if any)
+    pub fn replace(&self, value: T) -> Option<T> {
+        match self.0.lock() {
+            Ok(mut previous) => previous.replace(value),
+            Err(_) => None,
+        }
+    }
+}
+
+impl<T> Default for Previous<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(None)))
+    }
+}
+
+impl<T> Clone for Previous<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> PartialEq for Previous<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Previous;
+
+    #[test]
+    fn should_lag_by_one() {
+        let previous = Previous::default();
+        assert_eq!(None, previous.replace(1));
+        assert_eq!(Some(1), previous.replace(2));
+        assert_eq!(Some(2), previous.clone().replace(2));
+        assert_eq!(Some(2), previous.replace(3));
+    }
+}
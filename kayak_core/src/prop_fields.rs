@@ -0,0 +1,273 @@
+//! Validation of the attributes given to a widget in `rsx!`
+//!
+//! `rsx!` has no knowledge of a widget's props when it's expanded, so it instead generates a
+//! constant per attribute that looks the attribute up in the [`PropFields`] of the widget's props.
+//! The constant is evaluated while the surrounding function is type-checked, so an unknown
+//! attribute fails to compile with a message pointing at it, suggesting the closest field (such as "unknown attribute `siez`, did you mean `size`?").
+//!
+//! Props that don't implement [`PropFields`] (such as those implementing
+//! [`WidgetProps`](crate::WidgetProps) manually) aren't checked, leaving unknown attributes to the
+//! compiler's own "no field" error.
+
+use std::marker::PhantomData;
+
+/// The names of the fields of a props type
+///
+/// This is implemented automatically by `#[derive(WidgetProps)]`, and allows `rsx!` to report
+/// unknown attributes (see [`prop_fields`](crate::prop_fields)).
+pub trait PropFields {
+    /// The names of the fields, in order
+    ///
+    /// The fields of tuple structs are named by their index.
+    const FIELDS: &'static [&'static str];
+}
+
+/// The maximum edit distance for an attribute to be considered a misspelling of a field
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// The maximum length of an error message, beyond which the suggestion is left out
+const MAX_MESSAGE_LENGTH: usize = 256;
+
+/// The maximum length of a name compared for suggestions
+const MAX_NAME_LENGTH: usize = 64;
+
+/// Gets the fields of a props type, if it implements [`PropFields`]
+///
+/// This relies on inherent associated constants taking precedence over trait ones: the inherent
+/// `FIELDS` only exists if `P` implements [`PropFields`], otherwise the one from [`NoPropFields`]
+/// (which must be in scope) is used.
+#[doc(hidden)]
+pub struct PropFieldsProbe<P: ?Sized>(PhantomData<P>);
+
+impl<P: PropFields + ?Sized> PropFieldsProbe<P> {
+    pub const FIELDS: Option<&'static [&'static str]> = Some(P::FIELDS);
+}
+
+/// The fallback for [`PropFieldsProbe`], for props that don't implement [`PropFields`]
+#[doc(hidden)]
+pub trait NoPropFields {
+    const FIELDS: Option<&'static [&'static str]> = None;
+}
+
+impl<P: ?Sized> NoPropFields for PropFieldsProbe<P> {}
+
+/// The error message for an unknown attribute
+#[doc(hidden)]
+pub struct AttributeError {
+    bytes: [u8; MAX_MESSAGE_LENGTH],
+    len: usize,
+}
+
+impl AttributeError {
+    /// Creates the message made of the given parts, or `None` if it's too long
+    const fn concat(parts: &[&str]) -> Option<Self> {
+        let mut bytes = [0; MAX_MESSAGE_LENGTH];
+        let mut len = 0;
+        let mut part = 0;
+        while part < parts.len() {
+            let value = parts[part].as_bytes();
+            if len + value.len() > MAX_MESSAGE_LENGTH {
+                return None;
+            }
+            let mut index = 0;
+            while index < value.len() {
+                bytes[len + index] = value[index];
+                index += 1;
+            }
+            len += value.len();
+            part += 1;
+        }
+        Some(Self { bytes, len })
+    }
+
+    pub const fn as_str(&self) -> &str {
+        // Only whole strings are ever pushed, so the bytes are always valid UTF-8
+        unsafe {
+            std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.bytes.as_ptr(), self.len))
+        }
+    }
+}
+
+/// Checks that the attribute with the given name is one of the given fields
+///
+/// Returns the error message if it isn't, or `None` if it is (or the fields are unknown).
+#[doc(hidden)]
+pub const fn check_attribute(
+    fields: Option<&'static [&'static str]>,
+    name: &str,
+) -> Option<AttributeError> {
+    let fields = match fields {
+        Some(fields) => fields,
+        None => return None,
+    };
+
+    let mut suggestion = None;
+    let mut suggestion_distance = MAX_SUGGESTION_DISTANCE + 1;
+    let mut index = 0;
+    while index < fields.len() {
+        let field = fields[index];
+        if str_eq(field, name) {
+            return None;
+        }
+        let distance = edit_distance(name, field);
+        if distance < suggestion_distance {
+            suggestion = Some(field);
+            suggestion_distance = distance;
+        }
+        index += 1;
+    }
+
+    // Leave out the parts that don't fit, from the least useful one
+    if let Some(suggestion) = suggestion {
+        let parts = [
+            "unknown attribute `",
+            name,
+            "`, did you mean `",
+            suggestion,
+            "`?",
+        ];
+        if let Some(error) = AttributeError::concat(&parts) {
+            return Some(error);
+        }
+    }
+    match AttributeError::concat(&["unknown attribute `", name, "`"]) {
+        Some(error) => Some(error),
+        None => AttributeError::concat(&["unknown attribute"]),
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// Calculates the Levenshtein distance between the bytes of two strings
+///
+/// Strings longer than [`MAX_NAME_LENGTH`] are never considered close.
+const fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() > MAX_NAME_LENGTH || b.len() > MAX_NAME_LENGTH {
+        return usize::MAX;
+    }
+
+    let mut prev_row = [0; MAX_NAME_LENGTH + 1];
+    let mut j = 0;
+    while j <= b.len() {
+        prev_row[j] = j;
+        j += 1;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        let mut row = [0; MAX_NAME_LENGTH + 1];
+        row[0] = i + 1;
+        let mut j = 0;
+        while j < b.len() {
+            let cost = if a[i] == b[j] { 0 } else { 1 };
+            let mut distance = prev_row[j] + cost;
+            if prev_row[j + 1] + 1 < distance {
+                distance = prev_row[j + 1] + 1;
+            }
+            if row[j] + 1 < distance {
+                distance = row[j] + 1;
+            }
+            row[j + 1] = distance;
+            j += 1;
+        }
+        prev_row = row;
+        i += 1;
+    }
+
+    prev_row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_attribute, edit_distance, NoPropFields, PropFields, PropFieldsProbe};
+    use kayak_render_macros::WidgetProps;
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct TextProps {
+        content: String,
+        size: f32,
+    }
+
+    #[derive(Debug)]
+    struct ManualProps;
+
+    fn check(fields: Option<&'static [&'static str]>, name: &str) -> Option<String> {
+        check_attribute(fields, name).map(|error| error.as_str().to_string())
+    }
+
+    #[test]
+    fn should_list_the_fields_of_derived_props() {
+        assert_eq!(&["content", "size"], <TextProps as PropFields>::FIELDS);
+        assert_eq!(
+            Some(&["content", "size"][..]),
+            PropFieldsProbe::<TextProps>::FIELDS
+        );
+        assert_eq!(None, PropFieldsProbe::<ManualProps>::FIELDS);
+    }
+
+    #[test]
+    fn should_accept_known_attributes() {
+        let fields = PropFieldsProbe::<TextProps>::FIELDS;
+        assert_eq!(None, check(fields, "content"));
+        assert_eq!(None, check(fields, "size"));
+    }
+
+    #[test]
+    fn should_suggest_the_closest_field() {
+        let fields = PropFieldsProbe::<TextProps>::FIELDS;
+        assert_eq!(
+            Some(String::from(
+                "unknown attribute `siez`, did you mean `size`?"
+            )),
+            check(fields, "siez")
+        );
+        assert_eq!(
+            Some(String::from(
+                "unknown attribute `contnet`, did you mean `content`?"
+            )),
+            check(fields, "contnet")
+        );
+        assert_eq!(
+            Some(String::from("unknown attribute `color`")),
+            check(fields, "color")
+        );
+    }
+
+    #[test]
+    fn should_not_check_props_without_fields() {
+        assert_eq!(None, check(PropFieldsProbe::<ManualProps>::FIELDS, "siez"));
+    }
+
+    #[test]
+    fn should_check_attributes_at_compile_time() {
+        const ERROR: Option<&str> =
+            match check_attribute(PropFieldsProbe::<TextProps>::FIELDS, "size") {
+                Some(_) => Some("unknown"),
+                None => None,
+            };
+        assert_eq!(None, ERROR);
+    }
+
+    #[test]
+    fn should_calculate_edit_distances() {
+        assert_eq!(0, edit_distance("size", "size"));
+        assert_eq!(2, edit_distance("siez", "size"));
+        assert_eq!(1, edit_distance("style", "styles"));
+        assert_eq!(3, edit_distance("", "abc"));
+        assert_eq!(usize::MAX, edit_distance(&"a".repeat(65), "a"));
+    }
+}
@@ -0,0 +1,290 @@
+//! A trait for drawing the UI with a custom graphics backend
+//!
+//! The Bevy integration implements this for its own renderer and needs no setup. To draw the UI
+//! anywhere else (such as with wgpu directly, or into a test harness), implement [`RenderBackend`]
+//! and pass it to [`KayakContext::draw_with`](crate::KayakContext::draw_with) after each render.
+//! The operations are called in drawing order, using the coordinate space described in
+//! [`draw_list`](crate::draw_list).
+
+use kayak_font::{ColorAtlasRegion, ColorFont, ColorGlyphImage, GlyphRect};
+
+use crate::curve::LineSegment;
+use crate::draw_list::DrawQuad;
+use crate::layout_cache::Rect;
+use crate::render_primitive::ClipMask;
+use crate::styles::{Corner, Edge};
+use crate::Color;
+
+/// A run of glyphs set in a single font, size and color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphRun<'a> {
+    /// The name of the font
+    pub font: &'a str,
+    pub font_size: f32,
+    pub color: Color,
+    /// The glyphs, positioned in the UI's coordinate space
+    pub glyphs: &'a [GlyphRect],
+    /// The glyphs drawn in color (such as emoji), which aren't part of `glyphs`
+    pub color_glyphs: &'a [ColorGlyph],
+    /// If false, glyph positions should be rounded to whole physical pixels
+    pub subpixel_positioning: bool,
+    /// The bounds of the text
+    pub rect: Rect,
+}
+
+/// A glyph drawn from the [`UiTexture::ColorGlyphs`] texture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorGlyph {
+    /// The glyph, positioned in the UI's coordinate space
+    pub glyph: GlyphRect,
+    /// The region of the texture to draw the glyph from (in texels)
+    pub region: ColorAtlasRegion,
+}
+
+/// A texture generated by the UI itself, rather than registered by the integration with a handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiTexture {
+    /// The [color glyphs](kayak_font::ColorAtlas) (such as emoji) of the UI's text
+    ColorGlyphs,
+}
+
+/// The pixels of a texture generated by the UI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureUpload<'a> {
+    pub texture: UiTexture,
+    /// A number that changes whenever the pixels (or size) of the texture do
+    pub version: u64,
+    /// The width and height of the texture (in texels)
+    pub size: (u32, u32),
+    /// The texels, row by row from the top, as straight RGBA with 8 bits per channel
+    pub data: &'a [u8],
+}
+
+/// The operations needed to draw the UI
+///
+/// Textures registered by the integration are referred to by their handle, so loading (and caching)
+/// them is up to the backend, as is drawing the glyphs of each font from its distance field.
+/// Textures generated by the UI itself are handed to the backend with
+/// [`upload_texture`](Self::upload_texture).
+pub trait RenderBackend {
+    /// Called before anything else is drawn in a frame
+    ///
+    /// The generation is that of the [`DrawList`](crate::DrawList) being drawn, which a backend
+    /// can use to skip frames it already drew.
+    fn begin_frame(&mut self, _generation: u64) {}
+
+    /// Uploads a texture generated by the UI, before anything is drawn from it
+    ///
+    /// This is called at the start of every frame drawn by
+    /// [`KayakContext::draw_with`](crate::KayakContext::draw_with), so a backend should skip
+    /// versions it already uploaded.
+    fn upload_texture(&mut self, texture: TextureUpload);
+
+    /// Rasterizes a glyph of a color font (such as an emoji) at the given size, in pixels per em
+    ///
+    /// The UI packs the color glyphs it draws into the [`UiTexture::ColorGlyphs`] texture, asking
+    /// for each glyph once per size, before the frame that first draws it. The default
+    /// implementation uses the rasterizer of `kayak_font`.
+    fn rasterize_glyph(
+        &mut self,
+        font: &ColorFont,
+        glyph: char,
+        pixels_per_em: u16,
+    ) -> Option<ColorGlyphImage> {
+        font.rasterize(glyph, pixels_per_em)
+    }
+
+    /// Restricts everything drawn after this to the given rect (and mask), until the next clip
+    fn set_clip(&mut self, rect: Rect, mask: Option<ClipMask>);
+
+    /// Draws a batch of consecutive quads, from the bottom to the top
+    fn draw_quads(&mut self, quads: &[DrawQuad]);
+
+    /// Draws a run of glyphs
+    fn draw_glyphs(&mut self, run: GlyphRun);
+
+    /// Draws an image, stretched to fill the rect
+    fn draw_image(&mut self, rect: Rect, handle: u16, border_radius: Corner<f32>);
+
+    /// Draws the region of a texture atlas at the given position and size (in texels)
+    fn draw_texture_atlas(
+        &mut self,
+        rect: Rect,
+        handle: u16,
+        position: (f32, f32),
+        size: (f32, f32),
+    );
+
+    /// Draws a nine-patch image whose edges (in texels) keep their size
+    fn draw_nine_patch(&mut self, rect: Rect, handle: u16, border: Edge<f32>);
+
+    /// Draws a batch of anti-aliased lines with round ends, such as those making up a curve
+    ///
+    /// Consecutive lines that meet end to start (such as those of a curve) form a joint there,
+    /// where their round ends overlap. Backends should draw each joint once, so that it isn't
+    /// blended twice. The rect is the bounds of the widget drawing the lines, which the lines may
+    /// reach outside of.
+    fn draw_lines(&mut self, rect: Rect, lines: &[LineSegment]);
+
+    /// Called once everything in a frame has been drawn
+    fn end_frame(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_font::{ColorAtlas, ColorAtlasRegion, GlyphRect};
+
+    use super::{GlyphRun, RenderBackend, TextureUpload};
+    use crate::curve::LineSegment;
+    use crate::draw_list::{DrawCommand, DrawList, DrawQuad};
+    use crate::layout_cache::Rect;
+    use crate::render_primitive::ClipMask;
+    use crate::styles::{BorderStyle, Corner, Edge};
+    use crate::{Color, KayakContext};
+
+    /// A backend that records the operations it's given
+    #[derive(Default)]
+    struct RecordingBackend(Vec<String>);
+
+    impl RenderBackend for RecordingBackend {
+        fn begin_frame(&mut self, generation: u64) {
+            self.0.push(format!("begin {}", generation));
+        }
+
+        fn upload_texture(&mut self, texture: TextureUpload) {
+            self.0.push(format!(
+                "upload {:?} {}x{}",
+                texture.texture, texture.size.0, texture.size.1
+            ));
+        }
+
+        fn set_clip(&mut self, rect: Rect, _: Option<ClipMask>) {
+            self.0.push(format!("clip {}", rect.width));
+        }
+
+        fn draw_quads(&mut self, quads: &[DrawQuad]) {
+            self.0.push(format!("quads {}", quads.len()));
+        }
+
+        fn draw_glyphs(&mut self, run: GlyphRun) {
+            self.0.push(format!(
+                "glyphs {} color {}",
+                run.glyphs.len(),
+                run.color_glyphs.len()
+            ));
+        }
+
+        fn draw_image(&mut self, _: Rect, handle: u16, _: Corner<f32>) {
+            self.0.push(format!("image {}", handle));
+        }
+
+        fn draw_texture_atlas(&mut self, _: Rect, handle: u16, _: (f32, f32), _: (f32, f32)) {
+            self.0.push(format!("atlas {}", handle));
+        }
+
+        fn draw_nine_patch(&mut self, _: Rect, handle: u16, _: Edge<f32>) {
+            self.0.push(format!("nine patch {}", handle));
+        }
+
+        fn draw_lines(&mut self, _: Rect, lines: &[LineSegment]) {
+            self.0.push(format!("lines {}", lines.len()));
+        }
+
+        fn end_frame(&mut self) {
+            self.0.push(String::from("end"));
+        }
+    }
+
+    #[test]
+    fn should_replay_in_order_and_batch_quads() {
+        let quad = DrawCommand::Quad(DrawQuad {
+            rect: Rect::default(),
+            color: Color::WHITE,
+            border_color: Color::TRANSPARENT,
+            border_colors: Edge::all(Color::TRANSPARENT),
+            border: Edge::all(0.0),
+            border_radius: Corner::all(0.0),
+            border_style: Edge::all(BorderStyle::Solid),
+        });
+        let clip = DrawCommand::Clip {
+            rect: Rect {
+                width: 50.0,
+                ..Default::default()
+            },
+            mask: None,
+        };
+        let image = DrawCommand::Image {
+            rect: Rect::default(),
+            handle: 3,
+            border_radius: Corner::all(0.0),
+        };
+        let draw_list = DrawList {
+            commands: vec![quad.clone(), quad.clone(), clip, quad, image],
+            generation: 2,
+        };
+
+        let mut backend = RecordingBackend::default();
+        draw_list.replay(&mut backend);
+        assert_eq!(
+            vec!["begin 2", "quads 2", "clip 50", "quads 1", "image 3", "end"],
+            backend.0
+        );
+    }
+
+    #[test]
+    fn should_draw_color_glyphs_from_the_atlas() {
+        let glyph = |content| GlyphRect {
+            position: (0.0, 0.0),
+            size: (10.0, 10.0),
+            content,
+        };
+        let draw_list = DrawList {
+            commands: vec![DrawCommand::Glyphs {
+                font: String::from("roboto"),
+                font_size: 14.0,
+                color: Color::WHITE,
+                glyphs: vec![glyph('a'), glyph('x'), glyph('y'), glyph('b')],
+                subpixel_positioning: false,
+                rect: Rect::default(),
+            }],
+            generation: 1,
+        };
+
+        let mut backend = RecordingBackend::default();
+        // 'x' and 'y' are color glyphs, but 'y' didn't fit in the atlas
+        draw_list.replay_with(
+            &mut backend,
+            Some(&ColorAtlas::new()),
+            |_, _, glyph| match glyph {
+                'x' => Some(Some(ColorAtlasRegion::default())),
+                'y' => Some(None),
+                _ => None,
+            },
+        );
+        assert_eq!(
+            vec![
+                "begin 1",
+                "upload ColorGlyphs 256x256",
+                "glyphs 2 color 1",
+                "end"
+            ],
+            backend.0
+        );
+
+        // Without the fonts, every glyph is drawn from its font
+        let mut backend = RecordingBackend::default();
+        draw_list.replay(&mut backend);
+        assert_eq!(vec!["begin 1", "glyphs 4 color 0", "end"], backend.0);
+    }
+
+    #[test]
+    fn should_upload_the_color_atlas_before_drawing() {
+        let mut context = KayakContext::new();
+        let mut backend = RecordingBackend::default();
+        context.draw_with(&mut backend);
+        assert_eq!(
+            vec!["begin 0", "upload ColorGlyphs 256x256", "end"],
+            backend.0
+        );
+    }
+}
@@ -1,6 +1,17 @@
+use crate::curve::Curve;
 use crate::styles::Edge;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum RenderCommand {
     Empty,
     /// Represents a node that has no renderable object but contributes to the layout.
@@ -22,6 +33,10 @@ pub enum RenderCommand {
         border: Edge<f32>,
         handle: u16,
     },
+    /// Cubic bezier curves (and arrows), positioned relative to the widget's top-left corner
+    Curves {
+        curves: Vec<Curve>,
+    },
 }
 
 impl Default for RenderCommand {
@@ -1,23 +1,43 @@
 use crate::{
     color::Color,
+    curve::Curve,
     layout_cache::Rect,
     render_command::RenderCommand,
-    styles::{Corner, Edge, Style},
+    styles::{BorderStyle, Corner, Edge, Style},
 };
 use kayak_font::{TextLayout, TextProperties};
 
+/// A rounded outline that content is masked to
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClipMask {
+    /// The bounds of the mask
+    pub layout: Rect,
+    /// The radius of each of the mask's corners
+    pub border_radius: Corner<f32>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum RenderPrimitive {
     Empty,
     Clip {
+        /// The rectangle to clip to, limited to the bounds of any enclosing clips
         layout: Rect,
+        /// The rounded outline to mask content to, if any
+        ///
+        /// This is set from the clip's own border radius, falling back to the mask of the nearest
+        /// enclosing clip so that nested rectangular clips (such as scroll boxes) stay masked.
+        mask: Option<ClipMask>,
     },
     Quad {
         layout: Rect,
         background_color: Color,
+        /// The color of the border, as set by [`Style::border_color`]
         border_color: Color,
+        /// The color of each side of the border, falling back to `border_color` on every side
+        border_colors: Edge<Color>,
         border: Edge<f32>,
         border_radius: Corner<f32>,
+        border_style: Edge<BorderStyle>,
     },
     Text {
         color: Color,
@@ -43,29 +63,157 @@ pub enum RenderPrimitive {
         layout: Rect,
         handle: u16,
     },
+    Curves {
+        layout: Rect,
+        /// The curves to draw, positioned relative to the top-left corner of the layout
+        curves: Vec<Curve>,
+    },
 }
 
 impl RenderPrimitive {
     pub fn set_layout(&mut self, new_layout: Rect) {
         match self {
-            RenderPrimitive::Clip { layout, .. } => *layout = new_layout,
+            RenderPrimitive::Clip { layout, mask } => {
+                *layout = new_layout;
+                if let Some(mask) = mask {
+                    mask.layout = new_layout;
+                }
+            }
             RenderPrimitive::Quad { layout, .. } => *layout = new_layout,
             RenderPrimitive::Text { layout, .. } => *layout = new_layout,
             RenderPrimitive::Image { layout, .. } => *layout = new_layout,
             RenderPrimitive::NinePatch { layout, .. } => *layout = new_layout,
             RenderPrimitive::TextureAtlas { layout, .. } => *layout = new_layout,
+            RenderPrimitive::Curves { layout, .. } => *layout = new_layout,
+            _ => (),
+        }
+    }
+
+    /// Returns the layout of this primitive, if it has one
+    pub(crate) fn layout(&self) -> Option<Rect> {
+        match self {
+            RenderPrimitive::Clip { layout, .. }
+            | RenderPrimitive::Quad { layout, .. }
+            | RenderPrimitive::Text { layout, .. }
+            | RenderPrimitive::Image { layout, .. }
+            | RenderPrimitive::NinePatch { layout, .. }
+            | RenderPrimitive::TextureAtlas { layout, .. }
+            | RenderPrimitive::Curves { layout, .. } => Some(*layout),
+            _ => None,
+        }
+    }
+
+    /// Returns the z-index of this primitive, if it has a layout
+    pub(crate) fn z_index(&self) -> Option<f32> {
+        self.layout().map(|layout| layout.z_index)
+    }
+
+    /// Scales the sizes within this primitive (other than its layout) by the given factor
+    ///
+    /// This is used for widgets drawn with a [transform](Style::transform). Nine-patch borders
+    /// are left as is, since they keep their size by design.
+    pub(crate) fn scale(&mut self, scale: f32) {
+        match self {
+            RenderPrimitive::Clip {
+                mask: Some(mask), ..
+            } => mask.border_radius *= scale,
+            RenderPrimitive::Quad {
+                border,
+                border_radius,
+                ..
+            } => {
+                *border *= scale;
+                *border_radius *= scale;
+            }
+            RenderPrimitive::Text {
+                text_layout,
+                properties,
+                ..
+            } => {
+                *text_layout = text_layout.scaled(scale);
+                *properties = properties.scaled(scale);
+            }
+            RenderPrimitive::Image { border_radius, .. } => *border_radius *= scale,
+            RenderPrimitive::Curves { curves, .. } => {
+                for curve in curves.iter_mut() {
+                    *curve = curve.transformed(|(x, y)| (x * scale, y * scale), scale);
+                }
+            }
             _ => (),
         }
     }
+
+    /// Returns the extra passes drawn beneath this text, for its shadow and outline
+    ///
+    /// Each pass is a copy of the text, moved and recolored, with the shadow beneath the outline
+    /// and both just beneath the text itself. Nothing is returned for other primitives, or if the
+    /// style has neither a [`text_shadow`](Style::text_shadow) nor a
+    /// [`text_outline`](Style::text_outline).
+    pub(crate) fn text_effects(&self, style: &Style) -> Vec<RenderPrimitive> {
+        let layout = match self {
+            RenderPrimitive::Text { layout, .. } => *layout,
+            _ => return Vec::new(),
+        };
+        let shadow = style.text_shadow.resolve_or_default();
+        let outline = style.text_outline.resolve_or_default();
+        let opacity = style.opacity.resolve_or(1.0).clamp(0.0, 1.0);
+
+        let pass = |offset: (f32, f32), pass_color: Color, z_offset: f32| {
+            let mut primitive = self.clone();
+            if let RenderPrimitive::Text {
+                color: text_color,
+                layout: text_layout,
+                ..
+            } = &mut primitive
+            {
+                *text_color = fade(pass_color, opacity);
+                *text_layout = Rect {
+                    posx: layout.posx + offset.0,
+                    posy: layout.posy + offset.1,
+                    z_index: layout.z_index - z_offset,
+                    ..layout
+                };
+            }
+            primitive
+        };
+
+        let shadow_passes = shadow.passes().into_iter().map(|(offset, alpha)| {
+            let color = Color {
+                a: alpha,
+                ..shadow.color
+            };
+            pass(offset, color, 0.02)
+        });
+        let outline_passes = outline
+            .passes()
+            .into_iter()
+            .map(|offset| pass(offset, outline.color, 0.01));
+        shadow_passes.chain(outline_passes).collect()
+    }
 }
 
 impl From<&Style> for RenderPrimitive {
     fn from(style: &Style) -> Self {
         let render_command = style.render_command.resolve();
 
-        let background_color = style.background_color.resolve_or(Color::TRANSPARENT);
+        let opacity = style.opacity.resolve_or(1.0).clamp(0.0, 1.0);
+
+        let background_color = fade(
+            style.background_color.resolve_or(Color::TRANSPARENT),
+            opacity,
+        );
 
         let border_color = style.border_color.resolve_or(Color::TRANSPARENT);
+        let border_colors = style
+            .border_colors
+            .resolve_or_else(|| Edge::all(border_color));
+        let border_color = fade(border_color, opacity);
+        let border_colors = Edge::new(
+            fade(border_colors.top, opacity),
+            fade(border_colors.right, opacity),
+            fade(border_colors.bottom, opacity),
+            fade(border_colors.left, opacity),
+        );
 
         let font = style
             .font
@@ -78,25 +226,45 @@ impl From<&Style> for RenderPrimitive {
         match render_command {
             RenderCommand::Empty => Self::Empty,
             RenderCommand::Layout => Self::Empty,
-            RenderCommand::Clip => Self::Clip {
-                layout: Rect::default(),
-            },
+            RenderCommand::Clip => {
+                let border_radius = style.border_radius.resolve();
+                let is_rounded = border_radius.top_left > 0.0
+                    || border_radius.top_right > 0.0
+                    || border_radius.bottom_left > 0.0
+                    || border_radius.bottom_right > 0.0;
+                Self::Clip {
+                    layout: Rect::default(),
+                    mask: if is_rounded {
+                        Some(ClipMask {
+                            layout: Rect::default(),
+                            border_radius,
+                        })
+                    } else {
+                        None
+                    },
+                }
+            }
             RenderCommand::Quad => Self::Quad {
                 background_color,
                 border_color,
+                border_colors,
                 border_radius: style.border_radius.resolve(),
                 border: style.border.resolve(),
+                border_style: style.border_style.resolve(),
                 layout: Rect::default(),
             },
             RenderCommand::Text { content } => Self::Text {
-                color: style.color.resolve(),
-                content,
+                color: fade(style.color.resolve(), opacity),
+                content: style.text_transform.resolve_or_default().apply(&content),
                 font,
                 text_layout: TextLayout::default(),
                 layout: Rect::default(),
                 properties: TextProperties {
                     font_size,
                     line_height,
+                    subpixel_positioning: style.subpixel_positioning.resolve_or(false),
+                    letter_spacing: style.letter_spacing.resolve_or(0.0),
+                    word_spacing: style.word_spacing.resolve_or(0.0),
                     ..Default::default()
                 },
             },
@@ -105,7 +273,11 @@ impl From<&Style> for RenderPrimitive {
                 layout: Rect::default(),
                 handle,
             },
-            RenderCommand::TextureAtlas { handle, size, position,  } => Self::TextureAtlas {
+            RenderCommand::TextureAtlas {
+                handle,
+                size,
+                position,
+            } => Self::TextureAtlas {
                 handle,
                 layout: Rect::default(),
                 size,
@@ -116,6 +288,24 @@ impl From<&Style> for RenderPrimitive {
                 layout: Rect::default(),
                 handle,
             },
+            RenderCommand::Curves { curves } => Self::Curves {
+                layout: Rect::default(),
+                curves: curves
+                    .into_iter()
+                    .map(|curve| Curve {
+                        color: fade(curve.color, opacity),
+                        ..curve
+                    })
+                    .collect(),
+            },
         }
     }
 }
+
+/// Multiplies the alpha of the given color by the given opacity
+fn fade(color: Color, opacity: f32) -> Color {
+    Color {
+        a: color.a * opacity,
+        ..color
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{Binding, Bound};
+
+/// The projection of a binding that a widget is subscribed to, along with its last known value
+///
+/// See [`KayakContextRef::select`](crate::KayakContextRef::select).
+pub(crate) struct Selection<T, U> {
+    projection: Box<dyn Fn(&T) -> U + Send + Sync>,
+    value: U,
+}
+
+/// A [`Selection`] shared between a widget and the binding it's subscribed to
+pub(crate) type SharedSelection<T, U> = Arc<Mutex<Selection<T, U>>>;
+
+impl<T, U> Selection<T, U>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+    U: Clone + PartialEq + Send + Sync + 'static,
+{
+    /// Creates a selection with the given projection and its current value
+    pub fn new<F: Fn(&T) -> U + Send + Sync + 'static>(projection: F, value: U) -> Self {
+        Self {
+            projection: Box::new(projection),
+            value,
+        }
+    }
+
+    /// Replaces the projection and its current value
+    ///
+    /// This is called on every render, since the projection may capture values that change
+    /// between renders.
+    pub fn replace<F: Fn(&T) -> U + Send + Sync + 'static>(&mut self, projection: F, value: U) {
+        self.projection = Box::new(projection);
+        self.value = value;
+    }
+
+    /// Projects the current value of the given binding, returning true if the projected value
+    /// changed since the last time
+    pub fn update(&mut self, binding: &Binding<T>) -> bool {
+        let value = (self.projection)(&binding.get());
+        if value == self.value {
+            return false;
+        }
+
+        self.value = value;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selection;
+    use crate::{bind, MutableBound};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct User {
+        name: String,
+        count: u32,
+    }
+
+    #[test]
+    fn should_only_change_with_projected_value() {
+        let user = bind(User {
+            name: String::from("Alice"),
+            count: 0,
+        });
+        let mut selection = Selection::new(|user: &User| user.name.clone(), String::from("Alice"));
+
+        user.set(User {
+            name: String::from("Alice"),
+            count: 1,
+        });
+        assert!(!selection.update(&user));
+
+        user.set(User {
+            name: String::from("Bob"),
+            count: 1,
+        });
+        assert!(selection.update(&user));
+        assert!(!selection.update(&user));
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+use crate::tree::Tree;
+use crate::{Index, KayakContextRef, KeyCode, KeyboardEvent, KeyboardModifiers};
+
+/// A key combination (such as `Ctrl+S`) that triggers a shortcut
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    /// The key that triggers the shortcut
+    pub key: KeyCode,
+    /// The modifiers that must be held (and no others)
+    pub modifiers: KeyboardModifiers,
+}
+
+impl Shortcut {
+    /// Creates a shortcut for the given key, without any modifiers
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            modifiers: KeyboardModifiers::default(),
+        }
+    }
+
+    /// Requires one of the Control keys to be held
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.is_ctrl_pressed = true;
+        self
+    }
+
+    /// Requires one of the Shift keys to be held
+    pub fn shift(mut self) -> Self {
+        self.modifiers.is_shift_pressed = true;
+        self
+    }
+
+    /// Requires one of the Alt (or "Option") keys to be held
+    pub fn alt(mut self) -> Self {
+        self.modifiers.is_alt_pressed = true;
+        self
+    }
+
+    /// Requires one of the Meta keys to be held
+    pub fn meta(mut self) -> Self {
+        self.modifiers.is_meta_pressed = true;
+        self
+    }
+
+    /// Returns true if the given key event triggers this shortcut
+    pub fn matches(&self, event: &KeyboardEvent) -> bool {
+        self.key == event.key() && self.modifiers == event.modifiers()
+    }
+}
+
+/// Where a shortcut is active
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShortcutScope {
+    /// The shortcut is always active
+    Global,
+    /// The shortcut is only active while focus is within the registering widget's subtree
+    /// (including the widget itself)
+    Subtree,
+}
+
+/// A container for a function that handles a shortcut
+#[derive(Clone)]
+pub struct OnShortcut(Arc<RwLock<dyn FnMut(&mut KayakContextRef) + Send + Sync + 'static>>);
+
+impl OnShortcut {
+    /// Create a new shortcut handler
+    ///
+    /// The handler is given the context of the widget that registered the shortcut.
+    pub fn new<F: FnMut(&mut KayakContextRef) + Send + Sync + 'static>(f: F) -> OnShortcut {
+        OnShortcut(Arc::new(RwLock::new(f)))
+    }
+
+    /// Call the shortcut handler
+    ///
+    /// Returns true if the handler was successfully invoked.
+    pub fn try_call(&self, context: &mut KayakContextRef) -> bool {
+        if let Ok(mut on_shortcut) = self.0.write() {
+            on_shortcut(context);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Debug for OnShortcut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OnShortcut").finish()
+    }
+}
+
+impl PartialEq for OnShortcut {
+    fn eq(&self, _: &Self) -> bool {
+        // Never prevent "==" for being true because of this struct
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ShortcutEntry {
+    owner: Index,
+    shortcut: Shortcut,
+    scope: ShortcutScope,
+    handler: OnShortcut,
+}
+
+/// Keeps track of all registered shortcuts, in the order they were registered
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Shortcuts {
+    entries: Vec<ShortcutEntry>,
+}
+
+impl Shortcuts {
+    /// Registers a shortcut for the given widget
+    ///
+    /// Re-registering the same shortcut from the same widget (such as on re-render) replaces its
+    /// scope and handler but keeps its original place in the registration order.
+    pub fn register(
+        &mut self,
+        owner: Index,
+        shortcut: Shortcut,
+        scope: ShortcutScope,
+        handler: OnShortcut,
+    ) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.owner == owner && entry.shortcut == shortcut)
+        {
+            entry.scope = scope;
+            entry.handler = handler;
+        } else {
+            self.entries.push(ShortcutEntry {
+                owner,
+                shortcut,
+                scope,
+                handler,
+            });
+        }
+    }
+
+    /// Unregisters the given shortcut for the given widget
+    ///
+    /// Returns true if the shortcut was registered.
+    pub fn unregister(&mut self, owner: Index, shortcut: Shortcut) -> bool {
+        let count = self.entries.len();
+        self.entries
+            .retain(|entry| entry.owner != owner || entry.shortcut != shortcut);
+        self.entries.len() != count
+    }
+
+    /// Unregisters the shortcuts of all widgets that aren't in the given set of mounted widgets
+    pub fn prune(&mut self, mounted: &HashSet<Index>) {
+        self.entries.retain(|entry| mounted.contains(&entry.owner));
+    }
+
+    /// Finds the shortcut triggered by the given key event, returning its owner and handler
+    ///
+    /// Shortcuts registered by widgets that are no longer in the tree are ignored. Of the remaining
+    /// matches, [subtree](ShortcutScope::Subtree) shortcuts take priority over global ones, and the
+    /// most recently registered shortcut wins within each scope.
+    pub fn find(
+        &self,
+        event: &KeyboardEvent,
+        focus: Option<Index>,
+        tree: &Tree,
+    ) -> Option<(Index, OnShortcut)> {
+        let is_active = |entry: &&ShortcutEntry| match entry.scope {
+            ShortcutScope::Global => true,
+            ShortcutScope::Subtree => focus.map_or(false, |focus| {
+                focus == entry.owner || tree.is_descendant(focus, entry.owner)
+            }),
+        };
+
+        let mut matches = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| tree.contains(entry.owner))
+            .filter(|entry| entry.shortcut.matches(event))
+            .filter(is_active);
+        let first = matches.next()?;
+        let best = if first.scope == ShortcutScope::Subtree {
+            first
+        } else {
+            matches
+                .find(|entry| entry.scope == ShortcutScope::Subtree)
+                .unwrap_or(first)
+        };
+
+        Some((best.owner, best.handler.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OnShortcut, Shortcut, ShortcutScope, Shortcuts};
+    use crate::tree::Tree;
+    use crate::{Index, KeyCode, KeyboardEvent};
+
+    fn ctrl_s() -> Shortcut {
+        Shortcut::new(KeyCode::S).ctrl()
+    }
+
+    fn tree() -> (Tree, Index, Index, Index) {
+        let root = Index::from_raw_parts(0, 0);
+        let a = Index::from_raw_parts(1, 0);
+        let b = Index::from_raw_parts(2, 0);
+        let mut tree = Tree::default();
+        tree.add(root, None);
+        tree.add(a, Some(root));
+        tree.add(b, Some(root));
+        (tree, root, a, b)
+    }
+
+    #[test]
+    fn should_match_exact_modifiers() {
+        let event = KeyboardEvent::new(KeyCode::S, ctrl_s().modifiers);
+        assert!(ctrl_s().matches(&event));
+        assert!(!ctrl_s().shift().matches(&event));
+        assert!(!Shortcut::new(KeyCode::S).matches(&event));
+    }
+
+    #[test]
+    fn most_recent_should_win() {
+        let (tree, root, a, _) = tree();
+        let mut shortcuts = Shortcuts::default();
+        shortcuts.register(
+            root,
+            ctrl_s(),
+            ShortcutScope::Global,
+            OnShortcut::new(|_| {}),
+        );
+        shortcuts.register(a, ctrl_s(), ShortcutScope::Global, OnShortcut::new(|_| {}));
+        // Re-registering keeps the original order
+        shortcuts.register(
+            root,
+            ctrl_s(),
+            ShortcutScope::Global,
+            OnShortcut::new(|_| {}),
+        );
+
+        let event = KeyboardEvent::new(KeyCode::S, ctrl_s().modifiers);
+        let (owner, _) = shortcuts.find(&event, None, &tree).unwrap();
+        assert_eq!(a, owner);
+    }
+
+    #[test]
+    fn subtree_should_require_focus_and_take_priority() {
+        let (tree, root, a, b) = tree();
+        let mut shortcuts = Shortcuts::default();
+        shortcuts.register(a, ctrl_s(), ShortcutScope::Subtree, OnShortcut::new(|_| {}));
+        shortcuts.register(
+            root,
+            ctrl_s(),
+            ShortcutScope::Global,
+            OnShortcut::new(|_| {}),
+        );
+
+        let event = KeyboardEvent::new(KeyCode::S, ctrl_s().modifiers);
+        let (owner, _) = shortcuts.find(&event, Some(a), &tree).unwrap();
+        assert_eq!(a, owner);
+        let (owner, _) = shortcuts.find(&event, Some(b), &tree).unwrap();
+        assert_eq!(root, owner);
+    }
+
+    #[test]
+    fn should_remove_unmounted_owners() {
+        let (mut tree, _, a, _) = tree();
+        let mut shortcuts = Shortcuts::default();
+        shortcuts.register(a, ctrl_s(), ShortcutScope::Global, OnShortcut::new(|_| {}));
+        tree.remove(a);
+
+        let event = KeyboardEvent::new(KeyCode::S, ctrl_s().modifiers);
+        assert!(shortcuts.find(&event, None, &tree).is_none());
+        shortcuts.prune(&tree.flatten().into_iter().collect());
+        assert!(!shortcuts.unregister(a, ctrl_s()));
+    }
+}
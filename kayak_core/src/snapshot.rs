@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::multi_state::MultiState;
+use crate::tree::Tree;
+use crate::{Binding, Bound, Index, MutableBound};
+
+/// A serializable capture of widget state, created by
+/// [`KayakContext::snapshot_state`](crate::KayakContext::snapshot_state)
+///
+/// States are keyed by the path of their widget, which is made up of the widget's position amongst
+/// its siblings at each level of the tree (such as `/0/2`), followed by the name of the state's
+/// type. Each state type holds all of the widget's states of that type, in the order they were
+/// created.
+///
+/// # Limitations
+///
+/// * Only state types that have been registered via
+///   [`KayakContext::register_snapshot_state`](crate::KayakContext::register_snapshot_state)
+///   are captured. All other state is left as-is, so restoring a snapshot only rewinds part of
+///   the UI if it depends on any unregistered (or non-serializable) state.
+/// * Since states are matched by widget path, a snapshot can only be restored onto a tree with
+///   the same structure. States belonging to widgets that no longer exist are skipped.
+/// * Type names are not guaranteed to be stable across compiler versions, so snapshots are meant
+///   for debugging a single build rather than for long-term storage.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    widgets: BTreeMap<String, BTreeMap<String, Vec<serde_json::Value>>>,
+}
+
+impl StateSnapshot {
+    /// Returns true if this snapshot contains no state
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of widgets with captured state
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    pub(crate) fn insert(&mut self, path: String, type_name: &str, values: Vec<serde_json::Value>) {
+        self.widgets
+            .entry(path)
+            .or_default()
+            .insert(type_name.to_string(), values);
+    }
+
+    pub(crate) fn get(&self, path: &str, type_name: &str) -> Option<&Vec<serde_json::Value>> {
+        self.widgets.get(path)?.get(type_name)
+    }
+}
+
+/// A restore whose values have all been deserialized, which sets them when called
+///
+/// Returns the number of states restored.
+pub(crate) type PendingRestore = Box<dyn FnOnce() -> usize>;
+
+/// A type-erased state type that can be saved to and restored from a [`StateSnapshot`]
+pub(crate) trait SnapshotState: Send + Sync {
+    /// Serializes all states of this type
+    fn save(&self, states: &resources::Resources) -> Option<Vec<serde_json::Value>>;
+
+    /// Deserializes the values for all states of this type, without setting them yet
+    fn restore(
+        &self,
+        states: &resources::Resources,
+        values: &[serde_json::Value],
+    ) -> Result<PendingRestore, serde_json::Error>;
+}
+
+pub(crate) struct SnapshotStateType<T>(PhantomData<fn() -> T>);
+
+impl<T> SnapshotStateType<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> SnapshotState for SnapshotStateType<T>
+where
+    T: resources::Resource + Clone + PartialEq + Serialize + DeserializeOwned,
+{
+    fn save(&self, states: &resources::Resources) -> Option<Vec<serde_json::Value>> {
+        let states = states.get::<MultiState<Binding<T>>>().ok()?;
+        states
+            .data
+            .iter()
+            .map(|state| serde_json::to_value(state.get()))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+    }
+
+    fn restore(
+        &self,
+        states: &resources::Resources,
+        values: &[serde_json::Value],
+    ) -> Result<PendingRestore, serde_json::Error> {
+        let states = match states.get::<MultiState<Binding<T>>>() {
+            Ok(states) => states,
+            Err(..) => return Ok(Box::new(|| 0)),
+        };
+
+        let restored = states
+            .data
+            .iter()
+            .zip(values)
+            .map(|(state, value)| Ok((state.clone(), serde_json::from_value::<T>(value.clone())?)))
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        Ok(Box::new(move || {
+            let count = restored.len();
+            for (state, value) in restored {
+                state.set(value);
+            }
+            count
+        }))
+    }
+}
+
+/// Returns the path of the given widget, made up of its sibling index at each level of the tree
+pub(crate) fn widget_path(tree: &Tree, mut id: Index) -> String {
+    let mut segments = Vec::new();
+    while let Some(parent) = tree.get_parent(id) {
+        let index = tree
+            .children
+            .get(&parent)
+            .and_then(|children| children.iter().position(|child| *child == id))
+            .unwrap_or_default();
+        segments.push(index.to_string());
+        id = parent;
+    }
+
+    segments.reverse();
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::widget_path;
+    use crate::tree::Tree;
+    use crate::Index;
+
+    #[test]
+    fn path_should_use_sibling_indices() {
+        let root = Index::from_raw_parts(0, 0);
+        let a = Index::from_raw_parts(1, 0);
+        let b = Index::from_raw_parts(2, 0);
+        let c = Index::from_raw_parts(3, 0);
+        let mut tree = Tree::default();
+        tree.add(root, None);
+        tree.add(a, Some(root));
+        tree.add(b, Some(root));
+        tree.add(c, Some(b));
+
+        assert_eq!("/", widget_path(&tree, root));
+        assert_eq!("/1", widget_path(&tree, b));
+        assert_eq!("/1/0", widget_path(&tree, c));
+    }
+}
@@ -0,0 +1,146 @@
+/// The physical properties of a spring, for animating values with natural motion
+///
+/// Rather than taking a fixed duration, a value moved by a spring accelerates towards its target
+/// and settles once it has (nearly) stopped moving, carrying over any velocity it already had.
+/// This makes springs well suited to motion that continues from a drag or gesture.
+///
+/// The presets cover most uses. A stiffer spring moves faster, more damping reduces how much it
+/// overshoots and wobbles, and more mass makes it slower and more sluggish.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    /// How strongly the spring pulls the value towards its target
+    pub stiffness: f32,
+    /// How strongly the spring resists motion, which slows it down and settles it
+    pub damping: f32,
+    /// How heavy the value being moved is
+    pub mass: f32,
+}
+
+/// How close to its target (relative to the distance it traveled) a spring has to be to settle
+const REST_PRECISION: f32 = 0.001;
+
+impl Spring {
+    /// A balanced spring that settles quickly with barely any overshoot
+    pub const DEFAULT: Self = Self::new(170.0, 26.0, 1.0);
+    /// A soft spring that moves slowly and overshoots a little
+    pub const GENTLE: Self = Self::new(120.0, 14.0, 1.0);
+    /// A bouncy spring that overshoots and wobbles before settling
+    pub const WOBBLY: Self = Self::new(180.0, 12.0, 1.0);
+    /// A tight spring that moves fast and settles almost immediately
+    pub const STIFF: Self = Self::new(210.0, 20.0, 1.0);
+    /// A heavily damped spring that moves slowly without overshooting
+    pub const SLOW: Self = Self::new(280.0, 60.0, 1.0);
+
+    /// Creates a spring with the given stiffness, damping and mass
+    pub const fn new(stiffness: f32, damping: f32, mass: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass,
+        }
+    }
+
+    /// Returns the displacement from the target and the velocity of a value after the given time
+    ///
+    /// The value starts at the given displacement from its target, moving with the given velocity
+    /// (in units per second).
+    pub(crate) fn state(&self, displacement: f32, velocity: f32, seconds: f32) -> (f32, f32) {
+        let (x0, v0, t) = (displacement as f64, velocity as f64, seconds as f64);
+        let stiffness = self.stiffness.max(f32::EPSILON) as f64;
+        let mass = self.mass.max(f32::EPSILON) as f64;
+        let damping = self.damping.max(0.0) as f64;
+
+        let omega = (stiffness / mass).sqrt();
+        let zeta = damping / (2.0 * (stiffness * mass).sqrt());
+
+        let (x, v) = if zeta < 1.0 {
+            // Underdamped, so it oscillates around the target
+            let decay = zeta * omega;
+            let frequency = omega * (1.0 - zeta * zeta).sqrt();
+            let b = (v0 + decay * x0) / frequency;
+            let (sin, cos) = (frequency * t).sin_cos();
+            let envelope = (-decay * t).exp();
+            let x = envelope * (x0 * cos + b * sin);
+            let v = envelope * (frequency * (b * cos - x0 * sin)) - decay * x;
+            (x, v)
+        } else if zeta == 1.0 {
+            // Critically damped, so it moves to the target as fast as it can without overshooting
+            let c = v0 + omega * x0;
+            let envelope = (-omega * t).exp();
+            let x = envelope * (x0 + c * t);
+            let v = envelope * c - omega * x;
+            (x, v)
+        } else {
+            // Overdamped, so it creeps towards the target
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            let c1 = (v0 - r2 * x0) / (r1 - r2);
+            let c2 = x0 - c1;
+            let (e1, e2) = ((r1 * t).exp(), (r2 * t).exp());
+            (c1 * e1 + c2 * e2, c1 * r1 * e1 + c2 * r2 * e2)
+        };
+
+        (x as f32, v as f32)
+    }
+
+    /// Returns true if a value that started with the given displacement and velocity has settled
+    pub(crate) fn is_settled(&self, displacement: f32, velocity: f32, seconds: f32) -> bool {
+        let omega = (self.stiffness.max(f32::EPSILON) / self.mass.max(f32::EPSILON)).sqrt();
+        let precision =
+            ((displacement.abs() + velocity.abs() / omega) * REST_PRECISION).max(f32::EPSILON);
+        let (x, v) = self.state(displacement, velocity, seconds);
+        x.abs() <= precision && (v / omega).abs() <= precision
+    }
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Spring;
+
+    #[test]
+    fn should_settle_at_target() {
+        for spring in [
+            Spring::DEFAULT,
+            Spring::GENTLE,
+            Spring::WOBBLY,
+            Spring::STIFF,
+            Spring::SLOW,
+            // Critically damped
+            Spring::new(100.0, 20.0, 1.0),
+        ] {
+            let (x, v) = spring.state(100.0, 0.0, 0.0);
+            assert!((x - 100.0).abs() < 1e-4 && v.abs() < 1e-4);
+            assert!(!spring.is_settled(100.0, 0.0, 0.0));
+            assert!(spring.is_settled(100.0, 0.0, 10.0), "{:?}", spring);
+
+            let (x, _) = spring.state(100.0, 0.0, 10.0);
+            assert!(x.abs() < 0.1, "{:?}", spring);
+        }
+    }
+
+    #[test]
+    fn should_carry_velocity() {
+        // Released at the target while moving, so it swings past it before coming back
+        let spring = Spring::DEFAULT;
+        assert!(!spring.is_settled(0.0, 500.0, 0.0));
+        let (x, v) = spring.state(0.0, 500.0, 0.05);
+        assert!(x > 0.0);
+        assert!(v < 500.0);
+    }
+
+    #[test]
+    fn wobbly_springs_should_overshoot() {
+        let overshoots = |spring: Spring| {
+            (0..200).any(|frame| spring.state(-100.0, 0.0, frame as f32 / 100.0).0 > 1.0)
+        };
+        assert!(overshoots(Spring::WOBBLY));
+        assert!(!overshoots(Spring::SLOW));
+    }
+}
@@ -0,0 +1,197 @@
+//! Sizing of stack layouts to their content
+//!
+//! The children of a [`LayoutType::Stack`] are all self-directed, so the layout solver never sizes
+//! a stack to fit them. Instead, stacks with an `Auto` width or height are sized here to fit their
+//! largest child along that axis (plus any pixel padding), after which layout is calculated again.
+
+use std::collections::HashMap;
+
+use morphorm::{Node as LayoutNode, Units};
+
+use crate::flex::SIZE_EPSILON;
+use crate::layout_cache::LayoutCache;
+use crate::node::Node;
+use crate::styles::{LayoutType, StyleProp};
+use crate::{Arena, Index};
+
+/// Returns the value of the given units if they're in pixels, otherwise zero
+fn pixels(units: Option<Units>) -> f32 {
+    match units {
+        Some(Units::Pixels(value)) => value,
+        _ => 0.0,
+    }
+}
+
+/// Calculate the size of a stack along a single axis
+///
+/// Returns `None` if the stack isn't sized to its content along this axis or if none of its
+/// children contribute to its size.
+fn fit_axis(
+    id: Index,
+    child_ids: &[Index],
+    nodes: &Arena<Option<Node>>,
+    layout_cache: &LayoutCache,
+    horizontal: bool,
+) -> Option<f32> {
+    let size = if horizontal {
+        id.width(nodes)
+    } else {
+        id.height(nodes)
+    };
+    if size != Some(Units::Auto) {
+        return None;
+    }
+
+    let padding = if horizontal {
+        pixels(id.child_left(nodes)) + pixels(id.child_right(nodes))
+    } else {
+        pixels(id.child_top(nodes)) + pixels(id.child_bottom(nodes))
+    };
+
+    let largest = child_ids
+        .iter()
+        .filter(|child_id| matches!(nodes.get(**child_id), Some(Some(_))))
+        .filter_map(|child_id| {
+            let (size, start, end) = if horizontal {
+                (
+                    child_id.width(nodes),
+                    child_id.left(nodes),
+                    child_id.right(nodes),
+                )
+            } else {
+                (
+                    child_id.height(nodes),
+                    child_id.top(nodes),
+                    child_id.bottom(nodes),
+                )
+            };
+
+            // These depend on the size of the stack itself
+            if matches!(size, Some(Units::Stretch(..)) | Some(Units::Percentage(..))) {
+                return None;
+            }
+
+            let rect = layout_cache.rect.get(child_id)?;
+            let size = if horizontal { rect.width } else { rect.height };
+            Some(size + pixels(start) + pixels(end))
+        })
+        .fold(None, |largest: Option<f32>, size| {
+            Some(largest.map_or(size, |largest| largest.max(size)))
+        })?;
+
+    Some(largest + padding)
+}
+
+/// Calculate the sizes of all stacks that are sized to their content
+///
+/// Returns the overridden `(width, height)` for each stack whose size differs from the one given
+/// by the first layout pass.
+pub(crate) fn calculate_stack_sizes(
+    nodes: &Arena<Option<Node>>,
+    children: &HashMap<Index, Vec<Index>>,
+    layout_cache: &LayoutCache,
+) -> Vec<(Index, Option<f32>, Option<f32>)> {
+    let mut overrides = Vec::new();
+
+    for (parent_id, child_ids) in children {
+        let parent = match nodes.get(*parent_id) {
+            Some(Some(parent)) => parent,
+            _ => continue,
+        };
+        if !matches!(
+            parent.resolved_styles.layout_type,
+            StyleProp::Value(LayoutType::Stack)
+        ) {
+            continue;
+        }
+        let rect = match layout_cache.rect.get(parent_id) {
+            Some(rect) => rect,
+            None => continue,
+        };
+
+        let width = fit_axis(*parent_id, child_ids, nodes, layout_cache, true)
+            .filter(|width| (width - rect.width).abs() > SIZE_EPSILON);
+        let height = fit_axis(*parent_id, child_ids, nodes, layout_cache, false)
+            .filter(|height| (height - rect.height).abs() > SIZE_EPSILON);
+        if width.is_some() || height.is_some() {
+            overrides.push((*parent_id, width, height));
+        }
+    }
+
+    overrides
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::calculate_stack_sizes;
+    use crate::layout_cache::{LayoutCache, Rect};
+    use crate::node::NodeBuilder;
+    use crate::styles::{Edge, LayoutType, Style, StyleProp, Units};
+    use crate::Arena;
+
+    fn rect(width: f32, height: f32) -> Rect {
+        Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    #[test]
+    fn should_fit_largest_child() {
+        let mut nodes = Arena::new();
+        let stack = nodes.insert(None);
+        let badge = nodes.insert(None);
+        let avatar = nodes.insert(None);
+        let background = nodes.insert(None);
+
+        nodes[stack] = Some(
+            NodeBuilder::new(
+                stack,
+                Style {
+                    layout_type: StyleProp::Value(LayoutType::Stack),
+                    width: StyleProp::Value(Units::Auto),
+                    height: StyleProp::Value(Units::Pixels(100.0)),
+                    padding: StyleProp::Value(Edge::all(Units::Pixels(5.0))),
+                    ..Style::new_default()
+                },
+            )
+            .build(),
+        );
+        for (id, width) in [
+            (badge, Units::Pixels(10.0)),
+            (avatar, Units::Pixels(40.0)),
+            (background, Units::Stretch(1.0)),
+        ] {
+            let mut node = NodeBuilder::new(
+                id,
+                Style {
+                    width: StyleProp::Value(width),
+                    ..Style::new_default()
+                },
+            )
+            .build();
+            node.is_stacked = true;
+            nodes[id] = Some(node);
+        }
+
+        let mut layout_cache = LayoutCache::default();
+        layout_cache.rect.insert(stack, rect(0.0, 100.0));
+        layout_cache.rect.insert(badge, rect(10.0, 10.0));
+        layout_cache.rect.insert(avatar, rect(40.0, 40.0));
+        layout_cache.rect.insert(background, rect(500.0, 100.0));
+
+        let mut children = HashMap::new();
+        children.insert(stack, vec![badge, avatar, background]);
+
+        // The background stretches to fill the stack, so only the avatar counts
+        assert_eq!(
+            vec![(stack, Some(50.0), None)],
+            calculate_stack_sizes(&nodes, &children, &layout_cache)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Pinning of [sticky](crate::styles::Position::Sticky) widgets within their scroll container
+//!
+//! Sticky widgets are laid out like relative widgets. Afterwards, each one that has been scrolled
+//! past the top edge of its scroll container (its nearest clipping ancestor) is moved back down to
+//! its `top` offset below that edge, without ever leaving its parent. Since this runs after every
+//! layout, the pinned position always follows the container's current scroll position.
+//!
+//! Sticky siblings stack: a widget is never pinned above the bottom edge of the sticky siblings
+//! before it, so several header rows pin one below the other instead of on top of each other.
+
+use crate::layout_cache::{LayoutCache, Rect};
+use crate::node::Node;
+use crate::render_command::RenderCommand;
+use crate::tree::Tree;
+use crate::{flex, Arena, Index};
+
+/// Returns true if the given node is positioned stickily
+pub(crate) fn is_sticky(id: Index, nodes: &Arena<Option<Node>>) -> bool {
+    matches!(nodes.get(id), Some(Some(node)) if node.sticky_top().is_some())
+}
+
+/// Moves all sticky nodes to their pinned positions
+///
+/// Parents are visited before their children, so sticky nodes nested in other sticky nodes are
+/// pinned relative to their parent's pinned position.
+pub(crate) fn apply_sticky_offsets(
+    nodes: &Arena<Option<Node>>,
+    node_tree: &Tree,
+    layout_cache: &mut LayoutCache,
+) {
+    let parent_ids = match node_tree.root_node {
+        Some(root) => node_tree.flatten_node(root),
+        None => return,
+    };
+
+    for parent_id in parent_ids {
+        let child_ids = match node_tree.children.get(&parent_id) {
+            Some(child_ids) => child_ids,
+            None => continue,
+        };
+
+        // The bottom edge of the last sticky sibling, which the next one is pinned below
+        let mut stacked_end = None;
+        for child_id in child_ids {
+            let top = match nodes.get(*child_id) {
+                Some(Some(node)) => match node.sticky_top() {
+                    Some(top) => top,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let (rect, parent) = match (
+                layout_cache.rect.get(child_id),
+                layout_cache.rect.get(&parent_id),
+            ) {
+                (Some(rect), Some(parent)) => (*rect, *parent),
+                _ => continue,
+            };
+            let container = match scroll_container(parent_id, nodes, node_tree, layout_cache) {
+                Some(container) => container,
+                None => continue,
+            };
+
+            let posy = pinned_position(
+                rect.posy,
+                rect.height,
+                container.posy + top,
+                parent.posy + parent.height,
+                stacked_end,
+            );
+            flex::translate(*child_id, 0.0, posy - rect.posy, node_tree, layout_cache);
+            stacked_end = Some(posy + rect.height);
+        }
+    }
+}
+
+/// Finds the rect of the scroll container of the children of the given node
+///
+/// This is the node itself or its nearest ancestor that clips its content.
+fn scroll_container(
+    id: Index,
+    nodes: &Arena<Option<Node>>,
+    node_tree: &Tree,
+    layout_cache: &LayoutCache,
+) -> Option<Rect> {
+    let mut current = Some(id);
+    while let Some(id) = current {
+        if let Some(Some(node)) = nodes.get(id) {
+            if node.resolved_styles.render_command.resolve() == RenderCommand::Clip {
+                return layout_cache.rect.get(&id).copied();
+            }
+        }
+        current = node_tree.get_parent(id);
+    }
+    None
+}
+
+/// Calculates the vertical position of a sticky widget
+///
+/// # Arguments
+///
+/// * `natural`: The position of the widget within its parent's flow
+/// * `size`: The height of the widget
+/// * `pin_at`: The position the widget is pinned at once scrolled past it
+/// * `parent_end`: The bottom edge of the widget's parent, which the widget must stay above
+/// * `stacked_end`: The bottom edge of the previous sticky sibling, if any
+///
+fn pinned_position(
+    natural: f32,
+    size: f32,
+    pin_at: f32,
+    parent_end: f32,
+    stacked_end: Option<f32>,
+) -> f32 {
+    let pin_at = stacked_end.map_or(pin_at, |stacked_end| pin_at.max(stacked_end));
+    // Widgets are never moved above their place in the flow, even if they overflow their parent
+    let max = (parent_end - size).max(natural);
+    natural.max(pin_at).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pinned_position;
+
+    #[test]
+    fn should_pin_once_scrolled_past() {
+        // Not yet scrolled to the top of the container
+        assert_eq!(50.0, pinned_position(50.0, 20.0, 10.0, 500.0, None));
+        // Scrolled past the top, so it's pinned to the offset
+        assert_eq!(10.0, pinned_position(-30.0, 20.0, 10.0, 500.0, None));
+    }
+
+    #[test]
+    fn should_stay_within_parent() {
+        // The parent is scrolling out of view, taking the widget with it
+        assert_eq!(5.0, pinned_position(-300.0, 20.0, 10.0, 25.0, None));
+        // Widgets overflowing their parent are left in place
+        assert_eq!(-30.0, pinned_position(-30.0, 20.0, -40.0, -20.0, None));
+    }
+
+    #[test]
+    fn should_stack_below_sticky_siblings() {
+        assert_eq!(30.0, pinned_position(-10.0, 20.0, 10.0, 500.0, Some(30.0)));
+        // Siblings that haven't been pinned yet don't push the widget down
+        assert_eq!(80.0, pinned_position(80.0, 20.0, 10.0, 500.0, Some(60.0)));
+    }
+}
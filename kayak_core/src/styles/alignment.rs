@@ -0,0 +1,78 @@
+/// How the children of a row or column are distributed along its main axis
+///
+/// These follow the CSS flexbox values of the same names. Space is only distributed if the
+/// children don't already fill the parent (such as when none of them stretch).
+///
+/// The default is [`JustifyContent::Start`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum JustifyContent {
+    /// Children are packed towards the start
+    Start,
+    /// Children are packed around the center
+    Center,
+    /// Children are packed towards the end
+    End,
+    /// The first and last children are placed at the edges, with the free space split evenly
+    /// between the others
+    ///
+    /// Falls back to [`JustifyContent::Start`] if there's only one child or the children overflow.
+    SpaceBetween,
+    /// Each child gets an equal amount of space on either side, meaning the space at the edges is
+    /// half of the space between children
+    ///
+    /// Falls back to [`JustifyContent::Center`] if the children overflow.
+    SpaceAround,
+    /// The space at the edges and between all children is equal
+    ///
+    /// Falls back to [`JustifyContent::Center`] if the children overflow.
+    SpaceEvenly,
+}
+
+impl Default for JustifyContent {
+    fn default() -> Self {
+        Self::Start
+    }
+}
+
+/// How the children of a row or column are aligned along its cross axis
+///
+/// These follow the CSS flexbox values of the same names.
+///
+/// The default is [`AlignItems::Stretch`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum AlignItems {
+    /// Children are aligned to the start
+    Start,
+    /// Children are centered
+    Center,
+    /// Children are aligned to the end
+    End,
+    /// Children with an `Auto` size fill the parent, while all others are aligned to the start
+    Stretch,
+}
+
+impl Default for AlignItems {
+    fn default() -> Self {
+        Self::Stretch
+    }
+}
@@ -0,0 +1,28 @@
+/// How the border along one side of a widget is drawn
+///
+/// The default is [`BorderStyle::Solid`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum BorderStyle {
+    /// The border is a single solid line
+    Solid,
+    /// The border is a series of dashes, each twice as long as the border is wide
+    Dashed,
+    /// The border is a series of square dots, each as long as the border is wide
+    Dotted,
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
@@ -0,0 +1,104 @@
+use morphorm::Units;
+
+/// Controls what the width and height of a widget refer to
+///
+/// The default is [`BoxSizing::BorderBox`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum BoxSizing {
+    /// The width and height only include the content of the widget, meaning that padding
+    /// and borders are added on top of the specified size
+    ///
+    /// Only sizes and insets given in [`Units::Pixels`] can be combined, any other units
+    /// are left as-is.
+    ContentBox,
+    /// The width and height include the padding and borders of the widget
+    BorderBox,
+}
+
+impl Default for BoxSizing {
+    fn default() -> Self {
+        Self::BorderBox
+    }
+}
+
+impl BoxSizing {
+    /// Resolve the outer size of a widget along a single axis
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The specified size of the widget
+    /// * `insets`: The padding and border sizes along the same axis
+    ///
+    pub fn resolve(&self, size: Units, insets: &[Option<Units>]) -> Units {
+        match (self, size) {
+            (Self::ContentBox, Units::Pixels(size)) => {
+                let insets: f32 = insets
+                    .iter()
+                    .map(|inset| match inset {
+                        Some(Units::Pixels(value)) => *value,
+                        _ => 0.0,
+                    })
+                    .sum();
+                Units::Pixels(size + insets)
+            }
+            _ => size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoxSizing;
+    use morphorm::Units;
+
+    #[test]
+    fn border_box_should_include_insets() {
+        let insets = [Some(Units::Pixels(10.0)), Some(Units::Pixels(5.0))];
+        assert_eq!(
+            Units::Pixels(100.0),
+            BoxSizing::BorderBox.resolve(Units::Pixels(100.0), &insets)
+        );
+    }
+
+    #[test]
+    fn content_box_should_add_insets() {
+        let insets = [
+            Some(Units::Pixels(10.0)),
+            Some(Units::Pixels(5.0)),
+            Some(Units::Pixels(1.0)),
+            Some(Units::Pixels(1.0)),
+        ];
+        assert_eq!(
+            Units::Pixels(117.0),
+            BoxSizing::ContentBox.resolve(Units::Pixels(100.0), &insets)
+        );
+    }
+
+    #[test]
+    fn content_box_should_ignore_non_pixel_units() {
+        let insets = [Some(Units::Percentage(10.0)), Some(Units::Auto), None];
+        assert_eq!(
+            Units::Pixels(100.0),
+            BoxSizing::ContentBox.resolve(Units::Pixels(100.0), &insets)
+        );
+        assert_eq!(
+            Units::Stretch(1.0),
+            BoxSizing::ContentBox.resolve(Units::Stretch(1.0), &[Some(Units::Pixels(10.0))])
+        );
+    }
+
+    #[test]
+    fn default_should_be_border_box() {
+        assert_eq!(BoxSizing::BorderBox, BoxSizing::default());
+    }
+}
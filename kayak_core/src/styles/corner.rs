@@ -4,6 +4,7 @@ use std::ops::{Mul, MulAssign};
 ///
 /// This is useful for things like border radii, etc.
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Corner<T>
 where
     T: Copy + Default + PartialEq,
@@ -4,6 +4,7 @@ use std::ops::{Mul, MulAssign};
 ///
 /// This is useful for things like borders, padding, etc.
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge<T>
 where
     T: Copy + Default + PartialEq,
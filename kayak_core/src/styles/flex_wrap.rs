@@ -0,0 +1,31 @@
+/// Whether the children of a row or column are allowed to wrap onto multiple lines
+///
+/// The default is [`FlexWrap::NoWrap`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FlexWrap {
+    /// Children are placed on a single line, even if they overflow
+    NoWrap,
+    /// Children that would overflow the main axis are moved onto a new line
+    ///
+    /// Lines are separated by the spacing between children along the cross axis (i.e. the
+    /// [`row_between`](crate::styles::Style::row_between) of a row). A widget with an `Auto`
+    /// cross size grows to fit all of its lines, and children that stretch along the cross axis
+    /// fill their line rather than the whole widget.
+    Wrap,
+}
+
+impl Default for FlexWrap {
+    fn default() -> Self {
+        Self::NoWrap
+    }
+}
@@ -0,0 +1,202 @@
+use std::f32::consts::TAU;
+
+use super::{BorderStyle, Edge};
+use crate::border::{self, BorderQuad};
+use crate::color::Color;
+use crate::layout_cache::Rect;
+
+/// The outline drawn around a widget while it has focus
+///
+/// The outline is drawn above the rest of the UI, so it isn't covered by the widget's siblings,
+/// but it is still clipped by the widget's ancestors. It follows the widget's layout, so it moves
+/// along with it (such as while its window is being dragged).
+///
+/// The default is a solid, blue outline, 2 pixels wide and 2 pixels away from the widget. To stop
+/// a widget from showing an outline, set it to [`FocusOutline::NONE`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FocusOutline {
+    /// The color of the outline
+    pub color: Color,
+    /// The width of the outline, in pixels
+    ///
+    /// An outline with no width isn't drawn.
+    pub width: f32,
+    /// The gap between the edges of the widget and the outline, in pixels
+    ///
+    /// A negative offset draws the outline within the widget.
+    pub offset: f32,
+    /// How the outline is drawn
+    pub style: BorderStyle,
+    /// How the outline is animated
+    pub animation: FocusAnimation,
+}
+
+impl FocusOutline {
+    /// An outline that isn't drawn at all
+    pub const NONE: FocusOutline = FocusOutline {
+        color: Color::TRANSPARENT,
+        width: 0.0,
+        offset: 0.0,
+        style: BorderStyle::Solid,
+        animation: FocusAnimation::None,
+    };
+
+    /// Returns true if this outline is drawn
+    pub fn is_visible(&self) -> bool {
+        self.width > 0.0 && self.color.a > 0.0
+    }
+
+    /// Calculate the quads making up this outline around the given layout
+    ///
+    /// # Arguments
+    ///
+    /// * `layout`: The layout of the focused widget
+    /// * `elapsed`: The time since the widget was focused, in seconds
+    ///
+    pub(crate) fn quads(&self, layout: &Rect, elapsed: f32) -> Vec<BorderQuad> {
+        if !self.is_visible() {
+            return Vec::new();
+        }
+
+        let spread = self.offset + self.width;
+        let outline = Rect {
+            posx: layout.posx - spread,
+            posy: layout.posy - spread,
+            width: layout.width + spread * 2.0,
+            height: layout.height + spread * 2.0,
+            z_index: layout.z_index,
+        };
+        if outline.width <= 0.0 || outline.height <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut color = self.color;
+        let widths = Edge::all(self.width);
+        let styles = Edge::all(self.style);
+        match self.animation {
+            FocusAnimation::None => {
+                border::side_quads(&outline, &widths, &Edge::all(color), &styles)
+            }
+            FocusAnimation::Pulse { period } => {
+                if period > 0.0 {
+                    color.a *= 1.0 - 0.3 * (1.0 - (TAU * elapsed / period).cos());
+                }
+                border::side_quads(&outline, &widths, &Edge::all(color), &styles)
+            }
+            FocusAnimation::March { speed } => border::marching_side_quads(
+                &outline,
+                &widths,
+                &Edge::all(color),
+                &styles,
+                speed * elapsed,
+            ),
+        }
+    }
+}
+
+impl Default for FocusOutline {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0.26, 0.52, 0.96, 1.0),
+            width: 2.0,
+            offset: 2.0,
+            style: BorderStyle::Solid,
+            animation: FocusAnimation::None,
+        }
+    }
+}
+
+/// How a [`FocusOutline`] is animated
+///
+/// The animation starts over whenever focus moves to another widget.
+///
+/// The default is [`FocusAnimation::None`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FocusAnimation {
+    /// The outline isn't animated
+    None,
+    /// The outline fades in and out, taking `period` seconds to do so
+    Pulse { period: f32 },
+    /// The dashes (or dots) of the outline move clockwise around the widget, at `speed` pixels per
+    /// second
+    ///
+    /// This has no effect on a [solid](BorderStyle::Solid) outline.
+    March { speed: f32 },
+}
+
+impl FocusAnimation {
+    /// Returns true if this animation changes over time
+    pub fn is_animated(&self) -> bool {
+        match self {
+            FocusAnimation::None => false,
+            FocusAnimation::Pulse { period } => *period > 0.0,
+            FocusAnimation::March { speed } => *speed != 0.0,
+        }
+    }
+}
+
+impl Default for FocusAnimation {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FocusAnimation, FocusOutline};
+    use crate::layout_cache::Rect;
+
+    #[test]
+    fn should_surround_the_widget() {
+        let layout = Rect {
+            posx: 10.0,
+            posy: 20.0,
+            width: 100.0,
+            height: 50.0,
+            z_index: 1.0,
+        };
+        let quads = FocusOutline::default().quads(&layout, 0.0);
+        assert_eq!(4, quads.len());
+
+        let left = quads[0].layout;
+        assert_eq!(
+            (6.0, 16.0, 2.0, 58.0),
+            (left.posx, left.posy, left.width, left.height)
+        );
+
+        assert!(FocusOutline::NONE.quads(&layout, 0.0).is_empty());
+    }
+
+    #[test]
+    fn pulse_should_start_fully_visible() {
+        let outline = FocusOutline {
+            animation: FocusAnimation::Pulse { period: 1.0 },
+            ..Default::default()
+        };
+        let layout = Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: 10.0,
+            height: 10.0,
+            z_index: 0.0,
+        };
+        assert_eq!(1.0, outline.quads(&layout, 0.0)[0].color.a);
+        assert!(outline.quads(&layout, 0.5)[0].color.a < 0.5);
+    }
+}
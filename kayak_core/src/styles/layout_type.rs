@@ -0,0 +1,123 @@
+/// The layout method used for the children of a widget
+///
+/// The default is [`LayoutType::Column`].
+///
+/// This replaces the `LayoutType` that used to be re-exported from `morphorm`. Code that still
+/// works with the `morphorm` type can convert between the two with [`From`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LayoutType {
+    /// Children are placed side-by-side, from left to right
+    Row,
+    /// Children are placed one after another, from top to bottom
+    Column,
+    /// Children are placed in a grid
+    Grid,
+    /// Children are placed on top of each other, all within the same rect
+    ///
+    /// Each child is placed according to its [`stack_alignment`](crate::styles::Style::stack_alignment)
+    /// (unless given its own offsets), with later children drawn over earlier ones. A stack with an
+    /// `Auto` width or height is sized to fit its largest child along that axis, not counting
+    /// children that stretch to fill it.
+    Stack,
+}
+
+impl Default for LayoutType {
+    fn default() -> Self {
+        Self::Column
+    }
+}
+
+impl From<LayoutType> for morphorm::LayoutType {
+    fn from(layout_type: LayoutType) -> Self {
+        match layout_type {
+            LayoutType::Row => morphorm::LayoutType::Row,
+            LayoutType::Column => morphorm::LayoutType::Column,
+            LayoutType::Grid => morphorm::LayoutType::Grid,
+            // The children of a stack are all self-directed, so the main axis doesn't matter
+            LayoutType::Stack => morphorm::LayoutType::Column,
+        }
+    }
+}
+
+impl From<morphorm::LayoutType> for LayoutType {
+    fn from(layout_type: morphorm::LayoutType) -> Self {
+        match layout_type {
+            morphorm::LayoutType::Row => LayoutType::Row,
+            morphorm::LayoutType::Column => LayoutType::Column,
+            morphorm::LayoutType::Grid => LayoutType::Grid,
+        }
+    }
+}
+
+/// Where a widget is placed within a parent with a layout type of [`LayoutType::Stack`]
+///
+/// Alignment only applies along an axis where the widget doesn't stretch to fill the stack,
+/// so a widget should be given a size for its alignment to take effect.
+///
+/// The default is [`StackAlignment::TopLeft`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum StackAlignment {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Default for StackAlignment {
+    fn default() -> Self {
+        Self::TopLeft
+    }
+}
+
+/// The alignment of a widget along a single axis
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum AxisAlignment {
+    Start,
+    Center,
+    End,
+}
+
+impl StackAlignment {
+    /// The alignment along the horizontal axis
+    pub(crate) fn horizontal(&self) -> AxisAlignment {
+        match self {
+            Self::TopLeft | Self::Left | Self::BottomLeft => AxisAlignment::Start,
+            Self::Top | Self::Center | Self::Bottom => AxisAlignment::Center,
+            Self::TopRight | Self::Right | Self::BottomRight => AxisAlignment::End,
+        }
+    }
+
+    /// The alignment along the vertical axis
+    pub(crate) fn vertical(&self) -> AxisAlignment {
+        match self {
+            Self::TopLeft | Self::Top | Self::TopRight => AxisAlignment::Start,
+            Self::Left | Self::Center | Self::Right => AxisAlignment::Center,
+            Self::BottomLeft | Self::Bottom | Self::BottomRight => AxisAlignment::End,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+/// Builds a [`Style`](crate::styles::Style) from a list of fields
+///
+/// Each listed field is set to [`StyleProp::Value`](crate::styles::StyleProp::Value), converting
+/// the given value with [`Into`]. Tuples are converted element by element first, so edges and
+/// corners can be written as plain numbers. All other fields are left as
+/// [`StyleProp::Unset`](crate::styles::StyleProp::Unset), or taken from a base style given with
+/// `..base` at the end.
+///
+/// Since this expands to a struct expression, unknown fields and mismatched values are caught at
+/// compile time.
+///
+/// ```ignore
+/// let base = styles! {
+///     color: Color::WHITE,
+///     font_size: 14.0,
+/// };
+///
+/// let panel = styles! {
+///     width: 100.0,
+///     background_color: Color::new(0.2, 0.2, 0.2, 1.0),
+///     padding: (8.0, 8.0, 8.0, 8.0),
+///     ..base
+/// };
+/// ```
+#[macro_export]
+macro_rules! styles {
+    (@fields [$($fields:tt)*]) => {
+        $crate::styles::Style {
+            $($fields)*
+            ..::std::default::Default::default()
+        }
+    };
+    (@fields [$($fields:tt)*] .. $base:expr) => {
+        $crate::styles::Style {
+            $($fields)*
+            ..$base
+        }
+    };
+    (@fields [$($fields:tt)*] $field:ident : ($first:expr, $($rest:expr),+ $(,)?) $(, $($tail:tt)*)?) => {
+        $crate::styles!(@fields [
+            $($fields)*
+            $field: $crate::styles::StyleProp::Value(::std::convert::Into::into((
+                ::std::convert::Into::into($first),
+                $(::std::convert::Into::into($rest)),+
+            ))),
+        ] $($($tail)*)?)
+    };
+    (@fields [$($fields:tt)*] $field:ident : $value:expr $(, $($tail:tt)*)?) => {
+        $crate::styles!(@fields [
+            $($fields)*
+            $field: $crate::styles::StyleProp::Value(::std::convert::Into::into($value)),
+        ] $($($tail)*)?)
+    };
+    ($($tokens:tt)*) => {
+        $crate::styles!(@fields [] $($tokens)*)
+    };
+}
+
+/// Selects a [`Style`](crate::styles::Style) based on a condition
+///
+/// With two arguments, this returns the given style if the condition is true and an empty style
+/// otherwise. Since an empty style leaves every field [unset](crate::styles::StyleProp::Unset),
+/// it can be applied over a base style with [`Style::with_overrides`](crate::styles::Style::with_overrides)
+/// to only change the given fields while the condition holds.
+///
+/// With three arguments, this returns the second one if the condition is true and the third one
+/// otherwise.
+///
+/// ```ignore
+/// let (is_hovered, set_is_hovered, ..) = use_state!(false);
+///
+/// let button_styles = styles! {
+///     width: 100.0,
+///     background_color: Color::BLACK,
+/// }
+/// .with_overrides(style_if!(is_hovered, styles! {
+///     background_color: Color::new(0.2, 0.2, 0.2, 1.0),
+/// }));
+///
+/// let text_styles = style_if!(is_hovered, hovered_text_styles, text_styles);
+/// ```
+#[macro_export]
+macro_rules! style_if {
+    ($condition:expr, $when_true:expr $(,)?) => {
+        $crate::style_if!($condition, $when_true, $crate::styles::Style::default())
+    };
+    ($condition:expr, $when_true:expr, $when_false:expr $(,)?) => {
+        if $condition {
+            $when_true
+        } else {
+            $when_false
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::styles::{Edge, Style, StyleProp, Units};
+    use crate::Color;
+
+    #[test]
+    fn should_set_listed_fields() {
+        let style = styles! {
+            width: 100.0,
+            height: Units::Stretch(1.0),
+            background_color: Color::BLACK,
+            padding: (8.0, 4.0),
+        };
+
+        assert_eq!(
+            Style {
+                width: StyleProp::Value(Units::Pixels(100.0)),
+                height: StyleProp::Value(Units::Stretch(1.0)),
+                background_color: StyleProp::Value(Color::BLACK),
+                padding: StyleProp::Value(Edge::axis(Units::Pixels(8.0), Units::Pixels(4.0))),
+                ..Default::default()
+            },
+            style
+        );
+    }
+
+    #[test]
+    fn should_extend_base_style() {
+        let base = styles! {
+            width: 100.0,
+            color: Color::WHITE,
+        };
+        let style = styles! {
+            width: 50.0,
+            ..base.clone()
+        };
+
+        assert_eq!(StyleProp::Value(Units::Pixels(50.0)), style.width);
+        assert_eq!(base.color, style.color);
+        assert_eq!(StyleProp::Unset, style.height);
+    }
+
+    #[test]
+    fn overrides_should_leave_unset_fields_untouched() {
+        let base = styles! {
+            width: 100.0,
+            color: Color::WHITE,
+        };
+
+        let hovered = base.clone().with_overrides(style_if!(
+            true,
+            styles! {
+                color: Color::BLACK,
+            }
+        ));
+        assert_eq!(base.width, hovered.width);
+        assert_eq!(StyleProp::Value(Color::BLACK), hovered.color);
+
+        let idle = base.clone().with_overrides(style_if!(
+            false,
+            styles! {
+                color: Color::BLACK,
+            }
+        ));
+        assert_eq!(base, idle);
+    }
+}
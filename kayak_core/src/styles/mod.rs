@@ -1,14 +1,47 @@
 //! Contains code related to the styling of widgets
 
+mod alignment;
+mod border_style;
+mod box_sizing;
 mod corner;
 mod edge;
+mod flex_wrap;
+mod focus_outline;
+mod layout_type;
+mod macros;
 mod option_ref;
+mod position;
+#[cfg(feature = "reflect")]
+mod reflect;
+#[cfg(feature = "serde")]
+mod serialization;
+mod text_effects;
+mod text_transform;
+mod transform;
+mod units;
+mod visibility;
 
 use std::ops::Add;
 
+#[cfg(feature = "reflect")]
+use bevy::reflect::Reflect;
+
+pub use alignment::{AlignItems, JustifyContent};
+pub use border_style::BorderStyle;
+pub use box_sizing::BoxSizing;
 pub use corner::Corner;
 pub use edge::Edge;
-pub use morphorm::{LayoutType, PositionType, Units};
+pub use flex_wrap::FlexWrap;
+pub use focus_outline::{FocusAnimation, FocusOutline};
+pub(crate) use layout_type::AxisAlignment;
+pub use layout_type::{LayoutType, StackAlignment};
+pub use morphorm::PositionType;
+pub use position::Position;
+pub use text_effects::{TextOutline, TextShadow};
+pub use text_transform::TextTransform;
+pub use transform::Transform;
+pub use units::Units;
+pub use visibility::Visibility;
 
 use crate::cursor::PointerEvents;
 use crate::{color::Color, render_command::RenderCommand, CursorIcon};
@@ -47,6 +80,13 @@ impl<T> StyleProp<T>
 where
     T: Default + Clone,
 {
+    /// Returns true if this property was explicitly set (meaning it isn't [`StyleProp::Unset`])
+    ///
+    /// Set properties take precedence when [merging](Style::merge) styles.
+    pub fn is_set(&self) -> bool {
+        !matches!(self, StyleProp::Unset)
+    }
+
     /// Resolves this style property into a concrete value.
     ///
     /// # Panics
@@ -159,6 +199,17 @@ macro_rules! define_styles {
                 }
             }
 
+            /// Returns the names of all fields that are explicitly set (see [`StyleProp::is_set`])
+            pub fn set_fields(&self) -> Vec<&'static str> {
+                let mut fields = Vec::new();
+                $(
+                    if self.$field.is_set() {
+                        fields.push(stringify!($field));
+                    }
+                )*
+                fields
+            }
+
             /// If any field is set to [`StyleProp::Inherit`], its value will be taken from `other`
             pub fn inherit(&mut self, other: &Self) {
                  $(
@@ -189,6 +240,62 @@ macro_rules! define_styles {
                 self
             }
         }
+
+        /// Serializes all fields that are not [`StyleProp::Unset`]
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use ::serde::ser::SerializeStruct;
+
+                let mut len = 0;
+                $(
+                    if !matches!(self.$field, StyleProp::Unset) {
+                        len += 1;
+                    }
+                )*
+
+                let mut state = serializer.serialize_struct(stringify!($name), len)?;
+                $(
+                    if matches!(self.$field, StyleProp::Unset) {
+                        state.skip_field(stringify!($field))?;
+                    } else {
+                        state.serialize_field(stringify!($field), &self.$field)?;
+                    }
+                )*
+                state.end()
+            }
+        }
+
+        /// Deserializes the given fields, leaving all others as [`StyleProp::Unset`]
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                const FIELDS: &[&str] = &[$(stringify!($field)),*];
+
+                struct StyleVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for StyleVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str(concat!("struct ", stringify!($name)))
+                    }
+
+                    fn visit_map<A: ::serde::de::MapAccess<'de>>(self, mut map: A) -> Result<$name, A::Error> {
+                        let mut value = $name::default();
+                        while let Some(key) = map.next_key::<String>()? {
+                            match key.as_str() {
+                                $(stringify!($field) => value.$field = map.next_value()?,)*
+                                _ => return Err(::serde::de::Error::unknown_field(&key, FIELDS)),
+                            }
+                        }
+                        Ok(value)
+                    }
+                }
+
+                deserializer.deserialize_struct(stringify!($name), FIELDS, StyleVisitor)
+            }
+        }
     };
 }
 
@@ -229,18 +336,38 @@ define_styles! {
     ///   // Applied second (sets any remaining `StyleProp::Unset` fields)
     ///   .with_style(&style_b);
     /// ```
+    ///
+    /// With the `reflect` feature enabled, `Style` implements Bevy's `Reflect` trait so it can be
+    /// edited in inspectors. Each property is reflected as a struct with a single field named after
+    /// its variant (see the `reflect` module for details).
+    ///
+    /// With the `serde` feature enabled, `Style` can be serialized and deserialized. Only the
+    /// properties that are set are written out (see the `serialization` module for the format).
     #[derive(Debug, Default, Clone, PartialEq)]
+    #[cfg_attr(feature = "reflect", derive(bevy::reflect::Reflect))]
     pub struct Style {
+        /// How the children of this widget are aligned along its cross axis
+        ///
+        /// Only applies to widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Row`]
+        /// or [`LayoutType::Column`]. If unset, children are positioned by their own offsets.
+        pub align_items: StyleProp<AlignItems>,
         /// The background color of this widget
         ///
         /// Only applies to widgets marked [`RenderCommand::Quad`]
         pub background_color : StyleProp<Color>,
         /// The color of the border around this widget
         ///
-        /// Currently, this controls all border sides.
+        /// This controls all border sides, unless overridden by [`border_colors`](Self::border_colors).
         ///
         /// Only applies to widgets marked [`RenderCommand::Quad`]
         pub border_color: StyleProp<Color>,
+        /// The colors of each side of the border around this widget
+        ///
+        /// The order is (Top, Right, Bottom, Left). If set, this takes precedence over
+        /// [`border_color`](Self::border_color).
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_colors: StyleProp<Edge<Color>>,
         /// The radius of the corners (in pixels)
         ///
         /// The order is (Top, Right, Bottom, Left).
@@ -253,8 +380,20 @@ define_styles! {
         ///
         /// Only applies to widgets marked [`RenderCommand::Quad`]
         pub border: StyleProp<Edge<f32>>,
+        /// How each side of the border around this widget is drawn
+        ///
+        /// The order is (Top, Right, Bottom, Left).
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Quad`]
+        pub border_style: StyleProp<Edge<BorderStyle>>,
         /// The distance between the bottom edge of this widget and the bottom edge of its containing widget
         pub bottom: StyleProp<Units>,
+        /// Controls whether the [`width`](Self::width) and [`height`](Self::height) of this widget
+        /// include its padding and border
+        ///
+        /// This defaults to [`BoxSizing::BorderBox`], meaning padding and borders are contained
+        /// _within_ the specified size.
+        pub box_sizing: StyleProp<BoxSizing>,
         /// The text color for this widget
         ///
         /// This property defaults to [`StyleProp::Inherit`] meaning that setting this field to some value will
@@ -266,20 +405,52 @@ define_styles! {
         pub col_between: StyleProp<Units>,
         /// The cursor icon to display when hovering this widget
         pub cursor: StyleProp<CursorIcon>,
+        /// Whether the children of this widget wrap onto multiple lines
+        ///
+        /// Only applies to widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Row`]
+        /// or [`LayoutType::Column`].
+        pub flex_wrap: StyleProp<FlexWrap>,
+        /// The outline drawn around this widget while it has focus
+        ///
+        /// This property defaults to [`StyleProp::Inherit`], so setting it on a widget themes the
+        /// focus outline of all its descendants, up to the next set value. Set it to
+        /// [`FocusOutline::NONE`] to hide the outline (such as for a widget that draws its own).
+        pub focus_outline: StyleProp<FocusOutline>,
         /// The font name for this widget
         ///
         /// Only applies to [`RenderCommand::Text`]
         pub font: StyleProp<String>,
         /// The font size for this widget, in pixels
         ///
+        /// This property defaults to [`StyleProp::Inherit`], falling back to the root font size
+        /// if no ancestor sets it. It is also what [`Units::Em`] is relative to.
+        ///
         /// Only applies to [`RenderCommand::Text`]
         pub font_size: StyleProp<f32>,
         /// The height of this widget
         pub height: StyleProp<Units>,
+        /// How the children of this widget are distributed along its main axis
+        ///
+        /// Only applies to widgets with a [`layout_type`](Self::layout_type) of [`LayoutType::Row`]
+        /// or [`LayoutType::Column`]. If unset, children are positioned by their own offsets.
+        pub justify_content: StyleProp<JustifyContent>,
+        /// The name of the [layer](crate::portal) this widget and its descendants are drawn into
+        ///
+        /// Widgets in a layer are drawn (and hit) above all widgets in lower layers, without having
+        /// to set their z-index. If unset (or set to [`portal::CONTENT`](crate::portal::CONTENT)),
+        /// the widget is drawn along with its parent.
+        pub layer: StyleProp<String>,
         /// The layout method for children of this widget
         pub layout_type: StyleProp<LayoutType>,
         /// The distance between the left edge of this widget and the left edge of its containing widget
         pub left: StyleProp<Units>,
+        /// The extra space added after each character of this widget's text, in pixels
+        ///
+        /// Negative values tighten the text. Setting this will cause all descendents to receive
+        /// that value, up to the next set value.
+        ///
+        /// Only applies to [`RenderCommand::Text`]
+        pub letter_spacing: StyleProp<f32>,
         /// The line height for this widget, in pixels
         ///
         /// Only applies to [`RenderCommand::Text`]
@@ -302,6 +473,12 @@ define_styles! {
         /// this acts like margin around the widget. For [`PositionType`](PositionType::SelfDirected) this
         /// acts as the actual position from the parent.
         pub offset: StyleProp<Edge<Units>>,
+        /// How opaque this widget and its descendants are, from `0.0` (invisible) to `1.0`
+        ///
+        /// The opacity multiplies with that of the widget's ancestors. It fades the colors of
+        /// backgrounds, borders and text, but images are always drawn fully opaque. If unset, the
+        /// widget is as opaque as its parent.
+        pub opacity: StyleProp<f32>,
         /// The inner padding between the edges of this widget and its children
         ///
         /// This property has lower precedence than its more specific counterparts
@@ -335,9 +512,22 @@ define_styles! {
         /// Controls how the pointer interacts with the widget
         ///
         /// This can be used to block pointer events on itself and/or its children if needed, allowing
-        /// the event to "pass through" to widgets below.
+        /// the event to "pass through" to widgets below. This is also respected by hit testing
+        /// (see [`KayakContext::hit_test`](crate::KayakContext::hit_test)).
+        ///
+        /// This property is not inherited, but blocking pointer events on a widget's subtree cannot be
+        /// undone by its descendants (see [`PointerEvents`] for details).
         pub pointer_events: StyleProp<PointerEvents>,
+        /// Controls whether this widget participates in its parent's layout flow
+        /// or is positioned absolutely within it
+        ///
+        /// Setting this to [`Position::Absolute`] takes precedence over [`position_type`](Self::position_type).
+        pub position: StyleProp<Position>,
         /// The position type of the widget relative to its parent
+        ///
+        /// This comes from the layout library, so it isn't reflected (use [`position`](Self::position)
+        /// instead).
+        #[cfg_attr(feature = "reflect", reflect(ignore))]
         pub position_type: StyleProp<PositionType>,
         /// The render method for this widget
         ///
@@ -347,10 +537,63 @@ define_styles! {
         pub right: StyleProp<Units>,
         /// The spacing between child widgets along the vertical axis
         pub row_between: StyleProp<Units>,
+        /// Where this widget is placed within a parent with a [`layout_type`](Self::layout_type)
+        /// of [`LayoutType::Stack`]
+        pub stack_alignment: StyleProp<StackAlignment>,
+        /// Whether the text of this widget is positioned horizontally with subpixel precision
+        ///
+        /// By default, glyphs are snapped to whole pixels, which keeps them crisp. Enabling this
+        /// spaces glyphs more evenly, which is most noticeable in small text. Since glyphs are drawn
+        /// from a distance field, this needs no extra space in the font atlas.
+        ///
+        /// Setting this will cause all descendents to receive that value, up to the next set value.
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Text`]
+        pub subpixel_positioning: StyleProp<bool>,
+        /// The outline drawn around the glyphs of this widget's text
+        ///
+        /// Setting this will cause all descendents to receive that value, up to the next set value.
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Text`]
+        pub text_outline: StyleProp<TextOutline>,
+        /// The shadow drawn behind this widget's text
+        ///
+        /// Setting this will cause all descendents to receive that value, up to the next set value.
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Text`]
+        pub text_shadow: StyleProp<TextShadow>,
+        /// How the casing of this widget's text is changed when it's displayed
+        ///
+        /// The content itself is left unchanged, but it's measured as transformed. Setting this
+        /// will cause all descendents to receive that value, up to the next set value.
+        ///
+        /// Only applies to widgets marked [`RenderCommand::Text`]
+        pub text_transform: StyleProp<TextTransform>,
         /// The distance between the top edge of this widget and the top edge of its containing widget
         pub top: StyleProp<Units>,
+        /// The translation and scale applied to this widget and its descendants after layout
+        ///
+        /// Their layout is unaffected, but they're drawn and hit-tested where the transform moves
+        /// them. See [`Transform`] for details.
+        pub transform: StyleProp<Transform>,
+        /// Whether this widget is drawn
+        ///
+        /// A [`Visibility::Hidden`] widget keeps its place in the layout, but isn't drawn, doesn't
+        /// receive pointer events, and is skipped by focus navigation.
+        ///
+        /// Setting this will cause all descendents to receive that value, up to the next set value.
+        /// A descendant can therefore be shown within a hidden widget by setting it to
+        /// [`Visibility::Visible`].
+        pub visibility: StyleProp<Visibility>,
         /// The width of this widget
         pub width: StyleProp<Units>,
+        /// The extra space added to each space between the words of this widget's text, in pixels
+        ///
+        /// Negative values tighten the text. Setting this will cause all descendents to receive
+        /// that value, up to the next set value.
+        ///
+        /// Only applies to [`RenderCommand::Text`]
+        pub word_spacing: StyleProp<f32>,
     }
 }
 
@@ -361,45 +604,137 @@ impl Style {
     /// resolving the style.
     pub fn initial() -> Self {
         Self {
+            align_items: StyleProp::Default,
             background_color: StyleProp::Default,
             border: StyleProp::Default,
             border_color: StyleProp::Default,
+            border_colors: StyleProp::Default,
             border_radius: StyleProp::Default,
+            border_style: StyleProp::Default,
             bottom: StyleProp::Default,
+            box_sizing: StyleProp::Default,
             color: StyleProp::Inherit,
             cursor: StyleProp::Inherit,
             col_between: StyleProp::Default,
+            flex_wrap: StyleProp::Default,
+            focus_outline: StyleProp::Inherit,
             font: StyleProp::Inherit,
             font_size: StyleProp::Inherit,
             height: StyleProp::Default,
+            justify_content: StyleProp::Default,
+            layer: StyleProp::Default,
             layout_type: StyleProp::Default,
             line_height: StyleProp::Inherit,
             left: StyleProp::Default,
+            letter_spacing: StyleProp::Inherit,
             max_height: StyleProp::Default,
             max_width: StyleProp::Default,
             min_height: StyleProp::Default,
             min_width: StyleProp::Default,
             offset: StyleProp::Default,
+            opacity: StyleProp::Default,
             padding: StyleProp::Default,
             padding_bottom: StyleProp::Default,
             padding_left: StyleProp::Default,
             padding_right: StyleProp::Default,
             padding_top: StyleProp::Default,
             pointer_events: StyleProp::Default,
+            position: StyleProp::Default,
             position_type: StyleProp::Default,
             render_command: StyleProp::Value(RenderCommand::Empty),
             right: StyleProp::Default,
             row_between: StyleProp::Default,
+            stack_alignment: StyleProp::Default,
+            subpixel_positioning: StyleProp::Inherit,
+            text_outline: StyleProp::Inherit,
+            text_shadow: StyleProp::Inherit,
+            text_transform: StyleProp::Inherit,
             top: StyleProp::Default,
+            transform: StyleProp::Default,
+            visibility: StyleProp::Inherit,
             width: StyleProp::Default,
+            word_spacing: StyleProp::Inherit,
         }
     }
+
+    /// Converts all font-relative units ([`Units::Em`] and [`Units::Rem`]) into pixels
+    ///
+    /// # Arguments
+    ///
+    /// * `font_size`: The font size that [`Units::Em`] is relative to
+    /// * `root_font_size`: The font size that [`Units::Rem`] is relative to
+    ///
+    pub fn resolve_font_relative_units(&mut self, font_size: f32, root_font_size: f32) {
+        let resolve = |prop: &mut StyleProp<Units>| {
+            if let StyleProp::Value(units) = prop {
+                *units = units.resolve_font_relative(font_size, root_font_size);
+            }
+        };
+        let resolve_edge = |prop: &mut StyleProp<Edge<Units>>| {
+            if let StyleProp::Value(edge) = prop {
+                *edge = Edge::new(
+                    edge.top.resolve_font_relative(font_size, root_font_size),
+                    edge.right.resolve_font_relative(font_size, root_font_size),
+                    edge.bottom.resolve_font_relative(font_size, root_font_size),
+                    edge.left.resolve_font_relative(font_size, root_font_size),
+                );
+            }
+        };
+
+        for prop in [
+            &mut self.bottom,
+            &mut self.col_between,
+            &mut self.height,
+            &mut self.left,
+            &mut self.max_height,
+            &mut self.max_width,
+            &mut self.min_height,
+            &mut self.min_width,
+            &mut self.padding_bottom,
+            &mut self.padding_left,
+            &mut self.padding_right,
+            &mut self.padding_top,
+            &mut self.right,
+            &mut self.row_between,
+            &mut self.top,
+            &mut self.width,
+        ] {
+            resolve(prop);
+        }
+        resolve_edge(&mut self.offset);
+        resolve_edge(&mut self.padding);
+    }
+
+    /// Merges `other` over this style, returning the merged style
+    ///
+    /// Every field that is set in `other` (meaning anything but [`StyleProp::Unset`], including
+    /// [`StyleProp::Default`] and [`StyleProp::Inherit`]) takes precedence over the same field in
+    /// this style. Fields left unset in `other` fall through to this style.
+    ///
+    /// This makes it easy to layer styles from least to most specific:
+    ///
+    /// ```ignore
+    /// let style = theme.merge(&variant).merge(&props.styles.clone().unwrap_or_default());
+    /// ```
+    ///
+    /// Note that the precedence is the opposite of [`Style::with_style`], where this style wins.
+    pub fn merge(&self, other: &Style) -> Style {
+        other.clone().with_style(self)
+    }
+
+    /// Applies the set fields of `overrides` over this style and returns the updated style
+    ///
+    /// This is an owned version of [`Style::merge`], useful for applying a partial style (such as
+    /// one from [`style_if!`](crate::style_if)) over a base style.
+    pub fn with_overrides(self, overrides: Style) -> Self {
+        self.merge(&overrides)
+    }
 }
 
 impl Add for Style {
     type Output = Style;
 
-    /// Defines the `+` operator for [`Style`]. This is a convenience wrapper of the `self.with_style()` method and useful for concatenating many small `Style` variables. 
+    /// Defines the `+` operator for [`Style`]. This is a convenience wrapper of the `self.with_style()` method and useful for concatenating many small `Style` variables.
     /// Similar to `with_style()` In a `StyleA + StyleB` operation, values from `StyleB` are applied to any field of StyleA that are marked as [`StyleProp::Unset`].
     ///
     /// Note: since the changes are applied only to unset fields, addition is *not* commutative. This means StyleA + StyleB != StyleB + StyleA for most cases.
@@ -410,7 +745,7 @@ impl Add for Style {
 
 #[cfg(test)]
 mod tests {
-    use super::{Edge, Style, StyleProp, Units};
+    use super::{Edge, Style, StyleProp, Units, Visibility};
 
     #[test]
     fn styles_should_equal() {
@@ -441,6 +776,20 @@ mod tests {
         assert_eq!(border, child.border.resolve());
     }
 
+    #[test]
+    fn visibility_should_be_inherited_by_default() {
+        let parent = Style {
+            visibility: StyleProp::Value(Visibility::Hidden),
+            ..Default::default()
+        };
+        let mut child = Style::default();
+
+        child.apply(&Style::initial());
+        child.inherit(&parent);
+
+        assert_eq!(Visibility::Hidden, child.visibility.resolve());
+    }
+
     #[test]
     #[should_panic]
     fn style_should_panic_on_resolve_inherit_property() {
@@ -577,6 +926,57 @@ mod tests {
         assert_eq!(expected, style_a + style_b + style_c + style_d);
     }
 
+    #[test]
+    fn set_fields_should_include_default_and_inherit() {
+        let style = Style {
+            color: StyleProp::Inherit,
+            height: StyleProp::Default,
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            ..Default::default()
+        };
+
+        assert_eq!(vec!["color", "height", "width"], style.set_fields());
+        assert!(Style::default().set_fields().is_empty());
+    }
+
+    #[test]
+    fn merge_should_prefer_set_fields_of_later_layers() {
+        let theme = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(20.0)),
+            left: StyleProp::Value(Units::Pixels(5.0)),
+            ..Default::default()
+        };
+        let variant = Style {
+            height: StyleProp::Value(Units::Pixels(40.0)),
+            left: StyleProp::Default,
+            ..Default::default()
+        };
+        let instance = Style {
+            width: StyleProp::Value(Units::Stretch(1.0)),
+            ..Default::default()
+        };
+
+        let style = theme.merge(&variant).merge(&instance);
+
+        assert_eq!(StyleProp::Value(Units::Stretch(1.0)), style.width);
+        assert_eq!(StyleProp::Value(Units::Pixels(40.0)), style.height);
+        assert_eq!(StyleProp::Default, style.left);
+        assert_eq!(StyleProp::Unset, style.top);
+    }
+
+    #[test]
+    fn merge_should_fall_through_unset_fields() {
+        let base = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Inherit,
+            ..Default::default()
+        };
+
+        assert_eq!(base, base.merge(&Style::default()));
+        assert_eq!(base, Style::default().merge(&base));
+    }
+
     #[test]
     fn value_should_convert_to_property() {
         let expected_width = Units::Pixels(123.0);
@@ -596,4 +996,25 @@ mod tests {
         assert_eq!(expected, property.resolve_or_else(|| expected));
         assert_eq!(f32::default(), property.resolve_or_default());
     }
+
+    #[test]
+    fn font_relative_units_should_resolve_to_pixels() {
+        let mut style = Style {
+            width: StyleProp::Value(Units::Em(2.0)),
+            height: StyleProp::Value(Units::Rem(2.0)),
+            padding: StyleProp::Value(Edge::all(Units::Em(0.5))),
+            left: StyleProp::Default,
+            ..Default::default()
+        };
+
+        style.resolve_font_relative_units(20.0, 10.0);
+
+        assert_eq!(StyleProp::Value(Units::Pixels(40.0)), style.width);
+        assert_eq!(StyleProp::Value(Units::Pixels(20.0)), style.height);
+        assert_eq!(
+            StyleProp::Value(Edge::all(Units::Pixels(10.0))),
+            style.padding
+        );
+        assert_eq!(StyleProp::Default, style.left);
+    }
 }
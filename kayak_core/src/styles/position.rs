@@ -0,0 +1,46 @@
+/// Controls how a widget is positioned within its containing widget
+///
+/// The containing widget of an absolute widget is its nearest ancestor that isn't itself
+/// absolute, so an absolute widget nested within another one is still positioned relative to the
+/// (relative or sticky) widget containing them both.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Position {
+    /// The widget participates in the flow of its parent's layout
+    Relative,
+    /// The widget is removed from the flow of its parent's layout and is instead positioned
+    /// using its [`top`](crate::styles::Style::top), [`right`](crate::styles::Style::right),
+    /// [`bottom`](crate::styles::Style::bottom), and [`left`](crate::styles::Style::left) offsets
+    /// relative to its containing widget
+    ///
+    /// If both `left` and `right` (or `top` and `bottom`) are set and the width (or height) is
+    /// left unset, the widget stretches to fill the space between them. Otherwise, unset sizes
+    /// fit to the widget's content. Absolute widgets do not contribute to their parent's content size.
+    Absolute,
+    /// The widget participates in the flow of its parent's layout like a relative widget, until it's
+    /// scrolled past the top edge of its scroll container (its nearest clipping ancestor). It's then
+    /// pinned `top` pixels below that edge for as long as it fits within its parent, such as a table
+    /// header staying in view while the table's rows scroll.
+    ///
+    /// Sticky siblings stack rather than overlap: each one is pinned below the sticky siblings
+    /// before it. Pinned widgets are drawn above the content scrolling underneath them.
+    Sticky {
+        /// The distance from the top edge of the scroll container at which the widget is pinned, in pixels
+        top: f32,
+    },
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::Relative
+    }
+}
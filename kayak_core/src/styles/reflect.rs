@@ -0,0 +1,355 @@
+//! Bevy [`Reflect`](bevy::reflect::Reflect) implementations for the generic style types
+//!
+//! These are reflected as structs, so that inspectors can edit the values inside them:
+//!
+//! * [`Edge`] and [`Corner`] have a field per edge or corner.
+//! * Bevy's reflection has no notion of enums, so a [`StyleProp`] has a single field named after
+//!   its variant. The `value` field holds the value of a [`StyleProp::Value`], while the `unset`,
+//!   `default`, and `inherit` fields hold `()`. Applying a struct with another field switches to
+//!   that variant, starting from the default value when switching to `value`.
+//!
+//! The non-generic style types derive their implementations directly.
+
+use std::any::Any;
+
+use bevy::reflect::utility::GenericTypeInfoCell;
+use bevy::reflect::{
+    struct_partial_eq, DynamicStruct, FieldIter, GetTypeRegistration, NamedField, Reflect,
+    ReflectMut, ReflectRef, Struct, StructInfo, TypeInfo, TypeRegistration, Typed,
+};
+
+use super::{Corner, Edge, StyleProp};
+
+/// Implements the struct-related parts of `Reflect` for a type that also implements `Struct`
+macro_rules! impl_reflect_struct_methods {
+    () => {
+        fn type_name(&self) -> &str {
+            std::any::type_name::<Self>()
+        }
+
+        fn get_type_info(&self) -> &'static TypeInfo {
+            <Self as Typed>::type_info()
+        }
+
+        fn into_any(self: Box<Self>) -> Box<dyn Any> {
+            self
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn as_reflect(&self) -> &dyn Reflect {
+            self
+        }
+
+        fn as_reflect_mut(&mut self) -> &mut dyn Reflect {
+            self
+        }
+
+        fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+            *self = value.take()?;
+            Ok(())
+        }
+
+        fn reflect_ref(&self) -> ReflectRef {
+            ReflectRef::Struct(self)
+        }
+
+        fn reflect_mut(&mut self) -> ReflectMut {
+            ReflectMut::Struct(self)
+        }
+
+        fn clone_value(&self) -> Box<dyn Reflect> {
+            Box::new(self.clone_dynamic())
+        }
+
+        fn reflect_partial_eq(&self, value: &dyn Reflect) -> Option<bool> {
+            struct_partial_eq(self, value)
+        }
+    };
+}
+
+/// Implements `Reflect` for a generic struct with a field of type `T` for each of the given names
+macro_rules! impl_reflect_fields {
+    ($name: ident { $($field: ident),* $(,)? }) => {
+        impl<T: Reflect + Copy + Default + PartialEq> Typed for $name<T> {
+            fn type_info() -> &'static TypeInfo {
+                static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+                CELL.get_or_insert::<Self, _>(|| {
+                    let fields = [$(NamedField::new::<T, _>(stringify!($field))),*];
+                    TypeInfo::Struct(StructInfo::new::<Self>(&fields))
+                })
+            }
+        }
+
+        impl<T: Reflect + Copy + Default + PartialEq> GetTypeRegistration for $name<T> {
+            fn get_type_registration() -> TypeRegistration {
+                TypeRegistration::of::<Self>()
+            }
+        }
+
+        impl<T: Reflect + Copy + Default + PartialEq> Struct for $name<T> {
+            fn field(&self, name: &str) -> Option<&dyn Reflect> {
+                match name {
+                    $(stringify!($field) => Some(&self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+                match name {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+                self.field(self.name_at(index)?)
+            }
+
+            fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+                let name = [$(stringify!($field)),*].get(index)?;
+                self.field_mut(name)
+            }
+
+            fn name_at(&self, index: usize) -> Option<&str> {
+                [$(stringify!($field)),*].get(index).copied()
+            }
+
+            fn field_len(&self) -> usize {
+                [$(stringify!($field)),*].len()
+            }
+
+            fn iter_fields(&self) -> FieldIter {
+                FieldIter::new(self)
+            }
+
+            fn clone_dynamic(&self) -> DynamicStruct {
+                let mut dynamic = DynamicStruct::default();
+                dynamic.set_name(self.type_name().to_string());
+                $(dynamic.insert(stringify!($field), self.$field);)*
+                dynamic
+            }
+        }
+
+        impl<T: Reflect + Copy + Default + PartialEq> Reflect for $name<T> {
+            impl_reflect_struct_methods!();
+
+            fn apply(&mut self, value: &dyn Reflect) {
+                let value = match value.reflect_ref() {
+                    ReflectRef::Struct(value) => value,
+                    _ => panic!("Attempted to apply non-struct type to struct type."),
+                };
+                $(
+                    if let Some(field) = value.field(stringify!($field)) {
+                        self.$field.apply(field);
+                    }
+                )*
+            }
+        }
+    };
+}
+
+impl_reflect_fields!(Edge {
+    top,
+    right,
+    bottom,
+    left
+});
+impl_reflect_fields!(Corner {
+    top_left,
+    top_right,
+    bottom_left,
+    bottom_right
+});
+
+/// The names of the fields a [`StyleProp`] can have, one for each variant
+const STYLE_PROP_FIELDS: [&str; 4] = ["unset", "default", "inherit", "value"];
+
+impl<T: Reflect + Clone + Default> StyleProp<T> {
+    /// Returns the name of the only field of this property
+    fn field_name(&self) -> &'static str {
+        match self {
+            StyleProp::Unset => STYLE_PROP_FIELDS[0],
+            StyleProp::Default => STYLE_PROP_FIELDS[1],
+            StyleProp::Inherit => STYLE_PROP_FIELDS[2],
+            StyleProp::Value(..) => STYLE_PROP_FIELDS[3],
+        }
+    }
+}
+
+impl<T: Reflect + Clone + Default> Typed for StyleProp<T> {
+    /// Lists the fields of all variants, only one of which is present on each property
+    fn type_info() -> &'static TypeInfo {
+        static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+        CELL.get_or_insert::<Self, _>(|| {
+            let fields = [
+                NamedField::new::<(), _>(STYLE_PROP_FIELDS[0]),
+                NamedField::new::<(), _>(STYLE_PROP_FIELDS[1]),
+                NamedField::new::<(), _>(STYLE_PROP_FIELDS[2]),
+                NamedField::new::<T, _>(STYLE_PROP_FIELDS[3]),
+            ];
+            TypeInfo::Struct(StructInfo::new::<Self>(&fields))
+        })
+    }
+}
+
+impl<T: Reflect + Clone + Default> GetTypeRegistration for StyleProp<T> {
+    fn get_type_registration() -> TypeRegistration {
+        TypeRegistration::of::<Self>()
+    }
+}
+
+impl<T: Reflect + Clone + Default> Struct for StyleProp<T> {
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        if name != self.field_name() {
+            return None;
+        }
+        match self {
+            StyleProp::Value(value) => Some(value),
+            _ => Some(&()),
+        }
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        if name != self.field_name() {
+            return None;
+        }
+        match self {
+            StyleProp::Value(value) => Some(value),
+            // A unit doesn't take up any memory, so this doesn't leak anything
+            _ => Some(Box::leak(Box::new(()))),
+        }
+    }
+
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+        self.field(self.name_at(index)?)
+    }
+
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        if index != 0 {
+            return None;
+        }
+        let name = self.field_name();
+        self.field_mut(name)
+    }
+
+    fn name_at(&self, index: usize) -> Option<&str> {
+        (index == 0).then(|| self.field_name())
+    }
+
+    fn field_len(&self) -> usize {
+        1
+    }
+
+    fn iter_fields(&self) -> FieldIter {
+        FieldIter::new(self)
+    }
+
+    fn clone_dynamic(&self) -> DynamicStruct {
+        let mut dynamic = DynamicStruct::default();
+        dynamic.set_name(self.type_name().to_string());
+        match self {
+            StyleProp::Value(value) => dynamic.insert_boxed(self.field_name(), value.clone_value()),
+            _ => dynamic.insert(self.field_name(), ()),
+        }
+        dynamic
+    }
+}
+
+impl<T: Reflect + Clone + Default> Reflect for StyleProp<T> {
+    impl_reflect_struct_methods!();
+
+    fn apply(&mut self, value: &dyn Reflect) {
+        let value = match value.reflect_ref() {
+            ReflectRef::Struct(value) => value,
+            _ => panic!("Attempted to apply non-struct type to struct type."),
+        };
+        let name = match value.name_at(0) {
+            Some(name) => name,
+            None => return,
+        };
+        match name {
+            "unset" => *self = StyleProp::Unset,
+            "default" => *self = StyleProp::Default,
+            "inherit" => *self = StyleProp::Inherit,
+            "value" => {
+                if !matches!(self, StyleProp::Value(..)) {
+                    *self = StyleProp::Value(T::default());
+                }
+                if let (StyleProp::Value(current), Some(field)) = (self, value.field_at(0)) {
+                    current.apply(field);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::reflect::{Reflect, ReflectRef, Struct};
+
+    use crate::styles::{Edge, Style, StyleProp, Units};
+
+    #[test]
+    fn style_props_should_be_reflected_by_variant() {
+        let mut prop = StyleProp::Value(Units::Pixels(10.0));
+        assert_eq!(Some("value"), prop.name_at(0));
+        *prop.field_mut("value").unwrap().downcast_mut().unwrap() = Units::Auto;
+        assert_eq!(StyleProp::Value(Units::Auto), prop);
+
+        prop.apply(&StyleProp::<Units>::Inherit);
+        assert_eq!(StyleProp::Inherit, prop);
+        assert!(prop.field("value").is_none());
+        assert!(prop.field("inherit").is_some());
+
+        prop.apply(&*StyleProp::Value(Units::Stretch(1.0)).clone_value());
+        assert_eq!(StyleProp::Value(Units::Stretch(1.0)), prop);
+        assert_eq!(
+            Some(true),
+            prop.reflect_partial_eq(&StyleProp::Value(Units::Stretch(1.0)))
+        );
+        assert_eq!(
+            Some(false),
+            prop.reflect_partial_eq(&StyleProp::<Units>::Unset)
+        );
+    }
+
+    #[test]
+    fn edges_should_be_reflected_as_structs() {
+        let mut style = Style {
+            padding: StyleProp::Value(Edge::all(Units::Pixels(1.0))),
+            ..Default::default()
+        };
+
+        let padding = match style.field_mut("padding").unwrap().reflect_mut() {
+            bevy::reflect::ReflectMut::Struct(padding) => padding,
+            _ => panic!("Expected the padding to be a struct"),
+        };
+        let edge = match padding.field_mut("value").unwrap().reflect_mut() {
+            bevy::reflect::ReflectMut::Struct(edge) => edge,
+            _ => panic!("Expected the edge to be a struct"),
+        };
+        *edge.field_mut("left").unwrap().downcast_mut().unwrap() = Units::Pixels(5.0);
+
+        assert_eq!(
+            StyleProp::Value(Edge::new(
+                Units::Pixels(1.0),
+                Units::Pixels(1.0),
+                Units::Pixels(1.0),
+                Units::Pixels(5.0),
+            )),
+            style.padding
+        );
+        assert!(matches!(
+            style.clone_value().reflect_ref(),
+            ReflectRef::Struct(..)
+        ));
+    }
+}
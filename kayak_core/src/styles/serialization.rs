@@ -0,0 +1,427 @@
+//! Serde support for styles
+//!
+//! Styles serialize to a human-friendly representation:
+//!
+//! * [`Units`] are written as strings with a suffix: `"10px"`, `"50%"`, `"1s"` (stretch),
+//!   `"1.5em"`, `"2rem"`, or `"auto"`. Bare numbers are also accepted as pixels.
+//! * [`Color`] is written by name (`"black"`, `"white"`, or `"transparent"`) when it matches a
+//!   named color exactly, otherwise as `"rgba(r, g, b, a)"` with components from `0` to `1`. Hex
+//!   strings (`"#rgb"`, `"#rrggbb"`, and `"#rrggbbaa"`) are also accepted.
+//! * [`StyleProp::Value`] is written as the value itself, while the other variants are written as
+//!   the keywords `"unset"`, `"default"`, and `"inherit"`. Values that would be written as one of
+//!   these keywords (such as the string `"inherit"` or [`CursorIcon::Default`]) are wrapped as
+//!   `{ "value": ... }` instead, so they aren't read back as the keyword.
+//! * Unset properties are omitted from a serialized [`Style`](super::Style).
+//!
+//! Numbers are written with their shortest exact representation, so deserializing a serialized
+//! style always gives back the original.
+
+use std::fmt::{self, Formatter};
+
+use morphorm::PositionType;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    AlignItems, BorderStyle, BoxSizing, Corner, Edge, FlexWrap, FocusOutline, JustifyContent,
+    LayoutType, Position, StackAlignment, StyleProp, TextOutline, TextShadow, TextTransform,
+    Transform, Units, Visibility,
+};
+use crate::cursor::PointerEvents;
+use crate::render_command::RenderCommand;
+use crate::{Color, CursorIcon};
+
+/// A type that can be used as the value of a serialized [`StyleProp`]
+///
+/// This exists so that types from other crates (such as [`PositionType`]) can be serialized as well.
+pub trait StyleValue: Sized {
+    fn serialize_value<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+macro_rules! impl_style_value {
+    ($($ty: ty),* $(,)?) => {
+        $(
+            impl StyleValue for $ty {
+                fn serialize_value<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.serialize(serializer)
+                }
+
+                fn deserialize_value<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Self, D::Error> {
+                    Self::deserialize(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_style_value!(
+    bool,
+    f32,
+    String,
+    AlignItems,
+    BorderStyle,
+    BoxSizing,
+    Color,
+    Corner<f32>,
+    CursorIcon,
+    Edge<BorderStyle>,
+    Edge<Color>,
+    Edge<f32>,
+    Edge<Units>,
+    FlexWrap,
+    FocusOutline,
+    JustifyContent,
+    LayoutType,
+    PointerEvents,
+    Position,
+    RenderCommand,
+    StackAlignment,
+    TextOutline,
+    TextShadow,
+    TextTransform,
+    Transform,
+    Units,
+    Visibility,
+);
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PositionTypeDef {
+    SelfDirected,
+    ParentDirected,
+}
+
+impl StyleValue for PositionType {
+    fn serialize_value<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PositionType::SelfDirected => PositionTypeDef::SelfDirected,
+            PositionType::ParentDirected => PositionTypeDef::ParentDirected,
+        }
+        .serialize(serializer)
+    }
+
+    fn deserialize_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match PositionTypeDef::deserialize(deserializer)? {
+            PositionTypeDef::SelfDirected => PositionType::SelfDirected,
+            PositionTypeDef::ParentDirected => PositionType::ParentDirected,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Keyword {
+    Unset,
+    Default,
+    Inherit,
+}
+
+/// Deserializes a [`StyleValue`] through its regular `Deserialize` implementation
+struct ValueOf<T>(T);
+
+impl<'de, T: StyleValue> Deserialize<'de> for ValueOf<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_value(deserializer).map(ValueOf)
+    }
+}
+
+/// Serializes a [`StyleValue`] through its regular `Serialize` implementation
+struct ValueRef<'a, T>(&'a T);
+
+impl<'a, T: StyleValue> Serialize for ValueRef<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_value(serializer)
+    }
+}
+
+/// A value that would otherwise be read back as a [`Keyword`]
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Wrapped<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged, bound = "T: StyleValue")]
+enum StylePropRepr<T> {
+    Keyword(Keyword),
+    Wrapped(Wrapped<ValueOf<T>>),
+    Value(ValueOf<T>),
+}
+
+/// Returns true if the given value is serialized the same way as a [`Keyword`]
+fn is_keyword<T: StyleValue>(value: &T) -> bool {
+    match serde_json::to_value(ValueRef(value)) {
+        Ok(value @ serde_json::Value::String(..)) => {
+            serde_json::from_value::<Keyword>(value).is_ok()
+        }
+        _ => false,
+    }
+}
+
+impl<T: Default + Clone + StyleValue> Serialize for StyleProp<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            StyleProp::Unset => Keyword::Unset.serialize(serializer),
+            StyleProp::Default => Keyword::Default.serialize(serializer),
+            StyleProp::Inherit => Keyword::Inherit.serialize(serializer),
+            StyleProp::Value(value) if is_keyword(value) => Wrapped {
+                value: ValueRef(value),
+            }
+            .serialize(serializer),
+            StyleProp::Value(value) => value.serialize_value(serializer),
+        }
+    }
+}
+
+impl<'de, T: Default + Clone + StyleValue> Deserialize<'de> for StyleProp<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match StylePropRepr::deserialize(deserializer)? {
+            StylePropRepr::Keyword(Keyword::Unset) => StyleProp::Unset,
+            StylePropRepr::Keyword(Keyword::Default) => StyleProp::Default,
+            StylePropRepr::Keyword(Keyword::Inherit) => StyleProp::Inherit,
+            StylePropRepr::Wrapped(Wrapped {
+                value: ValueOf(value),
+            }) => StyleProp::Value(value),
+            StylePropRepr::Value(ValueOf(value)) => StyleProp::Value(value),
+        })
+    }
+}
+
+impl Serialize for Units {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Units::Pixels(value) => serializer.collect_str(&format_args!("{}px", value)),
+            Units::Percentage(value) => serializer.collect_str(&format_args!("{}%", value)),
+            Units::Stretch(value) => serializer.collect_str(&format_args!("{}s", value)),
+            Units::Em(value) => serializer.collect_str(&format_args!("{}em", value)),
+            Units::Rem(value) => serializer.collect_str(&format_args!("{}rem", value)),
+            Units::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Units {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UnitsVisitor;
+
+        impl<'de> Visitor<'de> for UnitsVisitor {
+            type Value = Units;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("a number of pixels or a string such as \"10px\", \"50%\", \"1s\", \"2em\", \"1rem\", or \"auto\"")
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Units, E> {
+                Ok(Units::Pixels(value as f32))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Units, E> {
+                Ok(Units::Pixels(value as f32))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Units, E> {
+                Ok(Units::Pixels(value as f32))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Units, E> {
+                let value = value.trim();
+                if value == "auto" {
+                    return Ok(Units::Auto);
+                }
+
+                // `rem` must be checked before `em`
+                let suffixes: [(&str, fn(f32) -> Units); 5] = [
+                    ("px", Units::Pixels),
+                    ("%", Units::Percentage),
+                    ("rem", Units::Rem),
+                    ("em", Units::Em),
+                    ("s", Units::Stretch),
+                ];
+                suffixes
+                    .iter()
+                    .find_map(|(suffix, units)| {
+                        let number = value.strip_suffix(suffix)?;
+                        number.trim_end().parse::<f32>().ok().map(units)
+                    })
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(UnitsVisitor)
+    }
+}
+
+/// The colors that are serialized by name
+const NAMED_COLORS: [(&str, Color); 3] = [
+    ("black", Color::BLACK),
+    ("white", Color::WHITE),
+    ("transparent", Color::TRANSPARENT),
+];
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some((name, _)) = NAMED_COLORS.iter().find(|(_, color)| color == self) {
+            return serializer.serialize_str(name);
+        }
+
+        serializer.collect_str(&format_args!(
+            "rgba({}, {}, {}, {})",
+            self.r, self.g, self.b, self.a
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_color(value.trim()).ok_or_else(|| {
+            de::Error::invalid_value(
+                de::Unexpected::Str(&value),
+                &"a color name, a hex color, or \"rgba(r, g, b, a)\"",
+            )
+        })
+    }
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    if let Some((_, color)) = NAMED_COLORS.iter().find(|(name, _)| *name == value) {
+        return Some(*color);
+    }
+
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex_color(hex);
+    }
+
+    let (components, has_alpha) = if let Some(components) = value.strip_prefix("rgba(") {
+        (components, true)
+    } else if let Some(components) = value.strip_prefix("rgb(") {
+        (components, false)
+    } else {
+        return None;
+    };
+
+    let components = components
+        .strip_suffix(')')?
+        .split(',')
+        .map(|component| component.trim().parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match (components.as_slice(), has_alpha) {
+        ([r, g, b, a], true) => Some(Color::new(*r, *g, *b, *a)),
+        ([r, g, b], false) => Some(Color::new(*r, *g, *b, 1.0)),
+        _ => None,
+    }
+}
+
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if !hex.is_ascii() {
+        return None;
+    }
+
+    let digits: String = match hex.len() {
+        // Shorthand digits are repeated (i.e. `#fff` is the same as `#ffffff`)
+        3 => hex.chars().flat_map(|digit| [digit, digit]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return None,
+    };
+
+    let channels = (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&digits[index..index + 2], 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let channel = |index: usize| {
+        channels
+            .get(index)
+            .map_or(1.0, |value| *value as f32 / 255.0)
+    };
+    Some(Color::new(channel(0), channel(1), channel(2), channel(3)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::styles::{Edge, LayoutType, Style, StyleProp, Units};
+    use crate::{Color, CursorIcon};
+
+    #[test]
+    fn units_should_use_suffixes() {
+        let units = [
+            Units::Pixels(10.5),
+            Units::Percentage(50.0),
+            Units::Stretch(1.0),
+            Units::Em(1.5),
+            Units::Rem(2.0),
+            Units::Auto,
+        ];
+        let json = serde_json::to_string(&units).unwrap();
+
+        assert_eq!(r#"["10.5px","50%","1s","1.5em","2rem","auto"]"#, json);
+        assert_eq!(
+            units.to_vec(),
+            serde_json::from_str::<Vec<Units>>(&json).unwrap()
+        );
+        assert_eq!(Units::Pixels(12.0), serde_json::from_str("12").unwrap());
+    }
+
+    #[test]
+    fn colors_should_use_names_when_possible() {
+        assert_eq!(r#""white""#, serde_json::to_string(&Color::WHITE).unwrap());
+
+        let color = Color::new(0.1, 0.2, 0.3, 0.4);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(r#""rgba(0.1, 0.2, 0.3, 0.4)""#, json);
+        assert_eq!(color, serde_json::from_str(&json).unwrap());
+
+        assert_eq!(
+            Color::new(1.0, 0.0, 1.0, 1.0),
+            serde_json::from_str(r##""#f0f""##).unwrap()
+        );
+    }
+
+    #[test]
+    fn style_should_round_trip() {
+        let style = Style {
+            background_color: StyleProp::Value(Color::new(0.1, 0.2, 0.3, 1.0)),
+            color: StyleProp::Inherit,
+            font_size: StyleProp::Value(14.0),
+            padding: StyleProp::Value(Edge::new(
+                Units::Pixels(1.0),
+                Units::Em(2.0),
+                Units::Auto,
+                Units::Stretch(0.25),
+            )),
+            width: StyleProp::Default,
+            layout_type: StyleProp::Value(LayoutType::Row),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&style).unwrap();
+
+        assert!(!json.contains("unset"));
+        assert_eq!(style, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn values_should_not_be_read_back_as_keywords() {
+        let style = Style {
+            cursor: StyleProp::Value(CursorIcon::Default),
+            font: StyleProp::Value("inherit".to_string()),
+            layer: StyleProp::Value("unset".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&style).unwrap();
+
+        assert_eq!(
+            r#"{"cursor":{"value":"default"},"font":{"value":"inherit"},"layer":{"value":"unset"}}"#,
+            json
+        );
+        assert_eq!(style, serde_json::from_str(&json).unwrap());
+
+        let font: StyleProp<String> = serde_json::from_str(r#""inherit""#).unwrap();
+        assert_eq!(StyleProp::Inherit, font);
+        let font: StyleProp<String> = serde_json::from_str(r#""Roboto""#).unwrap();
+        assert_eq!(StyleProp::Value("Roboto".to_string()), font);
+    }
+}
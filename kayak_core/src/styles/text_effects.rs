@@ -0,0 +1,163 @@
+use std::f32::consts::TAU;
+
+use crate::color::Color;
+
+/// A shadow drawn behind text, to keep it readable over busy backgrounds such as images
+///
+/// The shadow is a copy of the glyphs in the shadow color, moved by the offset (and fading
+/// with the opacity of the text).
+///
+/// The default is [`TextShadow::NONE`], which isn't drawn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextShadow {
+    /// How far the shadow is moved right and down from the text, in pixels
+    pub offset: (f32, f32),
+    /// How far the shadow is spread out, in pixels
+    ///
+    /// The glyphs can't be blurred, so a blurred shadow is approximated by drawing fainter copies
+    /// of the glyphs spread around the offset. A blur of `0.0` draws a single, sharp copy.
+    pub blur: f32,
+    /// The color of the shadow
+    pub color: Color,
+}
+
+impl TextShadow {
+    /// A shadow that isn't drawn at all
+    pub const NONE: TextShadow = TextShadow {
+        offset: (0.0, 0.0),
+        blur: 0.0,
+        color: Color::TRANSPARENT,
+    };
+
+    /// Returns the offsets (in pixels) and alphas of the passes making up this shadow
+    pub(crate) fn passes(&self) -> Vec<((f32, f32), f32)> {
+        if self.color.a <= 0.0 {
+            return Vec::new();
+        }
+
+        if self.blur <= 0.0 {
+            return vec![(self.offset, self.color.a)];
+        }
+
+        // The center pass carries half of the shadow, and the ring around it the other half
+        let radius = self.blur / 2.0;
+        let ring_alpha = self.color.a / 2.0 / BLUR_SAMPLES as f32;
+        std::iter::once((self.offset, self.color.a / 2.0))
+            .chain(
+                ring(BLUR_SAMPLES, radius)
+                    .map(|(x, y)| ((self.offset.0 + x, self.offset.1 + y), ring_alpha)),
+            )
+            .collect()
+    }
+}
+
+impl Default for TextShadow {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// An outline drawn around the glyphs of text, to keep it readable over busy backgrounds
+///
+/// The outline is drawn as copies of the glyphs in the outline color, moved outwards in each
+/// direction by the outline's width, so it works best for widths of a few pixels.
+///
+/// The default is [`TextOutline::NONE`], which isn't drawn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextOutline {
+    /// The width of the outline, in pixels
+    ///
+    /// An outline with no width isn't drawn.
+    pub width: f32,
+    /// The color of the outline
+    pub color: Color,
+}
+
+impl TextOutline {
+    /// An outline that isn't drawn at all
+    pub const NONE: TextOutline = TextOutline {
+        width: 0.0,
+        color: Color::TRANSPARENT,
+    };
+
+    /// Returns the offsets (in pixels) of the passes making up this outline
+    pub(crate) fn passes(&self) -> Vec<(f32, f32)> {
+        if self.width <= 0.0 || self.color.a <= 0.0 {
+            return Vec::new();
+        }
+        ring(OUTLINE_SAMPLES, self.width).collect()
+    }
+}
+
+impl Default for TextOutline {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// The number of passes spread around a blurred [`TextShadow`]
+const BLUR_SAMPLES: usize = 8;
+
+/// The number of passes making up a [`TextOutline`]
+const OUTLINE_SAMPLES: usize = 8;
+
+/// Returns the given number of offsets, evenly spaced around a circle of the given radius
+fn ring(samples: usize, radius: f32) -> impl Iterator<Item = (f32, f32)> {
+    (0..samples).map(move |sample| {
+        let (sin, cos) = (TAU * sample as f32 / samples as f32).sin_cos();
+        (cos * radius, sin * radius)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextOutline, TextShadow};
+    use crate::color::Color;
+
+    #[test]
+    fn outlines_should_surround_the_glyphs() {
+        let outline = TextOutline {
+            width: 2.0,
+            color: Color::BLACK,
+        };
+        let passes = outline.passes();
+        assert_eq!(8, passes.len());
+        for (x, y) in passes {
+            assert!(((x * x + y * y).sqrt() - 2.0).abs() < 1e-4);
+        }
+
+        let hidden = TextOutline {
+            width: 0.0,
+            ..outline
+        };
+        assert!(hidden.passes().is_empty());
+    }
+
+    #[test]
+    fn blurred_shadows_should_keep_their_alpha() {
+        let sharp = TextShadow {
+            offset: (2.0, 3.0),
+            blur: 0.0,
+            color: Color::new(0.0, 0.0, 0.0, 0.5),
+        };
+        assert_eq!(vec![((2.0, 3.0), 0.5)], sharp.passes());
+
+        let blurred = TextShadow { blur: 4.0, ..sharp };
+        let passes = blurred.passes();
+        assert_eq!(9, passes.len());
+        let alpha: f32 = passes.iter().map(|(_, alpha)| alpha).sum();
+        assert!((alpha - 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,82 @@
+/// How the casing of text is changed when it's displayed
+///
+/// The transform only changes the displayed (and measured) glyphs, never the content itself. It
+/// uses the Unicode case mappings, so a character may become more than one (such as `'ß'`
+/// uppercasing to `"SS"`).
+///
+/// The default is [`TextTransform::None`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TextTransform {
+    /// The text is displayed as is
+    None,
+    /// Every letter is displayed in uppercase
+    Uppercase,
+    /// Every letter is displayed in lowercase
+    Lowercase,
+    /// The first letter of each word is displayed in uppercase, and the rest is left as is
+    Capitalize,
+}
+
+impl TextTransform {
+    /// Returns the given text with this transform applied
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Self::None => text.to_string(),
+            Self::Uppercase => text.to_uppercase(),
+            Self::Lowercase => text.to_lowercase(),
+            Self::Capitalize => {
+                let mut capitalized = String::with_capacity(text.len());
+                let mut at_word_start = true;
+                for c in text.chars() {
+                    if c.is_whitespace() {
+                        at_word_start = true;
+                        capitalized.push(c);
+                    } else if at_word_start && c.is_alphanumeric() {
+                        at_word_start = false;
+                        capitalized.extend(c.to_uppercase());
+                    } else {
+                        capitalized.push(c);
+                    }
+                }
+                capitalized
+            }
+        }
+    }
+}
+
+impl Default for TextTransform {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TextTransform;
+
+    #[test]
+    fn should_change_case() {
+        assert_eq!("Straße", TextTransform::None.apply("Straße"));
+        assert_eq!("STRASSE", TextTransform::Uppercase.apply("Straße"));
+        assert_eq!("ἀθῆναι", TextTransform::Lowercase.apply("ἈΘῆΝΑΙ"));
+    }
+
+    #[test]
+    fn should_capitalize_each_word() {
+        assert_eq!(
+            "Hello (World) Don't Été\n\"Quoted\"",
+            TextTransform::Capitalize.apply("hello (world) don't été\n\"quoted\"")
+        );
+        assert_eq!("McDonald", TextTransform::Capitalize.apply("mcDonald"));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::layout_cache::Rect;
+
+/// A translation and uniform scale applied to a widget (and its descendants) after layout
+///
+/// The widget is scaled around its top-left corner, then moved by the translation. Layout is
+/// still calculated without the transform, so the widget and its descendants keep their own
+/// sizes and positions in their untransformed space, but they're drawn (and hit-tested) where
+/// the transform puts them.
+///
+/// The default is [`Transform::IDENTITY`], which leaves the widget in place.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// How far the widget is moved right and down, in pixels
+    ///
+    /// The translation is in the space of the widget's parent, so it isn't affected by the
+    /// scale.
+    pub translation: (f32, f32),
+    /// How much the widget is scaled, where `1.0` is its laid out size
+    pub scale: f32,
+}
+
+impl Transform {
+    /// A transform that leaves a widget in place
+    pub const IDENTITY: Transform = Transform {
+        translation: (0.0, 0.0),
+        scale: 1.0,
+    };
+
+    /// Creates a transform that moves and scales a widget
+    pub fn new(translation: (f32, f32), scale: f32) -> Self {
+        Self { translation, scale }
+    }
+
+    /// Creates a transform that scales the given content to fit within the given frame, centered
+    ///
+    /// The transform maps points around the origin `(0.0, 0.0)` (see [`apply`](Self::apply)), so
+    /// it can map any point of the content into the frame. Content with no width (or height) is
+    /// only fitted along the other axis, and isn't scaled at all if it's empty.
+    pub fn fit(content: Rect, frame: Rect) -> Self {
+        let scale = [(frame.width, content.width), (frame.height, content.height)]
+            .into_iter()
+            .filter(|(_, size)| *size > 0.0)
+            .map(|(frame, size)| frame / size)
+            .reduce(f32::min)
+            .unwrap_or(1.0);
+        Self::new(
+            (
+                frame.posx + (frame.width - content.width * scale) / 2.0 - content.posx * scale,
+                frame.posy + (frame.height - content.height * scale) / 2.0 - content.posy * scale,
+            ),
+            scale,
+        )
+    }
+
+    /// Returns true if this transform leaves a widget in place
+    pub fn is_identity(&self) -> bool {
+        self.translation == (0.0, 0.0) && self.scale == 1.0
+    }
+
+    /// Transforms a point in the untransformed space of a widget with the given origin
+    pub fn apply(&self, point: (f32, f32), origin: (f32, f32)) -> (f32, f32) {
+        (
+            origin.0 + self.translation.0 + (point.0 - origin.0) * self.scale,
+            origin.1 + self.translation.1 + (point.1 - origin.1) * self.scale,
+        )
+    }
+
+    /// Maps a transformed point back to the untransformed space of a widget with the given origin
+    ///
+    /// This is the inverse of [`apply`](Self::apply), which is useful for finding the content
+    /// under the cursor.
+    pub fn invert(&self, point: (f32, f32), origin: (f32, f32)) -> (f32, f32) {
+        let scale = if self.scale == 0.0 {
+            f32::EPSILON
+        } else {
+            self.scale
+        };
+        (
+            origin.0 + (point.0 - origin.0 - self.translation.0) / scale,
+            origin.1 + (point.1 - origin.1 - self.translation.1) / scale,
+        )
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transform;
+    use crate::layout_cache::Rect;
+
+    #[test]
+    fn should_invert_transformed_points() {
+        let transform = Transform::new((10.0, -5.0), 2.0);
+        let origin = (100.0, 50.0);
+        assert_eq!(origin, Transform::IDENTITY.apply(origin, origin));
+        assert_eq!((110.0, 45.0), transform.apply(origin, origin));
+
+        let point = transform.apply((120.0, 60.0), origin);
+        assert_eq!((150.0, 65.0), point);
+        assert_eq!((120.0, 60.0), transform.invert(point, origin));
+    }
+
+    #[test]
+    fn should_fit_content_centered_in_frame() {
+        let content = Rect {
+            posx: 100.0,
+            posy: 100.0,
+            width: 400.0,
+            height: 100.0,
+            z_index: 0.0,
+        };
+        let frame = Rect {
+            posx: 10.0,
+            posy: 20.0,
+            width: 200.0,
+            height: 100.0,
+            z_index: 0.0,
+        };
+        let fit = Transform::fit(content, frame);
+        assert_eq!(0.5, fit.scale);
+        assert_eq!((10.0, 45.0), fit.apply((100.0, 100.0), (0.0, 0.0)));
+        assert_eq!((210.0, 95.0), fit.apply((500.0, 200.0), (0.0, 0.0)));
+    }
+}
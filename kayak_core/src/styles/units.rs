@@ -0,0 +1,123 @@
+/// Units used for sizing and spacing widgets
+///
+/// The default is [`Units::Auto`].
+///
+/// This replaces the `Units` type that used to be re-exported from `morphorm`. Code that still
+/// works with the `morphorm` type can convert between the two with [`From`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+pub enum Units {
+    /// A number of pixels
+    Pixels(f32),
+    /// A percentage of the parent size
+    Percentage(f32),
+    /// A factor of the remaining free space
+    Stretch(f32),
+    /// A multiple of this widget's font size
+    ///
+    /// Widgets that don't set a [`font_size`](crate::styles::Style::font_size) inherit the font size
+    /// of their parent, falling back to the root font size (see [`Units::Rem`]) if no ancestor sets one.
+    Em(f32),
+    /// A multiple of the root font size
+    ///
+    /// The root font size can be changed with
+    /// [`KayakContext::set_root_font_size`](crate::KayakContext::set_root_font_size).
+    Rem(f32),
+    /// Automatically determine the value
+    ///
+    /// As the width or height of a row or column, this fits the widget to its content (clamped by
+    /// its min/max). Text is sized to its measured size.
+    Auto,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl Units {
+    /// Converts font-relative units ([`Units::Em`] and [`Units::Rem`]) into [`Units::Pixels`]
+    ///
+    /// All other units are returned as-is.
+    ///
+    /// # Arguments
+    ///
+    /// * `font_size`: The font size of the widget, in pixels
+    /// * `root_font_size`: The root font size, in pixels
+    ///
+    pub fn resolve_font_relative(self, font_size: f32, root_font_size: f32) -> Self {
+        match self {
+            Self::Em(value) => Self::Pixels(value * font_size),
+            Self::Rem(value) => Self::Pixels(value * root_font_size),
+            units => units,
+        }
+    }
+}
+
+impl From<f32> for Units {
+    /// Converts the given value into [`Units::Pixels`]
+    fn from(value: f32) -> Self {
+        Self::Pixels(value)
+    }
+}
+
+impl From<Units> for morphorm::Units {
+    /// Converts these units into their layout equivalent
+    ///
+    /// Font-relative units should be [resolved](Units::resolve_font_relative) beforehand,
+    /// otherwise they are treated as [`Units::Auto`].
+    fn from(units: Units) -> Self {
+        match units {
+            Units::Pixels(value) => Self::Pixels(value),
+            Units::Percentage(value) => Self::Percentage(value),
+            Units::Stretch(value) => Self::Stretch(value),
+            Units::Em(_) | Units::Rem(_) | Units::Auto => Self::Auto,
+        }
+    }
+}
+
+impl From<morphorm::Units> for Units {
+    fn from(units: morphorm::Units) -> Self {
+        match units {
+            morphorm::Units::Pixels(value) => Self::Pixels(value),
+            morphorm::Units::Percentage(value) => Self::Percentage(value),
+            morphorm::Units::Stretch(value) => Self::Stretch(value),
+            morphorm::Units::Auto => Self::Auto,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Units;
+
+    #[test]
+    fn em_and_rem_should_resolve_to_pixels() {
+        assert_eq!(
+            Units::Pixels(24.0),
+            Units::Em(1.5).resolve_font_relative(16.0, 10.0)
+        );
+        assert_eq!(
+            Units::Pixels(15.0),
+            Units::Rem(1.5).resolve_font_relative(16.0, 10.0)
+        );
+        assert_eq!(
+            Units::Percentage(50.0),
+            Units::Percentage(50.0).resolve_font_relative(16.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn should_convert_from_layout_units() {
+        assert_eq!(Units::Stretch(2.0), morphorm::Units::Stretch(2.0).into());
+        assert_eq!(
+            morphorm::Units::Pixels(4.0),
+            Units::from(morphorm::Units::Pixels(4.0)).into()
+        );
+    }
+}
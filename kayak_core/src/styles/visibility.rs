@@ -0,0 +1,29 @@
+/// Whether a widget is drawn
+///
+/// Unlike removing a widget from the tree, hiding it keeps its space in the layout.
+///
+/// The default is [`Visibility::Visible`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "reflect",
+    derive(bevy::reflect::Reflect),
+    reflect_value(PartialEq)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Visibility {
+    /// The widget is drawn and can be interacted with
+    Visible,
+    /// The widget is still laid out, but it isn't drawn, doesn't receive pointer events, and
+    /// can't be focused
+    Hidden,
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self::Visible
+    }
+}
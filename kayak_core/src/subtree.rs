@@ -0,0 +1,40 @@
+use std::fmt::{Debug, Formatter};
+
+use crate::KayakContextRef;
+
+/// A deferred widget subtree, created by the `render_subtree!` macro
+///
+/// This allows a fragment of UI to be defined in one place (such as a separate function) and
+/// committed to a context elsewhere, under a parent of your choosing (see
+/// [`KayakContext::commit_subtree`](crate::KayakContext::commit_subtree)).
+///
+/// # Ownership
+///
+/// A subtree doesn't belong to any context. It only owns the widget definitions (along with any
+/// values moved into their props), which are evaluated when it's committed. Since a widget's
+/// identity (and therefore its state) depends on its position within the tree, its widgets can't
+/// be created until their parent is known. This means:
+///
+/// * A subtree can be created before the context it's committed to even exists.
+/// * Committing consumes the subtree, so it can only be committed once. To insert the same
+///   fragment multiple times, create a new subtree for each (such as by calling the function that
+///   defines it again).
+/// * Once committed, its widgets are owned by the context just like any other widget.
+pub struct Subtree(Box<dyn FnOnce(&mut KayakContextRef) + Send + Sync>);
+
+impl Subtree {
+    pub fn new<F: FnOnce(&mut KayakContextRef) + Send + Sync + 'static>(builder: F) -> Self {
+        Self(Box::new(builder))
+    }
+
+    /// Builds the widgets of this subtree under the given context's current widget
+    pub(crate) fn build(self, context: &mut KayakContextRef) {
+        (self.0)(context);
+    }
+}
+
+impl Debug for Subtree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subtree").finish()
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{Binding, Color};
+
+/// A set of design tokens (colors, sizes, etc.) shared by the widgets of an app
+///
+/// Widgets read the current theme with [`use_theme`](crate::KayakContextRef::use_theme) (or the
+/// `use_theme!()` macro), which also subscribes them to it: when the theme changes, only the
+/// subscribed widgets are re-rendered. The theme can be set for the entire app with
+/// [`KayakContext::set_theme`](crate::KayakContext::set_theme), or for a part of the tree with the
+/// `ThemeProvider` widget.
+///
+/// # Examples
+///
+/// ```ignore
+/// let theme = use_theme!();
+///
+/// rsx! {
+///     <Background styles={Some(Style {
+///         background_color: theme.color.primary.into(),
+///         ..Default::default()
+///     })} />
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The name of the theme, such as `"dark"`
+    pub name: String,
+    /// The colors of the theme
+    pub color: ThemeColors,
+    /// The font sizes of the theme, in pixels
+    pub font_size: ThemeSizes,
+    /// The spacing between and within widgets, in pixels
+    pub spacing: ThemeSizes,
+    /// The radius of rounded corners, in pixels
+    pub radius: f32,
+}
+
+/// The colors of a [`Theme`]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ThemeColors {
+    /// The accent color, used for highlights and the main action of a view
+    pub primary: Color,
+    /// A less prominent accent color
+    pub secondary: Color,
+    /// The color behind all other content
+    pub background: Color,
+    /// The color of elements drawn on the background, such as buttons and text boxes
+    pub surface: Color,
+    /// The color of regular text
+    pub text: Color,
+    /// The color of less important text, such as placeholders
+    pub text_muted: Color,
+    /// The color of borders and dividers
+    pub border: Color,
+    /// The color used to indicate errors
+    pub error: Color,
+}
+
+/// A small, medium and large size of a [`Theme`], in pixels
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ThemeSizes {
+    pub small: f32,
+    pub medium: f32,
+    pub large: f32,
+}
+
+impl Theme {
+    /// The dark theme, matching the default look of the built-in widgets
+    pub fn dark() -> Self {
+        Self {
+            name: String::from("dark"),
+            color: ThemeColors {
+                primary: Color::new(0.26, 0.52, 0.96, 1.0),
+                secondary: Color::new(0.2, 0.6, 0.4, 1.0),
+                background: Color::new(0.0781, 0.0898, 0.101, 1.0),
+                surface: Color::new(0.176, 0.196, 0.215, 1.0),
+                text: Color::new(1.0, 1.0, 1.0, 1.0),
+                text_muted: Color::new(0.5, 0.5, 0.5, 1.0),
+                border: Color::new(0.1581, 0.1758, 0.191, 1.0),
+                error: Color::new(0.85, 0.26, 0.26, 1.0),
+            },
+            font_size: ThemeSizes {
+                small: 12.0,
+                medium: 14.0,
+                large: 20.0,
+            },
+            spacing: ThemeSizes {
+                small: 4.0,
+                medium: 8.0,
+                large: 16.0,
+            },
+            radius: 5.0,
+        }
+    }
+
+    /// A light theme, with dark text on a light background
+    pub fn light() -> Self {
+        let dark = Self::dark();
+        Self {
+            name: String::from("light"),
+            color: ThemeColors {
+                primary: Color::new(0.16, 0.42, 0.86, 1.0),
+                secondary: Color::new(0.14, 0.5, 0.32, 1.0),
+                background: Color::new(0.96, 0.96, 0.97, 1.0),
+                surface: Color::new(1.0, 1.0, 1.0, 1.0),
+                text: Color::new(0.1, 0.1, 0.12, 1.0),
+                text_muted: Color::new(0.45, 0.45, 0.5, 1.0),
+                border: Color::new(0.82, 0.83, 0.85, 1.0),
+                error: Color::new(0.75, 0.16, 0.16, 1.0),
+            },
+            ..dark
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// The theme shared by a `ThemeProvider` with its descendants
+///
+/// The provider's own state is never changed, only the theme within it, so that changing the
+/// theme only re-renders the widgets that subscribed to it (rather than the provider and its
+/// entire subtree).
+#[derive(Debug, Clone)]
+pub struct ThemeBinding(pub Binding<Theme>);
+
+impl ThemeBinding {
+    /// Creates a binding holding the given theme
+    pub fn new(theme: Theme) -> Self {
+        Self(crate::bind(theme))
+    }
+}
+
+impl PartialEq for ThemeBinding {
+    fn eq(&self, other: &Self) -> bool {
+        // Compare by identity, since the theme within may change at any time
+        self.0.id == other.0.id
+    }
+}
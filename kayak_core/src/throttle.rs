@@ -0,0 +1,238 @@
+use std::any::Any;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Binding, MutableBound};
+
+/// A type-erased throttle that can be flushed by the context
+pub(crate) trait Throttle: Send + Sync {
+    /// Commit the pending value, if any, as long as the throttle interval has elapsed
+    fn flush(&self, now: Instant);
+
+    /// Returns the time at which the pending value can be committed, if there is one
+    fn due(&self) -> Option<Instant>;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+#[derive(Debug)]
+struct ThrottleState<T> {
+    /// The minimum amount of time between commits
+    interval: Duration,
+    /// The latest value that has yet to be committed
+    pending: Option<T>,
+    /// The time of the last commit
+    last_commit: Option<Instant>,
+}
+
+/// The setter returned by the `use_throttled_state!` macro
+///
+/// This works like [`StateSetter`](crate::StateSetter) except that the underlying state is
+/// committed (causing a re-render) at most once per interval. Any values set in between are
+/// buffered, with only the latest one being kept. Once the interval has elapsed, the buffered
+/// value is committed on the next render, so the final value of a burst is always applied.
+pub struct ThrottledSetter<T: 'static + Clone + PartialEq + Send + Sync> {
+    binding: Binding<T>,
+    state: Arc<Mutex<ThrottleState<T>>>,
+    setter: Arc<dyn Fn(T) + Send + Sync>,
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> ThrottledSetter<T> {
+    pub fn new(binding: Binding<T>, interval: Duration) -> Self {
+        let state = Arc::new(Mutex::new(ThrottleState {
+            interval,
+            pending: None,
+            last_commit: None,
+        }));
+
+        let cloned_binding = binding.clone();
+        let cloned_state = state.clone();
+        let setter = Arc::new(move |value| {
+            Self::set_at(&cloned_binding, &cloned_state, value, Instant::now());
+        });
+
+        Self {
+            binding,
+            state,
+            setter,
+        }
+    }
+
+    /// Set the state, committing it immediately only if the interval has elapsed since the last commit
+    pub fn set(&self, value: T) {
+        Self::set_at(&self.binding, &self.state, value, Instant::now());
+    }
+
+    /// Change the minimum amount of time between commits
+    ///
+    /// This applies to every clone of this setter, including any value that's already buffered.
+    pub(crate) fn set_interval(&self, interval: Duration) {
+        if let Ok(mut state) = self.state.lock() {
+            state.interval = interval;
+        }
+    }
+
+    /// Returns true if there is a buffered value waiting to be committed
+    pub fn is_pending(&self) -> bool {
+        self.state
+            .lock()
+            .map_or(false, |state| state.pending.is_some())
+    }
+
+    /// Returns the time at which the buffered value can be committed, if there is one
+    ///
+    /// Returns `None` if nothing is buffered, or if the interval is too long to ever elapse.
+    pub(crate) fn due(&self) -> Option<Instant> {
+        let state = self.state.lock().ok()?;
+        state.pending.as_ref()?;
+        state.last_commit?.checked_add(state.interval)
+    }
+
+    fn set_at(binding: &Binding<T>, state: &Mutex<ThrottleState<T>>, value: T, now: Instant) {
+        if let Ok(mut state) = state.lock() {
+            let is_ready = state.last_commit.map_or(true, |last_commit| {
+                now.duration_since(last_commit) >= state.interval
+            });
+
+            if is_ready {
+                state.pending = None;
+                state.last_commit = Some(now);
+                binding.set(value);
+            } else {
+                state.pending = Some(value);
+            }
+        }
+    }
+
+    fn flush_at(&self, now: Instant) {
+        if let Ok(mut state) = self.state.lock() {
+            let is_ready = state.last_commit.map_or(true, |last_commit| {
+                now.duration_since(last_commit) >= state.interval
+            });
+
+            if is_ready {
+                if let Some(value) = state.pending.take() {
+                    state.last_commit = Some(now);
+                    self.binding.set(value);
+                }
+            }
+        }
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> Throttle for ThrottledSetter<T> {
+    fn flush(&self, now: Instant) {
+        self.flush_at(now);
+    }
+
+    fn due(&self) -> Option<Instant> {
+        ThrottledSetter::due(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> Clone for ThrottledSetter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            binding: self.binding.clone(),
+            state: self.state.clone(),
+            setter: self.setter.clone(),
+        }
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + Send + Sync> Deref for ThrottledSetter<T> {
+    type Target = dyn Fn(T) + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        self.setter.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::ThrottledSetter;
+    use crate::{bind, Bound};
+
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn first_set_should_commit_immediately() {
+        let state = bind(0);
+        let set_state = ThrottledSetter::new(state.clone(), INTERVAL);
+
+        set_state.set(1);
+
+        assert_eq!(1, state.get());
+        assert!(!set_state.is_pending());
+    }
+
+    #[test]
+    fn should_coalesce_sets_within_interval() {
+        let state = bind(0);
+        let set_state = ThrottledSetter::new(state.clone(), INTERVAL);
+
+        set_state.set(1);
+        set_state.set(2);
+        set_state.set(3);
+
+        assert_eq!(1, state.get());
+        assert!(set_state.is_pending());
+    }
+
+    #[test]
+    fn flush_should_apply_final_value() {
+        let state = bind(0);
+        let set_state = ThrottledSetter::new(state.clone(), INTERVAL);
+
+        set_state.set(1);
+        set_state.set(2);
+        set_state.set(3);
+
+        // Too early
+        set_state.flush_at(Instant::now());
+        assert_eq!(1, state.get());
+
+        set_state.flush_at(Instant::now() + INTERVAL);
+        assert_eq!(3, state.get());
+        assert!(!set_state.is_pending());
+    }
+
+    #[test]
+    fn should_be_due_once_the_interval_has_elapsed() {
+        let state = bind(0);
+        let set_state = ThrottledSetter::new(state.clone(), INTERVAL);
+
+        set_state.set(1);
+        assert_eq!(None, set_state.due());
+
+        let last_commit = set_state.state.lock().unwrap().last_commit.unwrap();
+        set_state.set(2);
+        assert_eq!(Some(last_commit + INTERVAL), set_state.due());
+
+        set_state.clone().set_interval(Duration::MAX);
+        assert_eq!(None, set_state.due());
+    }
+
+    #[test]
+    fn should_flush_with_updated_interval() {
+        let state = bind(0);
+        let set_state = ThrottledSetter::new(state.clone(), INTERVAL);
+
+        set_state.set(1);
+        set_state.set(2);
+        set_state.clone().set_interval(INTERVAL * 2);
+
+        set_state.flush_at(Instant::now() + INTERVAL);
+        assert_eq!(1, state.get());
+
+        set_state.flush_at(Instant::now() + INTERVAL * 2);
+        assert_eq!(2, state.get());
+    }
+}
@@ -0,0 +1,245 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Binding, Bound, MutableBound};
+
+/// The state shared by the timers created via [`KayakContextRef::create_interval`](crate::KayakContextRef::create_interval)
+/// and [`KayakContextRef::create_timeout`](crate::KayakContextRef::create_timeout)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct TimerState {
+    /// The duration the timer was last updated with
+    duration: Duration,
+    /// The hashed dependencies (including restarts) the timer was last updated with
+    key: u64,
+    /// The time the timer is next due, or `None` if it's stopped
+    deadline: Option<Instant>,
+    /// Whether the timer has been started at least once
+    started: bool,
+}
+
+/// A timer stored as widget state
+///
+/// Repeating ticks are scheduled relative to the previous tick rather than the frame they happened
+/// to fire on, so the timer doesn't drift as frames come in late.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Timer(Arc<Mutex<TimerState>>);
+
+impl Timer {
+    /// Updates a repeating timer, returning whether it ticked and the time of its next tick
+    ///
+    /// Changing the interval or pausing the timer restarts it, so the first tick after resuming is
+    /// a full interval away. If more than one tick was missed, the timer only ticks once and then
+    /// skips ahead to the next tick in phase with the original schedule.
+    pub fn update_interval(
+        &self,
+        now: Instant,
+        interval: Duration,
+        paused: bool,
+    ) -> (bool, Option<Instant>) {
+        let mut state = match self.0.lock() {
+            Ok(state) => state,
+            Err(_) => return (false, None),
+        };
+
+        if paused || interval.is_zero() {
+            state.deadline = None;
+            return (false, None);
+        }
+
+        if state.duration != interval {
+            state.duration = interval;
+            state.deadline = None;
+        }
+
+        let next_tick = match state.deadline {
+            Some(next_tick) => next_tick,
+            None => {
+                let next_tick = now + interval;
+                state.deadline = Some(next_tick);
+                return (false, Some(next_tick));
+            }
+        };
+
+        if next_tick > now {
+            return (false, Some(next_tick));
+        }
+
+        let mut next_tick = next_tick + interval;
+        if next_tick <= now {
+            let behind = now.duration_since(next_tick).as_nanos();
+            let remainder = (behind % interval.as_nanos()) as u64;
+            next_tick = now + (interval - Duration::from_nanos(remainder));
+        }
+        state.deadline = Some(next_tick);
+
+        (true, Some(next_tick))
+    }
+
+    /// Updates a one-shot timer, returning whether it fired and the time it's due (if still pending)
+    ///
+    /// The timer starts on its first update and is restarted whenever the delay or `key` changes.
+    /// Once it has fired (or been cancelled), it stays stopped until it's restarted.
+    pub fn update_timeout(
+        &self,
+        now: Instant,
+        delay: Duration,
+        key: u64,
+    ) -> (bool, Option<Instant>) {
+        let mut state = match self.0.lock() {
+            Ok(state) => state,
+            Err(_) => return (false, None),
+        };
+
+        if !state.started || state.duration != delay || state.key != key {
+            state.started = true;
+            state.duration = delay;
+            state.key = key;
+            state.deadline = Some(now + delay);
+        }
+
+        match state.deadline {
+            Some(deadline) if deadline <= now => {
+                state.deadline = None;
+                (true, None)
+            }
+            deadline => (false, deadline),
+        }
+    }
+
+    /// Stops the timer until it's restarted
+    pub fn cancel(&self) {
+        if let Ok(mut state) = self.0.lock() {
+            state.deadline = None;
+        }
+    }
+
+    /// Returns true if the timer is waiting to fire
+    pub fn is_pending(&self) -> bool {
+        self.0
+            .lock()
+            .map_or(false, |state| state.deadline.is_some())
+    }
+}
+
+/// A handle to a timeout created by the `use_timeout!` macro
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeoutHandle {
+    pub(crate) timer: Binding<Timer>,
+}
+
+impl TimeoutHandle {
+    /// Restarts the timeout, so that it fires once its full delay has elapsed again
+    ///
+    /// This works even if the timeout has already fired or was cancelled. The widget is re-rendered
+    /// in order to start it.
+    pub fn restart(&self) {
+        // A fresh timer starts on its next update
+        self.timer.set(Timer::default());
+    }
+
+    /// Cancels the timeout, if it hasn't fired yet
+    pub fn cancel(&self) {
+        self.timer.get().cancel();
+    }
+
+    /// Returns true if the timeout has yet to fire
+    pub fn is_pending(&self) -> bool {
+        self.timer.get().is_pending()
+    }
+}
+
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Timer;
+
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn should_tick_each_interval_without_drift() {
+        let start = Instant::now();
+        let timer = Timer::default();
+
+        assert_eq!(
+            (false, Some(start + INTERVAL)),
+            timer.update_interval(start, INTERVAL, false)
+        );
+
+        // A late frame still schedules the next tick relative to the previous one
+        let late = start + INTERVAL + Duration::from_millis(30);
+        assert_eq!(
+            (true, Some(start + INTERVAL * 2)),
+            timer.update_interval(late, INTERVAL, false)
+        );
+    }
+
+    #[test]
+    fn should_skip_missed_ticks() {
+        let start = Instant::now();
+        let timer = Timer::default();
+        timer.update_interval(start, INTERVAL, false);
+
+        let (ticked, next_tick) = timer.update_interval(start + INTERVAL * 5, INTERVAL, false);
+        assert!(ticked);
+        assert_eq!(Some(start + INTERVAL * 6), next_tick);
+    }
+
+    #[test]
+    fn pausing_should_restart_the_interval() {
+        let start = Instant::now();
+        let timer = Timer::default();
+        timer.update_interval(start, INTERVAL, false);
+
+        assert_eq!(
+            (false, None),
+            timer.update_interval(start + INTERVAL, INTERVAL, true)
+        );
+
+        let resumed = start + INTERVAL * 3;
+        assert_eq!(
+            (false, Some(resumed + INTERVAL)),
+            timer.update_interval(resumed, INTERVAL, false)
+        );
+    }
+
+    #[test]
+    fn timeout_should_fire_once() {
+        let start = Instant::now();
+        let timer = Timer::default();
+
+        assert_eq!(
+            (false, Some(start + INTERVAL)),
+            timer.update_timeout(start, INTERVAL, 0)
+        );
+        assert_eq!(
+            (true, None),
+            timer.update_timeout(start + INTERVAL, INTERVAL, 0)
+        );
+        assert_eq!(
+            (false, None),
+            timer.update_timeout(start + INTERVAL * 2, INTERVAL, 0)
+        );
+    }
+
+    #[test]
+    fn timeout_should_restart_when_key_changes() {
+        let start = Instant::now();
+        let timer = Timer::default();
+        timer.update_timeout(start, INTERVAL, 0);
+        timer.cancel();
+        assert!(!timer.is_pending());
+
+        let restart = start + INTERVAL * 3;
+        assert_eq!(
+            (false, Some(restart + INTERVAL)),
+            timer.update_timeout(restart, INTERVAL, 1)
+        );
+    }
+}
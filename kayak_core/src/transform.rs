@@ -0,0 +1,197 @@
+//! Moving and scaling [transformed](crate::styles::Style::transform) widgets after layout
+//!
+//! Layout is calculated without transforms. Afterwards, each transformed widget is moved and
+//! scaled around its top-left corner, taking all of its descendants with it, so everything that
+//! uses the final layout (drawing, hit-testing and layout events) sees the transformed rects.
+//! The accumulated scale of each transformed node is recorded in the layout cache, so that the
+//! sizes within its render primitive (like font sizes and border widths) can be scaled to match.
+
+use std::collections::{HashMap, HashSet};
+
+use morphorm::{Cache, GeometryChanged};
+
+use crate::layout_cache::{LayoutCache, Rect};
+use crate::node::Node;
+use crate::styles::Transform;
+use crate::tree::Tree;
+use crate::Arena;
+
+/// The flags of each part of a rect that a [`GeometryChanged`] tracks
+const GEOMETRY_FLAGS: [GeometryChanged; 4] = [
+    GeometryChanged::POSX_CHANGED,
+    GeometryChanged::POSY_CHANGED,
+    GeometryChanged::WIDTH_CHANGED,
+    GeometryChanged::HEIGHT_CHANGED,
+];
+
+/// Moves and scales all transformed nodes and their descendants
+///
+/// Parents are visited before their children, so nested transforms compose: a child's
+/// translation is in its parent's transformed (and scaled) space.
+///
+/// Layout compares the untransformed rects it calculates against the transformed rects of the
+/// last layout, so the [`GeometryChanged`] flags of transformed nodes are set again afterwards by
+/// comparing their transformed rects instead.
+pub(crate) fn apply_transforms(
+    nodes: &Arena<Option<Node>>,
+    node_tree: &Tree,
+    layout_cache: &mut LayoutCache,
+) {
+    layout_cache.scale.clear();
+    let ids = match node_tree.root_node {
+        Some(root) => node_tree.flatten_node(root),
+        None => {
+            layout_cache.transformed_rect.clear();
+            return;
+        }
+    };
+
+    let mut transformed = HashSet::new();
+    for id in ids {
+        let transform = match nodes.get(id) {
+            Some(Some(node)) => node.resolved_styles.transform.resolve_or_default(),
+            _ => continue,
+        };
+        if transform.is_identity() {
+            continue;
+        }
+        let origin = match layout_cache.rect.get(&id) {
+            Some(rect) => (rect.posx, rect.posy),
+            None => continue,
+        };
+
+        let parent_scale = layout_cache.scale.get(&id).copied().unwrap_or(1.0);
+        let transform = Transform::new(
+            (
+                transform.translation.0 * parent_scale,
+                transform.translation.1 * parent_scale,
+            ),
+            transform.scale,
+        );
+
+        for descendant in node_tree.flatten_node(id) {
+            if let Some(rect) = layout_cache.rect.get_mut(&descendant) {
+                *rect = transform_rect(*rect, transform, origin);
+            }
+            let scale = layout_cache.scale.get(&descendant).copied().unwrap_or(1.0);
+            layout_cache
+                .scale
+                .insert(descendant, scale * transform.scale);
+            transformed.insert(descendant);
+        }
+    }
+
+    let mut transformed_rect = HashMap::with_capacity(transformed.len());
+    for id in transformed {
+        let rect = match layout_cache.rect.get(&id) {
+            Some(rect) => *rect,
+            None => continue,
+        };
+        let changes = geometry_changes(layout_cache.transformed_rect.get(&id), &rect);
+        for flag in GEOMETRY_FLAGS {
+            layout_cache.set_geo_changed(id, flag, changes.contains(flag));
+        }
+        transformed_rect.insert(id, rect);
+    }
+    layout_cache.transformed_rect = transformed_rect;
+}
+
+/// Returns the parts of a rect that differ from its previous rect (all of them if it has none)
+fn geometry_changes(previous: Option<&Rect>, rect: &Rect) -> GeometryChanged {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return GEOMETRY_FLAGS.into_iter().collect(),
+    };
+
+    let mut changes = GeometryChanged::default();
+    changes.set(GeometryChanged::POSX_CHANGED, previous.posx != rect.posx);
+    changes.set(GeometryChanged::POSY_CHANGED, previous.posy != rect.posy);
+    changes.set(GeometryChanged::WIDTH_CHANGED, previous.width != rect.width);
+    changes.set(
+        GeometryChanged::HEIGHT_CHANGED,
+        previous.height != rect.height,
+    );
+    changes
+}
+
+/// Transforms a rect in the untransformed space of a widget with the given origin
+fn transform_rect(rect: Rect, transform: Transform, origin: (f32, f32)) -> Rect {
+    let (posx, posy) = transform.apply((rect.posx, rect.posy), origin);
+    Rect {
+        posx,
+        posy,
+        width: rect.width * transform.scale,
+        height: rect.height * transform.scale,
+        ..rect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphorm::GeometryChanged;
+
+    use super::{geometry_changes, transform_rect};
+    use crate::layout_cache::Rect;
+    use crate::styles::Transform;
+
+    const RECT: Rect = Rect {
+        posx: 10.0,
+        posy: 20.0,
+        width: 30.0,
+        height: 40.0,
+        z_index: 0.0,
+    };
+
+    #[test]
+    fn should_not_change_geometry_of_unmoved_rects() {
+        assert!(geometry_changes(Some(&RECT), &RECT).is_empty());
+
+        // The z-index isn't part of the geometry
+        let raised = Rect {
+            z_index: 5.0,
+            ..RECT
+        };
+        assert!(geometry_changes(Some(&RECT), &raised).is_empty());
+    }
+
+    #[test]
+    fn should_change_geometry_of_moved_rects() {
+        let moved = Rect {
+            posx: 15.0,
+            height: 80.0,
+            ..RECT
+        };
+        assert_eq!(
+            GeometryChanged::POSX_CHANGED | GeometryChanged::HEIGHT_CHANGED,
+            geometry_changes(Some(&RECT), &moved)
+        );
+        assert!(geometry_changes(None, &RECT).contains(
+            GeometryChanged::POSX_CHANGED
+                | GeometryChanged::POSY_CHANGED
+                | GeometryChanged::WIDTH_CHANGED
+                | GeometryChanged::HEIGHT_CHANGED
+        ));
+    }
+
+    #[test]
+    fn should_scale_around_origin() {
+        let rect = Rect {
+            posx: 110.0,
+            posy: 60.0,
+            width: 20.0,
+            height: 10.0,
+            z_index: 3.0,
+        };
+        let transformed = transform_rect(rect, Transform::new((5.0, 0.0), 0.5), (100.0, 50.0));
+        assert_eq!(
+            Rect {
+                posx: 110.0,
+                posy: 55.0,
+                width: 10.0,
+                height: 5.0,
+                z_index: 3.0,
+            },
+            transformed
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::time::{Duration, Instant};
+
+use crate::{Easing, Spring};
+
+/// A value animated from one number to another, such as a height
+///
+/// By default, the value moves over a fixed duration along an [`Easing`] curve, which eases in and
+/// out. With a [`Spring`], it instead moves with the spring's physics, ignoring both the duration
+/// and the easing, until it settles at its target. Retargeting a transition that's still running
+/// starts the new one from the current value (and for springs, velocity) rather than jumping back
+/// to the start, so interrupted animations (like collapsing something halfway through expanding
+/// it) stay smooth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    from: f32,
+    to: f32,
+    /// The velocity at the start, in units per second
+    velocity: f32,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+    spring: Option<Spring>,
+}
+
+impl Transition {
+    /// Creates a transition that's settled at the given value
+    pub fn new(value: f32, now: Instant) -> Self {
+        Self {
+            from: value,
+            to: value,
+            velocity: 0.0,
+            start: now,
+            duration: Duration::ZERO,
+            easing: Easing::default(),
+            spring: None,
+        }
+    }
+
+    /// Use the given easing curve for this transition and those it's retargeted to
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.set_easing(easing);
+        self
+    }
+
+    /// Changes the easing curve of this transition and those it's retargeted to
+    ///
+    /// Changing the curve while the transition is running makes the value jump to wherever the new
+    /// curve is at, so this is best done right after retargeting.
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    /// Move with the given spring, rather than over a duration, from now on
+    pub fn with_spring(mut self, spring: Spring) -> Self {
+        self.set_spring(Some(spring));
+        self
+    }
+
+    /// Changes the spring this transition and those it's retargeted to move with
+    ///
+    /// If `None`, transitions move over their duration instead. Like
+    /// [`set_easing`](Self::set_easing), this is best done right after retargeting.
+    pub fn set_spring(&mut self, spring: Option<Spring>) {
+        self.spring = spring;
+    }
+
+    /// The value the transition is moving towards
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    /// Returns the current value of the transition
+    pub fn value(&self, now: Instant) -> f32 {
+        match self.spring {
+            Some(..) if self.is_done(now) => self.to,
+            Some(spring) => {
+                let (displacement, _) = spring.state(
+                    self.from - self.to,
+                    self.velocity,
+                    self.elapsed(now).as_secs_f32(),
+                );
+                self.to + displacement
+            }
+            None => {
+                let progress = self.progress(now);
+                self.from + (self.to - self.from) * self.easing.ease(progress)
+            }
+        }
+    }
+
+    /// Returns the current velocity of the transition, in units per second
+    pub fn velocity(&self, now: Instant) -> f32 {
+        if self.is_done(now) {
+            return 0.0;
+        }
+
+        match self.spring {
+            Some(spring) => {
+                let elapsed = self.elapsed(now).as_secs_f32();
+                spring.state(self.from - self.to, self.velocity, elapsed).1
+            }
+            None => {
+                // Measured over a millisecond, which is plenty for a handoff to a spring
+                let step = Duration::from_millis(1);
+                (self.value(now + step) - self.value(now)) / step.as_secs_f32()
+            }
+        }
+    }
+
+    /// Returns true if the transition has reached its target
+    ///
+    /// For springs, this is once the value has (nearly) stopped moving at its target.
+    pub fn is_done(&self, now: Instant) -> bool {
+        match self.spring {
+            Some(spring) => spring.is_settled(
+                self.from - self.to,
+                self.velocity,
+                self.elapsed(now).as_secs_f32(),
+            ),
+            None => self.progress(now) >= 1.0,
+        }
+    }
+
+    /// Starts moving towards a new target, from the current value
+    ///
+    /// Retargeting to the current target leaves the transition as it is. The duration is ignored
+    /// by springs.
+    pub fn retarget(&mut self, to: f32, now: Instant, duration: Duration) {
+        self.retarget_after(to, now, Duration::ZERO, duration);
+    }
+
+    /// Starts moving towards a new target once the given delay has passed
+    ///
+    /// The value holds still until then. Like [`retarget`](Self::retarget), retargeting to
+    /// the current target leaves the transition as it is.
+    pub fn retarget_after(&mut self, to: f32, now: Instant, delay: Duration, duration: Duration) {
+        if to == self.to {
+            return;
+        }
+
+        *self = Self {
+            from: self.value(now),
+            to,
+            velocity: if delay.is_zero() {
+                self.velocity(now)
+            } else {
+                0.0
+            },
+            start: now + delay,
+            duration,
+            easing: self.easing.clone(),
+            spring: self.spring,
+        };
+    }
+
+    /// Continues moving towards the target from the current value, with the given velocity
+    ///
+    /// This is meant for springs, to carry on the motion of a drag or gesture once it's released.
+    /// Tweens keep their remaining duration, ignoring the velocity.
+    pub fn fling(&mut self, velocity: f32, now: Instant) {
+        if self.spring.is_none() {
+            return;
+        }
+
+        self.from = self.value(now);
+        self.velocity = velocity;
+        self.start = now;
+    }
+
+    /// Moves the transition straight to the given value, without animating
+    pub fn settle(&mut self, value: f32, now: Instant) {
+        *self = Self {
+            easing: self.easing.clone(),
+            spring: self.spring,
+            ..Self::new(value, now)
+        };
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.start)
+    }
+
+    fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        (self.elapsed(now).as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transition;
+    use crate::{Easing, Spring};
+    use std::time::{Duration, Instant};
+
+    const DURATION: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn should_animate_to_target() {
+        let start = Instant::now();
+        let mut transition = Transition::new(0.0, start);
+        assert!(transition.is_done(start));
+
+        transition.retarget(100.0, start, DURATION);
+        assert_eq!(0.0, transition.value(start));
+        assert_eq!(50.0, transition.value(start + DURATION / 2));
+        assert!(!transition.is_done(start + DURATION / 2));
+        assert_eq!(100.0, transition.value(start + DURATION * 2));
+        assert!(transition.is_done(start + DURATION));
+    }
+
+    #[test]
+    fn interruptions_should_start_from_current_value() {
+        let start = Instant::now();
+        let mut transition = Transition::new(0.0, start);
+        transition.retarget(100.0, start, DURATION);
+
+        let halfway = start + DURATION / 2;
+        transition.retarget(0.0, halfway, DURATION);
+        assert_eq!(50.0, transition.value(halfway));
+        assert_eq!(0.0, transition.value(halfway + DURATION));
+
+        // Retargeting to the same target doesn't restart the transition
+        transition.retarget(0.0, halfway + DURATION / 2, DURATION);
+        assert_eq!(0.0, transition.value(halfway + DURATION));
+    }
+
+    #[test]
+    fn should_hold_until_delay_passes() {
+        let start = Instant::now();
+        let mut transition = Transition::new(0.0, start);
+        transition.retarget_after(100.0, start, DURATION, DURATION);
+
+        assert_eq!(0.0, transition.value(start + DURATION / 2));
+        assert!(!transition.is_done(start + DURATION));
+        assert_eq!(50.0, transition.value(start + DURATION * 3 / 2));
+        assert!(transition.is_done(start + DURATION * 2));
+    }
+
+    #[test]
+    fn should_keep_easing_when_retargeted() {
+        let start = Instant::now();
+        let mut transition = Transition::new(0.0, start).with_easing(Easing::Linear);
+        transition.retarget(100.0, start, DURATION);
+        assert_eq!(25.0, transition.value(start + DURATION / 4));
+
+        transition.settle(0.0, start);
+        transition.retarget(100.0, start, DURATION);
+        assert_eq!(75.0, transition.value(start + DURATION * 3 / 4));
+    }
+
+    #[test]
+    fn springs_should_settle_at_target() {
+        let start = Instant::now();
+        let mut transition = Transition::new(0.0, start).with_spring(Spring::DEFAULT);
+        transition.retarget(100.0, start, DURATION);
+        assert_eq!(0.0, transition.value(start));
+
+        let later = start + Duration::from_millis(100);
+        assert!(transition.value(later) > 0.0);
+        assert!(transition.velocity(later) > 0.0);
+        assert!(!transition.is_done(later));
+
+        let settled = start + Duration::from_secs(5);
+        assert!(transition.is_done(settled));
+        assert_eq!(100.0, transition.value(settled));
+        assert_eq!(0.0, transition.velocity(settled));
+    }
+
+    #[test]
+    fn flung_springs_should_carry_velocity() {
+        let start = Instant::now();
+        let mut transition = Transition::new(50.0, start).with_spring(Spring::DEFAULT);
+        transition.fling(-1000.0, start);
+        assert!(!transition.is_done(start));
+
+        // Flung away from the target, which it's pulled back to
+        let later = start + Duration::from_millis(50);
+        assert!(transition.value(later) < 50.0);
+        assert!(transition.is_done(start + Duration::from_secs(5)));
+    }
+}
@@ -2,6 +2,7 @@ use as_any::AsAny;
 use std::any::Any;
 
 use crate::on_layout::OnLayout;
+use crate::on_mount::OnMount;
 use crate::{context_ref::KayakContextRef, styles::Style, Children, Event, Index, OnEvent};
 
 /// An internal trait that has a blanket implementation over all implementors of [`Widget`]
@@ -87,10 +88,27 @@ pub trait WidgetProps: std::fmt::Debug + AsAny + Send + Sync {
     ///
     /// Returns `None` if this widget doesn't contain a custom event handler
     fn get_on_event(&self) -> Option<OnEvent>;
+    /// Gets the capture phase event handler of this widget
+    ///
+    /// Unlike the regular event handler, this is called on the way _down_ to an event's target,
+    /// allowing ancestors to intercept events before their descendants receive them.
+    ///
+    /// Returns `None` if this widget doesn't contain a capture phase event handler
+    fn get_on_event_capture(&self) -> Option<OnEvent> {
+        None
+    }
     /// Gets the custom layout event handler of this widget
     ///
     /// Returns `None` if this widget doesn't contain a custom layout event handler
     fn get_on_layout(&self) -> Option<OnLayout>;
+    /// Gets the mount handler of this widget
+    ///
+    /// This is called once, right before the widget's first render (see [`OnMount`]).
+    ///
+    /// Returns `None` if this widget doesn't contain a mount handler
+    fn get_on_mount(&self) -> Option<OnMount> {
+        None
+    }
     /// Gets the focusability of this widget
     ///
     /// The meanings of the returned values are:
@@ -102,6 +120,52 @@ pub trait WidgetProps: std::fmt::Debug + AsAny + Send + Sync {
     /// | `None`        | The widget's focusability is unspecified |
     ///
     fn get_focusable(&self) -> Option<bool>;
+    /// Gets whether this widget should be focused when it's mounted
+    ///
+    /// The widget is focused at the end of the render it was mounted in (see
+    /// [`KayakContext::focus`](crate::KayakContext::focus)). If several widgets request focus in
+    /// the same render, the last one declared wins.
+    fn get_autofocus(&self) -> bool {
+        false
+    }
+    /// Gets the disabled state of this widget
+    ///
+    /// Disabled widgets (and their descendants) ignore interaction events and cannot be focused.
+    ///
+    /// The meanings of the returned values are:
+    ///
+    /// | Value         | Description                                        |
+    /// |---------------|----------------------------------------------------|
+    /// | `Some(true)`  | The widget is disabled                             |
+    /// | `Some(false)` | The widget is enabled (even if its parent is not)  |
+    /// | `None`        | The widget inherits its parent's disabled state    |
+    ///
+    fn get_disabled(&self) -> Option<bool> {
+        None
+    }
+    /// Checks the invariants of these props (such as a minimum not exceeding a maximum)
+    ///
+    /// In debug builds, this is called before every render of the widget, which panics with the
+    /// widget's name and the returned message on error. It's never called in release builds.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Validates the props of the given widget, panicking on error (see [`WidgetProps::validate`])
+///
+/// This does nothing in release builds.
+#[inline(always)]
+#[allow(unused_variables)]
+pub(crate) fn validate_props(widget: &dyn BaseWidget) {
+    #[cfg(debug_assertions)]
+    if let Err(error) = widget.get_props().validate() {
+        panic!(
+            "Invalid props for widget `{}`: {}",
+            widget.get_name(),
+            error
+        );
+    }
 }
 
 /// Automatically implements the `BaseWidget` trait for all implementors of [`Widget`]
@@ -177,3 +241,175 @@ impl WidgetProps for () {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use kayak_render_macros::{widget, PropsDebug, PropsPartialEq, WidgetProps};
+    use std::sync::Arc;
+
+    use crate::WidgetProps;
+
+    #[derive(WidgetProps, PropsPartialEq, Clone, Debug, Default)]
+    struct ListProps {
+        label: String,
+        #[prop_field(eq = "ptr")]
+        items: Arc<Vec<u32>>,
+        #[prop_field(eq = "ptr")]
+        selected: Option<Arc<u32>>,
+        #[prop_field(eq = "ignore")]
+        revision: u32,
+    }
+
+    #[derive(WidgetProps, PropsDebug, Clone, Default)]
+    struct ButtonProps {
+        label: String,
+        #[prop_field(skip_debug)]
+        on_press: Option<fn()>,
+    }
+
+    const DEFAULT_COUNT: usize = 3;
+
+    fn default_title() -> String {
+        String::from("Untitled")
+    }
+
+    #[derive(WidgetProps, Clone, Debug, PartialEq)]
+    struct CardProps {
+        #[prop_field(default = 14.0)]
+        size: f32,
+        #[prop_field(default = default_title())]
+        title: String,
+        #[prop_field(default = DEFAULT_COUNT)]
+        count: usize,
+        subtitle: Option<String>,
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    #[widget_props(validate = Self::check_range)]
+    struct RangeProps {
+        min: f32,
+        max: f32,
+    }
+
+    impl RangeProps {
+        fn check_range(&self) -> Result<(), String> {
+            if self.min <= self.max {
+                Ok(())
+            } else {
+                Err(format!(
+                    "min ({}) must not exceed max ({})",
+                    self.min, self.max
+                ))
+            }
+        }
+    }
+
+    #[widget]
+    fn Slider(props: RangeProps) {}
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct ToggleProps {
+        #[prop_field(Disabled)]
+        disabled: bool,
+    }
+
+    fn list_props() -> ListProps {
+        ListProps {
+            label: String::from("List"),
+            items: Arc::new(vec![1, 2, 3]),
+            selected: Some(Arc::new(1)),
+            revision: 0,
+        }
+    }
+
+    #[test]
+    fn should_compare_props_by_field_options() {
+        let props = list_props();
+
+        // Ignored fields never affect equality
+        assert_eq!(
+            props,
+            ListProps {
+                revision: 1,
+                ..props.clone()
+            }
+        );
+        // Pointer fields compare identity rather than value
+        assert_ne!(props, list_props());
+        assert_ne!(
+            props,
+            ListProps {
+                selected: Some(Arc::new(1)),
+                ..props.clone()
+            }
+        );
+        assert_ne!(
+            props,
+            ListProps {
+                selected: None,
+                ..props.clone()
+            }
+        );
+        // Other fields still compare by value
+        assert_ne!(
+            props,
+            ListProps {
+                label: String::from("Other"),
+                ..props.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn should_print_placeholders_for_skipped_props() {
+        let props = ButtonProps {
+            label: String::from("Press"),
+            on_press: Some(|| {}),
+        };
+
+        assert_eq!(
+            r#"ButtonProps { label: "Press", on_press: <callback> }"#,
+            format!("{:?}", props)
+        );
+    }
+
+    #[test]
+    fn should_default_props_to_their_default_expressions() {
+        assert_eq!(
+            CardProps {
+                size: 14.0,
+                title: String::from("Untitled"),
+                count: 3,
+                subtitle: None,
+            },
+            CardProps::default()
+        );
+    }
+
+    #[test]
+    fn should_validate_props_with_the_given_function() {
+        assert_eq!(Ok(()), RangeProps { min: 0.0, max: 1.0 }.validate());
+        assert_eq!(
+            Err(String::from("min (2) must not exceed max (1)")),
+            RangeProps { min: 2.0, max: 1.0 }.validate()
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "Invalid props for widget")]
+    fn should_panic_when_rendering_invalid_props() {
+        use crate::{mount_widget, KayakContext, Widget};
+
+        let mut context = KayakContext::new();
+        let props = RangeProps { min: 2.0, max: 1.0 };
+        mount_widget(&mut context, Slider::constructor(props));
+    }
+
+    #[test]
+    fn should_only_disable_with_bool_disabled_props() {
+        assert_eq!(Some(true), ToggleProps { disabled: true }.get_disabled());
+        // Enabled widgets inherit the disabled state of their parent
+        assert_eq!(None, ToggleProps { disabled: false }.get_disabled());
+    }
+}
@@ -1,13 +1,28 @@
 use indexmap::IndexSet;
-use kayak_font::KayakFont;
-use morphorm::Units;
-use std::collections::HashMap;
+use kayak_font::{ColorAtlas, KayakFont};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use crate::absolute;
 use crate::assets::Assets;
-use crate::layout_cache::Rect;
+use crate::debug_overlay;
+use crate::draw_list::DrawList;
+use crate::fit_content;
+use crate::flex;
+use crate::fragment::ForwardStylesError;
+use crate::instrument::phase_span;
+use crate::layout_cache::{IntrinsicSize, Rect};
 use crate::lifetime::WidgetLifetime;
-use crate::styles::StyleProp;
+use crate::overview::{self, OverviewContent};
+use crate::paused::{Effect, PausedWidgets};
+use crate::portal::{self, PortalLayers};
+use crate::stack;
+use crate::sticky;
+use crate::styles::{
+    BorderStyle, Corner, Edge, FocusOutline, LayoutType, StyleProp, Units, Visibility,
+};
+use crate::transform;
 use crate::{
     focus_tree::FocusTracker,
     focus_tree::FocusTree,
@@ -16,8 +31,8 @@ use crate::{
     render_command::RenderCommand,
     render_primitive::RenderPrimitive,
     styles::Style,
-    tree::Tree,
-    Arena, Binding, Bound, BoxedWidget, Index, Widget, WidgetProps,
+    tree::{DownwardIterator, Tree},
+    Arena, Binding, Bound, BoxedWidget, Gestures, Index, Widget, WidgetProps,
 };
 // use as_any::Downcast;
 
@@ -29,6 +44,42 @@ pub struct WidgetManager {
     pub(crate) nodes: Arena<Option<Node>>,
     /// A mapping of widgets to their lifetime
     widget_lifetimes: HashMap<Index, WidgetLifetime>,
+    /// A mapping of widgets to their resolved (i.e. inherited) disabled state
+    disabled: HashMap<Index, bool>,
+    /// A mapping of widgets to the gestures they recognize
+    gestures: HashMap<Index, Gestures>,
+    /// A mapping of portal widgets to the layer they're drawn into
+    portals: HashMap<Index, String>,
+    /// A mapping of raised portals to the order they were raised in
+    portal_raises: HashMap<Index, u32>,
+    /// The number of times a portal has been raised
+    portal_raise_count: u32,
+    /// A mapping of overview widgets to the widget whose content they show
+    overviews: HashMap<Index, Index>,
+    /// A mapping of overview widgets to the retained content of their source
+    overview_content: HashMap<Index, OverviewContent>,
+    /// A mapping of fragments to the styles they forward to their only child
+    forwarded_styles: HashMap<Index, Style>,
+    /// The inactive fragments (and widgets), whose children stay mounted without being laid out
+    /// (see [`FragmentProps::inactive`](crate::FragmentProps::inactive)), and whether their
+    /// descendants are paused while they're inactive
+    inactive: HashMap<Index, bool>,
+    /// The inactive widgets and their descendants, cached by [`Self::update_inactive_widgets`]
+    inactive_widgets: HashSet<Index>,
+    /// The descendants of inactive fragments, which don't render or run their effects until the
+    /// fragment is active again
+    pub(crate) paused: PausedWidgets,
+    /// A mapping of parents to the widgets they added by key (see [`Self::create_keyed_widget`])
+    keyed_children: HashMap<Index, HashMap<String, Index>>,
+    /// The widgets that stay mounted once their parent stops adding them, until they finish
+    /// exiting (see [`KayakContextRef::defer_unmount`](crate::KayakContextRef::defer_unmount))
+    deferred_unmounts: HashSet<Index>,
+    /// The widgets that their parent stopped adding, but that are still mounted while they exit
+    exiting: HashSet<Index>,
+    /// The layers that portals can be drawn into
+    portal_layers: PortalLayers,
+    /// The color glyphs (such as emoji) of the text drawn so far
+    color_atlas: ColorAtlas,
     /// A tree containing all widgets in the hierarchy.
     pub tree: Tree,
     /// A tree containing only the widgets with layouts in the hierarchy.
@@ -38,6 +89,14 @@ pub struct WidgetManager {
     pub layout_cache: LayoutCache,
     focus_tracker: FocusTracker,
     current_z: f32,
+    /// The font size of the root widget, used by [`Units::Rem`] and as the fallback for [`Units::Em`]
+    pub(crate) root_font_size: f32,
+    /// Whether the [debug overlay](crate::debug_overlay) is drawn above the widget tree
+    pub(crate) debug_overlay: bool,
+    /// The focused widget whose outline is being animated, along with the time it was focused
+    focus_outline_start: Option<(Index, Instant)>,
+    /// The time the focused widget had been focused for as of the last render, in seconds
+    focus_outline_elapsed: f32,
 }
 
 impl WidgetManager {
@@ -53,7 +112,27 @@ impl WidgetManager {
             focus_tree: FocusTree::default(),
             focus_tracker: FocusTracker::default(),
             current_z: 0.0,
+            root_font_size: 14.0,
+            debug_overlay: false,
+            focus_outline_start: None,
+            focus_outline_elapsed: 0.0,
             widget_lifetimes: HashMap::new(),
+            disabled: HashMap::new(),
+            gestures: HashMap::new(),
+            portals: HashMap::new(),
+            portal_raises: HashMap::new(),
+            portal_raise_count: 0,
+            overviews: HashMap::new(),
+            overview_content: HashMap::new(),
+            forwarded_styles: HashMap::new(),
+            inactive: HashMap::new(),
+            inactive_widgets: HashSet::new(),
+            paused: PausedWidgets::default(),
+            keyed_children: HashMap::new(),
+            deferred_unmounts: HashSet::new(),
+            exiting: HashSet::new(),
+            portal_layers: PortalLayers::default(),
+            color_atlas: ColorAtlas::new(),
         }
     }
 
@@ -74,10 +153,13 @@ impl WidgetManager {
         }
     }
 
+    /// Creates (or updates) the widget at the given index among the children of `parent`
+    ///
+    /// Returns whether the widget was newly mounted, along with its ID.
     pub fn create_widget<T: Widget + 'static>(
         &mut self,
         index: usize,
-        mut widget: T,
+        widget: T,
         parent: Option<Index>,
     ) -> (bool, Index) {
         let widget_id = if let Some(parent) = parent.clone() {
@@ -90,6 +172,122 @@ impl WidgetManager {
             None
         };
 
+        self.create_or_update_widget(widget_id, widget, parent)
+    }
+
+    /// Creates (or updates) the widget with the given key among the children of `parent`
+    ///
+    /// Unlike [`Self::create_widget`], the widget is matched by its key rather than its index, so
+    /// it keeps its ID (and therefore its state) when it's moved to another index.
+    ///
+    /// Returns whether the widget was newly mounted, along with its ID.
+    pub fn create_keyed_widget<T: Widget + 'static>(
+        &mut self,
+        key: &str,
+        widget: T,
+        parent: Option<Index>,
+    ) -> (bool, Index) {
+        let parent_id = parent.unwrap_or_default();
+        // Widgets that have since been removed from the parent are created anew
+        let widget_id = self
+            .keyed_children
+            .get(&parent_id)
+            .and_then(|keys| keys.get(key))
+            .copied()
+            .filter(|id| {
+                self.current_widgets.get(*id).is_some() && self.tree.get_parent(*id) == parent
+            });
+
+        let (is_new, widget_id) = self.create_or_update_widget(widget_id, widget, parent);
+        if is_new {
+            self.keyed_children
+                .entry(parent_id)
+                .or_default()
+                .insert(key.to_string(), widget_id);
+        }
+        (is_new, widget_id)
+    }
+
+    /// Keeps the given widget mounted once its parent stops adding it, until it finishes exiting
+    pub(crate) fn defer_unmount(&mut self, id: Index) {
+        self.deferred_unmounts.insert(id);
+    }
+
+    /// Returns true if the parent of the given widget stopped adding it, but it's still mounted
+    /// while it exits
+    pub(crate) fn is_exiting(&self, id: Index) -> bool {
+        self.exiting.contains(&id)
+    }
+
+    /// Lets the given widget be removed once its parent stops adding it
+    ///
+    /// Returns the parent of the widget if it was exiting, which has to render again to remove it.
+    pub(crate) fn finish_exit(&mut self, id: Index) -> Option<Index> {
+        self.deferred_unmounts.remove(&id);
+        if self.exiting.remove(&id) {
+            self.tree.get_parent(id)
+        } else {
+            None
+        }
+    }
+
+    /// Keeps the children that deferred their unmount in the newly built children of the given
+    /// parent, even if it stopped adding them
+    ///
+    /// Each one stays right after the closest sibling before it that's still there. Children that
+    /// were added back stop exiting. Returns the children that just started exiting.
+    pub(crate) fn retain_exiting_children(&mut self, tree: &mut Tree, parent: Index) -> Vec<Index> {
+        let previous = match self.tree.children.get(&parent) {
+            Some(previous) => previous.clone(),
+            None => return Vec::new(),
+        };
+        let mut children = tree.children.get(&parent).cloned().unwrap_or_default();
+        let mut retained = Vec::new();
+        let mut started = Vec::new();
+        let mut position = 0;
+        for child in previous {
+            if let Some(index) = children.iter().position(|id| *id == child) {
+                self.exiting.remove(&child);
+                position = index + 1;
+                continue;
+            }
+            if !self.deferred_unmounts.contains(&child) {
+                continue;
+            }
+            if self.exiting.insert(child) {
+                started.push(child);
+            }
+            children.insert(position, child);
+            retained.push(child);
+            position += 1;
+        }
+
+        if !retained.is_empty() {
+            for child in retained {
+                tree.parents.insert(child, parent);
+            }
+            tree.children.insert(parent, children);
+        }
+        started
+    }
+
+    fn create_or_update_widget<T: Widget + 'static>(
+        &mut self,
+        widget_id: Option<Index>,
+        mut widget: T,
+        parent: Option<Index>,
+    ) -> (bool, Index) {
+        // A widget of another type replaces the previous one rather than updating it, so that it
+        // gets mounted anew (with its own state) and the previous one is removed
+        let widget_id = widget_id.filter(|id| {
+            self.current_widgets
+                .get(*id)
+                .and_then(|current| current.as_ref())
+                .map_or(false, |current| {
+                    current.get_name() == Widget::get_name(&widget)
+                })
+        });
+
         // Pull child and update.
         if let Some(widget_id) = widget_id {
             widget.set_id(widget_id);
@@ -104,6 +302,7 @@ impl WidgetManager {
             } else {
                 self.set_focusable(widget.get_props().get_focusable(), widget_id, true);
             }
+            self.set_disabled(widget.get_props().get_disabled(), widget_id, parent);
 
             // TODO: Figure a good way of diffing props passed to children of a widget
             // that wont naturally-rerender it's children because of a lack of changes
@@ -119,7 +318,7 @@ impl WidgetManager {
             *self.current_widgets[widget_id].as_mut().unwrap() = boxed_widget;
             // Tell renderer that the nodes changed.
             self.dirty_render_nodes.insert(widget_id);
-            return (true, widget_id);
+            return (false, widget_id);
             // } else {
             //     return (false, *widget_id);
             // }
@@ -153,6 +352,12 @@ impl WidgetManager {
         self.tree.add(widget_id, parent);
         self.layout_cache.add(widget_id);
         self.set_focusable(focusable, widget_id, true);
+        let disabled = self.current_widgets[widget_id]
+            .as_ref()
+            .unwrap()
+            .get_props()
+            .get_disabled();
+        self.set_disabled(disabled, widget_id, parent);
 
         (true, widget_id)
     }
@@ -170,6 +375,57 @@ impl WidgetManager {
         self.layout_cache.rect.get(id)
     }
 
+    /// The atlas containing the color glyphs of every text widget, for renderers to draw them from
+    ///
+    /// The glyphs of new text are queued when it's measured, and rasterized by the backend the
+    /// context is next [drawn with](crate::KayakContext::draw_with).
+    pub fn color_atlas(&self) -> &ColorAtlas {
+        &self.color_atlas
+    }
+
+    pub(crate) fn color_atlas_mut(&mut self) -> &mut ColorAtlas {
+        &mut self.color_atlas
+    }
+
+    /// Clears the color atlas once it's full, queuing the color glyphs of all mounted text again
+    ///
+    /// This drops the glyphs that aren't displayed anymore. Returns true if the atlas was cleared,
+    /// in which case its queued glyphs need to be rasterized again.
+    pub(crate) fn evict_unused_color_glyphs(&mut self, assets: &mut Assets) -> bool {
+        if !self.color_atlas.is_full() {
+            return false;
+        }
+
+        let mut fonts = HashMap::new();
+        let mut texts = Vec::new();
+        for (id, node) in self.nodes.iter() {
+            if let Some(Node {
+                primitive:
+                    RenderPrimitive::Text {
+                        font, text_layout, ..
+                    },
+                ..
+            }) = node
+            {
+                if !fonts.contains_key(font) {
+                    let asset = assets.get_asset::<KayakFont, _>(font.clone()).get();
+                    fonts.insert(font.clone(), asset);
+                }
+                // Text is measured in its untransformed space (see `create_primitive`)
+                let scale = self
+                    .get_valid_parent(id)
+                    .and_then(|parent_id| self.layout_cache.scale.get(&parent_id).copied());
+                texts.push((font, text_layout, scale.unwrap_or(1.0).max(f32::EPSILON)));
+            }
+        }
+
+        let layouts = texts.into_iter().filter_map(|(font, text_layout, scale)| {
+            let font = fonts.get(font)?.as_ref()?;
+            Some((font, text_layout, scale))
+        });
+        self.color_atlas.evict_unused(layouts)
+    }
+
     pub fn get_name(&self, id: &Index) -> Option<String> {
         if let Some(widget) = &self.current_widgets[*id] {
             return Some(widget.get_name().to_string());
@@ -186,6 +442,51 @@ impl WidgetManager {
     /// [`calculate_layout`]: Self::calculate_layout
     pub fn render(&mut self, assets: &mut Assets) {
         self.render_internal(assets, 0);
+        self.update_overview_content();
+    }
+
+    /// Records the content of each overview's source into a retained draw list
+    ///
+    /// This only happens when the tree renders, so that drawing the overviews on every frame just
+    /// scales their retained lists.
+    fn update_overview_content(&mut self) {
+        let portals = self.portal_orders();
+        let mut overview_content = HashMap::new();
+        for (overview, source) in self.overviews.iter() {
+            if !matches!(self.nodes.get(*source), Some(Some(..))) {
+                continue;
+            }
+            let bounds =
+                match overview::content_bounds(*source, &self.node_tree, &self.layout_cache) {
+                    Some(bounds) => bounds,
+                    None => continue,
+                };
+
+            // Overviews within the source aren't drawn, which also keeps an overview within its
+            // own source from drawing itself over and over
+            let primitives = Self::recurse_node_tree_to_build_primitives(
+                &self.node_tree,
+                &self.layout_cache,
+                &self.nodes,
+                &portals,
+                &HashMap::new(),
+                *source,
+                0.0,
+                RenderPrimitive::Empty,
+            );
+            let generation = self
+                .overview_content
+                .get(overview)
+                .map_or(0, |content| content.draw_list.generation + 1);
+            overview_content.insert(
+                *overview,
+                OverviewContent {
+                    bounds,
+                    draw_list: DrawList::from_primitives(&primitives, generation),
+                },
+            );
+        }
+        self.overview_content = overview_content;
     }
 
     fn render_internal(&mut self, assets: &mut Assets, depth: usize) {
@@ -196,8 +497,10 @@ impl WidgetManager {
         let initial_styles = Style::initial();
         let default_styles = Style::new_default();
         let nodes: Vec<_> = self.dirty_render_nodes.drain(..).collect();
+
+        let styles_span = phase_span!("kayak_styles", nodes);
+        styles_span.record("nodes", nodes.len());
         for dirty_node_index in nodes {
-            let dirty_widget = self.current_widgets[dirty_node_index].as_ref().unwrap();
             // Get the parent styles. Will be one of the following:
             // 1. Already-resolved node styles (best)
             // 2. Unresolved widget prop styles
@@ -206,12 +509,8 @@ impl WidgetManager {
                 if let Some(parent_widget_id) = self.tree.parents.get(&dirty_node_index) {
                     if let Some(parent) = self.nodes[*parent_widget_id].as_ref() {
                         parent.resolved_styles.clone()
-                    } else if let Some(parent) = self.current_widgets[*parent_widget_id].as_ref() {
-                        if let Some(styles) = parent.get_props().get_styles() {
-                            styles
-                        } else {
-                            default_styles.clone()
-                        }
+                    } else if let Some(styles) = self.get_styles(*parent_widget_id) {
+                        styles
                     } else {
                         default_styles.clone()
                     }
@@ -241,12 +540,23 @@ impl WidgetManager {
                 }
             };
 
-            let raw_styles = dirty_widget.get_props().get_styles();
+            let raw_styles = self.get_styles(dirty_node_index);
             let mut styles = raw_styles.clone().unwrap_or_default();
             // Fill in all `initial` values for any unset property
             styles.apply(&initial_styles);
             // Fill in all `inherited` values for any `inherit` property
             styles.inherit(&parent_styles);
+            // Opacity multiplies with that of the ancestors
+            styles.opacity = StyleProp::Value(
+                styles.opacity.resolve_or(1.0) * parent_styles.opacity.resolve_or(1.0),
+            );
+            // Widgets without a font size anywhere in their ancestry use the root font size
+            if !matches!(styles.font_size, StyleProp::Value(..)) {
+                styles.font_size = StyleProp::Value(self.root_font_size);
+            }
+            // Resolve `em` and `rem` units into pixels now that the font size is known
+            let font_size = styles.font_size.resolve();
+            styles.resolve_font_relative_units(font_size, self.root_font_size);
 
             let primitive = self.create_primitive(dirty_node_index, &mut styles, assets);
 
@@ -267,8 +577,10 @@ impl WidgetManager {
 
             self.nodes[dirty_node_index] = Some(node);
         }
+        styles_span.exit();
 
         self.node_tree = self.build_nodes_tree();
+        self.mark_stacked_nodes();
         self.calculate_layout();
 
         if !self.dirty_render_nodes.is_empty() && depth < MAX_RECURSION_DEPTH {
@@ -278,8 +590,120 @@ impl WidgetManager {
         }
     }
 
+    /// Marks the nodes whose parent (within the layout tree) has a layout type of [`LayoutType::Stack`]
+    fn mark_stacked_nodes(&mut self) {
+        let stacked = self
+            .node_tree
+            .parents
+            .iter()
+            .map(|(child, parent)| {
+                let is_stacked = self
+                    .nodes
+                    .get(*parent)
+                    .and_then(|node| node.as_ref())
+                    .map_or(false, |node| {
+                        matches!(
+                            node.resolved_styles.layout_type,
+                            StyleProp::Value(LayoutType::Stack)
+                        )
+                    });
+                (*child, is_stacked)
+            })
+            .collect::<Vec<_>>();
+
+        for (id, is_stacked) in stacked {
+            if let Some(Some(node)) = self.nodes.get_mut(id) {
+                node.is_stacked = is_stacked;
+            }
+        }
+    }
+
+    /// Calculates the layout of all nodes
+    ///
+    /// If any node's computed size violates its min/max constraints, the affected sizes are
+    /// re-resolved (see [`flex`](crate::flex)) and layout is calculated a second time. The same
+    /// goes for containers with an `Auto` size (see [`fit_content`](crate::fit_content)), for
+    /// stacks that are sized to their content (see [`stack`](crate::stack)), for children
+    /// stretched by [`AlignItems::Stretch`](crate::styles::AlignItems::Stretch), for wrapping
+    /// widgets that grow to fit their lines and for nested absolute widgets stretching within
+    /// their containing widget.
+    ///
+    /// Finally, children are moved into place according to their parent's `flex_wrap`,
+    /// `justify_content` and `align_items`, nested absolute widgets are moved to their offsets
+    /// (see [`absolute`](crate::absolute)) and sticky widgets are pinned within their scroll
+    /// container (see [`sticky`](crate::sticky)).
     pub fn calculate_layout(&mut self) {
+        let span = phase_span!("kayak_layout", nodes, clamped);
+        span.record("nodes", self.node_tree.len());
+
+        for (_, node) in self.nodes.iter_mut() {
+            if let Some(node) = node {
+                node.clamped_size = (None, None);
+            }
+        }
+
         morphorm::layout(&mut self.layout_cache, &self.node_tree, &self.nodes);
+
+        self.layout_cache.intrinsic_size = match self.node_tree.root_node {
+            Some(root) => fit_content::calculate_intrinsic_sizes(
+                &self.nodes,
+                &self.node_tree.children,
+                root,
+                &self.layout_cache.measured_size,
+            ),
+            None => HashMap::new(),
+        };
+
+        // Later overrides take precedence, so content sizes come first
+        let mut overrides = match self.node_tree.root_node {
+            Some(root) => fit_content::calculate_content_sizes(
+                &self.nodes,
+                &self.node_tree.children,
+                root,
+                &self.layout_cache,
+            ),
+            None => Vec::new(),
+        };
+        overrides.extend(flex::calculate_clamped_sizes(
+            &self.nodes,
+            &self.node_tree.children,
+            &self.layout_cache,
+        ));
+        overrides.extend(stack::calculate_stack_sizes(
+            &self.nodes,
+            &self.node_tree.children,
+            &self.layout_cache,
+        ));
+        overrides.extend(flex::calculate_cross_sizes(
+            &self.nodes,
+            &self.node_tree.children,
+            &self.layout_cache,
+        ));
+        overrides.extend(absolute::calculate_absolute_sizes(
+            &self.nodes,
+            &self.node_tree,
+            &self.layout_cache,
+        ));
+        span.record("clamped", overrides.len());
+
+        if !overrides.is_empty() {
+            for (id, width, height) in overrides {
+                if let Some(Some(node)) = self.nodes.get_mut(id) {
+                    node.clamped_size = (
+                        width.or(node.clamped_size.0),
+                        height.or(node.clamped_size.1),
+                    );
+                }
+            }
+
+            morphorm::layout(&mut self.layout_cache, &self.node_tree, &self.nodes);
+        }
+
+        flex::apply_alignment(&self.nodes, &self.node_tree, &mut self.layout_cache);
+        absolute::apply_absolute_offsets(&self.nodes, &self.node_tree, &mut self.layout_cache);
+        sticky::apply_sticky_offsets(&self.nodes, &self.node_tree, &mut self.layout_cache);
+        transform::apply_transforms(&self.nodes, &self.node_tree, &mut self.layout_cache);
+        self.apply_portal_z_indices();
     }
 
     fn create_primitive(
@@ -306,11 +730,43 @@ impl WidgetManager {
                 if let Some(font) = asset.get() {
                     if let Some(parent_id) = self.get_valid_parent(id) {
                         if let Some(parent_layout) = self.get_layout(&parent_id) {
-                            properties.max_size = (parent_layout.width, parent_layout.height);
+                            // Text is measured in its untransformed space
+                            let scale = self.layout_cache.scale.get(&parent_id).copied();
+                            let scale = scale.unwrap_or(1.0).max(f32::EPSILON);
+                            // A parent sized to its content doesn't have a width to wrap to yet,
+                            // so the text wraps to the space available to that parent instead
+                            let available = self
+                                .get_valid_parent(parent_id)
+                                .and_then(|id| self.get_layout(&id))
+                                .map(|layout| layout.width / scale);
+                            let content_width =
+                                fit_content::max_content_width(parent_id, &self.nodes, available);
+                            let parent_width = content_width.unwrap_or(parent_layout.width / scale);
+                            properties.max_size = (parent_width, parent_layout.height / scale);
+
+                            // --- Calculate Intrinsic Size --- //
+                            let (min_content, max_content) =
+                                font.intrinsic_widths(content, *properties);
+                            let intrinsic_size = IntrinsicSize {
+                                min_content,
+                                max_content,
+                            };
+
+                            // `Auto` widths shrink-to-fit the content within the parent
+                            if matches!(styles.width, StyleProp::Value(Units::Auto)) {
+                                let width = intrinsic_size.fit(parent_width);
+                                properties.max_size.0 = width;
+                                styles.width = StyleProp::Value(Units::Pixels(width));
+                            } else if content_width.is_some() {
+                                properties.max_size.0 = intrinsic_size.fit(parent_width);
+                            }
+
+                            self.layout_cache.measured_size.insert(id, intrinsic_size);
 
                             // --- Calculate Text Layout --- //
                             *text_layout = font.measure(&content, *properties);
                             let measurement = text_layout.size();
+                            self.color_atlas.queue_layout(&font, text_layout, scale);
 
                             // --- Apply Layout --- //
                             if matches!(styles.width, StyleProp::Default) {
@@ -325,7 +781,9 @@ impl WidgetManager {
                     }
                 }
             }
-            _ => {}
+            _ => {
+                self.layout_cache.measured_size.remove(&id);
+            }
         }
 
         if needs_layout {
@@ -339,6 +797,8 @@ impl WidgetManager {
         node_tree: &Tree,
         layout_cache: &LayoutCache,
         nodes: &Arena<Option<Node>>,
+        portals: &HashMap<Index, i32>,
+        overviews: &HashMap<Index, OverviewContent>,
         current_node: Index,
         mut main_z_index: f32,
         mut prev_clip: RenderPrimitive,
@@ -356,7 +816,40 @@ impl WidgetManager {
                 };
                 layout.z_index = new_z_index;
                 render_primitive.set_layout(layout);
-                render_primitives.push(render_primitive.clone());
+                if let Some(scale) = layout_cache.scale.get(&current_node) {
+                    render_primitive.scale(*scale);
+                }
+
+                // Nested clips are limited to (and masked by) their enclosing clip
+                if let (
+                    RenderPrimitive::Clip {
+                        layout: clip_layout,
+                        mask,
+                    },
+                    RenderPrimitive::Clip {
+                        layout: prev_layout,
+                        mask: prev_mask,
+                    },
+                ) = (&mut render_primitive, &prev_clip)
+                {
+                    *clip_layout = clip_layout.intersection(prev_layout);
+                    if mask.is_none() {
+                        *mask = *prev_mask;
+                    }
+                }
+
+                // Hidden widgets still clip their children, which may be visible
+                let is_hidden = node
+                    .resolved_styles
+                    .visibility
+                    .resolve_or(Visibility::Visible)
+                    == Visibility::Hidden;
+                if !is_hidden {
+                    render_primitives.extend(render_primitive.text_effects(&node.resolved_styles));
+                }
+                if !is_hidden || matches!(render_primitive, RenderPrimitive::Clip { .. }) {
+                    render_primitives.push(render_primitive.clone());
+                }
 
                 let new_prev_clip = if matches!(render_primitive, RenderPrimitive::Clip { .. }) {
                     render_primitive.clone()
@@ -366,14 +859,47 @@ impl WidgetManager {
 
                 prev_clip = new_prev_clip.clone();
 
+                if let Some(content) = overviews.get(&current_node) {
+                    if !is_hidden {
+                        render_primitives.extend(Self::build_overview_primitives(
+                            node_tree,
+                            layout_cache,
+                            content,
+                            layout,
+                            &new_prev_clip,
+                        ));
+                    }
+                }
+
                 if node_tree.children.contains_key(&current_node) {
-                    for child in node_tree.children.get(&current_node).unwrap() {
+                    // Sticky children are drawn last, above any content scrolling underneath them
+                    let (sticky_children, flow_children): (Vec<_>, Vec<_>) = node_tree
+                        .children
+                        .get(&current_node)
+                        .unwrap()
+                        .iter()
+                        .partition(|child| sticky::is_sticky(**child, nodes));
+                    for child in flow_children.iter().chain(sticky_children.iter()) {
+                        // Portals are drawn separately
+                        if portals.contains_key(*child) {
+                            continue;
+                        }
+
                         main_z_index += 1.0;
+                        if sticky::is_sticky(**child, nodes) {
+                            main_z_index = render_primitives
+                                .iter()
+                                .filter_map(RenderPrimitive::z_index)
+                                .fold(main_z_index, f32::max)
+                                + 1.0;
+                        }
                         render_primitives.extend(Self::recurse_node_tree_to_build_primitives(
                             node_tree,
                             layout_cache,
                             nodes,
-                            *child,
+                            portals,
+                            overviews,
+                            **child,
                             main_z_index,
                             new_prev_clip.clone(),
                         ));
@@ -383,7 +909,7 @@ impl WidgetManager {
                         if matches!(prev_clip, RenderPrimitive::Clip { .. }) {
                             // main_z_index = new_z_index;
                             match &mut prev_clip {
-                                RenderPrimitive::Clip { layout } => {
+                                RenderPrimitive::Clip { layout, .. } => {
                                     layout.z_index = main_z_index + 0.1;
                                 }
                                 _ => {}
@@ -398,6 +924,23 @@ impl WidgetManager {
         render_primitives
     }
 
+    /// Builds the primitives of an overview from its retained content, fitted into the given frame
+    ///
+    /// See [`overview`] for details.
+    fn build_overview_primitives(
+        node_tree: &Tree,
+        layout_cache: &LayoutCache,
+        content: &OverviewContent,
+        frame: Rect,
+        prev_clip: &RenderPrimitive,
+    ) -> Vec<RenderPrimitive> {
+        let unclipped = node_tree
+            .root_node
+            .and_then(|root| layout_cache.rect.get(&root).copied())
+            .unwrap_or(frame);
+        overview::build_primitives(content, frame, prev_clip, unclipped, frame.z_index + 0.5)
+    }
+
     /// Forces layout to be recalculated before rendering.
     ///
     /// This should be used _sparingly_, if at all.
@@ -410,19 +953,175 @@ impl WidgetManager {
             return vec![];
         }
 
-        Self::recurse_node_tree_to_build_primitives(
+        let span = phase_span!("kayak_render", primitives);
+        let root = self.node_tree.root_node.unwrap();
+        let portals = self.portal_orders();
+        let mut primitives = Self::recurse_node_tree_to_build_primitives(
             &self.node_tree,
             &self.layout_cache,
             &self.nodes,
-            self.node_tree.root_node.unwrap(),
+            &portals,
+            &self.overview_content,
+            root,
             0.0,
             RenderPrimitive::Empty,
-        )
+        );
+
+        // Portals are drawn after the main tree, by layer and then in tree order. Each one starts
+        // above everything drawn before it and outside of any clip.
+        let mut portal_ids = self
+            .node_tree
+            .flatten_node(root)
+            .into_iter()
+            .filter(|id| *id != root && portals.contains_key(id))
+            .collect::<Vec<_>>();
+        portal_ids.sort_by_key(|id| (portals[id], self.portal_raise(*id)));
+
+        let max_z_index = |primitives: &[RenderPrimitive]| {
+            primitives
+                .iter()
+                .filter_map(RenderPrimitive::z_index)
+                .fold(0.0, f32::max)
+        };
+        let mut z_index = max_z_index(&primitives);
+        for portal_id in portal_ids {
+            let portal_primitives = Self::recurse_node_tree_to_build_primitives(
+                &self.node_tree,
+                &self.layout_cache,
+                &self.nodes,
+                &portals,
+                &self.overview_content,
+                portal_id,
+                z_index + 1.0,
+                RenderPrimitive::Empty,
+            );
+            z_index = z_index.max(max_z_index(&portal_primitives));
+            primitives.extend(portal_primitives);
+        }
+
+        let focus_outline = self.build_focus_outline(&portals, z_index + 1.0);
+        if !focus_outline.is_empty() {
+            z_index += 1.0;
+            primitives.extend(focus_outline);
+        }
+
+        if self.debug_overlay {
+            primitives.extend(debug_overlay::build_primitives(
+                &self.node_tree,
+                &self.layout_cache,
+                &self.nodes,
+                z_index + 1.0,
+            ));
+        }
+
+        span.record("primitives", primitives.len());
+        primitives
+    }
+
+    /// Returns the focused widget along with its [focus outline](Style::focus_outline), if it shows one
+    fn focused_outline(&self) -> Option<(Index, FocusOutline)> {
+        let focused = self.focus_tree.current()?;
+        if Some(focused) == self.node_tree.root_node || self.is_hidden(focused) {
+            return None;
+        }
+
+        let node = self.nodes.get(focused)?.as_ref()?;
+        let outline = node.resolved_styles.focus_outline.resolve_or_default();
+        outline.is_visible().then_some((focused, outline))
+    }
+
+    /// Advances the animation of the focus outline to the given time
+    ///
+    /// Returns true if the outline is animated, meaning another render is needed to keep it moving.
+    pub(crate) fn update_focus_outline(&mut self, now: Instant) -> bool {
+        let (focused, outline) = match self.focused_outline() {
+            Some(focused) => focused,
+            None => {
+                self.focus_outline_start = None;
+                return false;
+            }
+        };
+
+        let start = match self.focus_outline_start {
+            Some((index, start)) if index == focused => start,
+            _ => now,
+        };
+        self.focus_outline_start = Some((focused, start));
+        self.focus_outline_elapsed = now.duration_since(start).as_secs_f32();
+        outline.animation.is_animated()
+    }
+
+    /// Builds the primitives making up the outline of the focused widget, drawn above the given z-index
+    fn build_focus_outline(
+        &self,
+        portals: &HashMap<Index, i32>,
+        z_index: f32,
+    ) -> Vec<RenderPrimitive> {
+        let (focused, outline) = match self.focused_outline() {
+            Some(focused) => focused,
+            None => return Vec::new(),
+        };
+        let (layout, root_layout) = match (
+            self.layout_cache.rect.get(&focused),
+            self.node_tree
+                .root_node
+                .and_then(|root| self.layout_cache.rect.get(&root)),
+        ) {
+            (Some(layout), Some(root_layout)) => (
+                Rect { z_index, ..*layout },
+                Rect {
+                    z_index,
+                    ..*root_layout
+                },
+            ),
+            _ => return Vec::new(),
+        };
+
+        // The outline is clipped by the same ancestors as the widget, up to the portal it's drawn
+        // into (if any)
+        let mut clip = root_layout;
+        let mut current = focused;
+        while !portals.contains_key(&current) {
+            let parent = match self.node_tree.get_parent(current) {
+                Some(parent) => parent,
+                None => break,
+            };
+            if let Some(Some(Node {
+                primitive: RenderPrimitive::Clip { .. },
+                ..
+            })) = self.nodes.get(parent)
+            {
+                if let Some(parent_layout) = self.layout_cache.rect.get(&parent) {
+                    clip = clip.intersection(parent_layout);
+                }
+            }
+            current = parent;
+        }
+
+        let mut primitives = vec![RenderPrimitive::Clip {
+            layout: clip,
+            mask: None,
+        }];
+        let quads = outline.quads(&layout, self.focus_outline_elapsed);
+        primitives.extend(quads.into_iter().map(|quad| RenderPrimitive::Quad {
+            layout: quad.layout,
+            background_color: quad.color,
+            border_color: quad.color,
+            border_colors: Edge::all(quad.color),
+            border: Edge::all(0.0),
+            border_radius: Corner::default(),
+            border_style: Edge::all(BorderStyle::Solid),
+        }));
+        primitives
     }
 
     fn build_nodes_tree(&mut self) -> Tree {
         let mut tree = Tree::default();
-        let (root_node_id, _) = self.current_widgets.iter().next().unwrap();
+        let (root_node_id, _) = match self.current_widgets.iter().next() {
+            Some(root) => root,
+            // Nothing has been rendered yet
+            None => return tree,
+        };
         tree.root_node = Some(root_node_id);
         tree.children.insert(
             tree.root_node.unwrap(),
@@ -433,9 +1132,12 @@ impl WidgetManager {
         self.focus_tree.clear();
         self.focus_tree.add(root_node_id, &self.tree);
 
-        for (widget_id, widget) in self.current_widgets.iter().skip(1) {
-            let widget_styles = widget.as_ref().unwrap().get_props().get_styles();
-            if let Some(widget_styles) = widget_styles {
+        for (widget_id, _) in self.current_widgets.iter().skip(1) {
+            if self.inactive_widgets.contains(&widget_id) {
+                continue;
+            }
+
+            if let Some(widget_styles) = self.get_styles(widget_id) {
                 // Only add widgets who have renderable nodes.
                 if widget_styles.render_command.resolve() != RenderCommand::Empty {
                     let valid_children = self.get_valid_node_children(widget_id);
@@ -466,8 +1168,10 @@ impl WidgetManager {
         let mut children = Vec::new();
         if let Some(node_children) = self.tree.children.get(&node_id) {
             for child_id in node_children {
-                if let Some(child_widget) = &self.current_widgets[*child_id] {
-                    if let Some(child_styles) = child_widget.get_props().get_styles() {
+                if self.current_widgets[*child_id].is_some()
+                    && !self.inactive.contains_key(child_id)
+                {
+                    if let Some(child_styles) = self.get_styles(*child_id) {
                         if child_styles.render_command.resolve() != RenderCommand::Empty {
                             children.push(*child_id);
                         } else {
@@ -496,6 +1200,33 @@ impl WidgetManager {
         None
     }
 
+    /// Forgets the data kept for widgets that are no longer part of the tree
+    ///
+    /// Unmounted widgets are never removed from the arena, so this is what keeps the per-widget
+    /// maps from growing with every widget that was ever rendered. Returns the widgets that are
+    /// still mounted, so that the context can prune its own data as well.
+    pub(crate) fn prune_removed_widgets(&mut self) -> HashSet<Index> {
+        let mounted: HashSet<Index> = self.tree.flatten().into_iter().collect();
+        self.keyed_children.retain(|parent, keys| {
+            keys.retain(|_, id| mounted.contains(id));
+            mounted.contains(parent) && !keys.is_empty()
+        });
+        self.disabled.retain(|id, _| mounted.contains(id));
+        self.inactive.retain(|id, _| mounted.contains(id));
+        self.deferred_unmounts.retain(|id| mounted.contains(id));
+        self.exiting.retain(|id| mounted.contains(id));
+        self.layout_cache
+            .measured_size
+            .retain(|id, _| mounted.contains(id));
+        self.gestures.retain(|id, _| mounted.contains(id));
+        self.portals.retain(|id, _| mounted.contains(id));
+        self.forwarded_styles.retain(|id, _| mounted.contains(id));
+        self.portal_raises.retain(|id, _| mounted.contains(id));
+        self.overviews
+            .retain(|id, source| mounted.contains(id) && mounted.contains(source));
+        mounted
+    }
+
     pub fn get_node(&self, id: &Index) -> Option<Node> {
         self.nodes[*id].clone()
     }
@@ -539,7 +1270,13 @@ impl WidgetManager {
         }
     }
 
+    /// Gets the focusability of the widget with the given ID
+    ///
+    /// Disabled and hidden widgets are never focusable.
     pub fn get_focusable(&self, index: Index) -> Option<bool> {
+        if self.is_disabled(index) || self.is_hidden(index) {
+            return Some(false);
+        }
         self.focus_tracker.get_focusability(index)
     }
 
@@ -547,4 +1284,820 @@ impl WidgetManager {
         self.focus_tracker
             .set_focusability(index, focusable, is_parent);
     }
+
+    /// Gets the gestures recognized by the widget with the given ID
+    pub fn get_gestures(&self, index: Index) -> Gestures {
+        self.gestures.get(&index).copied().unwrap_or_default()
+    }
+
+    pub fn set_gestures(&mut self, gestures: Gestures, index: Index) {
+        if gestures.is_empty() {
+            self.gestures.remove(&index);
+        } else {
+            self.gestures.insert(index, gestures);
+        }
+    }
+
+    /// Gets the widget whose content the widget with the given ID shows an overview of, if any
+    pub fn get_overview(&self, index: Index) -> Option<Index> {
+        self.overviews.get(&index).copied()
+    }
+
+    /// Makes the widget with the given ID draw an overview of the source's content (or stops
+    /// drawing one if `None`)
+    pub fn set_overview(&mut self, source: Option<Index>, index: Index) {
+        match source {
+            Some(source) => self.overviews.insert(index, source),
+            None => self.overviews.remove(&index),
+        };
+    }
+
+    /// Registers a layer that portals can be drawn into
+    ///
+    /// See [`portal`](crate::portal) for details on how layers are ordered.
+    pub fn register_portal_layer(&mut self, name: &str, order: i32) {
+        self.portal_layers.register(name, order);
+    }
+
+    /// Gets the layer the widget with the given ID is drawn into, if it's a portal
+    pub fn get_portal(&self, index: Index) -> Option<&str> {
+        self.portals.get(&index).map(|layer| layer.as_str())
+    }
+
+    /// Draws the portal with the given ID above all other portals in its layer
+    ///
+    /// The portal stays above them until another one is raised. This has no effect on widgets
+    /// that aren't portals.
+    pub fn raise_portal(&mut self, index: Index) {
+        if !self.portals.contains_key(&index) {
+            return;
+        }
+
+        self.portal_raise_count += 1;
+        self.portal_raises.insert(index, self.portal_raise_count);
+        self.apply_portal_z_indices();
+    }
+
+    /// Returns the order the given portal was last raised in, or zero if it was never raised
+    fn portal_raise(&self, index: Index) -> u32 {
+        self.portal_raises.get(&index).copied().unwrap_or_default()
+    }
+
+    /// Returns the styles of the given widget, including any forwarded to it by its parent
+    ///
+    /// Forwarded styles take precedence over the widget's own (see
+    /// [`FragmentProps::forward_styles`](crate::FragmentProps::forward_styles)).
+    pub fn get_styles(&self, index: Index) -> Option<Style> {
+        let styles = self
+            .current_widgets
+            .get(index)?
+            .as_ref()?
+            .get_props()
+            .get_styles();
+        let forwarded = self
+            .tree
+            .parents
+            .get(&index)
+            .and_then(|parent| self.forwarded_styles.get(parent));
+
+        match (styles, forwarded) {
+            (Some(styles), Some(forwarded)) => Some(styles.merge(forwarded)),
+            (None, Some(forwarded)) => Some(forwarded.clone()),
+            (styles, None) => styles,
+        }
+    }
+
+    /// Sets the styles the given fragment forwards to its only child (or stops forwarding if `None`)
+    ///
+    /// Returns an error (and stops forwarding) if the fragment has more than one child.
+    pub(crate) fn set_forwarded_styles(
+        &mut self,
+        styles: Option<Style>,
+        index: Index,
+    ) -> Result<(), ForwardStylesError> {
+        let child_count = self.tree.children.get(&index).map_or(0, Vec::len);
+        match styles {
+            Some(_) if child_count > 1 => {
+                self.forwarded_styles.remove(&index);
+                return Err(ForwardStylesError {
+                    fragment: index,
+                    child_count,
+                });
+            }
+            Some(styles) => self.forwarded_styles.insert(index, styles),
+            None => self.forwarded_styles.remove(&index),
+        };
+        Ok(())
+    }
+
+    /// Marks the given widget as inactive (or active again)
+    ///
+    /// If `pauses`, its descendants are paused while it's inactive, as with inactive fragments.
+    pub(crate) fn set_inactive(&mut self, inactive: bool, pauses: bool, index: Index) {
+        if inactive {
+            self.inactive.insert(index, pauses);
+        } else {
+            self.inactive.remove(&index);
+        }
+    }
+
+    /// Caches the inactive and paused widgets, walking the subtree of each inactive widget once
+    ///
+    /// Returns the deferred effects of the widgets that are no longer paused, in the order their
+    /// dependencies changed.
+    pub(crate) fn update_inactive_widgets(&mut self) -> Vec<Effect> {
+        let mut inactive_widgets = HashSet::new();
+        let mut paused = HashSet::new();
+        for (&root, &pauses) in self.inactive.iter() {
+            // Skip the widgets whose subtree was already walked from an inactive ancestor
+            let walked = paused.contains(&root) || (!pauses && inactive_widgets.contains(&root));
+            if !self.tree.contains(root) || walked {
+                continue;
+            }
+            for widget_id in self.tree.flatten_node(root) {
+                inactive_widgets.insert(widget_id);
+                if pauses && widget_id != root {
+                    paused.insert(widget_id);
+                }
+            }
+        }
+        self.inactive_widgets = inactive_widgets;
+
+        self.paused
+            .set(paused)
+            .into_iter()
+            .filter(|(widget_id, _)| self.tree.contains(*widget_id))
+            .map(|(_, effect)| effect)
+            .collect()
+    }
+
+    /// Checks if the widget with the given ID is within an inactive fragment
+    ///
+    /// Such widgets stay mounted (keeping their state), but aren't laid out, drawn or focusable.
+    pub fn is_inactive(&self, index: Index) -> bool {
+        let mut current = Some(index);
+        while let Some(index) = current {
+            if self.inactive.contains_key(&index) {
+                return true;
+            }
+            current = self.tree.get_parent(index);
+        }
+        false
+    }
+
+    /// Marks the widget with the given ID as a portal into the given layer (or unmarks it if `None`)
+    pub fn set_portal(&mut self, layer: Option<String>, index: Index) {
+        match layer {
+            Some(layer) => {
+                self.portals.insert(index, layer);
+            }
+            None => {
+                self.portals.remove(&index);
+                self.portal_raises.remove(&index);
+            }
+        };
+    }
+
+    /// Returns the layer order of each portal in the node tree whose layer is registered
+    ///
+    /// Besides the widgets marked as portals, this includes those with a [`layer`](Style::layer)
+    /// style. Widgets in the content layer are drawn in place, so they aren't portals.
+    fn portal_orders(&self) -> HashMap<Index, i32> {
+        let styled = self.nodes.iter().filter_map(|(index, node)| {
+            match &node.as_ref()?.resolved_styles.layer {
+                StyleProp::Value(layer) => Some((index, layer)),
+                _ => None,
+            }
+        });
+        styled
+            .chain(self.portals.iter().map(|(index, layer)| (*index, layer)))
+            .filter(|(index, layer)| {
+                layer.as_str() != portal::CONTENT && self.node_tree.contains(*index)
+            })
+            .filter_map(|(index, layer)| Some((index, self.portal_layers.order(layer)?)))
+            .collect()
+    }
+
+    /// Sets the z-index of each node to the order of the portal layer it's drawn into (or zero)
+    ///
+    /// This ensures that widgets drawn into a layer are also hit above the rest of the tree. Raised
+    /// portals are offset by a fraction, so they're also hit above the others in their layer.
+    /// Sticky widgets are offset by half that fraction, so they're hit above the content
+    /// scrolling underneath them.
+    fn apply_portal_z_indices(&mut self) {
+        let root = match self.node_tree.root_node {
+            Some(root) => root,
+            None => return,
+        };
+        let orders = self.portal_orders();
+        // Sticky widgets are hit before the content scrolling underneath them, but never before
+        // the next raised portal (which is at least one raise step above)
+        let sticky_raise = 0.5 / (self.portal_raise_count + 1) as f32;
+
+        let mut stack = vec![(root, 0.0, false)];
+        while let Some((id, z_index, is_raised)) = stack.pop() {
+            let (mut z_index, mut is_raised) = match orders.get(&id) {
+                Some(order) => {
+                    let raise = self.portal_raise(id) as f32 / (self.portal_raise_count + 1) as f32;
+                    (*order as f32 + raise, false)
+                }
+                None => (z_index, is_raised),
+            };
+            if !is_raised && sticky::is_sticky(id, &self.nodes) {
+                z_index += sticky_raise;
+                is_raised = true;
+            }
+            if let Some(rect) = self.layout_cache.rect.get_mut(&id) {
+                rect.z_index = z_index;
+            }
+            if let Some(children) = self.node_tree.children.get(&id) {
+                stack.extend(children.iter().map(|child| (*child, z_index, is_raised)));
+            }
+        }
+    }
+
+    /// Checks if the widget with the given ID is disabled, either directly or through one of its ancestors
+    pub fn is_disabled(&self, index: Index) -> bool {
+        self.disabled.get(&index).copied().unwrap_or_default()
+    }
+
+    /// Checks if the widget with the given ID is hidden by its [`visibility`](Style::visibility) style
+    pub fn is_hidden(&self, index: Index) -> bool {
+        match self.nodes.get(index) {
+            Some(Some(node)) => {
+                node.resolved_styles
+                    .visibility
+                    .resolve_or(Visibility::Visible)
+                    == Visibility::Hidden
+            }
+            _ => false,
+        }
+    }
+
+    /// Resolves the disabled state of a widget
+    ///
+    /// Widgets with an unspecified state (`None`) inherit the state of their parent. Since parents
+    /// always render (and are therefore resolved) before their children, the parent's state is
+    /// already up-to-date by the time this is called.
+    ///
+    /// If the resolved state changed, the widget's descendants are resolved again as well, since
+    /// they aren't necessarily re-rendered along with it.
+    fn set_disabled(&mut self, disabled: Option<bool>, index: Index, parent: Option<Index>) {
+        let disabled =
+            disabled.unwrap_or_else(|| parent.map_or(false, |parent| self.is_disabled(parent)));
+        if self.disabled.insert(index, disabled) == Some(!disabled) {
+            self.update_disabled_descendants(index);
+        }
+    }
+
+    /// Resolves the disabled state of the descendants of the given widget again, marking those
+    /// whose state changed as dirty so that they re-render (such as to update their styles)
+    fn update_disabled_descendants(&mut self, index: Index) {
+        let descendants = DownwardIterator::new(&self.tree, Some(index), false).collect::<Vec<_>>();
+        for descendant in descendants {
+            let disabled = self
+                .current_widgets
+                .get(descendant)
+                .and_then(|widget| widget.as_ref())
+                .and_then(|widget| widget.get_props().get_disabled());
+            let parent = self.tree.get_parent(descendant);
+            let disabled =
+                disabled.unwrap_or_else(|| parent.map_or(false, |parent| self.is_disabled(parent)));
+            if self.disabled.insert(descendant, disabled) != Some(disabled) {
+                if let Ok(mut dirty_nodes) = self.dirty_nodes.lock() {
+                    dirty_nodes.insert(descendant);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_render_macros::{rsx, widget, WidgetProps};
+
+    use crate::render_command::RenderCommand;
+    use crate::styles::{PositionType, Style, StyleProp, Units};
+    use crate::{
+        mount_widget, portal, Binding, Bound, Children, Fragment, Gestures, Index, KayakContext,
+        MutableBound, PointerEvents, Widget,
+    };
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct KeyedListProps {
+        keys: Binding<Vec<&'static str>>,
+    }
+
+    #[widget]
+    fn KeyedList(props: KeyedListProps) {
+        context.bind(&props.keys);
+        for key in props.keys.get() {
+            context.add_keyed_widget(key, Fragment::default());
+        }
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct ExitingListProps {
+        keys: Binding<Vec<&'static str>>,
+        /// Whether the exiting children are done exiting
+        done: Binding<bool>,
+    }
+
+    #[widget]
+    fn ExitingList(props: ExitingListProps) {
+        context.bind(&props.keys);
+        for key in props.keys.get() {
+            context.add_keyed_widget(
+                key,
+                Exiting::constructor(ExitingProps {
+                    done: props.done.clone(),
+                }),
+            );
+        }
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct ExitingProps {
+        done: Binding<bool>,
+    }
+
+    #[widget]
+    fn Exiting(props: ExitingProps) {
+        context.bind(&props.done);
+        context.defer_unmount();
+        if context.is_exiting() && props.done.get() {
+            context.finish_exit();
+        }
+    }
+
+    /// Mounts an `ExitingList` as the root, returning the bindings to its keys and exit
+    fn mount_exiting(
+        context: &mut KayakContext,
+        keys: Vec<&'static str>,
+    ) -> (Binding<Vec<&'static str>>, Binding<bool>) {
+        let keys = Binding::new(keys);
+        let done = Binding::new(false);
+        let props = ExitingListProps {
+            keys: keys.clone(),
+            done: done.clone(),
+        };
+        mount_widget(context, ExitingList::constructor(props));
+        (keys, done)
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct ToggleProps {
+        #[prop_field(Disabled)]
+        disabled: Option<bool>,
+        /// If set, adds a nested `Toggle` with this disabled state (rather than a `Fragment`)
+        nested: Option<Option<bool>>,
+    }
+
+    #[widget]
+    fn Toggle(props: ToggleProps) {
+        match props.nested {
+            Some(disabled) => context.add_widget(
+                Toggle::constructor(ToggleProps {
+                    disabled,
+                    nested: None,
+                }),
+                0,
+            ),
+            None => context.add_widget(Fragment::default(), 0),
+        }
+    }
+
+    /// Mounts a `Toggle` as the root, returning the IDs of it and its descendants
+    fn mount_toggle(context: &mut KayakContext, props: ToggleProps) -> Vec<Index> {
+        mount_widget(context, Toggle::constructor(props));
+        context.widget_manager.tree.flatten()
+    }
+
+    /// Mounts a `KeyedList` as the root, returning the binding to its keys
+    fn mount(context: &mut KayakContext, keys: Vec<&'static str>) -> Binding<Vec<&'static str>> {
+        let keys = Binding::new(keys);
+        let props = KeyedListProps { keys: keys.clone() };
+        mount_widget(context, KeyedList::constructor(props));
+        keys
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct PanelProps {
+        #[prop_field(Styles)]
+        styles: Option<Style>,
+        #[prop_field(Children)]
+        children: Option<Children>,
+    }
+
+    #[widget]
+    fn Panel(props: PanelProps) {
+        rsx! {
+            <Fragment>
+                {children}
+            </Fragment>
+        }
+    }
+
+    /// Styles placing a panel of the given size over the top-left corner of its parent, drawn into
+    /// the given layer (or in place if `None`)
+    fn overlay(size: f32, layer: Option<&str>) -> Style {
+        Style {
+            render_command: StyleProp::Value(RenderCommand::Quad),
+            position_type: StyleProp::Value(PositionType::SelfDirected),
+            left: StyleProp::Value(Units::Pixels(0.0)),
+            top: StyleProp::Value(Units::Pixels(0.0)),
+            width: StyleProp::Value(Units::Pixels(size)),
+            height: StyleProp::Value(Units::Pixels(size)),
+            layer: layer.map_or(StyleProp::Default, |layer| {
+                StyleProp::Value(layer.to_string())
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a panel with the given [`overlay`] styles
+    fn panel(size: f32, layer: Option<&str>, children: Vec<Panel>) -> Panel {
+        styled_panel(overlay(size, layer), children)
+    }
+
+    fn styled_panel(styles: Style, children: Vec<Panel>) -> Panel {
+        Panel::constructor(PanelProps {
+            styles: Some(styles),
+            children: Some(Children::new(move |_, context| {
+                for (index, child) in children.iter().enumerate() {
+                    context.add_widget(child.clone(), index);
+                }
+                context.commit();
+            })),
+        })
+    }
+
+    /// Returns the ID of the panel of the given size, along with its z-index
+    fn sized(context: &KayakContext, size: f32) -> (Index, f32) {
+        let (id, rect) = context
+            .widget_manager
+            .layout_cache
+            .rect
+            .iter()
+            .find(|(_, rect)| rect.width == size)
+            .unwrap();
+        (*id, rect.z_index)
+    }
+
+    fn children(context: &KayakContext) -> Vec<Index> {
+        let tree = &context.widget_manager.tree;
+        tree.children
+            .get(&tree.root_node.unwrap())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn should_keep_keyed_widgets_when_reordered() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b", "c"]);
+        let before = children(&context);
+
+        keys.set(vec!["c", "a", "b"]);
+        context.render();
+        assert_eq!(vec![before[2], before[0], before[1]], children(&context));
+    }
+
+    #[test]
+    fn should_remount_removed_keys() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+
+        keys.set(vec!["a"]);
+        context.render();
+        assert_eq!(vec![before[0]], children(&context));
+
+        keys.set(vec!["a", "b"]);
+        context.render();
+        let after = children(&context);
+        assert_eq!(before[0], after[0]);
+        assert_ne!(before[1], after[1]);
+    }
+
+    #[test]
+    fn should_keep_exiting_widgets_in_place_until_they_finish() {
+        let mut context = KayakContext::new();
+        let (keys, done) = mount_exiting(&mut context, vec!["a", "b", "c"]);
+        let before = children(&context);
+
+        keys.set(vec!["a", "c"]);
+        context.render();
+        assert_eq!(before, children(&context));
+        assert!(context.widget_manager.is_exiting(before[1]));
+        assert!(!context.widget_manager.is_exiting(before[0]));
+
+        // The exiting widget finishes, after which its parent renders without it
+        done.set(true);
+        context.render();
+        context.render();
+        assert_eq!(vec![before[0], before[2]], children(&context));
+        assert!(context.widget_manager.exiting.is_empty());
+        assert_eq!(2, context.widget_manager.deferred_unmounts.len());
+    }
+
+    #[test]
+    fn should_stop_exiting_widgets_that_are_added_back() {
+        let mut context = KayakContext::new();
+        let (keys, _) = mount_exiting(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+
+        keys.set(vec!["a"]);
+        context.render();
+        assert!(context.widget_manager.is_exiting(before[1]));
+
+        keys.set(vec!["b", "a"]);
+        context.render();
+        assert_eq!(vec![before[1], before[0]], children(&context));
+        assert!(!context.widget_manager.is_exiting(before[1]));
+    }
+
+    #[test]
+    fn should_prune_keys_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        keys.set(vec!["a"]);
+        context.render();
+
+        let keyed_children = &context.widget_manager.keyed_children;
+        let root = context.widget_manager.tree.root_node.unwrap();
+        assert_eq!(1, keyed_children.len());
+        assert_eq!(vec!["a"], keyed_children[&root].keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn should_inherit_disabled_state() {
+        let mut context = KayakContext::new();
+        let ids = mount_toggle(
+            &mut context,
+            ToggleProps {
+                disabled: Some(true),
+                nested: Some(None),
+            },
+        );
+        assert_eq!(3, ids.len());
+        assert!(ids.iter().all(|id| context.is_disabled(*id)));
+    }
+
+    #[test]
+    fn should_override_inherited_disabled_state() {
+        let mut context = KayakContext::new();
+        let ids = mount_toggle(
+            &mut context,
+            ToggleProps {
+                disabled: Some(true),
+                nested: Some(Some(false)),
+            },
+        );
+        assert!(context.is_disabled(ids[0]));
+        assert!(!context.is_disabled(ids[1]));
+        assert!(!context.is_disabled(ids[2]));
+    }
+
+    #[test]
+    fn should_disable_descendants_that_are_not_re_rendered() {
+        let mut context = KayakContext::new();
+        let ids = mount_toggle(
+            &mut context,
+            ToggleProps {
+                disabled: None,
+                nested: Some(None),
+            },
+        );
+        assert!(!context.is_disabled(ids[2]));
+
+        // Update the nested toggle in place, without rendering its child
+        let widget_manager = &mut context.widget_manager;
+        widget_manager.dirty_nodes.lock().unwrap().clear();
+        let toggle = Toggle::constructor(ToggleProps {
+            disabled: Some(true),
+            nested: None,
+        });
+        widget_manager.create_widget(0, toggle, Some(ids[0]));
+
+        assert!(context.is_disabled(ids[1]));
+        assert!(context.is_disabled(ids[2]));
+        let dirty_nodes = context.widget_manager.dirty_nodes.lock().unwrap();
+        assert!(dirty_nodes.contains(&ids[2]));
+    }
+
+    #[test]
+    fn should_prune_disabled_state_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+        keys.set(vec!["a"]);
+        context.render();
+
+        assert!(context.widget_manager.disabled.contains_key(&before[0]));
+        assert!(!context.widget_manager.disabled.contains_key(&before[1]));
+    }
+
+    #[test]
+    fn should_prune_forwarded_styles_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+        for id in &before {
+            context
+                .widget_manager
+                .set_forwarded_styles(Some(crate::styles::Style::default()), *id)
+                .unwrap();
+        }
+        keys.set(vec!["a"]);
+        context.render();
+
+        assert!(!context
+            .widget_manager
+            .forwarded_styles
+            .contains_key(&before[1]));
+    }
+
+    #[test]
+    fn should_prune_gestures_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+        context.set_gestures(Gestures::PAN, before[0]);
+        context.set_gestures(Gestures::PAN, before[1]);
+        keys.set(vec!["a"]);
+        context.render();
+
+        assert_eq!(Gestures::PAN, context.get_gestures(before[0]));
+        assert!(!context.widget_manager.gestures.contains_key(&before[1]));
+    }
+
+    #[test]
+    fn should_prune_measured_sizes_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+        for id in &before {
+            context
+                .widget_manager
+                .layout_cache
+                .measured_size
+                .insert(*id, crate::layout_cache::IntrinsicSize::default());
+        }
+        keys.set(vec!["a"]);
+        context.render();
+
+        // The remaining widget is re-rendered without any text, so it isn't measured either
+        let measured_size = &context.widget_manager.layout_cache.measured_size;
+        assert!(!measured_size.contains_key(&before[0]));
+        assert!(!measured_size.contains_key(&before[1]));
+    }
+
+    #[test]
+    fn should_prune_raises_of_removed_portals() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b"]);
+        let before = children(&context);
+        for id in &before {
+            context.set_portal(Some(crate::portal::WINDOW), *id);
+            context.raise_portal(*id);
+        }
+        keys.set(vec!["a"]);
+        context.render();
+
+        let portal_raises = &context.widget_manager.portal_raises;
+        assert!(portal_raises.contains_key(&before[0]));
+        assert!(!portal_raises.contains_key(&before[1]));
+    }
+
+    #[test]
+    fn should_draw_and_hit_layered_widgets_above_content() {
+        let mut context = KayakContext::new();
+        // The content is declared later and is deeper, so it would otherwise be hit first
+        let root = panel(
+            100.0,
+            None,
+            vec![
+                panel(40.0, Some(portal::WINDOW), vec![]),
+                panel(60.0, None, vec![panel(50.0, None, vec![])]),
+            ],
+        );
+        mount_widget(&mut context, root);
+
+        let (layered, layered_z_index) = sized(&context, 40.0);
+        let (_, content_z_index) = sized(&context, 50.0);
+        assert!(layered_z_index > content_z_index);
+        assert_eq!(Some(layered), context.hit_test((20.0, 20.0)));
+    }
+
+    #[test]
+    fn nested_layers_should_keep_their_order() {
+        let mut context = KayakContext::new();
+        let root = panel(
+            100.0,
+            None,
+            vec![
+                panel(
+                    80.0,
+                    Some(portal::MODAL),
+                    vec![
+                        panel(40.0, Some(portal::TOOLTIP), vec![]),
+                        panel(60.0, Some(portal::CONTENT), vec![]),
+                    ],
+                ),
+                panel(70.0, Some(portal::WINDOW), vec![]),
+            ],
+        );
+        mount_widget(&mut context, root);
+
+        let (tooltip, tooltip_z_index) = sized(&context, 40.0);
+        let (modal, modal_z_index) = sized(&context, 80.0);
+        let (_, content_z_index) = sized(&context, 60.0);
+        let (_, window_z_index) = sized(&context, 70.0);
+        assert!(tooltip_z_index > modal_z_index);
+        assert!(modal_z_index > window_z_index);
+        // Widgets in the content layer are drawn along with their parent
+        assert_eq!(modal_z_index, content_z_index);
+
+        assert_eq!(Some(tooltip), context.hit_test((20.0, 20.0)));
+        assert_eq!(Some(modal), context.hit_test((65.0, 65.0)));
+    }
+
+    #[test]
+    fn should_hit_through_widgets_without_pointer_events() {
+        let mut context = KayakContext::new();
+        // Both overlays are in a higher layer, so they'd otherwise be hit above the content
+        let root = panel(
+            100.0,
+            None,
+            vec![
+                panel(60.0, None, vec![panel(50.0, None, vec![])]),
+                styled_panel(
+                    Style {
+                        pointer_events: StyleProp::Value(PointerEvents::None),
+                        ..overlay(45.0, Some(portal::WINDOW))
+                    },
+                    vec![],
+                ),
+                styled_panel(
+                    Style {
+                        pointer_events: StyleProp::Value(PointerEvents::ChildrenOnly),
+                        ..overlay(40.0, Some(portal::WINDOW))
+                    },
+                    vec![panel(30.0, None, vec![])],
+                ),
+            ],
+        );
+        mount_widget(&mut context, root);
+
+        let (content, _) = sized(&context, 50.0);
+        let (interactive, _) = sized(&context, 30.0);
+        assert_eq!(Some(interactive), context.hit_test((20.0, 20.0)));
+        assert_eq!(Some(content), context.hit_test((35.0, 35.0)));
+    }
+
+    #[test]
+    fn should_prune_overviews_of_removed_widgets() {
+        let mut context = KayakContext::new();
+        let keys = mount(&mut context, vec!["a", "b", "c"]);
+        let before = children(&context);
+        // The first child shows an overview of the second, which shows one of the third
+        context.set_overview(Some(before[1]), before[0]);
+        context.set_overview(Some(before[2]), before[1]);
+        keys.set(vec!["a", "b"]);
+        context.render();
+
+        let overviews = &context.widget_manager.overviews;
+        assert_eq!(Some(&before[1]), overviews.get(&before[0]));
+        assert!(!overviews.contains_key(&before[1]));
+        assert!(!context
+            .widget_manager
+            .overview_content
+            .contains_key(&before[1]));
+    }
+
+    #[test]
+    fn should_treat_unresolved_visibility_as_visible() {
+        use crate::node::NodeBuilder;
+        use crate::styles::{Style, StyleProp, Visibility};
+
+        let mut context = KayakContext::new();
+        let manager = &mut context.widget_manager;
+        let node = |visibility| {
+            let styles = Style {
+                visibility,
+                ..Default::default()
+            };
+            Some(NodeBuilder::empty().with_styles(styles, None).build())
+        };
+        let inherited = manager.nodes.insert(node(StyleProp::Inherit));
+        let unset = manager.nodes.insert(node(StyleProp::Unset));
+        let hidden = manager
+            .nodes
+            .insert(node(StyleProp::Value(Visibility::Hidden)));
+
+        assert!(!manager.is_hidden(inherited));
+        assert!(!manager.is_hidden(unset));
+        assert!(manager.is_hidden(hidden));
+    }
 }
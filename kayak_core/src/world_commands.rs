@@ -0,0 +1,137 @@
+use bevy::ecs::event::{Event, Events};
+use bevy::prelude::World;
+
+type WorldCommand = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// A queue of commands to apply to the Bevy `World` once the current frame has been rendered
+///
+/// This is accessed via [`KayakContextRef::world_commands`](crate::KayakContextRef::world_commands),
+/// which allows event handlers (and widgets) to send Bevy events or mutate resources without having
+/// to thread channels through to a Bevy system.
+///
+/// Commands are applied in the order they were queued, after the render that follows them has been
+/// committed. This means they run after every widget has rendered, and that any state set alongside
+/// them has already been applied to the UI.
+///
+/// # Examples
+///
+/// ```ignore
+/// struct MyEvent;
+/// struct Score(u32);
+///
+/// let on_event = OnEvent::new(|context, event| match event.event_type {
+///     EventType::Click(..) => {
+///         let commands = context.world_commands();
+///         commands.send_event(MyEvent);
+///         commands.add(|world| {
+///             if let Some(mut score) = world.get_resource_mut::<Score>() {
+///                 score.0 += 1;
+///             }
+///         });
+///     }
+///     _ => {}
+/// });
+/// ```
+#[derive(Default)]
+pub struct WorldCommands {
+    queue: Vec<WorldCommand>,
+}
+
+impl std::fmt::Debug for WorldCommands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorldCommands")
+            .field("len", &self.queue.len())
+            .finish()
+    }
+}
+
+impl WorldCommands {
+    /// Queue a command that is given exclusive access to the `World`
+    pub fn add<F: FnOnce(&mut World) + Send + Sync + 'static>(&mut self, command: F) {
+        self.queue.push(Box::new(command));
+    }
+
+    /// Queue a Bevy event to be sent
+    ///
+    /// If the event type hasn't been registered (via `App::add_event`), the event is dropped.
+    pub fn send_event<E: Event>(&mut self, event: E) {
+        self.add(move |world| {
+            if let Some(mut events) = world.get_resource_mut::<Events<E>>() {
+                events.send(event);
+            }
+        });
+    }
+
+    /// Queue a resource to be inserted, replacing any existing resource of the same type
+    pub fn insert_resource<R: Send + Sync + 'static>(&mut self, resource: R) {
+        self.add(move |world| world.insert_resource(resource));
+    }
+
+    /// Returns true if there are no commands waiting to be applied
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Apply all queued commands to the given `World`, in the order they were queued
+    pub(crate) fn apply(&mut self, world: &mut World) {
+        for command in std::mem::take(&mut self.queue) {
+            command(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::World;
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{mount_widget, Binding, Bound, KayakContext, MutableBound, Widget};
+
+    struct Score(u32);
+
+    #[widget]
+    fn Scorer() {
+        context.world_commands().insert_resource(Score(1));
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct CheckerProps {
+        /// Whether the queued resource was in the world while rendering
+        applied: Binding<Option<bool>>,
+    }
+
+    #[widget]
+    fn Checker(props: CheckerProps) {
+        let applied = context
+            .get_global::<World>()
+            .unwrap()
+            .get_resource::<Score>()
+            .is_some();
+        props.applied.set(Some(applied));
+    }
+
+    #[widget]
+    fn Scoreboard(props: CheckerProps) {
+        context.add_widget(Scorer::default(), 0);
+        context.add_widget(Checker::constructor(props.clone()), 1);
+    }
+
+    #[test]
+    fn should_apply_commands_after_the_render() {
+        let mut context = KayakContext::new();
+        context.set_global(World::new());
+        let props = CheckerProps::default();
+        let applied = props.applied.clone();
+        mount_widget(&mut context, Scoreboard::constructor(props));
+
+        // Queued by a widget, but not applied while its siblings render or once the render is done
+        assert_eq!(Some(false), applied.get());
+        let mut world = context.remove_global::<World>().unwrap();
+        assert!(world.get_resource::<Score>().is_none());
+        assert!(!context.world_commands().is_empty());
+
+        context.apply_world_commands(&mut world);
+        assert_eq!(1, world.get_resource::<Score>().unwrap().0);
+        assert!(context.world_commands().is_empty());
+    }
+}
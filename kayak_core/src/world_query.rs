@@ -0,0 +1,138 @@
+use std::any::Any;
+
+use bevy::ecs::system::{SystemParam, SystemParamFetch, SystemState};
+use bevy::prelude::World;
+
+/// A type-erased query of the Bevy `World` that can be re-run by the context
+pub(crate) trait WorldQuery: Send + Sync {
+    /// Re-runs the query, returning true if the projected value changed since the last time
+    fn update(&mut self, world: &mut World) -> bool;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// The projection of a `SystemParam` that a widget is subscribed to, along with its last known value
+///
+/// See [`KayakContextRef::use_query`](crate::KayakContextRef::use_query).
+///
+/// The `SystemState` is kept between runs, so change detection (such as `Changed<T>` filters or
+/// `Res::is_changed`) is relative to the previous time the query was run.
+pub(crate) struct ProjectedQuery<P: SystemParam + 'static, T, F> {
+    state: SystemState<P>,
+    projection: F,
+    value: T,
+}
+
+impl<P, T, F> ProjectedQuery<P, T, F>
+where
+    P: SystemParam + 'static,
+    T: Clone + PartialEq + Send + Sync + 'static,
+    F: FnMut(<P::Fetch as SystemParamFetch<'_, '_>>::Item) -> T + Send + Sync + 'static,
+{
+    /// Creates a query with the given projection, running it once to get its current value
+    pub fn new(world: &mut World, mut projection: F) -> Self {
+        let mut state = SystemState::new(world);
+        let value = Self::run(&mut state, &mut projection, world);
+        Self {
+            state,
+            projection,
+            value,
+        }
+    }
+
+    /// Replaces the projection and re-runs it, returning its current value
+    ///
+    /// This is called on every render, since the projection may capture values that change
+    /// between renders.
+    pub fn replace(&mut self, world: &mut World, projection: F) -> T {
+        self.projection = projection;
+        self.value = Self::run(&mut self.state, &mut self.projection, world);
+        self.value.clone()
+    }
+
+    /// Returns the last known value of the projection
+    pub fn value(&self) -> T {
+        self.value.clone()
+    }
+
+    fn run(state: &mut SystemState<P>, projection: &mut F, world: &mut World) -> T {
+        let value = projection(state.get_mut(world));
+        state.apply(world);
+        value
+    }
+}
+
+impl<P, T, F> WorldQuery for ProjectedQuery<P, T, F>
+where
+    P: SystemParam + 'static,
+    T: Clone + PartialEq + Send + Sync + 'static,
+    F: FnMut(<P::Fetch as SystemParamFetch<'_, '_>>::Item) -> T + Send + Sync + 'static,
+{
+    fn update(&mut self, world: &mut World) -> bool {
+        let value = Self::run(&mut self.state, &mut self.projection, world);
+        if value == self.value {
+            return false;
+        }
+
+        self.value = value;
+        true
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Res, World};
+    use kayak_render_macros::{widget, WidgetProps};
+
+    use crate::{mount_widget, Binding, Bound, KayakContext, MutableBound, Widget};
+
+    struct Stats {
+        health: u32,
+        mana: u32,
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct HealthBarProps {
+        /// The health shown by the last render
+        shown: Binding<u32>,
+    }
+
+    #[widget]
+    fn HealthBar(props: HealthBarProps) {
+        let health = context.use_query::<Res<Stats>, _, _>(|stats| stats.health);
+        props.shown.set(health);
+    }
+
+    #[test]
+    fn should_only_re_render_when_the_projection_changes() {
+        let mut context = KayakContext::new();
+        let mut world = World::new();
+        world.insert_resource(Stats {
+            health: 10,
+            mana: 5,
+        });
+        context.set_global(world);
+        let props = HealthBarProps::default();
+        let shown = props.shown.clone();
+        mount_widget(&mut context, HealthBar::constructor(props));
+        assert_eq!(10, shown.get());
+        let mut world = context.remove_global::<World>().unwrap();
+
+        // Data outside of the projection doesn't affect the widget
+        world.resource_mut::<Stats>().mana = 3;
+        context.update_world_queries(&mut world);
+        assert!(!context.is_dirty());
+
+        world.resource_mut::<Stats>().health = 7;
+        context.update_world_queries(&mut world);
+        assert!(context.is_dirty());
+
+        context.set_global(world);
+        context.render();
+        assert_eq!(7, shown.get());
+    }
+}
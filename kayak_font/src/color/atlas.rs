@@ -0,0 +1,537 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::{ColorFont, ColorGlyphImage, KayakFont, TextLayout};
+
+/// The region of a glyph within a [`ColorAtlas`] (in pixels).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ColorAtlasRegion {
+    /// The top-left corner of the glyph.
+    pub position: (u32, u32),
+    pub size: (u32, u32),
+}
+
+/// A row of glyphs within the atlas.
+#[derive(Debug, Clone, Copy)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// The width taken up by the glyphs in this row so far
+    width: u32,
+}
+
+/// An RGBA image containing rasterized [color glyphs](ColorFont).
+///
+/// Glyphs are rasterized on demand, the first time they're [inserted](Self::insert) at a given
+/// size, or [queued](Self::queue_layout) to be rasterized later (such as by a render backend). The atlas grows as glyphs are added, up to [`ColorAtlas::MAX_SIZE`] pixels along either
+/// side. Once a glyph doesn't fit anymore, the atlas is [full](Self::is_full) and should be
+/// [cleared](Self::clear), after which the glyphs that are still displayed can be queued again.
+///
+/// Renderers should upload the atlas again whenever its [version](Self::version) changes.
+pub struct ColorAtlas {
+    size: (u32, u32),
+    data: Vec<u8>,
+    /// The regions of each glyph, by the size it was rasterized at. Glyphs that couldn't be
+    /// rasterized (or didn't fit) have no region.
+    glyphs: HashMap<(u64, char), BTreeMap<u16, Option<ColorAtlasRegion>>>,
+    /// The glyphs waiting to be rasterized, along with the size to rasterize them at
+    queued: Vec<(Arc<ColorFont>, char, u16)>,
+    shelves: Vec<Shelf>,
+    /// Set when a glyph didn't fit into the atlas
+    is_full: bool,
+    version: u64,
+}
+
+impl ColorAtlas {
+    /// The initial width and height of the atlas (in pixels).
+    pub const INITIAL_SIZE: u32 = 256;
+    /// The maximum width and height of the atlas (in pixels).
+    pub const MAX_SIZE: u32 = 4096;
+    /// The empty space left around each glyph, so that they don't bleed into each other.
+    const PADDING: u32 = 1;
+
+    pub fn new() -> Self {
+        let size = (Self::INITIAL_SIZE, Self::INITIAL_SIZE);
+        Self {
+            size,
+            data: vec![0; (size.0 * size.1 * 4) as usize],
+            glyphs: HashMap::new(),
+            queued: Vec::new(),
+            shelves: Vec::new(),
+            is_full: false,
+            version: 0,
+        }
+    }
+
+    /// The width and height of the atlas (in pixels).
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// The pixels of the atlas, row by row from the top, as straight RGBA.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// A number that changes whenever the contents of the atlas change.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the size (in pixels per em) glyphs are rasterized at for the given font size.
+    ///
+    /// Glyphs are rasterized at twice the font size, so that they stay sharp on high-DPI
+    /// displays, rounded up so that similar font sizes share the same glyphs.
+    pub fn pixels_per_em(font_size: f32) -> u16 {
+        ((font_size * 2.0 / 16.0).ceil() * 16.0).clamp(16.0, 256.0) as u16
+    }
+
+    /// Returns the region of the given glyph, rasterized at the size closest to the given font
+    /// size (in pixels).
+    ///
+    /// Returns `None` if the glyph hasn't been [inserted](Self::insert) at any size, or it
+    /// couldn't be added.
+    pub fn get(&self, font: &ColorFont, c: char, font_size: f32) -> Option<ColorAtlasRegion> {
+        let sizes = self.glyphs.get(&(font.id(), c))?;
+        let size = font.strike_size(c, Self::pixels_per_em(font_size));
+        // Prefer scaling down a larger glyph over scaling up a smaller one
+        let (_, region) = sizes
+            .range(size..)
+            .next()
+            .or_else(|| sizes.range(..size).next_back())?;
+        *region
+    }
+
+    /// Rasterizes the given glyph for the given font size (in pixels), if it isn't already.
+    ///
+    /// Returns the region of the glyph, or `None` if it couldn't be rasterized or added.
+    pub fn insert(
+        &mut self,
+        font: &ColorFont,
+        c: char,
+        font_size: f32,
+    ) -> Option<ColorAtlasRegion> {
+        let size = font.strike_size(c, Self::pixels_per_em(font_size));
+        self.insert_with(font, c, size, ColorFont::rasterize)
+    }
+
+    /// Rasterizes the given glyph at the given size (in pixels per em) with the given function, if
+    /// it isn't already.
+    fn insert_with(
+        &mut self,
+        font: &ColorFont,
+        c: char,
+        size: u16,
+        rasterize: impl FnOnce(&ColorFont, char, u16) -> Option<ColorGlyphImage>,
+    ) -> Option<ColorAtlasRegion> {
+        if let Some(region) = self
+            .glyphs
+            .get(&(font.id(), c))
+            .and_then(|sizes| sizes.get(&size))
+        {
+            return *region;
+        }
+
+        let mut is_full = false;
+        let region = rasterize(font, c, size).and_then(|image| {
+            // Images of the wrong size would overflow their region
+            if image.data.len() != (image.width * image.height * 4) as usize {
+                return None;
+            }
+
+            let position = self.allocate(image.width, image.height);
+            is_full = position.is_none() && Self::fits(image.width, image.height);
+            let position = position?;
+            let row_size = (image.width * 4) as usize;
+            for row in 0..image.height {
+                let start = (((position.1 + row) * self.size.0 + position.0) * 4) as usize;
+                let source = row as usize * row_size;
+                self.data[start..start + row_size]
+                    .copy_from_slice(&image.data[source..source + row_size]);
+            }
+            self.version += 1;
+            Some(ColorAtlasRegion {
+                position,
+                size: (image.width, image.height),
+            })
+        });
+        if is_full {
+            // Not remembered, so that the glyph is added again once the atlas is cleared
+            self.is_full = true;
+            return None;
+        }
+        self.glyphs
+            .entry((font.id(), c))
+            .or_default()
+            .insert(size, region);
+        region
+    }
+
+    /// Rasterizes the color glyphs of the given text layout, measured with the given font.
+    ///
+    /// # Arguments
+    ///
+    /// * `font`: The font the layout was [measured](KayakFont::measure) with.
+    /// * `layout`: The measured layout.
+    /// * `scale`: The scale the text is drawn at, for text that's transformed after it's measured.
+    ///
+    pub fn insert_layout(&mut self, font: &KayakFont, layout: &TextLayout, scale: f32) {
+        let fallback = match font.fallback() {
+            Some(fallback) => fallback,
+            None => return,
+        };
+        let font_size = layout.properties().font_size * scale;
+        for glyph in layout.glyphs() {
+            if font.is_color_glyph(glyph.content) {
+                self.insert(fallback, glyph.content, font_size);
+            }
+        }
+    }
+
+    /// Queues the color glyphs of the given text layout that aren't in the atlas yet, to be
+    /// rasterized by [`rasterize_queued`](Self::rasterize_queued).
+    ///
+    /// This takes the same arguments as [`insert_layout`](Self::insert_layout).
+    pub fn queue_layout(&mut self, font: &KayakFont, layout: &TextLayout, scale: f32) {
+        let fallback = match font.fallback() {
+            Some(fallback) => fallback,
+            None => return,
+        };
+        let font_size = layout.properties().font_size * scale;
+        for glyph in layout.glyphs() {
+            let c = glyph.content;
+            if !font.is_color_glyph(c) {
+                continue;
+            }
+            let size = fallback.strike_size(c, Self::pixels_per_em(font_size));
+            let is_known = self
+                .glyphs
+                .get(&(fallback.id(), c))
+                .map_or(false, |sizes| sizes.contains_key(&size));
+            let is_queued = self.queued.iter().any(|(font, queued_c, queued_size)| {
+                font.id() == fallback.id() && *queued_c == c && *queued_size == size
+            });
+            if !is_known && !is_queued {
+                self.queued.push((fallback.clone(), c, size));
+            }
+        }
+    }
+
+    /// Returns the number of glyph rasterizations in the atlas (counting each size separately).
+    pub fn len(&self) -> usize {
+        self.glyphs
+            .values()
+            .flat_map(|sizes| sizes.values())
+            .filter(|region| region.is_some())
+            .count()
+    }
+
+    /// Returns true if the atlas doesn't contain any glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if a glyph didn't fit into the atlas since it was last [cleared](Self::clear).
+    ///
+    /// Such glyphs aren't drawn. Clearing the atlas and queuing the glyphs that are still displayed
+    /// makes room for them, by dropping the glyphs that aren't displayed anymore.
+    pub fn is_full(&self) -> bool {
+        self.is_full
+    }
+
+    /// Returns true if there are glyphs waiting to be rasterized.
+    pub fn has_queued(&self) -> bool {
+        !self.queued.is_empty()
+    }
+
+    /// Rasterizes the queued glyphs with the given function, adding them to the atlas.
+    ///
+    /// The function is given the font, the glyph and the size to rasterize it at (in pixels per
+    /// em), returning the glyph's image (or `None` if it can't be rasterized).
+    pub fn rasterize_queued(
+        &mut self,
+        mut rasterize: impl FnMut(&ColorFont, char, u16) -> Option<ColorGlyphImage>,
+    ) {
+        for (font, c, size) in std::mem::take(&mut self.queued) {
+            self.insert_with(&font, c, size, &mut rasterize);
+        }
+    }
+
+    /// Removes all glyphs from the atlas.
+    pub fn clear(&mut self) {
+        *self = Self {
+            version: self.version + 1,
+            ..Self::new()
+        };
+    }
+
+    /// Returns true if a glyph of the given size fits into an empty atlas.
+    fn fits(width: u32, height: u32) -> bool {
+        width + Self::PADDING <= Self::MAX_SIZE && height + Self::PADDING <= Self::MAX_SIZE
+    }
+
+    /// Clears the atlas if it's [full](Self::is_full), queuing the color glyphs of the given text
+    /// layouts again.
+    ///
+    /// This should be given every layout that's still displayed (with the same arguments as
+    /// [`queue_layout`](Self::queue_layout)), so that only the glyphs that aren't displayed
+    /// anymore are dropped. Returns true if the atlas was cleared, in which case the queued glyphs
+    /// need to be [rasterized](Self::rasterize_queued) again.
+    pub fn evict_unused<'a>(
+        &mut self,
+        layouts: impl IntoIterator<Item = (&'a KayakFont, &'a TextLayout, f32)>,
+    ) -> bool {
+        if !self.is_full {
+            return false;
+        }
+
+        self.clear();
+        for (font, layout, scale) in layouts {
+            self.queue_layout(font, layout, scale);
+        }
+        true
+    }
+
+    /// Finds a free position for a glyph of the given size, growing the atlas if needed.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if !Self::fits(width, height) {
+            return None;
+        }
+        let (padded_width, padded_height) = (width + Self::PADDING, height + Self::PADDING);
+
+        loop {
+            // Use the shortest row the glyph fits in
+            let atlas_width = self.size.0;
+            let shelf = self
+                .shelves
+                .iter_mut()
+                .filter(|shelf| {
+                    shelf.height >= padded_height && shelf.width + padded_width <= atlas_width
+                })
+                .min_by_key(|shelf| shelf.height);
+            if let Some(shelf) = shelf {
+                let position = (shelf.width, shelf.y);
+                shelf.width += padded_width;
+                return Some(position);
+            }
+
+            let bottom = self
+                .shelves
+                .last()
+                .map_or(0, |shelf| shelf.y + shelf.height);
+            if bottom + padded_height <= self.size.1 && padded_width <= self.size.0 {
+                self.shelves.push(Shelf {
+                    y: bottom,
+                    height: padded_height,
+                    width: padded_width,
+                });
+                return Some((0, bottom));
+            }
+
+            if !self.grow() {
+                return None;
+            }
+        }
+    }
+
+    /// Doubles the shorter side of the atlas, keeping its contents where they are.
+    ///
+    /// Returns false if the atlas is already at its maximum size.
+    fn grow(&mut self) -> bool {
+        let (width, height) = self.size;
+        let size = if height < width {
+            (width, height * 2)
+        } else {
+            (width * 2, height)
+        };
+        if size.0 > Self::MAX_SIZE || size.1 > Self::MAX_SIZE {
+            return false;
+        }
+
+        let mut data = vec![0; (size.0 * size.1 * 4) as usize];
+        let row_size = (width * 4) as usize;
+        for row in 0..height as usize {
+            let start = row * (size.0 * 4) as usize;
+            data[start..start + row_size]
+                .copy_from_slice(&self.data[row * row_size..(row + 1) * row_size]);
+        }
+        self.size = size;
+        self.data = data;
+        self.version += 1;
+        true
+    }
+}
+
+impl Default for ColorAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ColorAtlas {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColorAtlas")
+            .field("size", &self.size)
+            .field("glyphs", &self.glyphs)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorAtlas, ColorAtlasRegion};
+    use crate::{ColorFont, ColorGlyphImage};
+
+    fn make_font() -> ColorFont {
+        let data = std::fs::read("../resources/Roboto-Regular.ttf").unwrap();
+        ColorFont::from_outlines(data, &['A', 'B', '.'], [255, 0, 0, 255])
+    }
+
+    /// Rasterizes every glyph as an opaque square of the given size
+    fn square(size: u32) -> impl Fn(&ColorFont, char, u16) -> Option<ColorGlyphImage> {
+        move |_, _, _| {
+            Some(ColorGlyphImage {
+                width: size,
+                height: size,
+                data: vec![255; (size * size * 4) as usize],
+            })
+        }
+    }
+
+    #[test]
+    fn should_rasterize_glyphs_once_per_size() {
+        let font = make_font();
+        let mut atlas = ColorAtlas::new();
+
+        let region = atlas.insert(&font, 'A', 14.0).unwrap();
+        let version = atlas.version();
+        assert_eq!(Some(region), atlas.insert(&font, 'A', 14.0));
+        // Similar sizes share the same glyph
+        assert_eq!(Some(region), atlas.insert(&font, 'A', 15.0));
+        assert_eq!(version, atlas.version());
+
+        let larger = atlas.insert(&font, 'A', 40.0).unwrap();
+        assert_ne!(region, larger);
+        assert!(larger.size.0 > region.size.0);
+        assert_eq!(Some(larger), atlas.get(&font, 'A', 40.0));
+        // Sizes that weren't rasterized use the closest glyph, preferring a larger one
+        assert_eq!(Some(larger), atlas.get(&font, 'A', 20.0));
+        assert_eq!(Some(larger), atlas.get(&font, 'A', 200.0));
+
+        assert_eq!(None, atlas.get(&font, 'B', 14.0));
+        assert_eq!(None, atlas.insert(&font, 'C', 14.0));
+        // Glyphs that couldn't be rasterized aren't counted
+        assert_eq!(2, atlas.len());
+
+        atlas.clear();
+        assert!(atlas.is_empty());
+    }
+
+    #[test]
+    fn should_copy_glyphs_into_their_region() {
+        let font = make_font();
+        let mut atlas = ColorAtlas::new();
+        let image = font
+            .rasterize('B', ColorAtlas::pixels_per_em(14.0))
+            .unwrap();
+        let region = atlas.insert(&font, 'B', 14.0).unwrap();
+        assert_eq!((image.width, image.height), region.size);
+
+        let (atlas_width, _) = atlas.size();
+        for row in 0..image.height {
+            let start =
+                (((region.position.1 + row) * atlas_width + region.position.0) * 4) as usize;
+            let source = (row * image.width * 4) as usize;
+            let len = (image.width * 4) as usize;
+            assert_eq!(
+                &image.data[source..source + len],
+                &atlas.data()[start..start + len]
+            );
+        }
+    }
+
+    #[test]
+    fn should_pack_glyphs_without_overlapping() {
+        let font = make_font();
+        let mut atlas = ColorAtlas::new();
+        let regions: Vec<ColorAtlasRegion> = [14.0, 30.0, 60.0, 100.0, 128.0]
+            .iter()
+            .flat_map(|size| {
+                ['A', 'B', '.']
+                    .iter()
+                    .map(|c| atlas.insert(&font, *c, *size).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // The largest glyphs don't all fit in the initial size
+        assert!(atlas.size().0 > ColorAtlas::INITIAL_SIZE);
+        assert_eq!(
+            (atlas.size().0 * atlas.size().1 * 4) as usize,
+            atlas.data().len()
+        );
+
+        for (index, a) in regions.iter().enumerate() {
+            assert!(a.position.0 + a.size.0 <= atlas.size().0);
+            assert!(a.position.1 + a.size.1 <= atlas.size().1);
+            for b in &regions[index + 1..] {
+                let overlaps = a.position.0 < b.position.0 + b.size.0
+                    && b.position.0 < a.position.0 + a.size.0
+                    && a.position.1 < b.position.1 + b.size.1
+                    && b.position.1 < a.position.1 + a.size.1;
+                assert!(!overlaps, "{:?} overlaps {:?}", a, b);
+            }
+        }
+
+        // Growing the atlas keeps the glyphs already in it
+        let first = regions[0];
+        let image = font
+            .rasterize('A', ColorAtlas::pixels_per_em(14.0))
+            .unwrap();
+        let start = ((first.position.1 * atlas.size().0 + first.position.0) * 4) as usize;
+        let len = (image.width * 4) as usize;
+        assert_eq!(&image.data[..len], &atlas.data()[start..start + len]);
+    }
+
+    #[test]
+    fn should_forget_glyphs_when_cleared() {
+        let font = make_font();
+        let mut atlas = ColorAtlas::new();
+        atlas.insert(&font, 'A', 14.0);
+        let version = atlas.version();
+
+        atlas.clear();
+        assert_eq!(None, atlas.get(&font, 'A', 14.0));
+        assert!(atlas.version() > version);
+        assert!(atlas.data().iter().all(|channel| *channel == 0));
+    }
+
+    #[test]
+    fn should_become_full_once_a_glyph_does_not_fit() {
+        let font = make_font();
+        let mut atlas = ColorAtlas::new();
+        // Glyphs that could never fit don't make the atlas full
+        let too_large = square(ColorAtlas::MAX_SIZE);
+        assert_eq!(None, atlas.insert_with(&font, 'A', 16, too_large));
+        assert!(!atlas.is_full());
+
+        // Four of these fill the largest atlas
+        let quarter = square(ColorAtlas::MAX_SIZE / 2 - 1);
+        for c in ['A', 'B', 'C', 'D'] {
+            assert!(atlas.insert_with(&font, c, 32, &quarter).is_some());
+        }
+        assert!(!atlas.is_full());
+        assert_eq!(None, atlas.insert_with(&font, 'E', 32, &quarter));
+        assert!(atlas.is_full());
+        assert_eq!(4, atlas.len());
+
+        // Evicting makes room again, keeping only the glyphs that are queued again
+        assert!(atlas.evict_unused(std::iter::empty()));
+        assert!(!atlas.is_full());
+        assert!(atlas.is_empty());
+        assert!(atlas.insert_with(&font, 'E', 32, &quarter).is_some());
+        assert!(!atlas.evict_unused(std::iter::empty()));
+        assert_eq!(1, atlas.len());
+    }
+}
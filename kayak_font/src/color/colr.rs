@@ -0,0 +1,354 @@
+//! Parsing and rasterization of layered color glyphs (`COLR`/`CPAL` version 0).
+
+use std::collections::HashMap;
+
+use ab_glyph_rasterizer::{point, Point, Rasterizer};
+use owned_ttf_parser::{Face, GlyphId, OutlineBuilder, Rect};
+
+/// The palette index standing for the text color, which is drawn black.
+const FOREGROUND: u16 = 0xFFFF;
+
+/// A single layer of a color glyph: an outline filled with one color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Layer {
+    pub glyph_id: GlyphId,
+    /// The color of this layer, as straight RGBA.
+    pub color: [u8; 4],
+}
+
+/// The layers of every color glyph in a font, bottom-most first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ColorLayers {
+    glyphs: HashMap<u16, Vec<Layer>>,
+}
+
+impl ColorLayers {
+    /// Parses the given `COLR` and `CPAL` tables, using the first palette.
+    ///
+    /// Returns `None` if either table is malformed. Version 1 layers (gradients and transforms)
+    /// aren't supported, so only the version 0 glyphs of a `COLR` table are read.
+    pub fn parse(colr: &[u8], cpal: &[u8]) -> Option<Self> {
+        let palette = parse_palette(cpal)?;
+
+        let base_count = read_u16(colr, 2)? as usize;
+        let base_offset = read_u32(colr, 4)? as usize;
+        let layer_offset = read_u32(colr, 8)? as usize;
+        let layer_count = read_u16(colr, 12)? as usize;
+
+        let mut glyphs = HashMap::new();
+        for index in 0..base_count {
+            let record = base_offset + index * 6;
+            let glyph_id = read_u16(colr, record)?;
+            let first_layer = read_u16(colr, record + 2)? as usize;
+            let total_layers = read_u16(colr, record + 4)? as usize;
+            if first_layer + total_layers > layer_count {
+                return None;
+            }
+
+            let layers = (first_layer..first_layer + total_layers)
+                .map(|layer| {
+                    let record = layer_offset + layer * 4;
+                    let glyph_id = read_u16(colr, record)?;
+                    let color = match read_u16(colr, record + 2)? {
+                        FOREGROUND => [0, 0, 0, 255],
+                        index => *palette.get(index as usize)?,
+                    };
+                    Some(Layer {
+                        glyph_id: GlyphId(glyph_id),
+                        color,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            glyphs.insert(glyph_id, layers);
+        }
+
+        Some(Self { glyphs })
+    }
+
+    /// Returns the layers of the given glyph, if it's a color glyph.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<&[Layer]> {
+        self.glyphs.get(&glyph_id.0).map(Vec::as_slice)
+    }
+
+    #[cfg(test)]
+    pub fn insert(&mut self, glyph_id: GlyphId, layers: Vec<Layer>) {
+        self.glyphs.insert(glyph_id.0, layers);
+    }
+}
+
+/// Reads the first palette of a `CPAL` table, as straight RGBA colors.
+fn parse_palette(cpal: &[u8]) -> Option<Vec<[u8; 4]>> {
+    let entry_count = read_u16(cpal, 2)? as usize;
+    let palette_count = read_u16(cpal, 4)?;
+    let records_offset = read_u32(cpal, 8)? as usize;
+    if palette_count == 0 {
+        return Some(Vec::new());
+    }
+    let first_record = read_u16(cpal, 12)? as usize;
+
+    (first_record..first_record + entry_count)
+        .map(|record| {
+            // Records are stored as BGRA
+            let offset = records_offset + record * 4;
+            let bgra = cpal.get(offset..offset + 4)?;
+            Some([bgra[2], bgra[1], bgra[0], bgra[3]])
+        })
+        .collect()
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Returns the bounds of all of the given layers combined, in font units.
+pub(crate) fn layers_bounds(face: &Face, layers: &[Layer]) -> Option<Rect> {
+    layers
+        .iter()
+        .filter_map(|layer| face.glyph_bounding_box(layer.glyph_id))
+        .reduce(|a, b| Rect {
+            x_min: a.x_min.min(b.x_min),
+            y_min: a.y_min.min(b.y_min),
+            x_max: a.x_max.max(b.x_max),
+            y_max: a.y_max.max(b.y_max),
+        })
+}
+
+/// Rasterizes the given layers on top of each other.
+///
+/// The layers are scaled so that an em is `pixels_per_em` pixels wide. Returns the width, height
+/// and straight RGBA pixels of the image, which covers the combined bounds of the layers.
+pub(crate) fn rasterize_layers(
+    face: &Face,
+    layers: &[Layer],
+    pixels_per_em: u16,
+) -> Option<(u32, u32, Vec<u8>)> {
+    let bounds = layers_bounds(face, layers)?;
+    let scale = pixels_per_em as f32 / face.units_per_em() as f32;
+    let width = ((bounds.x_max - bounds.x_min) as f32 * scale)
+        .ceil()
+        .max(1.0) as u32;
+    let height = ((bounds.y_max - bounds.y_min) as f32 * scale)
+        .ceil()
+        .max(1.0) as u32;
+
+    // Layers are composited with premultiplied alpha
+    let mut premultiplied = vec![[0.0f32; 4]; (width * height) as usize];
+    for layer in layers {
+        let mut builder = LayerBuilder {
+            rasterizer: Rasterizer::new(width as usize, height as usize),
+            origin: (bounds.x_min as f32, bounds.y_max as f32),
+            scale,
+            start: point(0.0, 0.0),
+            last: point(0.0, 0.0),
+        };
+        if face.outline_glyph(layer.glyph_id, &mut builder).is_none() {
+            continue;
+        }
+        builder.close();
+
+        let color = layer.color.map(|channel| channel as f32 / 255.0);
+        builder.rasterizer.for_each_pixel(|index, coverage| {
+            let alpha = color[3] * coverage.min(1.0);
+            let pixel = &mut premultiplied[index];
+            for channel in 0..3 {
+                pixel[channel] = color[channel] * alpha + pixel[channel] * (1.0 - alpha);
+            }
+            pixel[3] = alpha + pixel[3] * (1.0 - alpha);
+        });
+    }
+
+    let data = premultiplied
+        .into_iter()
+        .flat_map(|[r, g, b, a]| {
+            let unmultiply = |channel: f32| {
+                if a > 0.0 {
+                    (channel / a * 255.0).round().clamp(0.0, 255.0) as u8
+                } else {
+                    0
+                }
+            };
+            [
+                unmultiply(r),
+                unmultiply(g),
+                unmultiply(b),
+                (a * 255.0).round() as u8,
+            ]
+        })
+        .collect();
+    Some((width, height, data))
+}
+
+/// Draws an outline, in font units, into a rasterizer, flipping it so that rows go downwards.
+struct LayerBuilder {
+    rasterizer: Rasterizer,
+    /// The top-left corner of the image, in font units
+    origin: (f32, f32),
+    scale: f32,
+    start: Point,
+    last: Point,
+}
+
+impl LayerBuilder {
+    fn point(&self, x: f32, y: f32) -> Point {
+        point(
+            (x - self.origin.0) * self.scale,
+            (self.origin.1 - y) * self.scale,
+        )
+    }
+}
+
+impl OutlineBuilder for LayerBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.rasterizer.draw_line(self.last, to);
+        self.last = to;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.rasterizer.draw_quad(self.last, self.point(x1, y1), to);
+        self.last = to;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.rasterizer
+            .draw_cubic(self.last, self.point(x1, y1), self.point(x2, y2), to);
+        self.last = to;
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.rasterizer.draw_line(self.last, self.start);
+        }
+        self.last = self.start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rasterize_layers, ColorLayers, Layer};
+    use owned_ttf_parser::{Face, GlyphId};
+
+    /// Builds `COLR` and `CPAL` tables with one color glyph made of two layers
+    fn make_tables() -> (Vec<u8>, Vec<u8>) {
+        let mut colr = Vec::new();
+        // Version, base glyph count and offset, layer offset and count
+        colr.extend(0u16.to_be_bytes());
+        colr.extend(1u16.to_be_bytes());
+        colr.extend(14u32.to_be_bytes());
+        colr.extend(20u32.to_be_bytes());
+        colr.extend(2u16.to_be_bytes());
+        // Base glyph 7 has two layers, starting with the first
+        colr.extend(7u16.to_be_bytes());
+        colr.extend(0u16.to_be_bytes());
+        colr.extend(2u16.to_be_bytes());
+        // The layers: glyph 3 in the second color, then glyph 4 in the text color
+        colr.extend(3u16.to_be_bytes());
+        colr.extend(1u16.to_be_bytes());
+        colr.extend(4u16.to_be_bytes());
+        colr.extend(0xFFFFu16.to_be_bytes());
+
+        let mut cpal = Vec::new();
+        // Version, entry count, palette count, color count and offset, first color of palette 0
+        cpal.extend(0u16.to_be_bytes());
+        cpal.extend(2u16.to_be_bytes());
+        cpal.extend(1u16.to_be_bytes());
+        cpal.extend(2u16.to_be_bytes());
+        cpal.extend(14u32.to_be_bytes());
+        cpal.extend(0u16.to_be_bytes());
+        // BGRA colors: red, then half-transparent blue
+        cpal.extend([0, 0, 255, 255]);
+        cpal.extend([255, 0, 0, 128]);
+
+        (colr, cpal)
+    }
+
+    #[test]
+    fn should_parse_layers_with_their_palette_colors() {
+        let (colr, cpal) = make_tables();
+        let layers = ColorLayers::parse(&colr, &cpal).unwrap();
+
+        assert_eq!(
+            Some(
+                &[
+                    Layer {
+                        glyph_id: GlyphId(3),
+                        color: [0, 0, 255, 128],
+                    },
+                    Layer {
+                        glyph_id: GlyphId(4),
+                        color: [0, 0, 0, 255],
+                    },
+                ][..]
+            ),
+            layers.get(GlyphId(7))
+        );
+        assert_eq!(None, layers.get(GlyphId(3)));
+    }
+
+    #[test]
+    fn should_reject_truncated_tables() {
+        let (colr, cpal) = make_tables();
+        assert_eq!(None, ColorLayers::parse(&colr[..colr.len() - 2], &cpal));
+        assert_eq!(None, ColorLayers::parse(&colr, &cpal[..cpal.len() - 1]));
+    }
+
+    #[test]
+    fn should_composite_layers_in_order() {
+        let data = std::fs::read("../resources/Roboto-Regular.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        let stem = face.glyph_index('I').unwrap();
+        let layer = |color: [u8; 4]| Layer {
+            glyph_id: stem,
+            color,
+        };
+        let center = |layers: &[Layer]| {
+            let (width, height, pixels) = rasterize_layers(&face, layers, 64).unwrap();
+            assert_eq!((width * height * 4) as usize, pixels.len());
+            let index = (((height / 2) * width + width / 2) * 4) as usize;
+            [
+                pixels[index],
+                pixels[index + 1],
+                pixels[index + 2],
+                pixels[index + 3],
+            ]
+        };
+
+        assert_eq!([255, 0, 0, 255], center(&[layer([255, 0, 0, 255])]));
+        // Later layers are drawn on top of earlier ones
+        assert_eq!(
+            [0, 0, 255, 255],
+            center(&[layer([255, 0, 0, 255]), layer([0, 0, 255, 255])])
+        );
+        let blended = center(&[layer([255, 0, 0, 255]), layer([0, 0, 255, 128])]);
+        assert_eq!(255, blended[3]);
+        assert!((blended[0] as i32 - 127).abs() <= 1 && (blended[2] as i32 - 128).abs() <= 1);
+    }
+
+    #[test]
+    fn should_leave_pixels_outside_of_the_outline_transparent() {
+        let data = std::fs::read("../resources/Roboto-Regular.ttf").unwrap();
+        let face = Face::from_slice(&data, 0).unwrap();
+        let layers = [Layer {
+            glyph_id: face.glyph_index('A').unwrap(),
+            color: [255, 0, 0, 255],
+        }];
+        let (width, _, pixels) = rasterize_layers(&face, &layers, 64).unwrap();
+
+        // The top corners are beside the apex of the "A"
+        assert_eq!(0, pixels[3]);
+        assert_eq!(0, pixels[(width * 4 - 1) as usize]);
+    }
+}
@@ -0,0 +1,339 @@
+//! Color glyphs, such as emoji, drawn from their original font file.
+//!
+//! Kayak fonts are distance fields that can only be filled with a single color, so color glyphs
+//! are instead rasterized from a [`ColorFont`] into a shared [`ColorAtlas`] and drawn as images.
+//! A color font is used as the [fallback](crate::KayakFont::set_fallback) of a Kayak font, for
+//! emoji and any other character the Kayak font is missing.
+//!
+//! Both layered glyphs (`COLR`/`CPAL` version 0) and bitmap glyphs (`CBDT`/`CBLC` and `sbix`
+//! PNGs) are supported. Each character is drawn on its own: emoji sequences (such as flags or
+//! emoji joined with zero width joiners) aren't shaped into a single glyph, and are drawn as the
+//! emoji they're made of.
+
+mod atlas;
+mod colr;
+
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::anyhow;
+use owned_ttf_parser::{AsFaceRef, GlyphId, OwnedFace, RasterGlyphImage, RasterImageFormat, Tag};
+
+use colr::ColorLayers;
+
+pub use atlas::*;
+
+/// The number of color fonts created so far, used to identify them.
+static FONT_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// The placement of a color glyph, relative to its position on the baseline (in pixels).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ColorGlyphMetrics {
+    /// The horizontal distance to the next glyph.
+    pub advance: f32,
+    /// The distance from the glyph's position to the left of its image.
+    pub left: f32,
+    /// The distance from the baseline up to the top of its image.
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A rasterized color glyph.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ColorGlyphImage {
+    pub width: u32,
+    pub height: u32,
+    /// The pixels of the image, row by row from the top, as straight (not premultiplied) RGBA.
+    pub data: Vec<u8>,
+}
+
+/// A font containing color glyphs, such as an emoji font.
+///
+/// Color fonts are loaded from their original file (`.ttf` or `.otf`), rather than from a
+/// generated `.kayak_font`.
+pub struct ColorFont {
+    id: u64,
+    face: OwnedFace,
+    layers: Option<ColorLayers>,
+}
+
+impl ColorFont {
+    /// Loads a color font from the contents of its font file.
+    ///
+    /// Fails if the font can't be parsed or doesn't contain any color glyphs.
+    pub fn from_bytes(data: Vec<u8>) -> anyhow::Result<Self> {
+        let face = OwnedFace::from_vec(data, 0).map_err(|err| anyhow!("{}", err))?;
+
+        let table = |tag: &[u8; 4]| face.as_face_ref().table_data(Tag::from_bytes(tag));
+        let layers = match (table(b"COLR"), table(b"CPAL")) {
+            (Some(colr), Some(cpal)) => Some(
+                ColorLayers::parse(colr, cpal)
+                    .ok_or_else(|| anyhow!("malformed COLR or CPAL table"))?,
+            ),
+            _ => None,
+        };
+        let has_bitmaps = table(b"CBDT").is_some() || table(b"sbix").is_some();
+        if layers.is_none() && !has_bitmaps {
+            return Err(anyhow!("the font doesn't contain any color glyphs"));
+        }
+
+        Ok(Self {
+            id: FONT_COUNT.fetch_add(1, Ordering::Relaxed),
+            face,
+            layers,
+        })
+    }
+
+    /// A unique identifier for this font.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns true if this font has a color glyph for the given character.
+    pub fn has_glyph(&self, c: char) -> bool {
+        let face = self.face.as_face_ref();
+        face.glyph_index(c).map_or(false, |glyph_id| {
+            self.layers_of(glyph_id).is_some()
+                || face.glyph_raster_image(glyph_id, u16::MAX).is_some()
+        })
+    }
+
+    /// Calculates the placement of the color glyph for the given character.
+    ///
+    /// Returns `None` if this font doesn't have a color glyph for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `c`: The character to measure.
+    /// * `font_size`: The font size (in pixels).
+    ///
+    pub fn metrics(&self, c: char, font_size: f32) -> Option<ColorGlyphMetrics> {
+        let face = self.face.as_face_ref();
+        let glyph_id = face.glyph_index(c)?;
+        let scale = font_size / face.units_per_em() as f32;
+        let advance = face.glyph_hor_advance(glyph_id).unwrap_or_default() as f32 * scale;
+
+        if let Some(layers) = self.layers_of(glyph_id) {
+            let bounds = colr::layers_bounds(face, layers)?;
+            return Some(ColorGlyphMetrics {
+                advance,
+                left: bounds.x_min as f32 * scale,
+                top: bounds.y_max as f32 * scale,
+                width: (bounds.x_max - bounds.x_min) as f32 * scale,
+                height: (bounds.y_max - bounds.y_min) as f32 * scale,
+            });
+        }
+
+        let pixels_per_em = font_size.ceil().clamp(1.0, u16::MAX as f32) as u16;
+        let image = face.glyph_raster_image(glyph_id, pixels_per_em)?;
+        Some(bitmap_metrics(&image, advance, font_size))
+    }
+
+    /// Returns the size the given character is rasterized at, when asking for the given size.
+    ///
+    /// This is the size itself for layered glyphs, or the size of the closest strike for bitmap
+    /// glyphs (which are never scaled when rasterized).
+    pub fn strike_size(&self, c: char, pixels_per_em: u16) -> u16 {
+        let face = self.face.as_face_ref();
+        face.glyph_index(c)
+            .filter(|glyph_id| self.layers_of(*glyph_id).is_none())
+            .and_then(|glyph_id| face.glyph_raster_image(glyph_id, pixels_per_em))
+            .map_or(pixels_per_em, |image| image.pixels_per_em)
+    }
+
+    /// Rasterizes the color glyph for the given character.
+    ///
+    /// Layered glyphs are rasterized so that an em is `pixels_per_em` pixels wide. Bitmap glyphs
+    /// are decoded from the strike closest to that size, without scaling them.
+    ///
+    /// Returns `None` if this font doesn't have a color glyph for it, or it can't be decoded.
+    pub fn rasterize(&self, c: char, pixels_per_em: u16) -> Option<ColorGlyphImage> {
+        let face = self.face.as_face_ref();
+        let glyph_id = face.glyph_index(c)?;
+
+        if let Some(layers) = self.layers_of(glyph_id) {
+            let (width, height, data) = colr::rasterize_layers(face, layers, pixels_per_em)?;
+            return Some(ColorGlyphImage {
+                width,
+                height,
+                data,
+            });
+        }
+
+        let image = face.glyph_raster_image(glyph_id, pixels_per_em)?;
+        match image.format {
+            RasterImageFormat::PNG => decode_png(image.data),
+        }
+    }
+
+    fn layers_of(&self, glyph_id: GlyphId) -> Option<&[colr::Layer]> {
+        self.layers.as_ref().and_then(|layers| layers.get(glyph_id))
+    }
+}
+
+impl Debug for ColorFont {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColorFont")
+            .field("id", &self.id)
+            .field("layered", &self.layers.is_some())
+            .finish()
+    }
+}
+
+impl PartialEq for ColorFont {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// Scales the placement of a bitmap glyph from its strike to the given font size.
+fn bitmap_metrics(image: &RasterGlyphImage, advance: f32, font_size: f32) -> ColorGlyphMetrics {
+    let scale = font_size / image.pixels_per_em.max(1) as f32;
+    ColorGlyphMetrics {
+        advance,
+        left: image.x as f32 * scale,
+        // The offset is to the bottom of the image, upwards from the baseline
+        top: (image.y as f32 + image.height as f32) * scale,
+        width: image.width as f32 * scale,
+        height: image.height as f32 * scale,
+    }
+}
+
+/// Decodes a PNG image into straight RGBA pixels.
+fn decode_png(data: &[u8]) -> Option<ColorGlyphImage> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    buffer.truncate(info.buffer_size());
+
+    let data = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|gray| [gray[0], gray[0], gray[0], gray[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer
+            .into_iter()
+            .flat_map(|gray| [gray, gray, gray, 255])
+            .collect(),
+        // Palettes are expanded into RGB(A) by the transformations
+        png::ColorType::Indexed => return None,
+    };
+
+    Some(ColorGlyphImage {
+        width: info.width,
+        height: info.height,
+        data,
+    })
+}
+
+#[cfg(test)]
+impl ColorFont {
+    /// Makes a color font out of a monochrome one, filling the outlines of the given characters
+    /// with a single color.
+    pub(crate) fn from_outlines(data: Vec<u8>, chars: &[char], color: [u8; 4]) -> Self {
+        let face = OwnedFace::from_vec(data, 0).unwrap();
+        let mut layers = ColorLayers::default();
+        for c in chars {
+            let glyph_id = face.as_face_ref().glyph_index(*c).unwrap();
+            layers.insert(glyph_id, vec![colr::Layer { glyph_id, color }]);
+        }
+
+        Self {
+            id: FONT_COUNT.fetch_add(1, Ordering::Relaxed),
+            face,
+            layers: Some(layers),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bitmap_metrics, decode_png, ColorFont, ColorGlyphMetrics};
+    use owned_ttf_parser::{RasterGlyphImage, RasterImageFormat};
+
+    fn encode_png(width: u32, height: u32, color_type: png::ColorType, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn should_decode_pngs_into_rgba() {
+        let rgba = decode_png(&encode_png(
+            1,
+            2,
+            png::ColorType::Rgba,
+            &[1, 2, 3, 4, 5, 6, 7, 8],
+        ))
+        .unwrap();
+        assert_eq!((1, 2), (rgba.width, rgba.height));
+        assert_eq!(vec![1, 2, 3, 4, 5, 6, 7, 8], rgba.data);
+
+        let gray = decode_png(&encode_png(
+            2,
+            1,
+            png::ColorType::GrayscaleAlpha,
+            &[10, 20, 30, 40],
+        ))
+        .unwrap();
+        assert_eq!(vec![10, 10, 10, 20, 30, 30, 30, 40], gray.data);
+
+        let rgb = decode_png(&encode_png(1, 1, png::ColorType::Rgb, &[1, 2, 3])).unwrap();
+        assert_eq!(vec![1, 2, 3, 255], rgb.data);
+
+        assert_eq!(None, decode_png(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn should_scale_bitmaps_from_their_strike() {
+        // A 136x128 image at 109 pixels per em, reaching 27 pixels below the baseline
+        let image = RasterGlyphImage {
+            x: 0,
+            y: -27,
+            width: 136,
+            height: 128,
+            pixels_per_em: 109,
+            format: RasterImageFormat::PNG,
+            data: &[],
+        };
+        let metrics = bitmap_metrics(&image, 12.0, 10.9);
+        let expected = ColorGlyphMetrics {
+            advance: 12.0,
+            left: 0.0,
+            top: 10.1,
+            width: 13.6,
+            height: 12.8,
+        };
+        for (a, b) in [
+            (expected.left, metrics.left),
+            (expected.top, metrics.top),
+            (expected.width, metrics.width),
+            (expected.height, metrics.height),
+        ] {
+            assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+        }
+        assert_eq!(expected.advance, metrics.advance);
+    }
+
+    #[test]
+    fn should_reject_fonts_without_color_glyphs() {
+        let data = std::fs::read("../resources/Roboto-Regular.ttf").unwrap();
+        assert!(ColorFont::from_bytes(data).is_err());
+        assert!(ColorFont::from_bytes(vec![0, 1, 2, 3]).is_err());
+    }
+}
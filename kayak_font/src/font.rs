@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[cfg(feature = "bevy_renderer")]
 use bevy::{prelude::Handle, reflect::TypeUuid, render::texture::Image};
@@ -6,7 +7,8 @@ use unicode_segmentation::UnicodeSegmentation;
 
 use crate::utility::{BreakableWord, MISSING, SPACE};
 use crate::{
-    utility, Alignment, Glyph, GlyphRect, Grapheme, Line, Sdf, TextLayout, TextProperties,
+    utility, Alignment, ColorFont, ColorGlyphMetrics, FontMetrics, Glyph, GlyphRect, Grapheme,
+    Line, Sdf, TextLayout, TextProperties,
 };
 
 #[cfg(feature = "bevy_renderer")]
@@ -18,6 +20,7 @@ pub struct KayakFont {
     pub missing_glyph: Option<char>,
     char_ids: HashMap<char, u32>,
     max_glyph_size: (f32, f32),
+    fallback: Option<Arc<ColorFont>>,
 }
 
 #[cfg(not(feature = "bevy_renderer"))]
@@ -27,6 +30,7 @@ pub struct KayakFont {
     pub missing_glyph: Option<char>,
     char_ids: HashMap<char, u32>,
     max_glyph_size: (f32, f32),
+    fallback: Option<Arc<ColorFont>>,
 }
 
 impl KayakFont {
@@ -59,6 +63,7 @@ impl KayakFont {
             missing_glyph,
             char_ids,
             max_glyph_size,
+            fallback: None,
         }
     }
 
@@ -74,24 +79,137 @@ impl KayakFont {
         self.char_ids.get(&c).and_then(|id| Some(*id))
     }
 
+    /// Sets the color font to fall back to, for emoji and characters this font doesn't have.
+    pub fn set_fallback(&mut self, fallback: Option<Arc<ColorFont>>) {
+        self.fallback = fallback;
+    }
+
+    /// Returns the color font this font falls back to, if any.
+    pub fn fallback(&self) -> Option<&Arc<ColorFont>> {
+        self.fallback.as_ref()
+    }
+
+    /// Returns true if the given character is drawn from the [fallback](Self::set_fallback)
+    /// color font.
+    ///
+    /// That's the case for emoji (which are drawn in color even if this font has a glyph for
+    /// them), and for any other character this font is missing, as long as the fallback has it.
+    pub fn is_color_glyph(&self, c: char) -> bool {
+        self.fallback.as_ref().map_or(false, |fallback| {
+            (utility::is_emoji_presentation(c) || self.get_glyph(c).is_none())
+                && fallback.has_glyph(c)
+        })
+    }
+
+    /// Returns the placement of the given character's color glyph, if it's
+    /// [drawn in color](Self::is_color_glyph).
+    fn get_color_glyph(&self, c: char, font_size: f32) -> Option<ColorGlyphMetrics> {
+        if !self.is_color_glyph(c) {
+            return None;
+        }
+        self.fallback.as_ref()?.metrics(c, font_size)
+    }
+
+    /// Returns true if the char at the given index of a grapheme only modifies the emoji before
+    /// it, and so isn't drawn on its own.
+    ///
+    /// This only applies to fonts with a fallback, leaving other fonts to draw their missing glyph.
+    fn is_emoji_component(&self, index: usize, c: char) -> bool {
+        self.fallback.is_some() && index > 0 && utility::is_emoji_component(c)
+    }
+
     pub fn get_word_width(&self, word: &str, properties: TextProperties) -> f32 {
         let space_width = self.get_space_width(properties);
         let tab_width = self.get_tab_width(properties);
 
         let mut width = 0.0;
-        for c in word.chars() {
-            if utility::is_space(c) {
-                width += space_width;
-            } else if utility::is_tab(c) {
-                width += tab_width;
-            } else if let Some(glyph) = self.get_glyph(c) {
-                width += glyph.advance * properties.font_size;
+        for grapheme in word.graphemes(true) {
+            let mut is_spaced = false;
+            for (index, c) in grapheme.chars().enumerate() {
+                if self.is_emoji_component(index, c) {
+                    continue;
+                }
+
+                if utility::is_space(c) {
+                    width += space_width + properties.word_spacing;
+                    is_spaced = true;
+                } else if utility::is_tab(c) {
+                    width += tab_width;
+                    is_spaced = true;
+                } else if let Some(metrics) = self.get_color_glyph(c, properties.font_size) {
+                    width += metrics.advance;
+                    is_spaced = true;
+                } else if let Some(glyph) = self.get_glyph(c) {
+                    width += glyph.advance * properties.font_size;
+                    is_spaced = true;
+                }
+            }
+            if is_spaced {
+                width += properties.letter_spacing;
             }
         }
 
         width
     }
 
+    /// Calculates the vertical metrics of this font at the given size.
+    ///
+    /// The metrics use the same conventions as the text renderer, so they can be used to align
+    /// other content to the baselines of [measured](Self::measure) text.
+    ///
+    /// # Arguments
+    ///
+    /// * `font_size`: The font size (in pixels).
+    ///
+    pub fn metrics(&self, font_size: f32) -> FontMetrics {
+        let metrics = self.sdf.metrics();
+        let scale = font_size / metrics.em_size();
+
+        let ascent = metrics.ascender() * scale;
+        let descent = -metrics.descender() * scale;
+        let line_gap = (metrics.line_height() * scale - ascent - descent).max(0.0);
+        let cap_height = self
+            .get_glyph('H')
+            .and_then(|glyph| glyph.plane_bounds)
+            .map_or(ascent, |bounds| bounds.top * scale);
+
+        FontMetrics {
+            font_size,
+            // Glyphs are placed relative to a baseline one font size below the top of their line
+            baseline: font_size,
+            ascent,
+            descent,
+            line_gap,
+            cap_height,
+        }
+    }
+
+    /// Calculates the intrinsic widths of the given text content.
+    ///
+    /// Returns a tuple of `(min_content, max_content)` where the min-content width is the
+    /// width of the longest word (the narrowest the text can be without overflowing) and the
+    /// max-content width is the width of the text without any wrapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `content`: The textual content to measure.
+    /// * `properties`: The text properties to use. The `max_size` is ignored.
+    ///
+    pub fn intrinsic_widths(&self, content: &str, properties: TextProperties) -> (f32, f32) {
+        let min_content = content
+            .split_whitespace()
+            .map(|word| self.get_word_width(word, properties))
+            .fold(0.0, f32::max);
+
+        let unbounded = TextProperties {
+            max_size: (f32::MAX, f32::MAX),
+            ..properties
+        };
+        let max_content = self.measure(content, unbounded).size().0;
+
+        (min_content, max_content.max(min_content))
+    }
+
     /// Measures the given text content and calculates an appropriate layout
     /// given a set of properties.
     ///
@@ -174,8 +292,10 @@ impl KayakFont {
                     char_index,
                     ..Default::default()
                 };
+                // Whether the grapheme takes up space, and so is followed by the letter spacing
+                let mut is_spaced = false;
 
-                for c in grapheme_content.chars() {
+                for (index, c) in grapheme_content.chars().enumerate() {
                     char_index += 1;
                     grapheme.char_total += 1;
 
@@ -184,13 +304,35 @@ impl KayakFont {
                         continue;
                     }
 
+                    if self.is_emoji_component(index, c) {
+                        // Drawn as part of the emoji (or not at all)
+                        continue;
+                    }
+
                     if utility::is_space(c) {
                         if !will_break {
                             // Don't add the space if we're about to break the line
-                            grapheme.size.0 += space_width;
+                            grapheme.size.0 += space_width + properties.word_spacing;
+                            is_spaced = true;
                         }
                     } else if utility::is_tab(c) {
                         grapheme.size.0 += tab_width;
+                        is_spaced = true;
+                    } else if let Some(metrics) = self.get_color_glyph(c, properties.font_size) {
+                        // Color glyphs are drawn at their own size, from the fallback font
+                        let pos_x = grapheme.position.0 + grapheme.size.0 + metrics.left;
+                        let pos_y = grapheme.position.1 + grapheme.size.1 - metrics.top;
+
+                        glyph_rects.push(GlyphRect {
+                            position: (pos_x, pos_y),
+                            size: (metrics.width, metrics.height),
+                            content: c,
+                        });
+
+                        glyph_index += 1;
+                        grapheme.glyph_total += 1;
+                        grapheme.size.0 += metrics.advance;
+                        is_spaced = true;
                     } else {
                         let glyph = self.get_glyph(c).or_else(|| {
                             if let Some(missing) = self.missing_glyph {
@@ -228,10 +370,15 @@ impl KayakFont {
                             glyph_index += 1;
                             grapheme.glyph_total += 1;
                             grapheme.size.0 += glyph.advance * properties.font_size;
+                            is_spaced = true;
                         }
                     }
                 }
 
+                if is_spaced {
+                    grapheme.size.0 += properties.letter_spacing;
+                }
+
                 line.add_grapheme(grapheme);
                 size.0 = size.0.max(line.width());
             }
@@ -126,6 +126,23 @@ impl Line {
         self.width += grapheme.size.0;
         self.graphemes.push(grapheme)
     }
+
+    /// Returns a copy of this line with the positions and sizes of its graphemes scaled.
+    pub fn scaled(&self, scale: f32) -> Self {
+        Self {
+            grapheme_index: self.grapheme_index,
+            graphemes: self
+                .graphemes
+                .iter()
+                .map(|grapheme| Grapheme {
+                    position: (grapheme.position.0 * scale, grapheme.position.1 * scale),
+                    size: (grapheme.size.0 * scale, grapheme.size.1 * scale),
+                    ..*grapheme
+                })
+                .collect(),
+            width: self.width * scale,
+        }
+    }
 }
 
 impl PartialOrd for Line {
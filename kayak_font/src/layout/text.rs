@@ -1,5 +1,6 @@
 use crate::{GlyphRect, Line, RowCol};
 use std::cmp::Ordering;
+use std::ops::Range;
 
 /// The text alignment.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -10,6 +11,9 @@ pub enum Alignment {
 }
 
 /// Properties to control text layout.
+///
+/// More properties may be added over time, so prefer creating these with [`new`](Self::new) or
+/// [`Default`] (and struct update syntax) over listing every field.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct TextProperties {
     /// The font size (in pixels).
@@ -22,6 +26,18 @@ pub struct TextProperties {
     pub alignment: Alignment,
     /// The size of a tab (`'\t'`) character in equivalent spaces.
     pub tab_size: u8,
+    /// Whether glyphs keep their subpixel horizontal position when rendered.
+    ///
+    /// When disabled (the default), glyphs are snapped to whole pixels.
+    pub subpixel_positioning: bool,
+    /// The extra space added after each grapheme (in pixels).
+    ///
+    /// Negative values tighten the text.
+    pub letter_spacing: f32,
+    /// The extra space added to each space character (in pixels).
+    ///
+    /// Negative values tighten the text.
+    pub word_spacing: f32,
 }
 
 impl Default for TextProperties {
@@ -32,8 +48,63 @@ impl Default for TextProperties {
             max_size: (f32::MAX, f32::MAX),
             tab_size: 4,
             alignment: Alignment::Start,
+            subpixel_positioning: false,
+            letter_spacing: 0.0,
+            word_spacing: 0.0,
+        }
+    }
+}
+
+impl TextProperties {
+    /// Create new [`TextProperties`] with the given sizes, leaving all other properties at their
+    /// defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `font_size`: The font size (in pixels).
+    /// * `line_height`: The line height (in pixels).
+    /// * `max_size`: The maximum width and height a block of text can take up (in pixels).
+    ///
+    pub fn new(font_size: f32, line_height: f32, max_size: (f32, f32)) -> Self {
+        Self {
+            font_size,
+            line_height,
+            max_size,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a copy of these properties with all of their sizes scaled.
+    pub fn scaled(&self, scale: f32) -> Self {
+        Self {
+            font_size: self.font_size * scale,
+            line_height: self.line_height * scale,
+            max_size: (self.max_size.0 * scale, self.max_size.1 * scale),
+            letter_spacing: self.letter_spacing * scale,
+            word_spacing: self.word_spacing * scale,
+            ..*self
         }
     }
+
+    /// Snaps the given glyph position to the pixel grid.
+    ///
+    /// The vertical position is always rounded to a whole (physical) pixel, while the horizontal
+    /// position is only rounded if [subpixel positioning](Self::subpixel_positioning) is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The position of the glyph (in logical pixels).
+    /// * `scale_factor`: The number of physical pixels per logical pixel.
+    ///
+    pub fn snap_position(&self, position: (f32, f32), scale_factor: f32) -> (f32, f32) {
+        let snap = |value: f32| (value * scale_factor).round() / scale_factor;
+        let x = if self.subpixel_positioning {
+            position.0
+        } else {
+            snap(position.0)
+        };
+        (x, snap(position.1))
+    }
 }
 
 /// Calculated text layout.
@@ -89,6 +160,27 @@ impl TextLayout {
         self.properties
     }
 
+    /// Returns a copy of this layout scaled around its top-left corner.
+    ///
+    /// This matches measuring the text with [scaled](TextProperties::scaled) properties, but
+    /// without having to measure it again.
+    pub fn scaled(&self, scale: f32) -> Self {
+        Self {
+            glyphs: self
+                .glyphs
+                .iter()
+                .map(|glyph| GlyphRect {
+                    position: (glyph.position.0 * scale, glyph.position.1 * scale),
+                    size: (glyph.size.0 * scale, glyph.size.1 * scale),
+                    content: glyph.content,
+                })
+                .collect(),
+            lines: self.lines.iter().map(|line| line.scaled(scale)).collect(),
+            size: (self.size.0 * scale, self.size.1 * scale),
+            properties: self.properties.scaled(scale),
+        }
+    }
+
     /// The total number of lines.
     pub fn total_lines(&self) -> usize {
         self.lines.len()
@@ -141,4 +233,217 @@ impl TextLayout {
             })
             .ok()
     }
+
+    /// Finds the grapheme boundary closest to the given position.
+    ///
+    /// The position is relative to the top-left of the text content. The returned index is between
+    /// `0` and [`total_graphemes`](Self::total_graphemes) (inclusive), where an index of `n` is the
+    /// boundary just before the `n`th grapheme. Positions above or below the text are clamped to
+    /// the first or last line, respectively.
+    ///
+    /// Positions before the start of a line map to the start of that line, while positions past the
+    /// end of a line map to its end. If a line (other than the last) ends in a line break or the
+    /// whitespace it was wrapped at, its end is the boundary _before_ that grapheme, so that the
+    /// boundary stays on the same line.
+    pub fn hit_test(&self, position: (f32, f32)) -> usize {
+        let line_height = self.properties.line_height;
+        let row = if line_height > 0.0 {
+            (position.1 / line_height).floor().max(0.0) as usize
+        } else {
+            0
+        };
+        let row = row.min(self.lines.len().saturating_sub(1));
+        let line = match self.lines.get(row) {
+            Some(line) => line,
+            None => return 0,
+        };
+
+        let is_last_line = row + 1 == self.lines.len();
+        let ends_in_break = line
+            .graphemes()
+            .last()
+            .map_or(false, |grapheme| grapheme.glyph_total == 0);
+        let end = if !is_last_line && ends_in_break {
+            line.total_graphemes() - 1
+        } else {
+            line.total_graphemes()
+        };
+
+        let x = position.0 - self.line_offset(line);
+        line.graphemes()
+            .iter()
+            .position(|grapheme| x < grapheme.position.0 + grapheme.size.0 / 2.0)
+            .map_or(end, |col| col.min(end))
+            + line.grapheme_index()
+    }
+
+    /// Finds the char boundary closest to the given position.
+    ///
+    /// This works like [`hit_test`](Self::hit_test), except that the returned index is a char index
+    /// within the text content. It's always at the start of a grapheme, so a boundary never falls
+    /// within a grapheme made of multiple chars. Positions before the first glyph map to `0`, while
+    /// positions after the last glyph map to [`total_chars`](Self::total_chars).
+    pub fn hit_test_char(&self, position: (f32, f32)) -> usize {
+        let index = self.hit_test(position);
+        self.char_range(index..index).start
+    }
+
+    /// Finds the grapheme boundary at the given char index within the text content.
+    ///
+    /// This is the inverse of [`char_range`](Self::char_range). A char index within a grapheme
+    /// made of multiple chars maps to the boundary just before that grapheme.
+    pub fn grapheme_at_char(&self, char_index: usize) -> usize {
+        for line in &self.lines {
+            let end = line.char_index() + line.total_chars();
+            if char_index < end {
+                let col = line
+                    .graphemes()
+                    .iter()
+                    .rposition(|grapheme| grapheme.char_index <= char_index)
+                    .unwrap_or_default();
+                return line.grapheme_index() + col;
+            }
+        }
+
+        self.total_graphemes()
+    }
+
+    /// Returns the position of a caret placed at the given grapheme boundary.
+    ///
+    /// The position is the top of the caret, relative to the top-left of the text content. The
+    /// caret spans the [line height](TextProperties::line_height). A boundary after a trailing line
+    /// break is placed at the start of the (empty) line that follows it.
+    pub fn caret_position(&self, index: usize) -> (f32, f32) {
+        let line_height = self.properties.line_height;
+        if let Some(row_col) = self.find_grapheme(index) {
+            let line = &self.lines[row_col.row];
+            let x = row_col.grapheme.position.0 + self.line_offset(line);
+            return (x, line_height * row_col.row as f32);
+        }
+
+        let (row, line) = match self.lines.iter().enumerate().last() {
+            Some(last) => last,
+            None => return (0.0, 0.0),
+        };
+        let ends_in_break = line.graphemes().last().map_or(false, |grapheme| {
+            grapheme.glyph_total == 0 && grapheme.size.0 == 0.0
+        });
+        if ends_in_break {
+            let next = Line::new_after(line);
+            (self.line_offset(&next), line_height * (row + 1) as f32)
+        } else {
+            (
+                line.width() + self.line_offset(line),
+                line_height * row as f32,
+            )
+        }
+    }
+
+    /// Returns the rects covering the graphemes in the given range, as `(position, size)` pairs.
+    ///
+    /// One rect is returned for each line the range spans, relative to the top-left of the text
+    /// content. This is useful for drawing selection highlights.
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<((f32, f32), (f32, f32))> {
+        let line_height = self.properties.line_height;
+        let mut rects = Vec::new();
+
+        for (row, line) in self.lines.iter().enumerate() {
+            let start = range.start.max(line.grapheme_index());
+            let end = range
+                .end
+                .min(line.grapheme_index() + line.total_graphemes());
+            if start >= end {
+                continue;
+            }
+
+            let offset = self.line_offset(line);
+            let first = line.grapheme(start - line.grapheme_index());
+            let last = line.grapheme(end - 1 - line.grapheme_index());
+            let left = first.position.0 + offset;
+            let right = last.position.0 + last.size.0 + offset;
+            rects.push((
+                (left, line_height * row as f32),
+                (right - left, line_height),
+            ));
+        }
+
+        rects
+    }
+
+    /// Returns the range of graphemes in the line containing the given grapheme boundary.
+    pub fn line_range(&self, index: usize) -> Range<usize> {
+        let line = match self.find_grapheme(index) {
+            Some(row_col) => self.lines.get(row_col.row),
+            None => self.lines.last(),
+        };
+
+        line.map_or(0..0, |line| {
+            line.grapheme_index()..line.grapheme_index() + line.total_graphemes()
+        })
+    }
+
+    /// Returns the range of graphemes in the word at the given grapheme boundary.
+    ///
+    /// Words are made of alphanumeric characters and underscores. If neither grapheme next to the
+    /// boundary belongs to a word, the range only contains the grapheme after the boundary.
+    ///
+    /// # Arguments
+    ///
+    /// * `content`: The text content this layout was measured from.
+    /// * `index`: The grapheme boundary.
+    ///
+    pub fn word_range(&self, content: &str, index: usize) -> Range<usize> {
+        let chars: Vec<char> = content.chars().collect();
+        let is_word = |index: usize| {
+            self.find_grapheme(index)
+                .and_then(|row_col| chars.get(row_col.grapheme.char_index))
+                .map_or(false, |c| c.is_alphanumeric() || *c == '_')
+        };
+
+        let total = self.total_graphemes();
+        if total == 0 {
+            return 0..0;
+        }
+
+        let mut index = index.min(total - 1);
+        if !is_word(index) {
+            if index > 0 && is_word(index - 1) {
+                index -= 1;
+            } else {
+                return index..index + 1;
+            }
+        }
+
+        let mut start = index;
+        while start > 0 && is_word(start - 1) {
+            start -= 1;
+        }
+        let mut end = index + 1;
+        while end < total && is_word(end) {
+            end += 1;
+        }
+
+        start..end
+    }
+
+    /// Converts the given range of graphemes into a range of chars within the text content.
+    pub fn char_range(&self, range: Range<usize>) -> Range<usize> {
+        let char_index = |index: usize| {
+            self.find_grapheme(index)
+                .map_or(self.total_chars(), |row_col| row_col.grapheme.char_index)
+        };
+
+        char_index(range.start)..char_index(range.end)
+    }
+
+    /// The horizontal offset applied to the given line by the text alignment.
+    ///
+    /// Grapheme positions don't include this offset, unlike glyph positions.
+    fn line_offset(&self, line: &Line) -> f32 {
+        match self.properties.alignment {
+            Alignment::Start => 0.0,
+            Alignment::Middle => (self.properties.max_size.0 - line.width()) / 2.0,
+            Alignment::End => self.properties.max_size.0 - line.width(),
+        }
+    }
 }
@@ -1,4 +1,5 @@
 mod atlas;
+mod color;
 mod font;
 mod glyph;
 mod layout;
@@ -7,6 +8,7 @@ mod sdf;
 mod utility;
 
 pub use atlas::*;
+pub use color::*;
 pub use font::*;
 pub use glyph::*;
 pub use layout::*;
@@ -18,7 +20,8 @@ pub mod bevy;
 
 #[cfg(test)]
 mod tests {
-    use crate::{Alignment, KayakFont, Sdf, TextProperties};
+    use crate::{ColorAtlas, ColorFont, KayakFont, Sdf, TextProperties};
+    use std::sync::Arc;
 
     fn make_font() -> KayakFont {
         let bytes = std::fs::read("assets/roboto.kayak_font")
@@ -32,13 +35,7 @@ mod tests {
     }
 
     fn make_properties() -> TextProperties {
-        TextProperties {
-            line_height: 14.0 * 1.2,
-            font_size: 14.0,
-            alignment: Alignment::Start,
-            max_size: (200.0, 300.0),
-            tab_size: 4,
-        }
+        TextProperties::new(14.0, 14.0 * 1.2, (200.0, 300.0))
     }
 
     #[test]
@@ -133,4 +130,269 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn hit_test_should_find_nearest_boundary() {
+        let content = "Hello world!\nHow is everyone on this super-awesome rock doing today?";
+        let font = make_font();
+        let properties = make_properties();
+        let layout = font.measure(content, properties);
+
+        assert_eq!(0, layout.hit_test((-10.0, -10.0)));
+        assert_eq!(0, layout.hit_test((1.0, 1.0)));
+
+        let second_line = layout.lines()[1].grapheme_index();
+        let y = properties.line_height * 1.5;
+        assert_eq!(second_line, layout.hit_test((0.0, y)));
+        assert_eq!(second_line + 1, layout.hit_test((10.0, y)));
+
+        assert_eq!(layout.total_graphemes(), layout.hit_test((1000.0, 1000.0)));
+    }
+
+    #[test]
+    fn hit_test_should_stay_before_line_breaks() {
+        let content = "Hello world!\nHow is everyone on this super-awesome rock doing today?";
+        let font = make_font();
+        let properties = make_properties();
+        let layout = font.measure(content, properties);
+
+        let newline = content.find('\n').unwrap();
+        assert_eq!(newline, layout.hit_test((1000.0, 1.0)));
+        assert_eq!(newline, layout.hit_test_char((1000.0, 1.0)));
+
+        // Boundaries never fall within a grapheme made of multiple chars
+        let emoji = "a\u{1F44D}\u{1F3FD}b";
+        let layout = font.measure(emoji, properties);
+        let boundaries: Vec<_> = (0..100)
+            .map(|x| layout.hit_test_char((x as f32, 1.0)))
+            .collect();
+        assert!(boundaries.iter().all(|index| [0, 1, 3, 4].contains(index)));
+        assert_eq!(emoji.chars().count(), layout.hit_test_char((1000.0, 1.0)));
+    }
+
+    #[test]
+    fn caret_should_follow_graphemes() {
+        let content = "Hello world!\nHow";
+        let font = make_font();
+        let properties = make_properties();
+        let layout = font.measure(content, properties);
+
+        assert_eq!((0.0, 0.0), layout.caret_position(0));
+        let how = content.find("How").unwrap();
+        assert_eq!(how, layout.grapheme_at_char(how));
+        assert_eq!(
+            (0.0, properties.line_height),
+            layout.caret_position(layout.grapheme_at_char(how))
+        );
+        let end = layout.caret_position(layout.total_graphemes());
+        assert_eq!(properties.line_height, end.1);
+        assert!(end.0 > 0.0);
+
+        // A trailing line break moves the caret onto the next line
+        let layout = font.measure("Hello\n", properties);
+        assert_eq!(
+            (0.0, properties.line_height),
+            layout.caret_position(layout.total_graphemes())
+        );
+
+        // Chars within a grapheme map to the boundary before it
+        let emoji = "a\u{1F44D}\u{1F3FD}b";
+        let layout = font.measure(emoji, properties);
+        assert_eq!(1, layout.grapheme_at_char(2));
+        assert_eq!(2, layout.grapheme_at_char(3));
+        assert_eq!(3, layout.grapheme_at_char(4));
+    }
+
+    #[test]
+    fn selection_should_span_lines() {
+        let content = "Hello world!\nHow is everyone on this super-awesome rock doing today?";
+        let font = make_font();
+        let properties = make_properties();
+        let layout = font.measure(content, properties);
+
+        let rects =
+            layout.selection_rects(content.find("world").unwrap()..content.find("is").unwrap());
+        assert_eq!(2, rects.len());
+        assert_eq!(0.0, rects[0].0 .1);
+        assert_eq!(0.0, rects[1].0 .0);
+        assert_eq!(properties.line_height, rects[1].0 .1);
+
+        assert!(layout.selection_rects(3..3).is_empty());
+    }
+
+    #[test]
+    fn should_find_word_and_line_ranges() {
+        let content = "Hello world!\nHow is everyone on this super-awesome rock doing today?";
+        let font = make_font();
+        let properties = make_properties();
+        let layout = font.measure(content, properties);
+
+        let world = content.find("world").unwrap();
+        assert_eq!(world..world + 5, layout.word_range(content, world + 2));
+        // The boundary at the end of a word still selects that word
+        assert_eq!(world..world + 5, layout.word_range(content, world + 5));
+        let newline = content.find('\n').unwrap();
+        assert_eq!(newline..newline + 1, layout.word_range(content, newline));
+
+        let line = layout.line_range(world);
+        assert_eq!(0, line.start);
+        assert_eq!(layout.lines()[1].grapheme_index(), line.end);
+        assert_eq!(world..world + 5, layout.char_range(world..world + 5));
+    }
+
+    #[test]
+    fn spacing_should_widen_text_and_move_boundaries() {
+        let content = "Hello world";
+        let font = make_font();
+        let properties = TextProperties {
+            max_size: (f32::MAX, f32::MAX),
+            ..make_properties()
+        };
+        let layout = font.measure(content, properties);
+
+        let letter_spaced = TextProperties {
+            letter_spacing: 2.0,
+            ..properties
+        };
+        let spaced_layout = font.measure(content, letter_spaced);
+        let spaced_width = layout.size().0 + 2.0 * content.len() as f32;
+        assert!((spaced_width - spaced_layout.size().0).abs() < 1e-3);
+        assert!(
+            (spaced_width - font.get_word_width(content, letter_spaced)).abs() < 1e-3,
+            "measuring words should match the layout"
+        );
+
+        let word_spaced = TextProperties {
+            word_spacing: -3.0,
+            ..properties
+        };
+        let tight_layout = font.measure(content, word_spaced);
+        assert!((layout.size().0 - 3.0 - tight_layout.size().0).abs() < 1e-3);
+
+        // Boundaries follow the spaced graphemes
+        let caret = spaced_layout.caret_position(5);
+        assert!((layout.caret_position(5).0 + 10.0 - caret.0).abs() < 1e-3);
+        assert_eq!(5, spaced_layout.hit_test((caret.0 + 0.5, 1.0)));
+    }
+
+    #[test]
+    fn scaled_layout_should_match_scaled_measurement() {
+        let content = "Hello world!\nHow is everyone on this super-awesome rock doing today?";
+        let font = make_font();
+        let properties = make_properties();
+        let scaled = font.measure(content, properties).scaled(2.0);
+        let measured = font.measure(content, properties.scaled(2.0));
+
+        assert_eq!(measured.total_lines(), scaled.total_lines());
+        assert_eq!(measured.properties(), scaled.properties());
+        for (a, b) in measured.glyphs().iter().zip(scaled.glyphs()) {
+            assert!((a.position.0 - b.position.0).abs() < 1e-2);
+            assert!((a.position.1 - b.position.1).abs() < 1e-2);
+        }
+        let caret = measured.caret_position(20);
+        assert!((caret.0 - scaled.caret_position(20).0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn metrics_should_scale_with_font_size() {
+        let font = make_font();
+        let small = font.metrics(10.0);
+        let large = font.metrics(20.0);
+
+        assert_eq!(10.0, small.baseline);
+        assert!(small.ascent > 0.0 && small.descent > 0.0);
+        assert!(small.cap_height > 0.0 && small.cap_height <= small.ascent);
+        assert_eq!(small.ascent * 2.0, large.ascent);
+        assert_eq!(small.descent * 2.0, large.descent);
+        assert_eq!(small.cap_height * 2.0, large.cap_height);
+    }
+
+    #[test]
+    fn should_fall_back_to_color_glyphs() {
+        let content = "a\u{10C}\u{FE0F}b";
+        let mut font = make_font();
+        let properties = TextProperties {
+            max_size: (f32::MAX, f32::MAX),
+            ..make_properties()
+        };
+        // Without a fallback, missing characters are drawn as the missing glyph
+        let layout = font.measure(content, properties);
+        assert_eq!(4, layout.total_glyphs());
+        assert_eq!('\u{FFFD}', layout.glyphs()[1].content);
+        assert!(!font.is_color_glyph('\u{10C}'));
+
+        let data = std::fs::read("../resources/Roboto-Regular.ttf").unwrap();
+        let fallback = Arc::new(ColorFont::from_outlines(
+            data,
+            &['a', '\u{10C}'],
+            [255, 0, 0, 255],
+        ));
+        font.set_fallback(Some(fallback.clone()));
+        assert!(font.is_color_glyph('\u{10C}'));
+        // Characters this font has are still drawn from it, unless they're emoji
+        assert!(!font.is_color_glyph('a'));
+        assert!(!font.is_color_glyph('c'));
+
+        // The variation selector is drawn as part of the glyph before it
+        let layout = font.measure(content, properties);
+        assert_eq!(3, layout.total_glyphs());
+        assert_eq!(3, layout.total_graphemes());
+        let metrics = fallback.metrics('\u{10C}', properties.font_size).unwrap();
+        let glyph = layout.glyphs()[1];
+        assert_eq!('\u{10C}', glyph.content);
+        assert_eq!((metrics.width, metrics.height), glyph.size);
+        assert_eq!(-metrics.top, glyph.position.1);
+
+        let grapheme = layout.lines()[0].graphemes()[1];
+        assert_eq!(metrics.advance, grapheme.size.0);
+        assert_eq!(grapheme.position.0 + metrics.left, glyph.position.0);
+        assert!(
+            (layout.size().0 - font.get_word_width(content, properties)).abs() < 1e-3,
+            "measuring words should match the layout"
+        );
+
+        // Only the color glyphs are added to the atlas, at the size they're drawn at
+        let mut atlas = ColorAtlas::new();
+        atlas.insert_layout(&font, &layout, 2.0);
+        let font_size = properties.font_size * 2.0;
+        let region = atlas.get(&fallback, '\u{10C}', font_size).unwrap();
+        let image = fallback
+            .rasterize('\u{10C}', ColorAtlas::pixels_per_em(font_size))
+            .unwrap();
+        assert_eq!((image.width, image.height), region.size);
+        assert_eq!(None, atlas.get(&fallback, 'a', font_size));
+
+        // Queued glyphs are only rasterized once, with the given rasterizer
+        let mut atlas = ColorAtlas::new();
+        atlas.queue_layout(&font, &layout, 2.0);
+        atlas.queue_layout(&font, &layout, 2.0);
+        assert!(atlas.has_queued());
+        assert_eq!(None, atlas.get(&fallback, '\u{10C}', font_size));
+        let mut rasterized = Vec::new();
+        atlas.rasterize_queued(|font, c, size| {
+            rasterized.push((c, size));
+            font.rasterize(c, size)
+        });
+        assert_eq!(
+            vec![('\u{10C}', ColorAtlas::pixels_per_em(font_size))],
+            rasterized
+        );
+        assert_eq!(Some(region), atlas.get(&fallback, '\u{10C}', font_size));
+        atlas.queue_layout(&font, &layout, 2.0);
+        assert!(!atlas.has_queued());
+    }
+
+    #[test]
+    fn should_snap_glyphs_unless_subpixel() {
+        let snapped = make_properties();
+        assert_eq!((10.0, 4.0), snapped.snap_position((10.3, 3.6), 1.0));
+        // Snaps to physical pixels on high-DPI displays
+        assert_eq!((10.5, 3.5), snapped.snap_position((10.3, 3.6), 2.0));
+
+        let subpixel = TextProperties {
+            subpixel_positioning: true,
+            ..snapped
+        };
+        assert_eq!((10.3, 4.0), subpixel.snap_position((10.3, 3.6), 1.0));
+    }
 }
@@ -13,3 +13,55 @@ pub struct Metrics {
     #[nserde(rename = "underlineThickness")]
     underline_thickness: f32,
 }
+
+impl Metrics {
+    /// The size of an em, in font units
+    pub(crate) fn em_size(&self) -> f32 {
+        self.em_size
+    }
+
+    /// The distance between the baselines of consecutive lines, in ems
+    pub(crate) fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    /// The distance from the baseline to the top of the tallest glyphs, in ems
+    pub(crate) fn ascender(&self) -> f32 {
+        self.ascender
+    }
+
+    /// The distance from the baseline to the bottom of the lowest glyphs, in ems
+    ///
+    /// This is negative for descenders that fall below the baseline.
+    pub(crate) fn descender(&self) -> f32 {
+        self.descender
+    }
+}
+
+/// The vertical metrics of a font at a given size
+///
+/// These can be retrieved using [`metrics`](crate::KayakFont::metrics). All values are in pixels
+/// and, like the rest of the layout, the y-axis points down. Distances from the baseline are
+/// given as positive values regardless of their direction.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct FontMetrics {
+    /// The font size these metrics were calculated for (in pixels).
+    pub font_size: f32,
+    /// The distance from the top of a line (as laid out by [`measure`](crate::KayakFont::measure))
+    /// down to its baseline.
+    ///
+    /// The baseline of line `n` is at `n * line_height + baseline`, where `line_height` is the
+    /// one given in the [`TextProperties`](crate::TextProperties).
+    pub baseline: f32,
+    /// The distance from the baseline up to the top of the tallest glyphs.
+    pub ascent: f32,
+    /// The distance from the baseline down to the bottom of the lowest glyphs.
+    pub descent: f32,
+    /// The extra spacing the font recommends between the descent of one line and the ascent of
+    /// the next.
+    pub line_gap: f32,
+    /// The distance from the baseline up to the top of a capital letter (measured from `'H'`).
+    ///
+    /// Falls back to the ascent if the font has no `'H'` glyph.
+    pub cap_height: f32,
+}
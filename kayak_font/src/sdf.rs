@@ -39,6 +39,11 @@ impl Sdf {
         value
     }
 
+    /// Returns the font-wide metrics of this SDF
+    pub(crate) fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
     pub fn max_glyph_size(&self) -> (f32, f32) {
         let mut size = (0.0, 0.0);
 
@@ -24,6 +24,76 @@ pub fn is_tab(c: char) -> bool {
     c == TAB
 }
 
+/// Returns true if the given character is presented as an emoji by default.
+///
+/// This approximates the Unicode `Emoji_Presentation` property with the blocks and symbols that
+/// fonts commonly draw in color, rather than listing every code point.
+pub fn is_emoji_presentation(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x231A..=0x231B
+            | 0x23E9..=0x23EC
+            | 0x23F0
+            | 0x23F3
+            | 0x25FD..=0x25FE
+            | 0x2614..=0x2615
+            | 0x2648..=0x2653
+            | 0x267F
+            | 0x2693
+            | 0x26A1
+            | 0x26AA..=0x26AB
+            | 0x26BD..=0x26BE
+            | 0x26C4..=0x26C5
+            | 0x26CE
+            | 0x26D4
+            | 0x26EA
+            | 0x26F2..=0x26F3
+            | 0x26F5
+            | 0x26FA
+            | 0x26FD
+            | 0x2705
+            | 0x270A..=0x270B
+            | 0x2728
+            | 0x274C
+            | 0x274E
+            | 0x2753..=0x2755
+            | 0x2757
+            | 0x2795..=0x2797
+            | 0x27B0
+            | 0x27BF
+            | 0x2B1B..=0x2B1C
+            | 0x2B50
+            | 0x2B55
+            | 0x1F004
+            | 0x1F0CF
+            | 0x1F18E
+            | 0x1F191..=0x1F19A
+            | 0x1F1E6..=0x1F1FF
+            | 0x1F201
+            | 0x1F21A
+            | 0x1F22F
+            | 0x1F232..=0x1F236
+            | 0x1F238..=0x1F23A
+            | 0x1F250..=0x1F251
+            | 0x1F300..=0x1F64F
+            | 0x1F680..=0x1F6FF
+            | 0x1F7E0..=0x1F7EB
+            | 0x1F90C..=0x1F9FF
+            | 0x1FA70..=0x1FAFF
+    )
+}
+
+/// Returns true if the given character only modifies the emoji before it.
+///
+/// These are the zero width joiner, variation selectors, skin tone modifiers, the combining
+/// keycap and tag characters. They're drawn as part of the emoji they follow (if at all).
+pub fn is_emoji_component(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x200D | 0x20E3 | 0xFE00..=0xFE0F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F
+    )
+}
+
 /// Split a string into a collection of "words" that may be followed by a line break,
 /// according to [UAX #14](https://www.unicode.org/reports/tr14/).
 ///
@@ -36,6 +36,19 @@ impl Attribute {
         }
     }
 
+    /// Returns the closure expression if this attribute's value is a single closure
+    ///
+    /// For example, this would return the closure in `on_event={|ctx, event| { ... }}`.
+    pub fn closure(&self) -> Option<&syn::ExprClosure> {
+        match self {
+            Self::WithValue(_, value) if value.stmts.len() == 1 => match &value.stmts[0] {
+                syn::Stmt::Expr(syn::Expr::Closure(closure)) => Some(closure),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub fn idents(&self) -> Vec<&syn::Ident> {
         self.ident().iter().collect::<Vec<_>>()
     }
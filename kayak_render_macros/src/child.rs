@@ -1,18 +1,54 @@
 use quote::{quote, ToTokens};
+use syn::parse::discouraged::Speculative;
 use syn::parse::{Parse, ParseStream, Result};
 
+use crate::if_child::IfChild;
+use crate::match_child::MatchChild;
 use crate::widget::Widget;
 
 #[derive(Clone)]
 pub enum Child {
     Widget(Widget),
+    Match(MatchChild),
+    If(IfChild),
     RawBlock(syn::Block),
 }
 
+impl Child {
+    /// The number of sibling indices taken up by this child
+    pub fn slot_count(&self) -> usize {
+        match self {
+            Self::Match(match_child) => match_child.slot_count(),
+            Self::If(if_child) => if_child.slot_count(),
+            _ => 1,
+        }
+    }
+
+    /// Creates the token stream for adding this child's slots, if it's rendered into slots
+    pub fn build_slots(&self, base_index: usize) -> Option<proc_macro2::TokenStream> {
+        match self {
+            Self::Match(match_child) => Some(match_child.build(base_index)),
+            Self::If(if_child) => Some(if_child.build(base_index)),
+            _ => None,
+        }
+    }
+
+    /// The clonable attributes of a child that's rendered into slots
+    pub fn get_slot_clonable_attributes(&self) -> Vec<proc_macro2::TokenStream> {
+        match self {
+            Self::Match(match_child) => match_child.get_clonable_attributes(),
+            Self::If(if_child) => if_child.get_clonable_attributes(),
+            _ => vec![],
+        }
+    }
+}
+
 impl ToTokens for Child {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         match self {
             Self::Widget(widget) => widget.to_tokens(tokens),
+            Self::Match(match_child) => match_child.build(0).to_tokens(tokens),
+            Self::If(if_child) => if_child.build(0).to_tokens(tokens),
             Self::RawBlock(block) => {
                 let ts = if block.stmts.len() == 1 {
                     let first = &block.stmts[0];
@@ -30,6 +66,16 @@ impl Parse for Child {
     fn parse(input: ParseStream) -> Result<Self> {
         match Widget::custom_parse(input, true) {
             Ok(widget) => Ok(Self::Widget(widget)),
+            Err(_) if MatchChild::peek(input) => match parse_speculative::<MatchChild>(input) {
+                Some(match_child) => Ok(Self::Match(match_child)),
+                // The arms hold plain expressions rather than widgets
+                None => Ok(Self::RawBlock(input.parse::<syn::Block>()?)),
+            },
+            Err(_) if IfChild::peek(input) => match parse_speculative::<IfChild>(input) {
+                Some(if_child) => Ok(Self::If(if_child)),
+                // The branches hold plain expressions rather than widgets
+                None => Ok(Self::RawBlock(input.parse::<syn::Block>()?)),
+            },
             Err(_) => {
                 let block = input.parse::<syn::Block>()?;
                 Ok(Self::RawBlock(block))
@@ -38,6 +84,14 @@ impl Parse for Child {
     }
 }
 
+/// Parses a `T` from a fork of the input, only advancing the input if it succeeds
+fn parse_speculative<T: Parse>(input: ParseStream) -> Option<T> {
+    let fork = input.fork();
+    let parsed = fork.parse::<T>().ok()?;
+    input.advance_to(&fork);
+    Some(parsed)
+}
+
 pub fn walk_block_to_variable(block: &syn::Block) -> Option<proc_macro2::TokenStream> {
     if let Some(statement) = block.stmts.first() {
         return walk_statement(statement);
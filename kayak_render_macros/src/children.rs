@@ -32,6 +32,7 @@ impl Children {
                     _ => None,
                 })
                 .collect(),
+            child @ (Child::Match(..) | Child::If(..)) => child.get_slot_clonable_attributes(),
             _ => vec![],
         };
         tokens.extend(regular_tokens);
@@ -52,6 +53,24 @@ impl Children {
         tokens
     }
 
+    /// The clonable attributes of all of these children, excluding the given bindings
+    ///
+    /// Multiple children also clone `children` (see [`Self::as_option_of_tuples_tokens`]), so it's
+    /// included for them.
+    pub fn get_all_clonable_attributes(
+        &self,
+        bindings: &HashSet<String>,
+    ) -> Vec<proc_macro2::TokenStream> {
+        let mut tokens: Vec<_> = (0..self.nodes.len())
+            .flat_map(|index| self.get_clonable_attributes(index))
+            .filter(|attr| !bindings.contains(&attr.to_string()))
+            .collect();
+        if self.nodes.len() > 1 {
+            tokens.push(quote! { children });
+        }
+        tokens
+    }
+
     pub fn as_option_of_tuples_tokens(&self) -> proc_macro2::TokenStream {
         let kayak_core = get_core_crate();
 
@@ -84,6 +103,14 @@ impl Children {
                         quote! {
                             #(#children_quotes)*.clone()
                         }
+                    } else if let Some(slots_builder) = self.nodes[0].build_slots(0) {
+                        quote! {
+                            Some(#kayak_core::Children::new(move |parent_id: Option<#kayak_core::Index>, context: &mut #kayak_core::KayakContextRef| {
+                                #cloned_attrs
+                                #slots_builder
+                                context.commit();
+                            }))
+                        }
                     } else {
                         let children_builder = build_widget_stream(
                             quote! { child_widget },
@@ -146,12 +173,19 @@ impl Children {
 
                 let mut output = Vec::new();
                 output.push(quote! { #base_clone });
+                // Matches and `if` expressions take up one index per branch, so the index can differ
+                // from `i`
+                let mut index = 0;
                 for i in 0..children_quotes.len() {
                     output.push(quote! { #base_clones_inner });
-                    let name: proc_macro2::TokenStream = format!("child{}", i).parse().unwrap();
-                    let child =
-                        build_widget_stream(quote! { #name }, children_quotes[i].clone(), i);
+                    let child = if let Some(slots_builder) = self.nodes[i].build_slots(index) {
+                        slots_builder
+                    } else {
+                        let name: proc_macro2::TokenStream = format!("child{}", i).parse().unwrap();
+                        build_widget_stream(quote! { #name }, children_quotes[i].clone(), index)
+                    };
                     output.push(quote! { #child });
+                    index += self.nodes[i].slot_count();
                 }
 
                 quote! {
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::quote;
+use syn::braced;
+use syn::parse::{Parse, ParseStream, Result};
+
+use crate::children::Children;
+use crate::match_child::{build_slots, collect_bindings, parse_braced_children};
+
+/// An `if` expression in the children position (`{ if show { <Foo /> } else { <Bar /> } }`)
+///
+/// Like a [`MatchChild`](crate::match_child::MatchChild), each branch is rendered into its own
+/// slot. Only the taken branch's children are created, so the widgets of a branch that isn't
+/// taken are never constructed, rendered, or committed. Unlike the arms of a `match`, a branch's
+/// widgets are removed as soon as it's no longer taken.
+#[derive(Clone)]
+pub struct IfChild {
+    branches: Vec<IfBranch>,
+}
+
+#[derive(Clone)]
+struct IfBranch {
+    /// The condition of this branch, or `None` for the final `else`
+    condition: Option<syn::Expr>,
+    children: Children,
+}
+
+impl IfChild {
+    /// Returns true if the given input is a braced `if` expression
+    pub fn peek(input: ParseStream) -> bool {
+        match input.fork().parse::<Group>() {
+            Ok(group) if group.delimiter() == Delimiter::Brace => matches!(
+                group.stream().into_iter().next(),
+                Some(TokenTree::Ident(ident)) if ident == "if"
+            ),
+            _ => false,
+        }
+    }
+
+    /// The number of child indices taken up by this expression (one per branch)
+    pub fn slot_count(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// The clonable attributes of all branches, excluding those bound by an `if let` pattern
+    pub fn get_clonable_attributes(&self) -> Vec<TokenStream> {
+        self.branches
+            .iter()
+            .flat_map(|branch| {
+                let mut bindings = HashSet::new();
+                if let Some(syn::Expr::Let(condition)) = &branch.condition {
+                    collect_bindings(&condition.pat, &mut bindings);
+                }
+                branch.children.get_all_clonable_attributes(&bindings)
+            })
+            .collect()
+    }
+
+    /// Creates the token stream for adding this expression's slots, starting at the given sibling index
+    pub fn build(&self, base_index: usize) -> TokenStream {
+        let slot_count = self.branches.len();
+
+        let mut selector = TokenStream::new();
+        for (index, branch) in self.branches.iter().enumerate() {
+            let children = branch.children.as_option_of_tuples_tokens();
+            let branch = match &branch.condition {
+                Some(condition) => quote! { if #condition { (#index, #children) } },
+                None => quote! { { (#index, #children) } },
+            };
+
+            if index > 0 {
+                selector.extend(quote! { else });
+            }
+            selector.extend(branch);
+        }
+
+        // Without an `else`, no slot is active if none of the conditions hold
+        if self
+            .branches
+            .iter()
+            .all(|branch| branch.condition.is_some())
+        {
+            selector.extend(quote! { else { (#slot_count, None) } });
+        }
+
+        build_slots(selector, slot_count, base_index, false)
+    }
+}
+
+impl Parse for IfChild {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        braced!(content in input);
+
+        let mut branches = Vec::new();
+        content.parse::<syn::Token![if]>()?;
+        loop {
+            let condition = syn::Expr::parse_without_eager_brace(&content)?;
+            branches.push(IfBranch {
+                condition: Some(condition),
+                children: parse_braced_children(&content)?,
+            });
+
+            if content.parse::<Option<syn::Token![else]>>()?.is_none() {
+                break;
+            }
+
+            if content.parse::<Option<syn::Token![if]>>()?.is_none() {
+                branches.push(IfBranch {
+                    condition: None,
+                    children: parse_braced_children(&content)?,
+                });
+                break;
+            }
+        }
+
+        if !content.is_empty() {
+            return Err(content.error("Expected only a single `if` expression"));
+        }
+
+        Ok(Self { branches })
+    }
+}
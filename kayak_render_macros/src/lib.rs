@@ -1,13 +1,22 @@
 extern crate proc_macro;
 
 mod function_component;
+mod if_child;
+mod match_child;
 mod tags;
 
 mod attribute;
 mod child;
 mod children;
 mod partial_eq;
+mod props_debug;
+mod props_partial_eq;
 mod use_effect;
+mod use_interval;
+mod use_list_navigation;
+mod use_query;
+mod use_throttled_state;
+mod use_timeout;
 mod widget;
 mod widget_attributes;
 mod widget_builder;
@@ -17,9 +26,16 @@ use function_component::WidgetArguments;
 use partial_eq::impl_dyn_partial_eq;
 use proc_macro::TokenStream;
 use proc_macro_error::proc_macro_error;
+use props_debug::impl_props_debug;
+use props_partial_eq::impl_props_partial_eq;
 use quote::quote;
 use syn::{parse_macro_input, parse_quote};
 use use_effect::UseEffect;
+use use_interval::UseInterval;
+use use_list_navigation::UseListNavigation;
+use use_query::UseQuery;
+use use_throttled_state::UseThrottledState;
+use use_timeout::UseTimeout;
 use widget::ConstructedWidget;
 
 use crate::widget::Widget;
@@ -45,8 +61,111 @@ pub fn render(input: TokenStream) -> TokenStream {
     TokenStream::from(result)
 }
 
+/// A variant of [`render`] that defers building its widgets, returning a `Subtree`
+///
+/// The returned subtree can be committed under any parent (or as the root) using
+/// `KayakContext::commit_subtree`. Unlike [`render`], this doesn't require a context to be in
+/// scope, allowing UI fragments to be defined in separate functions.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn sidebar() -> Subtree {
+///     render_subtree! {
+///         <Element>
+///             <Text content={"Sidebar".to_string()} />
+///         </Element>
+///     }
+/// }
+///
+/// context.commit_subtree(sidebar(), Some(sidebar_container));
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn render_subtree(input: TokenStream) -> TokenStream {
+    let widget = parse_macro_input!(input as Widget);
+
+    let kayak_core = get_core_crate();
+
+    let result = quote! {
+        #kayak_core::Subtree::new(move |context: &mut #kayak_core::KayakContextRef| {
+            let children: Option<#kayak_core::Children> = None;
+            #widget
+        })
+    };
+
+    TokenStream::from(result)
+}
+
 /// A proc macro that turns RSX syntax into structure constructors and calls the
 /// context to create the widgets.
+///
+/// # Matching
+///
+/// A `match` expression can be used in the children position to render different widgets for each
+/// arm. An arm can contain a single widget (including a fragment), multiple widgets wrapped in braces,
+/// or `()` to render nothing:
+///
+/// ```ignore
+/// rsx! {
+///     <>
+///         {match status.clone() {
+///             Status::Loading => <Text content={"Loading...".to_string()} />,
+///             Status::Error(message) => {
+///                 <Text content={message} />
+///                 <Button on_event={Some(on_retry)} />
+///             }
+///             Status::Done => (),
+///         }}
+///     </>
+/// }
+/// ```
+///
+/// Each arm is given its own child index, so the widgets of one arm are never reused by another.
+/// The widgets of an arm stay mounted while a different arm matches (without being laid out, drawn
+/// or focusable), so they keep their state for when the arm matches again. Values bound by a
+/// pattern are moved into the arm's children, so match on an owned (or cloned) value.
+/// Exhaustiveness is checked like any other `match`.
+///
+/// # Conditions
+///
+/// Similarly, an `if` expression (including `else if`, `else`, and `if let`) can be used to only
+/// render widgets when a condition holds. Each branch contains any number of widgets:
+///
+/// ```ignore
+/// rsx! {
+///     <>
+///         {if show_details {
+///             <Details />
+///         } else if let Some(error) = error.clone() {
+///             <Text content={error} />
+///         }}
+///     </>
+/// }
+/// ```
+///
+/// Only the branch that's taken is built, so the widgets of every other branch are never
+/// constructed or rendered. This makes it cheap to keep large panels behind a condition. As with
+/// `match`, each branch has its own child index. Unlike the arms of a `match`, a branch's widgets
+/// are removed once it's no longer taken, so it starts fresh whenever it's taken again.
+///
+/// # Custom Tags
+///
+/// A namespaced tag (such as `<plugin:Foo>`) refers to a widget that's registered at runtime
+/// rather than a widget type, which allows plugins or data to decide which widget is shown:
+///
+/// ```ignore
+/// context.register_widget::<Foo>("plugin:Foo");
+///
+/// rsx! {
+///     <plugin:Foo styles={Some(styles)} label={"Hello".to_string()} />
+/// }
+/// ```
+///
+/// The common props (`styles`, `on_event`, `on_event_capture`, `on_layout`, `focusable` and
+/// `children`) are passed as-is, while all other attributes are stored by name in the widget's
+/// `DynamicProps`, which it converts into its own props via `From<DynamicProps>`. Rendering a tag
+/// that hasn't been registered panics with the name of the missing tag.
 #[proc_macro]
 #[proc_macro_error]
 pub fn rsx(input: TokenStream) -> TokenStream {
@@ -89,12 +208,106 @@ pub fn widget(args: TokenStream, item: TokenStream) -> TokenStream {
 }
 
 /// A derive macro for the `WidgetProps` trait
-#[proc_macro_derive(WidgetProps, attributes(prop_field))]
+///
+/// Fields can be given a default value with `#[prop_field(default = ...)]`, which is used whenever
+/// the prop isn't set in `rsx!`. The value can be any expression (including constants and
+/// function calls) that evaluates to the field's type. When any field has a default, this derive
+/// also implements `Default` (using `Default::default()` for all other fields), so the struct
+/// must not derive `Default` itself.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(WidgetProps, Debug, PartialEq, Clone)]
+/// struct MyWidgetProps {
+///     #[prop_field(default = 14.0)]
+///     size: f32,
+///     #[prop_field(default = Color::new(1.0, 1.0, 1.0, 1.0))]
+///     color: Color,
+///     #[prop_field(default = String::from("Untitled"))]
+///     title: String,
+///     #[prop_field(Styles)]
+///     styles: Option<Style>,
+/// }
+/// ```
+///
+/// The props can also be checked before each render in debug builds by giving a validation function
+/// with `#[widget_props(validate = ...)]`. It takes the props by reference and returns a
+/// `Result<(), String>`, where an error causes the render to panic with the widget's name.
+///
+/// ```ignore
+/// #[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+/// #[widget_props(validate = Self::check_range)]
+/// struct SliderProps {
+///     min: f32,
+///     max: f32,
+/// }
+///
+/// impl SliderProps {
+///     fn check_range(&self) -> Result<(), String> {
+///         if self.min <= self.max {
+///             Ok(())
+///         } else {
+///             Err(format!("min ({}) must not exceed max ({})", self.min, self.max))
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro_derive(WidgetProps, attributes(prop_field, widget_props))]
 #[proc_macro_error]
 pub fn derive_widget_props(item: TokenStream) -> TokenStream {
     impl_widget_props(item)
 }
 
+/// A derive macro for `PartialEq` on widget props
+///
+/// This works like `#[derive(PartialEq)]` but allows individual fields to control how they're
+/// compared (and therefore how sensitive a widget is to re-rendering) using `#[prop_field(eq = "...")]`:
+///
+/// * `eq = "value"`: Compare the field using its own `PartialEq` implementation (the default)
+/// * `eq = "ptr"`: Compare the field by pointer using `ptr_eq` (only valid for `Arc`, `Rc`, or an
+///   `Option` of either)
+/// * `eq = "ignore"`: Exclude the field from comparison entirely
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(WidgetProps, PropsPartialEq, Default, Debug, Clone)]
+/// struct MyWidgetProps {
+///     #[prop_field(eq = "ptr")]
+///     data: Arc<LargeData>,
+///     #[prop_field(eq = "ignore")]
+///     last_updated: Option<Instant>,
+/// }
+/// ```
+#[proc_macro_derive(PropsPartialEq, attributes(prop_field))]
+#[proc_macro_error]
+pub fn derive_props_partial_eq(item: TokenStream) -> TokenStream {
+    impl_props_partial_eq(item)
+}
+
+/// A derive macro for `Debug` on widget props
+///
+/// This works like `#[derive(Debug)]` but allows fields that don't implement `Debug` (such as
+/// closures and handlers) to be marked with `#[prop_field(skip_debug)]`. These fields will be
+/// printed as `"<callback>"` instead.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(WidgetProps, PropsDebug, Default, Clone, PartialEq)]
+/// struct MyWidgetProps {
+///     label: String,
+///     #[prop_field(skip_debug)]
+///     on_select: Option<MyCallback>,
+/// }
+/// ```
+#[proc_macro_derive(PropsDebug, attributes(prop_field))]
+#[proc_macro_error]
+pub fn derive_props_debug(item: TokenStream) -> TokenStream {
+    impl_props_debug(item)
+}
+
 #[proc_macro_derive(DynPartialEq)]
 pub fn dyn_partial_eq_macro_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
@@ -128,9 +341,13 @@ pub fn dyn_partial_eq(_: TokenStream, input: TokenStream) -> TokenStream {
 
 /// Register some state data with an initial value.
 ///
-/// Once the state is created, this macro returns the current value, a closure for updating the current value, and
+/// Once the state is created, this macro returns the current value, a setter for updating the current value, and
 /// the raw Binding in a tuple.
 ///
+/// The setter can be called like a closure (`set_state(value)`) to replace the value. For struct state,
+/// `set_state.update(|state| state.count += 1)` mutates the latest value in-place, which avoids stale values
+/// when multiple updates happen within the same frame.
+///
 /// For more details, check out [React's documentation](https://reactjs.org/docs/hooks-state.html),
 /// upon which this macro is based.
 ///
@@ -171,10 +388,7 @@ pub fn use_state(initial_state: TokenStream) -> TokenStream {
     let result = quote! {{
         use #kayak_core::{Bound, MutableBound};
         let state = context.create_state(#initial_state).unwrap();
-        let cloned_state = state.clone();
-        let set_state = move |value| {
-            cloned_state.set(value);
-        };
+        let set_state = #kayak_core::StateSetter::new(state.clone());
 
         let state_value = state.get();
 
@@ -183,6 +397,114 @@ pub fn use_state(initial_state: TokenStream) -> TokenStream {
     TokenStream::from(result)
 }
 
+/// Register some state data with an initial value, throttling how often it can be updated.
+///
+/// This works like [`use_state!`] except that the setter commits its value (and re-renders the
+/// widget) at most once per `interval`. Intermediate values set within the interval are coalesced,
+/// with only the latest being kept. That latest value is always committed once the interval has
+/// elapsed, so the state settles on the final value of a burst of updates. The interval may be
+/// changed between renders.
+///
+/// This is useful for high-frequency inputs, like sliders, that drive expensive re-renders.
+///
+/// # Arguments
+///
+/// * `initial_state`: The initial value for the state
+/// * `interval`: The minimum [`Duration`](std::time::Duration) between commits
+///
+/// returns: (state, set_state, state_binding)
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use kayak_render_macros::use_throttled_state;
+///
+/// let (value, set_value, ..) = use_throttled_state!(0.0, Duration::from_millis(100));
+///
+/// // Called many times per frame, but only re-renders every 100ms
+/// set_value(0.5);
+/// ```
+#[proc_macro]
+pub fn use_throttled_state(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as UseThrottledState);
+    args.build()
+}
+
+/// Runs a callback periodically, every `interval`.
+///
+/// This macro takes on the form: `use_interval!(interval, callback)` or
+/// `use_interval!(interval, callback, paused)`. The callback is run (at most once per render) whenever
+/// the interval elapses, with the widget automatically re-rendering when the next tick is due. Ticks
+/// are timed from the previous tick using the context clock, so they don't drift with the frame rate.
+///
+/// Changing the interval restarts the timer, as does resuming it after it was paused. The timer
+/// stops on its own once the widget is removed.
+///
+/// # Arguments
+///
+/// * `interval`: The [`Duration`](std::time::Duration) between ticks
+/// * `callback`: The closure to run on each tick
+/// * `paused`: Whether the timer is paused (defaults to `false`)
+///
+/// returns: ()
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use kayak_render_macros::{use_interval, use_state};
+///
+/// let (is_paused, set_is_paused, ..) = use_state!(false);
+/// let (seconds, set_seconds, ..) = use_state!(0);
+///
+/// use_interval!(Duration::from_secs(1), move || {
+///     set_seconds(seconds + 1);
+/// }, is_paused);
+/// ```
+#[proc_macro]
+pub fn use_interval(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as UseInterval);
+    args.build()
+}
+
+/// Runs a callback once, after a delay.
+///
+/// This macro takes on the form: `use_timeout!(delay, callback)` or `use_timeout!(delay, callback, [dep_1, dep_2, ...])`.
+/// The callback is run once the delay has elapsed since the widget was first rendered. It shares its
+/// timing with [`use_interval!`], using the context clock and re-rendering the widget when it's due.
+///
+/// The timeout restarts whenever the delay or any of the dependencies change. Dependencies are
+/// values implementing [`Hash`] (such as a `bool` or an ID), rather than Bindings. The timer is dropped
+/// once the widget is removed, and each `use_timeout!` in a widget keeps its own timer.
+///
+/// # Arguments
+///
+/// * `delay`: The [`Duration`](std::time::Duration) to wait
+/// * `callback`: The closure to run once the delay has elapsed
+/// * `dependencies`: The values that restart the timeout when changed (in the form `[dep_1, dep_2, ...]`)
+///
+/// returns: [`TimeoutHandle`](kayak_core::TimeoutHandle), which can be used to restart or cancel the timeout
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use kayak_render_macros::{use_state, use_timeout};
+///
+/// let (is_visible, set_is_visible, ..) = use_state!(true);
+///
+/// // Hide the toast after 3 seconds, starting over whenever it's shown again
+/// let timeout = use_timeout!(Duration::from_secs(3), move || {
+///     set_is_visible(false);
+/// }, [is_visible]);
+/// ```
+#[proc_macro]
+pub fn use_timeout(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as UseTimeout);
+    args.build()
+}
+
 /// Registers a side-effect callback for a given set of dependencies.
 ///
 /// This macro takes on the form: `use_effect!(callback, dependencies)`. The callback is
@@ -236,6 +558,228 @@ pub fn use_effect(input: TokenStream) -> TokenStream {
     effect.build()
 }
 
+/// Get the current widget's layout as of the previous render.
+///
+/// This macro takes on the form: `use_layout!()` and returns an `Option<Layout>`. Calling it will
+/// cause the widget to be re-rendered whenever its layout changes.
+///
+/// # Important Note
+///
+/// Layout is calculated _after_ a widget renders. This means the value returned by this macro will
+/// always be one frame behind (and `None` on the very first render). This is fine for most adaptive
+/// cases, such as switching between wrapped and unwrapped content, but it should not be relied on for
+/// anything that needs to be pixel-perfect on the same frame.
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_render_macros::use_layout;
+///
+/// let layout = use_layout!();
+/// let is_narrow = layout.map(|layout| layout.width < 200.0).unwrap_or_default();
+/// ```
+#[proc_macro]
+pub fn use_layout(_input: TokenStream) -> TokenStream {
+    let result = quote! {
+        context.use_layout()
+    };
+    TokenStream::from(result)
+}
+
+/// Get the current theme of the widget.
+///
+/// This macro takes on the form: `use_theme!()` and returns a `Theme`, which is the theme of the
+/// nearest `ThemeProvider` (or the app-wide theme if there is none). Calling it subscribes the
+/// widget to the theme, so that it's re-rendered whenever the theme changes. Since it only needs
+/// the `context`, it can also be used directly within the attributes of `rsx!`.
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_render_macros::{rsx, use_theme};
+///
+/// rsx! {
+///     <Text content={"Hello".to_string()} size={use_theme!().font_size.large} />
+/// }
+/// ```
+#[proc_macro]
+pub fn use_theme(_input: TokenStream) -> TokenStream {
+    let result = quote! {
+        context.use_theme()
+    };
+    TokenStream::from(result)
+}
+
+/// Get the value passed to this macro on the previous render.
+///
+/// This macro takes on the form: `use_previous!(value)` and returns an `Option` of the value's
+/// type. The value is stored for the next render, while the one from the previous render is
+/// returned (or `None` on the first render). Storing the value doesn't re-render the widget, so the
+/// returned value always lags exactly one render behind.
+///
+/// # Arguments
+///
+/// * `value`: The value to store for the next render
+///
+/// returns: `Option<T>`
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_render_macros::{use_previous, use_state};
+///
+/// let (count, set_count, ..) = use_state!(0);
+/// let previous_count = use_previous!(count);
+///
+/// // Whether the count went up since the last render
+/// let is_increasing = previous_count.map_or(false, |previous_count| count > previous_count);
+/// ```
+#[proc_macro]
+pub fn use_previous(input: TokenStream) -> TokenStream {
+    let value = parse_macro_input!(input as syn::Expr);
+    let result = quote! {
+        context.use_previous(#value)
+    };
+    TokenStream::from(result)
+}
+
+/// Create an animation that's controlled from code.
+///
+/// This macro takes on the form: `use_animation!(options)` and returns an `AnimationController`.
+/// The animation is configured by the given `AnimationOptions` (its duration, easing, and loop
+/// count), and starts out paused at the beginning. Its controls (`play`, `pause`, `reverse`, and
+/// `seek`) can be used from anywhere, such as event handlers, and take effect on the next render.
+/// While it plays, the widget re-renders every frame so it can read the animation's `value()`.
+///
+/// # Arguments
+///
+/// * `options`: The `AnimationOptions` of the animation
+///
+/// returns: `AnimationController`
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use kayak_core::{AnimationOptions, Easing};
+/// # use kayak_render_macros::use_animation;
+///
+/// let pulse = use_animation!(AnimationOptions {
+///     duration: Duration::from_secs(1),
+///     easing: Easing::EaseInOut,
+///     loops: None,
+/// });
+///
+/// let opacity = 0.5 + pulse.value() * 0.5;
+/// let on_click = move || pulse.play();
+/// ```
+#[proc_macro]
+pub fn use_animation(input: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(input as syn::Expr);
+    let result = quote! {
+        context.use_animation(#options).unwrap()
+    };
+    TokenStream::from(result)
+}
+
+/// Get an identifier that's unique to the current widget instance.
+///
+/// This macro takes on the form: `use_id!()` and returns a `String`. The identifier stays the same
+/// across renders for as long as the widget is mounted, and isn't shared with any other mounted
+/// widget. It's derived from the widget's index in the tree, so building the same tree always
+/// produces the same identifiers.
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_render_macros::use_id;
+///
+/// let input_id = use_id!();
+/// let label_id = format!("{}-label", input_id);
+/// ```
+#[proc_macro]
+pub fn use_id(_input: TokenStream) -> TokenStream {
+    let result = quote! {
+        context.use_id()
+    };
+    TokenStream::from(result)
+}
+
+/// Manages the keyboard navigation of a list, such as a menu or listbox.
+///
+/// This macro takes on the form: `use_list_navigation!(items, on_activate)` or
+/// `use_list_navigation!(items, on_activate, wrap)`. The highlighted item is moved with the arrow
+/// keys, Home/End, and type-ahead search, skipping disabled items, and the widget re-renders whenever
+/// it moves. Pressing Enter calls `on_activate` with the index of the highlighted item.
+///
+/// The returned navigation's `on_event()` handler should be given to the focusable widget that
+/// contains the list.
+///
+/// # Arguments
+///
+/// * `items`: The [`ListItem`](kayak_core::ListItem)s of the list
+/// * `on_activate`: The closure to run with the index of an activated item
+/// * `wrap`: Whether moving past either end of the list continues from the other end (defaults to `true`)
+///
+/// returns: [`ListNavigation`](kayak_core::ListNavigation)
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_core::ListItem;
+/// # use kayak_render_macros::use_list_navigation;
+///
+/// let items = vec![ListItem::new("Open"), ListItem::new("Save").with_disabled(true), ListItem::new("Close")];
+/// let navigation = use_list_navigation!(items, move |index| {
+///     println!("Activated: {}", index);
+/// });
+///
+/// let highlighted = navigation.highlighted();
+/// let on_event = navigation.on_event();
+/// ```
+#[proc_macro]
+pub fn use_list_navigation(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as UseListNavigation);
+    args.build()
+}
+
+/// Read data from the Bevy `World`, re-rendering the widget when it changes.
+///
+/// This macro takes on the form: `use_query!(param, projection)`, where `param` is any Bevy
+/// `SystemParam` (such as a `Query`, a `Res`, or a tuple of them) and `projection` is a closure
+/// that selects a value from it. The value returned by the projection is returned by the macro.
+///
+/// The projection is re-run once per frame, and the widget is only re-rendered when the projected
+/// value changes (as determined by `PartialEq`). This means change detection is as granular as the
+/// projection: mutating a component re-renders the widget only if the projected part of it changed.
+/// Change detection within the projection itself (such as `Changed<T>` filters or
+/// `Res::is_changed`) is relative to the previous frame.
+///
+/// This requires the `bevy_renderer` feature.
+///
+/// # Arguments
+///
+/// * `param`: The `SystemParam` to query the `World` with
+/// * `projection`: The closure that selects a value from the system parameter
+///
+/// returns: The projected value
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_render_macros::use_query;
+///
+/// let health = use_query!(Query<&Health, With<Player>>, |query| {
+///     query.get_single().map_or(0, |health| health.0)
+/// });
+/// let score = use_query!(Res<Score>, |score| score.0);
+/// ```
+#[proc_macro]
+pub fn use_query(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as UseQuery);
+    args.build()
+}
+
 /// Helper method for getting the core crate
 ///
 /// Depending on the usage of the macro, this will become `crate`, `kayak_core`,
@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{braced, parenthesized, Pat, PatOr};
+
+use crate::child::Child;
+use crate::children::Children;
+use crate::get_core_crate;
+use crate::widget::Widget;
+
+/// A `match` expression in the children position (`{ match state { A => <Foo />, _ => <Bar /> } }`)
+///
+/// Each arm is rendered into its own slot (an empty `Fragment`). The slots are always added, with
+/// only the matched arm's slot receiving children, so each arm keeps a stable child index and its
+/// widgets are never reused for a different arm. The slots of the other arms are made inactive,
+/// which keeps their widgets mounted (along with their state) for when their arm matches again.
+#[derive(Clone)]
+pub struct MatchChild {
+    expr: syn::Expr,
+    arms: Vec<MatchArm>,
+}
+
+#[derive(Clone)]
+struct MatchArm {
+    pat: Pat,
+    guard: Option<syn::Expr>,
+    children: Children,
+}
+
+impl MatchChild {
+    /// Returns true if the given input is a braced `match` expression
+    pub fn peek(input: ParseStream) -> bool {
+        match input.fork().parse::<Group>() {
+            Ok(group) if group.delimiter() == Delimiter::Brace => matches!(
+                group.stream().into_iter().next(),
+                Some(TokenTree::Ident(ident)) if ident == "match"
+            ),
+            _ => false,
+        }
+    }
+
+    /// The number of child indices taken up by this match (one per arm)
+    pub fn slot_count(&self) -> usize {
+        self.arms.len()
+    }
+
+    /// The clonable attributes of all arms, excluding those bound by the arm's pattern
+    pub fn get_clonable_attributes(&self) -> Vec<TokenStream> {
+        self.arms
+            .iter()
+            .flat_map(|arm| {
+                let mut bindings = HashSet::new();
+                collect_bindings(&arm.pat, &mut bindings);
+                arm.children.get_all_clonable_attributes(&bindings)
+            })
+            .collect()
+    }
+
+    /// Creates the token stream for adding this match's slots, starting at the given sibling index
+    pub fn build(&self, base_index: usize) -> TokenStream {
+        let expr = &self.expr;
+        let arm_count = self.arms.len();
+
+        let arms = self.arms.iter().enumerate().map(|(index, arm)| {
+            let pat = &arm.pat;
+            let guard = arm.guard.as_ref().map(|guard| quote! { if #guard });
+            let children = arm.children.as_option_of_tuples_tokens();
+            quote! {
+                #pat #guard => (#index, #children),
+            }
+        });
+
+        build_slots(
+            quote! {
+                match #expr {
+                    #(#arms)*
+                }
+            },
+            arm_count,
+            base_index,
+            true,
+        )
+    }
+}
+
+/// Creates the token stream for adding the given number of slots, starting at the given sibling index
+///
+/// The `selector` must evaluate to the index of the active slot along with its children. Only the
+/// active slot receives children, so the selector should only create the children it returns.
+///
+/// If `keep_inactive` is true, the other slots are made
+/// [inactive](kayak_core::FragmentProps::inactive), keeping what they last rendered. Otherwise,
+/// their widgets are removed.
+pub fn build_slots(
+    selector: TokenStream,
+    slot_count: usize,
+    base_index: usize,
+    keep_inactive: bool,
+) -> TokenStream {
+    let kayak_core = get_core_crate();
+
+    quote! {{
+        let (active_arm, mut arm_children): (usize, Option<#kayak_core::Children>) = #selector;
+
+        for arm_index in 0..#slot_count {
+            let is_active = arm_index == active_arm;
+            let slot_children = if is_active { arm_children.take() } else { None };
+
+            // Empty slots still need to commit so that their previous widgets are removed
+            let mut slot_props = #kayak_core::FragmentProps::default();
+            slot_props.inactive = #keep_inactive && !is_active;
+            slot_props.children = Some(slot_children.unwrap_or_else(|| {
+                #kayak_core::Children::new(|_, context: &mut #kayak_core::KayakContextRef| {
+                    context.commit();
+                })
+            }));
+
+            let slot = <#kayak_core::Fragment as #kayak_core::Widget>::constructor(slot_props);
+            context.add_widget(slot, #base_index + arm_index);
+        }
+    }}
+}
+
+impl Parse for MatchChild {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        braced!(content in input);
+
+        content.parse::<syn::Token![match]>()?;
+        let expr = syn::Expr::parse_without_eager_brace(&content)?;
+
+        let arms_content;
+        braced!(arms_content in content);
+        let mut arms = Vec::new();
+        while !arms_content.is_empty() {
+            arms.push(arms_content.parse::<MatchArm>()?);
+        }
+
+        if !content.is_empty() {
+            return Err(content.error("Expected only a single `match` expression"));
+        }
+
+        Ok(Self { expr, arms })
+    }
+}
+
+impl Parse for MatchArm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let leading_vert: Option<syn::Token![|]> = input.parse()?;
+        let mut pat: Pat = input.parse()?;
+        if leading_vert.is_some() || input.peek(syn::Token![|]) {
+            let mut cases = Punctuated::new();
+            cases.push_value(pat);
+            while input.peek(syn::Token![|]) {
+                cases.push_punct(input.parse()?);
+                cases.push_value(input.parse()?);
+            }
+            pat = Pat::Or(PatOr {
+                attrs: Vec::new(),
+                leading_vert,
+                cases,
+            });
+        }
+
+        let guard = if input.peek(syn::Token![if]) {
+            input.parse::<syn::Token![if]>()?;
+            Some(input.parse::<syn::Expr>()?)
+        } else {
+            None
+        };
+
+        input.parse::<syn::Token![=>]>()?;
+
+        let children = if input.peek(syn::token::Brace) {
+            // Multiple children: `A => { <Foo /> <Bar /> }`
+            parse_braced_children(input)?
+        } else if input.peek(syn::token::Paren) {
+            // No children: `A => ()`
+            let body;
+            parenthesized!(body in input);
+            if !body.is_empty() {
+                return Err(body.error("Expected `()` or a widget"));
+            }
+            Children::new(vec![])
+        } else {
+            // A single widget (or fragment): `A => <Foo />`
+            Children::new(vec![Child::Widget(Widget::custom_parse(input, true)?)])
+        };
+
+        input.parse::<Option<syn::Token![,]>>()?;
+
+        Ok(Self {
+            pat,
+            guard,
+            children,
+        })
+    }
+}
+
+/// Parses a braced list of children (`{ <Foo /> <Bar /> }`)
+pub fn parse_braced_children(input: ParseStream) -> Result<Children> {
+    let body;
+    braced!(body in input);
+    let mut nodes = Vec::new();
+    while !body.is_empty() {
+        nodes.push(body.parse::<Child>()?);
+    }
+    Ok(Children::new(nodes))
+}
+
+/// Collects the names of all variables bound by the given pattern
+pub fn collect_bindings(pat: &Pat, bindings: &mut HashSet<String>) {
+    match pat {
+        Pat::Ident(pat) => {
+            bindings.insert(pat.ident.to_string());
+            if let Some((_, subpat)) = &pat.subpat {
+                collect_bindings(subpat, bindings);
+            }
+        }
+        Pat::Box(pat) => collect_bindings(&pat.pat, bindings),
+        Pat::Reference(pat) => collect_bindings(&pat.pat, bindings),
+        Pat::Type(pat) => collect_bindings(&pat.pat, bindings),
+        Pat::Or(pat) => pat
+            .cases
+            .iter()
+            .for_each(|pat| collect_bindings(pat, bindings)),
+        Pat::Slice(pat) => pat
+            .elems
+            .iter()
+            .for_each(|pat| collect_bindings(pat, bindings)),
+        Pat::Tuple(pat) => pat
+            .elems
+            .iter()
+            .for_each(|pat| collect_bindings(pat, bindings)),
+        Pat::TupleStruct(pat) => pat
+            .pat
+            .elems
+            .iter()
+            .for_each(|pat| collect_bindings(pat, bindings)),
+        Pat::Struct(pat) => pat
+            .fields
+            .iter()
+            .for_each(|field| collect_bindings(&field.pat, bindings)),
+        _ => {}
+    }
+}
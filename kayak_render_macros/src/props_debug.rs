@@ -0,0 +1,107 @@
+use proc_macro::TokenStream;
+use proc_macro_error::emit_error;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Field, Fields};
+
+use crate::widget_props::parse_prop_field_args;
+
+/// The ident for the debug skip option (`#[prop_field(skip_debug)]`)
+pub(crate) const PROP_SKIP_DEBUG: &str = "skip_debug";
+
+/// The placeholder printed in place of skipped fields
+const SKIPPED_PLACEHOLDER: &str = "<callback>";
+
+pub(crate) fn impl_props_debug(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        ..
+    } = parse_macro_input!(input);
+
+    let fields = match data {
+        Data::Struct(data) => data.fields,
+        Data::Enum(data) => {
+            emit_error!(data.enum_token.span(), "Cannot derive PropsDebug for enum");
+            return quote!().into();
+        }
+        Data::Union(data) => {
+            emit_error!(
+                data.union_token.span(),
+                "Cannot derive PropsDebug for union"
+            );
+            return quote!().into();
+        }
+    };
+
+    let name = ident.to_string();
+    let body = match fields {
+        Fields::Named(fields) => {
+            let entries = fields.named.into_iter().map(|field| {
+                let ident = field.ident.clone().unwrap();
+                let name = ident.to_string();
+                let value = quote_value(quote!(#ident), &field);
+                quote! {
+                    .field(#name, #value)
+                }
+            });
+            quote! {
+                f.debug_struct(#name)
+                    #(#entries)*
+                    .finish()
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let entries = fields
+                .unnamed
+                .into_iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    let index = syn::Index::from(index);
+                    let value = quote_value(quote!(#index), &field);
+                    quote! {
+                        .field(#value)
+                    }
+                });
+            quote! {
+                f.debug_tuple(#name)
+                    #(#entries)*
+                    .finish()
+            }
+        }
+        Fields::Unit => quote! {
+            f.write_str(#name)
+        },
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let output = quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    };
+
+    output.into()
+}
+
+/// Generates the value to print for a single field
+fn quote_value(member: proc_macro2::TokenStream, field: &Field) -> proc_macro2::TokenStream {
+    if should_skip(field) {
+        quote! {
+            &::core::format_args!(#SKIPPED_PLACEHOLDER)
+        }
+    } else {
+        quote! {
+            &self.#member
+        }
+    }
+}
+
+/// Checks if the given field is marked with `#[prop_field(skip_debug)]`
+fn should_skip(field: &Field) -> bool {
+    parse_prop_field_args(field)
+        .iter()
+        .any(|arg| arg.ident == PROP_SKIP_DEBUG && arg.value.is_none())
+}
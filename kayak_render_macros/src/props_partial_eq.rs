@@ -0,0 +1,199 @@
+use proc_macro::TokenStream;
+use proc_macro_error::emit_error;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Expr, ExprLit, Field, Fields,
+    GenericArgument, Lit, PathArguments, Type,
+};
+
+use crate::widget_props::parse_prop_field_args;
+
+/// The ident for the equality option (`#[prop_field(eq = "ptr")]`)
+pub(crate) const PROP_EQ: &str = "eq";
+
+const EQ_VALUE: &str = "value";
+const EQ_PTR: &str = "ptr";
+const EQ_IGNORE: &str = "ignore";
+
+/// The pointer types that support `eq = "ptr"`
+const POINTER_TYPES: &[&str] = &["Arc", "Rc"];
+
+/// How a single field should be compared
+enum FieldEq {
+    /// Compare using `PartialEq` (the default)
+    Value,
+    /// Compare using `ptr_eq` on the given pointer type
+    Ptr { is_option: bool },
+    /// Exclude the field from comparison
+    Ignore,
+}
+
+pub(crate) fn impl_props_partial_eq(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        ..
+    } = parse_macro_input!(input);
+
+    let fields = match data {
+        Data::Struct(data) => data.fields,
+        Data::Enum(data) => {
+            emit_error!(
+                data.enum_token.span(),
+                "Cannot derive PropsPartialEq for enum"
+            );
+            return quote!().into();
+        }
+        Data::Union(data) => {
+            emit_error!(
+                data.union_token.span(),
+                "Cannot derive PropsPartialEq for union"
+            );
+            return quote!().into();
+        }
+    };
+
+    let comparisons = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .into_iter()
+            .filter_map(|field| {
+                let eq = process_field(&field);
+                let ident = field.ident.unwrap();
+                quote_comparison(quote!(#ident), &field.ty, eq)
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, field)| {
+                let eq = process_field(&field);
+                let index = syn::Index::from(index);
+                quote_comparison(quote!(#index), &field.ty, eq)
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let output = quote! {
+        impl #impl_generics ::core::cmp::PartialEq for #ident #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& #comparisons)*
+            }
+        }
+    };
+
+    output.into()
+}
+
+/// Generates the comparison for a single field
+fn quote_comparison(
+    member: proc_macro2::TokenStream,
+    ty: &Type,
+    eq: FieldEq,
+) -> Option<proc_macro2::TokenStream> {
+    match eq {
+        FieldEq::Value => Some(quote! {
+            self.#member == other.#member
+        }),
+        FieldEq::Ptr { is_option: false } => Some(quote! {
+            <#ty>::ptr_eq(&self.#member, &other.#member)
+        }),
+        FieldEq::Ptr { is_option: true } => {
+            let inner = option_inner_type(ty)?;
+            Some(quote! {
+                match (&self.#member, &other.#member) {
+                    (Some(a), Some(b)) => <#inner>::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            })
+        }
+        FieldEq::Ignore => None,
+    }
+}
+
+/// Process a field to determine how it should be compared
+fn process_field(field: &Field) -> FieldEq {
+    let mut eq = FieldEq::Value;
+
+    for arg in parse_prop_field_args(field) {
+        let value = match arg.value {
+            Some(value) if arg.ident == PROP_EQ => value,
+            // Other helpers are handled by their respective derives
+            _ => continue,
+        };
+
+        let lit = match value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => lit,
+            value => {
+                emit_error!(value.span(), "Expected a string literal");
+                continue;
+            }
+        };
+
+        eq = match lit.value().as_str() {
+            EQ_VALUE => FieldEq::Value,
+            EQ_IGNORE => FieldEq::Ignore,
+            EQ_PTR => {
+                if is_pointer_type(&field.ty) {
+                    FieldEq::Ptr { is_option: false }
+                } else if option_inner_type(&field.ty).map_or(false, is_pointer_type) {
+                    FieldEq::Ptr { is_option: true }
+                } else {
+                    emit_error!(
+                        field.ty.span(),
+                        "`eq = \"ptr\"` can only be used on `Arc` or `Rc` fields (or an `Option` of them)"
+                    );
+                    FieldEq::Value
+                }
+            }
+            err => {
+                emit_error!(
+                    lit.span(),
+                    "Invalid eq option `{}`, expected one of: `value`, `ptr`, `ignore`",
+                    err
+                );
+                FieldEq::Value
+            }
+        };
+    }
+
+    eq
+}
+
+/// Returns the last path segment of the given type, if it's a path type
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// Checks if the given type is one of the supported pointer types
+fn is_pointer_type(ty: &Type) -> bool {
+    last_segment(ty).map_or(false, |segment| {
+        POINTER_TYPES.contains(&segment.ident.to_string().as_str())
+    })
+}
+
+/// Returns the inner type of an `Option<T>`
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let segment = last_segment(ty)?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
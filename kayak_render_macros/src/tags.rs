@@ -7,11 +7,57 @@ use syn::spanned::Spanned;
 
 pub struct OpenTag {
     pub name: syn::Path,
+    /// Set if this is a namespaced tag (such as `<plugin:Foo>`) resolved at runtime
+    pub dynamic: Option<DynamicTag>,
     pub attributes: WidgetAttributes,
     pub self_closing: bool,
     pub is_custom_element: bool,
 }
 
+impl OpenTag {
+    /// The name of this tag as written (excluding the angle brackets)
+    fn tag_name(&self) -> String {
+        match &self.dynamic {
+            Some(dynamic) => dynamic.to_string(),
+            None => {
+                let path = &self.name;
+                quote!(#path).to_string()
+            }
+        }
+    }
+}
+
+/// A namespaced tag, such as `plugin:Foo`
+///
+/// Rather than naming a widget type, these refer to a widget registered with the context at
+/// runtime under the full tag (`"plugin:Foo"`).
+pub struct DynamicTag {
+    pub namespace: syn::Ident,
+    pub name: syn::Ident,
+}
+
+impl DynamicTag {
+    /// Returns true if the input starts with a namespaced tag name
+    fn peek(input: ParseStream) -> bool {
+        input.peek(syn::Ident) && input.peek2(syn::Token![:]) && !input.peek2(syn::Token![::])
+    }
+}
+
+impl std::fmt::Display for DynamicTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.name)
+    }
+}
+
+impl Parse for DynamicTag {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let namespace = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![:]>()?;
+        let name = input.parse::<syn::Ident>()?;
+        Ok(Self { namespace, name })
+    }
+}
+
 fn name_or_fragment(maybe_name: Result<syn::Path>) -> syn::Path {
     let kayak_core = get_core_crate();
 
@@ -34,8 +80,12 @@ fn is_custom_element_name(path: &syn::Path) -> bool {
 impl Parse for OpenTag {
     fn parse(input: ParseStream) -> Result<Self> {
         input.parse::<syn::Token![<]>()?;
-        let maybe_name = syn::Path::parse_mod_style(input);
-        let name = name_or_fragment(maybe_name);
+        let (name, dynamic) = if DynamicTag::peek(input) {
+            let dynamic = input.parse::<DynamicTag>()?;
+            (syn::Path::from(dynamic.name.clone()), Some(dynamic))
+        } else {
+            (name_or_fragment(syn::Path::parse_mod_style(input)), None)
+        };
         let is_custom_element = is_custom_element_name(&name);
         let attributes = WidgetAttributes::custom_parse(input)?;
         let self_closing = input.parse::<syn::Token![/]>().is_ok();
@@ -43,6 +93,7 @@ impl Parse for OpenTag {
 
         Ok(Self {
             name,
+            dynamic,
             attributes,
             self_closing,
             is_custom_element,
@@ -52,14 +103,19 @@ impl Parse for OpenTag {
 
 pub struct ClosingTag {
     name: syn::Path,
+    dynamic: Option<DynamicTag>,
 }
 
 impl ClosingTag {
     pub fn validate(&self, open_tag: &OpenTag) {
-        let open_tag_path = &open_tag.name;
-        let open_tag_path_str = quote!(#open_tag_path).to_string();
-        let self_path = &self.name;
-        let self_path_str = quote!(#self_path).to_string();
+        let open_tag_path_str = open_tag.tag_name();
+        let self_path_str = match &self.dynamic {
+            Some(dynamic) => dynamic.to_string(),
+            None => {
+                let self_path = &self.name;
+                quote!(#self_path).to_string()
+            }
+        };
         if self_path_str != open_tag_path_str {
             abort!(
                 self.name.span(),
@@ -74,10 +130,13 @@ impl Parse for ClosingTag {
     fn parse(input: ParseStream) -> Result<Self> {
         input.parse::<syn::Token![<]>()?;
         input.parse::<syn::Token![/]>()?;
-        let maybe_name = input.parse::<syn::Path>();
+        let (name, dynamic) = if DynamicTag::peek(input) {
+            let dynamic = input.parse::<DynamicTag>()?;
+            (syn::Path::from(dynamic.name.clone()), Some(dynamic))
+        } else {
+            (name_or_fragment(input.parse::<syn::Path>()), None)
+        };
         input.parse::<syn::Token![>]>()?;
-        Ok(Self {
-            name: name_or_fragment(maybe_name),
-        })
+        Ok(Self { name, dynamic })
     }
 }
@@ -0,0 +1,46 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+pub(crate) struct UseInterval {
+    pub interval: syn::Expr,
+    pub callback: syn::Expr,
+    pub paused: Option<syn::Expr>,
+}
+
+impl Parse for UseInterval {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let interval = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let callback = input.parse()?;
+        let mut paused = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            paused = Some(input.parse()?);
+            let _: Option<Token![,]> = input.parse()?;
+        }
+
+        Ok(Self {
+            interval,
+            callback,
+            paused,
+        })
+    }
+}
+
+impl UseInterval {
+    /// Build the output token stream, creating the actual use_interval code
+    pub fn build(self) -> TokenStream {
+        let interval = self.interval;
+        let callback = self.callback;
+        let paused = self
+            .paused
+            .map(|paused| quote! { #paused })
+            .unwrap_or_else(|| quote! { false });
+
+        let result = quote! {{
+            context.create_interval(#interval, #paused, #callback);
+        }};
+        TokenStream::from(result)
+    }
+}
@@ -0,0 +1,48 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+pub(crate) struct UseListNavigation {
+    pub items: syn::Expr,
+    pub on_activate: syn::Expr,
+    pub wrap: Option<syn::Expr>,
+}
+
+impl Parse for UseListNavigation {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let items = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let on_activate = input.parse()?;
+        let mut wrap = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            wrap = Some(input.parse()?);
+            let _: Option<Token![,]> = input.parse()?;
+        }
+
+        Ok(Self {
+            items,
+            on_activate,
+            wrap,
+        })
+    }
+}
+
+impl UseListNavigation {
+    /// Build the output token stream, creating the actual use_list_navigation code
+    pub fn build(self) -> TokenStream {
+        let items = self.items;
+        let on_activate = self.on_activate;
+        let wrap = self
+            .wrap
+            .map(|wrap| quote! { #wrap })
+            .unwrap_or_else(|| quote! { true });
+
+        let result = quote! {{
+            context
+                .use_list_navigation(#items, #wrap, #on_activate)
+                .unwrap()
+        }};
+        TokenStream::from(result)
+    }
+}
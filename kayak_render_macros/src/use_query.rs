@@ -0,0 +1,33 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+pub(crate) struct UseQuery {
+    pub param: syn::Type,
+    pub projection: syn::Expr,
+}
+
+impl Parse for UseQuery {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let param = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let projection = input.parse()?;
+        let _: Option<Token![,]> = input.parse()?;
+
+        Ok(Self { param, projection })
+    }
+}
+
+impl UseQuery {
+    /// Build the output token stream, creating the actual use_query code
+    pub fn build(self) -> TokenStream {
+        let param = self.param;
+        let projection = self.projection;
+
+        let result = quote! {{
+            context.use_query::<#param, _, _>(#projection)
+        }};
+        TokenStream::from(result)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::get_core_crate;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+pub(crate) struct UseThrottledState {
+    pub initial_state: syn::Expr,
+    pub interval: syn::Expr,
+}
+
+impl Parse for UseThrottledState {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let initial_state = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let interval = input.parse()?;
+        let _: Option<Token![,]> = input.parse()?;
+
+        Ok(Self {
+            initial_state,
+            interval,
+        })
+    }
+}
+
+impl UseThrottledState {
+    /// Build the output token stream, creating the actual use_throttled_state code
+    pub fn build(self) -> TokenStream {
+        let kayak_core = get_core_crate();
+        let initial_state = self.initial_state;
+        let interval = self.interval;
+
+        let result = quote! {{
+            use #kayak_core::{Bound, MutableBound};
+            let state = context.create_state(#initial_state).unwrap();
+            let set_state = context.create_throttled_setter(&state, #interval);
+
+            (state.get(), set_state, state)
+        }};
+        TokenStream::from(result)
+    }
+}
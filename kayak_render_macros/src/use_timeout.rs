@@ -0,0 +1,52 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Token};
+
+pub(crate) struct UseTimeout {
+    pub delay: syn::Expr,
+    pub callback: syn::Expr,
+    pub dependencies: Punctuated<syn::Expr, Token![,]>,
+}
+
+impl Parse for UseTimeout {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let delay = input.parse()?;
+        let _: Token![,] = input.parse()?;
+        let callback = input.parse()?;
+        let mut dependencies = Punctuated::new();
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let raw_deps;
+            let _ = bracketed!(raw_deps in input);
+            dependencies = raw_deps.parse_terminated(syn::Expr::parse)?;
+            let _: Option<Token![,]> = input.parse()?;
+        }
+
+        Ok(Self {
+            delay,
+            callback,
+            dependencies,
+        })
+    }
+}
+
+impl UseTimeout {
+    /// Build the output token stream, creating the actual use_timeout code
+    pub fn build(self) -> TokenStream {
+        let delay = self.delay;
+        let callback = self.callback;
+        let dependencies = self.dependencies.iter();
+
+        let result = quote! {{
+            let dependency_key = {
+                use std::hash::{Hash, Hasher};
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                #(#dependencies.hash(&mut hasher);)*
+                hasher.finish()
+            };
+            context.create_timeout(#delay, dependency_key, #callback).unwrap()
+        }};
+        TokenStream::from(result)
+    }
+}
@@ -5,7 +5,7 @@ use syn::parse::{Parse, ParseStream, Result};
 use syn::Path;
 
 use crate::children::Children;
-use crate::tags::ClosingTag;
+use crate::tags::{ClosingTag, DynamicTag};
 use crate::widget_attributes::CustomWidgetAttributes;
 use crate::widget_builder::build_widget_stream;
 use crate::{get_core_crate, tags::OpenTag, widget_attributes::WidgetAttributes};
@@ -61,9 +61,12 @@ impl Widget {
         };
 
         let name = open_tag.name;
-        let declaration = if Self::is_custom_element(&name) {
+        let declaration = if open_tag.dynamic.is_some() || Self::is_custom_element(&name) {
             let attrs = &open_tag.attributes.for_custom_element(&children);
-            let (props, constructor) = Self::construct(&name, attrs);
+            let (props, constructor) = match &open_tag.dynamic {
+                Some(dynamic) => Self::construct_dynamic(dynamic, attrs),
+                None => Self::construct(&name, attrs),
+            };
             if !as_prop {
                 let widget_block = build_widget_stream(quote! { built_widget }, constructor, 0);
                 quote! {{
@@ -104,9 +107,11 @@ impl Widget {
         let kayak_core = get_core_crate();
 
         let prop_ident = format_ident!("internal_rsx_props");
+        let checks = attrs.check_attributes(name);
         let attrs = attrs.assign_attributes(&prop_ident);
 
         let props = quote! {
+            #checks
             let mut #prop_ident = <#name as #kayak_core::Widget>::Props::default();
             #attrs
         };
@@ -117,6 +122,39 @@ impl Widget {
 
         (props, constructor)
     }
+
+    /// Constructs a widget and its props for a namespaced tag (such as `<plugin:Foo>`)
+    ///
+    /// The widget itself is resolved at runtime, so this creates a `DynamicWidget` whose props
+    /// contain the common props as fields and all other attributes by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The namespaced tag
+    /// * `attrs`: The attributes (props) to apply to this widget
+    ///
+    /// returns: (TokenStream, TokenStream)
+    fn construct_dynamic(
+        tag: &DynamicTag,
+        attrs: &CustomWidgetAttributes,
+    ) -> (TokenStream, TokenStream) {
+        let kayak_core = get_core_crate();
+
+        let prop_ident = format_ident!("internal_rsx_props");
+        let attrs = attrs.assign_dynamic_attributes(&prop_ident);
+        let tag = tag.to_string();
+
+        let props = quote! {
+            let mut #prop_ident = #kayak_core::DynamicProps::new(#tag);
+            #attrs
+        };
+
+        let constructor = quote! {
+            #kayak_core::DynamicWidget::new(#prop_ident)
+        };
+
+        (props, constructor)
+    }
 }
 
 impl ToTokens for Widget {
@@ -1,16 +1,31 @@
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::emit_error;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use std::collections::HashSet;
 use syn::{
     ext::IdentExt,
     parse::{Parse, ParseStream, Result},
     spanned::Spanned,
+    Path,
 };
 
 use crate::child::Child;
 use crate::{attribute::Attribute, children::Children, get_core_crate};
 
+/// Props that are assigned as fields on the props of a namespaced tag (such as `<plugin:Foo>`)
+///
+/// All other attributes are stored by name.
+const DYNAMIC_PROP_FIELDS: &[&str] = &[
+    "styles",
+    "on_event",
+    "on_event_capture",
+    "on_layout",
+    "focusable",
+];
+
+/// Attributes that are handled specially and should never be validated
+const RESERVED_ATTRIBUTES: &[&str] = &["key", "on_event"];
+
 #[derive(Clone)]
 pub struct WidgetAttributes {
     pub attributes: HashSet<Attribute>,
@@ -46,6 +61,22 @@ impl WidgetAttributes {
     }
 }
 
+/// Gets the full name of the given attribute
+fn attribute_name(attribute: &Attribute) -> String {
+    attribute
+        .idents()
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Checks if the given attribute is the `on_event` prop
+fn is_on_event(attribute: &Attribute) -> bool {
+    let idents = attribute.idents();
+    idents.len() == 1 && *idents[0] == "on_event"
+}
+
 impl Parse for WidgetAttributes {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut attributes: HashSet<Attribute> = HashSet::new();
@@ -75,20 +106,120 @@ impl<'a, 'c> CustomWidgetAttributes<'a, 'c> {
     ///
     /// This takes the form: `IDENT.ATTR_NAME = ATTR_VALUE;`
     ///
+    /// An `on_event` attribute given as an inline closure is automatically wrapped, resulting in
+    /// `IDENT.on_event = Some(OnEvent::new(CLOSURE));`
+    ///
     /// # Arguments
     ///
     /// * `ident`: The ident to assign to (i.e. "props")
     ///
     /// returns: TokenStream
     pub fn assign_attributes(&self, ident: &Ident) -> TokenStream {
+        self.assign(ident, false)
+    }
+
+    /// Assign this widget's attributes to the given ident of type `DynamicProps`
+    ///
+    /// This works like [`assign_attributes`](Self::assign_attributes), except that only the
+    /// common props are assigned as fields. All other attributes are stored by name, taking the
+    /// form: `IDENT.set("ATTR_NAME", ATTR_VALUE);`
+    ///
+    /// # Arguments
+    ///
+    /// * `ident`: The ident to assign to (i.e. "props")
+    ///
+    /// returns: TokenStream
+    pub fn assign_dynamic_attributes(&self, ident: &Ident) -> TokenStream {
+        self.assign(ident, true)
+    }
+
+    /// Checks that each of this widget's attributes is one of the fields of its props
+    ///
+    /// The macro has no knowledge of the widget's props, so this generates a constant per
+    /// attribute that fails to evaluate (with a message spanned to the attribute and a suggestion
+    /// of the closest field) if it isn't one of them. See `kayak_core::prop_fields` for details.
+    ///
+    /// Constants can't refer to generic parameters, so widgets with generic arguments (such as
+    /// `<List<T>>`) aren't checked, leaving unknown attributes to the compiler's own error.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The full-path name of the widget
+    ///
+    /// returns: TokenStream
+    pub fn check_attributes(&self, name: &Path) -> TokenStream {
+        if name
+            .segments
+            .iter()
+            .any(|segment| !segment.arguments.is_empty())
+        {
+            return quote! {};
+        }
+
+        let kayak_core = get_core_crate();
+        let checks = self
+            .attributes
+            .iter()
+            .filter_map(|attribute| {
+                let attribute_name = attribute_name(attribute);
+                if RESERVED_ATTRIBUTES.contains(&attribute_name.as_str()) {
+                    return None;
+                }
+
+                let key = attribute.ident();
+                // The check is the length of an array so that it's evaluated while the function is
+                // type-checked, and reported along with (and before) the "no field" error
+                Some(quote_spanned! {key.span()=>
+                    let _: [(); 0] = [(); {
+                        use #kayak_core::prop_fields::NoPropFields as _;
+                        let fields = #kayak_core::prop_fields::PropFieldsProbe::<
+                            <#name as #kayak_core::Widget>::Props,
+                        >::FIELDS;
+                        if let ::core::option::Option::Some(error) =
+                            #kayak_core::prop_fields::check_attribute(fields, #attribute_name)
+                        {
+                            ::core::panic!("{}", error.as_str());
+                        }
+                        0
+                    }];
+                })
+            })
+            .collect::<Vec<_>>();
+
+        quote! {
+            #( #checks )*
+        }
+    }
+
+    fn assign(&self, ident: &Ident, dynamic: bool) -> TokenStream {
+        let kayak_core = get_core_crate();
         let mut attrs = self
             .attributes
             .iter()
             .map(|attribute| {
                 let key = attribute.ident();
+
+                if dynamic {
+                    let name = attribute_name(attribute);
+                    if !DYNAMIC_PROP_FIELDS.contains(&name.as_str()) {
+                        let value = attribute.value_tokens();
+                        return quote_spanned! {key.span()=>
+                            #ident.set(#name, #value);
+                        };
+                    }
+                }
+
+                if let Some(closure) = attribute.closure().filter(|_| is_on_event(attribute)) {
+                    // Span to the closure so that signature mismatches are reported on it
+                    return quote_spanned! {closure.span()=>
+                        #ident.#key = Some(#kayak_core::OnEvent::new(#closure));
+                    };
+                }
+
                 let value = attribute.value_tokens();
 
-                quote! {
+                // Span the assignment to the attribute so that unknown fields are reported there
+                quote_spanned! {key.span()=>
                     #ident.#key = #value;
                 }
             })
@@ -96,7 +227,6 @@ impl<'a, 'c> CustomWidgetAttributes<'a, 'c> {
 
         // If this widget contains children, add it (should result in error if widget does not accept children)
         if self.should_add_children() {
-            let kayak_core = get_core_crate();
             let children_tuple = self.children.as_option_of_tuples_tokens();
             attrs.push(quote! {
                 let children = children.clone();
@@ -3,30 +3,99 @@ use proc_macro::TokenStream;
 use proc_macro2::Ident;
 use proc_macro_error::emit_error;
 use quote::quote;
-use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Field, Meta, NestedMeta};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Expr, Field, Token, Type,
+};
 
 use crate::get_core_crate;
+use crate::props_debug::PROP_SKIP_DEBUG;
+use crate::props_partial_eq::PROP_EQ;
 
 /// The ident for the props helper attribute (`#[prop_field(Children)]`)
-const PROPS_HELPER_IDENT: &str = "prop_field";
+pub(crate) const PROPS_HELPER_IDENT: &str = "prop_field";
+/// The ident for the struct-level helper attribute (`#[widget_props(validate = Self::check)]`)
+const STRUCT_HELPER_IDENT: &str = "widget_props";
+/// The ident for the validation option (`#[widget_props(validate = Self::check)]`)
+const PROP_VALIDATE: &str = "validate";
 
 const PROP_CHILDREN: &str = "Children";
 const PROP_STYLE: &str = "Styles";
 const PROP_ON_EVENT: &str = "OnEvent";
+const PROP_ON_EVENT_CAPTURE: &str = "OnEventCapture";
 const PROP_ON_LAYOUT: &str = "OnLayout";
+const PROP_ON_MOUNT: &str = "OnMount";
 const PROP_FOCUSABLE: &str = "Focusable";
+const PROP_AUTOFOCUS: &str = "Autofocus";
+const PROP_DISABLED: &str = "Disabled";
+/// The ident for the default value option (`#[prop_field(default = 14.0)]`)
+const PROP_DEFAULT: &str = "default";
 
 #[derive(Default)]
 struct PropsHelpers {
     children_ident: Option<Ident>,
     styles_ident: Option<Ident>,
     on_event_ident: Option<Ident>,
+    on_event_capture_ident: Option<Ident>,
     on_layout_ident: Option<Ident>,
+    on_mount_ident: Option<Ident>,
     focusable_ident: Option<Ident>,
+    autofocus_ident: Option<Ident>,
+    /// The disabled field, along with whether it's a `bool` (rather than an `Option<bool>`)
+    disabled_ident: Option<(Ident, bool)>,
+    /// The member and default value (if any) of each field, in order
+    defaults: Vec<(proc_macro2::TokenStream, Option<Expr>)>,
+    /// The names of the fields that can be given as `rsx!` attributes (only known for structs)
+    field_names: Option<Vec<String>>,
+}
+
+/// A single argument of the props helper attribute, such as `Children` or `eq = "ptr"`
+pub(crate) struct PropFieldArg {
+    pub ident: Ident,
+    pub value: Option<Expr>,
+}
+
+impl Parse for PropFieldArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { ident, value })
+    }
+}
+
+/// Parses the arguments of all props helper attributes on the given field
+///
+/// Unlike `Attribute::parse_meta`, this allows values to be arbitrary expressions. Attributes
+/// that fail to parse are reported and skipped.
+pub(crate) fn parse_prop_field_args(field: &Field) -> Vec<PropFieldArg> {
+    parse_helper_args(&field.attrs, PROPS_HELPER_IDENT)
+}
+
+/// Parses the arguments of all helper attributes with the given ident
+fn parse_helper_args(attrs: &[Attribute], helper_ident: &str) -> Vec<PropFieldArg> {
+    let mut args = Vec::new();
+    for attr in attrs {
+        if !attr.path.is_ident(helper_ident) {
+            continue;
+        }
+
+        match attr.parse_args_with(Punctuated::<PropFieldArg, Token![,]>::parse_terminated) {
+            Ok(parsed) => args.extend(parsed),
+            Err(err) => emit_error!(err.span(), "Invalid attribute: {}", err),
+        }
+    }
+    args
 }
 
 pub(crate) fn impl_widget_props(input: TokenStream) -> TokenStream {
     let DeriveInput {
+        attrs,
         ident,
         data,
         generics,
@@ -34,6 +103,7 @@ pub(crate) fn impl_widget_props(input: TokenStream) -> TokenStream {
     } = parse_macro_input!(input);
 
     let helpers = process_data(data);
+    let validate = process_struct_attrs(&attrs);
 
     let set_children = if let Some(ident) = helpers.children_ident.clone() {
         quote! {
@@ -45,11 +115,56 @@ pub(crate) fn impl_widget_props(input: TokenStream) -> TokenStream {
     let children_return = quote_clone_field(helpers.children_ident);
     let styles_return = quote_clone_field(helpers.styles_ident);
     let on_event_return = quote_clone_field(helpers.on_event_ident);
+    let on_event_capture_return = quote_clone_field(helpers.on_event_capture_ident);
     let on_layout_return = quote_clone_field(helpers.on_layout_ident);
+    let on_mount_return = quote_clone_field(helpers.on_mount_ident);
     let focusable_return = quote_clone_field(helpers.focusable_ident);
+    let autofocus_return = match helpers.autofocus_ident {
+        Some(ident) => quote! { self.#ident },
+        None => quote! { false },
+    };
+    let disabled_return = match helpers.disabled_ident {
+        // A `bool` can only disable the widget, otherwise it inherits its parent's state
+        Some((ident, true)) => quote! { self.#ident.then_some(true) },
+        Some((ident, false)) => quote! { self.#ident.clone() },
+        None => quote! { None },
+    };
 
     let kayak_core = get_core_crate();
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Only implement `Default` if it's been customized, so that it can still be derived otherwise
+    let default_impl = if helpers.defaults.iter().any(|(_, value)| value.is_some()) {
+        let values = helpers.defaults.iter().map(|(member, value)| {
+            let value = match value {
+                Some(value) => quote! { #value },
+                None => quote! { ::core::default::Default::default() },
+            };
+            quote! { #member: #value }
+        });
+
+        // Tuple structs can also be constructed this way, using their indices as field names
+        quote! {
+            impl #impl_generics ::core::default::Default for #ident #ty_generics #where_clause {
+                fn default() -> Self {
+                    Self { #(#values),* }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Unions have no "fields" to check attributes against
+    let prop_fields_impl = match &helpers.field_names {
+        Some(field_names) => quote! {
+            impl #impl_generics #kayak_core::PropFields for #ident #ty_generics #where_clause {
+                const FIELDS: &'static [&'static str] = &[#(#field_names),*];
+            }
+        },
+        None => quote! {},
+    };
+
     let output = quote! {
         impl #impl_generics #kayak_core::WidgetProps for #ident #ty_generics #where_clause {
             fn get_children(&self) -> Option<#kayak_core::Children> {
@@ -68,20 +183,66 @@ pub(crate) fn impl_widget_props(input: TokenStream) -> TokenStream {
                 #on_event_return
             }
 
+            fn get_on_event_capture(&self) -> Option<#kayak_core::OnEvent> {
+                #on_event_capture_return
+            }
+
             fn get_on_layout(&self) -> Option<#kayak_core::OnLayout> {
                 #on_layout_return
             }
 
+            fn get_on_mount(&self) -> Option<#kayak_core::OnMount> {
+                #on_mount_return
+            }
+
             fn get_focusable(&self) -> Option<bool> {
                 #focusable_return
             }
 
+            fn get_autofocus(&self) -> bool {
+                #autofocus_return
+            }
+
+            fn get_disabled(&self) -> Option<bool> {
+                #disabled_return
+            }
+
+            #validate
         }
+
+        #default_impl
+
+        #prop_fields_impl
     };
 
     output.into()
 }
 
+/// Processes the struct-level helper attributes, returning the `validate` method (if any)
+fn process_struct_attrs(attrs: &[Attribute]) -> proc_macro2::TokenStream {
+    let mut validate = quote! {};
+
+    for arg in parse_helper_args(attrs, STRUCT_HELPER_IDENT) {
+        let ident_str = arg.ident.to_string();
+        match (ident_str.as_str(), arg.value) {
+            (PROP_VALIDATE, Some(value)) => {
+                validate = quote! {
+                    fn validate(&self) -> Result<(), String> {
+                        (#value)(self)
+                    }
+                };
+            }
+            (PROP_VALIDATE, None) => emit_error!(
+                arg.ident.span(),
+                "Expected a validation function, such as `validate = Self::check`"
+            ),
+            (err, _) => emit_error!(arg.ident.span(), "Invalid attribute: {}", err),
+        }
+    }
+
+    validate
+}
+
 /// Processes all fields of the given struct to collect the helper attribute data
 ///
 /// Attributes are processed in order and may overwrite previous attributes of the same type. This
@@ -91,13 +252,26 @@ fn process_data(data: Data) -> PropsHelpers {
 
     match data {
         Data::Struct(data) => {
-            for field in data.fields {
-                process_field(field, &mut helpers);
+            let mut field_names = Vec::new();
+            for (index, field) in data.fields.into_iter().enumerate() {
+                let member = match &field.ident {
+                    Some(ident) => quote! { #ident },
+                    None => {
+                        let index = syn::Index::from(index);
+                        quote! { #index }
+                    }
+                };
+                field_names.push(member.to_string());
+                let default = process_field(field, &mut helpers);
+                helpers.defaults.push((member, default));
             }
+            helpers.field_names = Some(field_names);
         }
         Data::Union(data) => {
             for field in data.fields.named {
-                process_field(field, &mut helpers);
+                if let Some(default) = process_field(field, &mut helpers) {
+                    emit_error!(default.span(), "Cannot use a default value in a union");
+                }
             }
         }
         Data::Enum(data) => {
@@ -108,39 +282,45 @@ fn process_data(data: Data) -> PropsHelpers {
     helpers
 }
 
-/// Process a field to collect the helper attribute
-fn process_field(field: Field, props: &mut PropsHelpers) {
-    for attr in field.attrs {
-        if let Ok(meta) = attr.parse_meta() {
-            if let Meta::List(meta) = meta {
-                if !meta.path.is_ident(PROPS_HELPER_IDENT) {
-                    continue;
-                }
+/// Process a field to collect the helper attribute, returning its default value (if any)
+fn process_field(field: Field, props: &mut PropsHelpers) -> Option<Expr> {
+    let mut default = None;
 
-                for nested in meta.nested {
-                    let ident = match nested {
-                        NestedMeta::Meta(meta) => meta.path().get_ident().cloned(),
-                        err => {
-                            emit_error!(err.span(), "Invalid attribute: {:?}", err);
-                            None
-                        }
-                    };
-
-                    if let Some(ident) = ident {
-                        let ident_str = ident.to_string();
-                        match ident_str.as_str() {
-                            PROP_CHILDREN => props.children_ident = field.ident.clone(),
-                            PROP_STYLE => props.styles_ident = field.ident.clone(),
-                            PROP_ON_EVENT => props.on_event_ident = field.ident.clone(),
-                            PROP_ON_LAYOUT => props.on_layout_ident = field.ident.clone(),
-                            PROP_FOCUSABLE => props.focusable_ident = field.ident.clone(),
-                            err => emit_error!(err.span(), "Invalid attribute: {}", err),
-                        }
-                    }
-                }
+    for arg in parse_prop_field_args(&field) {
+        let ident_str = arg.ident.to_string();
+        match (ident_str.as_str(), arg.value) {
+            (PROP_DEFAULT, Some(value)) => default = Some(value),
+            (PROP_DEFAULT, None) => emit_error!(
+                arg.ident.span(),
+                "Expected a default value, such as `default = 14.0`"
+            ),
+            // Handled by `PropsPartialEq` and `PropsDebug`, respectively
+            (PROP_EQ | PROP_SKIP_DEBUG, _) => {}
+            (_, Some(value)) => emit_error!(value.span(), "Unexpected value for `{}`", ident_str),
+            (PROP_CHILDREN, None) => props.children_ident = field.ident.clone(),
+            (PROP_STYLE, None) => props.styles_ident = field.ident.clone(),
+            (PROP_ON_EVENT, None) => props.on_event_ident = field.ident.clone(),
+            (PROP_ON_EVENT_CAPTURE, None) => props.on_event_capture_ident = field.ident.clone(),
+            (PROP_ON_LAYOUT, None) => props.on_layout_ident = field.ident.clone(),
+            (PROP_ON_MOUNT, None) => props.on_mount_ident = field.ident.clone(),
+            (PROP_FOCUSABLE, None) => props.focusable_ident = field.ident.clone(),
+            (PROP_AUTOFOCUS, None) => props.autofocus_ident = field.ident.clone(),
+            (PROP_DISABLED, None) => {
+                props.disabled_ident = field.ident.clone().map(|ident| (ident, is_bool(&field.ty)))
             }
+            (err, None) => emit_error!(arg.ident.span(), "Invalid attribute: {}", err),
         }
     }
+
+    default
+}
+
+/// Checks if the given type is `bool`
+fn is_bool(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("bool"),
+        _ => false,
+    }
 }
 
 fn quote_clone_field(field_ident: Option<Ident>) -> proc_macro2::TokenStream {
@@ -2,7 +2,9 @@
 pub mod core {
     pub use kayak_core::*;
     pub use kayak_render_macros::{
-        constructor, render, rsx, use_effect, use_state, widget, WidgetProps,
+        constructor, render, render_subtree, rsx, use_animation, use_effect, use_id, use_interval,
+        use_layout, use_list_navigation, use_previous, use_query, use_state, use_theme,
+        use_throttled_state, use_timeout, widget, PropsDebug, PropsPartialEq, WidgetProps,
     };
 }
 
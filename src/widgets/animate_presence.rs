@@ -0,0 +1,584 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::{
+    constructor, rsx,
+    styles::{Edge, Style, StyleProp, Transform, Units},
+    widget, Bound, Children, Easing, Spring, Transition, WidgetProps,
+};
+
+use crate::widgets::{Element, ForEachItems, ItemBuilder};
+
+/// How a widget looks before it enters (or after it exits) an [`AnimatePresence`]
+///
+/// The widget is animated between this and its regular look, where it's fully opaque, in place
+/// and at its laid out size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresenceAnimation {
+    /// The opacity of the widget, from `0.0` (invisible) to `1.0`
+    pub opacity: f32,
+    /// How far the widget is moved from its place, in pixels
+    ///
+    /// The widget is moved with opposite margins on either side, so the widgets around it stay
+    /// where they are.
+    pub offset: (f32, f32),
+    /// How much the widget is scaled, where `1.0` is its laid out size
+    ///
+    /// The widget is scaled around its top-left corner (see [`Transform`]), without affecting
+    /// the layout.
+    pub scale: f32,
+    /// How long the animation takes
+    pub duration: Duration,
+    /// The easing curve of the animation
+    pub easing: Easing,
+    /// If set, the widget moves with this spring instead of over the duration and easing
+    pub spring: Option<Spring>,
+}
+
+impl PresenceAnimation {
+    /// An animation that fades the widget in or out
+    pub fn fade(duration: Duration) -> Self {
+        Self {
+            opacity: 0.0,
+            offset: (0.0, 0.0),
+            scale: 1.0,
+            duration,
+            easing: Easing::default(),
+            spring: None,
+        }
+    }
+
+    /// Also slide the widget in from (or out to) the given offset
+    pub fn with_offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// Also scale the widget up from (or down to) the given scale
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Use the given easing curve for the animation
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Move the widget with the given spring, rather than over the animation's duration
+    pub fn with_spring(mut self, spring: Spring) -> Self {
+        self.spring = Some(spring);
+        self
+    }
+
+    /// The styles of a widget that's the given fraction of the way from this look to its regular one
+    fn styles(&self, progress: f32) -> Style {
+        let lerp = |from: f32, to: f32| from + (to - from) * progress;
+        let (x, y) = (lerp(self.offset.0, 0.0), lerp(self.offset.1, 0.0));
+        Style {
+            // Springs can overshoot, but opacity can't
+            opacity: StyleProp::Value(lerp(self.opacity, 1.0).clamp(0.0, 1.0)),
+            offset: StyleProp::Value(Edge::new(
+                Units::Pixels(y),
+                Units::Pixels(-x),
+                Units::Pixels(-y),
+                Units::Pixels(x),
+            )),
+            // Neither can the scale go below zero
+            transform: StyleProp::Value(Transform::new((0.0, 0.0), lerp(self.scale, 1.0).max(0.0))),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for PresenceAnimation {
+    fn default() -> Self {
+        Self::fade(Duration::from_millis(200))
+    }
+}
+
+/// Delays the animations of items that enter (or exit) an [`AnimatePresence`] together
+///
+/// Items entering together are staggered in list order. Items exiting together are staggered in
+/// reverse, so the last item to have entered is the first to leave.
+#[derive(Clone)]
+pub struct Stagger(Arc<dyn Fn(usize, usize) -> Duration + Send + Sync>);
+
+impl Stagger {
+    /// Staggers items with a custom function
+    ///
+    /// The function is given the index of an item among those animating together, and how many
+    /// of them there are, and returns how long that item waits before it starts animating.
+    pub fn new<F: Fn(usize, usize) -> Duration + Send + Sync + 'static>(delay: F) -> Self {
+        Self(Arc::new(delay))
+    }
+
+    /// Delays each item by a fixed amount more than the item before it
+    pub fn each(delay: Duration) -> Self {
+        Self::new(move |index, _| delay * index as u32)
+    }
+
+    fn delay(&self, index: usize, count: usize) -> Duration {
+        (self.0)(index, count)
+    }
+}
+
+impl PartialEq for Stagger {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            Arc::as_ptr(&self.0) as *const (),
+            Arc::as_ptr(&other.0) as *const (),
+        )
+    }
+}
+
+impl Debug for Stagger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stagger").finish()
+    }
+}
+
+/// Props used by the [`AnimatePresence`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct AnimatePresenceProps {
+    /// The items to render, created with [`ForEachItems::new`]
+    pub items: ForEachItems,
+    /// How items look before they enter
+    ///
+    /// If `None`, items fade in over 200 milliseconds.
+    pub enter: Option<PresenceAnimation>,
+    /// How items look once they've exited
+    ///
+    /// If `None`, items fade out over 200 milliseconds.
+    pub exit: Option<PresenceAnimation>,
+    /// How to delay items that enter or exit at the same time
+    ///
+    /// If `None`, they all animate at once.
+    pub stagger: Option<Stagger>,
+}
+
+/// An item rendered by an [`AnimatePresence`], which may be in the middle of entering or exiting
+#[derive(Clone)]
+struct PresenceEntry {
+    key: String,
+    builder: ItemBuilder,
+    /// How far the item is from hidden (`0.0`) to fully shown (`1.0`)
+    transition: Transition,
+    /// Whether the item was removed from the list and is animating out
+    exiting: bool,
+}
+
+impl PresenceEntry {
+    /// The value the item's transition should be moving towards
+    fn target(&self) -> f32 {
+        if self.exiting {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// The enter and exit animations of an [`AnimatePresence`], and how they're staggered
+struct PresenceTiming {
+    enter: PresenceAnimation,
+    exit: PresenceAnimation,
+    stagger: Option<Stagger>,
+}
+
+/// The items of an [`AnimatePresence`], stored as widget state and shared with each item
+///
+/// Updating the items doesn't re-render the widget, since they're updated while rendering.
+#[derive(Default, Clone)]
+struct PresenceEntries(Arc<Mutex<Vec<PresenceEntry>>>);
+
+impl PresenceEntries {
+    /// Merges the current items into the entries (see [`update_entries`])
+    ///
+    /// Returns the keys of the items in the list, leaving out those that are exiting.
+    fn update(
+        &self,
+        items: Vec<(String, ItemBuilder)>,
+        now: Instant,
+        timing: &PresenceTiming,
+    ) -> Vec<String> {
+        match self.0.lock() {
+            Ok(mut entries) => {
+                let previous = std::mem::take(&mut *entries);
+                *entries = update_entries(previous, items, now, timing);
+                entries
+                    .iter()
+                    .filter(|entry| !entry.exiting)
+                    .map(|entry| entry.key.clone())
+                    .collect()
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the entry of the item with the given key
+    fn get(&self, key: &str) -> Option<PresenceEntry> {
+        self.0
+            .lock()
+            .ok()?
+            .iter()
+            .find(|entry| entry.key == key)
+            .cloned()
+    }
+}
+
+impl PartialEq for PresenceEntries {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for PresenceEntries {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PresenceEntries").finish()
+    }
+}
+
+/// Merges the current items into the previous ones, keeping removed items around as exiting
+///
+/// The current items are kept in order, and each exiting item stays right after the item it
+/// followed before it was removed. Items that finished exiting are dropped. Items that start
+/// entering or exiting are staggered as a group.
+fn update_entries(
+    previous: Vec<PresenceEntry>,
+    items: Vec<(String, ItemBuilder)>,
+    now: Instant,
+    timing: &PresenceTiming,
+) -> Vec<PresenceEntry> {
+    let order = previous
+        .iter()
+        .map(|entry| entry.key.clone())
+        .collect::<Vec<_>>();
+    let mut previous = previous
+        .into_iter()
+        .map(|entry| (entry.key.clone(), entry))
+        .collect::<HashMap<_, _>>();
+
+    let mut entries = items
+        .into_iter()
+        .map(|(key, builder)| match previous.remove(&key) {
+            Some(mut entry) => {
+                // Added back while exiting, so it turns around from wherever it is
+                entry.exiting = false;
+                entry.builder = builder;
+                entry
+            }
+            None => PresenceEntry {
+                key,
+                builder,
+                transition: Transition::new(0.0, now),
+                exiting: false,
+            },
+        })
+        .collect::<Vec<_>>();
+
+    // Place each removed item after the closest item before it that's still in the list
+    let mut position = 0;
+    for key in order {
+        let mut entry = match previous.remove(&key) {
+            Some(entry) => entry,
+            None => {
+                if let Some(index) = entries.iter().position(|entry| entry.key == key) {
+                    position = index + 1;
+                }
+                continue;
+            }
+        };
+
+        entry.exiting = true;
+        entries.insert(position, entry);
+        position += 1;
+    }
+
+    for exiting in [false, true] {
+        let starting = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.exiting == exiting && entry.transition.target() != entry.target()
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let count = starting.len();
+        let animation = if exiting { &timing.exit } else { &timing.enter };
+
+        for (order, index) in starting.into_iter().enumerate() {
+            let order = if exiting { count - 1 - order } else { order };
+            let delay = timing
+                .stagger
+                .as_ref()
+                .map_or(Duration::ZERO, |stagger| stagger.delay(order, count));
+            let entry = &mut entries[index];
+            let target = entry.target();
+            entry
+                .transition
+                .retarget_after(target, now, delay, animation.duration);
+            entry.transition.set_easing(animation.easing.clone());
+            entry.transition.set_spring(animation.spring);
+        }
+    }
+
+    entries.retain(|entry| !entry.exiting || !entry.transition.is_done(now));
+    entries
+}
+
+#[widget]
+/// A widget that animates items in when they're added to a list and out before they're removed
+///
+/// Like a [`ForEach`](crate::widgets::ForEach), each item is identified by its key. An item that's
+/// no longer in the list isn't unmounted right away: it stays in place, playing its
+/// [`exit`](AnimatePresenceProps::exit) animation, and the context only unmounts it once the
+/// animation completes (see [`defer_unmount`](crate::core::KayakContextRef::defer_unmount)). New items (including those present when this widget is first rendered) play their
+/// [`enter`](AnimatePresenceProps::enter) animation as they mount. An item added back while it's
+/// still exiting turns around and animates back in.
+///
+/// When several items enter or exit at once, a [`stagger`](AnimatePresenceProps::stagger) can
+/// delay each of them based on its index among them.
+///
+/// Each item is wrapped in an element that carries its animated styles.
+///
+/// # Props
+///
+/// __Type:__ [`AnimatePresenceProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ❌        |
+/// | `on_event`  | ❌        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use kayak_ui::core::{constructor, rsx, widget};
+/// # use kayak_ui::widgets::{AnimatePresence, ForEachItems, PresenceAnimation, Stagger, Text};
+///
+/// #[widget]
+/// fn Toasts() {
+///     let toasts = vec![(1, "Saved"), (2, "Uploaded")];
+///
+///     let items = ForEachItems::new(
+///         toasts,
+///         |(id, _)| id.to_string(),
+///         |(_, message)| constructor! {
+///             <Text content={message.to_string()} size={16.0} />
+///         },
+///     );
+///     let enter = PresenceAnimation::fade(Duration::from_millis(300))
+///         .with_offset(0.0, -12.0)
+///         .with_scale(0.9);
+///     let stagger = Stagger::each(Duration::from_millis(50));
+///
+///     rsx! {
+///         <AnimatePresence items={items} enter={Some(enter)} stagger={Some(stagger)} />
+///     }
+/// }
+/// ```
+pub fn AnimatePresence(props: AnimatePresenceProps) {
+    let now = context.frame_time();
+    let timing = PresenceTiming {
+        enter: props.enter.clone().unwrap_or_default(),
+        exit: props.exit.clone().unwrap_or_default(),
+        stagger: props.stagger.clone(),
+    };
+
+    let entries = context
+        .create_state(PresenceEntries::default())
+        .unwrap()
+        .get();
+    // Exiting items aren't added, since the context keeps them mounted until they're done
+    for item_key in entries.update(props.items.unique_keys(), now, &timing) {
+        let entries = entries.clone();
+        let enter = timing.enter.clone();
+        let exit = timing.exit.clone();
+        context.add_keyed_widget(
+            &item_key.clone(),
+            constructor! {
+                <PresenceItem item_key={item_key} entries={entries} enter={enter} exit={exit} />
+            },
+        );
+    }
+}
+
+/// Props used by the [`PresenceItem`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+struct PresenceItemProps {
+    item_key: String,
+    entries: PresenceEntries,
+    enter: PresenceAnimation,
+    exit: PresenceAnimation,
+}
+
+#[widget]
+/// An item of an [`AnimatePresence`], wrapped in an element that carries its animated styles
+///
+/// The item [defers its unmount](crate::core::KayakContextRef::defer_unmount), and keeps
+/// rendering itself until its animation completes. Once it's done exiting, it lets the context
+/// unmount it.
+fn PresenceItem(props: PresenceItemProps) {
+    context.defer_unmount();
+    let now = context.frame_time();
+    let entry = match props.entries.get(&props.item_key) {
+        Some(entry) if !(context.is_exiting() && entry.transition.is_done(now)) => entry,
+        _ => {
+            context.finish_exit();
+            return;
+        }
+    };
+
+    if !entry.transition.is_done(now) {
+        context.request_render();
+    }
+
+    let animation = if entry.exiting {
+        &props.exit
+    } else {
+        &props.enter
+    };
+    let styles = animation.styles(entry.transition.value(now));
+    let PresenceEntry { key, builder, .. } = entry;
+    let children = Some(Children::new(move |_, context| {
+        builder(&key, context);
+        context.commit();
+    }));
+    rsx! {
+        <Element styles={Some(styles)}>
+            {children}
+        </Element>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use super::{update_entries, PresenceAnimation, PresenceEntry, PresenceTiming, Stagger};
+    use crate::core::styles::{StyleProp, Transform};
+    use crate::widgets::ItemBuilder;
+
+    const DURATION: Duration = Duration::from_millis(100);
+
+    fn items(keys: &[&str]) -> Vec<(String, ItemBuilder)> {
+        keys.iter()
+            .map(|key| {
+                let builder: ItemBuilder = Arc::new(|_, _| {});
+                (key.to_string(), builder)
+            })
+            .collect()
+    }
+
+    fn timing(stagger: Option<Stagger>) -> PresenceTiming {
+        PresenceTiming {
+            enter: PresenceAnimation::fade(DURATION),
+            exit: PresenceAnimation::fade(DURATION),
+            stagger,
+        }
+    }
+
+    fn keys(entries: &[PresenceEntry]) -> Vec<(&str, bool)> {
+        entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.exiting))
+            .collect()
+    }
+
+    #[test]
+    fn should_enter_new_items() {
+        let now = Instant::now();
+        let entries = update_entries(Vec::new(), items(&["a", "b"]), now, &timing(None));
+        assert_eq!(vec![("a", false), ("b", false)], keys(&entries));
+        assert_eq!(0.0, entries[0].transition.value(now));
+        assert_eq!(1.0, entries[0].transition.target());
+    }
+
+    #[test]
+    fn should_keep_exiting_items_after_the_item_they_followed() {
+        let now = Instant::now();
+        let timing = timing(None);
+        let entries = update_entries(Vec::new(), items(&["a", "b", "c", "d"]), now, &timing);
+        let entries = update_entries(entries, items(&["d", "a"]), now, &timing);
+
+        assert_eq!(
+            vec![("d", false), ("a", false), ("b", true), ("c", true)],
+            keys(&entries)
+        );
+        assert_eq!(0.0, entries[2].transition.target());
+    }
+
+    #[test]
+    fn should_turn_around_items_added_back_while_exiting() {
+        let now = Instant::now();
+        let timing = timing(None);
+        let entries = update_entries(Vec::new(), items(&["a"]), now, &timing);
+        let entries = update_entries(entries, Vec::new(), now + DURATION, &timing);
+        assert_eq!(vec![("a", true)], keys(&entries));
+
+        let halfway = now + DURATION + DURATION / 2;
+        let entries = update_entries(entries, items(&["a"]), halfway, &timing);
+        assert_eq!(vec![("a", false)], keys(&entries));
+        assert_eq!(1.0, entries[0].transition.target());
+        // It animates back in from wherever it was, rather than from the start
+        assert!(entries[0].transition.value(halfway) > 0.0);
+    }
+
+    #[test]
+    fn should_drop_items_that_finished_exiting() {
+        let now = Instant::now();
+        let timing = timing(None);
+        let entries = update_entries(Vec::new(), items(&["a", "b"]), now, &timing);
+        let entries = update_entries(entries, items(&["a"]), now + DURATION, &timing);
+        assert_eq!(2, entries.len());
+
+        let entries = update_entries(entries, items(&["a"]), now + DURATION * 2, &timing);
+        assert_eq!(vec![("a", false)], keys(&entries));
+    }
+
+    #[test]
+    fn should_stagger_entering_items_in_order_and_exiting_ones_in_reverse() {
+        let now = Instant::now();
+        let delay = Duration::from_millis(50);
+        let timing = timing(Some(Stagger::each(delay)));
+        let entries = update_entries(Vec::new(), items(&["a", "b"]), now, &timing);
+        assert!(entries[0].transition.is_done(now + DURATION));
+        assert!(!entries[1].transition.is_done(now + DURATION));
+        assert!(entries[1].transition.is_done(now + DURATION + delay));
+
+        let later = now + DURATION * 2;
+        let entries = update_entries(entries, Vec::new(), later, &timing);
+        assert!(!entries[0].transition.is_done(later + DURATION));
+        assert!(entries[1].transition.is_done(later + DURATION));
+    }
+
+    #[test]
+    fn should_scale_from_the_animation_to_the_laid_out_size() {
+        let animation = PresenceAnimation::fade(DURATION).with_scale(0.5);
+        assert_eq!(
+            StyleProp::Value(Transform::new((0.0, 0.0), 0.5)),
+            animation.styles(0.0).transform
+        );
+        assert_eq!(
+            StyleProp::Value(Transform::IDENTITY),
+            animation.styles(1.0).transform
+        );
+        // Springs overshooting past the hidden look don't flip the widget
+        assert_eq!(
+            StyleProp::Value(Transform::new((0.0, 0.0), 0.0)),
+            PresenceAnimation::fade(DURATION)
+                .with_scale(0.0)
+                .styles(-0.5)
+                .transform
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use kayak_core::OnLayout;
+
+use crate::core::{
+    render_command::RenderCommand,
+    rsx,
+    styles::{Style, StyleProp},
+    widget, Bound, Children, MutableBound, OnEvent, WidgetProps,
+};
+
+/// The frames of an [`AnimatedImage`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationFrames {
+    /// Frames laid out in a grid on a sprite sheet, read left-to-right then top-to-bottom
+    Grid {
+        /// The handle to the sprite sheet
+        handle: u16,
+        /// The size of each frame (in pixels)
+        tile_size: (f32, f32),
+        /// The number of frames in each row of the sheet
+        columns: usize,
+        /// The total number of frames
+        count: usize,
+    },
+    /// A list of image handles, one per frame
+    Handles(Vec<u16>),
+}
+
+impl Default for AnimationFrames {
+    fn default() -> Self {
+        Self::Handles(Vec::new())
+    }
+}
+
+impl AnimationFrames {
+    /// The number of frames in the animation
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Grid { count, .. } => *count,
+            Self::Handles(handles) => handles.len(),
+        }
+    }
+
+    /// Returns true if the animation has no frames
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The render command used to draw the given frame
+    fn render_command(&self, frame: usize) -> RenderCommand {
+        match self {
+            Self::Grid {
+                handle,
+                tile_size,
+                columns,
+                ..
+            } => {
+                let columns = (*columns).max(1);
+                RenderCommand::TextureAtlas {
+                    position: (
+                        (frame % columns) as f32 * tile_size.0,
+                        (frame / columns) as f32 * tile_size.1,
+                    ),
+                    size: *tile_size,
+                    handle: *handle,
+                }
+            }
+            Self::Handles(handles) => RenderCommand::Image {
+                handle: handles.get(frame).copied().unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// A callback that's run when an [`AnimatedImage`] finishes playing
+#[derive(Clone)]
+pub struct OnComplete(pub Arc<RwLock<dyn FnMut() + Send + Sync + 'static>>);
+
+impl OnComplete {
+    pub fn new<F: FnMut() + Send + Sync + 'static>(f: F) -> OnComplete {
+        OnComplete(Arc::new(RwLock::new(f)))
+    }
+}
+
+impl PartialEq for OnComplete {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for OnComplete {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OnComplete").finish()
+    }
+}
+
+/// Props used by the [`AnimatedImage`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct AnimatedImageProps {
+    /// The frames to play
+    pub frames: AnimationFrames,
+    /// The number of frames shown per second
+    pub fps: f32,
+    /// If true, the animation stops on its last frame instead of looping
+    pub play_once: bool,
+    /// Called once the animation has finished playing (only when `play_once` is set)
+    pub on_complete: Option<OnComplete>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(Focusable)]
+    pub focusable: Option<bool>,
+}
+
+/// The time at which an [`AnimatedImage`] started playing
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AnimationStart(Instant);
+
+/// Whether an [`AnimatedImage`] has already run its `on_complete` callback
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct AnimationCompleted(bool);
+
+/// The point an [`AnimatedImage`] has reached in its animation
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Playback {
+    /// The frame to show
+    frame: usize,
+    /// The time (since the animation started) at which the next frame is due, if there is one
+    next_frame: Option<Duration>,
+    /// True if the animation has played once through and stopped on its last frame
+    finished: bool,
+}
+
+impl Playback {
+    /// Finds the point reached after the given time has elapsed
+    fn at(elapsed: Duration, fps: f32, count: usize, play_once: bool) -> Self {
+        if count == 0 || fps <= 0.0 {
+            return Self::default();
+        }
+
+        let frame = (elapsed.as_secs_f32() * fps) as usize;
+        if play_once && frame >= count {
+            Self {
+                frame: count - 1,
+                next_frame: None,
+                finished: true,
+            }
+        } else {
+            Self {
+                frame: frame % count,
+                next_frame: Some(Duration::from_secs_f32((frame + 1) as f32 / fps)),
+                finished: false,
+            }
+        }
+    }
+}
+
+#[widget]
+/// A widget that plays a frame-based animation, such as a loading spinner
+///
+/// The animation starts when the widget is first rendered and is advanced using the context's
+/// [frame time](kayak_core::KayakContextRef::frame_time). Rather than re-rendering every frame,
+/// the widget schedules its next render for when the following frame is due, so a slow animation
+/// costs next to nothing in between.
+///
+/// # Props
+///
+/// __Type:__ [`AnimatedImageProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ✅        |
+///
+pub fn AnimatedImage(props: AnimatedImageProps) {
+    let now = context.frame_time();
+    let start = context.create_state(AnimationStart(now)).unwrap();
+    let completed = context.create_state(AnimationCompleted(false)).unwrap();
+
+    let start = start.get().0;
+    let playback = Playback::at(
+        now.saturating_duration_since(start),
+        props.fps,
+        props.frames.len(),
+        props.play_once,
+    );
+
+    if let Some(next_frame) = playback.next_frame {
+        // Wake up again once the next frame is due
+        context.request_render_at(start + next_frame);
+    } else if playback.finished && !completed.get().0 {
+        completed.set(AnimationCompleted(true));
+        if let Some(on_complete) = props.on_complete.as_ref() {
+            if let Ok(mut on_complete) = on_complete.0.write() {
+                on_complete();
+            }
+        }
+    }
+
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(props.frames.render_command(playback.frame)),
+        ..props.styles.clone().unwrap_or_default()
+    });
+
+    rsx! {
+        <>
+            {children}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::core::render_command::RenderCommand;
+
+    use super::{AnimationFrames, Playback};
+
+    #[test]
+    fn should_advance_frames_over_time() {
+        let at = |millis: u64| Playback::at(Duration::from_millis(millis), 10.0, 4, false);
+
+        assert_eq!(0, at(0).frame);
+        assert_eq!(0, at(99).frame);
+        assert_eq!(1, at(100).frame);
+        assert_eq!(3, at(350).frame);
+
+        // Loops back to the first frame
+        assert_eq!(0, at(400).frame);
+        assert_eq!(2, at(650).frame);
+        assert!(!at(650).finished);
+    }
+
+    #[test]
+    fn should_schedule_the_next_frame() {
+        let playback = Playback::at(Duration::from_millis(250), 10.0, 4, false);
+        let next_frame = playback.next_frame.unwrap();
+        assert!((next_frame.as_secs_f32() - 0.3).abs() < 0.0001);
+
+        // Keeps scheduling frames while looping
+        let playback = Playback::at(Duration::from_millis(1050), 10.0, 4, false);
+        let next_frame = playback.next_frame.unwrap();
+        assert!((next_frame.as_secs_f32() - 1.1).abs() < 0.0001);
+    }
+
+    #[test]
+    fn should_stop_on_the_last_frame_when_played_once() {
+        let playback = Playback::at(Duration::from_millis(350), 10.0, 4, true);
+        assert_eq!(3, playback.frame);
+        assert!(playback.next_frame.is_some());
+        assert!(!playback.finished);
+
+        let playback = Playback::at(Duration::from_secs(5), 10.0, 4, true);
+        assert_eq!(3, playback.frame);
+        assert_eq!(None, playback.next_frame);
+        assert!(playback.finished);
+    }
+
+    #[test]
+    fn should_not_schedule_frames_without_frames_or_fps() {
+        assert_eq!(
+            Playback::default(),
+            Playback::at(Duration::from_secs(1), 10.0, 0, false)
+        );
+        assert_eq!(
+            Playback::default(),
+            Playback::at(Duration::from_secs(1), 0.0, 4, false)
+        );
+    }
+
+    #[test]
+    fn should_pick_grid_frames_left_to_right() {
+        let frames = AnimationFrames::Grid {
+            handle: 1,
+            tile_size: (16.0, 8.0),
+            columns: 3,
+            count: 5,
+        };
+        assert_eq!(
+            RenderCommand::TextureAtlas {
+                position: (16.0, 8.0),
+                size: (16.0, 8.0),
+                handle: 1,
+            },
+            frames.render_command(4)
+        );
+    }
+}
@@ -43,13 +43,22 @@ pub struct AppProps {
 ///
 /// When the `bevy_renderer` feature is enabled, this widget will automatically bind to the window size
 /// of the Bevy app. This allows it to update on window resize in order to match the width and height of the window.
+/// For contexts attached to a camera, this is the size of the window (or image) that camera renders to.
 pub fn App(props: AppProps) {
     #[cfg(feature = "bevy_renderer")]
     {
         use crate::bevy::WindowSize;
         use crate::core::styles::Units;
         use crate::core::{Binding, Bound};
-        let window_size = if let Ok(world) = context.get_global::<bevy::prelude::World>() {
+        // Contexts attached to a camera store their own window size
+        let context_window_size = context
+            .get_global::<Binding<WindowSize>>()
+            .map(|window_size| (*window_size).clone())
+            .ok();
+
+        let window_size = if let Some(window_size) = context_window_size {
+            window_size
+        } else if let Ok(world) = context.get_global::<bevy::prelude::World>() {
             if let Some(window_size) = world.get_resource::<Binding<WindowSize>>() {
                 window_size.clone()
             } else {
@@ -4,7 +4,7 @@ use crate::core::{
     styles::{Style, StyleProp},
     widget, Children, Fragment, OnEvent, WidgetProps,
 };
-use kayak_core::OnLayout;
+use kayak_core::{OnLayout, OnMount};
 
 /// Props used by the [`Background`] widget
 #[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
@@ -15,10 +15,23 @@ pub struct BackgroundProps {
     pub children: Option<Children>,
     #[prop_field(OnEvent)]
     pub on_event: Option<OnEvent>,
+    /// An event handler called before any descendants receive the event
+    ///
+    /// Calling `stop_propagation` from this handler prevents the event from reaching its target.
+    #[prop_field(OnEventCapture)]
+    pub on_event_capture: Option<OnEvent>,
     #[prop_field(OnLayout)]
     pub on_layout: Option<OnLayout>,
+    /// A handler called once, right before this widget is first rendered
+    #[prop_field(OnMount)]
+    pub on_mount: Option<OnMount>,
     #[prop_field(Focusable)]
     pub focusable: Option<bool>,
+    /// If set, disables (or re-enables) this widget and all its descendants
+    ///
+    /// Descendants can still override this by setting their own `disabled` prop.
+    #[prop_field(Disabled)]
+    pub disabled: Option<bool>,
 }
 
 #[widget]
@@ -6,13 +6,18 @@ use crate::core::{
 };
 use kayak_core::CursorIcon;
 
+use super::disabled::apply_disabled_styles;
+
 /// Props used by the [`Button`] widget
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct ButtonProps {
-    /// If true, disables this widget not allowing it to be focusable
+    /// If true, this widget is focused when it's first mounted
+    ///
+    /// If several widgets autofocus at once, the last one declared wins.
+    pub autofocus: bool,
+    /// If true, disables this widget, preventing it from being focused or interacted with
     ///
-    // TODO: Update this documentation when the disabled issue is fixed
-    /// Currently, this does not actually disable the button from being clicked.
+    /// If false, the button inherits the disabled state of its parent.
     pub disabled: bool,
     pub styles: Option<Style>,
     pub children: Option<Children>,
@@ -43,7 +48,15 @@ impl WidgetProps for ButtonProps {
     }
 
     fn get_focusable(&self) -> Option<bool> {
-        Some(!self.disabled)
+        Some(true)
+    }
+
+    fn get_autofocus(&self) -> bool {
+        self.autofocus
+    }
+
+    fn get_disabled(&self) -> Option<bool> {
+        self.disabled.then_some(true)
     }
 }
 
@@ -66,8 +79,6 @@ pub fn Button(props: ButtonProps) {
     // TODO: This should probably do more than just provide basic styling.
     //       Ideally, we could add a `Handler` prop for `on_click` and other common cursor
     //       events. Giving it the additional purpose of being a compact way to define a button.
-    //       This also allows us to make `disable` trule disable the button.
-    //       Also, styles need to reflect disabled status.
     props.styles = Some(
         Style::default()
             .with_style(Style {
@@ -86,9 +97,85 @@ pub fn Button(props: ButtonProps) {
             }),
     );
 
+    if context.is_disabled() {
+        if let Some(styles) = &mut props.styles {
+            apply_disabled_styles(styles);
+        }
+    }
+
     rsx! {
         <Fragment>
             {children}
         </Fragment>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::styles::{Style, StyleProp, Units};
+    use crate::core::{
+        mount_widget, rsx, widget, Children, EventType, InputEvent, KayakContext, OnEvent, Widget,
+    };
+    use crate::widgets::{Button, Element, ElementProps};
+
+    /// The name of the button that was last clicked
+    struct Clicked(&'static str);
+
+    #[widget]
+    fn Buttons() {
+        let on_event = OnEvent::new(|ctx, event| {
+            if let EventType::Click(..) = event.event_type {
+                ctx.set_global(Clicked("explicit"));
+            }
+        });
+
+        rsx! {
+            <>
+                <Button on_event={Some(on_event)}>{}</Button>
+                <Button on_event={|ctx, event| {
+                    if let EventType::Click(..) = event.event_type {
+                        ctx.set_global(Clicked("inline"));
+                    }
+                }}>{}</Button>
+            </>
+        }
+    }
+
+    fn click(context: &mut KayakContext, position: (f32, f32)) -> &'static str {
+        context.process_events(vec![InputEvent::MouseMoved(position)]);
+        context.process_events(vec![InputEvent::MouseLeftPress]);
+        context.process_events(vec![InputEvent::MouseLeftRelease]);
+        context
+            .get_global::<Clicked>()
+            .map_or("", |clicked| clicked.0)
+    }
+
+    #[test]
+    fn should_accept_inline_and_explicit_on_event_handlers() {
+        let mut context = KayakContext::new();
+        // The buttons are laid out within the root, stacked on top of each other
+        let root = ElementProps {
+            children: Some(Children::new(|_, context| {
+                let container = ElementProps {
+                    styles: Some(Style {
+                        width: StyleProp::Value(Units::Pixels(100.0)),
+                        height: StyleProp::Value(Units::Pixels(100.0)),
+                        ..Default::default()
+                    }),
+                    children: Some(Children::new(|_, context| {
+                        context.add_widget(Buttons::default(), 0);
+                        context.commit();
+                    })),
+                    ..Default::default()
+                };
+                context.add_widget(Element::constructor(container), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Element::constructor(root));
+
+        assert_eq!("explicit", click(&mut context, (50.0, 20.0)));
+        assert_eq!("inline", click(&mut context, (50.0, 65.0)));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::core::{
+    render_command::RenderCommand,
+    rsx,
+    styles::{Style, StyleProp},
+    widget, Children, Curve, OnEvent, OnLayout, WidgetProps,
+};
+
+/// Props used by the [`Canvas`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct CanvasProps {
+    /// The curves to draw, in order
+    ///
+    /// Their points are relative to the top-left corner of the canvas, in its untransformed space,
+    /// so curves drawn within a [`Viewport`](crate::widgets::Viewport) are panned and zoomed along
+    /// with the rest of its content.
+    pub curves: Vec<Curve>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+}
+
+#[widget]
+/// A widget that draws anti-aliased curves and arrows, such as the connections of a node editor
+///
+/// The curves are drawn behind the canvas's children, and aren't hit-tested themselves. All of a
+/// canvas's curves are drawn together, so it's cheaper to draw many curves with one canvas than
+/// with many.
+///
+/// To connect two widgets, get their layouts (such as with their `on_layout` events) and convert
+/// their ports to the canvas's space before building the curve with
+/// [`Bezier::between`](crate::core::Bezier::between).
+///
+/// # Props
+///
+/// __Type:__ [`CanvasProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, Arrow, Bezier, Color, Curve, widget};
+/// # use kayak_ui::widgets::Canvas;
+/// # #[widget]
+/// # fn Example() {
+/// // From an output port on the right of one node to an input port on the left of another
+/// let bezier = Bezier::between((120.0, 40.0), (1.0, 0.0), (300.0, 160.0), (-1.0, 0.0));
+/// let curves = vec![Curve::new(bezier, 2.0, Color::WHITE).with_arrow(Arrow::default())];
+///
+/// rsx! {
+///     <Canvas curves={curves}>
+///         {/* ... */}
+///     </Canvas>
+/// }
+/// # }
+/// ```
+pub fn Canvas(props: CanvasProps) {
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(RenderCommand::Curves {
+            curves: props.curves.clone(),
+        }),
+        ..props.styles.clone().unwrap_or_default()
+    });
+
+    rsx! {
+        <>
+            {children}
+        </>
+    }
+}
@@ -60,3 +60,55 @@ pub fn Clip(props: ClipProps) {
         </>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Clip, ClipProps};
+    use crate::core::styles::{Style, StyleProp, Units};
+    use crate::core::{mount_widget, Children, KayakContext, Widget};
+    use crate::widgets::{Background, BackgroundProps};
+
+    fn size(size: f32) -> Option<Style> {
+        Some(Style {
+            width: StyleProp::Value(Units::Pixels(size)),
+            height: StyleProp::Value(Units::Pixels(size)),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn should_not_hit_clipped_out_widgets() {
+        let mut context = KayakContext::new();
+        // The inner background overflows the clip, so its bottom-right corner isn't visible
+        let clip = ClipProps {
+            styles: size(50.0),
+            children: Some(Children::new(move |_, context| {
+                let background = BackgroundProps {
+                    styles: size(100.0),
+                    ..Default::default()
+                };
+                context.add_widget(Background::constructor(background), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        let parent = BackgroundProps {
+            styles: size(200.0),
+            children: Some(Children::new(move |_, context| {
+                context.add_widget(Clip::constructor(clip.clone()), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Background::constructor(parent));
+
+        let root = context.widget_manager.node_tree.root_node;
+        let hit = context.hit_test((25.0, 25.0));
+        let name = hit.and_then(|index| context.widget_manager.get_name(&index));
+        assert!(name.unwrap().ends_with("::Background"));
+        assert_ne!(root, hit);
+
+        // The inner background is still under this point, but it's been clipped out of view
+        assert_ne!(hit, context.hit_test((75.0, 75.0)));
+    }
+}
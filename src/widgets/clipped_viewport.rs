@@ -0,0 +1,76 @@
+use kayak_core::OnLayout;
+
+use crate::core::{
+    render_command::RenderCommand,
+    rsx,
+    styles::{Corner, Style, StyleProp, Units},
+    widget, Children, OnEvent, WidgetProps,
+};
+
+/// Props used by the [`ClippedViewport`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ClippedViewportProps {
+    /// If true, the contents are clipped to a circle (or a pill shape if the viewport isn't square)
+    ///
+    /// This overrides the [`border_radius`](Style::border_radius) styling.
+    pub circular: bool,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+}
+
+#[widget]
+/// A widget that clips its contents to its own rounded outline
+///
+/// This works like [`Clip`](crate::widgets::Clip), except that the contents are also masked to the
+/// shape given by the [`border_radius`](Style::border_radius) styling, which is useful for things
+/// like avatars and pills.
+///
+/// Clips can be nested: content is always limited to the bounds of every enclosing clip, while
+/// the shape of the innermost rounded clip is used for masking.
+///
+/// # Props
+///
+/// __Type:__ [`ClippedViewportProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ❌        |
+///
+pub fn ClippedViewport(props: ClippedViewportProps) {
+    let incoming_styles = props.styles.clone().unwrap_or_default();
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(RenderCommand::Clip),
+        width: if matches!(incoming_styles.width, StyleProp::Value(..)) {
+            incoming_styles.width
+        } else {
+            StyleProp::Value(Units::Stretch(1.0))
+        },
+        height: if matches!(incoming_styles.height, StyleProp::Value(..)) {
+            incoming_styles.height
+        } else {
+            StyleProp::Value(Units::Stretch(1.0))
+        },
+        border_radius: if props.circular {
+            // The radius is limited to half of the viewport's size when rendering
+            StyleProp::Value(Corner::all(f32::MAX))
+        } else {
+            incoming_styles.border_radius
+        },
+        ..incoming_styles
+    });
+    rsx! {
+        <>
+            {children}
+        </>
+    }
+}
@@ -0,0 +1,348 @@
+use std::time::{Duration, Instant};
+
+use crate::core::{
+    rsx,
+    styles::{Style, StyleProp, Units, Visibility},
+    widget, Bound, Children, Easing, GeometryChanged, MutableBound, OnLayout, Spring, Transition,
+    WidgetProps,
+};
+
+use crate::widgets::{Clip, Element, If};
+
+/// The duration of a [`Collapse`] transition if not set by its props
+const DEFAULT_DURATION: Duration = Duration::from_millis(200);
+
+/// Props used by the [`Collapse`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct CollapseProps {
+    /// If true, the content is expanded to its full height, otherwise it's collapsed to nothing
+    pub open: bool,
+    /// How long it takes to expand or collapse the content
+    ///
+    /// If `None`, the transition takes 200 milliseconds.
+    pub duration: Option<Duration>,
+    /// The easing curve of the transition
+    ///
+    /// If `None`, the transition eases in and out.
+    pub easing: Option<Easing>,
+    /// If set, the content slides with this spring instead of over the duration and easing
+    pub spring: Option<Spring>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+}
+
+/// The measured height of the content of a [`Collapse`], or `None` until it's measured
+///
+/// This is reset whenever the content is unmounted.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct ContentHeight(Option<f32>);
+
+/// The animation of a [`Collapse`], kept between renders
+#[derive(Debug, Clone, PartialEq)]
+struct CollapseState {
+    transition: Transition,
+    /// True if the collapse was opened, but its content wasn't measured since being mounted
+    opening: bool,
+}
+
+/// How a [`Collapse`] is drawn on a given frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CollapseFrame {
+    /// The height of the clip around the content
+    height: Units,
+    /// Whether the content is mounted, which it is unless fully collapsed
+    mounted: bool,
+    /// Whether the content is sliding open or closed
+    animating: bool,
+}
+
+impl CollapseState {
+    fn new(now: Instant) -> Self {
+        Self {
+            transition: Transition::new(0.0, now),
+            opening: false,
+        }
+    }
+
+    /// Updates the transition for the given props and content height
+    ///
+    /// The content isn't animated when first mounted. Content mounted by opening the collapse
+    /// only slides open once it has been measured.
+    fn update(
+        &mut self,
+        props: &CollapseProps,
+        was_open: Option<bool>,
+        content_height: Option<f32>,
+        now: Instant,
+    ) -> CollapseFrame {
+        let toggled = was_open.map_or(false, |was_open| was_open != props.open);
+        let opening = props.open && (toggled || self.opening);
+        self.opening = opening && content_height.is_none();
+
+        let target = if props.open {
+            content_height
+        } else {
+            Some(0.0)
+        };
+        if let Some(target) = target.filter(|target| *target != self.transition.target()) {
+            if opening || toggled || !self.transition.is_done(now) {
+                let duration = props.duration.unwrap_or(DEFAULT_DURATION);
+                self.transition.retarget(target, now, duration);
+                self.transition
+                    .set_easing(props.easing.clone().unwrap_or_default());
+                self.transition.set_spring(props.spring);
+            } else {
+                // The content was resized while at rest
+                self.transition.settle(target, now);
+            }
+        }
+
+        let animating = !self.transition.is_done(now);
+        let height = if animating || self.opening {
+            // Springs can overshoot past closed
+            Units::Pixels(self.transition.value(now).max(0.0))
+        } else if props.open {
+            Units::Auto
+        } else {
+            Units::Pixels(0.0)
+        };
+        CollapseFrame {
+            height,
+            mounted: props.open || animating,
+            animating,
+        }
+    }
+}
+
+#[widget]
+/// A widget that slides its content open and closed
+///
+/// The content is always laid out at its natural (auto) height, which the widget measures and
+/// then animates its own height to, clipping the content along the way. Toggling `open` while the
+/// content is still sliding turns it around from wherever it currently is. Like with
+/// [`If`](crate::widgets::If), the content is only mounted while it's (at least partly) open, so
+/// it doesn't keep its state once fully collapsed.
+///
+/// # Props
+///
+/// __Type:__ [`CollapseProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ❌        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, use_state, widget};
+/// # use kayak_ui::widgets::{Collapse, Text};
+///
+/// #[widget]
+/// fn Details() {
+///     let (open, ..) = use_state!(false);
+///
+///     rsx! {
+///         <Collapse open={open}>
+///             <Text content={"Details".to_string()} size={16.0} />
+///         </Collapse>
+///     }
+/// }
+/// ```
+pub fn Collapse(props: CollapseProps) {
+    let now = context.frame_time();
+
+    let content_height = context.create_state(ContentHeight::default()).unwrap();
+    let was_open = context.use_previous(props.open);
+    let state = context.create_state(CollapseState::new(now)).unwrap();
+    let previous = state.get();
+    let mut current = previous.clone();
+    let frame = current.update(&props, was_open, content_height.get().0, now);
+    if current != previous {
+        state.set(current);
+    }
+
+    if frame.animating {
+        context.request_render();
+    }
+    if !frame.mounted && content_height.get().0.is_some() {
+        content_height.set(ContentHeight(None));
+    }
+
+    let incoming_styles = props.styles.clone().unwrap_or_default();
+    let clip_styles = Style {
+        height: StyleProp::Value(frame.height),
+        visibility: if frame.mounted {
+            incoming_styles.visibility.clone()
+        } else {
+            StyleProp::Value(Visibility::Hidden)
+        },
+        ..incoming_styles
+    };
+    let content_styles = Style {
+        width: StyleProp::Value(Units::Stretch(1.0)),
+        height: StyleProp::Value(Units::Auto),
+        ..Default::default()
+    };
+
+    let on_content_layout = OnLayout::new(move |_, evt| {
+        if evt.flags.intersects(GeometryChanged::HEIGHT_CHANGED)
+            && content_height.get().0 != Some(evt.layout.height)
+        {
+            content_height.set(ContentHeight(Some(evt.layout.height)));
+        }
+    });
+
+    let mounted = frame.mounted;
+    rsx! {
+        <Clip styles={Some(clip_styles)}>
+            <If condition={mounted}>
+                <Element styles={Some(content_styles)} on_layout={Some(on_content_layout)}>
+                    {children}
+                </Element>
+            </If>
+        </Clip>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Collapse, CollapseProps, CollapseState};
+    use crate::core::styles::Units;
+    use crate::core::{mount_widget, widget, Children, KayakContext, Widget};
+    use crate::widgets::{Element, ElementProps};
+
+    #[widget]
+    fn Details() {}
+
+    const DURATION: Duration = Duration::from_millis(100);
+
+    fn props(open: bool) -> CollapseProps {
+        CollapseProps {
+            open,
+            duration: Some(DURATION),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_not_mount_closed_content() {
+        let mut context = KayakContext::new();
+        // The collapse is laid out within the root
+        let root = ElementProps {
+            children: Some(Children::new(|_, context| {
+                let collapse = CollapseProps {
+                    children: Some(Children::new(|_, context| {
+                        context.add_widget(Details::default(), 0);
+                        context.commit();
+                    })),
+                    ..props(false)
+                };
+                context.add_widget(Collapse::constructor(collapse), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Element::constructor(root));
+
+        let widget_manager = &context.widget_manager;
+        assert!(widget_manager.tree.flatten().iter().all(|id| {
+            !widget_manager
+                .get_name(id)
+                .map_or(false, |widget| widget.ends_with("::Details"))
+        }));
+    }
+
+    #[test]
+    fn should_not_animate_when_first_mounted() {
+        let now = Instant::now();
+        let mut state = CollapseState::new(now);
+        let frame = state.update(&props(true), None, None, now);
+        assert!(frame.mounted);
+        assert_eq!(Units::Auto, frame.height);
+
+        // Measuring the content doesn't start sliding it open either
+        let frame = state.update(&props(true), Some(true), Some(50.0), now);
+        assert!(!frame.animating);
+        assert_eq!(Units::Auto, frame.height);
+
+        let mut state = CollapseState::new(now);
+        let frame = state.update(&props(false), None, None, now);
+        assert!(!frame.mounted);
+        assert_eq!(Units::Pixels(0.0), frame.height);
+    }
+
+    #[test]
+    fn should_slide_open_once_the_content_is_measured() {
+        let now = Instant::now();
+        let mut state = CollapseState::new(now);
+        state.update(&props(false), None, None, now);
+
+        // The content is mounted to be measured, without showing any of it yet
+        let frame = state.update(&props(true), Some(false), None, now);
+        assert!(frame.mounted);
+        assert_eq!(Units::Pixels(0.0), frame.height);
+
+        let later = now + DURATION / 4;
+        let frame = state.update(&props(true), Some(true), Some(80.0), later);
+        assert!(frame.animating);
+        assert_eq!(80.0, state.transition.target());
+        assert_eq!(0.0, state.transition.value(later));
+
+        let frame = state.update(&props(true), Some(true), Some(80.0), later + DURATION);
+        assert!(!frame.animating);
+        assert_eq!(Units::Auto, frame.height);
+    }
+
+    #[test]
+    fn should_unmount_the_content_once_collapsed() {
+        let now = Instant::now();
+        let mut state = CollapseState::new(now);
+        state.update(&props(true), None, Some(80.0), now);
+
+        let frame = state.update(&props(false), Some(true), Some(80.0), now);
+        assert!(frame.mounted);
+        assert!(frame.animating);
+
+        let frame = state.update(&props(false), Some(false), Some(80.0), now + DURATION);
+        assert!(!frame.mounted);
+        assert_eq!(Units::Pixels(0.0), frame.height);
+    }
+
+    #[test]
+    fn should_turn_around_from_the_current_height() {
+        let now = Instant::now();
+        let mut state = CollapseState::new(now);
+        state.update(&props(true), None, Some(80.0), now);
+        state.update(&props(false), Some(true), Some(80.0), now);
+
+        // Reopening halfway through collapsing doesn't wait for the (still mounted) content
+        let halfway = now + DURATION / 2;
+        let frame = state.update(&props(true), Some(false), Some(80.0), halfway);
+        assert!(frame.animating);
+        assert_eq!(80.0, state.transition.target());
+        match frame.height {
+            Units::Pixels(height) => assert!(height > 0.0 && height < 80.0),
+            height => panic!("expected a fixed height, got {:?}", height),
+        }
+    }
+
+    #[test]
+    fn should_follow_content_resized_at_rest() {
+        let now = Instant::now();
+        let mut state = CollapseState::new(now);
+        state.update(&props(true), None, Some(80.0), now);
+
+        let frame = state.update(&props(true), Some(true), Some(120.0), now + DURATION);
+        assert!(!frame.animating);
+        assert_eq!(120.0, state.transition.target());
+        assert_eq!(Units::Auto, frame.height);
+    }
+}
@@ -0,0 +1,383 @@
+use crate::core::{
+    constructor,
+    render_command::RenderCommand,
+    rsx,
+    styles::{Corner, Edge, PositionType, Style, StyleProp, Units},
+    use_state, widget, Children, Color, CursorIcon, EventType, Handler, OnEvent, OnLayout,
+    PointerEvents, VecTracker, WidgetProps,
+};
+
+use crate::widgets::{Background, Slider};
+
+use super::disabled::apply_disabled_styles;
+
+/// The number of columns (saturation steps) in the saturation/value square
+const SQUARE_COLUMNS: usize = 12;
+/// The number of rows (value steps) in the saturation/value square
+const SQUARE_ROWS: usize = 8;
+/// The number of segments drawn along the hue slider
+const HUE_SEGMENTS: usize = 12;
+/// The number of segments drawn along the alpha slider
+const ALPHA_SEGMENTS: usize = 6;
+/// The size of the marker showing the picked color within the saturation/value square, in pixels
+const MARKER_SIZE: f32 = 10.0;
+
+/// Props used by the [`ColorPicker`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ColorPickerProps {
+    /// Sets the controlled color of the picker
+    ///
+    /// If `None`, the color will be automatically handled internally, starting from the
+    /// [`default_color`](Self::default_color).
+    pub color: Option<Color>,
+    /// The initial color of the picker
+    pub default_color: Color,
+    /// If true, disables this widget, preventing its color from being changed
+    ///
+    /// If false, the widget inherits the disabled state of its parent.
+    #[prop_field(Disabled)]
+    pub disabled: bool,
+    /// If true, hides the alpha slider
+    ///
+    /// The alpha of the picked color is then left as is.
+    pub hide_alpha: bool,
+    /// A callback for when the user changes the color
+    ///
+    /// This is called continuously while the user drags any part of the picker.
+    pub on_change: Option<Handler<Color>>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(Focusable)]
+    pub focusable: Option<bool>,
+}
+
+#[widget]
+/// A widget for picking a color, using a saturation/value square along with hue and alpha sliders
+///
+/// The picker keeps track of the color's hue itself, so the hue isn't lost while the picked color
+/// is a shade of gray (or black).
+///
+/// # Props
+///
+/// __Type:__ [`ColorPickerProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ✅        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{Color, Handler, rsx, use_state, widget};
+/// # use kayak_ui::widgets::ColorPicker;
+/// # #[widget]
+/// # fn Example() {
+/// let (color, set_color, ..) = use_state!(Color::new(0.2, 0.6, 0.4, 1.0));
+/// let on_change = Handler::new(move |color| {
+///     set_color(color);
+/// });
+///
+/// rsx! {
+///     <ColorPicker color={Some(color)} on_change={Some(on_change)} />
+/// }
+/// # }
+/// ```
+pub fn ColorPicker(props: ColorPickerProps) {
+    let ColorPickerProps {
+        color,
+        default_color,
+        hide_alpha,
+        on_change,
+        ..
+    } = props.clone();
+
+    // === State === //
+    let (hsva, set_hsva, ..) = use_state!(color.unwrap_or(default_color).to_hsva());
+    let hsva = match color {
+        // This is a controlled state
+        Some(color) if !is_same_color(color, from_hsva(hsva)) => {
+            let next = keep_hue(color.to_hsva(), hsva);
+            set_hsva(next);
+            next
+        }
+        _ => hsva,
+    };
+    let (hue, saturation, value, alpha) = hsva;
+
+    let update = move |next: (f32, f32, f32, f32)| {
+        if color.is_none() {
+            // This is an internally-managed state
+            set_hsva(next);
+        }
+        if let Some(ref on_change) = on_change {
+            on_change.call(from_hsva(next));
+        }
+    };
+
+    let on_square_change = {
+        let update = update.clone();
+        Handler::new(move |(saturation, value)| update((hue, saturation, value, alpha)))
+    };
+    let on_hue_change = {
+        let update = update.clone();
+        Handler::new(move |hue| update((hue, saturation, value, alpha)))
+    };
+    let on_alpha_change = Handler::new(move |alpha| update((hue, saturation, value, alpha)));
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Layout.into(),
+                ..Default::default()
+            })
+            .with_style(&props.styles)
+            .with_style(Style {
+                width: Units::Pixels(200.0).into(),
+                height: Units::Auto.into(),
+                row_between: Units::Pixels(8.0).into(),
+                ..Default::default()
+            }),
+    );
+
+    let hue_colors = (0..HUE_SEGMENTS)
+        .map(|index| {
+            let hue = (index as f32 + 0.5) * 360.0 / HUE_SEGMENTS as f32;
+            Color::from_hsva(hue, 1.0, 1.0, 1.0)
+        })
+        .collect::<Vec<_>>();
+    let alpha_colors = (0..ALPHA_SEGMENTS)
+        .map(|index| {
+            let alpha = (index as f32 + 0.5) / ALPHA_SEGMENTS as f32;
+            Color::from_hsva(hue, saturation, value, alpha)
+        })
+        .collect::<Vec<_>>();
+
+    let mut preview_styles = Style {
+        background_color: from_hsva(hsva).into(),
+        border: Edge::all(1.0).into(),
+        border_color: Color::new(0.0781, 0.0898, 0.101, 1.0).into(),
+        border_radius: Corner::all(3.0).into(),
+        width: Units::Stretch(1.0).into(),
+        height: Units::Pixels(24.0).into(),
+        ..Default::default()
+    };
+    if context.is_disabled() {
+        apply_disabled_styles(&mut preview_styles);
+    }
+
+    rsx! {
+        <>
+            <SaturationValueSquare
+                hue={hue}
+                saturation={saturation}
+                value={value}
+                on_change={Some(on_square_change)}
+            />
+            <Slider
+                value={hue}
+                range={Some((0.0, 360.0))}
+                track_colors={hue_colors}
+                thumb_color={Some(Color::from_hsva(hue, 1.0, 1.0, 1.0))}
+                on_change={Some(on_hue_change)}
+            />
+            {if !hide_alpha {
+                <Slider
+                    value={alpha}
+                    track_colors={alpha_colors}
+                    on_change={Some(on_alpha_change)}
+                />
+            }}
+            <Background styles={Some(preview_styles)} />
+        </>
+    }
+}
+
+/// Props used by the [`SaturationValueSquare`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+struct SaturationValueSquareProps {
+    /// The hue displayed by the square
+    hue: f32,
+    /// The picked saturation, increasing from left to right
+    saturation: f32,
+    /// The picked value, increasing from bottom to top
+    value: f32,
+    /// A callback for when the user picks a new saturation and value
+    on_change: Option<Handler<(f32, f32)>>,
+    #[prop_field(Styles)]
+    styles: Option<Style>,
+}
+
+#[widget]
+/// A widget that displays the saturations and values of a hue, picked by dragging a marker
+///
+/// The square is drawn as a grid of cells, each showing the color at its center.
+fn SaturationValueSquare(props: SaturationValueSquareProps) {
+    let SaturationValueSquareProps {
+        hue,
+        saturation,
+        value,
+        on_change,
+        ..
+    } = props.clone();
+    let disabled = context.is_disabled();
+
+    // === Layout === //
+    let size = context
+        .use_layout()
+        .map_or((0.0, 0.0), |layout| (layout.width, layout.height));
+
+    // === States === //
+    let (is_dragging, set_is_dragging, ..) = use_state!(false);
+
+    // === Events === //
+    let handler = OnEvent::new(move |ctx, event| {
+        let layout = match ctx.get_layout(&event.current_target) {
+            Some(layout) => *layout,
+            None => return,
+        };
+        let position = match event.event_type {
+            EventType::MouseDown(data) => {
+                ctx.capture_cursor(event.current_target);
+                set_is_dragging(true);
+                data.position
+            }
+            EventType::MouseUp(..) => {
+                ctx.release_cursor(event.current_target);
+                set_is_dragging(false);
+                return;
+            }
+            EventType::Hover(data) if is_dragging => data.position,
+            _ => return,
+        };
+
+        let next_saturation = ((position.0 - layout.posx) / layout.width.max(1.0)).clamp(0.0, 1.0);
+        let next_value =
+            1.0 - ((position.1 - layout.posy) / layout.height.max(1.0)).clamp(0.0, 1.0);
+        if (next_saturation, next_value) != (saturation, value) {
+            if let Some(ref on_change) = on_change {
+                on_change.call((next_saturation, next_value));
+            }
+        }
+    });
+    let handler = if disabled { None } else { Some(handler) };
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Layout.into(),
+                ..Default::default()
+            })
+            .with_style(&props.styles)
+            .with_style(Style {
+                width: Units::Stretch(1.0).into(),
+                height: Units::Pixels(120.0).into(),
+                cursor: if disabled {
+                    CursorIcon::Default
+                } else {
+                    CursorIcon::Crosshair
+                }
+                .into(),
+                ..Default::default()
+            }),
+    );
+
+    let grid_styles = Style {
+        background_color: Color::TRANSPARENT.into(),
+        cursor: StyleProp::Inherit,
+        width: Units::Stretch(1.0).into(),
+        height: Units::Stretch(1.0).into(),
+        ..Default::default()
+    };
+
+    let cell_width = 100.0 / SQUARE_COLUMNS as f32;
+    let cell_height = 100.0 / SQUARE_ROWS as f32;
+    let cells: Vec<(usize, usize)> = (0..SQUARE_ROWS)
+        .flat_map(|row| (0..SQUARE_COLUMNS).map(move |column| (row, column)))
+        .collect();
+
+    let mut marker_styles = Style {
+        background_color: Color::TRANSPARENT.into(),
+        border: Edge::all(2.0).into(),
+        border_color: Color::WHITE.into(),
+        border_radius: Corner::all(MARKER_SIZE / 2.0).into(),
+        pointer_events: PointerEvents::None.into(),
+        position_type: PositionType::SelfDirected.into(),
+        left: Units::Pixels(saturation * size.0 - MARKER_SIZE / 2.0).into(),
+        top: Units::Pixels((1.0 - value) * size.1 - MARKER_SIZE / 2.0).into(),
+        width: Units::Pixels(MARKER_SIZE).into(),
+        height: Units::Pixels(MARKER_SIZE).into(),
+        ..Default::default()
+    };
+    if disabled {
+        marker_styles.border_color = Color::new(1.0, 1.0, 1.0, 0.5).into();
+    }
+
+    rsx! {
+        <Background on_event={handler} styles={Some(grid_styles)}>
+            {VecTracker::from(cells.iter().map(|&(row, column)| {
+                let cell_saturation = (column as f32 + 0.5) / SQUARE_COLUMNS as f32;
+                let cell_value = 1.0 - (row as f32 + 0.5) / SQUARE_ROWS as f32;
+                let mut cell_styles = Style {
+                    background_color: Color::from_hsva(hue, cell_saturation, cell_value, 1.0).into(),
+                    pointer_events: PointerEvents::None.into(),
+                    position_type: PositionType::SelfDirected.into(),
+                    left: Units::Percentage(column as f32 * cell_width).into(),
+                    top: Units::Percentage(row as f32 * cell_height).into(),
+                    width: Units::Percentage(cell_width).into(),
+                    height: Units::Percentage(cell_height).into(),
+                    ..Default::default()
+                };
+                if disabled {
+                    apply_disabled_styles(&mut cell_styles);
+                }
+                constructor! {
+                    <Background styles={Some(cell_styles)} />
+                }
+            }))}
+            <Background styles={Some(marker_styles)} />
+        </Background>
+    }
+}
+
+/// Converts a tuple of hue, saturation, value and alpha back into a color
+fn from_hsva((hue, saturation, value, alpha): (f32, f32, f32, f32)) -> Color {
+    Color::from_hsva(hue, saturation, value, alpha)
+}
+
+/// Replaces the hue (and saturation) of the given HSVA with the previous one's if they were lost
+///
+/// A gray color has no hue, and black has neither hue nor saturation. Keeping the previous ones
+/// means the picker doesn't jump back to red as the user drags through them.
+fn keep_hue(
+    (hue, saturation, value, alpha): (f32, f32, f32, f32),
+    previous: (f32, f32, f32, f32),
+) -> (f32, f32, f32, f32) {
+    if value <= f32::EPSILON {
+        (previous.0, previous.1, value, alpha)
+    } else if saturation <= f32::EPSILON {
+        (previous.0, saturation, value, alpha)
+    } else {
+        (hue, saturation, value, alpha)
+    }
+}
+
+/// Returns true if the two colors are equal, within the precision lost in HSVA conversions
+fn is_same_color(a: Color, b: Color) -> bool {
+    const PRECISION: f32 = 1e-4;
+    (a.r - b.r).abs() < PRECISION
+        && (a.g - b.g).abs() < PRECISION
+        && (a.b - b.b).abs() < PRECISION
+        && (a.a - b.a).abs() < PRECISION
+}
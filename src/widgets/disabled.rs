@@ -0,0 +1,19 @@
+use crate::core::styles::{Style, StyleProp};
+use kayak_core::CursorIcon;
+
+/// The opacity multiplier applied to the colors of a disabled widget
+const DISABLED_OPACITY: f32 = 0.5;
+
+/// Applies the "disabled" look to the given styles
+///
+/// This dims any background and text colors and resets the cursor so the widget no longer
+/// appears interactive.
+pub(crate) fn apply_disabled_styles(styles: &mut Style) {
+    if let StyleProp::Value(color) = &mut styles.background_color {
+        color.a *= DISABLED_OPACITY;
+    }
+    if let StyleProp::Value(color) = &mut styles.color {
+        color.a *= DISABLED_OPACITY;
+    }
+    styles.cursor = CursorIcon::Default.into();
+}
@@ -1,4 +1,4 @@
-use kayak_core::OnLayout;
+use kayak_core::{OnLayout, OnMount};
 
 use crate::core::{
     render_command::RenderCommand,
@@ -16,10 +16,23 @@ pub struct ElementProps {
     pub children: Option<Children>,
     #[prop_field(OnEvent)]
     pub on_event: Option<OnEvent>,
+    /// An event handler called before any descendants receive the event
+    ///
+    /// Calling `stop_propagation` from this handler prevents the event from reaching its target.
+    #[prop_field(OnEventCapture)]
+    pub on_event_capture: Option<OnEvent>,
     #[prop_field(OnLayout)]
     pub on_layout: Option<OnLayout>,
+    /// A handler called once, right before this widget is first rendered
+    #[prop_field(OnMount)]
+    pub on_mount: Option<OnMount>,
     #[prop_field(Focusable)]
     pub focusable: Option<bool>,
+    /// If set, disables (or re-enables) this widget and all its descendants
+    ///
+    /// Descendants can still override this by setting their own `disabled` prop.
+    #[prop_field(Disabled)]
+    pub disabled: Option<bool>,
 }
 
 #[widget]
@@ -51,3 +64,78 @@ pub fn Element(props: ElementProps) {
         </>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{Element, ElementProps};
+    use crate::core::{
+        mount_widget, Children, Event, EventType, InputEvent, KayakContext, OnEvent, Widget,
+    };
+
+    type Log = Arc<Mutex<Vec<&'static str>>>;
+
+    /// Creates an event handler that logs character input under the given name, stopping it
+    /// if `stop` is set
+    fn log_handler(log: &Log, name: &'static str, stop: bool) -> Option<OnEvent> {
+        let log = log.clone();
+        Some(OnEvent::new(move |_, event: &mut Event| {
+            if matches!(event.event_type, EventType::CharInput { .. }) {
+                log.lock().unwrap().push(name);
+                if stop {
+                    event.stop_propagation();
+                }
+            }
+        }))
+    }
+
+    /// Mounts an element around a focused one, returning the log of the events each one received
+    fn mount(context: &mut KayakContext, stop_capture: bool) -> Log {
+        let log = Log::default();
+        let target = ElementProps {
+            on_event: log_handler(&log, "target", false),
+            on_event_capture: log_handler(&log, "target capture", false),
+            focusable: Some(true),
+            ..Default::default()
+        };
+        let root = ElementProps {
+            on_event: log_handler(&log, "root", false),
+            on_event_capture: log_handler(&log, "root capture", stop_capture),
+            children: Some(Children::new(move |_, context| {
+                context.add_widget(Element::constructor(target.clone()), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(context, Element::constructor(root));
+
+        let widget_manager = &context.widget_manager;
+        let target = widget_manager.tree.flatten().into_iter().rev().find(|id| {
+            widget_manager
+                .get_name(id)
+                .map_or(false, |widget| widget.ends_with("::Element"))
+        });
+        context.focus(target.unwrap());
+        context.render();
+        context.process_events(vec![InputEvent::CharEvent { c: 'a' }]);
+        log
+    }
+
+    #[test]
+    fn should_capture_events_before_they_bubble() {
+        let mut context = KayakContext::new();
+        let log = mount(&mut context, false);
+        assert_eq!(
+            vec!["root capture", "target capture", "target", "root"],
+            *log.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn should_not_reach_the_target_when_stopped_while_capturing() {
+        let mut context = KayakContext::new();
+        let log = mount(&mut context, true);
+        assert_eq!(vec!["root capture"], *log.lock().unwrap());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::core::{
+    render_command::RenderCommand,
+    rsx,
+    styles::{Style, StyleProp},
+    use_state, widget, Children, Handler, OnEvent, WidgetProps,
+};
+
+use crate::widgets::Text;
+
+/// Props used by the [`ErrorBoundary`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ErrorBoundaryProps {
+    /// The widgets to render in place of the children once a panic has been caught
+    ///
+    /// If `None`, the panic message is rendered as text instead.
+    pub fallback: Option<Children>,
+    /// A callback for when a panic is caught
+    ///
+    /// The handler is given the message of the panic.
+    pub on_error: Option<Handler<String>>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+}
+
+#[widget]
+/// A widget that catches panics from its children and renders a fallback instead
+///
+/// Panics are caught both while the children are first built and whenever one of them is
+/// re-rendered later on. Once a panic has been caught, the fallback is rendered for as long as the
+/// boundary stays mounted.
+///
+/// This uses [`KayakContextRef::catch_panic`](crate::core::KayakContextRef::catch_panic), so the
+/// same caveats apply: only unwinding panics are caught (never aborts), and any state the
+/// panicking widgets changed beforehand is kept as-is.
+///
+/// # Props
+///
+/// __Type:__ [`ErrorBoundaryProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```ignore
+/// # use kayak_ui::core::{rsx, widget, Handler};
+///
+/// #[widget]
+/// fn MyWidget() {
+///   let on_error = Handler::new(|message: String| eprintln!("Widget panicked: {}", message));
+///
+///   rsx! {
+///     <ErrorBoundary on_error={Some(on_error)}>
+///       <BuggyWidget />
+///     </ErrorBoundary>
+///   }
+/// }
+/// ```
+pub fn ErrorBoundary(props: ErrorBoundaryProps) {
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(RenderCommand::Layout),
+        ..props.styles.clone().unwrap_or_default()
+    });
+
+    let (error, set_error, ..) = use_state!(None::<String>);
+
+    let error = error.or_else(|| {
+        let children = children.clone();
+        let message = context
+            .catch_panic(move |context| {
+                rsx! {
+                    <>
+                        {children}
+                    </>
+                }
+            })
+            .err()?;
+
+        if let Some(on_error) = &props.on_error {
+            on_error.call(message.clone());
+        }
+        set_error(Some(message.clone()));
+        Some(message)
+    });
+
+    if let Some(error) = error {
+        let children = props.fallback.clone();
+        if children.is_some() {
+            rsx! {
+                <>
+                    {children}
+                </>
+            }
+        } else {
+            rsx! {
+                <Text content={error} size={14.0} />
+            }
+        }
+    }
+}
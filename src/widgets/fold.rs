@@ -7,7 +7,7 @@ use crate::core::{
     use_state, widget, Children, EventType, Handler, OnEvent, WidgetProps,
 };
 
-use crate::widgets::{Background, Clip, If, Text};
+use crate::widgets::{Background, Clip, Collapse, Text};
 
 // TODO: Add `disabled` prop
 
@@ -44,6 +44,8 @@ pub struct FoldProps {
 #[widget]
 /// A widget container that toggles its content between visible and hidden when clicked
 ///
+/// The content slides open and closed (see [`Collapse`]).
+///
 /// # Props
 ///
 /// __Type:__ [`FoldProps`]
@@ -130,11 +132,11 @@ pub fn Fold(props: FoldProps) {
         <Background styles={Some(background_styles)}>
             <Clip styles={Some(container_style)}>
                 <Text content={label} on_event={Some(handler)} size={14.0} />
-                <If condition={is_open}>
+                <Collapse open={is_open}>
                     <Clip styles={Some(inner_container_styles)}>
                         {children}
                     </Clip>
-                </If>
+                </Collapse>
             </Clip>
         </Background>
     }
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::core::{widget, DynamicValue, KayakContextRef, Widget, WidgetProps};
+
+/// Renders a widget with a key, once it's been built from an item by a [`ForEach`]
+pub(crate) type ItemBuilder = Arc<dyn Fn(&str, &mut KayakContextRef) + Send + Sync>;
+
+/// The items rendered by a [`ForEach`], each with the widget built for it
+///
+/// The widgets are built right away, when the items are created, and each widget is identified by
+/// the key extracted from its item.
+#[derive(Clone, Default)]
+pub struct ForEachItems {
+    items: Vec<(String, ItemBuilder)>,
+    /// The widget built for each item, used to tell whether the items changed
+    widgets: Vec<DynamicValue>,
+}
+
+impl ForEachItems {
+    /// Builds a widget for each of the given items
+    ///
+    /// # Arguments
+    ///
+    /// * `items`: The items to render, in order
+    /// * `key`: Extracts the key identifying an item, which should be unique within the list
+    /// * `render`: Builds the widget for an item
+    ///
+    pub fn new<T, I, K, R, W, S>(items: I, key: K, render: R) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        K: Fn(&T) -> S,
+        R: Fn(&T) -> W,
+        W: Widget + 'static,
+        S: Into<String>,
+    {
+        let (items, widgets) = items
+            .into_iter()
+            .map(|item| {
+                let widget = render(&item);
+                let value = DynamicValue::new(widget.clone());
+                let builder: ItemBuilder = Arc::new(move |key, context| {
+                    context.add_keyed_widget(key, widget.clone());
+                });
+                ((key(&item).into(), builder), value)
+            })
+            .unzip();
+        Self { items, widgets }
+    }
+
+    /// The keys of the items, in order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// The number of items
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if there are no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the builder of each item along with a key that's unique within the list
+    ///
+    /// Items with a duplicate key are told apart by how many items with the same key come before
+    /// them.
+    pub(crate) fn unique_keys(&self) -> Vec<(String, ItemBuilder)> {
+        let mut occurrences = HashMap::<&str, usize>::new();
+        self.items
+            .iter()
+            .map(|(key, builder)| {
+                let occurrence = occurrences.entry(key.as_str()).or_default();
+                let unique_key = if *occurrence == 0 {
+                    key.clone()
+                } else {
+                    format!("{}\u{0}{}", key, occurrence)
+                };
+                *occurrence += 1;
+                (unique_key, builder.clone())
+            })
+            .collect()
+    }
+}
+
+impl PartialEq for ForEachItems {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys().eq(other.keys()) && self.widgets == other.widgets
+    }
+}
+
+impl Debug for ForEachItems {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.keys()).finish()
+    }
+}
+
+/// Props used by the [`ForEach`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ForEachProps {
+    /// The items to render, created with [`ForEachItems::new`]
+    pub items: ForEachItems,
+}
+
+#[widget]
+/// A widget that renders a widget for each item of a list
+///
+/// Unlike widgets rendered in a loop within `rsx!`, which are matched up with the previous render
+/// by their position, each widget is identified by the key of its item. When the items are
+/// reordered, the widgets are moved along with them and keep their state (such as the text in a
+/// `TextBox`). Widgets of removed items are unmounted, and widgets of new items are mounted,
+/// without affecting the others.
+///
+/// Keys should be unique within the list. Items with a duplicate key are told apart by how many
+/// items with the same key come before them.
+///
+/// Like a [`Fragment`](crate::core::Fragment), this doesn't add a node to the layout.
+///
+/// # Props
+///
+/// __Type:__ [`ForEachProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ❌        |
+/// | `on_event`  | ❌        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{constructor, rsx, widget};
+/// # use kayak_ui::widgets::{ForEach, ForEachItems, TextBox};
+/// # #[widget]
+/// # fn Example() {
+/// let todos = vec![(1, "Buy milk"), (2, "Walk the dog")];
+///
+/// let items = ForEachItems::new(
+///     todos,
+///     |(id, _)| format!("todo-{}", id),
+///     |(_, label)| constructor! {
+///         <TextBox value={label.to_string()} />
+///     },
+/// );
+///
+/// rsx! {
+///     <ForEach items={items} />
+/// }
+/// # }
+/// ```
+pub fn ForEach(props: ForEachProps) {
+    for (key, builder) in props.items.unique_keys() {
+        builder(&key, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ForEachItems;
+    use crate::core::constructor;
+    use crate::widgets::Text;
+
+    /// Builds items that all share a key, so that they only differ by their widgets
+    fn items(labels: &[&str]) -> ForEachItems {
+        ForEachItems::new(
+            labels.iter().map(|label| label.to_string()),
+            |_| "item",
+            |label| {
+                constructor! {
+                    <Text content={label.clone()} size={16.0} />
+                }
+            },
+        )
+    }
+
+    #[test]
+    fn should_equal_items_with_equal_widgets() {
+        assert_eq!(items(&["a", "b"]), items(&["a", "b"]));
+    }
+
+    #[test]
+    fn should_not_equal_changed_items() {
+        assert_ne!(items(&["a", "b"]), items(&["b", "a"]));
+        assert_ne!(items(&["a", "b"]), items(&["a"]));
+    }
+}
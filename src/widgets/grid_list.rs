@@ -0,0 +1,471 @@
+use std::ops::Range;
+
+use crate::core::{
+    constructor,
+    render_command::RenderCommand,
+    styles::{Edge, PositionType, Style, Units},
+    widget, Bound, Children, Color, CursorIcon, EventType, Handler, KeyCode, MutableBound, OnEvent,
+    OnLayout, PointerEvents, WidgetProps,
+};
+
+use crate::widgets::{Background, ForEachItems, ScrollContext};
+
+/// The width of each cell of a [`GridList`] if not set by its props, in pixels
+const DEFAULT_CELL_WIDTH: f32 = 100.0;
+/// The space between the cells of a [`GridList`] if not set by its props, in pixels
+const DEFAULT_GAP: f32 = 8.0;
+/// The number of rows rendered beyond each edge of a scroll box, so fast scrolling doesn't reveal
+/// missing rows before the next render
+const OVERSCAN_ROWS: usize = 1;
+/// The width of the ring around the highlighted cell, in pixels
+const HIGHLIGHT_WIDTH: f32 = 2.0;
+
+/// The key of the ring drawn around the highlighted cell, which can't collide with an item's key
+const HIGHLIGHT_KEY: &str = "\u{0}highlight";
+
+/// Props used by the [`GridList`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct GridListProps {
+    /// The items to display, created with [`ForEachItems::new`]
+    pub items: ForEachItems,
+    /// The number of columns
+    ///
+    /// If `None`, the grid fits as many columns of [`cell_width`](Self::cell_width) as it can
+    /// within its width, so the number of columns follows the size of the grid.
+    pub columns: Option<usize>,
+    /// The width of each cell in pixels
+    ///
+    /// If [`columns`](Self::columns) is set, cells are instead stretched to fill the width of the
+    /// grid. If `None`, each cell is 100 pixels wide.
+    pub cell_width: Option<f32>,
+    /// The height of each cell in pixels
+    ///
+    /// If `None`, cells are square.
+    pub cell_height: Option<f32>,
+    /// The space between columns in pixels
+    ///
+    /// If `None`, columns are 8 pixels apart.
+    pub column_gap: Option<f32>,
+    /// The space between rows in pixels
+    ///
+    /// If `None`, rows are 8 pixels apart.
+    pub row_gap: Option<f32>,
+    /// A callback for when an item is clicked, or when Enter is pressed while it's highlighted
+    ///
+    /// The handler is given the index of the item.
+    pub on_item_activate: Option<Handler<usize>>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(Focusable)]
+    pub focusable: Option<bool>,
+}
+
+/// The size and position of the cells of a [`GridList`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GridGeometry {
+    columns: usize,
+    cell_width: f32,
+    cell_height: f32,
+    column_gap: f32,
+    row_gap: f32,
+}
+
+impl GridGeometry {
+    /// Lays out the cells of a grid with the given props and width
+    fn new(props: &GridListProps, width: f32) -> Self {
+        let column_gap = props.column_gap.unwrap_or(DEFAULT_GAP).max(0.0);
+        let row_gap = props.row_gap.unwrap_or(DEFAULT_GAP).max(0.0);
+        let (columns, cell_width) = match props.columns {
+            Some(columns) => {
+                let columns = columns.max(1);
+                let gaps = column_gap * (columns - 1) as f32;
+                (columns, ((width - gaps) / columns as f32).max(0.0))
+            }
+            None => {
+                let cell_width = props.cell_width.unwrap_or(DEFAULT_CELL_WIDTH).max(1.0);
+                let columns = ((width + column_gap) / (cell_width + column_gap)).floor();
+                ((columns as usize).max(1), cell_width)
+            }
+        };
+
+        Self {
+            columns,
+            cell_width,
+            cell_height: props.cell_height.unwrap_or(cell_width).max(0.0),
+            column_gap,
+            row_gap,
+        }
+    }
+
+    /// The number of rows needed for the given number of items
+    fn rows(&self, count: usize) -> usize {
+        (count + self.columns - 1) / self.columns
+    }
+
+    /// The total height of the grid, in pixels
+    fn height(&self, count: usize) -> f32 {
+        let rows = self.rows(count);
+        rows as f32 * self.cell_height + rows.saturating_sub(1) as f32 * self.row_gap
+    }
+
+    /// The position of the cell at the given index, relative to the top-left corner of the grid
+    fn position(&self, index: usize) -> (f32, f32) {
+        let (row, column) = (index / self.columns, index % self.columns);
+        (
+            column as f32 * (self.cell_width + self.column_gap),
+            row as f32 * (self.cell_height + self.row_gap),
+        )
+    }
+
+    /// The rows that overlap the given vertical span (relative to the top of the grid), along with
+    /// the overscan on either side
+    fn visible_rows(&self, top: f32, bottom: f32, count: usize) -> Range<usize> {
+        let pitch = (self.cell_height + self.row_gap).max(1.0);
+        let rows = self.rows(count);
+        let first = (top / pitch).floor().max(0.0) as usize;
+        let last = (bottom / pitch).ceil().max(0.0) as usize;
+        first.saturating_sub(OVERSCAN_ROWS).min(rows)..(last + OVERSCAN_ROWS).min(rows)
+    }
+}
+
+/// Returns the index highlighted by pressing the given key, or `None` if it doesn't navigate
+fn navigate(key: KeyCode, current: Option<usize>, columns: usize, count: usize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+
+    let last = count - 1;
+    let index = match (key, current) {
+        (KeyCode::Home, _) => 0,
+        (KeyCode::End, _) => last,
+        (KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down, None) => 0,
+        (KeyCode::Left, Some(current)) => current.saturating_sub(1),
+        (KeyCode::Right, Some(current)) => (current + 1).min(last),
+        (KeyCode::Up, Some(current)) => current.checked_sub(columns).unwrap_or(current),
+        (KeyCode::Down, Some(current)) if current + columns <= last => current + columns,
+        (KeyCode::Down, Some(current)) => current,
+        _ => return None,
+    };
+    Some(index.min(last))
+}
+
+#[widget]
+/// A widget that lays out items in a grid of uniformly sized cells, such as an image gallery
+///
+/// Items are placed row by row, wrapping onto a new row once the current one is full. The number
+/// of columns is either fixed, or derived from the width of the grid and the width of each cell,
+/// so that the grid reflows as it's resized. The grid is as tall as its rows.
+///
+/// When used within a [`ScrollBox`](crate::widgets::ScrollBox), only the rows that are scrolled
+/// into view are rendered, so that large collections stay fast.
+///
+/// Clicking an item calls [`on_item_activate`](GridListProps::on_item_activate). While the grid is
+/// focused, the arrow keys (and Home/End) move a highlight between the items, scrolling it into
+/// view, and Enter activates the highlighted item.
+///
+/// # Props
+///
+/// __Type:__ [`GridListProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ✅        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{constructor, rsx, Handler, widget};
+/// # use kayak_ui::widgets::{ForEachItems, GridList, Image};
+/// # #[widget]
+/// # fn Example() {
+/// let photos = vec![(1, 10), (2, 11), (3, 12)];
+/// let items = ForEachItems::new(
+///     photos,
+///     |(id, _)| id.to_string(),
+///     |(_, handle)| constructor! {
+///         <Image handle={*handle} />
+///     },
+/// );
+/// let on_item_activate = Handler::new(|index| println!("Opened photo {}", index));
+///
+/// rsx! {
+///     <GridList
+///         items={items}
+///         cell_width={Some(120.0)}
+///         on_item_activate={Some(on_item_activate)}
+///     />
+/// }
+/// # }
+/// ```
+pub fn GridList(props: GridListProps) {
+    let items = props.items.unique_keys();
+    let count = items.len();
+    let on_item_activate = props.on_item_activate.clone();
+
+    // === Layout === //
+    let layout = context.use_layout();
+    let width = layout.map_or(0.0, |layout| layout.width);
+    let grid_top = layout.map_or(0.0, |layout| layout.y);
+    let geometry = GridGeometry::new(&props, width);
+    let highlight_color = context.use_theme().color.primary;
+
+    // === States === //
+    let highlighted = context.create_state::<Option<usize>>(None).unwrap();
+    let has_focus = context.create_state(false).unwrap();
+    let current = highlighted.get().filter(|index| *index < count);
+    let previous = context.use_previous(current).flatten();
+
+    // === Virtualization === //
+    let rows = match context.create_consumer::<ScrollContext>() {
+        Some(scroll_ctx) => {
+            context.bind(&scroll_ctx);
+            let mut scroll = scroll_ctx.get();
+            let visible = scroll.visible_rect();
+
+            // Scroll a newly highlighted item into view
+            if let Some(index) = current.filter(|index| Some(*index) != previous) {
+                let top = grid_top + geometry.position(index).1;
+                let bottom = top + geometry.cell_height;
+                let before = scroll.scroll_y();
+                if top < visible.posy {
+                    scroll.set_scroll_y(before + visible.posy - top);
+                } else if bottom > visible.posy + visible.height {
+                    scroll.set_scroll_y(before - (bottom - visible.posy - visible.height));
+                }
+                if scroll.scroll_y() != before {
+                    scroll_ctx.set(scroll);
+                }
+            }
+
+            geometry.visible_rows(
+                visible.posy - grid_top,
+                visible.posy + visible.height - grid_top,
+                count,
+            )
+        }
+        None => 0..geometry.rows(count),
+    };
+
+    // === Events === //
+    let cloned_highlighted = highlighted.clone();
+    let cloned_has_focus = has_focus.clone();
+    let cloned_on_activate = on_item_activate.clone();
+    let columns = geometry.columns;
+    let grid_id = context.current_id().unwrap_or_default();
+    let on_event = props.on_event.take();
+    props.on_event = Some(OnEvent::wrap(
+        grid_id,
+        on_event,
+        move |_, event| match event.event_type {
+            EventType::KeyDown(key_event) => {
+                let current = cloned_highlighted.get().filter(|index| *index < count);
+                if key_event.key() == KeyCode::Return {
+                    if let (Some(index), Some(on_activate)) = (current, &cloned_on_activate) {
+                        on_activate.call(index);
+                        event.stop_propagation();
+                    }
+                } else if let Some(index) = navigate(key_event.key(), current, columns, count) {
+                    cloned_highlighted.set(Some(index));
+                    event.stop_propagation();
+                }
+            }
+            EventType::Focus => cloned_has_focus.set(true),
+            EventType::Blur => cloned_has_focus.set(false),
+            _ => {}
+        },
+    ));
+    // The focusability from `props` has already been registered by now, so this is set on the
+    // widget itself instead
+    context.set_focusable(props.focusable.is_none().then_some(true));
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Layout.into(),
+                ..Default::default()
+            })
+            .with_style(&props.styles)
+            .with_style(Style {
+                width: Units::Stretch(1.0).into(),
+                height: Units::Pixels(geometry.height(count)).into(),
+                ..Default::default()
+            }),
+    );
+
+    let cell_styles = |index: usize| {
+        let (left, top) = geometry.position(index);
+        Style {
+            background_color: Color::TRANSPARENT.into(),
+            cursor: CursorIcon::Hand.into(),
+            position_type: PositionType::SelfDirected.into(),
+            left: Units::Pixels(left).into(),
+            top: Units::Pixels(top).into(),
+            width: Units::Pixels(geometry.cell_width).into(),
+            height: Units::Pixels(geometry.cell_height).into(),
+            ..Default::default()
+        }
+    };
+
+    let first = rows.start * columns;
+    let last = (rows.end * columns).min(count);
+    for (index, (key, builder)) in items.into_iter().enumerate().take(last).skip(first) {
+        let item_key = key.clone();
+        let children = Some(Children::new(move |_, context| {
+            builder(&item_key, context);
+            context.commit();
+        }));
+
+        let highlighted = highlighted.clone();
+        let on_item_activate = on_item_activate.clone();
+        let on_event = Some(OnEvent::new(move |_, event| {
+            if let EventType::Click(..) = event.event_type {
+                highlighted.set(Some(index));
+                if let Some(ref on_item_activate) = on_item_activate {
+                    on_item_activate.call(index);
+                }
+            }
+        }));
+
+        context.add_keyed_widget(
+            &key,
+            constructor! {
+                <Background styles={Some(cell_styles(index))} on_event={on_event}>
+                    {children}
+                </Background>
+            },
+        );
+    }
+
+    // Drawn after the items, so it's on top of them
+    if let Some(index) = current.filter(|index| has_focus.get() && (first..last).contains(index)) {
+        let highlight_styles = Style {
+            border: Edge::all(HIGHLIGHT_WIDTH).into(),
+            border_color: highlight_color.into(),
+            cursor: CursorIcon::Hand.into(),
+            pointer_events: PointerEvents::None.into(),
+            ..cell_styles(index)
+        };
+        context.add_keyed_widget(
+            HIGHLIGHT_KEY,
+            constructor! {
+                <Background styles={Some(highlight_styles)} />
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::KeyCode;
+
+    use super::{navigate, GridGeometry, GridListProps};
+
+    fn geometry(props: GridListProps, width: f32) -> GridGeometry {
+        GridGeometry::new(&props, width)
+    }
+
+    #[test]
+    fn should_fit_columns_of_the_cell_width() {
+        let props = GridListProps {
+            cell_width: Some(100.0),
+            column_gap: Some(10.0),
+            ..Default::default()
+        };
+        // Three cells and two gaps take up 320 pixels
+        assert_eq!(3, geometry(props.clone(), 320.0).columns);
+        assert_eq!(2, geometry(props.clone(), 319.0).columns);
+        // There's always at least one column
+        assert_eq!(1, geometry(props, 0.0).columns);
+    }
+
+    #[test]
+    fn should_stretch_cells_to_fixed_columns() {
+        let props = GridListProps {
+            columns: Some(4),
+            column_gap: Some(10.0),
+            cell_height: Some(30.0),
+            ..Default::default()
+        };
+        let geometry = geometry(props, 430.0);
+        assert_eq!(4, geometry.columns);
+        assert_eq!(100.0, geometry.cell_width);
+        assert_eq!(30.0, geometry.cell_height);
+    }
+
+    #[test]
+    fn should_use_square_cells_by_default() {
+        let geometry = geometry(GridListProps::default(), 500.0);
+        assert_eq!(geometry.cell_width, geometry.cell_height);
+    }
+
+    #[test]
+    fn should_position_cells_row_by_row() {
+        let props = GridListProps {
+            columns: Some(3),
+            column_gap: Some(10.0),
+            row_gap: Some(5.0),
+            cell_height: Some(20.0),
+            ..Default::default()
+        };
+        let geometry = geometry(props, 320.0);
+        assert_eq!((0.0, 0.0), geometry.position(0));
+        assert_eq!((220.0, 0.0), geometry.position(2));
+        assert_eq!((110.0, 25.0), geometry.position(4));
+
+        assert_eq!(0, geometry.rows(0));
+        assert_eq!(2, geometry.rows(4));
+        assert_eq!(0.0, geometry.height(0));
+        assert_eq!(45.0, geometry.height(4));
+    }
+
+    #[test]
+    fn should_find_visible_rows_with_overscan() {
+        let props = GridListProps {
+            columns: Some(2),
+            row_gap: Some(0.0),
+            cell_height: Some(10.0),
+            ..Default::default()
+        };
+        let geometry = geometry(props, 100.0);
+        // 20 items make 10 rows
+        assert_eq!(0..4, geometry.visible_rows(0.0, 25.0, 20));
+        assert_eq!(3..8, geometry.visible_rows(40.0, 65.0, 20));
+        assert_eq!(8..10, geometry.visible_rows(95.0, 200.0, 20));
+    }
+
+    #[test]
+    fn should_navigate_with_arrow_keys() {
+        // Three columns, with 8 items
+        assert_eq!(Some(0), navigate(KeyCode::Right, None, 3, 8));
+        assert_eq!(Some(2), navigate(KeyCode::Right, Some(1), 3, 8));
+        assert_eq!(Some(0), navigate(KeyCode::Left, Some(0), 3, 8));
+        assert_eq!(Some(7), navigate(KeyCode::Right, Some(7), 3, 8));
+        assert_eq!(Some(4), navigate(KeyCode::Down, Some(1), 3, 8));
+        assert_eq!(Some(1), navigate(KeyCode::Up, Some(4), 3, 8));
+        assert_eq!(Some(1), navigate(KeyCode::Up, Some(1), 3, 8));
+    }
+
+    #[test]
+    fn should_not_navigate_past_the_last_row() {
+        // The last row only has items 6 and 7
+        assert_eq!(Some(7), navigate(KeyCode::Down, Some(4), 3, 8));
+        assert_eq!(Some(5), navigate(KeyCode::Down, Some(5), 3, 8));
+    }
+
+    #[test]
+    fn should_navigate_to_either_end() {
+        assert_eq!(Some(0), navigate(KeyCode::Home, Some(5), 3, 8));
+        assert_eq!(Some(7), navigate(KeyCode::End, None, 3, 8));
+        assert_eq!(None, navigate(KeyCode::End, None, 3, 0));
+        assert_eq!(None, navigate(KeyCode::A, Some(2), 3, 8));
+    }
+}
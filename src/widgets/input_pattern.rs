@@ -0,0 +1,189 @@
+use std::sync::{Arc, RwLock};
+
+/// A callback that checks each edit made to a [`TextBox`](crate::widgets::TextBox)
+///
+/// The callback is given the candidate value (the value as it would be after the edit). It returns
+/// the value to accept, which may be transformed (such as upper-cased), or `None` to reject the
+/// edit and keep the previous value.
+#[derive(Clone)]
+pub struct TextValidator(
+    pub Arc<RwLock<dyn FnMut(&str) -> Option<String> + Send + Sync + 'static>>,
+);
+
+impl TextValidator {
+    pub fn new<F: FnMut(&str) -> Option<String> + Send + Sync + 'static>(f: F) -> TextValidator {
+        TextValidator(Arc::new(RwLock::new(f)))
+    }
+
+    /// Validate the given candidate value
+    ///
+    /// Returns `None` if the value was rejected (or the validator couldn't be called).
+    pub fn validate(&self, candidate: &str) -> Option<String> {
+        match self.0.write() {
+            Ok(mut validate) => validate(candidate),
+            Err(_) => None,
+        }
+    }
+}
+
+impl PartialEq for TextValidator {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for TextValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TextValidator").finish()
+    }
+}
+
+/// A common pattern that the value of a [`TextBox`](crate::widgets::TextBox) must follow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputPattern {
+    /// Only the digits `0` to `9`
+    Digits,
+    /// A whole number, optionally starting with a `-`
+    Integer,
+    /// A number with an optional fractional part (such as `-12.5`)
+    Decimal,
+    /// A fixed format, such as `(###) ###-####` for a phone number
+    ///
+    /// Each `#` accepts a digit, each `A` accepts a letter and each `*` accepts either. Any other
+    /// character is a literal, which is inserted automatically as the user types. Input longer than
+    /// the mask is rejected.
+    Mask(String),
+}
+
+impl InputPattern {
+    /// Apply this pattern to the given candidate value
+    ///
+    /// Returns the formatted value, or `None` if the value doesn't follow the pattern.
+    pub fn apply(&self, candidate: &str) -> Option<String> {
+        match self {
+            InputPattern::Digits => candidate
+                .chars()
+                .all(|c| c.is_ascii_digit())
+                .then(|| candidate.to_string()),
+            InputPattern::Integer => {
+                let digits = candidate.strip_prefix('-').unwrap_or(candidate);
+                digits
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+                    .then(|| candidate.to_string())
+            }
+            InputPattern::Decimal => {
+                let number = candidate.strip_prefix('-').unwrap_or(candidate);
+                let mut parts = number.splitn(2, '.');
+                let is_valid = parts.all(|part| part.chars().all(|c| c.is_ascii_digit()));
+                is_valid.then(|| candidate.to_string())
+            }
+            InputPattern::Mask(mask) => InputPattern::apply_mask(mask, candidate),
+        }
+    }
+
+    fn apply_mask(mask: &str, candidate: &str) -> Option<String> {
+        let mut formatted = String::new();
+        let mut input = candidate.chars().peekable();
+
+        for slot in mask.chars() {
+            let next = match input.peek() {
+                Some(next) => *next,
+                None => break,
+            };
+
+            let accepts: Option<fn(&char) -> bool> = match slot {
+                '#' => Some(char::is_ascii_digit),
+                'A' => Some(char::is_ascii_alphabetic),
+                '*' => Some(char::is_ascii_alphanumeric),
+                _ => None,
+            };
+
+            match accepts {
+                Some(accepts) => {
+                    if !accepts(&next) {
+                        return None;
+                    }
+                    formatted.push(next);
+                    input.next();
+                }
+                None => {
+                    // Literals are inserted if the user didn't type them
+                    formatted.push(slot);
+                    if next == slot {
+                        input.next();
+                    }
+                }
+            }
+        }
+
+        if input.next().is_some() {
+            None
+        } else {
+            Some(formatted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InputPattern, TextValidator};
+
+    #[test]
+    fn should_accept_numbers() {
+        assert_eq!(Some("042".to_string()), InputPattern::Digits.apply("042"));
+        assert_eq!(Some(String::new()), InputPattern::Digits.apply(""));
+        assert_eq!(None, InputPattern::Digits.apply("-42"));
+        assert_eq!(None, InputPattern::Digits.apply("4a"));
+
+        assert_eq!(Some("-42".to_string()), InputPattern::Integer.apply("-42"));
+        // A lone sign is accepted, so that negative numbers can be typed
+        assert_eq!(Some("-".to_string()), InputPattern::Integer.apply("-"));
+        assert_eq!(None, InputPattern::Integer.apply("4-2"));
+        assert_eq!(None, InputPattern::Integer.apply("4.2"));
+
+        assert_eq!(
+            Some("-12.5".to_string()),
+            InputPattern::Decimal.apply("-12.5")
+        );
+        assert_eq!(Some("12.".to_string()), InputPattern::Decimal.apply("12."));
+        assert_eq!(Some(".5".to_string()), InputPattern::Decimal.apply(".5"));
+        assert_eq!(None, InputPattern::Decimal.apply("1.2.3"));
+        assert_eq!(None, InputPattern::Decimal.apply("1,5"));
+    }
+
+    #[test]
+    fn should_insert_mask_literals() {
+        let phone = InputPattern::Mask("(###) ###-####".to_string());
+        assert_eq!(Some(String::new()), phone.apply(""));
+        assert_eq!(Some("(5".to_string()), phone.apply("5"));
+        assert_eq!(Some("(555) 1".to_string()), phone.apply("5551"));
+        // Literals typed by the user aren't doubled
+        assert_eq!(Some("(555) 1".to_string()), phone.apply("(555) 1"));
+        assert_eq!(
+            Some("(555) 123-4567".to_string()),
+            phone.apply("5551234567")
+        );
+    }
+
+    #[test]
+    fn should_reject_input_not_following_the_mask() {
+        let phone = InputPattern::Mask("(###) ###-####".to_string());
+        assert_eq!(None, phone.apply("55a"));
+        assert_eq!(None, phone.apply("55512345678"));
+
+        let code = InputPattern::Mask("AA-**".to_string());
+        assert_eq!(Some("ab-1c".to_string()), code.apply("ab1c"));
+        assert_eq!(None, code.apply("a1"));
+        assert_eq!(None, code.apply("ab-1-"));
+    }
+
+    #[test]
+    fn should_validate_with_the_callback() {
+        let validator = TextValidator::new(|candidate| {
+            (candidate.len() <= 3).then(|| candidate.to_uppercase())
+        });
+        assert_eq!(Some("ABC".to_string()), validator.validate("abc"));
+        assert_eq!(None, validator.validate("abcd"));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::core::{rsx, widget, Bound, Children, MutableBound, WidgetProps};
+
+/// Props used by the [`KeyedFragment`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct KeyedFragmentProps {
+    /// Identifies the content currently rendered by this fragment
+    ///
+    /// Changing the key discards the previous children, along with all of their state, and builds
+    /// the new children from scratch.
+    pub key: String,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+}
+
+/// The key last rendered by a [`KeyedFragment`], along with the slot it was rendered into
+#[derive(Default, Debug, Clone, PartialEq)]
+struct KeyedSlot {
+    key: String,
+    odd: bool,
+}
+
+impl KeyedSlot {
+    /// Returns the slot to render the given key into
+    ///
+    /// The same key stays in its slot, while a different key moves to the other one.
+    fn next(&self, key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            odd: self.odd != (self.key != key),
+        }
+    }
+}
+
+#[widget]
+/// A widget that resets its children whenever its key changes
+///
+/// Widgets are normally matched up with the previous render by their position. When one widget
+/// tree is swapped out for another in the same place, the new widgets reuse the old widgets' state
+/// (such as the text in a `TextBox` or the open state of a `Fold`). Wrapping the contents in a
+/// `KeyedFragment` makes the difference explicit:
+///
+/// * __Same key:__ The children are re-rendered in place and keep their state, even if the props
+///   passed to them changed.
+/// * __Different key:__ The previous children are removed and the new ones are constructed from
+///   scratch, so no state carries over. Switching back to an earlier key doesn't restore the state
+///   it had before either.
+///
+/// Like a [`Fragment`](crate::core::Fragment), this doesn't add a node to the layout.
+///
+/// # Props
+///
+/// __Type:__ [`KeyedFragmentProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ❌        |
+/// | `on_event`  | ❌        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, widget, WidgetProps};
+/// # use kayak_ui::widgets::{Fold, KeyedFragment, Text};
+///
+/// #[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+/// struct UserDetailsProps {
+///     user_id: u32,
+/// }
+///
+/// #[widget]
+/// fn UserDetails(props: UserDetailsProps) {
+///     let user_id = props.user_id;
+///
+///     // Each user gets a fold in its initial state, rather than the one left open for the last
+///     // user
+///     rsx! {
+///         <KeyedFragment key={format!("user-{}", user_id)}>
+///             <Fold label={"Details".to_string()} default_open={false}>
+///                 <Text content={format!("User {}", user_id)} size={16.0} />
+///             </Fold>
+///         </KeyedFragment>
+///     }
+/// }
+/// ```
+pub fn KeyedFragment(props: KeyedFragmentProps) {
+    let slot = context
+        .create_state(KeyedSlot {
+            key: props.key.clone(),
+            odd: false,
+        })
+        .unwrap();
+
+    // Alternate between two slots, so that the previous children are never reused
+    let last = slot.get();
+    let current = last.next(&props.key);
+    let odd = current.odd;
+    if current != last {
+        context.after_render(move |_| slot.set(current));
+    }
+
+    rsx! {
+        <>
+            {if odd { {children} } else { {children} }}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyedSlot;
+
+    #[test]
+    fn should_keep_the_slot_of_the_same_key() {
+        let slot = KeyedSlot::default().next("a");
+        assert_eq!(slot.odd, slot.next("a").odd);
+    }
+
+    #[test]
+    fn should_alternate_slots_between_keys() {
+        let a = KeyedSlot {
+            key: String::from("a"),
+            odd: false,
+        };
+        let b = a.next("b");
+        assert!(b.odd);
+        assert_eq!("b", b.key);
+        // Switching back to an earlier key still moves to the other slot, resetting it
+        let a = b.next("a");
+        assert!(!a.odd);
+        assert!(a.next("c").odd);
+    }
+}
@@ -0,0 +1,319 @@
+use kayak_core::layout_cache::Rect;
+
+use crate::core::{
+    render_command::RenderCommand,
+    rsx,
+    styles::{Edge, PositionType, Style, StyleProp, Transform, Units},
+    use_state, widget, Bound, Color, EventType, MutableBound, OnEvent, OnLayout, PointerEvents,
+    WidgetProps,
+};
+
+use crate::widgets::{Element, ViewportController};
+
+/// Props used by the [`Minimap`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct MinimapProps {
+    /// The controller of the [`Viewport`](crate::widgets::Viewport) to show an overview of
+    pub controller: ViewportController,
+    /// The styles of the rectangle marking the part of the content that's visible in the viewport
+    ///
+    /// These are applied over the default styles, which draw a translucent white rectangle with a
+    /// white border.
+    pub view_styles: Option<Style>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+}
+
+/// The geometry of a [`Minimap`], as of the previous render
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MinimapView {
+    /// Maps points of the content (relative to the viewport) to points within the minimap
+    fit: Transform,
+    /// The layout of the minimap
+    minimap: Rect,
+    /// The size of the viewport
+    viewport_size: (f32, f32),
+}
+
+impl MinimapView {
+    /// Returns the pan that centers the viewport on the content under the given point
+    fn recenter(&self, position: (f32, f32), zoom: f32) -> (f32, f32) {
+        let local = (
+            position.0 - self.minimap.posx,
+            position.1 - self.minimap.posy,
+        );
+        let point = self.fit.invert(local, (0.0, 0.0));
+        (
+            self.viewport_size.0 / 2.0 - point.0 * zoom,
+            self.viewport_size.1 / 2.0 - point.1 * zoom,
+        )
+    }
+
+    /// Returns the part of the content visible in the viewport, relative to the minimap
+    ///
+    /// The rect is limited to the bounds of the minimap.
+    fn visible_rect(&self, pan: (f32, f32), zoom: f32) -> Rect {
+        let min = self.fit.apply((-pan.0 / zoom, -pan.1 / zoom), (0.0, 0.0));
+        let max = self.fit.apply(
+            (
+                (self.viewport_size.0 - pan.0) / zoom,
+                (self.viewport_size.1 - pan.1) / zoom,
+            ),
+            (0.0, 0.0),
+        );
+        let posx = min.0.clamp(0.0, self.minimap.width);
+        let posy = min.1.clamp(0.0, self.minimap.height);
+        Rect {
+            posx,
+            posy,
+            width: (max.0.min(self.minimap.width) - posx).max(0.0),
+            height: (max.1.min(self.minimap.height) - posy).max(0.0),
+            z_index: 0.0,
+        }
+    }
+}
+
+#[widget]
+/// A read-only, scaled-down overview of the content of a [`Viewport`](crate::widgets::Viewport)
+///
+/// The minimap draws the viewport's content again, scaled down to fit within the minimap, along
+/// with a rectangle marking the part that's currently visible. It reuses the content's retained
+/// draw list rather than laying anything out again, and nothing within it can be interacted
+/// with. Pressing (or dragging) on the minimap centers the viewport on that point.
+///
+/// # Props
+///
+/// __Type:__ [`MinimapProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, styles::{Style, Units}, widget, Bound};
+/// # use kayak_ui::widgets::{Minimap, Viewport, ViewportController};
+///
+/// #[widget]
+/// fn NodeEditor() {
+///     let controller = context
+///         .create_state(ViewportController::new())
+///         .unwrap()
+///         .get();
+///     let minimap_controller = controller.clone();
+///     let minimap_styles = Style {
+///         width: Units::Pixels(200.0).into(),
+///         height: Units::Pixels(150.0).into(),
+///         ..Default::default()
+///     };
+///
+///     rsx! {
+///         <>
+///             <Viewport controller={Some(controller)}>
+///                 // ...
+///             </Viewport>
+///             <Minimap controller={minimap_controller} styles={Some(minimap_styles)} />
+///         </>
+///     }
+/// }
+/// ```
+pub fn Minimap(props: MinimapProps) {
+    let controller = props.controller.clone();
+    context.bind(&controller.pan);
+    context.bind(&controller.zoom);
+    context.bind(&controller.viewport);
+    let pan = controller.pan.get();
+    let zoom = controller.zoom.get().max(f32::EPSILON);
+
+    // === Overview === //
+    let viewport_id = controller.viewport.get();
+    let layer = viewport_id.and_then(|id| context.get_valid_children(id).first().copied());
+    context.set_overview(layer);
+    if viewport_id.is_some() && layer.is_none() {
+        // The viewport hasn't been laid out yet, so try again on the next frame
+        context.request_render();
+    }
+
+    let minimap_id = context.current_id().unwrap_or_default();
+    let view = viewport_id.zip(layer).and_then(|(viewport_id, layer)| {
+        let viewport = *context.get_layout(&viewport_id)?;
+        let layer_layout = *context.get_layout(&layer)?;
+        let minimap = *context.get_layout(&minimap_id)?;
+        let scale = context.get_scale(&layer).max(f32::EPSILON);
+        // The bounds of the content relative to the viewport, unaffected by its pan and zoom
+        let content = context
+            .get_valid_children(layer)
+            .iter()
+            .filter_map(|child| context.get_layout(child).copied())
+            .map(|child| Rect {
+                posx: (child.posx - layer_layout.posx) / scale,
+                posy: (child.posy - layer_layout.posy) / scale,
+                width: child.width / scale,
+                height: child.height / scale,
+                ..child
+            })
+            .reduce(|a, b| a.union(&b))?;
+        Some(MinimapView {
+            fit: Transform::fit(
+                content,
+                Rect {
+                    posx: 0.0,
+                    posy: 0.0,
+                    ..minimap
+                },
+            ),
+            minimap,
+            viewport_size: (viewport.width, viewport.height),
+        })
+    });
+
+    // === Events === //
+    let (is_dragging, set_is_dragging, ..) = use_state!(false);
+    let event_controller = controller.clone();
+    let recenter = move |position: (f32, f32)| {
+        if let Some(view) = view {
+            let zoom = event_controller.zoom.get().max(f32::EPSILON);
+            event_controller.pan.set(view.recenter(position, zoom));
+        }
+    };
+    let on_event = props.on_event.take();
+    props.on_event = Some(OnEvent::wrap(
+        minimap_id,
+        on_event,
+        move |ctx, event| match event.event_type {
+            EventType::MouseDown(data) => {
+                ctx.capture_cursor(event.current_target);
+                set_is_dragging(true);
+                recenter(data.position);
+            }
+            EventType::MouseUp(..) => {
+                ctx.release_cursor(event.current_target);
+                set_is_dragging(false);
+            }
+            EventType::Hover(data) if is_dragging => recenter(data.position),
+            _ => {}
+        },
+    ));
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Quad.into(),
+                background_color: Color::new(0.0781, 0.0898, 0.101, 1.0).into(),
+                ..Default::default()
+            })
+            .with_style(&props.styles),
+    );
+
+    // The part of the content visible in the viewport, within the minimap
+    let visible = view.map(|view| view.visible_rect(pan, zoom));
+    let has_view = visible.is_some();
+    let visible = visible.unwrap_or_default();
+    let view_styles = Style::default()
+        .with_style(Style {
+            background_color: Color::new(1.0, 1.0, 1.0, 0.1).into(),
+            border: Edge::all(1.0).into(),
+            border_color: Color::WHITE.into(),
+            ..Default::default()
+        })
+        .with_style(&props.view_styles)
+        .with_style(Style {
+            render_command: RenderCommand::Quad.into(),
+            pointer_events: PointerEvents::None.into(),
+            position_type: PositionType::SelfDirected.into(),
+            left: Units::Pixels(visible.posx).into(),
+            top: Units::Pixels(visible.posy).into(),
+            width: Units::Pixels(visible.width).into(),
+            height: Units::Pixels(visible.height).into(),
+            cursor: StyleProp::Inherit,
+            ..Default::default()
+        });
+
+    rsx! {
+        <>
+            {if has_view {
+                <Element styles={Some(view_styles)} />
+            }}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_core::layout_cache::Rect;
+
+    use crate::core::styles::Transform;
+
+    use super::MinimapView;
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    /// A 200x100 minimap at (10, 20) of 1000x500 of content, shown in a 400x300 viewport
+    fn view() -> MinimapView {
+        MinimapView {
+            fit: Transform::fit(rect(0.0, 0.0, 1000.0, 500.0), rect(0.0, 0.0, 200.0, 100.0)),
+            minimap: rect(10.0, 20.0, 200.0, 100.0),
+            viewport_size: (400.0, 300.0),
+        }
+    }
+
+    #[test]
+    fn should_recenter_on_the_pressed_point() {
+        let view = view();
+        // The center of the minimap is the center of the content
+        assert_eq!((-300.0, -100.0), view.recenter((110.0, 70.0), 1.0));
+        // Zooming in moves the content further
+        assert_eq!((-800.0, -350.0), view.recenter((110.0, 70.0), 2.0));
+        // The top-left corner of the minimap is the top-left corner of the content
+        assert_eq!((200.0, 150.0), view.recenter((10.0, 20.0), 1.0));
+    }
+
+    #[test]
+    fn should_mark_the_visible_rect() {
+        let view = view();
+        assert_eq!(
+            rect(0.0, 0.0, 80.0, 60.0),
+            view.visible_rect((0.0, 0.0), 1.0)
+        );
+        assert_eq!(
+            rect(20.0, 10.0, 40.0, 30.0),
+            view.visible_rect((-200.0, -100.0), 2.0)
+        );
+        // Recentering shows the content around the pressed point
+        let pan = view.recenter((110.0, 70.0), 1.0);
+        assert_eq!(rect(60.0, 20.0, 80.0, 60.0), view.visible_rect(pan, 1.0));
+    }
+
+    #[test]
+    fn should_limit_the_visible_rect_to_the_minimap() {
+        let view = view();
+        // Panned past the top-left corner of the content
+        assert_eq!(
+            rect(0.0, 0.0, 60.0, 40.0),
+            view.visible_rect((100.0, 100.0), 1.0)
+        );
+        // Panned entirely past the bottom-right corner of the content
+        assert_eq!(
+            rect(200.0, 100.0, 0.0, 0.0),
+            view.visible_rect((-2000.0, -1000.0), 1.0)
+        );
+    }
+}
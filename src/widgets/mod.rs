@@ -1,37 +1,81 @@
+mod animate_presence;
+mod animated_image;
 mod app;
 mod background;
 mod button;
+mod canvas;
 mod clip;
+mod clipped_viewport;
+mod collapse;
+mod color_picker;
+mod disabled;
 mod element;
+mod error_boundary;
 mod fold;
+mod for_each;
+mod grid_list;
 mod if_element;
 mod image;
+mod input_pattern;
 mod inspector;
+mod keyed_fragment;
+mod minimap;
 mod nine_patch;
 mod texture_atlas;
 mod on_change;
+mod portal;
 mod scroll;
+mod show;
+mod slider;
+mod sortable_list;
 mod spin_box;
+mod suspense;
 mod text;
+mod text_area;
 mod text_box;
+mod text_editing;
+mod text_history;
+mod theme_provider;
 mod tooltip;
+mod viewport;
 mod window;
 
+pub use animate_presence::*;
+pub use animated_image::*;
 pub use app::*;
 pub use background::*;
 pub use button::*;
+pub use canvas::*;
 pub use clip::*;
+pub use clipped_viewport::*;
+pub use collapse::*;
+pub use color_picker::*;
 pub use element::*;
+pub use error_boundary::*;
 pub use fold::*;
+pub use for_each::*;
+pub use grid_list::*;
 pub use if_element::*;
 pub use image::*;
+pub use input_pattern::*;
 pub use inspector::*;
+pub use keyed_fragment::*;
+pub use minimap::*;
 pub use nine_patch::*;
 pub use texture_atlas::*;
 pub use on_change::*;
+pub use portal::*;
 pub use scroll::*;
+pub use show::*;
+pub use slider::*;
+pub use sortable_list::*;
 pub use spin_box::*;
+pub use suspense::*;
 pub use text::*;
+pub use text_area::*;
 pub use text_box::*;
+pub use text_history::*;
+pub use theme_provider::*;
 pub use tooltip::*;
+pub use viewport::*;
 pub use window::*;
@@ -0,0 +1,160 @@
+use crate::core::{
+    portal,
+    render_command::RenderCommand,
+    rsx,
+    styles::{Style, StyleProp},
+    widget, Children, OnEvent, WidgetProps,
+};
+
+/// Props used by the [`Portal`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct PortalProps {
+    /// The layer to draw the children into
+    ///
+    /// If `None`, the children are drawn into the [`portal::MODAL`] layer.
+    pub layer: Option<String>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+}
+
+#[widget]
+/// A widget that draws its children into a portal layer, above the rest of the tree
+///
+/// The children are still laid out relative to the portal, but are drawn (and hit) above all
+/// widgets outside of the layer and aren't clipped by any of their ancestors. This is useful for
+/// dropdowns, modals and tooltips that need to escape a scrolling or clipped container.
+///
+/// See [`portal`](crate::core::portal) for the available layers.
+///
+/// # Props
+///
+/// __Type:__ [`PortalProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+pub fn Portal(props: PortalProps) {
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(RenderCommand::Layout),
+        ..props.styles.clone().unwrap_or_default()
+    });
+
+    let layer = props.layer.clone();
+    context.set_portal(Some(layer.as_deref().unwrap_or(portal::MODAL)));
+
+    rsx! {
+        <>
+            {children}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Portal, PortalProps};
+    use crate::core::color::Color;
+    use crate::core::render_primitive::RenderPrimitive;
+    use crate::core::styles::{PositionType, Style, StyleProp, Units};
+    use crate::core::{mount_widget, portal, Children, KayakContext, KayakContextRef, Widget};
+    use crate::widgets::{Background, BackgroundProps, Element, ElementProps};
+
+    const CONTENT: Color = Color::WHITE;
+    const WINDOW: Color = Color::BLACK;
+    const TOOLTIP: Color = Color::TRANSPARENT;
+
+    /// Styles placing a widget over the top-left corner of its parent
+    fn overlay(color: Color) -> Option<Style> {
+        Some(Style {
+            background_color: StyleProp::Value(color),
+            position_type: StyleProp::Value(PositionType::SelfDirected),
+            left: StyleProp::Value(Units::Pixels(0.0)),
+            top: StyleProp::Value(Units::Pixels(0.0)),
+            width: StyleProp::Value(Units::Pixels(50.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        })
+    }
+
+    /// Adds a background of the given color, drawn into the given layer (or in place if `None`)
+    fn add_overlay(
+        context: &mut KayakContextRef,
+        color: Color,
+        layer: Option<&'static str>,
+        index: usize,
+    ) {
+        let background = BackgroundProps {
+            styles: overlay(color),
+            ..Default::default()
+        };
+        match layer {
+            Some(layer) => {
+                let portal = PortalProps {
+                    layer: Some(layer.to_string()),
+                    styles: overlay(color),
+                    children: Some(Children::new(move |_, context| {
+                        context.add_widget(Background::constructor(background.clone()), 0);
+                        context.commit();
+                    })),
+                    ..Default::default()
+                };
+                context.add_widget(Portal::constructor(portal), index);
+            }
+            None => context.add_widget(Background::constructor(background), index),
+        }
+    }
+
+    #[test]
+    fn should_draw_portals_above_the_tree_by_layer() {
+        let mut context = KayakContext::new();
+        // Added in reverse order, so that the tree order alone would draw them the wrong way around
+        let root = ElementProps {
+            children: Some(Children::new(|_, context| {
+                add_overlay(context, TOOLTIP, Some(portal::TOOLTIP), 0);
+                add_overlay(context, WINDOW, Some(portal::WINDOW), 1);
+                add_overlay(context, CONTENT, None, 2);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Element::constructor(root));
+
+        let quads = context
+            .widget_manager
+            .build_render_primitives()
+            .into_iter()
+            .filter_map(|primitive| match primitive {
+                RenderPrimitive::Quad {
+                    layout,
+                    background_color,
+                    ..
+                } => Some((background_color, layout.z_index)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let colors = quads.iter().map(|(color, _)| *color).collect::<Vec<_>>();
+        assert_eq!(vec![CONTENT, WINDOW, TOOLTIP], colors);
+        assert!(quads.windows(2).all(|pair| pair[0].1 < pair[1].1));
+
+        // The widget in the highest layer is also hit first
+        let mut current = context.hit_test((10.0, 10.0));
+        while let Some(index) = current {
+            if context.get_portal(index).is_some() {
+                break;
+            }
+            current = context.widget_manager.tree.get_parent(index);
+        }
+        assert_eq!(
+            Some(portal::TOOLTIP),
+            current.and_then(|index| context.get_portal(index))
+        );
+    }
+}
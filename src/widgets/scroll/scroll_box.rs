@@ -2,7 +2,8 @@ use crate::core::{
     render_command::RenderCommand,
     rsx,
     styles::{PositionType, Style, Units},
-    widget, Bound, Children, EventType, MutableBound, OnEvent, ScrollUnit, WidgetProps,
+    widget, Binding, Bound, Children, EventType, Gestures, Handler, MutableBound, OnEvent,
+    ScrollUnit, WidgetProps,
 };
 
 use kayak_core::styles::LayoutType;
@@ -10,7 +11,7 @@ use kayak_core::{Color, GeometryChanged, OnLayout};
 
 use crate::widgets::{Clip, Element, If};
 
-use super::{ScrollBar, ScrollContent, ScrollContext, ScrollMode};
+use super::{ScrollBar, ScrollContent, ScrollContext, ScrollMode, ScrollOffset};
 
 /// Props used by the [`ScrollBox`] widget
 #[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
@@ -26,6 +27,17 @@ pub struct ScrollBoxProps {
     pub disable_vertical: bool,
     /// The scroll mode to use
     pub mode: ScrollMode,
+    /// A binding kept in sync with the scroll offset
+    ///
+    /// The scroll box writes its offset to this binding whenever it scrolls. Setting the binding
+    /// from elsewhere scrolls the box instead, clamped to the scrollable range. For each axis,
+    /// a changed pixel offset takes precedence over a changed fraction. Passing the same binding
+    /// to two scroll boxes links them together.
+    ///
+    /// Offsets along a disabled axis are ignored.
+    pub offset: Option<Binding<ScrollOffset>>,
+    /// Called with the new offset whenever the scroll box scrolls
+    pub on_scroll: Option<Handler<ScrollOffset>>,
     /// If true, hides the horizontal scrollbar
     pub hide_horizontal: bool,
     /// If true, hides the vertical scrollbar
@@ -48,11 +60,52 @@ pub struct ScrollBoxProps {
     pub children: Option<Children>,
     #[prop_field(OnLayout)]
     on_layout: Option<OnLayout>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+}
+
+/// The offset of a [`ScrollBox`] as of its last render
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+struct SyncedOffset(ScrollOffset);
+
+/// Scrolls to an offset set from outside of the scroll box
+///
+/// Only the parts of the offset that changed since the `last` synced offset are applied, with a
+/// changed pixel offset taking precedence over a changed fraction. Disabled axes are left as is.
+fn apply_external_offset(
+    scroll: ScrollContext,
+    external: ScrollOffset,
+    last: ScrollOffset,
+    disable_horizontal: bool,
+    disable_vertical: bool,
+) -> ScrollContext {
+    let mut next = scroll;
+    if !disable_horizontal {
+        if external.x != last.x {
+            next.set_offset_x(external.x);
+        } else if external.fraction_x != last.fraction_x {
+            next.set_fraction_x(external.fraction_x);
+        }
+    }
+    if !disable_vertical {
+        if external.y != last.y {
+            next.set_offset_y(external.y);
+        } else if external.fraction_y != last.fraction_y {
+            next.set_fraction_y(external.fraction_y);
+        }
+    }
+    next
 }
 
 #[widget]
 /// A widget that creates a scrollable area for overflowing content
 ///
+/// On touch devices, the content can also be scrolled by panning. Nested widgets that recognize
+/// pans themselves take precedence over the scroll box.
+///
+/// The scroll offset can be observed with [`on_scroll`](ScrollBoxProps::on_scroll), or both
+/// observed and driven with an [`offset`](ScrollBoxProps::offset) binding.
+///
 /// # Props
 ///
 /// __Type:__ [`ScrollBoxProps`]
@@ -61,7 +114,7 @@ pub struct ScrollBoxProps {
 /// | :---------: | :------: |
 /// | `children`  | ✅        |
 /// | `styles`    | ✅        |
-/// | `on_event`  | ❌        |
+/// | `on_event`  | ✅        |
 /// | `focusable` | ❌        |
 ///
 pub fn ScrollBox(props: ScrollBoxProps) {
@@ -84,7 +137,62 @@ pub fn ScrollBox(props: ScrollBoxProps) {
         mode,
         ..Default::default()
     });
-    let scroll: ScrollContext = scroll_ctx.get();
+    let mut scroll: ScrollContext = scroll_ctx.get();
+
+    // === Offset === //
+    // The offset as of the last render, used to tell which side changed
+    let synced = context.create_state(SyncedOffset::default()).unwrap();
+    let last = synced.get().0;
+    let has_layout = scroll.scrollbox_width > 0.0 || scroll.scrollbox_height > 0.0;
+    if has_layout {
+        let mut scrolled = None;
+        if let Some(binding) = &props.offset {
+            context.bind(binding);
+
+            let external = binding.get();
+            if external != last {
+                let next = apply_external_offset(
+                    scroll,
+                    external,
+                    last,
+                    disable_horizontal,
+                    disable_vertical,
+                );
+                if next != scroll {
+                    scrolled = Some(next);
+                    scroll = next;
+                }
+            }
+        }
+
+        // The bindings and handler are only updated once this render is done, since each of them
+        // may re-render widgets (including this one)
+        let offset = scroll.offset();
+        let binding = props.offset.clone();
+        let on_scroll = props.on_scroll.clone();
+        let scroll_ctx = scroll_ctx.clone();
+        context.after_render(move |_| {
+            if let Some(scrolled) = scrolled {
+                // Only the offset is applied, keeping any other changes made since
+                let mut next = scroll_ctx.get();
+                next.scroll_x = scrolled.scroll_x;
+                next.scroll_y = scrolled.scroll_y;
+                scroll_ctx.set(next);
+            }
+            if let Some(binding) = &binding {
+                if binding.get() != offset {
+                    binding.set(offset);
+                }
+            }
+            if offset != last {
+                synced.set(SyncedOffset(offset));
+                if let Some(on_scroll) = &on_scroll {
+                    on_scroll.call(offset);
+                }
+            }
+        });
+    }
+
     let scroll_x = scroll.scroll_x();
     let scroll_y = scroll.scroll_y();
     let scrollable_width = scroll.scrollable_width();
@@ -110,11 +218,15 @@ pub fn ScrollBox(props: ScrollBoxProps) {
     // === Layout === //
     let _scroll_ctx = scroll_ctx.clone();
     props.on_layout = Some(OnLayout::new(move |_, evt| {
-        if evt
-            .flags
-            .intersects(GeometryChanged::WIDTH_CHANGED | GeometryChanged::HEIGHT_CHANGED)
-        {
+        if evt.flags.intersects(
+            GeometryChanged::WIDTH_CHANGED
+                | GeometryChanged::HEIGHT_CHANGED
+                | GeometryChanged::POSX_CHANGED
+                | GeometryChanged::POSY_CHANGED,
+        ) {
             let mut next = _scroll_ctx.get();
+            next.scrollbox_x = evt.layout.x;
+            next.scrollbox_y = evt.layout.y;
             next.scrollbox_width = evt.layout.width;
             next.scrollbox_height = evt.layout.height;
             _scroll_ctx.set(next);
@@ -157,6 +269,7 @@ pub fn ScrollBox(props: ScrollBoxProps) {
     });
 
     // === Events === //
+    let pan_scroll_ctx = scroll_ctx.clone();
     let event_handler = OnEvent::new(move |_, event| match event.event_type {
         EventType::Scroll(evt) => {
             match evt.delta {
@@ -186,6 +299,25 @@ pub fn ScrollBox(props: ScrollBoxProps) {
         _ => {}
     });
 
+    // === Touch === //
+    context.set_gestures(Gestures::PAN);
+    let scroll_box_id = context.current_id().unwrap_or_default();
+    let on_event = props.on_event.take();
+    props.on_event = Some(OnEvent::wrap(scroll_box_id, on_event, move |_, event| {
+        if let EventType::Pan(evt) = event.event_type {
+            // Content follows the touch
+            let (x, y) = evt.delta;
+            let mut old = pan_scroll_ctx.get();
+            if !disable_horizontal {
+                old.set_scroll_x(old.scroll_x() + x);
+            }
+            if !disable_vertical {
+                old.set_scroll_y(old.scroll_y() + y);
+            }
+            pan_scroll_ctx.set(old);
+        }
+    }));
+
     // === Render === //
     let children = props.get_children();
     rsx! {
@@ -223,3 +355,80 @@ pub fn ScrollBox(props: ScrollBoxProps) {
         </Element>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_external_offset, ScrollContext, ScrollOffset};
+
+    /// A 100x100 scroll box of 300x500 of content, scrolled by (50, 100)
+    fn scroll() -> ScrollContext {
+        ScrollContext {
+            scroll_x: -50.0,
+            scroll_y: -100.0,
+            content_width: 300.0,
+            content_height: 500.0,
+            scrollbox_width: 100.0,
+            scrollbox_height: 100.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_apply_changed_offsets() {
+        let scroll = scroll();
+        let last = scroll.offset();
+        let external = ScrollOffset { x: 150.0, ..last };
+        let next = apply_external_offset(scroll, external, last, false, false);
+        assert_eq!(150.0, next.offset_x());
+        assert_eq!(100.0, next.offset_y());
+
+        // A changed pixel offset takes precedence over a changed fraction
+        let external = ScrollOffset {
+            y: 200.0,
+            fraction_y: 1.0,
+            ..last
+        };
+        let next = apply_external_offset(scroll, external, last, false, false);
+        assert_eq!(200.0, next.offset_y());
+
+        let external = ScrollOffset {
+            fraction_y: 1.0,
+            ..last
+        };
+        let next = apply_external_offset(scroll, external, last, false, false);
+        assert_eq!(400.0, next.offset_y());
+        assert_eq!(50.0, next.offset_x());
+    }
+
+    #[test]
+    fn should_clamp_external_offsets() {
+        let scroll = scroll();
+        let last = scroll.offset();
+        let external = ScrollOffset {
+            x: -10.0,
+            y: 1000.0,
+            ..last
+        };
+        let next = apply_external_offset(scroll, external, last, false, false);
+        assert_eq!(0.0, next.offset_x());
+        assert_eq!(400.0, next.offset_y());
+        assert_eq!(1.0, next.fraction_y());
+    }
+
+    #[test]
+    fn should_leave_disabled_axes_as_is() {
+        let scroll = scroll();
+        let last = scroll.offset();
+        let external = ScrollOffset {
+            x: 150.0,
+            y: 200.0,
+            ..last
+        };
+        let next = apply_external_offset(scroll, external, last, true, false);
+        assert_eq!(50.0, next.offset_x());
+        assert_eq!(200.0, next.offset_y());
+        let next = apply_external_offset(scroll, external, last, false, true);
+        assert_eq!(150.0, next.offset_x());
+        assert_eq!(100.0, next.offset_y());
+    }
+}
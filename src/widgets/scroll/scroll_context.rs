@@ -1,3 +1,5 @@
+use kayak_core::layout_cache::Rect;
+
 /// Context data provided by a [`ScrollBox`](crate::ScrollBox) widget
 #[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub struct ScrollContext {
@@ -5,6 +7,8 @@ pub struct ScrollContext {
     pub(super) scroll_y: f32,
     pub(super) content_width: f32,
     pub(super) content_height: f32,
+    pub(super) scrollbox_x: f32,
+    pub(super) scrollbox_y: f32,
     pub(super) scrollbox_width: f32,
     pub(super) scrollbox_height: f32,
     pub(super) pad_x: f32,
@@ -12,6 +16,22 @@ pub struct ScrollContext {
     pub(super) mode: ScrollMode,
 }
 
+/// The scroll offset of a [`ScrollBox`](crate::ScrollBox)
+///
+/// Each axis is given both as a distance in pixels and as a fraction (from 0 to 1) of the distance
+/// that can be scrolled along that axis.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct ScrollOffset {
+    /// The distance scrolled along the x-axis, in pixels
+    pub x: f32,
+    /// The distance scrolled along the y-axis, in pixels
+    pub y: f32,
+    /// The fraction of the scrollable width that has been scrolled
+    pub fraction_x: f32,
+    /// The fraction of the scrollable height that has been scrolled
+    pub fraction_y: f32,
+}
+
 #[non_exhaustive]
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ScrollMode {
@@ -71,6 +91,19 @@ impl ScrollContext {
         self.mode
     }
 
+    /// The area of the screen the scroll box takes up, as of its last layout
+    ///
+    /// This is useful for scrolling when the cursor is dragged near the edges of the scroll box.
+    pub fn visible_rect(&self) -> Rect {
+        Rect {
+            posx: self.scrollbox_x,
+            posy: self.scrollbox_y,
+            width: self.scrollbox_width,
+            height: self.scrollbox_height,
+            ..Default::default()
+        }
+    }
+
     /// Set the scroll offset along the x-axis
     ///
     /// This automatically accounts for the scroll mode
@@ -115,6 +148,62 @@ impl ScrollContext {
         }
     }
 
+    /// The distance scrolled along the x-axis, in pixels
+    pub fn offset_x(&self) -> f32 {
+        -self.scroll_x
+    }
+
+    /// The distance scrolled along the y-axis, in pixels
+    pub fn offset_y(&self) -> f32 {
+        -self.scroll_y
+    }
+
+    /// The fraction of the scrollable width that has been scrolled, from 0 to 1
+    pub fn fraction_x(&self) -> f32 {
+        -self.percent_x()
+    }
+
+    /// The fraction of the scrollable height that has been scrolled, from 0 to 1
+    pub fn fraction_y(&self) -> f32 {
+        -self.percent_y()
+    }
+
+    /// The current scroll offset along both axes
+    pub fn offset(&self) -> ScrollOffset {
+        ScrollOffset {
+            x: self.offset_x(),
+            y: self.offset_y(),
+            fraction_x: self.fraction_x(),
+            fraction_y: self.fraction_y(),
+        }
+    }
+
+    /// Scroll to the given distance along the x-axis, in pixels
+    ///
+    /// Unlike [`set_scroll_x`](Self::set_scroll_x), this is always clamped to the scrollable
+    /// range, regardless of the scroll mode.
+    pub fn set_offset_x(&mut self, x: f32) {
+        self.scroll_x = -ScrollContext::clamped(x, 0.0, self.scrollable_width());
+    }
+
+    /// Scroll to the given distance along the y-axis, in pixels
+    ///
+    /// Unlike [`set_scroll_y`](Self::set_scroll_y), this is always clamped to the scrollable
+    /// range, regardless of the scroll mode.
+    pub fn set_offset_y(&mut self, y: f32) {
+        self.scroll_y = -ScrollContext::clamped(y, 0.0, self.scrollable_height());
+    }
+
+    /// Scroll to the given fraction (from 0 to 1) of the scrollable width
+    pub fn set_fraction_x(&mut self, fraction: f32) {
+        self.set_offset_x(fraction * self.scrollable_width());
+    }
+
+    /// Scroll to the given fraction (from 0 to 1) of the scrollable height
+    pub fn set_fraction_y(&mut self, fraction: f32) {
+        self.set_offset_y(fraction * self.scrollable_height());
+    }
+
     /// Clamps a given value between a range
     fn clamped(value: f32, min: f32, max: f32) -> f32 {
         value.clamp(min, max)
@@ -0,0 +1,257 @@
+use crate::core::{rsx, widget, Children, Widget, WidgetProps};
+
+/// Props used by the [`Show`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ShowProps {
+    /// If true, the content is rendered, otherwise the [`otherwise`](Self::otherwise) fallback is
+    pub when: bool,
+    /// The content to render when [`when`](Self::when) is true
+    ///
+    /// If `None`, the children are rendered instead. If there are no children either, there's
+    /// nothing to show, so the [`otherwise`](Self::otherwise) fallback is rendered even when
+    /// [`when`](Self::when) is true. Create it with [`show_content`], as in
+    /// `user.map(|user| show_content(...))`, to only show content when an `Option` is `Some`.
+    pub content: Option<Children>,
+    /// The fallback to render when [`when`](Self::when) is false
+    ///
+    /// If `None`, nothing is rendered.
+    pub otherwise: Option<Children>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+}
+
+/// Creates content for a [`Show`] widget from a closure that builds its widget
+///
+/// The closure is only called when the content is actually rendered, so the widgets of a hidden
+/// branch are never constructed.
+pub fn show_content<W, F>(build: F) -> Children
+where
+    W: Widget + 'static,
+    F: Fn() -> W + Send + Sync + 'static,
+{
+    Children::new(move |_, context| {
+        context.add_widget(build(), 0);
+        context.commit();
+    })
+}
+
+#[widget]
+/// A widget that renders its content only when a condition is met
+///
+/// This is a runtime alternative to an `if` expression within `rsx!`, for conditions computed
+/// outside of the macro. The content and the [`otherwise`](ShowProps::otherwise) fallback are
+/// rendered in separate slots, so toggling between them never carries state from one over to the
+/// other. Since the `Show` itself stays in place, toggling it doesn't disturb its siblings either.
+///
+/// Like a [`Fragment`](crate::core::Fragment), this doesn't add a node to the layout.
+///
+/// # Props
+///
+/// __Type:__ [`ShowProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ❌        |
+/// | `on_event`  | ❌        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{constructor, rsx, widget};
+/// # use kayak_ui::widgets::{show_content, Show, Text};
+/// # #[widget]
+/// # fn Example() {
+/// let user: Option<String> = None;
+/// let signed_in = user.is_some();
+/// let fallback = show_content(|| constructor! {
+///     <Text content={"Signed out".to_string()} size={16.0} />
+/// });
+///
+/// rsx! {
+///     <Show
+///         when={signed_in}
+///         content={user.map(|name| show_content(move || constructor! {
+///             <Text content={format!("Hello, {}!", name)} size={16.0} />
+///         }))}
+///         otherwise={Some(fallback)}
+///     />
+/// }
+/// # }
+/// ```
+pub fn Show(props: ShowProps) {
+    let content = props.content.clone().or(children);
+    let shown = props.when && content.is_some();
+    let children = if shown {
+        content
+    } else {
+        props.otherwise.clone()
+    };
+
+    rsx! {
+        <>
+            {if shown { {children} } else { {children} }}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use super::{show_content, Show, ShowProps};
+    use crate::core::{
+        mount_widget, widget, Binding, Bound, Index, KayakContext, KayakContextRef, MutableBound,
+        Widget, WidgetProps,
+    };
+
+    /// Where a counter puts its state as it renders
+    #[derive(Clone, Debug, Default)]
+    struct CountSlot(Arc<Mutex<Option<Binding<u32>>>>);
+
+    impl PartialEq for CountSlot {
+        fn eq(&self, other: &Self) -> bool {
+            Arc::ptr_eq(&self.0, &other.0)
+        }
+    }
+
+    impl CountSlot {
+        fn get(&self) -> Binding<u32> {
+            self.0.lock().unwrap().clone().unwrap()
+        }
+    }
+
+    /// The number of times the content was built
+    #[derive(Clone, Debug, Default)]
+    struct Builds(Arc<AtomicUsize>);
+
+    impl PartialEq for Builds {
+        fn eq(&self, other: &Self) -> bool {
+            Arc::ptr_eq(&self.0, &other.0)
+        }
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct CounterProps {
+        count: CountSlot,
+    }
+
+    fn track_count(context: &mut KayakContextRef, count: &CountSlot) {
+        let state = context.create_state(0u32).unwrap();
+        *count.0.lock().unwrap() = Some(state);
+    }
+
+    #[widget]
+    fn Content(props: CounterProps) {
+        track_count(context, &props.count);
+    }
+
+    #[widget]
+    fn Fallback(props: CounterProps) {
+        track_count(context, &props.count);
+    }
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct ToggleProps {
+        when: Binding<bool>,
+        /// Whether the `Show` is given any content
+        has_content: bool,
+        content: CountSlot,
+        fallback: CountSlot,
+        builds: Builds,
+    }
+
+    #[widget]
+    fn Toggle(props: ToggleProps) {
+        context.bind(&props.when);
+        let content = CounterProps {
+            count: props.content.clone(),
+        };
+        let builds = props.builds.clone();
+        let fallback = CounterProps {
+            count: props.fallback.clone(),
+        };
+        let show = ShowProps {
+            when: props.when.get(),
+            content: props.has_content.then(|| {
+                show_content(move || {
+                    builds.0.fetch_add(1, Ordering::SeqCst);
+                    Content::constructor(content.clone())
+                })
+            }),
+            otherwise: Some(show_content(move || {
+                Fallback::constructor(fallback.clone())
+            })),
+            ..Default::default()
+        };
+        context.add_widget(Show::constructor(show), 0);
+    }
+
+    /// Mounts a `Show` with a counter as its content and another as its fallback
+    fn mount(context: &mut KayakContext, when: bool, has_content: bool) -> ToggleProps {
+        let props = ToggleProps {
+            when: Binding::new(when),
+            has_content,
+            ..Default::default()
+        };
+        let toggle = props.clone();
+        mount_widget(context, Toggle::constructor(toggle));
+        props
+    }
+
+    fn find(context: &KayakContext, name: &str) -> Option<Index> {
+        let widget_manager = &context.widget_manager;
+        widget_manager.tree.flatten().into_iter().find(|id| {
+            widget_manager
+                .get_name(id)
+                .map_or(false, |widget| widget.ends_with(name))
+        })
+    }
+
+    #[test]
+    fn should_only_build_the_shown_branch() {
+        let mut context = KayakContext::new();
+        let props = mount(&mut context, false, true);
+        assert_eq!(0, props.builds.0.load(Ordering::SeqCst));
+        assert_eq!(None, find(&context, "::Content"));
+        assert!(find(&context, "::Fallback").is_some());
+
+        props.when.set(true);
+        context.render();
+        assert_eq!(1, props.builds.0.load(Ordering::SeqCst));
+        assert!(find(&context, "::Content").is_some());
+        assert_eq!(None, find(&context, "::Fallback"));
+    }
+
+    #[test]
+    fn should_not_carry_state_between_branches() {
+        let mut context = KayakContext::new();
+        let props = mount(&mut context, true, true);
+        props.content.get().set(5);
+        context.render();
+        assert_eq!(5, props.content.get().get());
+
+        // The fallback gets its own state rather than the content's
+        props.when.set(false);
+        context.render();
+        assert_eq!(0, props.fallback.get().get());
+        props.fallback.get().set(3);
+        context.render();
+
+        // The content comes back in its own slot, starting fresh
+        props.when.set(true);
+        context.render();
+        assert_eq!(0, props.content.get().get());
+        assert_eq!(None, find(&context, "::Fallback"));
+    }
+
+    #[test]
+    fn should_show_the_fallback_without_content() {
+        let mut context = KayakContext::new();
+        mount(&mut context, true, false);
+        assert!(find(&context, "::Fallback").is_some());
+    }
+}
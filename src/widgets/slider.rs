@@ -0,0 +1,260 @@
+use kayak_core::layout_cache::Rect;
+
+use crate::core::{
+    constructor,
+    render_command::RenderCommand,
+    rsx,
+    styles::{Corner, Edge, LayoutType, PositionType, Style, StyleProp, Units},
+    use_state, widget, Children, Color, CursorIcon, EventType, Handler, OnEvent, OnLayout,
+    PointerEvents, WidgetProps,
+};
+
+use crate::widgets::{Background, Element};
+
+use super::disabled::apply_disabled_styles;
+
+/// The width (and height) of a [`Slider`]'s thumb, in pixels
+const THUMB_SIZE: f32 = 12.0;
+
+/// Props used by the [`Slider`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct SliderProps {
+    /// If true, disables this widget, preventing it from being dragged
+    ///
+    /// If false, the widget inherits the disabled state of its parent.
+    #[prop_field(Disabled)]
+    pub disabled: bool,
+    /// A callback for when the user drags the slider (or presses on its track)
+    ///
+    /// The handler is given the new value, which is always within the slider's range.
+    pub on_change: Option<Handler<f32>>,
+    /// The minimum and maximum values of the slider
+    ///
+    /// If `None`, the range is `(0.0, 1.0)`.
+    pub range: Option<(f32, f32)>,
+    /// The color of the thumb
+    ///
+    /// If `None`, the thumb is white.
+    pub thumb_color: Option<Color>,
+    /// The colors of the track, drawn as equally-sized segments from left to right
+    ///
+    /// This makes it easy to draw a gradient (such as the hues of a color picker). If empty, the
+    /// track is a dark gray.
+    pub track_colors: Vec<Color>,
+    /// The current value
+    ///
+    /// This is a controlled state. You _must_ set this to the value you wish to be displayed.
+    /// You can use the [`on_change`](Self::on_change) callback to update this prop as the user
+    /// drags. Values outside the slider's range are clamped.
+    pub value: f32,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(Focusable)]
+    pub focusable: Option<bool>,
+}
+
+impl SliderProps {
+    /// The minimum and maximum values of the slider
+    pub fn range(&self) -> (f32, f32) {
+        self.range.unwrap_or((0.0, 1.0))
+    }
+}
+
+#[widget]
+/// A widget for picking a value within a range by dragging a thumb along a track
+///
+/// # Props
+///
+/// __Type:__ [`SliderProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ✅        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{Handler, rsx, use_state, widget};
+/// # use kayak_ui::widgets::Slider;
+/// # #[widget]
+/// # fn Example() {
+/// let (volume, set_volume, ..) = use_state!(50.0);
+/// let on_change = Handler::new(move |value| {
+///     set_volume(value);
+/// });
+///
+/// rsx! {
+///     <Slider value={volume} range={Some((0.0, 100.0))} on_change={Some(on_change)} />
+/// }
+/// # }
+/// ```
+pub fn Slider(props: SliderProps) {
+    let SliderProps {
+        on_change,
+        thumb_color,
+        track_colors,
+        value,
+        ..
+    } = props.clone();
+    let (min, max) = props.range();
+    let disabled = context.is_disabled();
+
+    // === Layout === //
+    let width = context.use_layout().map_or(0.0, |layout| layout.width);
+    let fraction = if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // === States === //
+    let (is_dragging, set_is_dragging, ..) = use_state!(false);
+
+    // === Events === //
+    let value_at = move |track: Rect, x: f32| {
+        let usable = (track.width - THUMB_SIZE).max(1.0);
+        let fraction = ((x - track.posx - THUMB_SIZE / 2.0) / usable).clamp(0.0, 1.0);
+        min + fraction * (max - min)
+    };
+    let track_handler = OnEvent::new(move |ctx, event| {
+        let track = match ctx.get_layout(&event.current_target) {
+            Some(layout) => *layout,
+            None => return,
+        };
+        let x = match event.event_type {
+            EventType::MouseDown(data) => {
+                ctx.capture_cursor(event.current_target);
+                set_is_dragging(true);
+                data.position.0
+            }
+            EventType::MouseUp(..) => {
+                ctx.release_cursor(event.current_target);
+                set_is_dragging(false);
+                return;
+            }
+            EventType::Hover(data) if is_dragging => data.position.0,
+            _ => return,
+        };
+
+        let next = value_at(track, x);
+        if next != value {
+            if let Some(ref on_change) = on_change {
+                on_change.call(next);
+            }
+        }
+    });
+    let track_handler = if disabled { None } else { Some(track_handler) };
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Layout.into(),
+                ..Default::default()
+            })
+            .with_style(&props.styles)
+            .with_style(Style {
+                width: Units::Stretch(1.0).into(),
+                height: Units::Pixels(THUMB_SIZE + 4.0).into(),
+                cursor: if disabled {
+                    CursorIcon::Default
+                } else if is_dragging {
+                    CursorIcon::Grabbing
+                } else {
+                    CursorIcon::Hand
+                }
+                .into(),
+                ..Default::default()
+            }),
+    );
+
+    let container_styles = Style {
+        background_color: Color::TRANSPARENT.into(),
+        cursor: StyleProp::Inherit,
+        width: Units::Stretch(1.0).into(),
+        height: Units::Stretch(1.0).into(),
+        ..Default::default()
+    };
+
+    let track_styles = Style {
+        border_radius: Corner::all(3.0).into(),
+        layout_type: LayoutType::Row.into(),
+        pointer_events: PointerEvents::None.into(),
+        position_type: PositionType::SelfDirected.into(),
+        left: Units::Pixels(THUMB_SIZE / 2.0).into(),
+        right: Units::Pixels(THUMB_SIZE / 2.0).into(),
+        top: Units::Stretch(1.0).into(),
+        bottom: Units::Stretch(1.0).into(),
+        height: Units::Pixels(6.0).into(),
+        ..Default::default()
+    };
+
+    let track_colors = if track_colors.is_empty() {
+        vec![Color::new(0.1581, 0.1758, 0.191, 1.0)]
+    } else {
+        track_colors
+    };
+
+    let mut thumb_styles = Style {
+        background_color: thumb_color.unwrap_or(Color::WHITE).into(),
+        border: Edge::all(1.0).into(),
+        border_color: Color::new(0.0781, 0.0898, 0.101, 1.0).into(),
+        border_radius: Corner::all(THUMB_SIZE / 2.0).into(),
+        pointer_events: PointerEvents::None.into(),
+        position_type: PositionType::SelfDirected.into(),
+        left: Units::Pixels(fraction * (width - THUMB_SIZE).max(0.0)).into(),
+        top: Units::Stretch(1.0).into(),
+        bottom: Units::Stretch(1.0).into(),
+        width: Units::Pixels(THUMB_SIZE).into(),
+        height: Units::Pixels(THUMB_SIZE).into(),
+        ..Default::default()
+    };
+    if disabled {
+        apply_disabled_styles(&mut thumb_styles);
+    }
+
+    let segment_styles = track_colors
+        .iter()
+        .map(|&color| {
+            let mut segment_styles = Style {
+                background_color: color.into(),
+                width: Units::Stretch(1.0).into(),
+                height: Units::Stretch(1.0).into(),
+                ..Default::default()
+            };
+            if disabled {
+                apply_disabled_styles(&mut segment_styles);
+            }
+            segment_styles
+        })
+        .collect::<Vec<_>>();
+    let track_segments = Some(Children::new(move |_, context| {
+        for (index, segment_styles) in segment_styles.iter().enumerate() {
+            let segment_styles = Some(segment_styles.clone());
+            context.add_widget(
+                constructor! {
+                    <Background styles={segment_styles} />
+                },
+                index,
+            );
+        }
+        context.commit();
+    }));
+
+    rsx! {
+        <Background on_event={track_handler} styles={Some(container_styles)}>
+            <Element styles={Some(track_styles)} children={track_segments} />
+            <Background styles={Some(thumb_styles)} />
+        </Background>
+    }
+}
@@ -0,0 +1,568 @@
+use kayak_core::layout_cache::Rect;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::core::{
+    constructor,
+    render_command::RenderCommand,
+    rsx,
+    styles::{PositionType, Style, Units},
+    widget, Bound, Children, Color, CursorIcon, DynamicValue, EventType, Handler, KayakContextRef,
+    MutableBound, OnEvent, OnLayout, PointerEvents, VecTracker, Widget, WidgetProps,
+};
+
+use crate::widgets::{Background, KeyedFragment, ScrollContext};
+
+/// The height of each item of a [`SortableList`] if not set by its props, in pixels
+const DEFAULT_ITEM_HEIGHT: f32 = 32.0;
+/// The distance from the edges of a scroll box within which dragging an item scrolls it, in pixels
+const AUTO_SCROLL_EDGE: f32 = 24.0;
+/// The distance a scroll box is scrolled each frame while an item is dragged near its edges
+const AUTO_SCROLL_STEP: f32 = 6.0;
+/// The time between scroll steps while an item is dragged near the edges of a scroll box
+const AUTO_SCROLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// An item of a [`SortableList`]
+///
+/// The key identifies the item across reorders, so the state of its widget moves along with it.
+/// Keys should be unique within a list.
+#[derive(Clone)]
+pub struct SortableItem {
+    key: String,
+    /// The item's widget, used to tell whether the item changed
+    widget: DynamicValue,
+    builder: Arc<dyn Fn(&mut KayakContextRef) + Send + Sync>,
+}
+
+impl SortableItem {
+    /// Create an item with the given key that renders the given widget
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kayak_ui::core::constructor;
+    /// # use kayak_ui::widgets::{SortableItem, Text};
+    ///
+    /// let item = SortableItem::new("apples", constructor! {
+    ///     <Text content={"Apples".to_string()} size={16.0} />
+    /// });
+    /// ```
+    pub fn new<K: Into<String>, W: Widget + 'static>(key: K, widget: W) -> Self {
+        Self {
+            key: key.into(),
+            widget: DynamicValue::new(widget.clone()),
+            builder: Arc::new(move |context| context.add_widget(widget.clone(), 0)),
+        }
+    }
+
+    /// The key identifying this item
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn children(&self) -> Children {
+        let builder = self.builder.clone();
+        Children::new(move |_, context| {
+            builder(context);
+            context.commit();
+        })
+    }
+}
+
+impl PartialEq for SortableItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.widget == other.widget
+    }
+}
+
+impl Debug for SortableItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SortableItem").field(&self.key).finish()
+    }
+}
+
+/// Props used by the [`SortableList`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct SortableListProps {
+    /// If true, disables this widget, preventing its items from being dragged
+    ///
+    /// If false, the widget inherits the disabled state of its parent.
+    #[prop_field(Disabled)]
+    pub disabled: bool,
+    /// The color of the line showing where the dragged item will be dropped
+    ///
+    /// If `None`, the line is blue.
+    pub indicator_color: Option<Color>,
+    /// The height of each item in pixels
+    ///
+    /// If `None`, each item is 32 pixels tall.
+    pub item_height: Option<f32>,
+    /// The items to display, in order
+    pub items: Vec<SortableItem>,
+    /// A callback for when the user drops an item at a new position
+    ///
+    /// The handler is given the index the item was dragged `from` and the index it should be moved
+    /// `to`, such that removing it from the list and inserting it back at `to` gives the new order
+    /// (see [`reorder`]). The list doesn't reorder its items itself, so this should update the
+    /// [`items`](Self::items) prop.
+    pub on_reorder: Option<Handler<(usize, usize)>>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(Focusable)]
+    pub focusable: Option<bool>,
+}
+
+/// Moves the item at index `from` to index `to`, as given by [`SortableListProps::on_reorder`]
+///
+/// Indices out of bounds are ignored.
+pub fn reorder<T>(items: &mut Vec<T>, from: usize, to: usize) {
+    if from < items.len() && to < items.len() {
+        let item = items.remove(from);
+        items.insert(to, item);
+    }
+}
+
+/// The distance to scroll a scroll box by while an item is dragged with the cursor at `cursor_y`
+///
+/// This is positive (scrolling up) near the top edge of the visible rect, negative near the
+/// bottom edge, and zero elsewhere.
+fn auto_scroll_step(visible: Rect, cursor_y: f32) -> f32 {
+    if cursor_y < visible.posy + AUTO_SCROLL_EDGE {
+        AUTO_SCROLL_STEP
+    } else if cursor_y > visible.posy + visible.height - AUTO_SCROLL_EDGE {
+        -AUTO_SCROLL_STEP
+    } else {
+        0.0
+    }
+}
+
+/// The keys of the items rendered into each child slot of a [`SortableList`]
+///
+/// Items keep their slot (and with it, their widget state) as they're reordered. Slots of removed
+/// items are left empty until they're taken by a new item.
+#[derive(Default, Debug, Clone, PartialEq)]
+struct SortableSlots(Vec<Option<String>>);
+
+impl SortableSlots {
+    /// Assigns a slot to each of the given keys, returning true if any slot changed
+    fn update(&mut self, keys: &[&str]) -> bool {
+        let mut changed = false;
+        for slot in self.0.iter_mut() {
+            if slot.as_deref().map_or(false, |key| !keys.contains(&key)) {
+                *slot = None;
+                changed = true;
+            }
+        }
+
+        for key in keys {
+            if self.0.iter().any(|slot| slot.as_deref() == Some(*key)) {
+                continue;
+            }
+            match self.0.iter().position(Option::is_none) {
+                Some(free) => self.0[free] = Some(key.to_string()),
+                None => self.0.push(Some(key.to_string())),
+            }
+            changed = true;
+        }
+
+        while self.0.last().map_or(false, Option::is_none) {
+            self.0.pop();
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// The item being dragged within a [`SortableList`]
+#[derive(Debug, Clone, PartialEq)]
+struct SortDrag {
+    key: String,
+    /// The index the item was dragged from
+    from: usize,
+    /// The distance from the top of the item to the cursor when the drag started
+    grab_offset: f32,
+    /// The current position of the cursor
+    cursor: (f32, f32),
+}
+
+impl SortDrag {
+    /// The index in between items (from `0` to `count`) that the item would be dropped at
+    fn insertion_index(&self, list_top: f32, item_height: f32, count: usize) -> usize {
+        let center = self.cursor.1 - self.grab_offset + item_height / 2.0;
+        let index = ((center - list_top) / item_height.max(1.0) + 0.5).floor();
+        (index.max(0.0) as usize).min(count)
+    }
+
+    /// The index the item should be moved to if dropped at the given insertion index
+    fn target_index(&self, insertion: usize) -> usize {
+        if insertion > self.from {
+            insertion - 1
+        } else {
+            insertion
+        }
+    }
+}
+
+#[widget]
+/// A widget that displays a list of items, which the user can reorder by dragging them
+///
+/// Pressing on an item picks it up, after which it follows the cursor. A line is shown where the
+/// item will be dropped, and [`on_reorder`](SortableListProps::on_reorder) is called once it's
+/// dropped somewhere new. When used within a [`ScrollBox`](crate::widgets::ScrollBox), dragging an
+/// item near the top or bottom edge of the scroll box scrolls it.
+///
+/// Items are identified by their keys rather than their positions, so their widgets keep their
+/// state (such as the text of a `TextBox`) as they're reordered.
+///
+/// # Props
+///
+/// __Type:__ [`SortableListProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ✅        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{constructor, widget, Handler, rsx, use_state};
+/// # use kayak_ui::widgets::{reorder, SortableItem, SortableList, Text};
+///
+/// #[widget]
+/// fn FruitList() {
+///     let (fruits, set_fruits, ..) = use_state!(vec!["Apples", "Bananas", "Cherries"]);
+///     let items = fruits
+///         .iter()
+///         .map(|fruit| {
+///             SortableItem::new(*fruit, constructor! {
+///                 <Text content={fruit.to_string()} size={16.0} />
+///             })
+///         })
+///         .collect::<Vec<_>>();
+///     let on_reorder = Handler::new(move |(from, to)| {
+///         let mut fruits = fruits.clone();
+///         reorder(&mut fruits, from, to);
+///         set_fruits(fruits);
+///     });
+///
+///     rsx! {
+///         <SortableList items={items} on_reorder={Some(on_reorder)} />
+///     }
+/// }
+/// ```
+pub fn SortableList(props: SortableListProps) {
+    let SortableListProps {
+        indicator_color,
+        item_height,
+        items,
+        on_reorder,
+        ..
+    } = props.clone();
+    let item_height = item_height.unwrap_or(DEFAULT_ITEM_HEIGHT);
+    let list_id = self.get_id();
+    let disabled = context.is_disabled();
+
+    // === States === //
+    let slots = context.create_state(SortableSlots::default()).unwrap();
+    let drag = context.create_state::<Option<SortDrag>>(None).unwrap();
+    let dragging = drag.get();
+
+    let keys = items.iter().map(SortableItem::key).collect::<Vec<_>>();
+    let mut current_slots = slots.get();
+    if current_slots.update(&keys) {
+        slots.set(current_slots.clone());
+    }
+
+    // === Layout === //
+    context.use_layout();
+    let list_top = context
+        .get_layout(&list_id)
+        .map_or(0.0, |layout| layout.posy);
+    let insertion = dragging
+        .as_ref()
+        .map(|dragging| dragging.insertion_index(list_top, item_height, items.len()));
+
+    // === Auto-scroll === //
+    // Scrolls on the ticks of a timer rather than on every render, so that the speed doesn't
+    // depend on how often the list happens to re-render
+    let scroll_ctx = context.create_consumer::<ScrollContext>();
+    let auto_scroll = match (&dragging, &scroll_ctx) {
+        (Some(dragging), Some(scroll_ctx)) => {
+            let scroll = scroll_ctx.get();
+            let step = auto_scroll_step(scroll.visible_rect(), dragging.cursor.1);
+            let mut scrolled = scroll;
+            scrolled.set_scroll_y(scroll.scroll_y() + step);
+            (scrolled.scroll_y() != scroll.scroll_y()).then(|| (scroll_ctx.clone(), scrolled))
+        }
+        _ => None,
+    };
+    let mut ticked = false;
+    context.create_interval(AUTO_SCROLL_INTERVAL, auto_scroll.is_none(), || {
+        ticked = true
+    });
+    if let (true, Some((scroll_ctx, scrolled))) = (ticked, auto_scroll) {
+        // Scrolling re-renders the scroll box, so it waits until this render is done
+        context.after_render(move |_| scroll_ctx.set(scrolled));
+    }
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Layout.into(),
+                ..Default::default()
+            })
+            .with_style(&props.styles)
+            .with_style(Style {
+                width: Units::Stretch(1.0).into(),
+                height: Units::Pixels(item_height * items.len() as f32).into(),
+                ..Default::default()
+            }),
+    );
+
+    let indicator = insertion.filter(|insertion| {
+        // Dropping an item right before or after itself doesn't move it
+        let from = dragging.as_ref().map_or(0, |dragging| dragging.from);
+        *insertion != from && *insertion != from + 1
+    });
+
+    let slot_items = current_slots
+        .0
+        .iter()
+        .map(|key| {
+            key.as_ref().and_then(|key| {
+                let index = keys.iter().position(|item_key| item_key == key)?;
+                Some((index, items[index].clone()))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    rsx! {
+        <>
+            {VecTracker::from(slot_items.iter().cloned().map(|slot_item| {
+                let (index, item) = match slot_item {
+                    Some(slot_item) => slot_item,
+                    None => {
+                        // An empty slot, kept so the other items don't shift over
+                        let empty_styles = Style {
+                            position_type: PositionType::SelfDirected.into(),
+                            width: Units::Pixels(0.0).into(),
+                            height: Units::Pixels(0.0).into(),
+                            ..Default::default()
+                        };
+                        return constructor! {
+                            <Background styles={Some(empty_styles)} />
+                        };
+                    }
+                };
+
+                let key = item.key().to_string();
+                let is_dragged = dragging.as_ref().map_or(false, |dragging| dragging.key == key);
+                let top = match &dragging {
+                    Some(dragging) if is_dragged => {
+                        dragging.cursor.1 - dragging.grab_offset - list_top
+                    }
+                    _ => index as f32 * item_height,
+                };
+
+                let slot_styles = Style {
+                    background_color: Color::TRANSPARENT.into(),
+                    cursor: if disabled {
+                        CursorIcon::Default
+                    } else if is_dragged {
+                        CursorIcon::Grabbing
+                    } else {
+                        CursorIcon::Grab
+                    }
+                    .into(),
+                    position_type: PositionType::SelfDirected.into(),
+                    top: Units::Pixels(top).into(),
+                    width: Units::Stretch(1.0).into(),
+                    height: Units::Pixels(item_height).into(),
+                    ..Default::default()
+                };
+
+                let drag = drag.clone();
+                let on_reorder = on_reorder.clone();
+                let count = items.len();
+                let slot_handler = OnEvent::new(move |ctx, event| {
+                    let list_top = match ctx.get_layout(&list_id) {
+                        Some(layout) => layout.posy,
+                        None => return,
+                    };
+                    match event.event_type {
+                        EventType::MouseDown(data) => {
+                            ctx.capture_cursor(event.current_target);
+                            drag.set(Some(SortDrag {
+                                key: key.clone(),
+                                from: index,
+                                grab_offset: data.position.1
+                                    - (list_top + index as f32 * item_height),
+                                cursor: data.position,
+                            }));
+                        }
+                        EventType::Hover(data) => {
+                            if let Some(current) = drag.get().filter(|current| current.key == key) {
+                                drag.set(Some(SortDrag {
+                                    cursor: data.position,
+                                    ..current
+                                }));
+                            }
+                        }
+                        EventType::MouseUp(data) => {
+                            ctx.release_cursor(event.current_target);
+                            if let Some(current) = drag.get().filter(|current| current.key == key) {
+                                let dropped = SortDrag {
+                                    cursor: data.position,
+                                    ..current
+                                };
+                                let insertion =
+                                    dropped.insertion_index(list_top, item_height, count);
+                                let to = dropped.target_index(insertion);
+                                if to != dropped.from {
+                                    if let Some(ref on_reorder) = on_reorder {
+                                        on_reorder.call((dropped.from, to));
+                                    }
+                                }
+                            }
+                            drag.set(None);
+                        }
+                        _ => {}
+                    }
+                });
+                let slot_handler = if disabled { None } else { Some(slot_handler) };
+
+                let children = Some(item.children());
+                constructor! {
+                    <Background on_event={slot_handler} styles={Some(slot_styles)}>
+                        <KeyedFragment key={item.key().to_string()}>
+                            {children}
+                        </KeyedFragment>
+                    </Background>
+                }
+            }))}
+            {VecTracker::from(indicator.into_iter().map(|insertion| {
+                let indicator_styles = Style {
+                    background_color: indicator_color
+                        .unwrap_or(Color::new(0.26, 0.52, 0.96, 1.0))
+                        .into(),
+                    pointer_events: PointerEvents::None.into(),
+                    position_type: PositionType::SelfDirected.into(),
+                    top: Units::Pixels(insertion as f32 * item_height - 1.0).into(),
+                    width: Units::Stretch(1.0).into(),
+                    height: Units::Pixels(2.0).into(),
+                    ..Default::default()
+                };
+                constructor! {
+                    <Background styles={Some(indicator_styles)} />
+                }
+            }))}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_core::layout_cache::Rect;
+
+    use super::{auto_scroll_step, reorder, SortDrag, SortableSlots, AUTO_SCROLL_STEP};
+
+    #[test]
+    fn should_reorder_items() {
+        let mut items = vec!["a", "b", "c", "d"];
+        reorder(&mut items, 0, 2);
+        assert_eq!(vec!["b", "c", "a", "d"], items);
+        reorder(&mut items, 3, 0);
+        assert_eq!(vec!["d", "b", "c", "a"], items);
+
+        // Out of bounds indices are ignored
+        reorder(&mut items, 4, 0);
+        reorder(&mut items, 0, 4);
+        assert_eq!(vec!["d", "b", "c", "a"], items);
+    }
+
+    #[test]
+    fn should_keep_slots_of_reordered_items() {
+        let mut slots = SortableSlots::default();
+        assert!(slots.update(&["a", "b", "c"]));
+        assert!(!slots.update(&["c", "a", "b"]));
+        assert_eq!(
+            vec![Some("a"), Some("b"), Some("c")],
+            slots.0.iter().map(Option::as_deref).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_reuse_slots_of_removed_items() {
+        let mut slots = SortableSlots::default();
+        slots.update(&["a", "b", "c"]);
+
+        // Removed items leave their slot empty, and trailing empty slots are dropped
+        assert!(slots.update(&["a", "b"]));
+        assert!(slots.update(&["b"]));
+        assert_eq!(
+            vec![None, Some("b")],
+            slots.0.iter().map(Option::as_deref).collect::<Vec<_>>()
+        );
+
+        assert!(slots.update(&["b", "d"]));
+        assert_eq!(
+            vec![Some("d"), Some("b")],
+            slots.0.iter().map(Option::as_deref).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_find_insertion_index() {
+        let drag = |cursor_y: f32| SortDrag {
+            key: String::from("a"),
+            from: 1,
+            grab_offset: 10.0,
+            cursor: (0.0, cursor_y),
+        };
+
+        // With the list at 100 and items 20 tall, grabbed 10 below the top of the item
+        assert_eq!(0, drag(95.0).insertion_index(100.0, 20.0, 3));
+        assert_eq!(1, drag(119.0).insertion_index(100.0, 20.0, 3));
+        assert_eq!(1, drag(121.0).insertion_index(100.0, 20.0, 3));
+        assert_eq!(2, drag(131.0).insertion_index(100.0, 20.0, 3));
+        assert_eq!(3, drag(500.0).insertion_index(100.0, 20.0, 3));
+        assert_eq!(0, drag(-500.0).insertion_index(100.0, 20.0, 3));
+    }
+
+    #[test]
+    fn should_target_index_after_removal() {
+        let drag = SortDrag {
+            key: String::from("a"),
+            from: 1,
+            grab_offset: 0.0,
+            cursor: (0.0, 0.0),
+        };
+        assert_eq!(0, drag.target_index(0));
+        assert_eq!(1, drag.target_index(2));
+        assert_eq!(2, drag.target_index(3));
+    }
+
+    #[test]
+    fn should_auto_scroll_near_edges() {
+        let visible = Rect {
+            posx: 0.0,
+            posy: 100.0,
+            width: 200.0,
+            height: 200.0,
+            z_index: 0.0,
+        };
+        assert_eq!(AUTO_SCROLL_STEP, auto_scroll_step(visible, 110.0));
+        assert_eq!(0.0, auto_scroll_step(visible, 200.0));
+        assert_eq!(-AUTO_SCROLL_STEP, auto_scroll_step(visible, 290.0));
+    }
+}
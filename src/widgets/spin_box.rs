@@ -17,6 +17,9 @@ use kayak_render_macros::use_state;
 
 use crate::widgets::{Background, Clip, OnChange, Text};
 
+use super::disabled::apply_disabled_styles;
+use super::text_editing::is_backspace;
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum SpinBoxStyle {
     Horizontal,
@@ -31,7 +34,13 @@ impl Default for SpinBoxStyle {
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct SpinBoxProps {
-    /// If true, prevents the widget from being focused (and consequently edited)
+    /// If true, this widget is focused when it's first mounted
+    ///
+    /// If several widgets autofocus at once, the last one declared wins.
+    pub autofocus: bool,
+    /// If true, disables this widget, preventing it from being focused (and consequently edited)
+    ///
+    /// If false, the widget inherits the disabled state of its parent.
     pub disabled: bool,
     /// A callback for when the text value was changed
     pub on_change: Option<OnChange>,
@@ -84,6 +93,7 @@ impl SpinBoxProps {
 impl Default for SpinBoxProps {
     fn default() -> SpinBoxProps {
         SpinBoxProps {
+            autofocus: Default::default(),
             incr_str: "+".into(),
             decr_str: "-".into(),
             disabled: Default::default(),
@@ -126,7 +136,26 @@ impl WidgetProps for SpinBoxProps {
     }
 
     fn get_focusable(&self) -> Option<bool> {
-        Some(!self.disabled)
+        Some(true)
+    }
+
+    fn get_autofocus(&self) -> bool {
+        self.autofocus
+    }
+
+    fn get_disabled(&self) -> Option<bool> {
+        self.disabled.then_some(true)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.min_val <= self.max_val {
+            Ok(())
+        } else {
+            Err(format!(
+                "`min_val` ({}) must not exceed `max_val` ({})",
+                self.min_val, self.max_val
+            ))
+        }
     }
 }
 
@@ -178,7 +207,7 @@ pub fn SpinBox(props: SpinBoxProps) {
             }),
     );
 
-    let background_styles = Style {
+    let mut background_styles = Style {
         background_color: Color::new(0.176, 0.196, 0.215, 1.0).into(),
         border_radius: Corner::all(5.0).into(),
         height: Units::Pixels(26.0).into(),
@@ -187,6 +216,13 @@ pub fn SpinBox(props: SpinBoxProps) {
         ..Default::default()
     };
 
+    if context.is_disabled() {
+        if let Some(styles) = &mut props.styles {
+            apply_disabled_styles(styles);
+        }
+        apply_disabled_styles(&mut background_styles);
+    }
+
     let has_focus = context.create_state(FocusSpinbox(false)).unwrap();
 
     let mut current_value = value.clone();
@@ -343,10 +379,3 @@ pub fn SpinBox(props: SpinBoxProps) {
         }
     }
 }
-
-/// Checks if the given character contains the "Backspace" sequence
-///
-/// Context: [Wikipedia](https://en.wikipedia.org/wiki/Backspace#Common_use)
-fn is_backspace(c: char) -> bool {
-    c == '\u{8}' || c == '\u{7f}'
-}
@@ -0,0 +1,370 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use crate::core::{
+    constructor,
+    render_command::RenderCommand,
+    rsx,
+    styles::{Style, StyleProp},
+    widget, Bound, Children, Index, KayakContextRef, MutableBound, OnEvent, WidgetProps,
+};
+
+use crate::widgets::If;
+
+/// Data provided by a [`Suspense`] widget to track which of its descendants are still loading
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SuspenseData {
+    /// The descendants that are currently pending
+    pub pending: HashSet<Index>,
+}
+
+impl SuspenseData {
+    /// Returns true if any descendant is still pending
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+/// Marks the current widget as pending (or no longer pending) within its closest [`Suspense`]
+///
+/// While any widget within a `Suspense` is pending, its fallback is shown in place of its children.
+/// A widget that's pending should mark itself as no longer pending once its data has loaded. A
+/// widget that's removed while pending no longer counts as pending. [`use_async`] does all of this
+/// for data loaded in the background.
+///
+/// This does nothing if the current widget isn't within a `Suspense`.
+///
+/// ```ignore
+/// let (data, set_data, ..) = use_state!(None::<String>);
+/// set_suspended(context, data.is_none());
+/// ```
+pub fn set_suspended(context: &mut KayakContextRef, pending: bool) {
+    let id = match context.current_id() {
+        Some(id) => id,
+        None => return,
+    };
+
+    if let Some(suspense) = context.create_consumer::<SuspenseData>() {
+        let mut data = suspense.get();
+        let changed = if pending {
+            data.pending.insert(id)
+        } else {
+            data.pending.remove(&id)
+        };
+        if changed {
+            suspense.set(data);
+        }
+
+        if pending {
+            context.on_unmount(SUSPENDED_KEY, move || {
+                let mut data = suspense.get();
+                if data.pending.remove(&id) {
+                    suspense.set(data);
+                }
+            });
+        } else {
+            context.cancel_on_unmount(SUSPENDED_KEY);
+        }
+    }
+}
+
+/// The key of the unmount handler that stops a removed widget from being pending
+const SUSPENDED_KEY: &str = "kayak_ui::suspense";
+
+/// Loads data in the background, [suspending](set_suspended) the current widget until it's loaded
+///
+/// The given function runs on another thread, once, when the widget is first rendered. Until it
+/// returns, this returns `None` and the widget is pending within its closest [`Suspense`]. Once it
+/// has returned, the widget renders again with its data.
+///
+/// ```ignore
+/// let user_id = props.user_id;
+/// let profile = use_async(context, move || fetch_profile(user_id));
+/// ```
+pub fn use_async<T, F>(context: &mut KayakContextRef, load: F) -> Option<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let data = context.create_state::<Option<T>>(None)?;
+    let loaded = data.clone();
+    let load = Mutex::new(Some(load));
+    context.create_effect(
+        move || {
+            if let Some(load) = load.lock().ok().and_then(|mut load| load.take()) {
+                let loaded = loaded.clone();
+                std::thread::spawn(move || loaded.set(Some(load())));
+            }
+        },
+        &[],
+    );
+
+    let data = data.get();
+    set_suspended(context, data.is_none());
+    data
+}
+
+/// Props used by the [`Suspense`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct SuspenseProps {
+    /// The widgets to show while any descendant is pending
+    pub fallback: Option<Children>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+}
+
+#[widget]
+/// A widget that shows a fallback while any of its descendants are loading
+///
+/// Descendants signal that they're loading with [`set_suspended`] (or [`use_async`]). The children
+/// stay mounted (so they can finish loading) but are hidden, and can neither be interacted with nor
+/// focused, until none of them are pending. Since the children are rendered before deciding, the
+/// fallback is only shown while something is pending, even on the first frame.
+///
+/// Each descendant only affects its closest `Suspense`, so nested ones resolve independently.
+///
+/// # Props
+///
+/// __Type:__ [`SuspenseProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```ignore
+/// # use kayak_ui::core::{rsx, widget};
+///
+/// #[widget]
+/// fn MyWidget() {
+///   rsx! {
+///     <Suspense fallback={Some(loading_spinner)}>
+///       <UserProfile />
+///     </Suspense>
+///   }
+/// }
+/// ```
+pub fn Suspense(props: SuspenseProps) {
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(RenderCommand::Layout),
+        ..props.styles.clone().unwrap_or_default()
+    });
+
+    let suspense = context.create_provider(SuspenseData::default());
+
+    let content = children;
+    context.add_widget(
+        constructor! {
+            <SuspenseContent content={content} />
+        },
+        0,
+    );
+
+    // The content was just rendered, so its descendants have signaled whether they're pending
+    let is_pending = suspense.get().is_pending();
+    let children = props.fallback.clone();
+    context.add_widget(
+        constructor! {
+            <If condition={is_pending}>
+                {children}
+            </If>
+        },
+        1,
+    );
+}
+
+/// Props used by the [`SuspenseContent`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+struct SuspenseContentProps {
+    content: Option<Children>,
+    #[prop_field(Styles)]
+    styles: Option<Style>,
+}
+
+#[widget]
+/// A container that hides the content of a [`Suspense`] (without unmounting it) while pending
+fn SuspenseContent(props: SuspenseContentProps) {
+    props.styles = Some(Style {
+        render_command: StyleProp::Value(RenderCommand::Layout),
+        ..Default::default()
+    });
+
+    let children = props.content.clone();
+    rsx! {
+        <>
+            {children}
+        </>
+    }
+
+    let is_pending = context
+        .create_consumer::<SuspenseData>()
+        .map_or(false, |suspense| suspense.get().is_pending());
+    context.set_inactive(is_pending);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::{set_suspended, use_async, Suspense, SuspenseProps};
+    use crate::core::{
+        mount_widget, widget, Binding, Bound, Children, Index, KayakContext, MutableBound, Widget,
+        WidgetProps,
+    };
+
+    #[widget]
+    fn Placeholder() {}
+
+    #[derive(WidgetProps, Clone, Debug, Default, PartialEq)]
+    struct LoaderProps {
+        pending: Binding<bool>,
+        /// Whether the loader is mounted at all
+        mounted: Binding<bool>,
+        #[prop_field(Focusable)]
+        focusable: Option<bool>,
+    }
+
+    #[widget]
+    fn Loader(props: LoaderProps) {
+        context.bind(&props.pending);
+        set_suspended(context, props.pending.get());
+    }
+
+    #[widget]
+    fn LoaderSlot(props: LoaderProps) {
+        context.bind(&props.mounted);
+        if props.mounted.get() {
+            context.add_widget(
+                Loader::constructor(LoaderProps {
+                    focusable: Some(true),
+                    ..props.clone()
+                }),
+                0,
+            );
+        }
+    }
+
+    /// Held by tests to keep `AsyncLoader` from finishing
+    static LOADING: Mutex<()> = Mutex::new(());
+
+    #[widget]
+    fn AsyncLoader() {
+        use_async(context, || {
+            let _loading = LOADING.lock();
+            42
+        });
+    }
+
+    /// Mounts a `Suspense` around a loader, returning the bindings to whether its pending and
+    /// mounted
+    fn mount(context: &mut KayakContext, pending: bool) -> (Binding<bool>, Binding<bool>) {
+        let props = LoaderProps {
+            pending: Binding::new(pending),
+            mounted: Binding::new(true),
+            focusable: None,
+        };
+        let bindings = (props.pending.clone(), props.mounted.clone());
+        let suspense = SuspenseProps {
+            fallback: Some(Children::new(|_, context| {
+                context.add_widget(Placeholder::default(), 0);
+                context.commit();
+            })),
+            children: Some(Children::new(move |_, context| {
+                context.add_widget(LoaderSlot::constructor(props.clone()), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(context, Suspense::constructor(suspense));
+        bindings
+    }
+
+    fn find(context: &KayakContext, name: &str) -> Option<Index> {
+        let widget_manager = &context.widget_manager;
+        widget_manager.tree.flatten().into_iter().find(|id| {
+            widget_manager
+                .get_name(id)
+                .map_or(false, |widget| widget.ends_with(name))
+        })
+    }
+
+    #[test]
+    fn should_not_show_the_fallback_when_nothing_is_pending() {
+        let mut context = KayakContext::new();
+        mount(&mut context, false);
+
+        assert_eq!(None, find(&context, "::Placeholder"));
+        let loader = find(&context, "::Loader").unwrap();
+        assert!(!context.widget_manager.is_inactive(loader));
+    }
+
+    #[test]
+    fn should_hide_pending_content_behind_the_fallback() {
+        let mut context = KayakContext::new();
+        let (pending, _) = mount(&mut context, true);
+
+        // Shown from the first frame
+        assert!(find(&context, "::Placeholder").is_some());
+        let loader = find(&context, "::Loader").unwrap();
+        assert!(context.widget_manager.is_inactive(loader));
+        assert!(!context.widget_manager.focus_tree.contains(loader));
+
+        pending.set(false);
+        context.render();
+        context.render();
+        assert_eq!(None, find(&context, "::Placeholder"));
+        assert!(!context.widget_manager.is_inactive(loader));
+        assert!(context.widget_manager.focus_tree.contains(loader));
+    }
+
+    #[test]
+    fn should_wait_for_async_data() {
+        let mut context = KayakContext::new();
+        let loading = LOADING.lock();
+        let suspense = SuspenseProps {
+            fallback: Some(Children::new(|_, context| {
+                context.add_widget(Placeholder::default(), 0);
+                context.commit();
+            })),
+            children: Some(Children::new(|_, context| {
+                context.add_widget(AsyncLoader::default(), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Suspense::constructor(suspense));
+        assert!(find(&context, "::Placeholder").is_some());
+
+        // The data is loaded on another thread
+        drop(loading);
+        for _ in 0..1000 {
+            if find(&context, "::Placeholder").is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            context.render();
+        }
+        assert_eq!(None, find(&context, "::Placeholder"));
+    }
+
+    #[test]
+    fn should_stop_waiting_for_widgets_removed_while_pending() {
+        let mut context = KayakContext::new();
+        let (_, mounted) = mount(&mut context, true);
+
+        mounted.set(false);
+        context.render();
+        context.render();
+        assert_eq!(None, find(&context, "::Loader"));
+        assert_eq!(None, find(&context, "::Placeholder"));
+    }
+}
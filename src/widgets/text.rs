@@ -1,8 +1,16 @@
+use std::time::Instant;
+
 use crate::core::{
+    constructor,
     render_command::RenderCommand,
-    styles::{Style, StyleProp},
-    widget, CursorIcon, OnEvent, OnLayout, WidgetProps,
+    rsx,
+    styles::{PositionType, Style, StyleProp, TextOutline, TextShadow, TextTransform, Units},
+    use_state, widget, Bound, ClickCount, Color, CursorIcon, EventType, KeyCode, OnEvent, OnLayout,
+    PointerEvents, VecTracker, WidgetProps,
 };
+use crate::widgets::Background;
+
+use super::text_editing::{get_laid_out_content, get_text_layout};
 
 /// Props used by the [`Text`] widget
 #[derive(WidgetProps, Debug, PartialEq, Clone)]
@@ -13,9 +21,31 @@ pub struct TextProps {
     ///
     /// The given font must already be loaded into the [`KayakContext`](kayak_core::KayakContext)
     pub font: Option<String>,
+    /// The extra space added after each character (in pixels)
+    ///
+    /// Negative values tighten the text. This sets the [`letter_spacing`](Style::letter_spacing)
+    /// style.
+    pub letter_spacing: Option<f32>,
     /// The height of a line of text (currently in pixels)
     pub line_height: Option<f32>,
-    /// If true, displays the default text cursor when hovered.
+    /// An outline drawn around the glyphs, to keep the text readable over images
+    ///
+    /// This sets the [`text_outline`](Style::text_outline) style.
+    pub outline: Option<TextOutline>,
+    /// If true, the text can be selected with the cursor and copied (with `Ctrl+C`)
+    ///
+    /// Click and drag to select a range of text, double-click to select a word, or triple-click to
+    /// select a line. Selectable text is focusable unless `focusable` is set, since it needs focus
+    /// to receive the copy shortcut. The selected text is copied to the context's
+    /// [`Clipboard`](kayak_core::Clipboard) as it's displayed (i.e. after any
+    /// [`text_transform`](Self::text_transform)).
+    pub selectable: bool,
+    /// A shadow drawn behind the glyphs, to keep the text readable over images
+    ///
+    /// This sets the [`text_shadow`](Style::text_shadow) style.
+    pub shadow: Option<TextShadow>,
+    /// If true, displays the default text cursor when hovered (which is always the case for
+    /// [`selectable`](Self::selectable) text).
     ///
     /// This _will_ override the `cursor` style.
     pub show_cursor: bool,
@@ -23,6 +53,16 @@ pub struct TextProps {
     ///
     /// Negative values have no effect
     pub size: f32,
+    /// How the casing of the text is changed when it's displayed
+    ///
+    /// The content is left unchanged, so it can be uppercased (for example) without changing the
+    /// string itself. This sets the [`text_transform`](Style::text_transform) style.
+    pub text_transform: Option<TextTransform>,
+    /// The extra space added to each space between words (in pixels)
+    ///
+    /// Negative values tighten the text. This sets the [`word_spacing`](Style::word_spacing)
+    /// style.
+    pub word_spacing: Option<f32>,
     #[prop_field(Styles)]
     pub styles: Option<Style>,
     #[prop_field(OnEvent)]
@@ -38,9 +78,15 @@ impl Default for TextProps {
         Self {
             content: String::new(),
             font: None,
+            letter_spacing: None,
             line_height: None,
+            outline: None,
+            selectable: false,
+            shadow: None,
             show_cursor: false,
             size: -1.0,
+            text_transform: None,
+            word_spacing: None,
             styles: None,
             on_event: None,
             on_layout: None,
@@ -75,15 +121,222 @@ pub fn Text(props: TextProps) {
     if let Some(ref font) = props.font {
         styles.font = StyleProp::Value(font.clone());
     }
-    if props.show_cursor {
+    if props.show_cursor || props.selectable {
         styles.cursor = StyleProp::Value(CursorIcon::Text);
     }
     if props.size >= 0.0 {
         styles.font_size = StyleProp::Value(props.size);
     }
+    if let Some(letter_spacing) = props.letter_spacing {
+        styles.letter_spacing = StyleProp::Value(letter_spacing);
+    }
     if let Some(line_height) = props.line_height {
         styles.line_height = StyleProp::Value(line_height);
     }
+    if let Some(text_transform) = props.text_transform {
+        styles.text_transform = StyleProp::Value(text_transform);
+    }
+    if let Some(word_spacing) = props.word_spacing {
+        styles.word_spacing = StyleProp::Value(word_spacing);
+    }
+    if let Some(outline) = props.outline {
+        styles.text_outline = StyleProp::Value(outline);
+    }
+    if let Some(shadow) = props.shadow {
+        styles.text_shadow = StyleProp::Value(shadow);
+    }
 
     props.styles = Some(styles.with_style(&props.styles));
+
+    // Selectable text needs focus in order to receive the copy shortcut (the focusability from
+    // `props` has already been registered by now, so it's set on the widget itself instead)
+    let is_focusable = props.selectable && props.focusable.is_none();
+    context.set_focusable(is_focusable.then_some(true));
+
+    if props.selectable {
+        // The selection is stored as its anchor (where it started) and focus (where it ended)
+        let (selection, set_selection, ..) = use_state!(None::<(usize, usize)>);
+        let (is_selecting, set_is_selecting, ..) = use_state!(false);
+        let clicks = context.create_state(ClickCount::default()).unwrap().get();
+
+        let widget_id = context.current_id().unwrap_or_default();
+        let on_event = props.on_event.clone();
+        props.on_event = Some(OnEvent::wrap(widget_id, on_event, move |ctx, event| {
+            match event.event_type {
+                EventType::MouseDown(data) => {
+                    // The layout's indices match the content as it's displayed, which may have
+                    // been transformed
+                    if let (Some((text_layout, origin)), Some(content)) = (
+                        get_text_layout(ctx, event.current_target),
+                        get_laid_out_content(ctx, event.current_target),
+                    ) {
+                        let position = (data.position.0 - origin.0, data.position.1 - origin.1);
+                        let index = text_layout.hit_test(position);
+                        match clicks.press(data.position, Instant::now()) {
+                            1 => {
+                                ctx.capture_cursor(event.current_target);
+                                set_is_selecting(true);
+                                set_selection(Some((index, index)));
+                            }
+                            2 => {
+                                let word = text_layout.word_range(&content, index);
+                                set_selection(Some((word.start, word.end)));
+                            }
+                            _ => {
+                                let line = text_layout.line_range(index);
+                                set_selection(Some((line.start, line.end)));
+                            }
+                        }
+                    }
+                }
+                EventType::Hover(data) if is_selecting => {
+                    if let (Some((anchor, _)), Some((text_layout, origin))) =
+                        (selection, get_text_layout(ctx, event.current_target))
+                    {
+                        let position = (data.position.0 - origin.0, data.position.1 - origin.1);
+                        set_selection(Some((anchor, text_layout.hit_test(position))));
+                    }
+                }
+                EventType::MouseUp(..) if is_selecting => {
+                    ctx.release_cursor(event.current_target);
+                    set_is_selecting(false);
+                }
+                EventType::KeyDown(data) => {
+                    let is_copy = data.key() == KeyCode::C
+                        && (data.is_ctrl_pressed() || data.is_meta_pressed());
+                    if let (true, Some((anchor, focus))) = (is_copy, selection) {
+                        if let (Some((text_layout, _)), Some(content)) = (
+                            get_text_layout(ctx, event.current_target),
+                            get_laid_out_content(ctx, event.current_target),
+                        ) {
+                            let chars =
+                                text_layout.char_range(anchor.min(focus)..anchor.max(focus));
+                            if !chars.is_empty() {
+                                let text = content
+                                    .chars()
+                                    .skip(chars.start)
+                                    .take(chars.len())
+                                    .collect();
+                                ctx.clipboard().set_text(text);
+                                event.stop_propagation();
+                            }
+                        }
+                    }
+                }
+                EventType::Blur => set_selection(None),
+                _ => {}
+            }
+        }));
+
+        let highlights = match (selection, context.current_id()) {
+            (Some((anchor, focus)), Some(widget_id)) if anchor != focus => {
+                // Keep the highlights in sync with the text if it's laid out again (e.g. wrapped)
+                context.use_layout();
+                get_text_layout(context, widget_id)
+                    .map(|(text_layout, _)| {
+                        text_layout.selection_rects(anchor.min(focus)..anchor.max(focus))
+                    })
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+
+        rsx! {
+            <>
+                {VecTracker::from(highlights.iter().map(|&(position, size)| {
+                    let highlight_styles = Style {
+                        background_color: StyleProp::Value(Color::new(0.26, 0.52, 0.96, 0.4)),
+                        pointer_events: StyleProp::Value(PointerEvents::None),
+                        position_type: StyleProp::Value(PositionType::SelfDirected),
+                        left: StyleProp::Value(Units::Pixels(position.0)),
+                        top: StyleProp::Value(Units::Pixels(position.1)),
+                        width: StyleProp::Value(Units::Pixels(size.0)),
+                        height: StyleProp::Value(Units::Pixels(size.1)),
+                        ..Default::default()
+                    };
+                    constructor! {
+                        <Background styles={Some(highlight_styles)} />
+                    }
+                }))}
+            </>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Text, TextProps};
+    use crate::core::styles::{Style, StyleProp, TextTransform, Units};
+    use crate::core::{
+        mount_widget, Children, InputEvent, KayakContext, KeyCode, Widget, DEFAULT_FONT,
+    };
+    use crate::font::{KayakFont, Sdf};
+    use crate::widgets::{Background, BackgroundProps, Element, ElementProps};
+
+    fn make_font() -> KayakFont {
+        let bytes = std::fs::read("kayak_font/assets/roboto.kayak_font")
+            .expect("a `roboto.kayak_font` file in the `kayak_font/assets/` directory");
+
+        #[cfg(feature = "bevy_renderer")]
+        return KayakFont::new(Sdf::from_bytes(&bytes), bevy::asset::Handle::default());
+
+        #[cfg(not(feature = "bevy_renderer"))]
+        return KayakFont::new(Sdf::from_bytes(&bytes));
+    }
+
+    fn press_key(context: &mut KayakContext, key: KeyCode) {
+        context.process_events(vec![InputEvent::Keyboard {
+            key,
+            is_pressed: true,
+        }]);
+    }
+
+    #[test]
+    fn should_copy_selected_text_as_displayed() {
+        let mut context = KayakContext::new();
+        context.set_asset(DEFAULT_FONT, make_font());
+        let parent = BackgroundProps {
+            styles: Some(Style {
+                width: StyleProp::Value(Units::Pixels(200.0)),
+                height: StyleProp::Value(Units::Pixels(50.0)),
+                ..Default::default()
+            }),
+            children: Some(Children::new(move |_, context| {
+                let text = TextProps {
+                    content: String::from("straße straße"),
+                    selectable: true,
+                    size: 14.0,
+                    text_transform: Some(TextTransform::Uppercase),
+                    ..Default::default()
+                };
+                context.add_widget(Text::constructor(text), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        let root = ElementProps {
+            children: Some(Children::new(move |_, context| {
+                context.add_widget(Background::constructor(parent.clone()), 0);
+                context.commit();
+            })),
+            ..Default::default()
+        };
+        mount_widget(&mut context, Element::constructor(root));
+
+        // Double-click the second word, which is shifted over by the "SS" in the first one
+        context.process_events(vec![InputEvent::MouseMoved((100.0, 5.0))]);
+        for _ in 0..2 {
+            context.process_events(vec![InputEvent::MouseLeftPress]);
+            context.render();
+            context.process_events(vec![InputEvent::MouseLeftRelease]);
+            context.render();
+        }
+
+        press_key(&mut context, KeyCode::LControl);
+        press_key(&mut context, KeyCode::C);
+        assert_eq!(
+            Some(String::from("STRASSE")),
+            context.clipboard().get_text()
+        );
+    }
 }
@@ -0,0 +1,649 @@
+use kayak_core::{CursorIcon, OnLayout};
+
+use crate::core::{
+    constructor,
+    render_command::RenderCommand,
+    rsx,
+    styles::{Corner, Edge, PositionType, Style, StyleProp, Units},
+    widget, Binding, Bound, Children, Color, EventType, Handler, Index, KayakContextRef, KeyCode,
+    MutableBound, OnEvent, PointerEvents, ScrollUnit, VecTracker, WidgetProps,
+};
+use crate::widgets::{Background, Clip, Focus, TextHistory};
+
+use super::disabled::apply_disabled_styles;
+use super::text_editing::{
+    get_laid_out_content, get_text_layout, is_backspace, Preedit, TextCursor,
+};
+use super::text_history::{EditKind, EditSnapshot, HistoryShortcut};
+
+/// The height of a line of text within a [`TextArea`], in pixels
+const LINE_HEIGHT: f32 = 22.0;
+
+/// Props used by the [`TextArea`] widget
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct TextAreaProps {
+    /// If true, this widget is focused when it's first mounted
+    ///
+    /// If several widgets autofocus at once, the last one declared wins.
+    pub autofocus: bool,
+    /// If true, disables this widget, preventing it from being focused (and consequently edited)
+    ///
+    /// If false, the widget inherits the disabled state of its parent.
+    pub disabled: bool,
+    /// The undo history of the text area
+    ///
+    /// The text area keeps its own history (handling `Ctrl+Z` and `Ctrl+Y`), so this only needs
+    /// to be set in order to undo or redo edits programmatically.
+    pub history: Option<TextHistory>,
+    /// The maximum number of lines the value can have
+    ///
+    /// This limits the line breaks the user can insert, so lines that are only wrapped don't
+    /// count towards it. If `None`, any number of lines can be entered.
+    pub max_lines: Option<usize>,
+    /// A callback for when the text value was changed
+    pub on_change: Option<Handler<String>>,
+    /// The text to display when the user input is empty
+    pub placeholder: Option<String>,
+    /// The user input
+    ///
+    /// This is a controlled state. You _must_ set this to the value to you wish to be displayed.
+    /// You can use the [`on_change`](Self::on_change) callback to update this prop as the user types.
+    pub value: String,
+    pub styles: Option<Style>,
+    pub children: Option<Children>,
+    pub on_event: Option<OnEvent>,
+    pub on_layout: Option<OnLayout>,
+    pub focusable: Option<bool>,
+}
+
+impl WidgetProps for TextAreaProps {
+    fn get_children(&self) -> Option<Children> {
+        self.children.clone()
+    }
+
+    fn set_children(&mut self, children: Option<Children>) {
+        self.children = children;
+    }
+
+    fn get_styles(&self) -> Option<Style> {
+        self.styles.clone()
+    }
+
+    fn get_on_event(&self) -> Option<OnEvent> {
+        self.on_event.clone()
+    }
+
+    fn get_on_layout(&self) -> Option<OnLayout> {
+        self.on_layout.clone()
+    }
+
+    fn get_focusable(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn get_autofocus(&self) -> bool {
+        self.autofocus
+    }
+
+    fn get_disabled(&self) -> Option<bool> {
+        self.disabled.then_some(true)
+    }
+}
+
+/// The ID of the widget rendering the text of a [`TextArea`]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+struct ContentId(Option<Index>);
+
+/// Whether the user is currently selecting text in a [`TextArea`] with the cursor
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+struct Selecting(bool);
+
+/// The distance a [`TextArea`] has been scrolled down, in pixels
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+struct ScrollTop(f32);
+
+#[widget]
+/// A widget that displays a multi-line text input field
+///
+/// The text wraps to the width of the field, and pressing `Enter` inserts a line break. When the
+/// text is taller than the field, it can be scrolled, and the field scrolls to keep the caret in
+/// view as the user types.
+///
+/// The caret can be moved with the arrow keys (where up and down move across the wrapped lines
+/// as displayed), `Home` and `End`, or by clicking. Holding `Shift` while moving the caret, or
+/// dragging with the cursor, selects text. Edits can be undone with `Ctrl+Z` and redone with
+/// `Ctrl+Y` (see [`TextHistory`]).
+///
+/// # Props
+///
+/// __Type:__ [`TextAreaProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ❌        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ✅        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{Handler, rsx, use_state, widget};
+/// # use kayak_ui::widgets::TextArea;
+/// # #[widget]
+/// # fn Example() {
+/// let (notes, set_notes, ..) = use_state!(String::new());
+/// let on_change = Handler::new(move |value: String| set_notes(value));
+///
+/// rsx! {
+///     <TextArea value={notes} on_change={Some(on_change)} max_lines={Some(10)} />
+/// }
+/// # }
+/// ```
+pub fn TextArea(props: TextAreaProps) {
+    let TextAreaProps {
+        max_lines,
+        on_change,
+        placeholder,
+        value,
+        ..
+    } = props.clone();
+
+    props.styles = Some(
+        Style::default()
+            // Required styles
+            .with_style(Style {
+                render_command: RenderCommand::Layout.into(),
+                ..Default::default()
+            })
+            // Apply any prop-given styles
+            .with_style(&props.styles)
+            // If not set by props, apply these styles
+            .with_style(Style {
+                top: Units::Pixels(0.0).into(),
+                bottom: Units::Pixels(0.0).into(),
+                height: Units::Pixels(LINE_HEIGHT * 4.0 + 10.0).into(),
+                cursor: CursorIcon::Text.into(),
+                ..Default::default()
+            }),
+    );
+
+    let mut background_styles = Style {
+        background_color: Color::new(0.176, 0.196, 0.215, 1.0).into(),
+        border_radius: Corner::all(5.0).into(),
+        height: Units::Stretch(1.0).into(),
+        padding: StyleProp::Value(Edge::all(Units::Pixels(5.0))),
+        ..Default::default()
+    };
+
+    if context.is_disabled() {
+        if let Some(styles) = &mut props.styles {
+            apply_disabled_styles(styles);
+        }
+        apply_disabled_styles(&mut background_styles);
+    }
+
+    let has_focus = context.create_state(Focus(false)).unwrap();
+    let cursor_state = context.create_state(TextCursor::default()).unwrap();
+    let selecting = context.create_state(Selecting(false)).unwrap();
+    let content_id = context.create_state(ContentId::default()).unwrap();
+    let scroll_top = context.create_state(ScrollTop::default()).unwrap();
+    let preedit = context.create_state(Preedit::default()).unwrap();
+
+    let own_history = context.create_state(TextHistory::default()).unwrap().get();
+    let history = props.history.clone().unwrap_or(own_history);
+    history.sync(&value);
+
+    let mut current_value = value.clone();
+    let mut cursor = cursor_state.get().clamped(value.chars().count());
+    let cloned_has_focus = has_focus.clone();
+    let cloned_cursor_state = cursor_state.clone();
+    let cloned_content_id = content_id.clone();
+    let cloned_scroll_top = scroll_top.clone();
+    let cloned_preedit = preedit.clone();
+
+    props.on_event = Some(OnEvent::new(move |ctx, event| {
+        let content_id = cloned_content_id.get().0;
+        let len = current_value.chars().count();
+        let previous_cursor = cursor;
+        let mut edit = None;
+
+        match event.event_type {
+            EventType::Focus => cloned_has_focus.set(Focus(true)),
+            EventType::Blur => {
+                cloned_has_focus.set(Focus(false));
+                cloned_preedit.set(Preedit::default());
+            }
+            EventType::MouseDown(data) => {
+                if let Some(index) = content_id.and_then(|id| ctx.hit_test_text(&id, data.position))
+                {
+                    ctx.capture_cursor(event.current_target);
+                    selecting.set(Selecting(true));
+                    cursor.move_to(index.min(len), false);
+                }
+            }
+            EventType::Hover(data) if selecting.get().0 => {
+                if let Some(index) = content_id.and_then(|id| ctx.hit_test_text(&id, data.position))
+                {
+                    cursor.move_to(index.min(len), true);
+                }
+            }
+            EventType::MouseUp(..) if selecting.get().0 => {
+                ctx.release_cursor(event.current_target);
+                selecting.set(Selecting(false));
+            }
+            EventType::Scroll(evt) => {
+                let delta = match evt.delta {
+                    ScrollUnit::Line { y, .. } => y * LINE_HEIGHT,
+                    ScrollUnit::Pixel { y, .. } => y,
+                };
+                let max = content_id.map_or(0.0, |id| max_scroll(ctx, id));
+                let top = (cloned_scroll_top.get().0 - delta).clamp(0.0, max);
+                cloned_scroll_top.set(ScrollTop(top));
+                event.stop_propagation();
+            }
+            EventType::CharInput { c } => {
+                if !cloned_has_focus.get().0 {
+                    return;
+                }
+
+                let mut next = current_value.clone();
+                let kind = if is_backspace(c) {
+                    cursor.backspace(&mut next);
+                    EditKind::Deleting
+                } else if c == '\r' || c == '\n' {
+                    let lines = current_value.matches('\n').count() + 1;
+                    if max_lines.map_or(true, |max_lines| lines < max_lines) {
+                        cursor.insert(&mut next, "\n");
+                    }
+                    EditKind::Other
+                } else if !c.is_control() {
+                    cursor.insert(&mut next, &c.to_string());
+                    EditKind::Typing
+                } else {
+                    return;
+                };
+                edit = Some((next, kind));
+            }
+            EventType::ImePreedit {
+                ref text,
+                cursor: ime_cursor,
+            } => {
+                if !cloned_has_focus.get().0 {
+                    return;
+                }
+                cloned_preedit.set(Preedit {
+                    text: text.clone(),
+                    cursor: ime_cursor,
+                });
+            }
+            EventType::ImeCommit(ref text) => {
+                if !cloned_has_focus.get().0 {
+                    return;
+                }
+                cloned_preedit.set(Preedit::default());
+                let mut next = current_value.clone();
+                cursor.insert(&mut next, text);
+                edit = Some((next, EditKind::Typing));
+            }
+            EventType::KeyDown(data) => {
+                if !cloned_has_focus.get().0 {
+                    return;
+                }
+
+                if let Some(shortcut) = HistoryShortcut::from_key(&data) {
+                    if let Some((value, restored)) = history.apply(shortcut) {
+                        current_value = value;
+                        cursor = restored.clamped(current_value.chars().count());
+                        if let Some(on_change) = &on_change {
+                            on_change.call(current_value.clone());
+                        }
+                    }
+                } else {
+                    let extend = data.is_shift_pressed();
+                    match data.key() {
+                        KeyCode::Left => cursor.move_left(extend),
+                        KeyCode::Right => cursor.move_right(len, extend),
+                        KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End => {
+                            let target = content_id.and_then(|id| {
+                                find_caret_target(ctx, id, cursor.caret, data.key())
+                            });
+                            if let Some(index) = target {
+                                cursor.move_to(index.min(len), extend);
+                            }
+                        }
+                        KeyCode::Delete => {
+                            let mut next = current_value.clone();
+                            cursor.delete(&mut next);
+                            edit = Some((next, EditKind::Deleting));
+                        }
+                        _ => return,
+                    }
+                }
+                event.stop_propagation();
+            }
+            _ => {}
+        }
+
+        if let Some((next, kind)) = edit {
+            if next != current_value {
+                // Replacing a selection is never coalesced with other edits
+                let kind = if previous_cursor.has_selection() {
+                    EditKind::Other
+                } else {
+                    kind
+                };
+                history.record(
+                    EditSnapshot {
+                        value: current_value.clone(),
+                        cursor: previous_cursor,
+                    },
+                    EditSnapshot {
+                        value: next.clone(),
+                        cursor,
+                    },
+                    kind,
+                );
+
+                current_value = next;
+                if let Some(on_change) = &on_change {
+                    on_change.call(current_value.clone());
+                }
+            }
+        }
+
+        if cursor != previous_cursor {
+            cloned_cursor_state.set(cursor);
+        }
+    }));
+
+    let cursor = if has_focus.get().0 {
+        Some(cursor_state.get().clamped(value.chars().count()))
+    } else {
+        None
+    };
+
+    // Text being composed by an IME is shown in place of the selection, where it will be inserted
+    let preedit = preedit.get();
+    let (content, cursor) = match cursor {
+        Some(cursor) if !preedit.text.is_empty() => {
+            let (content, cursor) = preedit.display(&value, cursor);
+            (content, Some(cursor))
+        }
+        _ => (value, cursor),
+    };
+
+    let is_placeholder = content.is_empty();
+    let content = if is_placeholder {
+        placeholder.unwrap_or_default()
+    } else {
+        content
+    };
+
+    let content_styles = Style {
+        color: if is_placeholder {
+            Color::new(0.5, 0.5, 0.5, 1.0).into()
+        } else {
+            StyleProp::Default
+        },
+        font_size: StyleProp::Value(14.0),
+        line_height: StyleProp::Value(LINE_HEIGHT),
+        position_type: StyleProp::Value(PositionType::SelfDirected),
+        left: StyleProp::Value(Units::Pixels(0.0)),
+        top: StyleProp::Value(Units::Pixels(-scroll_top.get().0)),
+        width: StyleProp::Value(Units::Stretch(1.0)),
+        ..Default::default()
+    };
+
+    let on_content_layout = OnLayout::new(move |_, evt| {
+        if content_id.get().0 != Some(evt.target) {
+            content_id.set(ContentId(Some(evt.target)));
+        }
+    });
+
+    rsx! {
+        <Background styles={Some(background_styles)}>
+            <Clip>
+                <TextAreaContent
+                    content={content}
+                    cursor={cursor}
+                    scroll_top={scroll_top}
+                    styles={Some(content_styles)}
+                    on_layout={Some(on_content_layout)}
+                />
+            </Clip>
+        </Background>
+    }
+}
+
+/// Props used by the [`TextAreaContent`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+struct TextAreaContentProps {
+    /// The text to display
+    content: String,
+    /// The caret and selection, if the text area has focus
+    cursor: Option<TextCursor>,
+    /// The scroll position of the text area, which is updated to keep the caret in view
+    scroll_top: Binding<ScrollTop>,
+    #[prop_field(Styles)]
+    styles: Option<Style>,
+    #[prop_field(OnLayout)]
+    on_layout: Option<OnLayout>,
+}
+
+/// The caret position (and content length) that a [`TextAreaContent`] last scrolled into view
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+struct RevealedCaret(Option<(usize, usize)>);
+
+#[widget]
+/// A widget that renders the text of a [`TextArea`], along with its caret and selection
+///
+/// The caret is positioned using the text layout calculated for rendering, which is only
+/// available once the text has been laid out. If the text changed since then, this widget renders
+/// again on the next frame to catch up.
+fn TextAreaContent(props: TextAreaContentProps) {
+    let revealed = context.create_state(RevealedCaret::default()).unwrap();
+
+    props.styles = Some(
+        Style {
+            render_command: StyleProp::Value(RenderCommand::Text {
+                content: props.content.clone(),
+            }),
+            ..Default::default()
+        }
+        .with_style(&props.styles),
+    );
+
+    context.use_layout();
+    let text_layout = match (props.cursor, context.current_id()) {
+        (Some(_), Some(widget_id)) => {
+            let is_stale = get_laid_out_content(context, widget_id)
+                .map_or(true, |content| content != props.content);
+            if is_stale {
+                context.request_render_at(context.frame_time());
+                None
+            } else {
+                get_text_layout(context, widget_id).map(|(text_layout, _)| (widget_id, text_layout))
+            }
+        }
+        _ => None,
+    };
+
+    let mut caret = None;
+    let mut highlights = Vec::new();
+    if let (Some(cursor), Some((widget_id, text_layout))) = (props.cursor, text_layout) {
+        let caret_index = text_layout.grapheme_at_char(cursor.caret);
+        let position = text_layout.caret_position(caret_index);
+        caret = Some(position);
+
+        if cursor.has_selection() {
+            let selection = cursor.selection();
+            highlights = text_layout.selection_rects(
+                text_layout.grapheme_at_char(selection.start)
+                    ..text_layout.grapheme_at_char(selection.end),
+            );
+        }
+
+        // Scroll the caret into view whenever it moves (but not when the user scrolls away)
+        let key = (cursor.caret, props.content.chars().count());
+        if revealed.get().0 != Some(key) {
+            revealed.set(RevealedCaret(Some(key)));
+            let viewport = context
+                .get_valid_parent(widget_id)
+                .and_then(|parent| context.get_layout(&parent))
+                .map(|layout| layout.height);
+            if let Some(viewport) = viewport {
+                let line_height = text_layout.properties().line_height;
+                let max = (text_layout.size().1 - viewport).max(0.0);
+                let top = props.scroll_top.get().0;
+                let next = if position.1 < top {
+                    position.1
+                } else if position.1 + line_height > top + viewport {
+                    position.1 + line_height - viewport
+                } else {
+                    top
+                };
+                let next = next.clamp(0.0, max);
+                if next != top {
+                    props.scroll_top.set(ScrollTop(next));
+                }
+            }
+        }
+    }
+
+    rsx! {
+        <>
+            {VecTracker::from(highlights.iter().map(|&(position, size)| {
+                let highlight_styles = Style {
+                    background_color: StyleProp::Value(Color::new(0.26, 0.52, 0.96, 0.4)),
+                    pointer_events: StyleProp::Value(PointerEvents::None),
+                    position_type: StyleProp::Value(PositionType::SelfDirected),
+                    left: StyleProp::Value(Units::Pixels(position.0)),
+                    top: StyleProp::Value(Units::Pixels(position.1)),
+                    width: StyleProp::Value(Units::Pixels(size.0)),
+                    height: StyleProp::Value(Units::Pixels(size.1)),
+                    ..Default::default()
+                };
+                constructor! {
+                    <Background styles={Some(highlight_styles)} />
+                }
+            }))}
+            {VecTracker::from(caret.into_iter().map(|position| {
+                let caret_styles = Style {
+                    background_color: StyleProp::Value(Color::new(0.9, 0.9, 0.9, 1.0)),
+                    pointer_events: StyleProp::Value(PointerEvents::None),
+                    position_type: StyleProp::Value(PositionType::SelfDirected),
+                    left: StyleProp::Value(Units::Pixels(position.0)),
+                    top: StyleProp::Value(Units::Pixels(position.1)),
+                    width: StyleProp::Value(Units::Pixels(1.5)),
+                    height: StyleProp::Value(Units::Pixels(LINE_HEIGHT)),
+                    ..Default::default()
+                };
+                constructor! {
+                    <Background styles={Some(caret_styles)} />
+                }
+            }))}
+        </>
+    }
+}
+
+/// The furthest the given text area content can be scrolled down, in pixels
+fn max_scroll(context: &KayakContextRef, content_id: Index) -> f32 {
+    let content = context.get_layout(&content_id).map(|layout| layout.height);
+    let viewport = context
+        .get_valid_parent(content_id)
+        .and_then(|parent| context.get_layout(&parent))
+        .map(|layout| layout.height);
+    match (content, viewport) {
+        (Some(content), Some(viewport)) => (content - viewport).max(0.0),
+        _ => 0.0,
+    }
+}
+
+/// Finds the char index the caret moves to for the given key, following the lines as displayed
+fn find_caret_target(
+    context: &KayakContextRef,
+    content_id: Index,
+    caret: usize,
+    key: KeyCode,
+) -> Option<usize> {
+    let (text_layout, _) = get_text_layout(context, content_id)?;
+    let line_height = text_layout.properties().line_height;
+    let (x, y) = text_layout.caret_position(text_layout.grapheme_at_char(caret));
+    let middle = y + line_height / 2.0;
+
+    let target = match key {
+        KeyCode::Up if y < line_height / 2.0 => return Some(0),
+        KeyCode::Up => (x, middle - line_height),
+        KeyCode::Down if middle + line_height > text_layout.size().1 => {
+            return Some(text_layout.total_chars())
+        }
+        KeyCode::Down => (x, middle + line_height),
+        KeyCode::Home => (f32::NEG_INFINITY, middle),
+        KeyCode::End => (f32::INFINITY, middle),
+        _ => return None,
+    };
+    Some(text_layout.hit_test_char(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{TextArea, TextAreaProps};
+    use crate::core::render_command::RenderCommand;
+    use crate::core::styles::StyleProp;
+    use crate::core::{mount_widget, Handler, InputEvent, KayakContext, KeyCode, Widget};
+
+    /// Mounts a focused `TextArea` with the given value, returning the values it changed to
+    fn mount(context: &mut KayakContext, value: &str) -> Arc<Mutex<Vec<String>>> {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let on_change = changes.clone();
+        let props = TextAreaProps {
+            autofocus: true,
+            value: value.to_string(),
+            on_change: Some(Handler::new(move |value: String| {
+                on_change.lock().unwrap().push(value)
+            })),
+            ..Default::default()
+        };
+        mount_widget(context, TextArea::constructor(props));
+        changes
+    }
+
+    /// Returns the text displayed by the text area
+    fn displayed_text(context: &KayakContext) -> Option<String> {
+        let widget_manager = &context.widget_manager;
+        widget_manager.tree.flatten().into_iter().find_map(|id| {
+            match widget_manager.get_node(&id)?.resolved_styles.render_command {
+                StyleProp::Value(RenderCommand::Text { content }) => Some(content),
+                _ => None,
+            }
+        })
+    }
+
+    #[test]
+    fn should_display_ime_preedit_text_at_the_caret_until_it_is_committed() {
+        let mut context = KayakContext::new();
+        let changes = mount(&mut context, "ab");
+        context.process_events(vec![InputEvent::Keyboard {
+            key: KeyCode::Right,
+            is_pressed: true,
+        }]);
+        context.render();
+
+        context.process_events(vec![InputEvent::ImePreedit {
+            text: String::from("かな"),
+            cursor: Some((0, 3)),
+        }]);
+        context.render();
+        assert_eq!(Some(String::from("aかなb")), displayed_text(&context));
+        assert!(changes.lock().unwrap().is_empty());
+
+        context.process_events(vec![InputEvent::ImeCommit(String::from("仮名"))]);
+        context.render();
+        assert_eq!(vec!["a仮名b"], *changes.lock().unwrap());
+        assert_eq!(Some(String::from("ab")), displayed_text(&context));
+    }
+}
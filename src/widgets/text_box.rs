@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use crate::{
     core::{
         render_command::RenderCommand,
@@ -9,15 +11,39 @@ use crate::{
 };
 use kayak_core::{CursorIcon, OnLayout};
 
-use crate::widgets::{Background, Clip, OnChange, Text};
+use crate::widgets::{Background, Clip, InputPattern, OnChange, Text, TextHistory, TextValidator};
+
+use super::disabled::apply_disabled_styles;
+use super::text_editing::{is_backspace, Preedit};
+use super::text_history::{EditKind, EditSnapshot, HistoryShortcut};
 
 /// Props used by the [`TextBox`] widget
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct TextBoxProps {
-    /// If true, prevents the widget from being focused (and consequently edited)
+    /// If true, this widget is focused when it's first mounted
+    ///
+    /// If several widgets autofocus at once, the last one declared wins.
+    pub autofocus: bool,
+    /// If true, disables this widget, preventing it from being focused (and consequently edited)
+    ///
+    /// If false, the widget inherits the disabled state of its parent.
     pub disabled: bool,
+    /// If true, briefly highlights the text box when an edit is rejected
+    pub flash_invalid: bool,
+    /// The undo history of the text box
+    ///
+    /// The text box keeps its own history (handling `Ctrl+Z` and `Ctrl+Y`), so this only needs
+    /// to be set in order to undo or redo edits programmatically.
+    pub history: Option<TextHistory>,
     /// A callback for when the text value was changed
+    ///
+    /// This is only called with values accepted by the [`pattern`](Self::pattern) and
+    /// [`validator`](Self::validator).
     pub on_change: Option<OnChange>,
+    /// A common pattern that the value must follow, such as digits only or a phone number mask
+    ///
+    /// This is applied before the [`validator`](Self::validator).
+    pub pattern: Option<InputPattern>,
     /// The text to display when the user input is empty
     pub placeholder: Option<String>,
     /// The user input
@@ -25,6 +51,10 @@ pub struct TextBoxProps {
     /// This is a controlled state. You _must_ set this to the value to you wish to be displayed.
     /// You can use the [`on_change`] callback to update this prop as the user types.
     pub value: String,
+    /// A callback that can reject or transform each edit
+    ///
+    /// Rejected edits leave the value unchanged.
+    pub validator: Option<TextValidator>,
     pub styles: Option<Style>,
     pub children: Option<Children>,
     pub on_event: Option<OnEvent>,
@@ -54,16 +84,35 @@ impl WidgetProps for TextBoxProps {
     }
 
     fn get_focusable(&self) -> Option<bool> {
-        Some(!self.disabled)
+        Some(true)
+    }
+
+    fn get_autofocus(&self) -> bool {
+        self.autofocus
+    }
+
+    fn get_disabled(&self) -> Option<bool> {
+        self.disabled.then_some(true)
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Focus(pub bool);
 
+/// The time at which an edit to a [`TextBox`] was last rejected
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct RejectedAt(Option<Instant>);
+
+/// How long a [`TextBox`] is highlighted for after an edit is rejected
+const INVALID_FLASH_DURATION: Duration = Duration::from_millis(300);
+
 #[widget]
 /// A widget that displays a text input field
 ///
+/// Edits can be filtered with a [`pattern`](TextBoxProps::pattern) (such as
+/// [`InputPattern::Mask`] for formatted input) and a [`validator`](TextBoxProps::validator).
+/// Edits can be undone with `Ctrl+Z` and redone with `Ctrl+Y` (see [`TextHistory`]).
+///
 /// # Props
 ///
 /// __Type:__ [`TextBoxProps`]
@@ -78,8 +127,11 @@ pub struct Focus(pub bool);
 ///
 pub fn TextBox(props: TextBoxProps) {
     let TextBoxProps {
+        flash_invalid,
         on_change,
+        pattern,
         placeholder,
+        validator,
         value,
         ..
     } = props.clone();
@@ -103,7 +155,7 @@ pub fn TextBox(props: TextBoxProps) {
             }),
     );
 
-    let background_styles = Style {
+    let mut background_styles = Style {
         background_color: Color::new(0.176, 0.196, 0.215, 1.0).into(),
         border_radius: Corner::all(5.0).into(),
         height: Units::Pixels(26.0).into(),
@@ -112,24 +164,85 @@ pub fn TextBox(props: TextBoxProps) {
         ..Default::default()
     };
 
+    if context.is_disabled() {
+        if let Some(styles) = &mut props.styles {
+            apply_disabled_styles(styles);
+        }
+        apply_disabled_styles(&mut background_styles);
+    }
+
     let has_focus = context.create_state(Focus(false)).unwrap();
+    let rejected_at = context.create_state(RejectedAt::default()).unwrap();
+    let preedit = context.create_state(Preedit::default()).unwrap();
+
+    if let Some(rejected) = rejected_at.get().0 {
+        let flash_end = rejected + INVALID_FLASH_DURATION;
+        if flash_invalid && context.frame_time() < flash_end {
+            background_styles.background_color = Color::new(0.45, 0.16, 0.16, 1.0).into();
+            context.request_render_at(flash_end);
+        }
+    }
+
+    let own_history = context.create_state(TextHistory::default()).unwrap().get();
+    let history = props.history.clone().unwrap_or(own_history);
+    history.sync(&value);
 
     let mut current_value = value.clone();
     let cloned_on_change = on_change.clone();
     let cloned_has_focus = has_focus.clone();
+    let cloned_preedit = preedit.clone();
 
     props.on_event = Some(OnEvent::new(move |_, event| match event.event_type {
-        EventType::CharInput { c } => {
+        EventType::CharInput { .. } | EventType::ImeCommit(..) => {
             if !cloned_has_focus.get().0 {
                 return;
             }
-            if is_backspace(c) {
-                if !current_value.is_empty() {
-                    current_value.truncate(current_value.len() - 1);
+            let mut candidate = current_value.clone();
+            let kind = match &event.event_type {
+                EventType::CharInput { c } if is_backspace(*c) => {
+                    candidate.pop();
+                    EditKind::Deleting
+                }
+                EventType::CharInput { c } => {
+                    if !c.is_control() {
+                        candidate.push(*c);
+                    }
+                    EditKind::Typing
+                }
+                EventType::ImeCommit(text) => {
+                    cloned_preedit.set(Preedit::default());
+                    candidate.push_str(text);
+                    EditKind::Typing
+                }
+                _ => return,
+            };
+
+            let accepted = match &pattern {
+                Some(pattern) => pattern.apply(&candidate),
+                None => Some(candidate),
+            };
+            let accepted = match (&validator, accepted) {
+                (Some(validator), Some(candidate)) => validator.validate(&candidate),
+                (_, accepted) => accepted,
+            };
+            match accepted {
+                Some(accepted) if accepted != current_value => {
+                    history.record(
+                        EditSnapshot::at_end(&current_value),
+                        EditSnapshot::at_end(&accepted),
+                        kind,
+                    );
+                    current_value = accepted;
+                }
+                Some(_) => {}
+                None => {
+                    if flash_invalid {
+                        rejected_at.set(RejectedAt(Some(Instant::now())));
+                    }
+                    return;
                 }
-            } else if !c.is_control() {
-                current_value.push(c);
             }
+
             if let Some(on_change) = cloned_on_change.as_ref() {
                 if let Ok(mut on_change) = on_change.0.write() {
                     on_change(ChangeEvent {
@@ -138,12 +251,48 @@ pub fn TextBox(props: TextBoxProps) {
                 }
             }
         }
+        EventType::KeyDown(data) => {
+            if !cloned_has_focus.get().0 {
+                return;
+            }
+            let shortcut = match HistoryShortcut::from_key(&data) {
+                Some(shortcut) => shortcut,
+                None => return,
+            };
+            event.stop_propagation();
+
+            if let Some((value, _)) = history.apply(shortcut) {
+                current_value = value;
+                if let Some(on_change) = cloned_on_change.as_ref() {
+                    if let Ok(mut on_change) = on_change.0.write() {
+                        on_change(ChangeEvent {
+                            value: current_value.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        EventType::ImePreedit { ref text, cursor } => {
+            if cloned_has_focus.get().0 {
+                cloned_preedit.set(Preedit {
+                    text: text.clone(),
+                    cursor,
+                });
+            }
+        }
         EventType::Focus => cloned_has_focus.set(Focus(true)),
-        EventType::Blur => cloned_has_focus.set(Focus(false)),
+        EventType::Blur => {
+            cloned_has_focus.set(Focus(false));
+            cloned_preedit.set(Preedit::default());
+        }
         _ => {}
     }));
 
-    let text_styles = if value.is_empty() || (has_focus.get().0 && value.is_empty()) {
+    // Text being composed by an IME is shown at the end, where it will be inserted
+    let preedit = preedit.get().text;
+    let is_placeholder = value.is_empty() && preedit.is_empty();
+
+    let text_styles = if is_placeholder {
         Style {
             color: Color::new(0.5, 0.5, 0.5, 1.0).into(),
             ..Style::default()
@@ -152,10 +301,10 @@ pub fn TextBox(props: TextBoxProps) {
         Style::default()
     };
 
-    let value = if value.is_empty() {
-        placeholder.unwrap_or_else(|| value.clone())
+    let value = if is_placeholder {
+        placeholder.unwrap_or_default()
     } else {
-        value
+        value + &preedit
     };
 
     rsx! {
@@ -172,9 +321,60 @@ pub fn TextBox(props: TextBoxProps) {
     }
 }
 
-/// Checks if the given character contains the "Backspace" sequence
-///
-/// Context: [Wikipedia](https://en.wikipedia.org/wiki/Backspace#Common_use)
-fn is_backspace(c: char) -> bool {
-    c == '\u{8}' || c == '\u{7f}'
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{TextBox, TextBoxProps};
+    use crate::core::render_command::RenderCommand;
+    use crate::core::styles::StyleProp;
+    use crate::core::{mount_widget, InputEvent, KayakContext, Widget};
+    use crate::widgets::OnChange;
+
+    /// Mounts a focused `TextBox` with the given value, returning the values it changed to
+    fn mount(context: &mut KayakContext, value: &str) -> Arc<Mutex<Vec<String>>> {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let on_change = changes.clone();
+        let props = TextBoxProps {
+            autofocus: true,
+            value: value.to_string(),
+            on_change: Some(OnChange::new(move |event| {
+                on_change.lock().unwrap().push(event.value)
+            })),
+            ..Default::default()
+        };
+        mount_widget(context, TextBox::constructor(props));
+        changes
+    }
+
+    /// Returns the text displayed by the text box
+    fn displayed_text(context: &KayakContext) -> Option<String> {
+        let widget_manager = &context.widget_manager;
+        widget_manager.tree.flatten().into_iter().find_map(|id| {
+            match widget_manager.get_node(&id)?.resolved_styles.render_command {
+                StyleProp::Value(RenderCommand::Text { content }) => Some(content),
+                _ => None,
+            }
+        })
+    }
+
+    #[test]
+    fn should_display_ime_preedit_text_until_it_is_committed() {
+        let mut context = KayakContext::new();
+        let changes = mount(&mut context, "ab");
+
+        context.process_events(vec![InputEvent::ImePreedit {
+            text: String::from("かな"),
+            cursor: None,
+        }]);
+        context.render();
+        assert_eq!(Some(String::from("abかな")), displayed_text(&context));
+        assert!(changes.lock().unwrap().is_empty());
+
+        context.process_events(vec![InputEvent::ImeCommit(String::from("仮名"))]);
+        context.render();
+        assert_eq!(vec!["ab仮名"], *changes.lock().unwrap());
+        // The value is controlled, so only the preedit text is removed until it's updated
+        assert_eq!(Some(String::from("ab")), displayed_text(&context));
+    }
 }
@@ -0,0 +1,268 @@
+use std::ops::Range;
+
+use kayak_font::TextLayout;
+
+use crate::core::{render_primitive::RenderPrimitive, Index, KayakContextRef};
+
+/// The caret and selection of an editable text widget
+///
+/// Both ends are char indices into the text being edited. The `anchor` is where the selection
+/// started and the `caret` is where it ends (and where the caret is drawn). When they're equal,
+/// nothing is selected.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TextCursor {
+    pub caret: usize,
+    pub anchor: usize,
+}
+
+impl TextCursor {
+    /// A caret at the given char index, with nothing selected
+    pub fn at(index: usize) -> Self {
+        Self {
+            caret: index,
+            anchor: index,
+        }
+    }
+
+    /// Returns the range of chars that are selected
+    pub fn selection(&self) -> Range<usize> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    /// Returns true if any chars are selected
+    pub fn has_selection(&self) -> bool {
+        self.caret != self.anchor
+    }
+
+    /// Keeps both ends within text of the given length (in chars)
+    pub fn clamped(self, len: usize) -> Self {
+        Self {
+            caret: self.caret.min(len),
+            anchor: self.anchor.min(len),
+        }
+    }
+
+    /// Moves the caret to the given char index
+    ///
+    /// If `extend` is true, the selection is extended to the new index. Otherwise, the selection is
+    /// cleared.
+    pub fn move_to(&mut self, index: usize, extend: bool) {
+        self.caret = index;
+        if !extend {
+            self.anchor = index;
+        }
+    }
+
+    /// Moves the caret back by a char, or to the start of the selection
+    pub fn move_left(&mut self, extend: bool) {
+        if self.has_selection() && !extend {
+            self.move_to(self.selection().start, false);
+        } else {
+            self.move_to(self.caret.saturating_sub(1), extend);
+        }
+    }
+
+    /// Moves the caret forward by a char, or to the end of the selection
+    pub fn move_right(&mut self, len: usize, extend: bool) {
+        if self.has_selection() && !extend {
+            self.move_to(self.selection().end, false);
+        } else {
+            self.move_to((self.caret + 1).min(len), extend);
+        }
+    }
+
+    /// Replaces the selection in the given value with the given text, placing the caret after it
+    pub fn insert(&mut self, value: &mut String, text: &str) {
+        let selection = self.selection();
+        let bytes = byte_index(value, selection.start)..byte_index(value, selection.end);
+        value.replace_range(bytes, text);
+        self.move_to(selection.start + text.chars().count(), false);
+    }
+
+    /// Removes the selection, or the char before the caret if nothing is selected
+    pub fn backspace(&mut self, value: &mut String) {
+        if !self.has_selection() && self.caret > 0 {
+            self.anchor = self.caret - 1;
+        }
+        self.insert(value, "");
+    }
+
+    /// Removes the selection, or the char after the caret if nothing is selected
+    pub fn delete(&mut self, value: &mut String) {
+        if !self.has_selection() && self.caret < value.chars().count() {
+            self.anchor = self.caret + 1;
+        }
+        self.insert(value, "");
+    }
+}
+
+/// The text an IME is composing within an editable text widget
+///
+/// This text isn't part of the widget's value until the IME commits it, but it's displayed in
+/// place of the selection in the meantime.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(crate) struct Preedit {
+    pub text: String,
+    /// The `(start, end)` byte range within the text targeted by the IME, if any
+    pub cursor: Option<(usize, usize)>,
+}
+
+impl Preedit {
+    /// Returns the given value as displayed with this preedit text in place of the given cursor's
+    /// selection, along with the caret within the displayed value
+    ///
+    /// The caret is placed at the start of the range targeted by the IME, or after the preedit
+    /// text if there is none.
+    pub fn display(&self, value: &str, cursor: TextCursor) -> (String, TextCursor) {
+        let mut displayed = value.to_string();
+        let mut caret = cursor;
+        caret.insert(&mut displayed, &self.text);
+        if let Some(start) = self.cursor.and_then(|(start, _)| self.text.get(..start)) {
+            caret = TextCursor::at(cursor.selection().start + start.chars().count());
+        }
+        (displayed, caret)
+    }
+}
+
+/// Converts the given char index into a byte index within the given value
+fn byte_index(value: &str, char_index: usize) -> usize {
+    value
+        .char_indices()
+        .nth(char_index)
+        .map_or(value.len(), |(index, _)| index)
+}
+
+/// Checks if the given character contains the "Backspace" sequence
+///
+/// Context: [Wikipedia](https://en.wikipedia.org/wiki/Backspace#Common_use)
+pub(crate) fn is_backspace(c: char) -> bool {
+    c == '\u{8}' || c == '\u{7f}'
+}
+
+/// Returns the text layout of the given text widget, along with the position of the widget
+pub(crate) fn get_text_layout(
+    context: &KayakContextRef,
+    widget_id: Index,
+) -> Option<(TextLayout, (f32, f32))> {
+    let layout = context.get_layout(&widget_id)?;
+    let origin = (layout.posx, layout.posy);
+    match context.get_node(&widget_id)?.primitive {
+        RenderPrimitive::Text { text_layout, .. } => Some((text_layout, origin)),
+        _ => None,
+    }
+}
+
+/// Returns the content the given text widget was last laid out with
+pub(crate) fn get_laid_out_content(context: &KayakContextRef, widget_id: Index) -> Option<String> {
+    match context.get_node(&widget_id)?.primitive {
+        RenderPrimitive::Text { content, .. } => Some(content),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_backspace, Preedit, TextCursor};
+
+    fn selecting(anchor: usize, caret: usize) -> TextCursor {
+        TextCursor { caret, anchor }
+    }
+
+    #[test]
+    fn should_select_between_the_anchor_and_the_caret() {
+        assert_eq!(2..5, selecting(5, 2).selection());
+        assert_eq!(2..5, selecting(2, 5).selection());
+        assert!(selecting(2, 5).has_selection());
+        assert!(!TextCursor::at(3).has_selection());
+        assert_eq!(selecting(4, 2), selecting(9, 2).clamped(4));
+    }
+
+    #[test]
+    fn should_move_the_caret() {
+        let mut cursor = TextCursor::at(2);
+        cursor.move_left(false);
+        assert_eq!(TextCursor::at(1), cursor);
+        cursor.move_right(3, true);
+        cursor.move_right(3, true);
+        assert_eq!(selecting(1, 3), cursor);
+        // The caret doesn't move past the end of the text
+        cursor.move_right(3, true);
+        assert_eq!(selecting(1, 3), cursor);
+
+        // Without extending, a selection collapses to its start or end
+        let mut cursor = selecting(1, 3);
+        cursor.move_left(false);
+        assert_eq!(TextCursor::at(1), cursor);
+        let mut cursor = selecting(3, 1);
+        cursor.move_right(3, false);
+        assert_eq!(TextCursor::at(3), cursor);
+
+        let mut cursor = TextCursor::at(0);
+        cursor.move_left(true);
+        assert_eq!(TextCursor::at(0), cursor);
+    }
+
+    #[test]
+    fn should_replace_the_selection() {
+        let mut value = "héllo".to_string();
+        let mut cursor = selecting(1, 4);
+        cursor.insert(&mut value, "ur");
+        assert_eq!("huro", value);
+        assert_eq!(TextCursor::at(3), cursor);
+
+        cursor.insert(&mut value, "ß");
+        assert_eq!("hurßo", value);
+        assert_eq!(TextCursor::at(4), cursor);
+    }
+
+    #[test]
+    fn should_remove_chars_around_the_caret() {
+        let mut value = "añb".to_string();
+        let mut cursor = TextCursor::at(2);
+        cursor.backspace(&mut value);
+        assert_eq!("ab", value);
+        assert_eq!(TextCursor::at(1), cursor);
+
+        cursor.delete(&mut value);
+        assert_eq!("a", value);
+        assert_eq!(TextCursor::at(1), cursor);
+
+        // Nothing to remove at the ends of the text
+        cursor.delete(&mut value);
+        let mut start = TextCursor::at(0);
+        start.backspace(&mut value);
+        assert_eq!("a", value);
+
+        let mut value = "abcd".to_string();
+        let mut cursor = selecting(3, 1);
+        cursor.backspace(&mut value);
+        assert_eq!("ad", value);
+        assert_eq!(TextCursor::at(1), cursor);
+    }
+
+    #[test]
+    fn should_detect_backspace() {
+        assert!(is_backspace('\u{8}'));
+        assert!(is_backspace('\u{7f}'));
+        assert!(!is_backspace('a'));
+    }
+
+    #[test]
+    fn should_display_the_preedit_text_in_place_of_the_selection() {
+        let mut preedit = Preedit {
+            text: String::from("かな"),
+            cursor: None,
+        };
+        assert_eq!(
+            (String::from("aかなd"), TextCursor::at(3)),
+            preedit.display("abcd", selecting(1, 3))
+        );
+
+        // The caret is drawn where the IME is targeting, which is given in bytes
+        preedit.cursor = Some((3, 6));
+        assert_eq!(
+            (String::from("aかなd"), TextCursor::at(2)),
+            preedit.display("abcd", selecting(1, 3))
+        );
+    }
+}
@@ -0,0 +1,394 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use kayak_core::{KeyCode, KeyboardEvent};
+
+use super::text_editing::TextCursor;
+
+/// The default number of undo steps kept by a [`TextHistory`]
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+/// The undo and redo stacks of a text input, such as a [`TextBox`](crate::widgets::TextBox) or
+/// [`TextArea`](crate::widgets::TextArea)
+///
+/// Text inputs keep their own history, so this only needs to be created in order to undo or redo
+/// edits programmatically. Keep it in the widget's state, so the same history is used on every
+/// render, and pass it to the input's `history` prop. Then call [`undo`](Self::undo) or
+/// [`redo`](Self::redo). Since the inputs are controlled, these return the value to set rather
+/// than setting it themselves.
+///
+/// Consecutive typing (or deleting) is coalesced into a single undo step. Moving the caret in
+/// between starts a new step. Once the history holds its capacity of steps, the oldest ones are
+/// dropped. The whole history is cleared when the input's value is set externally (that is, to
+/// something other than the result of an edit, undo, or redo).
+///
+/// Clones of a `TextHistory` share the same stacks.
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, use_state, widget, Bound, OnEvent};
+/// # use kayak_ui::widgets::{Button, OnChange, Text, TextBox, TextHistory};
+///
+/// #[widget]
+/// fn Editor() {
+///     let (value, set_value, ..) = use_state!(String::new());
+///     let history = context.create_state(TextHistory::default()).unwrap().get();
+///
+///     let on_undo_clicked = {
+///         let history = history.clone();
+///         let set_value = set_value.clone();
+///         OnEvent::new(move |_, _| {
+///             if let Some(value) = history.undo() {
+///                 set_value(value);
+///             }
+///         })
+///     };
+///     let on_change = OnChange::new(move |event| set_value(event.value));
+///
+///     rsx! {
+///         <>
+///             <TextBox value={value} on_change={Some(on_change)} history={Some(history)} />
+///             <Button on_event={Some(on_undo_clicked)}>
+///                 <Text content={"Undo".to_string()} size={16.0} />
+///             </Button>
+///         </>
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TextHistory(Arc<Mutex<EditHistory>>);
+
+impl TextHistory {
+    /// Create a history that keeps up to [`DEFAULT_HISTORY_CAPACITY`] undo steps
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_HISTORY_CAPACITY)
+    }
+
+    /// Create a history that keeps up to the given number of undo steps
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(EditHistory {
+            capacity,
+            ..Default::default()
+        })))
+    }
+
+    /// Undo the last edit, returning the value from before it
+    ///
+    /// Returns `None` if there's nothing to undo.
+    pub fn undo(&self) -> Option<String> {
+        self.undo_edit().map(|(value, _)| value)
+    }
+
+    /// Redo the last undone edit, returning the value from after it
+    ///
+    /// Returns `None` if there's nothing to redo.
+    pub fn redo(&self) -> Option<String> {
+        self.redo_edit().map(|(value, _)| value)
+    }
+
+    /// Returns true if there's an edit to undo
+    pub fn can_undo(&self) -> bool {
+        self.0
+            .lock()
+            .map_or(false, |history| !history.undo.is_empty())
+    }
+
+    /// Returns true if there's an undone edit to redo
+    pub fn can_redo(&self) -> bool {
+        self.0
+            .lock()
+            .map_or(false, |history| !history.redo.is_empty())
+    }
+
+    /// Remove all undo and redo steps
+    pub fn clear(&self) {
+        if let Ok(mut history) = self.0.lock() {
+            history.undo.clear();
+            history.redo.clear();
+        }
+    }
+
+    /// Record an edit made by the user
+    pub(crate) fn record(&self, before: EditSnapshot, after: EditSnapshot, kind: EditKind) {
+        if let Ok(mut history) = self.0.lock() {
+            history.record(before, after, kind);
+        }
+    }
+
+    /// Undo the last edit, returning the value and cursor from before it
+    pub(crate) fn undo_edit(&self) -> Option<(String, TextCursor)> {
+        let mut history = self.0.lock().ok()?;
+        let edit = history.undo.pop_back()?;
+        let EditSnapshot { value, cursor } = edit.before.clone();
+        history.current = value.clone();
+        history.redo.push(edit);
+        Some((value, cursor))
+    }
+
+    /// Redo the last undone edit, returning the value and cursor from after it
+    pub(crate) fn redo_edit(&self) -> Option<(String, TextCursor)> {
+        let mut history = self.0.lock().ok()?;
+        let mut edit = history.redo.pop()?;
+        let EditSnapshot { value, cursor } = edit.after.clone();
+        history.current = value.clone();
+        // A redone edit is never extended by later typing
+        edit.kind = EditKind::Other;
+        history.undo.push_back(edit);
+        Some((value, cursor))
+    }
+
+    /// Undo or redo an edit for the given shortcut, returning the value and cursor to restore
+    pub(crate) fn apply(&self, shortcut: HistoryShortcut) -> Option<(String, TextCursor)> {
+        match shortcut {
+            HistoryShortcut::Undo => self.undo_edit(),
+            HistoryShortcut::Redo => self.redo_edit(),
+        }
+    }
+
+    /// Keep the history in sync with the value given to the input
+    ///
+    /// If the value changed to anything other than the result of the last edit, undo, or redo, it
+    /// was set externally, so the history is cleared.
+    pub(crate) fn sync(&self, value: &str) {
+        if let Ok(mut history) = self.0.lock() {
+            if history.seen != value {
+                history.seen = value.to_string();
+                if history.current != value {
+                    history.current = value.to_string();
+                    history.undo.clear();
+                    history.redo.clear();
+                }
+            }
+        }
+    }
+}
+
+impl Default for TextHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for TextHistory {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for TextHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TextHistory").finish()
+    }
+}
+
+/// The value and cursor of a text input at some point in its history
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(crate) struct EditSnapshot {
+    pub value: String,
+    pub cursor: TextCursor,
+}
+
+impl EditSnapshot {
+    /// A snapshot of the given value with the caret at its end
+    pub fn at_end(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            cursor: TextCursor::at(value.chars().count()),
+        }
+    }
+}
+
+/// An undo or redo requested with a keyboard shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HistoryShortcut {
+    Undo,
+    Redo,
+}
+
+impl HistoryShortcut {
+    /// Returns the action for the given key press, if it's `Ctrl+Z`, `Ctrl+Shift+Z` or `Ctrl+Y`
+    /// (or their `Meta` equivalents)
+    pub fn from_key(event: &KeyboardEvent) -> Option<Self> {
+        if !event.is_ctrl_pressed() && !event.is_meta_pressed() {
+            return None;
+        }
+
+        match event.key() {
+            KeyCode::Z if event.is_shift_pressed() => Some(HistoryShortcut::Redo),
+            KeyCode::Z => Some(HistoryShortcut::Undo),
+            KeyCode::Y => Some(HistoryShortcut::Redo),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of an edit, which determines whether it's coalesced with the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditKind {
+    /// Inserting a char at the caret
+    Typing,
+    /// Removing a char next to the caret
+    Deleting,
+    /// Any other edit, which is never coalesced
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    before: EditSnapshot,
+    after: EditSnapshot,
+    kind: EditKind,
+}
+
+#[derive(Default, Debug)]
+struct EditHistory {
+    capacity: usize,
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+    /// The value as of the last edit, undo, or redo
+    current: String,
+    /// The value last given to the input
+    seen: String,
+}
+
+impl EditHistory {
+    fn record(&mut self, before: EditSnapshot, after: EditSnapshot, kind: EditKind) {
+        self.redo.clear();
+        self.current = after.value.clone();
+
+        if let Some(last) = self.undo.back_mut() {
+            let is_continuation = last.after == before;
+            if is_continuation && kind != EditKind::Other && last.kind == kind {
+                last.after = after;
+                return;
+            }
+        }
+
+        self.undo.push_back(Edit {
+            before,
+            after,
+            kind,
+        });
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EditKind, EditSnapshot, TextHistory};
+
+    /// Types the given text one char at a time after the given value, as the user would
+    fn type_text(history: &TextHistory, value: &mut String, text: &str) {
+        for c in text.chars() {
+            let before = EditSnapshot::at_end(value);
+            value.push(c);
+            history.record(before, EditSnapshot::at_end(value), EditKind::Typing);
+            history.sync(value);
+        }
+    }
+
+    #[test]
+    fn should_coalesce_consecutive_typing() {
+        let history = TextHistory::new();
+        let mut value = String::new();
+        type_text(&history, &mut value, "hello");
+
+        assert_eq!(Some(String::new()), history.undo());
+        assert!(!history.can_undo());
+        assert_eq!(Some("hello".to_string()), history.redo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn should_start_a_new_step_after_other_edits() {
+        let history = TextHistory::new();
+        let mut value = String::new();
+        type_text(&history, &mut value, "ab");
+        let before = EditSnapshot::at_end(&value);
+        value.pop();
+        history.record(before, EditSnapshot::at_end(&value), EditKind::Deleting);
+        type_text(&history, &mut value, "c");
+
+        assert_eq!(Some("a".to_string()), history.undo());
+        assert_eq!(Some("ab".to_string()), history.undo());
+        assert_eq!(Some(String::new()), history.undo());
+        assert_eq!(None, history.undo());
+    }
+
+    #[test]
+    fn should_start_a_new_step_when_the_caret_moved() {
+        let history = TextHistory::new();
+        let mut value = String::new();
+        type_text(&history, &mut value, "ab");
+        // Typing at the start, after moving the caret there
+        let before = EditSnapshot {
+            cursor: Default::default(),
+            ..EditSnapshot::at_end(&value)
+        };
+        history.record(before, EditSnapshot::at_end("cab"), EditKind::Typing);
+
+        assert_eq!(Some("ab".to_string()), history.undo());
+        assert_eq!(Some(String::new()), history.undo());
+    }
+
+    #[test]
+    fn should_drop_the_oldest_steps_beyond_its_capacity() {
+        let history = TextHistory::with_capacity(2);
+        let mut value = String::new();
+        for text in ["a", "b", "c"] {
+            let before = EditSnapshot::at_end(&value);
+            value.push_str(text);
+            history.record(before, EditSnapshot::at_end(&value), EditKind::Other);
+        }
+
+        assert_eq!(Some("ab".to_string()), history.undo());
+        assert_eq!(Some("a".to_string()), history.undo());
+        assert_eq!(None, history.undo());
+    }
+
+    #[test]
+    fn should_clear_redo_steps_on_new_edits() {
+        let history = TextHistory::new();
+        let mut value = String::new();
+        type_text(&history, &mut value, "a");
+        value = history.undo().unwrap();
+        history.sync(&value);
+        assert!(history.can_redo());
+
+        type_text(&history, &mut value, "b");
+        assert!(!history.can_redo());
+        assert_eq!(None, history.redo());
+    }
+
+    #[test]
+    fn should_not_extend_redone_edits() {
+        let history = TextHistory::new();
+        let mut value = String::new();
+        type_text(&history, &mut value, "a");
+        history.undo();
+        value = history.redo().unwrap();
+        history.sync(&value);
+        type_text(&history, &mut value, "b");
+
+        assert_eq!(Some("a".to_string()), history.undo());
+    }
+
+    #[test]
+    fn should_clear_when_the_value_is_set_externally() {
+        let history = TextHistory::new();
+        let mut value = String::new();
+        type_text(&history, &mut value, "a");
+
+        // Undoing (or redoing) isn't an external change
+        value = history.undo().unwrap();
+        history.sync(&value);
+        assert!(history.can_redo());
+
+        history.sync("reset");
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::core::{
+    rsx, use_previous, widget, Bound, Children, MutableBound, Theme, ThemeBinding, WidgetProps,
+};
+
+/// Props used by the [`ThemeProvider`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ThemeProviderProps {
+    /// The theme to provide to the descendants of this widget
+    ///
+    /// Descendants can also switch the theme themselves by getting the provided [`ThemeBinding`]
+    /// with `context.create_consumer::<ThemeBinding>()` and setting the binding within it.
+    pub theme: Theme,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+}
+
+#[widget]
+/// A widget that provides a theme to its descendants
+///
+/// Descendants read the theme with `use_theme!()`, which returns the theme of their nearest
+/// `ThemeProvider` (or the app-wide theme set with `KayakContext::set_theme` if there is none).
+/// Providers can be nested to theme parts of the tree differently.
+///
+/// Switching the theme only re-renders the widgets that called `use_theme!()`, rather than every
+/// widget below the provider.
+///
+/// # Props
+///
+/// __Type:__ [`ThemeProviderProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ❌        |
+/// | `on_event`  | ❌        |
+/// | `on_layout` | ❌        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, Theme, use_theme, widget};
+/// # use kayak_ui::widgets::{Text, ThemeProvider};
+/// # #[widget]
+/// # fn Example() {
+/// #[widget]
+/// fn Title() {
+///     let theme = use_theme!();
+///     rsx! {
+///         <Text content={"Settings".to_string()} size={theme.font_size.large} />
+///     }
+/// }
+///
+/// rsx! {
+///     <ThemeProvider theme={Theme::light()}>
+///         <Title />
+///     </ThemeProvider>
+/// }
+/// # }
+/// ```
+pub fn ThemeProvider(props: ThemeProviderProps) {
+    // The provided binding never changes, so this widget isn't re-rendered when the theme does
+    let provided = context.create_provider(ThemeBinding::new(props.theme.clone()));
+
+    let previous_theme = use_previous!(props.theme.clone());
+    if previous_theme.map_or(false, |previous_theme| previous_theme != props.theme) {
+        provided.get().0.set(props.theme.clone());
+    }
+
+    rsx! {
+        <>
+            {children}
+        </>
+    }
+}
@@ -0,0 +1,432 @@
+use kayak_core::layout_cache::Rect;
+
+use crate::core::{
+    bind,
+    render_command::RenderCommand,
+    rsx,
+    styles::{PositionType, Style, StyleProp, Transform, Units},
+    use_state, widget, Binding, Bound, Children, CursorIcon, EventType, Gestures, Index,
+    MutableBound, OnEvent, OnLayout, ScrollUnit, WidgetProps,
+};
+
+use crate::widgets::Element;
+
+/// How much a [`Viewport`] zooms for each line scrolled
+const ZOOM_STEP: f32 = 1.1;
+/// The number of pixels scrolled that count as a line, for devices that scroll by pixel
+const PIXELS_PER_LINE: f32 = 16.0;
+/// The zoom range of a [`Viewport`] if none is given
+const DEFAULT_ZOOM_RANGE: (f32, f32) = (0.1, 10.0);
+/// The space left around the content of a [`Viewport`] when fitting it, if none is given
+const DEFAULT_FIT_PADDING: f32 = 20.0;
+
+/// The pan and zoom of a [`Viewport`], shared with the code controlling it
+///
+/// The viewport updates the bindings as it's dragged and zoomed, while setting them from
+/// elsewhere moves the viewport. Clones share the same bindings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportController {
+    /// How far the content is moved right and down, in pixels
+    pub pan: Binding<(f32, f32)>,
+    /// How much the content is scaled, where `1.0` is its laid out size
+    ///
+    /// This is clamped to the viewport's [zoom range](ViewportProps::zoom_range).
+    pub zoom: Binding<f32>,
+    /// Changed to ask the viewport to fit its content
+    fit_requests: Binding<u64>,
+    /// The ID of the viewport, once it has rendered
+    pub(crate) viewport: Binding<Option<Index>>,
+}
+
+impl ViewportController {
+    /// Creates a controller for a viewport that starts out unpanned and unzoomed
+    pub fn new() -> Self {
+        Self::with_view((0.0, 0.0), 1.0)
+    }
+
+    /// Creates a controller for a viewport that starts out with the given pan and zoom
+    pub fn with_view(pan: (f32, f32), zoom: f32) -> Self {
+        Self {
+            pan: bind(pan),
+            zoom: bind(zoom),
+            fit_requests: bind(0),
+            viewport: bind(None),
+        }
+    }
+
+    /// Pans and zooms the viewport so that all of its content is centered within it
+    ///
+    /// This takes effect on the viewport's next render, once its content has been laid out.
+    pub fn fit_to_content(&self) {
+        self.fit_requests
+            .set(self.fit_requests.get().wrapping_add(1));
+    }
+}
+
+impl Default for ViewportController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Props used by the [`Viewport`] widget
+#[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
+pub struct ViewportProps {
+    /// The bindings for the pan and zoom of the viewport
+    ///
+    /// If `None`, the viewport keeps track of its own pan and zoom.
+    pub controller: Option<ViewportController>,
+    /// The space left around the content when fitting it, in pixels
+    ///
+    /// If `None`, this is 20 pixels.
+    pub fit_padding: Option<f32>,
+    /// The minimum and maximum zoom
+    ///
+    /// If `None`, the range is `(0.1, 10.0)`.
+    pub zoom_range: Option<(f32, f32)>,
+    #[prop_field(Styles)]
+    pub styles: Option<Style>,
+    #[prop_field(Children)]
+    pub children: Option<Children>,
+    #[prop_field(OnLayout)]
+    pub on_layout: Option<OnLayout>,
+    #[prop_field(OnEvent)]
+    pub on_event: Option<OnEvent>,
+}
+
+#[widget]
+/// A pannable, zoomable canvas, such as for node editors and diagrams
+///
+/// The children are laid out in the viewport's content space as usual, then moved by the pan and
+/// scaled by the zoom with a [`Transform`], which applies to both drawing and hit-testing. Dragging
+/// the viewport pans it, while scrolling zooms it in and out around the cursor. On touch devices,
+/// it can also be panned and pinched.
+///
+/// Children that handle their own drags (such as the nodes of a node editor) should stop the
+/// propagation of their `MouseDown` events, so that dragging them doesn't pan the viewport too.
+///
+/// # Props
+///
+/// __Type:__ [`ViewportProps`]
+///
+/// | Common Prop | Accepted |
+/// | :---------: | :------: |
+/// | `children`  | ✅        |
+/// | `styles`    | ✅        |
+/// | `on_event`  | ✅        |
+/// | `on_layout` | ✅        |
+/// | `focusable` | ❌        |
+///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{rsx, EventType, OnEvent, widget};
+/// # use kayak_ui::widgets::{Button, Viewport, ViewportController};
+/// # #[widget]
+/// # fn Example() {
+/// let controller = ViewportController::new();
+/// let fit_controller = controller.clone();
+/// let on_fit = OnEvent::new(move |_, event| {
+///     if let EventType::Click(..) = event.event_type {
+///         fit_controller.fit_to_content();
+///     }
+/// });
+///
+/// rsx! {
+///     <>
+///         <Viewport controller={Some(controller)}>
+///             {/* ... */}
+///         </Viewport>
+///         <Button on_event={Some(on_fit)}>{/* ... */}</Button>
+///     </>
+/// }
+/// # }
+/// ```
+pub fn Viewport(props: ViewportProps) {
+    let (min_zoom, max_zoom) = props.zoom_range.unwrap_or(DEFAULT_ZOOM_RANGE);
+    let fit_padding = props.fit_padding.unwrap_or(DEFAULT_FIT_PADDING);
+    let clamp_zoom = move |zoom: f32| zoom.max(min_zoom).min(max_zoom);
+
+    // === Controller === //
+    let own_controller = context
+        .create_state(ViewportController::new())
+        .unwrap()
+        .get();
+    let controller = props.controller.clone().unwrap_or(own_controller);
+    context.bind(&controller.pan);
+    context.bind(&controller.zoom);
+    context.bind(&controller.fit_requests);
+    let pan = controller.pan.get();
+    let zoom = clamp_zoom(controller.zoom.get());
+
+    // Let any minimaps find the viewport
+    let viewport_id = context.current_id().unwrap_or_default();
+    if controller.viewport.get() != Some(viewport_id) {
+        let controller = controller.clone();
+        context.after_render(move |_| controller.viewport.set(Some(viewport_id)));
+    }
+
+    // === Fit To Content === //
+    // Fitted once the content has been laid out with the current pan and zoom
+    let handled_fits = context.create_state(0u64).unwrap();
+    let fit_requests = controller.fit_requests.get();
+    if fit_requests != handled_fits.get() {
+        let controller = controller.clone();
+        context.after_render(move |context| {
+            let viewport = context.get_layout(&viewport_id).copied();
+            let layer = context.get_valid_children(viewport_id).first().copied();
+            match (viewport, layer) {
+                (Some(viewport), Some(layer)) => {
+                    let bounds = context
+                        .get_valid_children(layer)
+                        .iter()
+                        .filter_map(|child| context.get_layout(child).copied())
+                        .reduce(|a, b| a.union(&b));
+                    if let Some(bounds) = bounds {
+                        let content = content_rect(bounds, viewport, pan, zoom);
+                        let (pan, zoom) = fit_view(
+                            content,
+                            (viewport.width, viewport.height),
+                            fit_padding,
+                            clamp_zoom,
+                        );
+                        controller.zoom.set(zoom);
+                        controller.pan.set(pan);
+                    }
+                    handled_fits.set(fit_requests);
+                }
+                // Nothing has been laid out, so try again on the next frame
+                _ => context.request_render(),
+            }
+        });
+    }
+
+    // === Events === //
+    let (drag, set_drag, ..) = use_state!(None::<((f32, f32), (f32, f32))>);
+    let zoom_by = {
+        let controller = controller.clone();
+        move |factor: f32, focus: (f32, f32)| {
+            let zoom = clamp_zoom(controller.zoom.get());
+            let next = clamp_zoom(zoom * factor);
+            controller
+                .pan
+                .set(zoom_at(controller.pan.get(), zoom, next, focus));
+            controller.zoom.set(next);
+        }
+    };
+    let event_controller = controller.clone();
+    let on_event = props.on_event.take();
+    props.on_event = Some(OnEvent::wrap(viewport_id, on_event, move |ctx, event| {
+        let viewport = match ctx.get_layout(&event.current_target) {
+            Some(layout) => *layout,
+            None => return,
+        };
+        let relative = |(x, y): (f32, f32)| (x - viewport.posx, y - viewport.posy);
+        match event.event_type {
+            EventType::MouseDown(data) => {
+                ctx.capture_cursor(event.current_target);
+                set_drag(Some((data.position, event_controller.pan.get())));
+            }
+            EventType::MouseUp(..) => {
+                ctx.release_cursor(event.current_target);
+                set_drag(None);
+            }
+            EventType::Hover(data) => {
+                if let Some((start, start_pan)) = drag {
+                    event_controller.pan.set((
+                        start_pan.0 + data.position.0 - start.0,
+                        start_pan.1 + data.position.1 - start.1,
+                    ));
+                }
+            }
+            EventType::Scroll(evt) => {
+                let lines = match evt.delta {
+                    ScrollUnit::Line { y, .. } => y,
+                    ScrollUnit::Pixel { y, .. } => y / PIXELS_PER_LINE,
+                };
+                zoom_by(ZOOM_STEP.powf(lines), relative(ctx.last_mouse_position()));
+                event.stop_propagation();
+            }
+            EventType::Pan(evt) => {
+                let pan = event_controller.pan.get();
+                event_controller
+                    .pan
+                    .set((pan.0 + evt.delta.0, pan.1 + evt.delta.1));
+            }
+            EventType::Pinch(evt) => zoom_by(evt.scale_delta, relative(evt.focal_point)),
+            _ => {}
+        }
+    }));
+    context.set_gestures(Gestures::PAN | Gestures::PINCH);
+
+    // === Styles === //
+    props.styles = Some(
+        Style::default()
+            .with_style(Style {
+                render_command: RenderCommand::Clip.into(),
+                cursor: if drag.is_some() {
+                    StyleProp::Value(CursorIcon::Grabbing)
+                } else {
+                    StyleProp::Unset
+                },
+                ..Default::default()
+            })
+            .with_style(&props.styles)
+            .with_style(Style {
+                width: Units::Stretch(1.0).into(),
+                height: Units::Stretch(1.0).into(),
+                ..Default::default()
+            }),
+    );
+
+    let layer_styles = Style {
+        render_command: RenderCommand::Layout.into(),
+        position_type: PositionType::SelfDirected.into(),
+        left: Units::Pixels(0.0).into(),
+        top: Units::Pixels(0.0).into(),
+        width: Units::Stretch(1.0).into(),
+        height: Units::Stretch(1.0).into(),
+        transform: StyleProp::Value(Transform::new(pan, zoom)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <Element styles={Some(layer_styles)}>
+            {children}
+        </Element>
+    }
+}
+
+/// Returns the pan that keeps the given point (relative to the viewport) in place while zooming
+fn zoom_at(pan: (f32, f32), zoom: f32, next: f32, focus: (f32, f32)) -> (f32, f32) {
+    let zoom = zoom.max(f32::EPSILON);
+    // The point of the content under the focus, in content space
+    let content = ((focus.0 - pan.0) / zoom, (focus.1 - pan.1) / zoom);
+    (focus.0 - content.0 * next, focus.1 - content.1 * next)
+}
+
+/// Maps the bounds of content laid out with the given pan and zoom back to content space
+fn content_rect(bounds: Rect, viewport: Rect, pan: (f32, f32), zoom: f32) -> Rect {
+    let scale = zoom.max(f32::EPSILON);
+    Rect {
+        posx: (bounds.posx - viewport.posx - pan.0) / scale,
+        posy: (bounds.posy - viewport.posy - pan.1) / scale,
+        width: bounds.width / scale,
+        height: bounds.height / scale,
+        ..bounds
+    }
+}
+
+/// Returns the pan and zoom that center the given content (in content space) within a viewport
+///
+/// The content is zoomed to fill the viewport (less the padding on each side), within the zoom
+/// range.
+fn fit_view<F: Fn(f32) -> f32>(
+    content: Rect,
+    viewport: (f32, f32),
+    padding: f32,
+    clamp_zoom: F,
+) -> ((f32, f32), f32) {
+    let available = (
+        (viewport.0 - padding * 2.0).max(0.0),
+        (viewport.1 - padding * 2.0).max(0.0),
+    );
+    let fits = [(available.0, content.width), (available.1, content.height)]
+        .into_iter()
+        .filter(|(_, size)| *size > 0.0)
+        .map(|(available, size)| available / size)
+        .reduce(f32::min)
+        .unwrap_or(1.0);
+    let zoom = clamp_zoom(fits);
+    let pan = (
+        (viewport.0 - content.width * zoom) / 2.0 - content.posx * zoom,
+        (viewport.1 - content.height * zoom) / 2.0 - content.posy * zoom,
+    );
+    (pan, zoom)
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_core::layout_cache::Rect;
+
+    use crate::core::Bound;
+
+    use super::{content_rect, fit_view, zoom_at, ViewportController};
+
+    fn rect(posx: f32, posy: f32, width: f32, height: f32) -> Rect {
+        Rect {
+            posx,
+            posy,
+            width,
+            height,
+            z_index: 0.0,
+        }
+    }
+
+    #[test]
+    fn should_zoom_around_the_focus() {
+        // The content point under the focus stays under it
+        let (pan, zoom, focus) = ((10.0, 20.0), 1.0, (110.0, 70.0));
+        let next = zoom_at(pan, zoom, 2.0, focus);
+        assert_eq!((-90.0, -30.0), next);
+
+        let content = ((focus.0 - pan.0) / zoom, (focus.1 - pan.1) / zoom);
+        let zoomed_content = ((focus.0 - next.0) / 2.0, (focus.1 - next.1) / 2.0);
+        assert_eq!(content, zoomed_content);
+
+        // Zooming around the origin of the content doesn't pan it
+        assert_eq!((0.0, 0.0), zoom_at((0.0, 0.0), 1.0, 3.0, (0.0, 0.0)));
+    }
+
+    #[test]
+    fn should_fit_content_centered() {
+        let no_clamp = |zoom: f32| zoom;
+        let (pan, zoom) = fit_view(rect(0.0, 0.0, 100.0, 50.0), (420.0, 420.0), 10.0, no_clamp);
+        // The width is the tighter fit
+        assert_eq!(4.0, zoom);
+        assert_eq!((10.0, 110.0), pan);
+
+        // Content away from the origin is panned back into view
+        let (pan, zoom) = fit_view(
+            rect(50.0, 50.0, 100.0, 50.0),
+            (420.0, 420.0),
+            10.0,
+            no_clamp,
+        );
+        assert_eq!(4.0, zoom);
+        assert_eq!((-190.0, -90.0), pan);
+    }
+
+    #[test]
+    fn should_fit_within_the_zoom_range() {
+        let clamp = |zoom: f32| zoom.max(0.5).min(2.0);
+        let (pan, zoom) = fit_view(rect(0.0, 0.0, 100.0, 100.0), (400.0, 400.0), 0.0, clamp);
+        assert_eq!(2.0, zoom);
+        assert_eq!((100.0, 100.0), pan);
+
+        // Empty content isn't zoomed
+        let (_, zoom) = fit_view(rect(0.0, 0.0, 0.0, 0.0), (400.0, 400.0), 0.0, clamp);
+        assert_eq!(1.0, zoom);
+    }
+
+    #[test]
+    fn should_map_laid_out_content_to_content_space() {
+        let viewport = rect(100.0, 50.0, 400.0, 300.0);
+        let bounds = rect(130.0, 90.0, 40.0, 20.0);
+        let content = content_rect(bounds, viewport, (10.0, 20.0), 2.0);
+        assert_eq!(rect(10.0, 10.0, 20.0, 10.0), content);
+    }
+
+    #[test]
+    fn should_request_fits_to_content() {
+        let controller = ViewportController::with_view((5.0, 5.0), 2.0);
+        let fit_requests = controller.fit_requests.get();
+        controller.fit_to_content();
+        controller.clone().fit_to_content();
+        assert_eq!(fit_requests + 2, controller.fit_requests.get());
+
+        // Fitting doesn't change the view until the viewport has laid out its content
+        assert_eq!((5.0, 5.0), controller.pan.get());
+        assert_eq!(2.0, controller.zoom.get());
+    }
+}
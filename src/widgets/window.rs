@@ -1,21 +1,72 @@
 use crate::core::{
     color::Color,
+    constructor,
     render_command::RenderCommand,
     rsx,
-    styles::{Corner, Edge, PositionType, Style, StyleProp, Units},
-    use_state, widget, Children, EventType, OnEvent, WidgetProps,
+    styles::{Corner, Edge, LayoutType, PositionType, Style, StyleProp, Units},
+    use_state, widget, Children, DragConstraints, DragPosition, EventType, Handler, OnEvent,
+    VecTracker, WidgetProps,
 };
+use kayak_core::layout_cache::Rect;
 use kayak_core::{CursorIcon, OnLayout};
 
 use crate::widgets::{Background, Clip, Element, Text};
 
+/// The height of a [`Window`]'s title bar, in pixels
+const TITLE_BAR_HEIGHT: f32 = 24.0;
+/// The thickness of the handles along the edges of a resizable [`Window`], in pixels
+const RESIZE_HANDLE_SIZE: f32 = 6.0;
+/// The minimum size of a resizable [`Window`] if not set by its props, in pixels
+const DEFAULT_MIN_SIZE: (f32, f32) = (100.0, 60.0);
+/// The handles of a resizable [`Window`], as the direction each one resizes in
+const RESIZE_HANDLES: [ResizeHandle; 8] = [
+    ResizeHandle { x: -1, y: 0 },
+    ResizeHandle { x: 1, y: 0 },
+    ResizeHandle { x: 0, y: -1 },
+    ResizeHandle { x: 0, y: 1 },
+    ResizeHandle { x: -1, y: -1 },
+    ResizeHandle { x: 1, y: -1 },
+    ResizeHandle { x: -1, y: 1 },
+    ResizeHandle { x: 1, y: 1 },
+];
+
 /// Props used by the [`Window`] widget
 #[derive(WidgetProps, Default, Debug, PartialEq, Clone)]
 pub struct WindowProps {
+    /// If true, shows a button in the title bar that collapses the window down to its title bar
+    pub collapsible: bool,
     /// If true, allows the window to be draggable by its title bar
     pub draggable: bool,
+    /// The constraints applied to the window's position while it's dragged
+    ///
+    /// If no bounds are set, the window is kept within the bounds of its parent (such as the
+    /// viewport, when used directly within an [`App`](crate::widgets::App)). Resizing the
+    /// window also keeps it within these bounds.
+    pub drag_constraints: DragConstraints,
+    /// The portal layer the window is drawn into, such as [`portal::WINDOW`](crate::core::portal::WINDOW)
+    ///
+    /// Windows in a layer are drawn above the rest of the tree, and are brought to the front of
+    /// the other windows in their layer when pressed. If `None`, the window is drawn in place,
+    /// like any other widget.
+    pub layer: Option<String>,
+    /// The minimum size of the window in pixels, when resized by the user
+    ///
+    /// If `None`, the minimum size is 100 by 60 pixels.
+    pub min_size: Option<(f32, f32)>,
+    /// A callback for when the user drags the window by its title bar
+    ///
+    /// The handler is given both the position following the cursor and the constrained position
+    /// the window was moved to.
+    pub on_drag: Option<Handler<DragPosition>>,
+    /// A callback for when the user presses the window's close button
+    ///
+    /// The close button is only shown if this is set. The window doesn't close itself, so this
+    /// should stop rendering it.
+    pub on_close: Option<Handler>,
     /// The position at which to display the window in pixels
     pub position: (f32, f32),
+    /// If true, allows the window to be resized by dragging its edges and corners
+    pub resizable: bool,
     /// The size of the window in pixels
     pub size: (f32, f32),
     /// The text to display in the window's title bar
@@ -32,9 +83,102 @@ pub struct WindowProps {
     pub focusable: Option<bool>,
 }
 
+/// An edge (or corner) of a resizable [`Window`]
+///
+/// Each axis is `-1` for the start edge (left or top), `1` for the end edge (right or bottom),
+/// or `0` if the handle doesn't resize along that axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ResizeHandle {
+    x: i8,
+    y: i8,
+}
+
+impl ResizeHandle {
+    fn cursor(&self) -> CursorIcon {
+        match (self.x, self.y) {
+            (0, _) => CursorIcon::NsResize,
+            (_, 0) => CursorIcon::EwResize,
+            (x, y) if x == y => CursorIcon::NwseResize,
+            _ => CursorIcon::NeswResize,
+        }
+    }
+
+    /// The styles positioning this handle along the edges of the window
+    fn styles(&self) -> Style {
+        let along = |edge: i8| match edge {
+            0 => (
+                Units::Pixels(RESIZE_HANDLE_SIZE),
+                Units::Pixels(RESIZE_HANDLE_SIZE),
+                Units::Stretch(1.0),
+            ),
+            -1 => (
+                Units::Pixels(0.0),
+                Units::Stretch(1.0),
+                Units::Pixels(RESIZE_HANDLE_SIZE),
+            ),
+            _ => (
+                Units::Stretch(1.0),
+                Units::Pixels(0.0),
+                Units::Pixels(RESIZE_HANDLE_SIZE),
+            ),
+        };
+        let (left, right, width) = along(self.x);
+        let (top, bottom, height) = along(self.y);
+
+        Style {
+            background_color: Color::TRANSPARENT.into(),
+            cursor: self.cursor().into(),
+            position_type: PositionType::SelfDirected.into(),
+            left: left.into(),
+            right: right.into(),
+            top: top.into(),
+            bottom: bottom.into(),
+            width: width.into(),
+            height: height.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Resizes a window by the distance the cursor moved, returning its new position and size
+    fn resize(
+        &self,
+        start: ((f32, f32), (f32, f32)),
+        delta: (f32, f32),
+        min_size: (f32, f32),
+    ) -> ((f32, f32), (f32, f32)) {
+        let (position, size) = start;
+        let resize_axis = |edge: i8, position: f32, size: f32, delta: f32, min: f32| match edge {
+            -1 => {
+                let next = (size - delta).max(min);
+                (position + size - next, next)
+            }
+            1 => (position, (size + delta).max(min)),
+            _ => (position, size),
+        };
+        let (x, width) = resize_axis(self.x, position.0, size.0, delta.0, min_size.0);
+        let (y, height) = resize_axis(self.y, position.1, size.1, delta.1, min_size.1);
+        ((x, y), (width, height))
+    }
+}
+
+/// The state of a window being resized
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Resizing {
+    handle: ResizeHandle,
+    /// The position of the cursor when the resize started
+    cursor: (f32, f32),
+    /// The position and size of the window when the resize started
+    start: ((f32, f32), (f32, f32)),
+}
+
 #[widget]
 /// A widget that renders a window-like container element
 ///
+/// The window can optionally be dragged by its title bar, resized by its edges and corners,
+/// collapsed, and closed. Given a [`layer`](WindowProps::layer), it's drawn into that portal
+/// layer, so it stacks above the rest of the tree and is brought to the front of the other
+/// windows when pressed.
+///
 /// # Props
 ///
 /// __Type:__ [`WindowProps`]
@@ -47,20 +191,108 @@ pub struct WindowProps {
 /// | `on_layout` | ✅        |
 /// | `focusable` | ✅        |
 ///
+/// # Examples
+///
+/// ```
+/// # use kayak_ui::core::{portal, widget, Handler, rsx, use_state};
+/// # use kayak_ui::widgets::Window;
+/// # #[widget]
+/// # fn Example() {
+/// let (is_open, set_is_open, ..) = use_state!(true);
+/// let on_close = Handler::new(move |_| {
+///     set_is_open(false);
+/// });
+///
+/// rsx! {
+///     <>
+///         {if is_open {
+///             <Window
+///                 draggable={true}
+///                 resizable={true}
+///                 collapsible={true}
+///                 on_close={Some(on_close)}
+///                 position={(50.0, 50.0)}
+///                 size={(300.0, 200.0)}
+///                 title={"Inspector".to_string()}
+///                 layer={Some(portal::WINDOW.to_string())}
+///             >
+///                 {}
+///             </Window>
+///         }}
+///     </>
+/// }
+/// # }
+/// ```
 pub fn Window(props: WindowProps) {
     let WindowProps {
+        collapsible,
         draggable,
+        drag_constraints,
+        layer,
+        min_size,
+        on_close,
+        on_drag,
         position,
+        resizable,
         size,
         title,
         ..
     } = props.clone();
+    let min_size = min_size.unwrap_or(DEFAULT_MIN_SIZE);
+    let window_id = self.get_id();
+
+    // === Layer === //
+    context.set_portal(layer.as_deref());
 
+    // === States === //
     let (is_dragging, set_is_dragging, ..) = use_state!(false);
     let (offset, set_offset, ..) = use_state!((0.0, 0.0));
     let (pos, set_pos, ..) = use_state!(position);
+    let (current_size, set_size, ..) = use_state!(size);
+    let (resizing, set_resizing, ..) = use_state!(None::<Resizing>);
+    let (is_collapsed, set_is_collapsed, ..) = use_state!(false);
+
+    // A new size given by the props replaces the size the user resized the window to
+    let size_changed = context
+        .use_previous(size)
+        .map_or(false, |previous| previous != size);
+    let size = if size_changed {
+        set_size(size);
+        size
+    } else {
+        current_size
+    };
+
+    // Unless given other bounds, the window is kept within its parent (as of the last layout)
+    let parent_bounds = context
+        .get_valid_parent(window_id)
+        .and_then(|parent| context.get_layout(&parent))
+        .map(|layout| Rect {
+            width: layout.width,
+            height: layout.height,
+            ..Default::default()
+        });
+    let constraints = DragConstraints {
+        bounds: drag_constraints.bounds.or(parent_bounds),
+        ..drag_constraints
+    };
+    let bounds = constraints.bounds;
+    let visible_height = if is_collapsed {
+        collapsed_height()
+    } else {
+        size.1
+    };
+    let pos = constraints.apply(pos, (size.0, visible_height)).snapped;
+
+    // === Events === //
+    let raise_handler = OnEvent::new(move |ctx, event| {
+        if let EventType::MouseDown(..) = event.event_type {
+            ctx.raise_portal(window_id);
+        }
+    });
 
     let drag_handler = if draggable {
+        let set_pos = set_pos.clone();
         Some(OnEvent::new(move |ctx, event| match event.event_type {
             EventType::MouseDown(data) => {
                 ctx.capture_cursor(event.current_target);
@@ -73,7 +305,14 @@ pub fn Window(props: WindowProps) {
             }
             EventType::Hover(data) => {
                 if is_dragging {
-                    set_pos((offset.0 + data.position.0, offset.1 + data.position.1));
+                    let position = constraints.apply(
+                        (offset.0 + data.position.0, offset.1 + data.position.1),
+                        (size.0, visible_height),
+                    );
+                    set_pos(position.snapped);
+                    if let Some(ref on_drag) = on_drag {
+                        on_drag.call(position);
+                    }
                 }
             }
             _ => {}
@@ -82,6 +321,28 @@ pub fn Window(props: WindowProps) {
         None
     };
 
+    let resize_handles = if resizable && !is_collapsed {
+        RESIZE_HANDLES.to_vec()
+    } else {
+        Vec::new()
+    };
+
+    let collapse_handler = OnEvent::new(move |_, event| match event.event_type {
+        // Keep the title bar from starting a drag
+        EventType::MouseDown(..) => event.stop_propagation(),
+        EventType::Click(..) => set_is_collapsed(!is_collapsed),
+        _ => {}
+    });
+
+    let close_handler = on_close.map(|on_close| {
+        OnEvent::new(move |_, event| match event.event_type {
+            EventType::MouseDown(..) => event.stop_propagation(),
+            EventType::Click(..) => on_close.call(()),
+            _ => {}
+        })
+    });
+
+    // === Styles === //
     props.styles = Some(Style {
         background_color: StyleProp::Value(Color::new(0.125, 0.125, 0.125, 1.0)),
         border_color: StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0)),
@@ -92,18 +353,25 @@ pub fn Window(props: WindowProps) {
         left: StyleProp::Value(Units::Pixels(pos.0)),
         top: StyleProp::Value(Units::Pixels(pos.1)),
         width: StyleProp::Value(Units::Pixels(size.0)),
-        height: StyleProp::Value(Units::Pixels(size.1)),
+        height: StyleProp::Value(Units::Pixels(visible_height)),
         max_width: StyleProp::Value(Units::Pixels(size.0)),
-        max_height: StyleProp::Value(Units::Pixels(size.1)),
+        max_height: StyleProp::Value(Units::Pixels(visible_height)),
         ..props.styles.clone().unwrap_or_default()
     });
 
+    let frame_styles = Style {
+        background_color: StyleProp::Value(Color::TRANSPARENT),
+        width: StyleProp::Value(Units::Stretch(1.0)),
+        height: StyleProp::Value(Units::Stretch(1.0)),
+        ..Style::default()
+    };
+
     let clip_styles = Style {
         padding: StyleProp::Value(Edge::all(Units::Pixels(5.0))),
         width: StyleProp::Value(Units::Stretch(1.0)),
         height: StyleProp::Value(Units::Stretch(1.0)),
         max_width: StyleProp::Value(Units::Pixels(size.0)),
-        max_height: StyleProp::Value(Units::Pixels(size.1)),
+        max_height: StyleProp::Value(Units::Pixels(visible_height)),
         ..Style::default()
     };
 
@@ -121,7 +389,8 @@ pub fn Window(props: WindowProps) {
         background_color: StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0)),
         border_radius: StyleProp::Value(Corner::all(5.0)),
         cursor: cursor.into(),
-        height: StyleProp::Value(Units::Pixels(24.0)),
+        layout_type: StyleProp::Value(LayoutType::Row),
+        height: StyleProp::Value(Units::Pixels(TITLE_BAR_HEIGHT)),
         width: StyleProp::Value(Units::Stretch(1.0)),
         left: StyleProp::Value(Units::Pixels(0.0)),
         right: StyleProp::Value(Units::Pixels(0.0)),
@@ -133,24 +402,201 @@ pub fn Window(props: WindowProps) {
 
     let title_text_styles = Style {
         height: StyleProp::Value(Units::Pixels(25.0)),
+        width: StyleProp::Value(Units::Stretch(1.0)),
         cursor: StyleProp::Inherit,
         ..Style::default()
     };
 
+    let title_button_styles = Some(Style {
+        background_color: StyleProp::Value(Color::TRANSPARENT),
+        cursor: CursorIcon::Hand.into(),
+        width: StyleProp::Value(Units::Pixels(TITLE_BAR_HEIGHT)),
+        height: StyleProp::Value(Units::Pixels(TITLE_BAR_HEIGHT)),
+        ..Style::default()
+    });
+
+    let title_button_text_styles = Some(Style {
+        cursor: StyleProp::Inherit,
+        left: StyleProp::Value(Units::Stretch(1.0)),
+        right: StyleProp::Value(Units::Stretch(1.0)),
+        ..Style::default()
+    });
+
     let content_styles = Style {
         padding: StyleProp::Value(Edge::all(Units::Pixels(10.0))),
         ..Style::default()
     };
 
+    let collapse_label = if is_collapsed { "+" } else { "-" }.to_string();
+    let has_close_button = close_handler.is_some();
     let title = title.clone();
     rsx! {
-        <Clip styles={Some(clip_styles)}>
-            <Background on_event={drag_handler} styles={Some(title_background_styles)}>
-                <Text styles={Some(title_text_styles)} size={16.0} content={title} />
-            </Background>
-            <Element styles={Some(content_styles)}>
-                {children}
-            </Element>
-        </Clip>
+        <Background on_event_capture={Some(raise_handler)} styles={Some(frame_styles)}>
+            <Clip styles={Some(clip_styles)}>
+                <Background on_event={drag_handler} styles={Some(title_background_styles)}>
+                    <Text styles={Some(title_text_styles)} size={16.0} content={title} />
+                    {if collapsible {
+                        <Background on_event={Some(collapse_handler)} styles={title_button_styles}>
+                            <Text styles={title_button_text_styles} size={16.0} content={collapse_label} />
+                        </Background>
+                    }}
+                    {if has_close_button {
+                        <Background on_event={close_handler} styles={title_button_styles}>
+                            <Text styles={title_button_text_styles} size={16.0} content={"x".to_string()} />
+                        </Background>
+                    }}
+                </Background>
+                {if !is_collapsed {
+                    <Element styles={Some(content_styles)}>
+                        {children}
+                    </Element>
+                }}
+            </Clip>
+            {VecTracker::from(resize_handles.iter().map(|&handle| {
+                let set_pos = set_pos.clone();
+                let set_size = set_size.clone();
+                let set_resizing = set_resizing.clone();
+                let resize_handler = OnEvent::new(move |ctx, event| match event.event_type {
+                    EventType::MouseDown(data) => {
+                        ctx.capture_cursor(event.current_target);
+                        set_resizing(Some(Resizing {
+                            handle,
+                            cursor: data.position,
+                            start: (pos, size),
+                        }));
+                    }
+                    EventType::MouseUp(..) => {
+                        ctx.release_cursor(event.current_target);
+                        set_resizing(None);
+                    }
+                    EventType::Hover(data) => {
+                        if let Some(resizing) = resizing.filter(|resizing| resizing.handle == handle) {
+                            let delta = (
+                                data.position.0 - resizing.cursor.0,
+                                data.position.1 - resizing.cursor.1,
+                            );
+                            let (next_pos, next_size) = handle.resize(resizing.start, delta, min_size);
+                            let (next_pos, next_size) = clamp_rect(next_pos, next_size, min_size, bounds);
+                            set_pos(next_pos);
+                            set_size(next_size);
+                        }
+                    }
+                    _ => {}
+                });
+                constructor! {
+                    <Background on_event={Some(resize_handler)} styles={Some(handle.styles())} />
+                }
+            }))}
+        </Background>
+    }
+}
+
+/// The height of a collapsed window, which only shows its title bar
+fn collapsed_height() -> f32 {
+    // The title bar, plus the padding of the clip and the window's border
+    TITLE_BAR_HEIGHT + 2.0 * 5.0 + 2.0 * 4.0
+}
+
+/// Shrinks a resized window so that it stays within the given bounds
+///
+/// The window is never shrunk below its minimum size. If it has to be kept at its minimum size,
+/// it's moved back within the bounds instead (as far as they're large enough to contain it).
+fn clamp_rect(
+    position: (f32, f32),
+    size: (f32, f32),
+    min_size: (f32, f32),
+    bounds: Option<Rect>,
+) -> ((f32, f32), (f32, f32)) {
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => return (position, size),
+    };
+
+    let clamp_axis = |position: f32, size: f32, min: f32, start: f32, extent: f32| {
+        let clamped = position.max(start);
+        let size = (size - (clamped - position))
+            .min(start + extent - clamped)
+            .max(min);
+        let position = clamped.min(start + extent - size).max(start);
+        (position, size)
+    };
+    let (x, width) = clamp_axis(position.0, size.0, min_size.0, bounds.posx, bounds.width);
+    let (y, height) = clamp_axis(position.1, size.1, min_size.1, bounds.posy, bounds.height);
+    ((x, y), (width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use kayak_core::layout_cache::Rect;
+
+    use super::{clamp_rect, ResizeHandle};
+
+    const BOUNDS: Rect = Rect {
+        posx: 0.0,
+        posy: 0.0,
+        width: 500.0,
+        height: 400.0,
+        z_index: 0.0,
+    };
+
+    #[test]
+    fn should_resize_from_the_end_edges() {
+        let handle = ResizeHandle { x: 1, y: 1 };
+        let resized = handle.resize(((50.0, 50.0), (200.0, 100.0)), (30.0, -20.0), (100.0, 60.0));
+        assert_eq!(((50.0, 50.0), (230.0, 80.0)), resized);
+    }
+
+    #[test]
+    fn should_move_when_resized_from_the_start_edges() {
+        let handle = ResizeHandle { x: -1, y: 0 };
+        let resized = handle.resize(((50.0, 50.0), (200.0, 100.0)), (-30.0, 40.0), (100.0, 60.0));
+        assert_eq!(((20.0, 50.0), (230.0, 100.0)), resized);
+    }
+
+    #[test]
+    fn should_not_resize_below_the_min_size() {
+        let handle = ResizeHandle { x: -1, y: 1 };
+        let resized = handle.resize(
+            ((50.0, 50.0), (200.0, 100.0)),
+            (150.0, -80.0),
+            (100.0, 60.0),
+        );
+        assert_eq!(((150.0, 50.0), (100.0, 60.0)), resized);
+    }
+
+    #[test]
+    fn should_clamp_to_the_bounds() {
+        let clamped = clamp_rect((-20.0, 350.0), (200.0, 100.0), (100.0, 40.0), Some(BOUNDS));
+        assert_eq!(((0.0, 350.0), (180.0, 50.0)), clamped);
+
+        let unclamped = clamp_rect((-20.0, 350.0), (200.0, 100.0), (100.0, 40.0), None);
+        assert_eq!(((-20.0, 350.0), (200.0, 100.0)), unclamped);
+    }
+
+    #[test]
+    fn should_keep_the_min_size_when_clamped() {
+        // Too close to the bottom edge to fit the minimum height, so it's moved up instead
+        let clamped = clamp_rect((100.0, 380.0), (200.0, 100.0), (100.0, 60.0), Some(BOUNDS));
+        assert_eq!(((100.0, 340.0), (200.0, 60.0)), clamped);
+
+        // Moved past the start edge at the minimum width
+        let clamped = clamp_rect((-50.0, 100.0), (120.0, 100.0), (100.0, 60.0), Some(BOUNDS));
+        assert_eq!(((0.0, 100.0), (100.0, 100.0)), clamped);
+    }
+
+    #[test]
+    fn should_use_resize_cursors_along_each_axis() {
+        use kayak_core::CursorIcon;
+
+        assert_eq!(CursorIcon::EwResize, ResizeHandle { x: 1, y: 0 }.cursor());
+        assert_eq!(CursorIcon::NsResize, ResizeHandle { x: 0, y: -1 }.cursor());
+        assert_eq!(
+            CursorIcon::NwseResize,
+            ResizeHandle { x: -1, y: -1 }.cursor()
+        );
+        assert_eq!(
+            CursorIcon::NeswResize,
+            ResizeHandle { x: 1, y: -1 }.cursor()
+        );
     }
 }